@@ -68,6 +68,7 @@ mod tests {
                 line_number: 10,
             },
             parent_id: None,
+            body_hash: None,
             metadata: serde_json::json!({
                 "path": "src/components/user/authentication/handlers/login.rs",
                 "hash": "abcdef1234567890abcdef1234567890abcdef1234567890",
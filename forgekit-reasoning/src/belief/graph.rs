@@ -1,19 +1,32 @@
 //! Dependency graph for beliefs (hypotheses)
 
 use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
 use sqlitegraph::typed_digraph::{tarjan_scc, Dfs, Direction, NodeIndex, TypedDiGraph};
 use std::collections::{HashMap, HashSet};
 
 use crate::errors::Result;
 use crate::hypothesis::types::HypothesisId;
 
+/// How one belief bears on another along a graph edge
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EdgeKind {
+    /// The dependee is evidence for the dependent
+    Supports,
+    /// The dependee is evidence against the dependent
+    Contradicts,
+    /// The dependent only holds if the dependee does
+    #[default]
+    DependsOn,
+}
+
 /// Dependency graph for beliefs (hypotheses)
 ///
 /// Edge direction: A -> B means "A depends on B"
 /// So B is a "dependee" of A, and A is a "dependent" of B
 #[derive(Clone)]
 pub struct BeliefGraph {
-    graph: TypedDiGraph<HypothesisId, ()>,
+    graph: TypedDiGraph<HypothesisId, EdgeKind>,
     node_indices: HashMap<HypothesisId, NodeIndex>,
 }
 
@@ -32,6 +45,19 @@ impl BeliefGraph {
         &mut self,
         hypothesis_id: HypothesisId,
         depends_on: HypothesisId,
+    ) -> Result<()> {
+        self.add_dependency_of_kind(hypothesis_id, depends_on, EdgeKind::DependsOn)
+    }
+
+    /// Add a dependency edge of the given kind
+    ///
+    /// Returns error if this would create a cycle. If the edge already
+    /// exists its kind is left unchanged.
+    pub fn add_dependency_of_kind(
+        &mut self,
+        hypothesis_id: HypothesisId,
+        depends_on: HypothesisId,
+        kind: EdgeKind,
     ) -> Result<()> {
         // Check if adding this edge would create a cycle
         // would_create_cycle returns TRUE if a cycle WOULD be created
@@ -50,7 +76,7 @@ impl BeliefGraph {
             return Ok(()); // Already exists, no-op
         }
 
-        self.graph.add_edge(from_idx, to_idx, ());
+        self.graph.add_edge(from_idx, to_idx, kind);
         Ok(())
     }
 
@@ -81,6 +107,18 @@ impl BeliefGraph {
         }
     }
 
+    /// Kind of the edge from `hypothesis_id` to `depends_on`, if there is one
+    pub fn edge_kind(
+        &self,
+        hypothesis_id: HypothesisId,
+        depends_on: HypothesisId,
+    ) -> Option<EdgeKind> {
+        let from_idx = *self.node_indices.get(&hypothesis_id)?;
+        let to_idx = *self.node_indices.get(&depends_on)?;
+        let edge = self.graph.find_edge(from_idx, to_idx)?;
+        self.graph.edge_weight(edge).copied()
+    }
+
     /// Get all hypotheses that depend on the given hypothesis (incoming edges)
     ///
     /// If A depends on B, then A is in B's dependents list
@@ -173,6 +211,10 @@ impl BeliefGraph {
     }
 
     /// Detect all cycles in the belief dependency graph
+    ///
+    /// Each cycle is returned as the members of one strongly connected
+    /// component. `add_dependency` refuses cycles, but a graph rebuilt from
+    /// stored edges may still contain them.
     pub fn detect_cycles(&self) -> Vec<Vec<HypothesisId>> {
         let sccs = tarjan_scc(&self.graph);
 
@@ -182,6 +224,46 @@ impl BeliefGraph {
             .collect()
     }
 
+    /// Edges to ignore so that no cycle remains, as (dependent, dependee)
+    ///
+    /// Within each cycle, the edge whose dependee has the lowest
+    /// `confidence` is dropped first, ties broken by id, until the cycle's
+    /// members no longer reach each other. Edges not on any cycle are
+    /// never dropped, and the same graph and confidences always give the
+    /// same edges.
+    pub fn cycle_breaking_edges(
+        &self,
+        confidence: impl Fn(HypothesisId) -> f64,
+    ) -> Vec<(HypothesisId, HypothesisId)> {
+        let mut dropped = Vec::new();
+        for cycle in self.detect_cycles() {
+            let members: HashSet<HypothesisId> = cycle.into_iter().collect();
+            let mut edges: Vec<(HypothesisId, HypothesisId)> = self
+                .all_edges()
+                .into_iter()
+                .filter(|(from, to)| members.contains(from) && members.contains(to))
+                .collect();
+            edges.sort_by(|a, b| {
+                confidence(a.1)
+                    .total_cmp(&confidence(b.1))
+                    .then_with(|| (a.0 .0, a.1 .0).cmp(&(b.0 .0, b.1 .0)))
+            });
+
+            // An edge is on a cycle while its dependee still reaches its
+            // dependent through the remaining edges
+            let mut i = 0;
+            while i < edges.len() {
+                let (from, to) = edges[i];
+                if reaches(&edges, to, from) {
+                    dropped.push(edges.remove(i));
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        dropped
+    }
+
     /// Check if adding an edge would create a cycle
     ///
     /// Returns true if adding the edge WOULD create a cycle (cycle detected).
@@ -208,7 +290,7 @@ impl BeliefGraph {
         };
 
         // Add the edge we're testing
-        temp_graph.add_edge(from_idx, to_idx, ());
+        temp_graph.add_edge(from_idx, to_idx, EdgeKind::DependsOn);
 
         // A cycle is created if 'to' can reach 'from' after adding edge
         let mut dfs = Dfs::new(&temp_graph, to_idx);
@@ -235,6 +317,30 @@ impl BeliefGraph {
         }
     }
 
+    /// Replace `absorb` with `keep` in every edge, then remove `absorb`
+    ///
+    /// Edges that would become self-loops are dropped. Returns an error,
+    /// leaving the graph unchanged, if a redirected edge would create a cycle.
+    pub fn merge_into(&mut self, keep: HypothesisId, absorb: HypothesisId) -> Result<()> {
+        if !self.node_indices.contains_key(&absorb) {
+            return Ok(());
+        }
+        let mut merged = self.clone();
+        let dependents = merged.dependents(absorb)?;
+        let dependees = merged.dependees(absorb)?;
+        merged.remove_hypothesis(absorb)?;
+        for dependent in dependents.into_iter().filter(|id| *id != keep) {
+            let kind = self.edge_kind(dependent, absorb).unwrap_or_default();
+            merged.add_dependency_of_kind(dependent, keep, kind)?;
+        }
+        for dependee in dependees.into_iter().filter(|id| *id != keep) {
+            let kind = self.edge_kind(absorb, dependee).unwrap_or_default();
+            merged.add_dependency_of_kind(keep, dependee, kind)?;
+        }
+        *self = merged;
+        Ok(())
+    }
+
     /// Returns all dependency edges as (dependent, dependee) pairs
     ///
     /// If A depends on B, returns (A, B)
@@ -245,6 +351,18 @@ impl BeliefGraph {
             .collect()
     }
 
+    /// Add an edge without the cycle check, to build cyclic graphs in tests
+    #[cfg(test)]
+    pub(crate) fn add_edge_unchecked(
+        &mut self,
+        hypothesis_id: HypothesisId,
+        depends_on: HypothesisId,
+    ) {
+        let from_idx = self.get_or_create_node(hypothesis_id);
+        let to_idx = self.get_or_create_node(depends_on);
+        self.graph.add_edge(from_idx, to_idx, EdgeKind::DependsOn);
+    }
+
     fn get_or_create_node(&mut self, id: HypothesisId) -> NodeIndex {
         if let Some(&idx) = self.node_indices.get(&id) {
             return idx;
@@ -255,6 +373,23 @@ impl BeliefGraph {
     }
 }
 
+/// Returns true if `to` can be reached from `from` along `edges`
+fn reaches(edges: &[(HypothesisId, HypothesisId)], from: HypothesisId, to: HypothesisId) -> bool {
+    let mut seen = HashSet::from([from]);
+    let mut stack = vec![from];
+    while let Some(node) = stack.pop() {
+        if node == to {
+            return true;
+        }
+        for (_, next) in edges.iter().filter(|(source, _)| *source == node) {
+            if seen.insert(*next) {
+                stack.push(*next);
+            }
+        }
+    }
+    false
+}
+
 impl Default for BeliefGraph {
     fn default() -> Self {
         Self::new()
@@ -347,10 +482,57 @@ mod tests {
         graph.get_or_create_node(b);
         let from_idx = *graph.node_indices.get(&a).unwrap();
         let to_idx = *graph.node_indices.get(&b).unwrap();
-        graph.graph.add_edge(from_idx, to_idx, ());
-        graph.graph.add_edge(to_idx, from_idx, ());
+        graph.graph.add_edge(from_idx, to_idx, EdgeKind::DependsOn);
+        graph.graph.add_edge(to_idx, from_idx, EdgeKind::DependsOn);
 
         let cycles = graph.detect_cycles();
         assert_eq!(cycles.len(), 1);
     }
+
+    #[test]
+    fn test_cycle_breaking_edges_drops_weakest_back_edge() {
+        let mut graph = BeliefGraph::new();
+        let (a, b, c, d) = (
+            HypothesisId::new(),
+            HypothesisId::new(),
+            HypothesisId::new(),
+            HypothesisId::new(),
+        );
+        // a -> b -> c -> a, plus d -> a which is on no cycle
+        graph.add_edge_unchecked(a, b);
+        graph.add_edge_unchecked(b, c);
+        graph.add_edge_unchecked(c, a);
+        graph.add_edge_unchecked(d, a);
+        let confidence = |id: HypothesisId| if id == c { 0.2 } else { 0.7 };
+
+        assert_eq!(graph.detect_cycles().len(), 1);
+        assert_eq!(graph.cycle_breaking_edges(confidence), vec![(b, c)]);
+        assert_eq!(graph.cycle_breaking_edges(confidence), vec![(b, c)]);
+        assert!(BeliefGraph::new().cycle_breaking_edges(|_| 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_edge_kinds_survive_merge() {
+        let mut graph = BeliefGraph::new();
+        let (keep, absorb, dependent) = (
+            HypothesisId::new(),
+            HypothesisId::new(),
+            HypothesisId::new(),
+        );
+        graph
+            .add_dependency_of_kind(dependent, absorb, EdgeKind::Contradicts)
+            .unwrap();
+        graph.add_dependency(keep, HypothesisId::new()).unwrap();
+        assert_eq!(
+            graph.edge_kind(dependent, absorb),
+            Some(EdgeKind::Contradicts)
+        );
+        assert_eq!(graph.edge_kind(absorb, dependent), None);
+
+        graph.merge_into(keep, absorb).unwrap();
+        assert_eq!(
+            graph.edge_kind(dependent, keep),
+            Some(EdgeKind::Contradicts)
+        );
+    }
 }
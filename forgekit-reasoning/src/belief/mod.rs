@@ -6,11 +6,11 @@
 
 mod graph;
 
-pub use graph::BeliefGraph;
+pub use graph::{BeliefGraph, EdgeKind};
 
 use crate::errors::Result;
 use crate::hypothesis::types::HypothesisId;
-use crate::hypothesis::HypothesisBoard;
+use crate::hypothesis::{HypothesisBoard, MergeReport, MergeStrategy, SkippedItem};
 
 /// Combined reasoning system with hypotheses and belief dependencies
 pub struct ReasoningSystem {
@@ -91,6 +91,52 @@ impl ReasoningSystem {
         self.graph.detect_cycles()
     }
 
+    /// Merge `absorb` into `keep` on the board and in the graph
+    ///
+    /// Dependency edges to or from `absorb` are redirected to `keep`. The
+    /// graph is checked first, so a merge that would create a cycle fails
+    /// without changing the board.
+    pub async fn merge(
+        &mut self,
+        keep: HypothesisId,
+        absorb: HypothesisId,
+    ) -> Result<crate::hypothesis::Confidence> {
+        let mut graph = self.graph.clone();
+        graph.merge_into(keep, absorb)?;
+        let confidence = self.board.merge(keep, absorb).await?;
+        self.graph = graph;
+        Ok(confidence)
+    }
+
+    /// Merge another system's hypotheses, evidence and dependencies into this one
+    ///
+    /// The boards are merged with [`HypothesisBoard::merge_from`], then each
+    /// dependency edge of `other` is re-added between the remapped ids with
+    /// its kind. Edges that would become self-loops or close a cycle are
+    /// reported as [`SkippedItem::Dependency`].
+    pub async fn merge_from(
+        &mut self,
+        other: &ReasoningSystem,
+        strategy: MergeStrategy,
+    ) -> Result<MergeReport> {
+        let mut report = self.board.merge_from(&other.board, strategy).await?;
+        for (dependent, dependee) in other.graph.all_edges() {
+            let (Some(from), Some(to)) = (report.remap(dependent), report.remap(dependee)) else {
+                continue;
+            };
+            let kind = other
+                .graph
+                .edge_kind(dependent, dependee)
+                .unwrap_or_default();
+            if from == to || self.graph.add_dependency_of_kind(from, to, kind).is_err() {
+                report
+                    .skipped
+                    .push(SkippedItem::Dependency(dependent, dependee));
+            }
+        }
+        Ok(report)
+    }
+
     /// Remove a hypothesis and its graph node
     pub async fn remove_hypothesis(&mut self, id: HypothesisId) -> Result<bool> {
         let removed_from_board = self.board.delete(id).await?;
@@ -160,4 +206,64 @@ mod tests {
         let result = system.add_dependency(h2, h1).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_merge_redirects_dependencies() {
+        let mut system = ReasoningSystem::in_memory();
+        let prior = Confidence::new(0.5).unwrap();
+        let keep = system.board.propose("Keep", prior).await.unwrap();
+        let absorb = system.board.propose("Absorb", prior).await.unwrap();
+        let dependent = system.board.propose("Dependent", prior).await.unwrap();
+        let base = system.board.propose("Base", prior).await.unwrap();
+
+        system.add_dependency(dependent, absorb).await.unwrap();
+        system.add_dependency(absorb, base).await.unwrap();
+        system.add_dependency(keep, absorb).await.unwrap();
+
+        system.merge(keep, absorb).await.unwrap();
+
+        assert!(system.dependees(dependent).unwrap().contains(&keep));
+        assert!(system.dependees(keep).unwrap().contains(&base));
+        assert!(!system.graph.nodes().contains(&absorb));
+        assert!(!system.dependees(keep).unwrap().contains(&keep));
+        assert!(system.board.get(absorb).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_from_remaps_dependencies() {
+        let prior = Confidence::new(0.5).unwrap();
+        let mut ours = ReasoningSystem::in_memory();
+        let stale = ours.board.propose("Cache is stale", prior).await.unwrap();
+        let slow = ours.board.propose("Lookups are slow", prior).await.unwrap();
+        ours.add_dependency(stale, slow).await.unwrap();
+
+        let mut theirs = ReasoningSystem::in_memory();
+        let their_stale = theirs.board.propose("Cache is stale", prior).await.unwrap();
+        let their_slow = theirs
+            .board
+            .propose("Lookups are slow", prior)
+            .await
+            .unwrap();
+        let evict = theirs.board.propose("No eviction", prior).await.unwrap();
+        theirs.add_dependency(evict, their_stale).await.unwrap();
+        // Reversed here, so it would close a cycle once merged
+        theirs
+            .add_dependency(their_slow, their_stale)
+            .await
+            .unwrap();
+
+        let report = ours
+            .merge_from(&theirs, MergeStrategy::KeepHigherConfidence)
+            .await
+            .unwrap();
+
+        assert_eq!(report.remap(their_stale), Some(stale));
+        assert_eq!(report.merged, vec![(evict, evict)]);
+        assert!(ours.dependees(evict).unwrap().contains(&stale));
+        assert_eq!(
+            report.skipped,
+            vec![SkippedItem::Dependency(their_slow, their_stale)]
+        );
+        assert!(ours.detect_cycles().is_empty());
+    }
 }
@@ -1,9 +1,10 @@
 //! Temporal Checkpointing - Core types and manager
 
 use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::rc::Rc;
 use uuid::Uuid;
@@ -110,7 +111,7 @@ impl TemporalCheckpoint {
     }
 
     /// Compute checksum of this checkpoint's data
-    fn compute_checksum(&self) -> String {
+    pub(crate) fn compute_checksum(&self) -> String {
         // Create a copy without checksum for serialization
         let data_for_hash = CheckpointDataForHash {
             id: self.id,
@@ -164,6 +165,10 @@ pub struct DebugStateSnapshot {
     pub metrics: SessionMetrics,
     /// Hypothesis state snapshot (optional for backward compatibility)
     pub hypothesis_state: Option<crate::hypothesis::types::HypothesisState>,
+    /// Notes recorded up to this point (omitted when empty, so older
+    /// checksums still validate)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<DebugNote>,
 }
 
 /// What triggered this checkpoint
@@ -209,6 +214,73 @@ pub enum CompactionPolicy {
         keep_recent: usize,
         preserve_tags: Vec<String>,
     },
+    /// Keep recent + preserve tagged, keep everything younger than
+    /// `thin_after`, and keep only the newest checkpoint of each hour
+    /// for older ones
+    Retention {
+        keep_recent: usize,
+        thin_after: chrono::Duration,
+        preserve_tags: Vec<String>,
+    },
+}
+
+impl CompactionPolicy {
+    /// Select the checkpoints this policy keeps, as of `now`
+    pub fn retained(
+        &self,
+        checkpoints: &[CheckpointSummary],
+        now: DateTime<Utc>,
+    ) -> HashSet<CheckpointId> {
+        let mut newest_first: Vec<&CheckpointSummary> = checkpoints.iter().collect();
+        newest_first.sort_by_key(|cp| std::cmp::Reverse(cp.sequence_number));
+        let recent = |n: usize| newest_first.iter().take(n).map(|cp| cp.id);
+        let tagged = |tags: &[String]| {
+            checkpoints
+                .iter()
+                .filter(|cp| cp.tags.iter().any(|t| tags.contains(t)))
+                .map(|cp| cp.id)
+                .collect::<Vec<_>>()
+        };
+
+        match self {
+            CompactionPolicy::KeepRecent(n) => recent(*n).collect(),
+            CompactionPolicy::PreserveTagged(tags) => tagged(tags).into_iter().collect(),
+            CompactionPolicy::Hybrid {
+                keep_recent,
+                preserve_tags,
+            } => recent(*keep_recent).chain(tagged(preserve_tags)).collect(),
+            CompactionPolicy::Retention {
+                keep_recent,
+                thin_after,
+                preserve_tags,
+            } => {
+                let mut to_keep: HashSet<CheckpointId> =
+                    recent(*keep_recent).chain(tagged(preserve_tags)).collect();
+                let mut hours_seen = HashSet::new();
+                for cp in &newest_first {
+                    let young = now.signed_duration_since(cp.timestamp) < *thin_after;
+                    // Newest first, so the first of each hour is its newest
+                    let first_of_hour =
+                        hours_seen.insert(cp.timestamp.timestamp().div_euclid(3600));
+                    if young || first_of_hour {
+                        to_keep.insert(cp.id);
+                    }
+                }
+                to_keep
+            }
+        }
+    }
+}
+
+/// Outcome of compacting a session's checkpoints
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Checkpoints deleted
+    pub removed: usize,
+    /// Checkpoints left in the session
+    pub kept: usize,
+    /// Serialized size of the deleted checkpoints
+    pub bytes_reclaimed: u64,
 }
 
 impl Default for CompactionPolicy {
@@ -218,7 +290,7 @@ impl Default for CompactionPolicy {
 }
 
 /// A user note/observation during debugging
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DebugNote {
     pub timestamp: DateTime<Utc>,
     pub content: String,
@@ -244,6 +316,191 @@ pub struct SessionMetrics {
     pub gaps_filled: u64,
 }
 
+/// What restoring a checkpoint would change in the live state
+///
+/// Produced by [`TemporalCheckpointManager::preview_restore`] without
+/// applying anything, so the restore can be reviewed first.
+#[derive(Clone, Debug)]
+pub struct RestoreDiff {
+    pub checkpoint_id: CheckpointId,
+    /// Live notes the checkpoint does not have (lost on restore)
+    pub notes_dropped: Vec<DebugNote>,
+    /// Checkpoint notes no longer live (brought back on restore)
+    pub notes_restored: Vec<DebugNote>,
+    pub hypotheses: Vec<HypothesisChange>,
+    pub metrics: Vec<MetricChange>,
+}
+
+impl RestoreDiff {
+    /// Returns true if restoring would change nothing
+    pub fn is_empty(&self) -> bool {
+        self.notes_dropped.is_empty()
+            && self.notes_restored.is_empty()
+            && self.hypotheses.is_empty()
+            && self.metrics.is_empty()
+    }
+}
+
+/// A hypothesis that restoring a checkpoint would change
+#[derive(Clone, Debug)]
+pub enum HypothesisChange {
+    /// Proposed after the checkpoint; restoring drops it
+    Dropped(crate::hypothesis::Hypothesis),
+    /// Deleted after the checkpoint; restoring brings it back
+    Restored(crate::hypothesis::Hypothesis),
+    /// Confidence or status changed; restoring reverts `current` to `restored`
+    Reverted {
+        current: crate::hypothesis::Hypothesis,
+        restored: crate::hypothesis::Hypothesis,
+    },
+}
+
+/// A session metric that restoring a checkpoint would change
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetricChange {
+    pub name: &'static str,
+    pub current: u64,
+    pub restored: u64,
+}
+
+/// How two checkpoints relate in a session's history
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointRelation {
+    /// Both ids name the same checkpoint
+    Same,
+    /// The first checkpoint was taken earlier in the same session
+    Ancestor,
+    /// The first checkpoint was taken later in the same session
+    Descendant,
+    /// The checkpoints belong to different sessions, so neither state led
+    /// to the other
+    Unrelated,
+}
+
+/// What changed between two checkpoints
+///
+/// Produced by [`TemporalCheckpointManager::diff`]. Changes read from the
+/// first checkpoint to the second, whatever their order in time; see
+/// [`relation`](Self::relation).
+#[derive(Clone, Debug)]
+pub struct CheckpointDiff {
+    pub from: CheckpointId,
+    pub to: CheckpointId,
+    pub relation: CheckpointRelation,
+    pub notes_added: Vec<DebugNote>,
+    pub notes_removed: Vec<DebugNote>,
+    /// Notes recorded at the same moment whose content or tags differ
+    pub notes_modified: Vec<NoteChange>,
+    pub hypotheses_added: Vec<crate::hypothesis::Hypothesis>,
+    pub hypotheses_removed: Vec<crate::hypothesis::Hypothesis>,
+    pub confidence_changes: Vec<ConfidenceDelta>,
+    pub status_transitions: Vec<StatusTransition>,
+}
+
+/// A note present in both checkpoints with different content or tags
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteChange {
+    pub before: DebugNote,
+    pub after: DebugNote,
+}
+
+/// A hypothesis whose confidence differs between two checkpoints
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfidenceDelta {
+    pub hypothesis_id: crate::hypothesis::HypothesisId,
+    pub statement: String,
+    pub before: crate::hypothesis::Confidence,
+    pub after: crate::hypothesis::Confidence,
+}
+
+/// A hypothesis whose status differs between two checkpoints
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusTransition {
+    pub hypothesis_id: crate::hypothesis::HypothesisId,
+    pub statement: String,
+    pub before: crate::hypothesis::HypothesisStatus,
+    pub after: crate::hypothesis::HypothesisStatus,
+}
+
+impl CheckpointDiff {
+    /// Returns true if the two checkpoints hold the same notes and hypotheses
+    pub fn is_empty(&self) -> bool {
+        self.notes_added.is_empty()
+            && self.notes_removed.is_empty()
+            && self.notes_modified.is_empty()
+            && self.hypotheses_added.is_empty()
+            && self.hypotheses_removed.is_empty()
+            && self.confidence_changes.is_empty()
+            && self.status_transitions.is_empty()
+    }
+
+    /// Renders the diff as plain text, one change per line
+    pub fn summary(&self) -> String {
+        use std::fmt::Write;
+
+        let mut out = match self.relation {
+            CheckpointRelation::Same => format!("Checkpoint {} compared with itself\n", self.from),
+            CheckpointRelation::Ancestor => {
+                format!("Changes from {} to later {}\n", self.from, self.to)
+            }
+            CheckpointRelation::Descendant => format!(
+                "Changes from {} back to earlier {} (reversed history)\n",
+                self.from, self.to
+            ),
+            CheckpointRelation::Unrelated => format!(
+                "Differences between {} and {} (unrelated sessions, not a history)\n",
+                self.from, self.to
+            ),
+        };
+        if self.is_empty() {
+            out.push_str("No changes\n");
+            return out;
+        }
+
+        for note in &self.notes_added {
+            let _ = writeln!(out, "+ note: {}", note.content);
+        }
+        for note in &self.notes_removed {
+            let _ = writeln!(out, "- note: {}", note.content);
+        }
+        for change in &self.notes_modified {
+            let _ = writeln!(
+                out,
+                "~ note: {} -> {}",
+                change.before.content, change.after.content
+            );
+        }
+        for hypothesis in &self.hypotheses_added {
+            let _ = writeln!(
+                out,
+                "+ hypothesis: {} ({:.2})",
+                hypothesis.statement,
+                hypothesis.posterior.get()
+            );
+        }
+        for hypothesis in &self.hypotheses_removed {
+            let _ = writeln!(out, "- hypothesis: {}", hypothesis.statement);
+        }
+        for delta in &self.confidence_changes {
+            let _ = writeln!(
+                out,
+                "~ confidence: {}: {:.2} -> {:.2}",
+                delta.statement,
+                delta.before.get(),
+                delta.after.get()
+            );
+        }
+        for transition in &self.status_transitions {
+            let _ = writeln!(
+                out,
+                "~ status: {}: {:?} -> {:?}",
+                transition.statement, transition.before, transition.after
+            );
+        }
+        out
+    }
+}
+
 /// Summary of a checkpoint (for listing)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CheckpointSummary {
@@ -262,6 +519,10 @@ pub struct TemporalCheckpointManager {
     session_id: SessionId,
     sequence_counter: Cell<u64>,
     last_checkpoint_time: RefCell<DateTime<Utc>>,
+    notes: RefCell<Vec<DebugNote>>,
+    metrics: RefCell<SessionMetrics>,
+    hypothesis_state: RefCell<Option<crate::hypothesis::types::HypothesisState>>,
+    compaction_policy: RefCell<CompactionPolicy>,
 }
 
 impl TemporalCheckpointManager {
@@ -272,9 +533,37 @@ impl TemporalCheckpointManager {
             session_id,
             sequence_counter: Cell::new(0),
             last_checkpoint_time: RefCell::new(Utc::now()),
+            notes: RefCell::new(Vec::new()),
+            metrics: RefCell::new(SessionMetrics::default()),
+            hypothesis_state: RefCell::new(None),
+            compaction_policy: RefCell::new(CompactionPolicy::default()),
         }
     }
 
+    /// Record a note in the live state
+    pub fn add_note(&self, content: impl Into<String>, tags: Vec<String>) {
+        self.notes.borrow_mut().push(DebugNote {
+            timestamp: Utc::now(),
+            content: content.into(),
+            tags,
+        });
+    }
+
+    /// Update the live session metrics
+    pub fn update_metrics(&self, update: impl FnOnce(&mut SessionMetrics)) {
+        update(&mut self.metrics.borrow_mut());
+    }
+
+    /// Set the live hypothesis state captured by later checkpoints
+    pub fn set_hypothesis_state(&self, state: crate::hypothesis::types::HypothesisState) {
+        *self.hypothesis_state.borrow_mut() = Some(state);
+    }
+
+    /// Set the policy used by [`compact`](Self::compact)
+    pub fn set_compaction_policy(&self, policy: CompactionPolicy) {
+        *self.compaction_policy.borrow_mut() = policy;
+    }
+
     /// Create a manual checkpoint
     pub fn checkpoint(&self, message: impl Into<String>) -> Result<CheckpointId> {
         let seq = self.sequence_counter.get();
@@ -332,6 +621,38 @@ impl TemporalCheckpointManager {
         self.storage.list_by_session(self.session_id)
     }
 
+    /// Stream this session's checkpoints in creation order
+    ///
+    /// Only summaries are listed up front; each full checkpoint is loaded
+    /// from storage as the stream reaches it.
+    pub fn iter_checkpoints(&self) -> impl Stream<Item = Result<TemporalCheckpoint>> + '_ {
+        self.iter_checkpoints_between(None, None)
+    }
+
+    /// Stream this session's checkpoints created at or after `since` and
+    /// before `until`, in creation order
+    ///
+    /// Either bound may be `None` to leave that side open. A failure to
+    /// list the session is yielded as the only item.
+    pub fn iter_checkpoints_between(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> impl Stream<Item = Result<TemporalCheckpoint>> + '_ {
+        let (ids, error) = match self.storage.list_by_session(self.session_id) {
+            Ok(mut summaries) => {
+                summaries.retain(|s| {
+                    since.is_none_or(|t| s.timestamp >= t) && until.is_none_or(|t| s.timestamp < t)
+                });
+                summaries.sort_by_key(|s| (s.timestamp, s.sequence_number));
+                (summaries.into_iter().map(|s| s.id).collect(), None)
+            }
+            Err(e) => (Vec::new(), Some(e)),
+        };
+
+        stream::iter(error.map(Err)).chain(stream::iter(ids).map(move |id| self.storage.get(id)))
+    }
+
     /// Get a checkpoint by ID
     pub fn get(&self, id: &CheckpointId) -> Result<Option<TemporalCheckpoint>> {
         match self.storage.get(*id) {
@@ -368,6 +689,7 @@ impl TemporalCheckpointManager {
             self.session_id,
         );
         checkpoint.tags = tags;
+        checkpoint.checksum = checkpoint.compute_checksum();
 
         self.storage.store(&checkpoint)?;
         self.update_last_checkpoint_time();
@@ -386,6 +708,189 @@ impl TemporalCheckpointManager {
         Ok(checkpoint.state.clone())
     }
 
+    /// Preview what restoring a checkpoint would change, without applying it
+    pub fn preview_restore(&self, id: &CheckpointId) -> Result<RestoreDiff> {
+        let checkpoint = self.get(id)?.ok_or_else(|| {
+            crate::errors::ReasoningError::NotFound(format!("Checkpoint {} not found", id))
+        })?;
+        let restored = checkpoint.state;
+        let live = self.capture_state()?;
+
+        let notes_dropped = live
+            .notes
+            .iter()
+            .filter(|n| !restored.notes.contains(n))
+            .cloned()
+            .collect();
+        let notes_restored = restored
+            .notes
+            .iter()
+            .filter(|n| !live.notes.contains(n))
+            .cloned()
+            .collect();
+
+        let hypotheses_of = |state: &DebugStateSnapshot| {
+            state
+                .hypothesis_state
+                .as_ref()
+                .map(|h| h.hypotheses.clone())
+                .unwrap_or_default()
+        };
+        let live_hypotheses = hypotheses_of(&live);
+        let restored_hypotheses = hypotheses_of(&restored);
+        let mut hypotheses = Vec::new();
+        for current in &live_hypotheses {
+            match restored_hypotheses.iter().find(|h| h.id == current.id) {
+                None => hypotheses.push(HypothesisChange::Dropped(current.clone())),
+                Some(old) if old.posterior != current.posterior || old.status != current.status => {
+                    hypotheses.push(HypothesisChange::Reverted {
+                        current: current.clone(),
+                        restored: old.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for old in &restored_hypotheses {
+            if !live_hypotheses.iter().any(|h| h.id == old.id) {
+                hypotheses.push(HypothesisChange::Restored(old.clone()));
+            }
+        }
+
+        let (now, then) = (&live.metrics, &restored.metrics);
+        let metrics = [
+            (
+                "checkpoints_created",
+                now.checkpoints_created,
+                then.checkpoints_created,
+            ),
+            (
+                "hypotheses_tested",
+                now.hypotheses_tested,
+                then.hypotheses_tested,
+            ),
+            (
+                "verifications_run",
+                now.verifications_run,
+                then.verifications_run,
+            ),
+            ("gaps_filled", now.gaps_filled, then.gaps_filled),
+        ]
+        .into_iter()
+        .filter(|(_, current, restored)| current != restored)
+        .map(|(name, current, restored)| MetricChange {
+            name,
+            current,
+            restored,
+        })
+        .collect();
+
+        Ok(RestoreDiff {
+            checkpoint_id: checkpoint.id,
+            notes_dropped,
+            notes_restored,
+            hypotheses,
+            metrics,
+        })
+    }
+
+    /// Compare two checkpoints, reading changes from `a` to `b`
+    ///
+    /// Checkpoints of one session form a single history ordered by
+    /// sequence number, so `a` is an ancestor of `b` when it was taken
+    /// first. Checkpoints of different sessions are compared as unrelated
+    /// branches: the diff lists their differences, not what was learned.
+    pub fn diff(&self, a: CheckpointId, b: CheckpointId) -> Result<CheckpointDiff> {
+        let load = |id: CheckpointId| {
+            self.get(&id)?.ok_or_else(|| {
+                crate::errors::ReasoningError::NotFound(format!("Checkpoint {} not found", id))
+            })
+        };
+        let (from, to) = (load(a)?, load(b)?);
+
+        let relation = if from.id == to.id {
+            CheckpointRelation::Same
+        } else if from.session_id != to.session_id {
+            CheckpointRelation::Unrelated
+        } else if (from.sequence_number, from.timestamp) <= (to.sequence_number, to.timestamp) {
+            CheckpointRelation::Ancestor
+        } else {
+            CheckpointRelation::Descendant
+        };
+
+        // Notes carry no id; the moment a note was recorded identifies it
+        let (before, after) = (&from.state.notes, &to.state.notes);
+        let mut notes_modified = Vec::new();
+        let mut notes_removed = Vec::new();
+        for note in before {
+            match after.iter().find(|n| n.timestamp == note.timestamp) {
+                Some(later) if later != note => notes_modified.push(NoteChange {
+                    before: note.clone(),
+                    after: later.clone(),
+                }),
+                Some(_) => {}
+                None => notes_removed.push(note.clone()),
+            }
+        }
+        let notes_added = after
+            .iter()
+            .filter(|n| !before.iter().any(|b| b.timestamp == n.timestamp))
+            .cloned()
+            .collect();
+
+        let hypotheses_of = |state: &DebugStateSnapshot| {
+            state
+                .hypothesis_state
+                .as_ref()
+                .map(|h| h.hypotheses.clone())
+                .unwrap_or_default()
+        };
+        let (before, after) = (hypotheses_of(&from.state), hypotheses_of(&to.state));
+        let mut hypotheses_removed = Vec::new();
+        let mut confidence_changes = Vec::new();
+        let mut status_transitions = Vec::new();
+        for old in &before {
+            let Some(new) = after.iter().find(|h| h.id == old.id) else {
+                hypotheses_removed.push(old.clone());
+                continue;
+            };
+            if old.posterior != new.posterior {
+                confidence_changes.push(ConfidenceDelta {
+                    hypothesis_id: new.id,
+                    statement: new.statement.clone(),
+                    before: old.posterior,
+                    after: new.posterior,
+                });
+            }
+            if old.status != new.status {
+                status_transitions.push(StatusTransition {
+                    hypothesis_id: new.id,
+                    statement: new.statement.clone(),
+                    before: old.status,
+                    after: new.status,
+                });
+            }
+        }
+        let hypotheses_added = after
+            .iter()
+            .filter(|h| !before.iter().any(|old| old.id == h.id))
+            .cloned()
+            .collect();
+
+        Ok(CheckpointDiff {
+            from: from.id,
+            to: to.id,
+            relation,
+            notes_added,
+            notes_removed,
+            notes_modified,
+            hypotheses_added,
+            hypotheses_removed,
+            confidence_changes,
+            status_transitions,
+        })
+    }
+
     /// Get a summary of a checkpoint by ID
     pub fn get_summary(&self, id: &CheckpointId) -> Result<Option<CheckpointSummary>> {
         match self.storage.get(*id) {
@@ -396,7 +901,7 @@ impl TemporalCheckpointManager {
                 message: cp.message,
                 trigger: cp.trigger.to_string(),
                 tags: cp.tags,
-                has_notes: false,
+                has_notes: !cp.state.notes.is_empty(),
             })),
             Err(_) => Ok(None),
         }
@@ -407,66 +912,36 @@ impl TemporalCheckpointManager {
         self.storage.delete(*id)
     }
 
-    /// Compact checkpoints, keeping only the most recent N
-    pub fn compact(&self, keep_recent: usize) -> Result<usize> {
-        self.compact_with_policy(CompactionPolicy::KeepRecent(keep_recent))
+    /// Compact checkpoints using the configured compaction policy
+    ///
+    /// Every checkpoint holds a full state snapshot, so the survivors
+    /// restore exactly as before.
+    pub fn compact(&self) -> Result<CompactionReport> {
+        let policy = self.compaction_policy.borrow().clone();
+        self.compact_with_policy(policy)
     }
 
     /// Compact checkpoints using a specific policy
-    pub fn compact_with_policy(&self, policy: CompactionPolicy) -> Result<usize> {
+    pub fn compact_with_policy(&self, policy: CompactionPolicy) -> Result<CompactionReport> {
         let all_checkpoints = self.storage.list_by_session(self.session_id)?;
-
-        // Determine which checkpoints to keep
-        let ids_to_keep: std::collections::HashSet<CheckpointId> = match &policy {
-            CompactionPolicy::KeepRecent(n) => {
-                // Sort by sequence number, keep last N
-                let mut sorted = all_checkpoints.clone();
-                sorted.sort_by_key(|cp| cp.sequence_number);
-                sorted.iter().rev().take(*n).map(|cp| cp.id).collect()
-            }
-            CompactionPolicy::PreserveTagged(tags) => {
-                // Keep all checkpoints with any of the specified tags
-                all_checkpoints
-                    .iter()
-                    .filter(|cp| cp.tags.iter().any(|t| tags.contains(t)))
-                    .map(|cp| cp.id)
-                    .collect()
-            }
-            CompactionPolicy::Hybrid {
-                keep_recent,
-                preserve_tags,
-            } => {
-                // Keep recent + preserve tagged
-                let mut to_keep = std::collections::HashSet::new();
-
-                // Add recent
-                let mut sorted = all_checkpoints.clone();
-                sorted.sort_by_key(|cp| cp.sequence_number);
-                for cp in sorted.iter().rev().take(*keep_recent) {
-                    to_keep.insert(cp.id);
-                }
-
-                // Add tagged
-                for cp in &all_checkpoints {
-                    if cp.tags.iter().any(|t| preserve_tags.contains(t)) {
-                        to_keep.insert(cp.id);
-                    }
-                }
-
-                to_keep
-            }
-        };
+        let ids_to_keep = policy.retained(&all_checkpoints, Utc::now());
 
         // Delete checkpoints not in keep list
-        let mut deleted = 0;
+        let mut report = CompactionReport::default();
         for cp in &all_checkpoints {
-            if !ids_to_keep.contains(&cp.id) {
-                self.storage.delete(cp.id)?;
-                deleted += 1;
+            if ids_to_keep.contains(&cp.id) {
+                report.kept += 1;
+                continue;
+            }
+            if let Ok(checkpoint) = self.storage.get(cp.id) {
+                let size = serde_json::to_vec(&checkpoint).map_or(0, |json| json.len());
+                report.bytes_reclaimed += size as u64;
             }
+            self.storage.delete(cp.id)?;
+            report.removed += 1;
         }
 
-        Ok(deleted)
+        Ok(report)
     }
 
     fn capture_state(&self) -> Result<DebugStateSnapshot> {
@@ -476,8 +951,9 @@ impl TemporalCheckpointManager {
             checkpoint_timestamp: Utc::now(),
             working_dir: std::env::current_dir().ok(),
             env_vars: std::env::vars().collect(),
-            metrics: SessionMetrics::default(),
-            hypothesis_state: None, // Will be populated when hypothesis state is captured
+            metrics: self.metrics.borrow().clone(),
+            hypothesis_state: self.hypothesis_state.borrow().clone(),
+            notes: self.notes.borrow().clone(),
         })
     }
 
@@ -1,13 +1,25 @@
 //! Export and Import functionality for checkpoints
 //!
-//! Provides serialization to/from JSON for backup and migration.
+//! Provides serialization to/from JSON for backup and migration, and
+//! gzip-compressed archives bundling a whole debugging session: every
+//! checkpoint, hypothesis, piece of evidence and belief dependency.
 
+use std::io::{Read, Write};
+use std::path::Path;
+
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
+use crate::belief::{BeliefGraph, EdgeKind, ReasoningSystem};
 use crate::checkpoint::{SessionId, TemporalCheckpoint};
-use crate::errors::{Result, StorageError};
+use crate::errors::{ReasoningError, Result, StorageError};
+use crate::hypothesis::{Evidence, Hypothesis, HypothesisBoard, HypothesisId};
+use crate::service::ImportResult;
 use crate::thread_safe::ThreadSafeStorage;
 
+/// Identifies a file as a reasoning archive
+const ARCHIVE_FORMAT: &str = "forge-reasoning-archive";
+
 /// Export format for a session's checkpoints
 #[derive(Serialize, Deserialize)]
 pub struct SessionExport {
@@ -17,15 +29,54 @@ pub struct SessionExport {
     pub checkpoints: Vec<TemporalCheckpoint>,
 }
 
+/// Describes the contents of a reasoning archive
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    /// Always `forge-reasoning-archive`
+    pub format: String,
+    /// Crate [`VERSION`](crate::VERSION) that wrote the archive
+    pub version: String,
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub checkpoints: usize,
+    pub hypotheses: usize,
+    pub evidence: usize,
+    pub dependencies: usize,
+}
+
+/// Full reasoning state as stored in an archive
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    manifest: ArchiveManifest,
+    checkpoints: Vec<TemporalCheckpoint>,
+    hypotheses: Vec<Hypothesis>,
+    evidence: Vec<Evidence>,
+    /// Belief graph edges as (dependent, dependee, kind)
+    dependencies: Vec<(HypothesisId, HypothesisId, EdgeKind)>,
+}
+
 /// Exports checkpoints to various formats
 pub struct CheckpointExporter {
     storage: ThreadSafeStorage,
+    board: Option<HypothesisBoard>,
+    graph: BeliefGraph,
 }
 
 impl CheckpointExporter {
     /// Create a new exporter for the given storage
     pub fn new(storage: ThreadSafeStorage) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            board: None,
+            graph: BeliefGraph::new(),
+        }
+    }
+
+    /// Include a reasoning system's hypotheses, evidence and belief graph
+    /// in archives
+    pub fn with_reasoning(mut self, system: &ReasoningSystem) -> Self {
+        self.board = Some(system.board.clone());
+        self.graph = system.graph.clone();
+        self
     }
 
     /// Export all checkpoints for a session as JSON
@@ -63,17 +114,89 @@ impl CheckpointExporter {
             StorageError::StoreFailed(format!("Failed to write export file: {}", e)).into()
         })
     }
+
+    /// Export the full reasoning state to a single archive file
+    ///
+    /// The archive is gzip-compressed JSON holding every checkpoint of
+    /// every session, plus the hypotheses, evidence and belief graph of
+    /// the system given to [`with_reasoning`](Self::with_reasoning), and
+    /// a manifest recording the crate version that wrote it.
+    pub async fn export_archive(&self, path: &Path) -> Result<()> {
+        let mut checkpoints = Vec::new();
+        for summary in self.storage.list_all()? {
+            checkpoints.push(self.storage.get(summary.id)?);
+        }
+
+        let (mut hypotheses, mut evidence) = match &self.board {
+            Some(board) => (
+                board.list().await?,
+                board.storage().list_all_evidence().await?,
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+        hypotheses.sort_by_key(|h| (h.created_at(), h.id().0));
+        evidence.sort_by_key(|e| (e.created_at(), e.id().0));
+
+        let dependencies: Vec<_> = self
+            .graph
+            .all_edges()
+            .into_iter()
+            .map(|(from, to)| {
+                let kind = self.graph.edge_kind(from, to).unwrap_or_default();
+                (from, to, kind)
+            })
+            .collect();
+
+        let archive = Archive {
+            manifest: ArchiveManifest {
+                format: ARCHIVE_FORMAT.to_string(),
+                version: crate::VERSION.to_string(),
+                exported_at: chrono::Utc::now(),
+                checkpoints: checkpoints.len(),
+                hypotheses: hypotheses.len(),
+                evidence: evidence.len(),
+                dependencies: dependencies.len(),
+            },
+            checkpoints,
+            hypotheses,
+            evidence,
+            dependencies,
+        };
+
+        let file = std::fs::File::create(path)?;
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        serde_json::to_writer(&mut encoder, &archive)?;
+        encoder.finish()?.flush()?;
+        Ok(())
+    }
 }
 
 /// Imports checkpoints from various formats
 pub struct CheckpointImporter {
     storage: ThreadSafeStorage,
+    board: Option<HypothesisBoard>,
+    graph: Mutex<BeliefGraph>,
 }
 
 impl CheckpointImporter {
     /// Create a new importer for the given storage
     pub fn new(storage: ThreadSafeStorage) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            board: None,
+            graph: Mutex::new(BeliefGraph::new()),
+        }
+    }
+
+    /// Restore archived hypotheses and evidence onto `board`
+    pub fn with_board(mut self, board: HypothesisBoard) -> Self {
+        self.board = Some(board);
+        self
+    }
+
+    /// The belief graph read by the last [`import_archive`](Self::import_archive)
+    pub fn belief_graph(&self) -> BeliefGraph {
+        self.graph.lock().clone()
     }
 
     /// Import checkpoints from JSON string
@@ -98,6 +221,76 @@ impl CheckpointImporter {
         })?;
         self.import_session(&json)
     }
+
+    /// Import a reasoning archive written by
+    /// [`CheckpointExporter::export_archive`]
+    ///
+    /// Checkpoints go into storage and, when a board was given with
+    /// [`with_board`](Self::with_board), hypotheses and evidence onto the
+    /// board with their ids and confidences intact. Items already present
+    /// are left alone. The archived belief graph is then available from
+    /// [`belief_graph`](Self::belief_graph).
+    ///
+    /// Fails without importing anything if the archive was written by a
+    /// different major version of this crate.
+    pub async fn import_archive(&self, path: &Path) -> Result<ImportResult> {
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(path)?).read_to_string(&mut json)?;
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+
+        // Check compatibility before trusting the rest of the layout
+        let manifest: ArchiveManifest = serde_json::from_value(value["manifest"].clone())?;
+        if manifest.format != ARCHIVE_FORMAT {
+            return Err(ReasoningError::ValidationFailed(format!(
+                "{} is not a reasoning archive",
+                path.display()
+            )));
+        }
+        let major = |version: &str| version.split('.').next().unwrap_or_default().to_string();
+        if major(&manifest.version) != major(crate::VERSION) {
+            return Err(ReasoningError::ValidationFailed(format!(
+                "Archive was written by version {}, incompatible with {}",
+                manifest.version,
+                crate::VERSION
+            )));
+        }
+        let archive: Archive = serde_json::from_value(value)?;
+
+        let mut result = ImportResult {
+            imported: 0,
+            skipped: 0,
+        };
+        for checkpoint in &archive.checkpoints {
+            if self.storage.get(checkpoint.id).is_ok() {
+                result.skipped += 1;
+            } else {
+                self.storage.store(checkpoint)?;
+                result.imported += 1;
+            }
+        }
+
+        if let Some(board) = &self.board {
+            let storage = board.storage();
+            for hypothesis in &archive.hypotheses {
+                if storage.get_hypothesis(hypothesis.id()).await?.is_none() {
+                    storage.create_hypothesis(hypothesis).await?;
+                }
+            }
+            for evidence in &archive.evidence {
+                if storage.get_evidence(evidence.id()).await?.is_none() {
+                    storage.attach_evidence(evidence).await?;
+                }
+            }
+        }
+
+        let mut graph = BeliefGraph::new();
+        for (from, to, kind) in archive.dependencies {
+            graph.add_dependency_of_kind(from, to, kind)?;
+        }
+        *self.graph.lock() = graph;
+
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
@@ -126,4 +319,126 @@ mod tests {
         // Empty export imports 0 checkpoints
         assert_eq!(count, 0);
     }
+
+    async fn reasoning_session() -> (ThreadSafeStorage, ReasoningSystem) {
+        use crate::checkpoint::{CheckpointTrigger, DebugStateSnapshot};
+        use crate::hypothesis::{Confidence, EvidenceMetadata, EvidenceType};
+
+        let storage = ThreadSafeStorage::in_memory().unwrap();
+        for session in [SessionId::new(), SessionId::new()] {
+            let checkpoint = TemporalCheckpoint::new(
+                1,
+                "before fix",
+                DebugStateSnapshot {
+                    session_id: session,
+                    ..Default::default()
+                },
+                CheckpointTrigger::Manual,
+                session,
+            );
+            storage.store(&checkpoint).unwrap();
+        }
+
+        let mut system = ReasoningSystem::in_memory();
+        let cause = system
+            .board
+            .propose("Cache is stale", Confidence::new(0.3).unwrap())
+            .await
+            .unwrap();
+        let symptom = system
+            .board
+            .propose_with_max_uncertainty("Lookups return old values")
+            .await
+            .unwrap();
+        system
+            .board
+            .attach_evidence(
+                cause,
+                EvidenceType::Observation,
+                0.4,
+                EvidenceMetadata::Observation {
+                    description: "value predates the write".to_string(),
+                    source_path: None,
+                },
+            )
+            .await
+            .unwrap();
+        system.add_dependency(symptom, cause).await.unwrap();
+        (storage, system)
+    }
+
+    #[tokio::test]
+    async fn test_archive_roundtrip_keeps_confidences() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.forge.gz");
+        let (storage, system) = reasoning_session().await;
+
+        CheckpointExporter::new(storage)
+            .with_reasoning(&system)
+            .export_archive(&path)
+            .await
+            .unwrap();
+
+        let board = HypothesisBoard::in_memory();
+        let importer = CheckpointImporter::new(ThreadSafeStorage::in_memory().unwrap())
+            .with_board(board.clone());
+        let result = importer.import_archive(&path).await.unwrap();
+        assert_eq!((result.imported, result.skipped), (2, 0));
+
+        let mut original = system.board.list().await.unwrap();
+        let mut restored = board.list().await.unwrap();
+        original.sort_by_key(|h| h.id().0);
+        restored.sort_by_key(|h| h.id().0);
+        assert_eq!(restored.len(), 2);
+        for (before, after) in original.iter().zip(&restored) {
+            assert_eq!(before.id(), after.id());
+            assert_eq!(before.posterior(), after.posterior());
+            assert_eq!(before.status(), after.status());
+            assert_eq!(
+                system.board.list_evidence(before.id()).await.unwrap().len(),
+                board.list_evidence(after.id()).await.unwrap().len()
+            );
+        }
+        assert_eq!(
+            importer.belief_graph().all_edges(),
+            system.graph.all_edges()
+        );
+
+        // Importing again changes nothing
+        let again = importer.import_archive(&path).await.unwrap();
+        assert_eq!((again.imported, again.skipped), (0, 2));
+        assert_eq!(board.list().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_archive_rejects_other_major_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.forge.gz");
+        let (storage, system) = reasoning_session().await;
+        CheckpointExporter::new(storage)
+            .with_reasoning(&system)
+            .export_archive(&path)
+            .await
+            .unwrap();
+
+        // Rewrite the manifest as if a future major version wrote it
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(&path).unwrap())
+            .read_to_string(&mut json)
+            .unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["manifest"]["version"] = "99.0.0".into();
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        serde_json::to_writer(&mut encoder, &value).unwrap();
+        encoder.finish().unwrap();
+
+        let storage = ThreadSafeStorage::in_memory().unwrap();
+        let importer = CheckpointImporter::new(storage.clone());
+        let err = importer.import_archive(&path).await.unwrap_err();
+        assert!(matches!(err, ReasoningError::ValidationFailed(_)));
+        assert!(storage.list_all().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,101 @@
+//! Calibration of confidences against how hypotheses were resolved
+//!
+//! Resolved hypotheses are bucketed by their final confidence. On a well
+//! calibrated board, about 80% of the hypotheses held at 0.8 turn out to
+//! be confirmed.
+
+/// Number of equal-width confidence buckets in a [`CalibrationReport`]
+pub const CALIBRATION_BUCKETS: usize = 10;
+
+/// Resolved hypotheses whose final confidence falls in one bucket
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationBucket {
+    /// Lowest confidence in the bucket, inclusive
+    pub lower: f64,
+    /// Highest confidence in the bucket, exclusive except for the last
+    pub upper: f64,
+    /// Resolved hypotheses in the bucket
+    pub count: usize,
+    /// How many of them were confirmed
+    pub confirmed: usize,
+    /// Sum of their final confidences
+    confidence_sum: f64,
+}
+
+impl CalibrationBucket {
+    /// Fraction of the bucket's hypotheses that were confirmed
+    ///
+    /// `None` for an empty bucket.
+    pub fn confirmed_fraction(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.confirmed as f64 / self.count as f64)
+    }
+
+    /// Mean final confidence of the bucket's hypotheses
+    ///
+    /// `None` for an empty bucket. Compare with
+    /// [`confirmed_fraction`](Self::confirmed_fraction): the closer the
+    /// two, the better calibrated.
+    pub fn mean_confidence(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.confidence_sum / self.count as f64)
+    }
+}
+
+/// How well final confidences predicted confirmation
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationReport {
+    /// [`CALIBRATION_BUCKETS`] buckets of equal width, lowest first
+    pub buckets: Vec<CalibrationBucket>,
+    /// Hypotheses that were confirmed or rejected
+    pub resolved: usize,
+    /// Mean squared difference between final confidence and outcome, 1
+    /// for confirmed and 0 for rejected
+    ///
+    /// 0 is perfect; always guessing 0.5 scores 0.25. `None` when nothing
+    /// is resolved.
+    pub brier_score: Option<f64>,
+}
+
+impl CalibrationReport {
+    /// Builds the report from `(final confidence, confirmed)` pairs.
+    pub(crate) fn from_outcomes(outcomes: impl IntoIterator<Item = (f64, bool)>) -> Self {
+        let width = 1.0 / CALIBRATION_BUCKETS as f64;
+        let mut buckets: Vec<CalibrationBucket> = (0..CALIBRATION_BUCKETS)
+            .map(|i| CalibrationBucket {
+                lower: i as f64 * width,
+                upper: (i + 1) as f64 * width,
+                count: 0,
+                confirmed: 0,
+                confidence_sum: 0.0,
+            })
+            .collect();
+
+        let mut resolved = 0;
+        let mut squared_error = 0.0;
+        for (confidence, confirmed) in outcomes {
+            let bucket = &mut buckets[bucket_index(confidence)];
+            bucket.count += 1;
+            bucket.confirmed += usize::from(confirmed);
+            bucket.confidence_sum += confidence;
+
+            let outcome = if confirmed { 1.0 } else { 0.0 };
+            squared_error += (confidence - outcome).powi(2);
+            resolved += 1;
+        }
+
+        Self {
+            buckets,
+            resolved,
+            brier_score: (resolved > 0).then(|| squared_error / resolved as f64),
+        }
+    }
+
+    /// The bucket holding `confidence`
+    pub fn bucket_for(&self, confidence: f64) -> &CalibrationBucket {
+        &self.buckets[bucket_index(confidence)]
+    }
+}
+
+/// Index of the bucket holding `confidence`; 1.0 goes in the last bucket
+fn bucket_index(confidence: f64) -> usize {
+    ((confidence * CALIBRATION_BUCKETS as f64) as usize).min(CALIBRATION_BUCKETS - 1)
+}
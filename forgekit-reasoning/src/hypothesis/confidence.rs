@@ -21,6 +21,9 @@ pub enum ConfidenceError {
 
     #[error("Evidence probability is zero, cannot compute Bayes update")]
     ZeroEvidenceProbability,
+
+    #[error("Log-likelihood ratio must be finite, got {0}")]
+    NonFiniteLogOdds(f64),
 }
 
 impl Confidence {
@@ -84,6 +87,49 @@ impl Confidence {
         Self::new(posterior)
     }
 
+    /// Log-odds ln(p / (1 - p)) of this confidence
+    ///
+    /// Infinite at 0.0 and 1.0.
+    pub fn log_odds(self) -> f64 {
+        (self.0 / (1.0 - self.0)).ln()
+    }
+
+    /// Confidence with the given log-odds
+    ///
+    /// Log-odds beyond what f64 can tell apart from certainty map to 0.0 or
+    /// 1.0.
+    ///
+    /// # Errors
+    /// - Returns `ConfidenceError::NaN` if `log_odds` is NaN
+    pub fn from_log_odds(log_odds: f64) -> Result<Self, ConfidenceError> {
+        // Logistic function, arranged so exp() never overflows
+        let p = if log_odds >= 0.0 {
+            1.0 / (1.0 + (-log_odds).exp())
+        } else {
+            let e = log_odds.exp();
+            e / (1.0 + e)
+        };
+        Self::new(p)
+    }
+
+    /// Update confidence by adding a log-likelihood ratio in log-odds space
+    ///
+    /// ln(P(E|H) / P(E|¬H)) added to the prior log-odds gives the same
+    /// posterior as [`update_with_evidence`](Self::update_with_evidence),
+    /// without forming P(E). To keep a belief from saturating over many
+    /// updates, accumulate the log-odds in f64 and convert once with
+    /// [`from_log_odds`](Self::from_log_odds).
+    ///
+    /// # Errors
+    /// - Returns `ConfidenceError::NonFiniteLogOdds` if the ratio is NaN
+    ///   or infinite
+    pub fn update_log_odds(&self, log_likelihood_ratio: f64) -> Result<Self, ConfidenceError> {
+        if !log_likelihood_ratio.is_finite() {
+            return Err(ConfidenceError::NonFiniteLogOdds(log_likelihood_ratio));
+        }
+        Self::from_log_odds(self.log_odds() + log_likelihood_ratio)
+    }
+
     /// Maximum uncertainty confidence (0.5)
     pub fn max_uncertainty() -> Self {
         Self(0.5)
@@ -165,6 +211,73 @@ mod tests {
         assert!((posterior.get() - 0.5).abs() < 0.01);
     }
 
+    #[test]
+    fn test_log_odds_update_matches_direct_form() {
+        let updates = [(0.9, 0.1), (0.3, 0.7), (0.6, 0.4), (0.8, 0.2)];
+        let mut direct = Confidence::new(0.3).unwrap();
+        let mut log_odds = direct;
+        for (likelihood_h, likelihood_not_h) in updates {
+            direct = direct
+                .update_with_evidence(likelihood_h, likelihood_not_h)
+                .unwrap();
+            log_odds = log_odds
+                .update_log_odds((likelihood_h / likelihood_not_h).ln())
+                .unwrap();
+            assert!((direct.get() - log_odds.get()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_log_odds_update_rejects_non_finite() {
+        let c = Confidence::max_uncertainty();
+        for ratio in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(
+                c.update_log_odds(ratio),
+                Err(ConfidenceError::NonFiniteLogOdds(_))
+            ));
+        }
+        assert!(matches!(
+            Confidence::from_log_odds(f64::NAN),
+            Err(ConfidenceError::NaN)
+        ));
+    }
+
+    #[test]
+    fn test_log_odds_round_trip_and_extremes() {
+        for p in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            let c = Confidence::new(p).unwrap();
+            let back = Confidence::from_log_odds(c.log_odds()).unwrap();
+            assert!((back.get() - p).abs() < 1e-12);
+        }
+        assert_eq!(Confidence::from_log_odds(1e6).unwrap().get(), 1.0);
+        assert_eq!(Confidence::from_log_odds(-1e6).unwrap().get(), 0.0);
+        // Certainty stays certain
+        let certain = Confidence::new(1.0).unwrap();
+        assert_eq!(certain.update_log_odds(-5.0).unwrap().get(), 1.0);
+    }
+
+    #[test]
+    fn test_log_odds_accumulation_survives_50_evidences() {
+        let ratio = 9.0_f64.ln();
+        let mut direct = Confidence::max_uncertainty();
+        let mut log_odds = direct.log_odds();
+        for _ in 0..50 {
+            direct = direct.update_with_evidence(0.9, 0.1).unwrap();
+            log_odds += ratio;
+        }
+        assert_eq!(direct.get(), 1.0);
+        assert!((log_odds - 50.0 * ratio).abs() < 1e-9);
+
+        // The same evidence against undoes it only in log-odds
+        for _ in 0..50 {
+            direct = direct.update_with_evidence(0.1, 0.9).unwrap();
+            log_odds -= ratio;
+        }
+        assert_eq!(direct.get(), 1.0);
+        let recovered = Confidence::from_log_odds(log_odds).unwrap();
+        assert!((recovered.get() - 0.5).abs() < 1e-9);
+    }
+
     #[test]
     fn test_max_uncertainty() {
         let c = Confidence::max_uncertainty();
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use super::confidence::Confidence;
 use super::types::HypothesisId;
 
 /// Unique identifier for evidence
@@ -149,6 +150,16 @@ impl Evidence {
     }
 }
 
+/// One step of a hypothesis's evidence timeline
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub evidence: Evidence,
+    /// When the evidence was attached
+    pub timestamp: DateTime<Utc>,
+    /// Confidence after this evidence was applied
+    pub confidence: Confidence,
+}
+
 /// Convert evidence strength to likelihood ratio for Bayes update
 pub fn strength_to_likelihood(strength: f64, evidence_type: EvidenceType) -> (f64, f64) {
     let max_strength = evidence_type.max_strength();
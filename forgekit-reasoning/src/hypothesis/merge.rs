@@ -0,0 +1,343 @@
+//! Merging one hypothesis board into another
+//!
+//! Hypotheses from the other board are copied over with their evidence.
+//! Those whose statement matches a hypothesis already on the board are
+//! conflicts, resolved by a [`MergeStrategy`].
+
+use super::confidence::Confidence;
+use super::evidence::EvidenceId;
+use super::types::{Hypothesis, HypothesisId, HypothesisStatus};
+use super::HypothesisBoard;
+use crate::errors::{ReasoningError, Result};
+
+/// How to resolve hypotheses with the same statement on both boards
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MergeStrategy {
+    /// Keep one hypothesis starting from the prior of the side with the
+    /// higher confidence, taking the status of whichever side had it
+    KeepHigherConfidence,
+    /// Keep one hypothesis starting from the mean of the two priors
+    Average,
+    /// Copy the other hypothesis as a separate hypothesis under a new id
+    KeepBoth,
+}
+
+/// Something from the other board that was not carried over
+#[derive(Clone, Debug, PartialEq)]
+pub enum SkippedItem {
+    /// Evidence whose id is already on this board
+    Evidence(EvidenceId),
+    /// A status that cannot be reached from the kept hypothesis's status
+    Status {
+        hypothesis: HypothesisId,
+        status: HypothesisStatus,
+    },
+    /// A dependency edge, as (dependent, dependee) ids from the other
+    /// graph, that would create a self-loop or cycle once remapped
+    Dependency(HypothesisId, HypothesisId),
+}
+
+/// Outcome of merging one board into another
+#[derive(Clone, Debug, Default)]
+pub struct MergeReport {
+    /// Hypotheses copied without conflict, as (id on the other board, id here)
+    pub merged: Vec<(HypothesisId, HypothesisId)>,
+    /// Hypotheses whose statement was already here, as (id on the other
+    /// board, id here) after applying the strategy
+    pub conflicted: Vec<(HypothesisId, HypothesisId)>,
+    /// Items left out of the merge
+    pub skipped: Vec<SkippedItem>,
+}
+
+impl MergeReport {
+    /// Where a hypothesis from the other board ended up on this board
+    pub fn remap(&self, other_id: HypothesisId) -> Option<HypothesisId> {
+        self.merged
+            .iter()
+            .chain(&self.conflicted)
+            .find(|(from, _)| *from == other_id)
+            .map(|(_, to)| *to)
+    }
+}
+
+impl HypothesisBoard {
+    /// Merge every hypothesis and piece of evidence from `other` into this board
+    ///
+    /// Copied hypotheses keep their confidence, which already reflects the
+    /// evidence copied with them. When both boards hold a hypothesis, the
+    /// kept one's confidence is recomputed like [`merge`](Self::merge)
+    /// does: the combined evidence is replayed, oldest first, from the
+    /// prior the strategy picks. Status changes only follow valid
+    /// transitions; a status that cannot be reached is reported as skipped.
+    pub async fn merge_from(
+        &self,
+        other: &HypothesisBoard,
+        strategy: MergeStrategy,
+    ) -> Result<MergeReport> {
+        let mut report = MergeReport::default();
+
+        let mut incoming = other.list().await?;
+        incoming.sort_by_key(|h| (h.created_at(), h.id().0));
+
+        for theirs in incoming {
+            let mut local = self.list().await?;
+            local.sort_by_key(|h| h.id().0);
+            let existing = local
+                .into_iter()
+                .find(|h| h.statement() == theirs.statement());
+
+            let mut resolved = None;
+            let target = match (existing, strategy) {
+                (Some(_), MergeStrategy::KeepBoth) => {
+                    let id = self.copy_hypothesis(&theirs).await?;
+                    report.conflicted.push((theirs.id(), id));
+                    id
+                }
+                (Some(ours), _) => {
+                    let prior = self
+                        .resolve_conflict(&ours, &theirs, strategy, &mut report)
+                        .await?;
+                    report.conflicted.push((theirs.id(), ours.id()));
+                    let id = ours.id();
+                    resolved = Some((ours, prior));
+                    id
+                }
+                (None, _) => {
+                    let id = self.copy_hypothesis(&theirs).await?;
+                    report.merged.push((theirs.id(), id));
+                    id
+                }
+            };
+
+            for evidence in other.list_evidence(theirs.id()).await? {
+                if self.storage.get_evidence(evidence.id()).await?.is_some() {
+                    report.skipped.push(SkippedItem::Evidence(evidence.id()));
+                    continue;
+                }
+                let moved = crate::hypothesis::Evidence {
+                    hypothesis_id: target,
+                    ..evidence
+                };
+                self.storage.attach_evidence(&moved).await?;
+            }
+
+            if let Some((ours, prior)) = resolved {
+                self.replay_from(&ours, prior).await?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Recompute `hypothesis`'s confidence from `prior` over its evidence
+    async fn replay_from(&self, hypothesis: &Hypothesis, prior: Confidence) -> Result<()> {
+        let evidence = self.list_evidence(hypothesis.id()).await?;
+        let (timeline, log_odds) = self.replay(prior, evidence)?;
+        let posterior = timeline.last().map_or(prior, |entry| entry.confidence);
+
+        match log_odds {
+            Some(sum) => self.storage.update_log_odds(hypothesis.id(), sum).await?,
+            None => {
+                self.storage
+                    .update_confidence(hypothesis.id(), posterior)
+                    .await?
+            }
+        }
+        self.notify_threshold(hypothesis.id(), hypothesis.current_confidence(), posterior);
+        Ok(())
+    }
+
+    /// Store a copy of `hypothesis`, under a new id if its id is taken
+    async fn copy_hypothesis(&self, hypothesis: &Hypothesis) -> Result<HypothesisId> {
+        let mut copy = hypothesis.clone();
+        if self.storage.get_hypothesis(copy.id()).await?.is_some() {
+            copy.id = HypothesisId::new();
+        }
+        self.storage.create_hypothesis(&copy).await
+    }
+
+    /// Apply `strategy`'s status change to `ours` given the conflicting
+    /// `theirs`, returning the prior to replay the combined evidence from
+    async fn resolve_conflict(
+        &self,
+        ours: &Hypothesis,
+        theirs: &Hypothesis,
+        strategy: MergeStrategy,
+        report: &mut MergeReport,
+    ) -> Result<Confidence> {
+        let (mine, other) = (ours.posterior().get(), theirs.posterior().get());
+        let (prior, adopt_status) = match strategy {
+            MergeStrategy::KeepHigherConfidence if other > mine => (theirs.prior(), true),
+            MergeStrategy::KeepHigherConfidence => (ours.prior(), false),
+            _ => {
+                let mean = (ours.prior().get() + theirs.prior().get()) / 2.0;
+                let mean = Confidence::new(mean.clamp(0.0, 1.0))
+                    .map_err(|e| ReasoningError::InvalidState(e.to_string()))?;
+                (mean, true)
+            }
+        };
+
+        if !adopt_status || theirs.status() == ours.status() {
+            return Ok(prior);
+        }
+        match status_path(ours.status(), theirs.status()) {
+            Some(steps) => {
+                for status in steps {
+                    self.storage.set_status(ours.id(), status).await?;
+                }
+            }
+            None => report.skipped.push(SkippedItem::Status {
+                hypothesis: theirs.id(),
+                status: theirs.status(),
+            }),
+        }
+        Ok(prior)
+    }
+}
+
+/// Valid transitions leading from `from` to `to`, if there are any
+fn status_path(from: HypothesisStatus, to: HypothesisStatus) -> Option<Vec<HypothesisStatus>> {
+    if from.can_transition_to(&to) {
+        return Some(vec![to]);
+    }
+    let via = HypothesisStatus::UnderTest;
+    (from.can_transition_to(&via) && via.can_transition_to(&to)).then(|| vec![via, to])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hypothesis::{EvidenceMetadata, EvidenceType};
+
+    fn observe(description: &str) -> EvidenceMetadata {
+        EvidenceMetadata::Observation {
+            description: description.to_string(),
+            source_path: None,
+        }
+    }
+
+    async fn boards() -> (HypothesisBoard, HypothesisBoard, HypothesisId, HypothesisId) {
+        let ours = HypothesisBoard::in_memory();
+        let theirs = HypothesisBoard::in_memory();
+        let mine = ours
+            .propose("Cache is stale", Confidence::new(0.4).unwrap())
+            .await
+            .unwrap();
+        let other = theirs
+            .propose("Cache is stale", Confidence::new(0.8).unwrap())
+            .await
+            .unwrap();
+        theirs
+            .attach_evidence(other, EvidenceType::Observation, 0.3, observe("old value"))
+            .await
+            .unwrap();
+        theirs
+            .set_status(other, HypothesisStatus::UnderTest)
+            .await
+            .unwrap();
+        theirs
+            .set_status(other, HypothesisStatus::Confirmed)
+            .await
+            .unwrap();
+        (ours, theirs, mine, other)
+    }
+
+    #[tokio::test]
+    async fn test_merge_from_keep_higher_confidence() {
+        let (ours, theirs, mine, other) = boards().await;
+        let unique = theirs
+            .propose_with_max_uncertainty("Index is corrupt")
+            .await
+            .unwrap();
+        let their_confidence = theirs.get(other).await.unwrap().unwrap().posterior();
+
+        let report = ours
+            .merge_from(&theirs, MergeStrategy::KeepHigherConfidence)
+            .await
+            .unwrap();
+
+        assert_eq!(report.conflicted, vec![(other, mine)]);
+        assert_eq!(report.merged, vec![(unique, unique)]);
+        assert!(report.skipped.is_empty());
+        assert_eq!(ours.list().await.unwrap().len(), 2);
+
+        let kept = ours.get(mine).await.unwrap().unwrap();
+        assert_eq!(kept.posterior(), their_confidence);
+        // Proposed -> UnderTest -> Confirmed, one valid step at a time
+        assert_eq!(kept.status(), HypothesisStatus::Confirmed);
+        assert_eq!(ours.list_supporting_evidence(mine).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_merge_from_average_and_keep_both() {
+        let (ours, theirs, mine, other) = boards().await;
+        let their_confidence = theirs.get(other).await.unwrap().unwrap().posterior();
+        let report = ours
+            .merge_from(&theirs, MergeStrategy::Average)
+            .await
+            .unwrap();
+        assert_eq!(report.remap(other), Some(mine));
+        // The evidence is applied once, from the mean of the priors
+        let averaged = ours.get(mine).await.unwrap().unwrap().posterior();
+        let fresh = HypothesisBoard::in_memory();
+        let expected = fresh
+            .propose("Cache is stale", Confidence::new(0.6).unwrap())
+            .await
+            .unwrap();
+        let (_, expected) = fresh
+            .attach_evidence(
+                expected,
+                EvidenceType::Observation,
+                0.3,
+                observe("old value"),
+            )
+            .await
+            .unwrap();
+        assert!((averaged.get() - expected.get()).abs() < 1e-9);
+        assert_ne!(averaged, their_confidence);
+        let timeline = ours.evidence_timeline(mine).await.unwrap();
+        assert_eq!(timeline.len(), 1);
+
+        // Merging again: the evidence is already here
+        let again = ours
+            .merge_from(&theirs, MergeStrategy::Average)
+            .await
+            .unwrap();
+        assert!(matches!(again.skipped[..], [SkippedItem::Evidence(_)]));
+
+        let (ours, theirs, mine, other) = boards().await;
+        let report = ours
+            .merge_from(&theirs, MergeStrategy::KeepBoth)
+            .await
+            .unwrap();
+        let copy = report.remap(other).unwrap();
+        assert_ne!(copy, mine);
+        assert_eq!(ours.list().await.unwrap().len(), 2);
+        assert_eq!(ours.list_evidence(copy).await.unwrap().len(), 1);
+        assert!(ours.list_evidence(mine).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_from_skips_unreachable_status() {
+        let (ours, theirs, mine, other) = boards().await;
+        ours.set_status(mine, HypothesisStatus::Rejected)
+            .await
+            .unwrap();
+
+        let report = ours
+            .merge_from(&theirs, MergeStrategy::Average)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            report.skipped,
+            vec![SkippedItem::Status {
+                hypothesis: other,
+                status: HypothesisStatus::Confirmed,
+            }]
+        );
+        let kept = ours.get(mine).await.unwrap().unwrap();
+        assert_eq!(kept.status(), HypothesisStatus::Rejected);
+        assert!((0.0..=1.0).contains(&kept.posterior().get()));
+    }
+}
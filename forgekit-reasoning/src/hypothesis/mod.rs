@@ -4,35 +4,73 @@
 //! during debugging. LLMs can maintain explicit belief states with proper
 //! Bayesian updates as evidence accumulates.
 
+pub mod calibration;
 pub mod confidence;
 pub mod evidence;
+pub mod merge;
 pub mod storage;
 pub mod types;
 
 // Public exports
+pub use calibration::{CalibrationBucket, CalibrationReport, CALIBRATION_BUCKETS};
 pub use confidence::{Confidence, ConfidenceError};
-pub use evidence::{strength_to_likelihood, Evidence, EvidenceId, EvidenceMetadata, EvidenceType};
+pub use evidence::{
+    strength_to_likelihood, Evidence, EvidenceId, EvidenceMetadata, EvidenceType, TimelineEntry,
+};
+pub use merge::{MergeReport, MergeStrategy, SkippedItem};
 pub use storage::{HypothesisStorage, InMemoryHypothesisStorage};
-pub use types::{Hypothesis, HypothesisId, HypothesisState, HypothesisStatus};
+pub use types::{Hypothesis, HypothesisId, HypothesisState, HypothesisStatus, RankWeights};
 
 use crate::errors::Result;
+use parking_lot::Mutex;
 use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Threshold subscriptions: the threshold and the sender to notify
+type ThresholdWatchers = Mutex<Vec<(Confidence, mpsc::Sender<(HypothesisId, Confidence)>)>>;
 
 /// Main API for hypothesis management
 #[derive(Clone)]
 pub struct HypothesisBoard {
     storage: Arc<dyn HypothesisStorage>,
+    watchers: Arc<ThresholdWatchers>,
+    /// Set by [`with_log_odds`](Self::with_log_odds)
+    log_odds: bool,
 }
 
 impl HypothesisBoard {
     pub fn new(storage: Arc<dyn HypothesisStorage>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            watchers: Arc::new(Mutex::new(Vec::new())),
+            log_odds: false,
+        }
+    }
+
+    /// Accumulate evidence in log-odds space
+    ///
+    /// [`attach_evidence`](Self::attach_evidence) then adds each evidence's
+    /// log-likelihood ratio to the hypothesis's
+    /// [`log_odds`](Hypothesis::log_odds) and stores the confidence
+    /// converted from the sum. Stored confidence still rounds to 0.0 or 1.0
+    /// after many strong updates, but the sum keeps counting, so opposing
+    /// evidence moves the belief back as far as it should.
+    /// [`evidence_timeline`](Self::evidence_timeline) and
+    /// [`merge`](Self::merge) replay evidence the same way.
+    pub fn with_log_odds(mut self) -> Self {
+        self.log_odds = true;
+        self
     }
 
     pub fn in_memory() -> Self {
         Self::new(Arc::new(InMemoryHypothesisStorage::new()))
     }
 
+    /// The storage backing this board
+    pub(crate) fn storage(&self) -> &Arc<dyn HypothesisStorage> {
+        &self.storage
+    }
+
     /// Propose a new hypothesis with explicit prior
     pub async fn propose(
         &self,
@@ -68,9 +106,39 @@ impl HypothesisBoard {
             .map_err(|e| crate::errors::ReasoningError::InvalidState(e.to_string()))?;
 
         self.storage.update_confidence(id, posterior).await?;
+        self.notify_threshold(id, current, posterior);
         Ok(posterior)
     }
 
+    /// Subscribe to threshold crossings
+    ///
+    /// The receiver gets the hypothesis id and its new confidence whenever an
+    /// update moves a hypothesis from below `threshold` to at or above it, or
+    /// back below it. Delivery is best-effort; events are dropped while the
+    /// receiver's buffer is full.
+    pub fn watch_threshold(
+        &self,
+        threshold: Confidence,
+    ) -> mpsc::Receiver<(HypothesisId, Confidence)> {
+        let (tx, rx) = mpsc::channel(100); // Buffer up to 100 events
+        self.watchers.lock().push((threshold, tx));
+        rx
+    }
+
+    /// Emit threshold events for a confidence change
+    fn notify_threshold(&self, id: HypothesisId, before: Confidence, after: Confidence) {
+        let mut watchers = self.watchers.lock();
+        watchers.retain(|(_, tx)| !tx.is_closed());
+        for (threshold, tx) in watchers.iter() {
+            let was_above = before.get() >= threshold.get();
+            let is_above = after.get() >= threshold.get();
+            if was_above != is_above {
+                // Best-effort delivery (try_send is non-blocking)
+                let _ = tx.try_send((id, after));
+            }
+        }
+    }
+
     /// Update hypothesis status
     pub async fn set_status(&self, id: HypothesisId, status: HypothesisStatus) -> Result<()> {
         self.storage.set_status(id, status).await
@@ -86,6 +154,72 @@ impl HypothesisBoard {
         self.storage.list_hypotheses().await
     }
 
+    /// Rank all hypotheses, best current theory first
+    ///
+    /// Scores combine posterior confidence, the balance of supporting over
+    /// refuting evidence, and how recently the hypothesis or its evidence
+    /// changed, as described on [`RankWeights`]. Equal scores are ordered
+    /// by hypothesis id so the ranking is deterministic.
+    pub async fn ranked(&self, weights: RankWeights) -> Result<Vec<(Hypothesis, f64)>> {
+        let factors = [
+            weights.posterior_weight,
+            weights.evidence_weight,
+            weights.recency_weight,
+        ];
+        if factors.iter().any(|w| !w.is_finite()) || weights.recency_half_life.is_zero() {
+            return Err(crate::errors::ReasoningError::InvalidState(format!(
+                "Invalid rank weights: {:?}",
+                weights
+            )));
+        }
+
+        let now = chrono::Utc::now();
+        let half_life = weights.recency_half_life.as_secs_f64();
+        let mut ranked = Vec::new();
+        for hypothesis in self.list().await? {
+            let evidence = self.list_evidence(hypothesis.id()).await?;
+            let supporting = evidence.iter().filter(|e| e.is_supporting()).count() as f64;
+            let refuting = evidence.iter().filter(|e| e.is_refuting()).count() as f64;
+            let balance = (supporting - refuting) / (supporting + refuting + 1.0);
+
+            let last_change = evidence
+                .iter()
+                .map(|e| e.created_at())
+                .fold(hypothesis.updated_at(), std::cmp::max);
+            let age = (now - last_change).to_std().unwrap_or_default();
+            let recency = 0.5_f64.powf(age.as_secs_f64() / half_life);
+
+            let score = weights.posterior_weight * hypothesis.posterior().get()
+                + weights.evidence_weight * balance
+                + weights.recency_weight * recency;
+            ranked.push((hypothesis, score));
+        }
+
+        ranked.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| a.id().0.cmp(&b.id().0))
+        });
+        Ok(ranked)
+    }
+
+    /// Compare final confidences with how hypotheses were resolved
+    ///
+    /// Only `Confirmed` and `Rejected` hypotheses count; those still
+    /// proposed or under test are left out. See [`CalibrationReport`].
+    pub async fn calibration_report(&self) -> Result<CalibrationReport> {
+        let outcomes = self
+            .list()
+            .await?
+            .into_iter()
+            .filter_map(|h| match h.status() {
+                HypothesisStatus::Confirmed => Some((h.posterior().get(), true)),
+                HypothesisStatus::Rejected => Some((h.posterior().get(), false)),
+                HypothesisStatus::Proposed | HypothesisStatus::UnderTest => None,
+            });
+        Ok(CalibrationReport::from_outcomes(outcomes))
+    }
+
     /// Delete a hypothesis
     pub async fn delete(&self, id: HypothesisId) -> Result<bool> {
         self.storage.delete_hypothesis(id).await
@@ -113,13 +247,41 @@ impl HypothesisBoard {
             strength_to_likelihood(evidence.strength(), evidence_type);
 
         // Update hypothesis confidence
-        let posterior = self
-            .update_with_evidence(hypothesis_id, likelihood_h, likelihood_not_h)
-            .await?;
+        let posterior = if self.log_odds {
+            self.accumulate_log_odds(hypothesis_id, (likelihood_h / likelihood_not_h).ln())
+                .await?
+        } else {
+            self.update_with_evidence(hypothesis_id, likelihood_h, likelihood_not_h)
+                .await?
+        };
 
         Ok((evidence_id, posterior))
     }
 
+    /// Add a log-likelihood ratio to a hypothesis's accumulated log-odds
+    ///
+    /// The sum restarts from the stored confidence if that was last set
+    /// by anything other than this path.
+    async fn accumulate_log_odds(
+        &self,
+        id: HypothesisId,
+        log_likelihood_ratio: f64,
+    ) -> Result<Confidence> {
+        let hypothesis = self.storage.get_hypothesis(id).await?.ok_or_else(|| {
+            crate::errors::ReasoningError::NotFound(format!("Hypothesis {} not found", id))
+        })?;
+
+        let current = hypothesis.current_confidence();
+        let sum = hypothesis.log_odds().unwrap_or_else(|| current.log_odds());
+        let sum = add_log_odds(sum, log_likelihood_ratio)?;
+        let posterior = Confidence::from_log_odds(sum)
+            .map_err(|e| crate::errors::ReasoningError::InvalidState(e.to_string()))?;
+
+        self.storage.update_log_odds(id, sum).await?;
+        self.notify_threshold(id, current, posterior);
+        Ok(posterior)
+    }
+
     /// Get evidence by ID
     pub async fn get_evidence(&self, id: EvidenceId) -> Result<Option<Evidence>> {
         self.storage.get_evidence(id).await
@@ -132,6 +294,67 @@ impl HypothesisBoard {
             .await
     }
 
+    /// Evidence for a hypothesis in the order it was attached
+    ///
+    /// Each entry carries the confidence after that evidence, replaying the
+    /// Bayes updates from the prior (in log-odds on boards that accumulate
+    /// them), so the last entry matches the stored posterior unless
+    /// confidence was also set without evidence.
+    pub async fn evidence_timeline(
+        &self,
+        hypothesis_id: HypothesisId,
+    ) -> Result<Vec<TimelineEntry>> {
+        let hypothesis = self
+            .storage
+            .get_hypothesis(hypothesis_id)
+            .await?
+            .ok_or_else(|| {
+                crate::errors::ReasoningError::NotFound(format!(
+                    "Hypothesis {} not found",
+                    hypothesis_id
+                ))
+            })?;
+
+        let evidence = self.list_evidence(hypothesis_id).await?;
+        Ok(self.replay(hypothesis.prior(), evidence)?.0)
+    }
+
+    /// Apply `evidence` to `prior`, oldest first
+    ///
+    /// Returns the timeline and the final log-odds, which are only
+    /// accumulated on boards set up [`with_log_odds`](Self::with_log_odds).
+    fn replay(
+        &self,
+        prior: Confidence,
+        mut evidence: Vec<Evidence>,
+    ) -> Result<(Vec<TimelineEntry>, Option<f64>)> {
+        let invalid =
+            |e: ConfidenceError| crate::errors::ReasoningError::InvalidState(e.to_string());
+        evidence.sort_by_key(|e| e.created_at());
+        let mut confidence = prior;
+        let mut log_odds = self.log_odds.then(|| prior.log_odds());
+        let mut timeline = Vec::with_capacity(evidence.len());
+        for e in evidence {
+            let (likelihood_h, likelihood_not_h) =
+                strength_to_likelihood(e.strength(), e.evidence_type());
+            confidence = match log_odds.as_mut() {
+                Some(sum) => {
+                    *sum = add_log_odds(*sum, (likelihood_h / likelihood_not_h).ln())?;
+                    Confidence::from_log_odds(*sum).map_err(invalid)?
+                }
+                None => confidence
+                    .update_with_evidence(likelihood_h, likelihood_not_h)
+                    .map_err(invalid)?,
+            };
+            timeline.push(TimelineEntry {
+                timestamp: e.created_at(),
+                evidence: e,
+                confidence,
+            });
+        }
+        Ok((timeline, log_odds))
+    }
+
     /// Trace supporting evidence for a hypothesis
     pub async fn list_supporting_evidence(
         &self,
@@ -155,6 +378,56 @@ impl HypothesisBoard {
         self.storage.delete_evidence(id).await
     }
 
+    /// Merge `absorb` into `keep`
+    ///
+    /// Moves all of `absorb`'s evidence to `keep`, recomputes `keep`'s
+    /// posterior from its prior over the combined evidence (oldest first),
+    /// and deletes `absorb`. Returns the recomputed confidence.
+    ///
+    /// Dependency edges live in the belief graph; use
+    /// [`ReasoningSystem::merge`](crate::belief::ReasoningSystem::merge) to
+    /// redirect them as well.
+    pub async fn merge(&self, keep: HypothesisId, absorb: HypothesisId) -> Result<Confidence> {
+        if keep == absorb {
+            return Err(crate::errors::ReasoningError::InvalidState(format!(
+                "Cannot merge hypothesis {} into itself",
+                keep
+            )));
+        }
+        let survivor = self.storage.get_hypothesis(keep).await?.ok_or_else(|| {
+            crate::errors::ReasoningError::NotFound(format!("Hypothesis {} not found", keep))
+        })?;
+        if self.storage.get_hypothesis(absorb).await?.is_none() {
+            return Err(crate::errors::ReasoningError::NotFound(format!(
+                "Hypothesis {} not found",
+                absorb
+            )));
+        }
+
+        for evidence in self.list_evidence(absorb).await? {
+            let moved = Evidence {
+                hypothesis_id: keep,
+                ..evidence
+            };
+            self.storage.delete_evidence(moved.id()).await?;
+            self.storage.attach_evidence(&moved).await?;
+        }
+
+        let evidence = self.list_evidence(keep).await?;
+        let (timeline, log_odds) = self.replay(survivor.prior(), evidence)?;
+        let posterior = timeline
+            .last()
+            .map_or(survivor.prior(), |entry| entry.confidence);
+
+        match log_odds {
+            Some(sum) => self.storage.update_log_odds(keep, sum).await?,
+            None => self.storage.update_confidence(keep, posterior).await?,
+        }
+        self.notify_threshold(keep, survivor.current_confidence(), posterior);
+        self.storage.delete_hypothesis(absorb).await?;
+        Ok(posterior)
+    }
+
     /// Query hypothesis state at a past checkpoint time
     ///
     /// This enables time-travel queries: "What did I believe at checkpoint X?"
@@ -177,8 +450,28 @@ impl HypothesisBoard {
         id: HypothesisId,
         confidence: Confidence,
     ) -> Result<()> {
-        self.storage.update_confidence(id, confidence).await
+        let before = self
+            .storage
+            .get_hypothesis(id)
+            .await?
+            .map(|h| h.current_confidence());
+        self.storage.update_confidence(id, confidence).await?;
+        if let Some(before) = before {
+            self.notify_threshold(id, before, confidence);
+        }
+        Ok(())
+    }
+}
+
+/// Add a log-likelihood ratio to a log-odds sum, rejecting non-finite
+/// ratios
+fn add_log_odds(sum: f64, log_likelihood_ratio: f64) -> Result<f64> {
+    if !log_likelihood_ratio.is_finite() {
+        return Err(crate::errors::ReasoningError::InvalidState(
+            ConfidenceError::NonFiniteLogOdds(log_likelihood_ratio).to_string(),
+        ));
     }
+    Ok(sum + log_likelihood_ratio)
 }
 
 #[cfg(test)]
@@ -220,6 +513,79 @@ mod tests {
         assert_eq!(h.posterior(), posterior);
     }
 
+    #[tokio::test]
+    async fn test_calibration_report_buckets_resolved_hypotheses() {
+        let board = HypothesisBoard::in_memory();
+        let resolve = |confidence: f64, status: Option<HypothesisStatus>| {
+            let board = board.clone();
+            async move {
+                let id = board
+                    .propose("h", Confidence::new(confidence).unwrap())
+                    .await
+                    .unwrap();
+                board
+                    .set_status(id, HypothesisStatus::UnderTest)
+                    .await
+                    .unwrap();
+                if let Some(status) = status {
+                    board.set_status(id, status).await.unwrap();
+                }
+            }
+        };
+        // Four held at 0.85: three confirmed
+        for status in [
+            HypothesisStatus::Confirmed,
+            HypothesisStatus::Confirmed,
+            HypothesisStatus::Confirmed,
+            HypothesisStatus::Rejected,
+        ] {
+            resolve(0.85, Some(status)).await;
+        }
+        // Two held at 0.15: both rejected
+        resolve(0.15, Some(HypothesisStatus::Rejected)).await;
+        resolve(0.15, Some(HypothesisStatus::Rejected)).await;
+        // Certain and confirmed: goes in the top bucket
+        resolve(1.0, Some(HypothesisStatus::Confirmed)).await;
+        // Unresolved hypotheses are ignored
+        resolve(0.55, None).await;
+        board
+            .propose("proposed", Confidence::new(0.55).unwrap())
+            .await
+            .unwrap();
+
+        let report = board.calibration_report().await.unwrap();
+
+        assert_eq!(report.resolved, 7);
+        assert_eq!(report.buckets.len(), CALIBRATION_BUCKETS);
+        let high = report.bucket_for(0.85);
+        assert_eq!((high.count, high.confirmed), (4, 3));
+        assert_eq!(high.confirmed_fraction(), Some(0.75));
+        assert!((high.mean_confidence().unwrap() - 0.85).abs() < 1e-9);
+        let low = report.bucket_for(0.15);
+        assert_eq!((low.count, low.confirmed), (2, 0));
+        assert_eq!(low.confirmed_fraction(), Some(0.0));
+        let top = &report.buckets[CALIBRATION_BUCKETS - 1];
+        assert_eq!((top.count, top.confirmed), (1, 1));
+        assert_eq!(report.bucket_for(0.55).count, 0);
+        assert_eq!(report.bucket_for(0.55).confirmed_fraction(), None);
+
+        // (3 * 0.15^2 + 0.85^2 + 2 * 0.15^2 + 0) / 7
+        let expected = (5.0 * 0.15_f64.powi(2) + 0.85_f64.powi(2)) / 7.0;
+        assert!((report.brier_score.unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_calibration_report_without_resolved_hypotheses() {
+        let board = HypothesisBoard::in_memory();
+        board.propose_with_max_uncertainty("open").await.unwrap();
+
+        let report = board.calibration_report().await.unwrap();
+
+        assert_eq!(report.resolved, 0);
+        assert_eq!(report.brier_score, None);
+        assert!(report.buckets.iter().all(|b| b.count == 0));
+    }
+
     #[tokio::test]
     async fn test_status_transitions() {
         let board = HypothesisBoard::in_memory();
@@ -275,6 +641,152 @@ mod tests {
         assert_eq!(evidence.hypothesis_id(), id);
     }
 
+    #[tokio::test]
+    async fn test_log_odds_board_matches_direct_form_for_few_updates() {
+        let direct = HypothesisBoard::in_memory();
+        let accumulating = HypothesisBoard::in_memory().with_log_odds();
+        let prior = Confidence::new(0.3).unwrap();
+        let a = direct.propose("Test", prior).await.unwrap();
+        let b = accumulating.propose("Test", prior).await.unwrap();
+        let log_odds = |board: &HypothesisBoard, id| {
+            let board = board.clone();
+            async move { board.get(id).await.unwrap().unwrap().log_odds() }
+        };
+        assert_eq!(log_odds(&accumulating, b).await, None);
+
+        for strength in [0.4, -0.2, 0.5] {
+            let observe = || EvidenceMetadata::Observation {
+                description: "Observed".to_string(),
+                source_path: None,
+            };
+            let (_, expected) = direct
+                .attach_evidence(a, EvidenceType::Observation, strength, observe())
+                .await
+                .unwrap();
+            let (_, actual) = accumulating
+                .attach_evidence(b, EvidenceType::Observation, strength, observe())
+                .await
+                .unwrap();
+            assert!((expected.get() - actual.get()).abs() < 1e-12);
+        }
+        assert!(log_odds(&direct, a).await.is_none());
+        let stored = accumulating.get(b).await.unwrap().unwrap().posterior();
+        assert!((log_odds(&accumulating, b).await.unwrap() - stored.log_odds()).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_log_odds_board_replays_50_evidences_without_saturating() {
+        let direct = HypothesisBoard::in_memory();
+        let accumulating = HypothesisBoard::in_memory().with_log_odds();
+        let a = direct.propose_with_max_uncertainty("Test").await.unwrap();
+        let b = accumulating
+            .propose_with_max_uncertainty("Test")
+            .await
+            .unwrap();
+
+        let attach = |board: &HypothesisBoard, id, strength| {
+            let board = board.clone();
+            async move {
+                let metadata = EvidenceMetadata::Observation {
+                    description: "Observed".to_string(),
+                    source_path: None,
+                };
+                board
+                    .attach_evidence(id, EvidenceType::Observation, strength, metadata)
+                    .await
+                    .unwrap()
+                    .1
+            }
+        };
+
+        let log_odds = |id| {
+            let board = accumulating.clone();
+            async move { board.get(id).await.unwrap().unwrap().log_odds() }
+        };
+        let mut previous = log_odds(b).await;
+        for _ in 0..50 {
+            attach(&direct, a, 0.5).await;
+            attach(&accumulating, b, 0.5).await;
+            let current = log_odds(b).await;
+            assert!(current > previous);
+            previous = current;
+        }
+        let ratio = 9.0_f64.ln();
+        assert!((log_odds(b).await.unwrap() - 50.0 * ratio).abs() < 1e-9);
+        assert_eq!(direct.get(a).await.unwrap().unwrap().posterior().get(), 1.0);
+
+        // 50 refuting evidences undo the supporting ones only in log-odds
+        let mut last = Confidence::max_uncertainty();
+        for _ in 0..50 {
+            attach(&direct, a, -0.5).await;
+            last = attach(&accumulating, b, -0.5).await;
+        }
+        assert_eq!(direct.get(a).await.unwrap().unwrap().posterior().get(), 1.0);
+        assert!((last.get() - 0.5).abs() < 1e-9);
+        assert!(log_odds(b).await.unwrap().abs() < 1e-9);
+
+        // The timeline replays the same sums and ends at the stored posterior
+        let timeline = accumulating.evidence_timeline(b).await.unwrap();
+        assert_eq!(timeline.len(), 100);
+        assert_eq!(timeline[49].confidence.get(), 1.0);
+        assert!((timeline[99].confidence.get() - 0.5).abs() < 1e-9);
+        let stored = accumulating.get(b).await.unwrap().unwrap().posterior();
+        assert_eq!(timeline[99].confidence, stored);
+    }
+
+    #[tokio::test]
+    async fn test_log_odds_board_merges_saturating_evidence() {
+        let board = HypothesisBoard::in_memory().with_log_odds();
+        let keep = board.propose_with_max_uncertainty("Keep").await.unwrap();
+        let absorb = board.propose_with_max_uncertainty("Absorb").await.unwrap();
+        let observe = || EvidenceMetadata::Observation {
+            description: "Observed".to_string(),
+            source_path: None,
+        };
+        for (id, strength) in [(keep, 0.5), (absorb, -0.5)] {
+            for _ in 0..50 {
+                board
+                    .attach_evidence(id, EvidenceType::Observation, strength, observe())
+                    .await
+                    .unwrap();
+            }
+        }
+        assert_eq!(
+            board.get(keep).await.unwrap().unwrap().posterior().get(),
+            1.0
+        );
+
+        // Opposing evidence that saturated separately cancels out once merged
+        let merged = board.merge(keep, absorb).await.unwrap();
+        assert!((merged.get() - 0.5).abs() < 1e-9);
+        let survivor = board.get(keep).await.unwrap().unwrap();
+        assert_eq!(survivor.posterior(), merged);
+        assert!(survivor.log_odds().unwrap().abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_log_odds_board_restarts_from_direct_updates() {
+        let board = HypothesisBoard::in_memory().with_log_odds();
+        let id = board.propose_with_max_uncertainty("Test").await.unwrap();
+        let metadata = || EvidenceMetadata::Observation {
+            description: "Observed".to_string(),
+            source_path: None,
+        };
+        board
+            .attach_evidence(id, EvidenceType::Observation, 0.5, metadata())
+            .await
+            .unwrap();
+
+        let set = Confidence::new(0.2).unwrap();
+        board.update_confidence_direct(id, set).await.unwrap();
+        let (_, posterior) = board
+            .attach_evidence(id, EvidenceType::Observation, 0.5, metadata())
+            .await
+            .unwrap();
+        let expected = set.update_with_evidence(0.9, 0.1).unwrap();
+        assert!((posterior.get() - expected.get()).abs() < 1e-12);
+    }
+
     #[tokio::test]
     async fn test_list_evidence_by_hypothesis() {
         let board = HypothesisBoard::in_memory();
@@ -355,4 +867,197 @@ mod tests {
         let evidence = board.get_evidence(evidence_id).await.unwrap().unwrap();
         assert_eq!(evidence.strength(), 0.5); // Clamped
     }
+
+    #[tokio::test]
+    async fn test_merge_combines_evidence_and_deletes_absorbed() {
+        let board = HypothesisBoard::in_memory();
+        let keep = board
+            .propose_with_max_uncertainty("Cache is stale")
+            .await
+            .unwrap();
+        let absorb = board
+            .propose_with_max_uncertainty("Cache is not invalidated")
+            .await
+            .unwrap();
+
+        let observe = |description: &str| EvidenceMetadata::Observation {
+            description: description.to_string(),
+            source_path: None,
+        };
+        let (kept_evidence, _) = board
+            .attach_evidence(keep, EvidenceType::Observation, 0.3, observe("old value"))
+            .await
+            .unwrap();
+        let (moved_evidence, _) = board
+            .attach_evidence(absorb, EvidenceType::Observation, 0.4, observe("no evict"))
+            .await
+            .unwrap();
+        let before = board.get(keep).await.unwrap().unwrap().posterior();
+
+        let merged = board.merge(keep, absorb).await.unwrap();
+
+        let ids: Vec<EvidenceId> = board
+            .list_evidence(keep)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.id())
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&kept_evidence) && ids.contains(&moved_evidence));
+
+        // Both supporting updates applied from the prior
+        let expected = Confidence::max_uncertainty()
+            .update_with_evidence(0.5 + 0.3 / 0.5 * 0.4, 0.5 - 0.3 / 0.5 * 0.4)
+            .unwrap()
+            .update_with_evidence(0.5 + 0.4 / 0.5 * 0.4, 0.5 - 0.4 / 0.5 * 0.4)
+            .unwrap();
+        assert!((merged.get() - expected.get()).abs() < 1e-9);
+        assert!(merged.get() > before.get());
+        assert_eq!(board.get(keep).await.unwrap().unwrap().posterior(), merged);
+
+        assert!(board.get(absorb).await.unwrap().is_none());
+        assert!(board.list_evidence(absorb).await.unwrap().is_empty());
+        assert!(board.merge(keep, keep).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_evidence_timeline_replays_to_posterior() {
+        let board = HypothesisBoard::in_memory();
+        let id = board
+            .propose_with_max_uncertainty("Parser drops trailing comments")
+            .await
+            .unwrap();
+        assert!(board.evidence_timeline(id).await.unwrap().is_empty());
+
+        let observe = |description: &str| EvidenceMetadata::Observation {
+            description: description.to_string(),
+            source_path: None,
+        };
+        let (first, after_first) = board
+            .attach_evidence(id, EvidenceType::Observation, 0.4, observe("comment lost"))
+            .await
+            .unwrap();
+        let (second, after_second) = board
+            .attach_evidence(id, EvidenceType::Observation, -0.2, observe("kept once"))
+            .await
+            .unwrap();
+
+        let timeline = board.evidence_timeline(id).await.unwrap();
+        let ids: Vec<EvidenceId> = timeline.iter().map(|t| t.evidence.id()).collect();
+        assert_eq!(ids, vec![first, second]);
+        assert!(timeline[0].timestamp <= timeline[1].timestamp);
+
+        assert_eq!(timeline[0].confidence, after_first);
+        assert!(timeline[0].confidence.get() > 0.5);
+        assert_eq!(timeline[1].confidence, after_second);
+        assert!(timeline[1].confidence.get() < timeline[0].confidence.get());
+        let posterior = board.get(id).await.unwrap().unwrap().posterior();
+        assert_eq!(timeline[1].confidence, posterior);
+
+        assert!(board.evidence_timeline(HypothesisId::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ranked_demotes_refuted_hypothesis() {
+        let board = HypothesisBoard::in_memory();
+        let leading = board
+            .propose_with_max_uncertainty("Race in the cache")
+            .await
+            .unwrap();
+        let runner_up = board
+            .propose_with_max_uncertainty("Stale index")
+            .await
+            .unwrap();
+        let observe = |description: &str| EvidenceMetadata::Observation {
+            description: description.to_string(),
+            source_path: None,
+        };
+        board
+            .attach_evidence(leading, EvidenceType::Observation, 0.5, observe("flaky"))
+            .await
+            .unwrap();
+        board
+            .attach_evidence(runner_up, EvidenceType::Observation, 0.3, observe("old"))
+            .await
+            .unwrap();
+
+        let ranked = board.ranked(RankWeights::default()).await.unwrap();
+        let order: Vec<HypothesisId> = ranked.iter().map(|(h, _)| h.id()).collect();
+        assert_eq!(order, vec![leading, runner_up]);
+        assert!(ranked[0].1 > ranked[1].1);
+
+        for description in ["passes under lock", "passes single-threaded"] {
+            board
+                .attach_evidence(
+                    leading,
+                    EvidenceType::Observation,
+                    -0.4,
+                    observe(description),
+                )
+                .await
+                .unwrap();
+        }
+
+        let ranked = board.ranked(RankWeights::default()).await.unwrap();
+        let order: Vec<HypothesisId> = ranked.iter().map(|(h, _)| h.id()).collect();
+        assert_eq!(order, vec![runner_up, leading]);
+    }
+
+    #[tokio::test]
+    async fn test_ranked_breaks_ties_on_id() {
+        let board = HypothesisBoard::in_memory();
+        let mut ids = Vec::new();
+        for statement in ["A", "B", "C"] {
+            ids.push(board.propose_with_max_uncertainty(statement).await.unwrap());
+        }
+        ids.sort_by_key(|id| id.0);
+
+        let weights = RankWeights {
+            recency_weight: 0.0,
+            ..RankWeights::default()
+        };
+        let ranked = board.ranked(weights).await.unwrap();
+        let order: Vec<HypothesisId> = ranked.iter().map(|(h, _)| h.id()).collect();
+        assert_eq!(order, ids);
+        assert!(ranked.iter().all(|(_, score)| *score == ranked[0].1));
+
+        let invalid = RankWeights {
+            evidence_weight: f64::NAN,
+            ..RankWeights::default()
+        };
+        assert!(board.ranked(invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_threshold_fires_on_crossing() {
+        let board = HypothesisBoard::in_memory();
+        let id = board.propose_with_max_uncertainty("Test").await.unwrap();
+        let other = board.propose_with_max_uncertainty("Other").await.unwrap();
+        let mut alerts = board.watch_threshold(Confidence::new(0.9).unwrap());
+
+        // Stays below the threshold: no event
+        board.update_with_evidence(other, 0.6, 0.4).await.unwrap();
+        assert!(alerts.try_recv().is_err());
+
+        let metadata = EvidenceMetadata::Experiment {
+            name: "repro".to_string(),
+            test_command: "cargo test repro".to_string(),
+            output: "bug reproduced".to_string(),
+            passed: true,
+        };
+        let (_, posterior) = board
+            .attach_evidence(id, EvidenceType::Experiment, 1.0, metadata)
+            .await
+            .unwrap();
+        assert!(posterior.get() >= 0.9);
+
+        let (alert_id, confidence) = alerts.try_recv().unwrap();
+        assert_eq!(alert_id, id);
+        assert_eq!(confidence, posterior);
+
+        // Already above: further support does not re-fire
+        board.update_with_evidence(id, 0.9, 0.1).await.unwrap();
+        assert!(alerts.try_recv().is_err());
+    }
 }
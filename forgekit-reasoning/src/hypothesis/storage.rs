@@ -24,6 +24,9 @@ pub trait HypothesisStorage: Send + Sync {
     /// Update hypothesis confidence
     async fn update_confidence(&self, id: HypothesisId, posterior: Confidence) -> Result<()>;
 
+    /// Update hypothesis confidence from accumulated log-odds, storing the sum
+    async fn update_log_odds(&self, id: HypothesisId, log_odds: f64) -> Result<()>;
+
     /// Set hypothesis status
     async fn set_status(&self, id: HypothesisId, status: HypothesisStatus) -> Result<()>;
 
@@ -103,6 +106,20 @@ impl HypothesisStorage for InMemoryHypothesisStorage {
         }
     }
 
+    async fn update_log_odds(&self, id: HypothesisId, log_odds: f64) -> Result<()> {
+        let mut store = self.hypotheses.write().await;
+        if let Some(h) = store.get_mut(&id) {
+            h.update_log_odds(log_odds)
+                .map_err(ReasoningError::InvalidState)?;
+            Ok(())
+        } else {
+            Err(ReasoningError::NotFound(format!(
+                "Hypothesis {} not found",
+                id
+            )))
+        }
+    }
+
     async fn set_status(&self, id: HypothesisId, status: HypothesisStatus) -> Result<()> {
         let mut store = self.hypotheses.write().await;
         if let Some(h) = store.get_mut(&id) {
@@ -258,6 +275,25 @@ mod tests {
         assert_eq!(retrieved.posterior(), new_posterior);
     }
 
+    #[tokio::test]
+    async fn test_in_memory_update_log_odds_keeps_sum() {
+        let storage = InMemoryHypothesisStorage::new();
+        let h = Hypothesis::new("Test", Confidence::max_uncertainty());
+        let id = h.id();
+        storage.create_hypothesis(&h).await.unwrap();
+
+        storage.update_log_odds(id, 80.0).await.unwrap();
+        let retrieved = storage.get_hypothesis(id).await.unwrap().unwrap();
+        assert_eq!(retrieved.posterior().get(), 1.0);
+        assert_eq!(retrieved.log_odds(), Some(80.0));
+
+        // Setting the posterior directly drops the sum
+        let posterior = Confidence::new(0.8).unwrap();
+        storage.update_confidence(id, posterior).await.unwrap();
+        let retrieved = storage.get_hypothesis(id).await.unwrap().unwrap();
+        assert_eq!(retrieved.log_odds(), None);
+    }
+
     #[tokio::test]
     async fn test_in_memory_update_confidence_not_found() {
         let storage = InMemoryHypothesisStorage::new();
@@ -5,6 +5,7 @@
 //! - HypothesisStatus: Lifecycle state machine
 //! - Hypothesis: Main data structure with confidence tracking
 //! - HypothesisState: Checkpoint snapshot of all hypotheses
+//! - RankWeights: Factor weights for ranking hypotheses
 
 use crate::hypothesis::confidence::Confidence;
 use chrono::{DateTime, Utc};
@@ -33,6 +34,35 @@ impl std::fmt::Display for HypothesisId {
     }
 }
 
+/// Weights for ranking hypotheses by posterior, evidence and recency
+///
+/// Each factor is scaled to at most 1.0 in magnitude before weighting:
+/// the posterior as is, the evidence balance as
+/// `(supporting - refuting) / (supporting + refuting + 1)`, and recency as
+/// `0.5^(age / recency_half_life)` of the latest update or evidence.
+#[derive(Clone, Debug)]
+pub struct RankWeights {
+    /// Weight for posterior confidence (default: 0.6)
+    pub posterior_weight: f64,
+    /// Weight for the balance of supporting over refuting evidence (default: 0.3)
+    pub evidence_weight: f64,
+    /// Weight for recency of the last update (default: 0.1)
+    pub recency_weight: f64,
+    /// Age at which the recency factor halves (default: 1 hour)
+    pub recency_half_life: std::time::Duration,
+}
+
+impl Default for RankWeights {
+    fn default() -> Self {
+        Self {
+            posterior_weight: 0.6,
+            evidence_weight: 0.3,
+            recency_weight: 0.1,
+            recency_half_life: std::time::Duration::from_secs(3600),
+        }
+    }
+}
+
 /// Lifecycle status of a hypothesis
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum HypothesisStatus {
@@ -71,6 +101,10 @@ pub struct Hypothesis {
     pub status: HypothesisStatus,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Log-odds of the posterior summed over evidence, on boards that
+    /// accumulate them; cleared when the posterior is set any other way
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_odds: Option<f64>,
 }
 
 impl Hypothesis {
@@ -86,6 +120,7 @@ impl Hypothesis {
             status: HypothesisStatus::Proposed,
             created_at: now,
             updated_at: now,
+            log_odds: None,
         }
     }
 
@@ -117,9 +152,23 @@ impl Hypothesis {
         self.updated_at
     }
 
+    /// Accumulated log-odds, if the posterior was last set from them
+    pub fn log_odds(&self) -> Option<f64> {
+        self.log_odds
+    }
+
     /// Update posterior based on evidence
     pub fn update_posterior(&mut self, new_posterior: Confidence) -> Result<(), String> {
         self.posterior = new_posterior;
+        self.log_odds = None;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Set the posterior from accumulated log-odds, keeping the sum
+    pub fn update_log_odds(&mut self, log_odds: f64) -> Result<(), String> {
+        self.posterior = Confidence::from_log_odds(log_odds).map_err(|e| e.to_string())?;
+        self.log_odds = Some(log_odds);
         self.updated_at = Utc::now();
         Ok(())
     }
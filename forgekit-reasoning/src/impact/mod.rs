@@ -22,7 +22,9 @@ use crate::hypothesis::{Confidence, HypothesisBoard, HypothesisId};
 
 // Re-exports from submodules
 pub use preview::{CascadePreview, CycleWarning, PaginationState, PreviewId, PreviewPage};
-pub use propagation::{CascadeError, ConfidenceChange, PropagationConfig, PropagationResult};
+pub use propagation::{
+    CascadeError, ConfidenceChange, DecayModel, PropagationConfig, PropagationResult,
+};
 pub use snapshot::{BeliefSnapshot, SnapshotId, SnapshotStore};
 
 /// Impact analysis engine with two-step preview/confirm API
@@ -206,6 +208,7 @@ mod tests {
             decay_factor: 0.9,
             min_confidence: 0.2,
             max_cascade_size: 5000,
+            ..Default::default()
         };
 
         let engine = ImpactAnalysisEngine::with_config(board, graph, config);
@@ -366,4 +369,85 @@ mod tests {
 
         assert_eq!(page.page_number, 0);
     }
+
+    #[tokio::test]
+    async fn test_preview_breaks_two_node_cycle() {
+        let board = Arc::new(HypothesisBoard::in_memory());
+        let a = board
+            .propose("A", Confidence::new(0.3).unwrap())
+            .await
+            .unwrap();
+        let b = board
+            .propose("B", Confidence::new(0.6).unwrap())
+            .await
+            .unwrap();
+        let mut graph = BeliefGraph::new();
+        graph.add_edge_unchecked(a, b);
+        graph.add_edge_unchecked(b, a);
+        let engine = ImpactAnalysisEngine::new(board, Arc::new(graph));
+
+        let preview = engine
+            .preview(b, Confidence::new(0.9).unwrap())
+            .await
+            .unwrap();
+
+        // b -> a leans on the weaker belief, so it is the edge dropped
+        assert_eq!(preview.result.broken_edges, vec![(b, a)]);
+        assert_eq!(preview.cycle_warnings.len(), 1);
+        let warning = &preview.cycle_warnings[0];
+        assert_eq!(warning.scc_members.len(), 2);
+        assert_eq!(warning.broken_edges, vec![(b, a)]);
+        let reached: Vec<_> = preview
+            .result
+            .changes
+            .iter()
+            .map(|c| c.hypothesis_id)
+            .collect();
+        assert_eq!(reached, vec![b, a]);
+    }
+
+    #[tokio::test]
+    async fn test_preview_breaks_three_node_cycle_deterministically() {
+        let board = Arc::new(HypothesisBoard::in_memory());
+        let a = board
+            .propose("A", Confidence::new(0.5).unwrap())
+            .await
+            .unwrap();
+        let b = board
+            .propose("B", Confidence::new(0.4).unwrap())
+            .await
+            .unwrap();
+        let c = board
+            .propose("C", Confidence::new(0.8).unwrap())
+            .await
+            .unwrap();
+        // a depends on b, b on c, c on a
+        let mut graph = BeliefGraph::new();
+        graph.add_edge_unchecked(a, b);
+        graph.add_edge_unchecked(b, c);
+        graph.add_edge_unchecked(c, a);
+        let engine = ImpactAnalysisEngine::new(board, Arc::new(graph));
+
+        let preview = engine
+            .preview(c, Confidence::new(0.9).unwrap())
+            .await
+            .unwrap();
+        assert!(preview.result.cycles_detected);
+        assert_eq!(preview.result.broken_edges, vec![(a, b)]);
+        // Propagation stops at the broken edge instead of wrapping around
+        let reached: Vec<_> = preview
+            .result
+            .changes
+            .iter()
+            .map(|ch| ch.hypothesis_id)
+            .collect();
+        assert_eq!(reached, vec![c, b]);
+        assert_eq!(preview::list_cycle_warnings(&preview).len(), 1);
+
+        let again = engine
+            .preview(c, Confidence::new(0.9).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(again.result.broken_edges, preview.result.broken_edges);
+    }
 }
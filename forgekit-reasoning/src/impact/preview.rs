@@ -78,6 +78,8 @@ pub struct CascadePreview {
     pub result: PropagationResult,
     pub created_at: DateTime<Utc>,
     pub pagination: PaginationState,
+    /// Dependency cycles the cascade ran into
+    pub cycle_warnings: Vec<CycleWarning>,
 }
 
 /// A page of cascade changes
@@ -96,6 +98,10 @@ pub struct CycleWarning {
     pub scc_members: Vec<HypothesisId>,
     pub avg_confidence: f64,
     pub description: String,
+    /// Edges of this cycle ignored during propagation, as
+    /// (dependent, dependee)
+    #[serde(default)]
+    pub broken_edges: Vec<(HypothesisId, HypothesisId)>,
 }
 
 /// Create a cascade preview
@@ -116,6 +122,7 @@ pub async fn create_preview(
 
     // Create pagination state
     let pagination = PaginationState::new(result.changes.len(), page_size);
+    let cycle_warnings = cycle_warnings(&result, graph);
 
     Ok(CascadePreview {
         preview_id: PreviewId::new(),
@@ -124,9 +131,46 @@ pub async fn create_preview(
         result,
         created_at: Utc::now(),
         pagination,
+        cycle_warnings,
     })
 }
 
+/// One warning per dependency cycle that has members in the cascade
+fn cycle_warnings(result: &PropagationResult, graph: &BeliefGraph) -> Vec<CycleWarning> {
+    graph
+        .detect_cycles()
+        .into_iter()
+        .filter_map(|members| {
+            let confidences: Vec<f64> = result
+                .changes
+                .iter()
+                .filter(|c| members.contains(&c.hypothesis_id))
+                .map(|c| c.new_confidence.get())
+                .collect();
+            if confidences.is_empty() {
+                return None;
+            }
+            let avg_confidence = confidences.iter().sum::<f64>() / confidences.len() as f64;
+            let broken_edges: Vec<_> = result
+                .broken_edges
+                .iter()
+                .filter(|(from, to)| members.contains(from) && members.contains(to))
+                .copied()
+                .collect();
+            Some(CycleWarning {
+                description: format!(
+                    "Cycle detected with {} members. Ignored {} dependency edge(s) so propagation terminates.",
+                    members.len(),
+                    broken_edges.len()
+                ),
+                scc_members: members,
+                avg_confidence,
+                broken_edges,
+            })
+        })
+        .collect()
+}
+
 /// Get a page from a preview
 pub fn get_page(preview: &CascadePreview, page_number: usize) -> PreviewPage {
     // Validate page number
@@ -156,60 +200,7 @@ pub fn get_page(preview: &CascadePreview, page_number: usize) -> PreviewPage {
 
 /// List cycle warnings from preview
 pub fn list_cycle_warnings(preview: &CascadePreview) -> Vec<CycleWarning> {
-    if !preview.result.cycles_detected {
-        return vec![];
-    }
-
-    // Group changes by depth to identify potential cycles
-    // (In a full implementation, we'd use the graph's SCC detection here)
-    let mut warnings = Vec::new();
-
-    // Find cycles in the changes by looking for repeated hypothesis IDs in paths
-    let mut cycle_members: std::collections::HashSet<HypothesisId> =
-        std::collections::HashSet::new();
-
-    for change in &preview.result.changes {
-        // Check if the hypothesis appears multiple times in its own propagation path
-        let mut seen = std::collections::HashSet::new();
-        for id in &change.propagation_path {
-            if !seen.insert(*id) {
-                // Duplicate found - this is part of a cycle
-                cycle_members.insert(*id);
-            }
-        }
-    }
-
-    if !cycle_members.is_empty() {
-        let members: Vec<_> = cycle_members.iter().cloned().collect();
-
-        // Compute average confidence for cycle members
-        let cycle_changes: Vec<_> = preview
-            .result
-            .changes
-            .iter()
-            .filter(|c| cycle_members.contains(&c.hypothesis_id))
-            .collect();
-
-        if !cycle_changes.is_empty() {
-            let avg_confidence: f64 = cycle_changes
-                .iter()
-                .map(|c| c.new_confidence.get())
-                .sum::<f64>()
-                / cycle_changes.len() as f64;
-
-            warnings.push(CycleWarning {
-                scc_members: members,
-                avg_confidence,
-                description: format!(
-                    "Cycle detected with {} members. Normalized to {:.2} confidence.",
-                    cycle_members.len(),
-                    avg_confidence
-                ),
-            });
-        }
-    }
-
-    warnings
+    preview.cycle_warnings.clone()
 }
 
 #[cfg(test)]
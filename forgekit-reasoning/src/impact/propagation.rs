@@ -1,9 +1,24 @@
 //! Confidence propagation through dependency graphs
 
-use crate::belief::BeliefGraph;
+use std::collections::HashMap;
+
+use crate::belief::{BeliefGraph, EdgeKind};
 use crate::errors::Result;
 use crate::hypothesis::{Confidence, HypothesisBoard, HypothesisId};
 
+/// How confidence decays as it propagates away from the changed hypothesis
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum DecayModel {
+    /// Drops by `1 - decay_factor` per hop: `1 - (1 - decay_factor) * hop`
+    Linear,
+    /// Multiplies by `decay_factor` per hop: `decay_factor^hop`
+    #[default]
+    Exponential,
+    /// Multiplies by the factor of each edge's kind along the path, using
+    /// `decay_factor` for kinds not in the map
+    PerEdgeType(HashMap<EdgeKind, f64>),
+}
+
 /// Configuration for confidence propagation
 #[derive(Clone, Debug)]
 pub struct PropagationConfig {
@@ -13,6 +28,8 @@ pub struct PropagationConfig {
     pub min_confidence: f64,
     /// Maximum cascade size limit (default: 10000)
     pub max_cascade_size: usize,
+    /// How `decay_factor` is applied per hop (default: exponential)
+    pub decay_model: DecayModel,
 }
 
 impl Default for PropagationConfig {
@@ -21,6 +38,21 @@ impl Default for PropagationConfig {
             decay_factor: 0.95,
             min_confidence: 0.1,
             max_cascade_size: 10000,
+            decay_model: DecayModel::default(),
+        }
+    }
+}
+
+impl PropagationConfig {
+    /// Decay multiplier for a hypothesis `hop` edges from the start,
+    /// reached over an edge of `kind` from one whose multiplier was `parent`
+    fn decay(&self, parent: f64, hop: usize, kind: EdgeKind) -> f64 {
+        match &self.decay_model {
+            DecayModel::Linear => (1.0 - (1.0 - self.decay_factor) * hop as f64).max(0.0),
+            DecayModel::Exponential => self.decay_factor.powi(hop as i32),
+            DecayModel::PerEdgeType(factors) => {
+                parent * factors.get(&kind).copied().unwrap_or(self.decay_factor)
+            }
         }
     }
 }
@@ -30,6 +62,8 @@ impl Default for PropagationConfig {
 pub struct PropagationResult {
     pub changes: Vec<ConfidenceChange>,
     pub cycles_detected: bool,
+    /// Dependency edges ignored to break cycles, as (dependent, dependee)
+    pub broken_edges: Vec<(HypothesisId, HypothesisId)>,
     pub normalized_cycles: usize,
     pub total_affected: usize,
     pub max_depth: usize,
@@ -63,6 +97,9 @@ pub enum CascadeError {
 }
 
 /// Compute cascade impact with BFS traversal
+///
+/// Each reached hypothesis gets the new confidence decayed by
+/// [`PropagationConfig::decay_model`] and floored at `min_confidence`.
 pub async fn compute_cascade(
     start: HypothesisId,
     new_confidence: Confidence,
@@ -79,16 +116,33 @@ pub async fn compute_cascade(
         .map_err(|e| CascadeError::GraphError(e.to_string()))?
         .ok_or(CascadeError::HypothesisNotFound(start))?;
 
+    // Break cycles before traversal so confidence cannot flow around one
+    let cycles = graph.detect_cycles();
+    let mut confidences = HashMap::new();
+    for id in cycles.iter().flatten() {
+        if let Some(h) = board
+            .get(*id)
+            .await
+            .map_err(|e| CascadeError::GraphError(e.to_string()))?
+        {
+            confidences.insert(*id, h.current_confidence().get());
+        }
+    }
+    let broken_edges: HashSet<(HypothesisId, HypothesisId)> = graph
+        .cycle_breaking_edges(|id| confidences.get(&id).copied().unwrap_or(0.0))
+        .into_iter()
+        .collect();
+
     let mut changes = Vec::new();
     let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
 
-    // Start node with depth 0
-    queue.push_back((start, vec![start], 0));
+    // Start node with depth 0 and no decay
+    queue.push_back((start, vec![start], 0, 1.0));
     visited.insert(start);
 
     // BFS traversal
-    while let Some((current_id, path, depth)) = queue.pop_front() {
+    while let Some((current_id, path, depth, decay)) = queue.pop_front() {
         // Check cascade size limit
         if visited.len() > config.max_cascade_size {
             return Err(CascadeError::CascadeTooLarge {
@@ -108,9 +162,8 @@ pub async fn compute_cascade(
         let name = hypothesis.statement().to_string();
 
         // Compute decayed confidence
-        let decay_factor = config.decay_factor.powi(depth as i32);
-        let decayed_value = new_confidence.get() * decay_factor;
-        let decayed_value = decayed_value.max(config.min_confidence);
+        let decayed_value = new_confidence.get() * decay;
+        let decayed_value = decayed_value.min(1.0).max(config.min_confidence);
         let new_conf_for_hyp = Confidence::new(decayed_value)?;
 
         // Create change record
@@ -128,25 +181,29 @@ pub async fn compute_cascade(
         // Get dependents (what depends on current hypothesis)
         if let Ok(dependents) = graph.dependents(current_id) {
             for dependent in dependents {
+                if broken_edges.contains(&(dependent, current_id)) {
+                    continue;
+                }
                 if !visited.contains(&dependent) {
                     visited.insert(dependent);
                     let mut new_path = path.clone();
                     new_path.push(dependent);
-                    queue.push_back((dependent, new_path, depth + 1));
+                    let kind = graph.edge_kind(dependent, current_id).unwrap_or_default();
+                    let decay = config.decay(decay, depth + 1, kind);
+                    queue.push_back((dependent, new_path, depth + 1, decay));
                 }
             }
         }
     }
 
-    // Detect cycles
-    let cycles_detected = graph.detect_cycles();
-    let has_cycles = !cycles_detected.is_empty();
-
     let max_depth = changes.iter().map(|c| c.depth).max().unwrap_or(0);
+    let mut broken_edges: Vec<_> = broken_edges.into_iter().collect();
+    broken_edges.sort_by_key(|(from, to)| (from.0, to.0));
 
     Ok(PropagationResult {
         changes,
-        cycles_detected: has_cycles,
+        cycles_detected: !cycles.is_empty(),
+        broken_edges,
         normalized_cycles: 0,
         total_affected: visited.len(),
         max_depth,
@@ -349,6 +406,7 @@ mod tests {
             decay_factor: 0.5,
             min_confidence: 0.15,
             max_cascade_size: 1000,
+            ..Default::default()
         };
 
         let result = compute_cascade(h_c, new_conf, &board, &graph, &config)
@@ -386,12 +444,109 @@ mod tests {
             decay_factor: 0.95,
             min_confidence: 0.1,
             max_cascade_size: 1, // Only 1 hypothesis allowed
+            ..Default::default()
         };
 
         let result = compute_cascade(h_a, new_conf, &board, &graph, &config).await;
         assert!(matches!(result, Err(CascadeError::CascadeTooLarge { .. })));
     }
 
+    /// Confidence of each hypothesis after propagating 0.8 from `c` along
+    /// a -> b -> c, where a supports b and b contradicts c
+    async fn decayed_chain(config: &PropagationConfig) -> (f64, f64, f64) {
+        let board = HypothesisBoard::in_memory();
+        let mut graph = BeliefGraph::new();
+        let prior = Confidence::new(0.5).unwrap();
+        let h_c = board.propose("C", prior).await.unwrap();
+        let h_b = board.propose("B", prior).await.unwrap();
+        let h_a = board.propose("A", prior).await.unwrap();
+        graph
+            .add_dependency_of_kind(h_b, h_c, EdgeKind::Contradicts)
+            .unwrap();
+        graph
+            .add_dependency_of_kind(h_a, h_b, EdgeKind::Supports)
+            .unwrap();
+
+        let result = compute_cascade(h_c, Confidence::new(0.8).unwrap(), &board, &graph, config)
+            .await
+            .unwrap();
+        let of = |id| {
+            result
+                .changes
+                .iter()
+                .find(|c| c.hypothesis_id == id)
+                .unwrap()
+                .new_confidence
+                .get()
+        };
+        (of(h_c), of(h_b), of(h_a))
+    }
+
+    #[tokio::test]
+    async fn test_decay_models() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        let exponential = PropagationConfig {
+            decay_factor: 0.5,
+            ..Default::default()
+        };
+        let (c, b, a) = decayed_chain(&exponential).await;
+        assert!(close(c, 0.8) && close(b, 0.4) && close(a, 0.2));
+
+        let linear = PropagationConfig {
+            decay_factor: 0.75,
+            decay_model: DecayModel::Linear,
+            ..Default::default()
+        };
+        let (c, b, a) = decayed_chain(&linear).await;
+        assert!(close(c, 0.8) && close(b, 0.6) && close(a, 0.4));
+
+        let per_edge = PropagationConfig {
+            decay_factor: 0.9,
+            decay_model: DecayModel::PerEdgeType(HashMap::from([
+                (EdgeKind::Contradicts, 0.25),
+                (EdgeKind::DependsOn, 1.0),
+            ])),
+            ..Default::default()
+        };
+        // Contradicts uses its own factor; Supports falls back to 0.9
+        let (c, b, a) = decayed_chain(&per_edge).await;
+        assert!(close(c, 0.8) && close(b, 0.2) && close(a, 0.18));
+
+        // The floor still applies, and factors above 1 cap at certainty
+        let clamped = PropagationConfig {
+            min_confidence: 0.3,
+            decay_model: DecayModel::PerEdgeType(HashMap::from([
+                (EdgeKind::Contradicts, 0.1),
+                (EdgeKind::Supports, 50.0),
+            ])),
+            ..Default::default()
+        };
+        let (_, b, a) = decayed_chain(&clamped).await;
+        assert!(close(b, 0.3) && close(a, 1.0));
+    }
+
+    #[tokio::test]
+    async fn test_per_edge_decay_respects_cascade_limit() {
+        let board = HypothesisBoard::in_memory();
+        let mut graph = BeliefGraph::new();
+        let prior = Confidence::new(0.5).unwrap();
+        let h_a = board.propose("A", prior).await.unwrap();
+        let h_b = board.propose("B", prior).await.unwrap();
+        graph
+            .add_dependency_of_kind(h_b, h_a, EdgeKind::Supports)
+            .unwrap();
+
+        let config = PropagationConfig {
+            max_cascade_size: 1,
+            decay_model: DecayModel::PerEdgeType(HashMap::new()),
+            ..Default::default()
+        };
+        let result =
+            compute_cascade(h_a, Confidence::new(0.9).unwrap(), &board, &graph, &config).await;
+        assert!(matches!(result, Err(CascadeError::CascadeTooLarge { .. })));
+    }
+
     #[tokio::test]
     async fn test_hypothesis_not_found_error() {
         let board = HypothesisBoard::in_memory();
@@ -484,6 +639,7 @@ mod tests {
                 },
             ],
             cycles_detected: false,
+            broken_edges: Vec::new(),
             normalized_cycles: 0,
             total_affected: 2,
             max_depth: 1,
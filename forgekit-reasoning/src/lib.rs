@@ -24,18 +24,21 @@ pub mod websocket;
 
 // Re-export main types
 pub use checkpoint::{
-    AutoTrigger, CheckpointId, CheckpointSummary, CheckpointTrigger, CompactionPolicy, DebugNote,
-    DebugStateSnapshot, SessionId, SessionMetrics, TemporalCheckpoint, TemporalCheckpointManager,
+    AutoTrigger, CheckpointDiff, CheckpointId, CheckpointRelation, CheckpointSummary,
+    CheckpointTrigger, CompactionPolicy, CompactionReport, ConfidenceDelta, DebugNote,
+    DebugStateSnapshot, HypothesisChange, MetricChange, NoteChange, RestoreDiff, SessionId,
+    SessionMetrics, StatusTransition, TemporalCheckpoint, TemporalCheckpointManager,
     VerificationResult,
 };
 
 pub use hypothesis::{
-    strength_to_likelihood, Confidence, ConfidenceError, Evidence, EvidenceId, EvidenceMetadata,
-    EvidenceType, Hypothesis, HypothesisBoard, HypothesisId, HypothesisStatus, HypothesisStorage,
-    InMemoryHypothesisStorage,
+    strength_to_likelihood, CalibrationBucket, CalibrationReport, Confidence, ConfidenceError,
+    Evidence, EvidenceId, EvidenceMetadata, EvidenceType, Hypothesis, HypothesisBoard,
+    HypothesisId, HypothesisStatus, HypothesisStorage, InMemoryHypothesisStorage, MergeReport,
+    MergeStrategy, RankWeights, SkippedItem, TimelineEntry,
 };
 
-pub use belief::{BeliefGraph, ReasoningSystem};
+pub use belief::{BeliefGraph, EdgeKind, ReasoningSystem};
 
 pub use gaps::{
     compute_gap_score, GapCriticality, GapId, GapSuggestion, GapType, KnowledgeGap,
@@ -49,6 +52,7 @@ pub use impact::{
     // Propagation types
     ConfidenceChange,
     CycleWarning,
+    DecayModel,
     // Impact analysis engine
     ImpactAnalysisEngine,
     PaginationState,
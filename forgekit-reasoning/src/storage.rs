@@ -22,6 +22,9 @@ pub trait CheckpointStorage: Send + Sync {
     /// List checkpoints with a specific tag
     fn list_by_tag(&self, tag: &str) -> Result<Vec<CheckpointSummary>>;
 
+    /// List every checkpoint across all sessions
+    fn list_all(&self) -> Result<Vec<CheckpointSummary>>;
+
     /// Delete a checkpoint
     fn delete(&self, id: CheckpointId) -> Result<()>;
 
@@ -262,6 +265,7 @@ mod tests {
                 env_vars: std::collections::HashMap::new(),
                 metrics: crate::checkpoint::SessionMetrics::default(),
                 hypothesis_state: None,
+                notes: Vec::new(),
             },
             trigger: CheckpointTrigger::Manual,
             session_id,
@@ -284,6 +284,27 @@ impl CheckpointStorage for SqliteGraphStorage {
         Ok(summaries)
     }
 
+    fn list_all(&self) -> Result<Vec<CheckpointSummary>> {
+        let cache = self.cache.borrow();
+        let mut summaries: Vec<CheckpointSummary> = cache
+            .values()
+            .map(|checkpoint| CheckpointSummary {
+                id: checkpoint.id,
+                timestamp: checkpoint.timestamp,
+                sequence_number: checkpoint.sequence_number,
+                message: checkpoint.message.clone(),
+                trigger: checkpoint.trigger.to_string(),
+                tags: checkpoint.tags.clone(),
+                has_notes: false,
+            })
+            .collect();
+
+        // Sort by sequence number
+        summaries.sort_by_key(|s: &CheckpointSummary| s.sequence_number);
+
+        Ok(summaries)
+    }
+
     fn delete(&self, id: CheckpointId) -> Result<()> {
         // Remove from cache
         self.cache.borrow_mut().remove(&id);
@@ -73,6 +73,12 @@ impl ThreadSafeStorage {
         storage.list_by_tag(tag)
     }
 
+    /// List every checkpoint across all sessions
+    pub fn list_all(&self) -> Result<Vec<CheckpointSummary>> {
+        let storage = self.inner.lock();
+        storage.list_all()
+    }
+
     /// Delete checkpoint
     pub fn delete(&self, id: CheckpointId) -> Result<()> {
         let storage = self.inner.lock();
@@ -177,6 +183,7 @@ impl ThreadSafeCheckpointManager {
             self.session_id,
         );
         checkpoint.tags = tags;
+        checkpoint.checksum = checkpoint.compute_checksum();
 
         self.storage.store(&checkpoint)?;
         self.update_last_checkpoint_time();
@@ -271,39 +278,7 @@ impl ThreadSafeCheckpointManager {
     pub fn compact_with_policy(&self, policy: CompactionPolicy) -> Result<usize> {
         let all_checkpoints = self.storage.list_by_session(self.session_id)?;
 
-        // Determine which checkpoints to keep
-        let ids_to_keep: std::collections::HashSet<CheckpointId> = match &policy {
-            CompactionPolicy::KeepRecent(n) => {
-                let mut sorted = all_checkpoints.clone();
-                sorted.sort_by_key(|cp| cp.sequence_number);
-                sorted.iter().rev().take(*n).map(|cp| cp.id).collect()
-            }
-            CompactionPolicy::PreserveTagged(tags) => all_checkpoints
-                .iter()
-                .filter(|cp| cp.tags.iter().any(|t| tags.contains(t)))
-                .map(|cp| cp.id)
-                .collect(),
-            CompactionPolicy::Hybrid {
-                keep_recent,
-                preserve_tags,
-            } => {
-                let mut to_keep = std::collections::HashSet::new();
-
-                let mut sorted = all_checkpoints.clone();
-                sorted.sort_by_key(|cp| cp.sequence_number);
-                for cp in sorted.iter().rev().take(*keep_recent) {
-                    to_keep.insert(cp.id);
-                }
-
-                for cp in &all_checkpoints {
-                    if cp.tags.iter().any(|t| preserve_tags.contains(t)) {
-                        to_keep.insert(cp.id);
-                    }
-                }
-
-                to_keep
-            }
-        };
+        let ids_to_keep = policy.retained(&all_checkpoints, Utc::now());
 
         // Delete checkpoints not in keep list
         let mut deleted = 0;
@@ -352,6 +327,7 @@ impl ThreadSafeCheckpointManager {
             env_vars: std::env::vars().collect(),
             metrics: crate::checkpoint::SessionMetrics::default(),
             hypothesis_state: None, // Will be populated when hypothesis state is captured
+            notes: Vec::new(),
         })
     }
 
@@ -3,6 +3,7 @@
 //! Tests drive the implementation. Run with: cargo test
 
 use chrono::Utc;
+use forgekit_reasoning::hypothesis::HypothesisState;
 use forgekit_reasoning::*;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -319,6 +320,7 @@ fn test_restore_invalid_checkpoint() {
             env_vars: HashMap::new(),
             metrics: SessionMetrics::default(),
             hypothesis_state: None,
+            notes: Vec::new(),
         },
         trigger: CheckpointTrigger::Manual,
         session_id: SessionId::new(),
@@ -538,8 +540,10 @@ fn test_checkpoint_compaction() {
     }
 
     // Compact to keep only last 5
-    let compacted = manager.compact(5).unwrap();
-    assert_eq!(compacted, 5, "Should remove 5 old checkpoints");
+    let compacted = manager
+        .compact_with_policy(CompactionPolicy::KeepRecent(5))
+        .unwrap();
+    assert_eq!(compacted.removed, 5, "Should remove 5 old checkpoints");
 
     // Verify only 5 remain
     let checkpoints = manager.list().unwrap();
@@ -676,3 +680,285 @@ fn test_export_import_roundtrip() {
     assert!(cp.tags.contains(&"tag1".to_string()));
     assert!(cp.tags.contains(&"tag2".to_string()));
 }
+
+/// Restore preview lists what a restore would revert without applying it
+#[test]
+fn test_preview_restore_lists_reverted_changes() {
+    let storage = Rc::new(SqliteGraphStorage::in_memory().unwrap());
+    let manager = TemporalCheckpointManager::new(storage, SessionId::new());
+
+    let kept = Hypothesis::new("Cache is stale", Confidence::new(0.5).unwrap());
+    manager.set_hypothesis_state(HypothesisState::new(vec![kept.clone()], vec![], 0));
+    manager.add_note("baseline", vec![]);
+    let cp_id = manager.checkpoint("Before changes").unwrap();
+
+    // Change live state after the checkpoint
+    let mut updated = kept.clone();
+    updated.posterior = Confidence::new(0.9).unwrap();
+    let added = Hypothesis::new("Lock is contended", Confidence::new(0.3).unwrap());
+    manager.set_hypothesis_state(HypothesisState::new(
+        vec![updated, added.clone()],
+        vec![],
+        1,
+    ));
+    manager.add_note("found the bug", vec!["lead".to_string()]);
+    manager.update_metrics(|m| m.hypotheses_tested = 2);
+
+    let diff = manager.preview_restore(&cp_id).unwrap();
+
+    assert_eq!(diff.checkpoint_id, cp_id);
+    assert_eq!(diff.notes_dropped.len(), 1);
+    assert_eq!(diff.notes_dropped[0].content, "found the bug");
+    assert!(diff.notes_restored.is_empty());
+    assert_eq!(
+        diff.metrics,
+        vec![MetricChange {
+            name: "hypotheses_tested",
+            current: 2,
+            restored: 0,
+        }]
+    );
+    assert_eq!(diff.hypotheses.len(), 2);
+    assert!(diff.hypotheses.iter().any(|c| matches!(
+        c,
+        HypothesisChange::Reverted { current, restored }
+            if current.id == kept.id
+                && current.posterior.get() == 0.9
+                && restored.posterior.get() == 0.5
+    )));
+    assert!(diff
+        .hypotheses
+        .iter()
+        .any(|c| matches!(c, HypothesisChange::Dropped(h) if h.id == added.id)));
+
+    // Previewing applies nothing
+    assert!(!manager.preview_restore(&cp_id).unwrap().is_empty());
+    assert!(manager.preview_restore(&CheckpointId::new()).is_err());
+}
+
+/// Diffing two checkpoints of one session reads as history in either direction
+#[test]
+fn test_diff_checkpoints_in_one_session() {
+    let storage = Rc::new(SqliteGraphStorage::in_memory().unwrap());
+    let manager = TemporalCheckpointManager::new(storage, SessionId::new());
+
+    let tested = Hypothesis::new("Cache is stale", Confidence::new(0.5).unwrap());
+    manager.set_hypothesis_state(HypothesisState::new(vec![tested.clone()], vec![], 0));
+    manager.add_note("baseline", vec![]);
+    let first = manager.checkpoint("Before test").unwrap();
+
+    let mut updated = tested.clone();
+    updated.posterior = Confidence::new(0.8).unwrap();
+    updated.status = HypothesisStatus::UnderTest;
+    let added = Hypothesis::new("Lock is contended", Confidence::new(0.3).unwrap());
+    manager.set_hypothesis_state(HypothesisState::new(
+        vec![updated, added.clone()],
+        vec![],
+        1,
+    ));
+    manager.add_note("stale read reproduced", vec![]);
+    let second = manager.checkpoint("After test").unwrap();
+
+    let diff = manager.diff(first, second).unwrap();
+    assert_eq!(diff.relation, CheckpointRelation::Ancestor);
+    assert_eq!(diff.notes_added.len(), 1);
+    assert_eq!(diff.notes_added[0].content, "stale read reproduced");
+    assert!(diff.notes_removed.is_empty() && diff.notes_modified.is_empty());
+    assert_eq!(diff.hypotheses_added.len(), 1);
+    assert_eq!(diff.hypotheses_added[0].id, added.id);
+    assert_eq!(diff.confidence_changes.len(), 1);
+    assert_eq!(diff.confidence_changes[0].before.get(), 0.5);
+    assert_eq!(diff.confidence_changes[0].after.get(), 0.8);
+    assert_eq!(
+        diff.status_transitions,
+        vec![StatusTransition {
+            hypothesis_id: tested.id,
+            statement: "Cache is stale".to_string(),
+            before: HypothesisStatus::Proposed,
+            after: HypothesisStatus::UnderTest,
+        }]
+    );
+    let summary = diff.summary();
+    assert!(summary.contains("+ note: stale read reproduced"));
+    assert!(summary.contains("~ confidence: Cache is stale: 0.50 -> 0.80"));
+    assert!(summary.contains("~ status: Cache is stale: Proposed -> UnderTest"));
+
+    // The reverse diff walks back through the same history
+    let back = manager.diff(second, first).unwrap();
+    assert_eq!(back.relation, CheckpointRelation::Descendant);
+    assert_eq!(back.notes_removed.len(), 1);
+    assert_eq!(back.hypotheses_removed[0].id, added.id);
+    assert_eq!(back.status_transitions[0].after, HypothesisStatus::Proposed);
+
+    let same = manager.diff(first, first).unwrap();
+    assert_eq!(same.relation, CheckpointRelation::Same);
+    assert!(same.is_empty());
+    assert!(same.summary().contains("No changes"));
+    assert!(manager.diff(first, CheckpointId::new()).is_err());
+}
+
+/// Checkpoints from different sessions are diffed as unrelated branches
+#[test]
+fn test_diff_checkpoints_across_sessions() {
+    let storage = Rc::new(SqliteGraphStorage::in_memory().unwrap());
+    let manager = TemporalCheckpointManager::new(storage.clone(), SessionId::new());
+    manager.add_note("timeout on retry", vec![]);
+    let ours = manager.checkpoint("Ours").unwrap();
+
+    // Another session holding an edited copy of the same note
+    let mut note = manager.get(&ours).unwrap().unwrap().state.notes[0].clone();
+    note.content = "timeout on first attempt".to_string();
+    let other_session = SessionId::new();
+    let state = DebugStateSnapshot {
+        session_id: other_session,
+        working_dir: Some(std::env::temp_dir()),
+        notes: vec![note],
+        ..Default::default()
+    };
+    let theirs =
+        TemporalCheckpoint::new(0, "Theirs", state, CheckpointTrigger::Manual, other_session);
+    storage.store(&theirs).unwrap();
+
+    let diff = manager.diff(ours, theirs.id).unwrap();
+    assert_eq!(diff.relation, CheckpointRelation::Unrelated);
+    assert!(diff.notes_added.is_empty() && diff.notes_removed.is_empty());
+    assert_eq!(diff.notes_modified.len(), 1);
+    assert_eq!(diff.notes_modified[0].before.content, "timeout on retry");
+    assert_eq!(
+        diff.notes_modified[0].after.content,
+        "timeout on first attempt"
+    );
+    assert!(diff.summary().contains("unrelated sessions"));
+}
+
+/// Retention keeps recent and tagged checkpoints and thins old ones to one per hour
+#[test]
+fn test_retention_policy_thins_old_checkpoints_hourly() {
+    use chrono::{Duration, DurationRound};
+
+    let now = Utc::now();
+    let summary = |seq: u64, timestamp, tags: &[&str]| CheckpointSummary {
+        id: CheckpointId::new(),
+        timestamp,
+        sequence_number: seq,
+        message: format!("Checkpoint {}", seq),
+        trigger: "manual".to_string(),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        has_notes: false,
+    };
+    // Start of an hour two days ago
+    let hour = (now - Duration::days(2))
+        .duration_trunc(Duration::hours(1))
+        .unwrap();
+
+    let checkpoints = vec![
+        // Tagged, so it survives alongside the newest of its hour
+        summary(0, hour + Duration::minutes(5), &["root-cause"]),
+        // Same hour: only the newer survives
+        summary(1, hour + Duration::minutes(10), &[]),
+        summary(2, hour + Duration::minutes(40), &[]),
+        // The following hour
+        summary(3, hour + Duration::minutes(90), &[]),
+        // Younger than a day: all survive
+        summary(4, now - Duration::hours(2), &[]),
+        summary(5, now - Duration::hours(2) + Duration::minutes(1), &[]),
+    ];
+    let policy = CompactionPolicy::Retention {
+        keep_recent: 1,
+        thin_after: Duration::days(1),
+        preserve_tags: vec!["root-cause".to_string()],
+    };
+
+    let kept = policy.retained(&checkpoints, now);
+    let mut kept_seqs: Vec<u64> = checkpoints
+        .iter()
+        .filter(|cp| kept.contains(&cp.id))
+        .map(|cp| cp.sequence_number)
+        .collect();
+    kept_seqs.sort();
+    assert_eq!(kept_seqs, vec![0, 2, 3, 4, 5]);
+}
+
+/// Compaction reports what it reclaimed and survivors still restore
+#[test]
+fn test_compact_reports_and_survivors_restore() {
+    let storage = Rc::new(SqliteGraphStorage::in_memory().unwrap());
+    let session_id = SessionId::new();
+    let manager = TemporalCheckpointManager::new(storage.clone(), session_id);
+
+    manager.add_note("suspect the cache", vec![]);
+    let tagged = manager
+        .checkpoint_with_tags("Found it", vec!["root-cause".to_string()])
+        .unwrap();
+    for i in 0..5 {
+        manager.checkpoint(format!("Checkpoint {}", i)).unwrap();
+    }
+
+    manager.set_compaction_policy(CompactionPolicy::Hybrid {
+        keep_recent: 2,
+        preserve_tags: vec!["root-cause".to_string()],
+    });
+    let report = manager.compact().unwrap();
+
+    assert_eq!(report.removed, 3);
+    assert_eq!(report.kept, 3);
+    assert!(report.bytes_reclaimed > 0);
+    assert_eq!(manager.list().unwrap().len(), 3);
+
+    let checkpoint = manager
+        .get(&tagged)
+        .unwrap()
+        .expect("tagged checkpoint kept");
+    assert!(checkpoint.validate().is_ok());
+    let state = manager.restore(&checkpoint).unwrap();
+    assert_eq!(state.session_id, session_id);
+    assert_eq!(state.notes[0].content, "suspect the cache");
+
+    // Nothing left to remove on a second pass
+    assert_eq!(manager.compact().unwrap().removed, 0);
+}
+
+#[tokio::test]
+async fn test_iter_checkpoints_streams_in_creation_order() {
+    use futures_util::StreamExt;
+
+    let storage = Rc::new(SqliteGraphStorage::in_memory().unwrap());
+    let manager = TemporalCheckpointManager::new(storage.clone(), SessionId::new());
+    let other = TemporalCheckpointManager::new(storage, SessionId::new());
+
+    let mut ids = Vec::new();
+    let mut times = Vec::new();
+    for i in 0..5 {
+        ids.push(manager.checkpoint(format!("step {}", i)).unwrap());
+        other.checkpoint("other session").unwrap();
+        times.push(Utc::now());
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    let streamed: Vec<TemporalCheckpoint> = manager
+        .iter_checkpoints()
+        .map(|cp| cp.unwrap())
+        .collect()
+        .await;
+    assert_eq!(
+        streamed.iter().map(|cp| cp.id).collect::<Vec<_>>(),
+        ids,
+        "stream yields only this session, oldest first"
+    );
+    assert_eq!(streamed[3].message, "step 3");
+
+    // Checkpoints 1..=3 were created after times[0] and before times[3]
+    let window: Vec<CheckpointId> = manager
+        .iter_checkpoints_between(Some(times[0]), Some(times[3]))
+        .map(|cp| cp.unwrap().id)
+        .collect()
+        .await;
+    assert_eq!(window, ids[1..=3]);
+
+    let tail: Vec<CheckpointId> = manager
+        .iter_checkpoints_between(Some(times[3]), None)
+        .map(|cp| cp.unwrap().id)
+        .collect()
+        .await;
+    assert_eq!(tail, ids[4..]);
+}
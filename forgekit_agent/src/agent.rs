@@ -338,12 +338,11 @@ impl Agent {
 
         let validator = policy::PolicyValidator::new(forge.clone());
 
-        let diff = policy::Diff {
-            file_path: std::path::PathBuf::from(&observation.query),
-            original: String::new(),
-            modified: format!("query: {}", observation.query),
-            changes: Vec::new(),
-        };
+        let diff = policy::Diff::new(
+            std::path::PathBuf::from(&observation.query),
+            String::new(),
+            format!("query: {}", observation.query),
+        );
 
         let report = validator.validate(&diff, &policies).await?;
 
@@ -359,13 +358,9 @@ impl Agent {
             planner_instance = planner_instance.with_llm(llm.clone());
         }
 
-        let obs = observe::Observation {
-            query: constrained.observation.query.clone(),
-            symbols: constrained.observation.symbols.clone(),
-            summary: constrained.observation.summary.clone(),
-        };
-
-        let steps = planner_instance.generate_steps(&obs).await?;
+        let steps = planner_instance
+            .generate_steps(&constrained.observation)
+            .await?;
         let impact = planner_instance.estimate_impact(&steps).await?;
         let conflicts = planner_instance.detect_conflicts(&steps)?;
 
@@ -401,11 +396,55 @@ impl Agent {
     }
 
     pub async fn mutate(&self, plan: ExecutionPlan) -> Result<MutationResult> {
+        let (modified_files, _) = self.apply_plan(&plan, mutate::Mutator::new()).await?;
+
+        let diffs: Vec<String> = modified_files
+            .iter()
+            .map(|f| f.to_string_lossy().to_string())
+            .collect();
+
+        Ok(MutationResult {
+            modified_files,
+            diffs,
+        })
+    }
+
+    /// Previews the effect of a plan without writing to disk.
+    ///
+    /// Runs the same steps as [`Agent::mutate`] against an in-memory view of
+    /// the files. `modified_files` lists the files the plan would touch and
+    /// `diffs` holds a unified diff for each file whose content would change.
+    pub async fn mutate_dry_run(&self, plan: ExecutionPlan) -> Result<MutationResult> {
+        let (modified_files, mutator) = self.apply_plan(&plan, mutate::Mutator::dry_run()).await?;
+        if mutator.wrote_to_disk() {
+            return Err(AgentError::MutationFailed(
+                "Dry run wrote to disk".to_string(),
+            ));
+        }
+
+        let diffs = mutator
+            .dry_run_diffs()
+            .into_iter()
+            .map(|(_, diff)| diff)
+            .collect();
+
+        Ok(MutationResult {
+            modified_files,
+            diffs,
+        })
+    }
+
+    /// Applies each step of `plan` with `mutator`, returning the files the
+    /// steps touch in plan order.
+    async fn apply_plan(
+        &self,
+        plan: &ExecutionPlan,
+        mut mutator: mutate::Mutator,
+    ) -> Result<(Vec<std::path::PathBuf>, mutate::Mutator)> {
         self.forge
             .as_ref()
             .ok_or_else(|| AgentError::MutationFailed("Forge SDK not available".to_string()))?;
 
-        let mut mutator = mutate::Mutator::new();
         mutator.begin_transaction().await?;
 
         let mut modified_files = Vec::new();
@@ -416,15 +455,7 @@ impl Agent {
             }
         }
 
-        let diffs: Vec<String> = modified_files
-            .iter()
-            .map(|f| f.to_string_lossy().to_string())
-            .collect();
-
-        Ok(MutationResult {
-            modified_files,
-            diffs,
-        })
+        Ok((modified_files, mutator))
     }
 
     pub async fn verify(&self, result: MutationResult) -> Result<VerificationResult> {
@@ -439,11 +470,7 @@ impl Agent {
 
         Ok(VerificationResult {
             passed: report.passed,
-            diagnostics: report
-                .diagnostics
-                .iter()
-                .map(|d| d.message.clone())
-                .collect(),
+            diagnostics: report.diagnostics.iter().map(|d| d.to_string()).collect(),
             suggestions: report.suggestions,
         })
     }
@@ -919,4 +946,75 @@ mod tests {
         let agent = minimal_agent(None, None);
         assert!(agent.resolve_chat_config().is_err());
     }
+
+    #[tokio::test]
+    async fn test_mutate_dry_run_leaves_files_untouched() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("lib.rs");
+        std::fs::write(&file, "fn greet() {}\nfn main() { greet(); }\n").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let agent = Agent::new(temp.path()).await.unwrap();
+        let plan = ExecutionPlan {
+            steps: vec![planner::PlanStep {
+                description: "Rename greet".to_string(),
+                operation: planner::PlanOperation::Rename {
+                    old: "greet".to_string(),
+                    new: "hello".to_string(),
+                    file: Some(file.clone()),
+                },
+            }],
+            estimated_impact: planner::ImpactEstimate {
+                affected_files: vec![file.clone()],
+                complexity: 1,
+            },
+            rollback_plan: Vec::new(),
+        };
+
+        let result = agent.mutate_dry_run(plan).await.unwrap();
+
+        assert_eq!(result.modified_files, vec![PathBuf::from(&file)]);
+        assert_eq!(result.diffs.len(), 1);
+        assert!(result.diffs[0].contains("-fn greet() {}"));
+        assert!(result.diffs[0].contains("+fn hello() {}"));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "fn greet() {}\nfn main() { greet(); }\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_uses_observed_symbols() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(temp.path().join("src")).unwrap();
+        std::fs::write(
+            temp.path().join("src/lib.rs"),
+            "pub fn load_config() -> u32 { 1 }\n\npub fn start() -> u32 { load_config() }\n",
+        )
+        .unwrap();
+
+        let agent = Agent::new(temp.path()).await.unwrap();
+        agent.forge.as_ref().unwrap().graph().index().await.unwrap();
+
+        let observation = agent
+            .observe("rename load_config to read_config")
+            .await
+            .unwrap();
+        assert!(observation.symbols.iter().any(|s| s.name == "load_config"));
+
+        let constrained = agent.constrain(observation, Vec::new()).await.unwrap();
+        let plan = agent.plan(constrained).await.unwrap();
+
+        assert_eq!(plan.steps.len(), 1);
+        match &plan.steps[0].operation {
+            planner::PlanOperation::Rename { old, new, file } => {
+                assert_eq!(old, "load_config");
+                assert_eq!(new, "read_config");
+                let file = file.as_deref().unwrap();
+                assert_eq!(file, temp.path().join("src/lib.rs").to_str().unwrap());
+            }
+            other => panic!("expected a rename, got {other:?}"),
+        }
+        assert_eq!(plan.estimated_impact.affected_files.len(), 1);
+    }
 }
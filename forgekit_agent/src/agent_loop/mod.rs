@@ -29,6 +29,7 @@ pub struct AgentLoop {
     discovery_store: Option<Arc<dyn DiscoveryStore>>,
     llm: Option<Arc<dyn crate::llm::LlmProvider>>,
     max_fix_attempts: u32,
+    verify_config: crate::verify::VerifyConfig,
     last_observation: Option<crate::observe::Observation>,
     reasoning: forgekit_reasoning::ReasoningSystem,
     current_hypothesis: Option<forgekit_reasoning::HypothesisId>,
@@ -54,6 +55,7 @@ impl AgentLoop {
             discovery_store: None,
             llm: None,
             max_fix_attempts: 3,
+            verify_config: crate::verify::VerifyConfig::default(),
             last_observation: None,
             reasoning: forgekit_reasoning::ReasoningSystem::in_memory(),
             current_hypothesis: None,
@@ -81,6 +83,11 @@ impl AgentLoop {
         self
     }
 
+    pub fn with_verify_config(mut self, config: crate::verify::VerifyConfig) -> Self {
+        self.verify_config = config;
+        self
+    }
+
     pub fn with_policies(mut self, policies: Vec<crate::policy::Policy>) -> Self {
         self.policies = policies;
         self
@@ -93,12 +93,7 @@ impl AgentLoop {
                 Ok(c) => c,
                 Err(_) => continue,
             };
-            let diff = crate::policy::Diff {
-                file_path: path.clone(),
-                original: content.clone(),
-                modified: content,
-                changes: Vec::new(),
-            };
+            let diff = crate::policy::Diff::new(path.clone(), content.clone(), content);
             let report = validator
                 .validate(&diff, &policies)
                 .await
@@ -281,7 +276,8 @@ impl AgentLoop {
         &mut self,
         result: MutationResult,
     ) -> Result<VerificationResult, crate::AgentError> {
-        let mut verifier = crate::verify::Verifier::with_forge((*self.forge).clone());
+        let mut verifier = crate::verify::Verifier::with_forge((*self.forge).clone())
+            .with_config(self.verify_config.clone());
         if let Some(ref llm) = self.llm {
             verifier = verifier.with_llm(llm.clone());
         }
@@ -472,12 +468,11 @@ impl AgentLoop {
             let modified = tokio::fs::read_to_string(&snapshot.path)
                 .await
                 .unwrap_or_default();
-            let diff = crate::policy::Diff {
-                file_path: snapshot.path.clone(),
-                original: snapshot.original_content.clone(),
+            let diff = crate::policy::Diff::new(
+                snapshot.path.clone(),
+                snapshot.original_content.clone(),
                 modified,
-                changes: Vec::new(),
-            };
+            );
             let report = validator
                 .validate(&diff, &self.policies)
                 .await
@@ -1,3 +1,4 @@
+use forgekit_core::walk::{DirWalk, WalkOptions};
 use std::fmt;
 use std::path::{Path, PathBuf};
 
@@ -46,6 +47,7 @@ pub trait CodeRetriever: Send + Sync {
 pub struct FileCodeRetriever {
     root: PathBuf,
     context_lines: usize,
+    walk_options: WalkOptions,
 }
 
 impl FileCodeRetriever {
@@ -53,6 +55,7 @@ impl FileCodeRetriever {
         FileCodeRetriever {
             root,
             context_lines: 3,
+            walk_options: WalkOptions::default(),
         }
     }
 
@@ -61,6 +64,12 @@ impl FileCodeRetriever {
         self
     }
 
+    /// Sets whether symlinked files and directories are searched (default: false).
+    pub fn with_follow_symlinks(mut self, follow: bool) -> Self {
+        self.walk_options.follow_symlinks = follow;
+        self
+    }
+
     fn is_source_file(path: &Path) -> bool {
         path.extension()
             .map(|e| {
@@ -79,7 +88,7 @@ impl FileCodeRetriever {
         )
     }
 
-    async fn collect_source_files(&self, dir: &Path, files: &mut Vec<PathBuf>) {
+    async fn collect_source_files(&self, dir: &Path, walk: &mut DirWalk, files: &mut Vec<PathBuf>) {
         let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
             return;
         };
@@ -91,8 +100,10 @@ impl FileCodeRetriever {
                         continue;
                     }
                 }
-                Box::pin(self.collect_source_files(&path, files)).await;
-            } else if path.is_file() && Self::is_source_file(&path) {
+                if walk.enter_dir(&path) {
+                    Box::pin(self.collect_source_files(&path, walk, files)).await;
+                }
+            } else if walk.is_file(&path) && Self::is_source_file(&path) {
                 files.push(path);
             }
         }
@@ -135,7 +146,9 @@ impl CodeRetriever for FileCodeRetriever {
         }
 
         let mut files = Vec::new();
-        self.collect_source_files(&self.root, &mut files).await;
+        let mut walk = DirWalk::new(&self.root, &self.walk_options);
+        self.collect_source_files(&self.root, &mut walk, &mut files)
+            .await;
 
         let mut candidates: Vec<CodeSnippet> = Vec::new();
 
@@ -148,7 +148,10 @@ pub async fn run() -> anyhow::Result<()> {
                                 "  Watch active: {}",
                                 if stats.watch_active { "yes" } else { "no" }
                             );
-                            println!("  Watch directory: {}", runtime.config().watch_dir);
+                            println!(
+                                "  Watch directories: {}",
+                                runtime.config().watch_dir.join(", ")
+                            );
                             println!("  Reindex operations: {}", stats.reindex_count);
                             println!("  ---");
                             println!("  Metrics:");
@@ -78,6 +78,10 @@ pub enum AgentError {
     #[error("Policy violation: {0}")]
     PolicyViolation(String),
 
+    /// Policy rule file could not be loaded
+    #[error("Invalid policy: {0}")]
+    InvalidPolicy(String),
+
     /// Error from Forge SDK
     #[error("Forge error: {0}")]
     ForgeError(#[from] forgekit_core::ForgeError),
@@ -97,6 +101,7 @@ impl AgentError {
             // nosemgrep: llm-giant-match — exhaustive enum→label dispatch, the idiomatic pattern
             AgentError::ObservationFailed(_) => "Observe",
             AgentError::PolicyViolation(_) => "Constrain",
+            AgentError::InvalidPolicy(_) => "Constrain",
             AgentError::PlanningFailed(_) => "Plan",
             AgentError::MutationFailed(_) => "Mutate",
             AgentError::VerificationFailed(_) => "Verify",
@@ -141,7 +146,7 @@ impl std::future::IntoFuture for AgentTask {
 }
 
 // Re-export policy module
-pub use policy::{Policy, PolicyReport, PolicyValidator, PolicyViolation};
+pub use policy::{Policy, PolicyReport, PolicyRule, PolicyValidator, PolicyViolation};
 
 // Re-export observation types
 pub use observe::Observation;
@@ -149,6 +154,9 @@ pub use observe::Observation;
 // Re-export loop types
 pub use agent_loop::{AgentLoop, AgentPhase, LoopResult};
 
+// Re-export verification limits
+pub use verify::VerifyConfig;
+
 // Re-export audit types
 pub use audit::{AuditEvent, AuditLog};
 
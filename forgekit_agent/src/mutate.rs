@@ -5,16 +5,29 @@
 
 use crate::transaction::Transaction;
 use crate::{AgentError, Result};
-use std::path::Path;
+use forgekit_core::diff::UnifiedDiff;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 /// Mutator for transaction-based code changes.
 ///
 /// The Mutator uses the Transaction module to apply changes atomically,
 /// snapshotting files before mutation and providing rollback capability.
+///
+/// A dry-run mutator (see [`Mutator::dry_run`]) applies the same steps to
+/// an in-memory view of the files instead, so a plan can be previewed as
+/// unified diffs without touching disk.
 #[derive(Clone, Default)]
 pub struct Mutator {
     transaction: Option<Transaction>,
+    dry_run: bool,
+    /// Set once any change has reached disk
+    wrote_to_disk: bool,
+    /// Dry run: content of each touched file before the first step
+    originals: BTreeMap<PathBuf, Option<String>>,
+    /// Dry run: current content of each touched file, `None` if removed
+    staged: BTreeMap<PathBuf, Option<String>>,
 }
 
 impl Mutator {
@@ -23,6 +36,22 @@ impl Mutator {
         Self::default()
     }
 
+    /// Creates a mutator that records the effect of each step but never
+    /// writes, renames or removes a file.
+    pub fn dry_run() -> Self {
+        Self {
+            dry_run: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns true if any step has changed a file on disk.
+    ///
+    /// Always false for a dry-run mutator.
+    pub fn wrote_to_disk(&self) -> bool {
+        self.wrote_to_disk
+    }
+
     /// Begins a new transaction.
     pub async fn begin_transaction(&mut self) -> Result<()> {
         if self.transaction.is_some() {
@@ -39,10 +68,11 @@ impl Mutator {
     ///
     /// Snapshots each file before mutation for rollback capability.
     pub async fn apply_step(&mut self, step: &crate::planner::PlanStep) -> Result<()> {
-        let transaction = self
-            .transaction
-            .as_mut()
-            .ok_or_else(|| AgentError::MutationFailed("No active transaction".to_string()))?;
+        if self.transaction.is_none() {
+            return Err(AgentError::MutationFailed(
+                "No active transaction".to_string(),
+            ));
+        }
 
         match &step.operation {
             crate::planner::PlanOperation::Rename { old, new, file, .. } => {
@@ -50,23 +80,23 @@ impl Mutator {
                     // Symbol rename: replace all whole-word occurrences of `old`
                     // with `new` within the source file.
                     let path = Path::new(file_path);
-                    transaction.snapshot_file(path).await?;
+                    self.snapshot(path).await?;
 
-                    let content = fs::read_to_string(path).await.map_err(|e| {
+                    let content = self.read(path).await.map_err(|e| {
                         AgentError::MutationFailed(format!("Failed to read {}: {}", file_path, e))
                     })?;
                     let modified = replace_whole_word(&content, old, new);
-                    fs::write(path, modified).await.map_err(|e| {
+                    self.write(path, modified).await.map_err(|e| {
                         AgentError::MutationFailed(format!("Failed to write {}: {}", file_path, e))
                     })?;
                 } else {
                     // File rename: rename the file at path `old` to `new`.
                     let old_path = Path::new(old);
-                    let _ = transaction.snapshot_file(old_path).await;
+                    let _ = self.snapshot(old_path).await;
 
-                    if old_path.exists() {
+                    if self.exists(old_path).await {
                         let new_path = Path::new(new);
-                        fs::rename(old_path, new_path).await.map_err(|e| {
+                        self.rename(old_path, new_path).await.map_err(|e| {
                             AgentError::MutationFailed(format!(
                                 "Failed to rename {} to {}: {}",
                                 old, new, e
@@ -80,24 +110,26 @@ impl Mutator {
                     .as_deref()
                     .map(Path::new)
                     .unwrap_or_else(|| Path::new(name));
-                transaction.snapshot_file(name_path).await?;
+                self.snapshot(name_path).await?;
 
-                if name_path.exists() {
-                    fs::remove_file(name_path).await.map_err(|e| {
+                if self.exists(name_path).await {
+                    self.remove(name_path).await.map_err(|e| {
                         AgentError::MutationFailed(format!("Failed to delete {}: {}", name, e))
                     })?;
                 }
             }
             crate::planner::PlanOperation::Create { path, content } => {
                 let p = Path::new(path);
-                let _ = transaction.snapshot_file(p).await;
+                let _ = self.snapshot(p).await;
 
                 if let Some(parent) = p.parent() {
-                    fs::create_dir_all(parent).await.map_err(|e| {
-                        AgentError::MutationFailed(format!("Failed to create dir: {}", e))
-                    })?;
+                    if !self.dry_run {
+                        fs::create_dir_all(parent).await.map_err(|e| {
+                            AgentError::MutationFailed(format!("Failed to create dir: {}", e))
+                        })?;
+                    }
                 }
-                fs::write(path, content).await.map_err(|e| {
+                self.write(p, content.as_str()).await.map_err(|e| {
                     AgentError::MutationFailed(format!("Failed to write {}: {}", path, e))
                 })?;
             }
@@ -111,9 +143,9 @@ impl Mutator {
                 replacement,
             } => {
                 let file_path = Path::new(file);
-                transaction.snapshot_file(file_path).await?;
+                self.snapshot(file_path).await?;
 
-                let content = fs::read_to_string(file_path).await.map_err(|e| {
+                let content = self.read(file_path).await.map_err(|e| {
                     AgentError::MutationFailed(format!("Failed to read {}: {}", file, e))
                 })?;
                 let content_bytes = content.as_bytes();
@@ -121,7 +153,7 @@ impl Mutator {
                     let mut modified = content_bytes[..*start].to_vec();
                     modified.extend_from_slice(replacement.as_bytes());
                     modified.extend_from_slice(&content_bytes[*end..]);
-                    fs::write(file_path, modified).await.map_err(|e| {
+                    self.write(file_path, modified).await.map_err(|e| {
                         AgentError::MutationFailed(format!("Failed to write {}: {}", file, e))
                     })?;
                 } else {
@@ -139,6 +171,98 @@ impl Mutator {
         Ok(())
     }
 
+    /// Returns a unified diff for each file a dry run would change.
+    ///
+    /// Files are ordered by path. A removed file diffs against empty
+    /// content; a file whose content ends up unchanged is omitted.
+    pub fn dry_run_diffs(&self) -> Vec<(PathBuf, String)> {
+        self.staged
+            .iter()
+            .filter_map(|(path, staged)| {
+                let original = self.originals.get(path).cloned().flatten();
+                if original == *staged {
+                    return None;
+                }
+                let name = path.to_string_lossy();
+                let diff = UnifiedDiff::generate(
+                    original.as_deref().unwrap_or(""),
+                    staged.as_deref().unwrap_or(""),
+                    &name,
+                    &name,
+                );
+                Some((path.clone(), diff.render()))
+            })
+            .collect()
+    }
+
+    async fn snapshot(&mut self, path: &Path) -> Result<()> {
+        let transaction = self
+            .transaction
+            .as_mut()
+            .ok_or_else(|| AgentError::MutationFailed("No active transaction".to_string()))?;
+        transaction.snapshot_file(path).await
+    }
+
+    /// Reads a file, seeing earlier dry-run changes.
+    async fn read(&mut self, path: &Path) -> std::io::Result<String> {
+        if self.dry_run {
+            if let Some(staged) = self.staged.get(path) {
+                return staged
+                    .clone()
+                    .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound));
+            }
+        }
+        fs::read_to_string(path).await
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        match self.staged.get(path) {
+            Some(staged) if self.dry_run => staged.is_some(),
+            _ => path.exists(),
+        }
+    }
+
+    /// Writes a file, or stages the content in a dry run.
+    async fn write(&mut self, path: &Path, content: impl Into<Vec<u8>>) -> std::io::Result<()> {
+        let content = content.into();
+        if self.dry_run {
+            self.record_original(path).await;
+            let content = String::from_utf8_lossy(&content).into_owned();
+            self.staged.insert(path.to_path_buf(), Some(content));
+            return Ok(());
+        }
+        self.wrote_to_disk = true;
+        fs::write(path, content).await
+    }
+
+    async fn remove(&mut self, path: &Path) -> std::io::Result<()> {
+        if self.dry_run {
+            self.record_original(path).await;
+            self.staged.insert(path.to_path_buf(), None);
+            return Ok(());
+        }
+        self.wrote_to_disk = true;
+        fs::remove_file(path).await
+    }
+
+    async fn rename(&mut self, from: &Path, to: &Path) -> std::io::Result<()> {
+        if self.dry_run {
+            let content = self.read(from).await?;
+            self.remove(from).await?;
+            return self.write(to, content).await;
+        }
+        self.wrote_to_disk = true;
+        fs::rename(from, to).await
+    }
+
+    /// Remembers what `path` held before the dry run first touched it.
+    async fn record_original(&mut self, path: &Path) {
+        if !self.originals.contains_key(path) {
+            let original = fs::read_to_string(path).await.ok();
+            self.originals.insert(path.to_path_buf(), original);
+        }
+    }
+
     /// Extracts the transaction from the mutator.
     ///
     /// This is used when transferring the transaction to another component
@@ -420,6 +544,81 @@ mod tests {
         assert!(old_path.exists());
     }
 
+    #[tokio::test]
+    async fn test_dry_run_stages_changes_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("main.rs");
+        let created = temp_dir.path().join("src/new.rs");
+        tokio::fs::write(&file_path, "fn greet() {}\n")
+            .await
+            .unwrap();
+        let file = file_path.to_string_lossy().to_string();
+
+        let mut mutator = Mutator::dry_run();
+        mutator.begin_transaction().await.unwrap();
+
+        let steps = [
+            crate::planner::PlanOperation::Rename {
+                old: "greet".to_string(),
+                new: "hello".to_string(),
+                file: Some(file.clone()),
+            },
+            // Sees the staged rename, so the span covers `hello`
+            crate::planner::PlanOperation::Modify {
+                file: file.clone(),
+                start: 3,
+                end: 8,
+                replacement: "hi".to_string(),
+            },
+            crate::planner::PlanOperation::Create {
+                path: created.to_string_lossy().to_string(),
+                content: "fn new() {}\n".to_string(),
+            },
+        ];
+        for operation in steps {
+            let step = crate::planner::PlanStep {
+                description: String::new(),
+                operation,
+            };
+            mutator.apply_step(&step).await.unwrap();
+        }
+
+        assert!(!mutator.wrote_to_disk());
+        assert!(!created.exists());
+        assert!(!temp_dir.path().join("src").exists());
+        let content = tokio::fs::read_to_string(&file_path).await.unwrap();
+        assert_eq!(content, "fn greet() {}\n");
+
+        let diffs = mutator.dry_run_diffs();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].0, file_path);
+        assert!(diffs[0].1.contains("-fn greet() {}\n+fn hi() {}"));
+        assert_eq!(diffs[1].0, created);
+        assert!(diffs[1].1.contains("+fn new() {}"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_step_sets_wrote_to_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.rs");
+
+        let mut mutator = Mutator::new();
+        mutator.begin_transaction().await.unwrap();
+        assert!(!mutator.wrote_to_disk());
+
+        let step = crate::planner::PlanStep {
+            description: "Create test file".to_string(),
+            operation: crate::planner::PlanOperation::Create {
+                path: file_path.to_string_lossy().to_string(),
+                content: "fn test() {}".to_string(),
+            },
+        };
+        mutator.apply_step(&step).await.unwrap();
+
+        assert!(mutator.wrote_to_disk());
+        assert!(mutator.dry_run_diffs().is_empty());
+    }
+
     #[test]
     fn test_replace_whole_word_basic() {
         assert_eq!(
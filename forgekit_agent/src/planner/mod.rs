@@ -272,17 +272,25 @@ Output ONLY a JSON array. No explanation.";
         &self,
         observation: &super::observe::Observation,
     ) -> Result<Vec<PlanStep>> {
-        let query_lower = observation.query.to_lowercase();
         let mut steps = Vec::new();
 
         // Detect intent from query
-        let intent = detect_intent(&query_lower);
+        let intent = detect_intent(&observation.query);
 
+        let mut renamed = std::collections::HashSet::new();
         for symbol in &observation.symbols {
             let file = symbol.location.file_path.to_str().map(|s| s.to_string());
 
             match &intent {
-                PlanIntent::Rename { new_name } => {
+                PlanIntent::Rename { old_name, new_name } => {
+                    // Search also observes related symbols; only the named
+                    // one is renamed, once per file
+                    if symbol.name != *old_name
+                        || is_relative_alias(&symbol.location.file_path, &observation.symbols)
+                        || !renamed.insert((file.clone(), symbol.name.clone()))
+                    {
+                        continue;
+                    }
                     steps.push(PlanStep {
                         description: format!("Rename {} to {}", symbol.name, new_name),
                         operation: PlanOperation::Rename {
@@ -539,3 +547,17 @@ Output ONLY a JSON array. No explanation.";
         }
     }
 }
+
+/// Returns true if `path` is relative and another observed symbol names
+/// the same file by an absolute path.
+///
+/// Search and the graph can report one file both ways; the absolute path
+/// is kept since mutation resolves relative paths against the working
+/// directory.
+fn is_relative_alias(path: &std::path::Path, symbols: &[super::observe::ObservedSymbol]) -> bool {
+    path.is_relative()
+        && symbols.iter().any(|s| {
+            let other = &s.location.file_path;
+            other.is_absolute() && other.ends_with(path)
+        })
+}
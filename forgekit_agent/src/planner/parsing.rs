@@ -97,20 +97,27 @@ pub(crate) fn describe_operation(op: &PlanOperation) -> String {
     }
 }
 
+/// Detects the plan intent of `query`.
+///
+/// Keywords match in any case; symbol names keep the case they were
+/// written in.
 pub(crate) fn detect_intent(query: &str) -> PlanIntent {
-    if let Some(rest) = query.strip_prefix("rename ") {
-        if let Some((_, new)) = rest.split_once(" to ") {
-            return PlanIntent::Rename {
-                new_name: new.trim().to_string(),
-            };
-        }
-        if let Some((_, new)) = rest.split_once(" -> ") {
-            return PlanIntent::Rename {
-                new_name: new.trim().to_string(),
-            };
+    // ASCII lowercasing keeps byte offsets, so splits found in `lower`
+    // apply to `query`
+    let lower = query.to_ascii_lowercase();
+    if let Some(rest_lower) = lower.strip_prefix("rename ") {
+        let rest = &query[query.len() - rest_lower.len()..];
+        for separator in [" to ", " -> "] {
+            if let Some(at) = rest_lower.find(separator) {
+                return PlanIntent::Rename {
+                    old_name: rest[..at].trim().to_string(),
+                    new_name: rest[at + separator.len()..].trim().to_string(),
+                };
+            }
         }
     }
 
+    let query = lower.as_str();
     if query.contains("delete ") || query.contains("remove ") {
         return PlanIntent::Delete;
     }
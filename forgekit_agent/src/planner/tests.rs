@@ -111,6 +111,85 @@ async fn test_planner_no_llm_uses_regex() {
     assert!(steps.is_empty());
 }
 
+#[tokio::test]
+async fn test_regex_rename_targets_named_symbol_only() {
+    let planner = Planner::new();
+    let symbol = |id: i64, name: &str, file: &str| crate::observe::ObservedSymbol {
+        id: forgekit_core::types::SymbolId(id),
+        name: name.to_string(),
+        kind: forgekit_core::types::SymbolKind::Function,
+        location: forgekit_core::types::Location {
+            file_path: std::path::PathBuf::from(file),
+            byte_start: 0,
+            byte_end: 0,
+            line_number: 1,
+        },
+    };
+
+    let observation = crate::observe::Observation {
+        query: "rename load_config to read_config".to_string(),
+        symbols: vec![
+            symbol(1, "load_config", "src/config.rs"),
+            // Found by search but not what the query names
+            symbol(2, "load_config_file", "src/config.rs"),
+            // A second declaration in the same file is covered by one step
+            symbol(3, "load_config", "src/config.rs"),
+            symbol(4, "load_config", "src/legacy.rs"),
+        ],
+        summary: None,
+    };
+
+    let steps = planner.generate_steps(&observation).await.unwrap();
+    let renames: Vec<_> = steps
+        .iter()
+        .map(|s| match &s.operation {
+            PlanOperation::Rename { old, new, file } => {
+                (old.as_str(), new.as_str(), file.as_deref())
+            }
+            other => panic!("unexpected step {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        renames,
+        vec![
+            ("load_config", "read_config", Some("src/config.rs")),
+            ("load_config", "read_config", Some("src/legacy.rs")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_regex_rename_matches_names_exactly() {
+    let planner = Planner::new();
+    let symbol = |id: i64, name: &str| crate::observe::ObservedSymbol {
+        id: forgekit_core::types::SymbolId(id),
+        name: name.to_string(),
+        kind: forgekit_core::types::SymbolKind::Struct,
+        location: forgekit_core::types::Location {
+            file_path: std::path::PathBuf::from("src/config.rs"),
+            byte_start: 0,
+            byte_end: 0,
+            line_number: 1,
+        },
+    };
+
+    let observation = crate::observe::Observation {
+        query: "Rename Config to AppConfig".to_string(),
+        symbols: vec![symbol(1, "Config"), symbol(2, "config")],
+        summary: None,
+    };
+
+    let steps = planner.generate_steps(&observation).await.unwrap();
+    let renames: Vec<_> = steps
+        .iter()
+        .map(|s| match &s.operation {
+            PlanOperation::Rename { old, new, .. } => (old.as_str(), new.as_str()),
+            other => panic!("unexpected step {other:?}"),
+        })
+        .collect();
+    assert_eq!(renames, vec![("Config", "AppConfig")]);
+}
+
 #[tokio::test]
 async fn test_planner_llm_generates_steps() {
     use std::sync::Arc;
@@ -64,7 +64,7 @@ pub enum RollbackOperation {
 
 #[derive(Clone, Debug)]
 pub(crate) enum PlanIntent {
-    Rename { new_name: String },
+    Rename { old_name: String, new_name: String },
     Delete,
     Create { content: String },
     Inspect,
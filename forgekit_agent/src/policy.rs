@@ -3,11 +3,16 @@
 //! This module implements policy validation for agent operations, ensuring
 //! that code changes comply with specified constraints.
 
-use crate::Result;
+use crate::{AgentError, Result};
 use forgekit_core::Forge;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+pub use forgekit_core::types::Diff;
+
 type CustomValidatorFn = Arc<dyn Fn(&Diff) -> Vec<PolicyViolation> + Send + Sync>;
 
 /// Policy for constraint validation.
@@ -24,6 +29,15 @@ pub enum Policy {
     /// Maximum cyclomatic complexity
     MaxComplexity(usize),
 
+    /// No edits to files under this path
+    ForbidPath(PathBuf),
+
+    /// Maximum number of files a change may touch
+    MaxChangedFiles(usize),
+
+    /// No removal of existing `pub fn` items
+    ForbidPubFnRemoval,
+
     /// Custom policy with a caller-supplied validation closure.
     ///
     /// Create with [`Policy::custom`].
@@ -40,6 +54,9 @@ impl fmt::Debug for Policy {
             Self::NoUnsafeInPublicAPI => write!(f, "NoUnsafeInPublicAPI"),
             Self::PreserveTests => write!(f, "PreserveTests"),
             Self::MaxComplexity(n) => write!(f, "MaxComplexity({n})"),
+            Self::ForbidPath(path) => write!(f, "ForbidPath({})", path.display()),
+            Self::MaxChangedFiles(n) => write!(f, "MaxChangedFiles({n})"),
+            Self::ForbidPubFnRemoval => write!(f, "ForbidPubFnRemoval"),
             Self::Custom {
                 name, description, ..
             } => f
@@ -70,6 +87,105 @@ impl Policy {
             validator: Arc::new(validator),
         }
     }
+
+    /// Loads policies from a TOML or JSON rule file.
+    ///
+    /// Files ending in `.json` are read as JSON, anything else as TOML.
+    /// Both hold a `rules` list of [`PolicyRule`] tables tagged by `kind`:
+    ///
+    /// ```toml
+    /// [[rules]]
+    /// kind = "forbid_path"
+    /// path = "src/generated/"
+    ///
+    /// [[rules]]
+    /// kind = "max_changed_files"
+    /// max = 20
+    /// ```
+    ///
+    /// Fails with [`AgentError::InvalidPolicy`] if the file cannot be read
+    /// or parsed, or if a rule has an unknown `kind`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Vec<Policy>> {
+        let path = path.as_ref();
+        let invalid =
+            |message: String| AgentError::InvalidPolicy(format!("{}: {}", path.display(), message));
+        let text = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let document: serde_json::Value = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?
+        } else {
+            toml::from_str(&text).map_err(|e| invalid(e.to_string()))?
+        };
+
+        let rules = match document.get("rules") {
+            Some(serde_json::Value::Array(rules)) => rules.clone(),
+            Some(_) => return Err(invalid("`rules` must be a list".to_string())),
+            None => Vec::new(),
+        };
+        rules
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let kind = rule.get("kind").and_then(|k| k.as_str());
+                match kind {
+                    None => Err(invalid(format!("rule {} has no `kind`", i + 1))),
+                    Some(kind) if !PolicyRule::KINDS.contains(&kind) => Err(invalid(format!(
+                        "rule {} has unknown kind `{}` (expected one of: {})",
+                        i + 1,
+                        kind,
+                        PolicyRule::KINDS.join(", ")
+                    ))),
+                    Some(_) => serde_json::from_value::<PolicyRule>(rule)
+                        .map(Policy::from)
+                        .map_err(|e| invalid(format!("rule {}: {}", i + 1, e))),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A declarative policy rule, as written in a rule file.
+///
+/// See [`Policy::from_file`] for the file layout.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PolicyRule {
+    /// No unsafe code in public API
+    NoUnsafeInPublicApi,
+    /// Preserve test coverage
+    PreserveTests,
+    /// Maximum cyclomatic complexity
+    MaxComplexity { max: usize },
+    /// No edits to files under `path`
+    ForbidPath { path: PathBuf },
+    /// Maximum number of files a change may touch
+    MaxChangedFiles { max: usize },
+    /// No removal of existing `pub fn` items
+    ForbidPubFnRemoval,
+}
+
+impl PolicyRule {
+    /// The accepted values of `kind`.
+    pub const KINDS: &'static [&'static str] = &[
+        "no_unsafe_in_public_api",
+        "preserve_tests",
+        "max_complexity",
+        "forbid_path",
+        "max_changed_files",
+        "forbid_pub_fn_removal",
+    ];
+}
+
+impl From<PolicyRule> for Policy {
+    fn from(rule: PolicyRule) -> Self {
+        match rule {
+            PolicyRule::NoUnsafeInPublicApi => Policy::NoUnsafeInPublicAPI,
+            PolicyRule::PreserveTests => Policy::PreserveTests,
+            PolicyRule::MaxComplexity { max } => Policy::MaxComplexity(max),
+            PolicyRule::ForbidPath { path } => Policy::ForbidPath(path),
+            PolicyRule::MaxChangedFiles { max } => Policy::MaxChangedFiles(max),
+            PolicyRule::ForbidPubFnRemoval => Policy::ForbidPubFnRemoval,
+        }
+    }
 }
 
 impl Policy {
@@ -93,6 +209,15 @@ impl Policy {
                     violations.push(v);
                 }
             }
+            Policy::ForbidPath(path) => {
+                violations.extend(check_forbid_path(path, diff));
+            }
+            Policy::MaxChangedFiles(max) => {
+                violations.extend(check_max_changed_files(*max, std::slice::from_ref(diff)));
+            }
+            Policy::ForbidPubFnRemoval => {
+                violations.extend(check_forbid_pub_fn_removal(diff));
+            }
             Policy::Custom { validator, .. } => {
                 violations.extend(validator(diff));
             }
@@ -137,6 +262,35 @@ impl PolicyValidator {
         })
     }
 
+    /// Validates a change spanning several files against all policies.
+    ///
+    /// [`Policy::MaxChangedFiles`] counts the changed files across all of
+    /// `diffs`; every other policy is checked against each diff in turn.
+    pub async fn validate_changes(
+        &self,
+        diffs: &[Diff],
+        policies: &[Policy],
+    ) -> Result<PolicyReport> {
+        let mut all_violations = Vec::new();
+
+        for policy in policies {
+            if let Policy::MaxChangedFiles(max) = policy {
+                all_violations.extend(check_max_changed_files(*max, diffs));
+                continue;
+            }
+            for diff in diffs {
+                let report = policy.validate(&self.forge, diff).await?;
+                all_violations.extend(report.violations);
+            }
+        }
+
+        Ok(PolicyReport {
+            policy: Policy::custom("All", "Combined policy check", |_| vec![]),
+            violations: all_violations.clone(),
+            passed: all_violations.is_empty(),
+        })
+    }
+
     /// Validates a single policy.
     pub async fn validate_single(&self, policy: &Policy, diff: &Diff) -> Result<PolicyReport> {
         policy.validate(&self.forge, diff).await
@@ -189,46 +343,6 @@ impl PolicyViolation {
     }
 }
 
-/// A diff representing code changes.
-///
-/// This is a simplified representation - in production, this would be
-/// a proper AST diff or line-based diff.
-#[derive(Clone, Debug)]
-pub struct Diff {
-    /// File path
-    pub file_path: std::path::PathBuf,
-    /// Original content
-    pub original: String,
-    /// Modified content
-    pub modified: String,
-    /// Changed lines
-    pub changes: Vec<DiffChange>,
-}
-
-/// A single change in a diff.
-#[derive(Clone, Debug)]
-pub struct DiffChange {
-    /// Line number
-    pub line: usize,
-    /// Original line
-    pub original: String,
-    /// Modified line
-    pub modified: String,
-    /// Change type
-    pub kind: DiffChangeKind,
-}
-
-/// Type of diff change.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum DiffChangeKind {
-    /// Line was added
-    Added,
-    /// Line was removed
-    Removed,
-    /// Line was modified
-    Modified,
-}
-
 // Policy validation implementations
 
 /// Checks that no unsafe code appears in public API.
@@ -343,6 +457,69 @@ async fn check_max_complexity(
     })
 }
 
+/// Checks that the diff does not edit a file under `forbidden`.
+fn check_forbid_path(forbidden: &Path, diff: &Diff) -> Option<PolicyViolation> {
+    if diff.hunks.is_empty() || !diff.file_path.starts_with(forbidden) {
+        return None;
+    }
+    Some(PolicyViolation::new(
+        "ForbidPath",
+        format!(
+            "{} is under forbidden path {}",
+            diff.file_path.display(),
+            forbidden.display()
+        ),
+    ))
+}
+
+/// Checks that no more than `max` of the diffs change their file.
+fn check_max_changed_files(max: usize, diffs: &[Diff]) -> Option<PolicyViolation> {
+    let changed: BTreeSet<&Path> = diffs
+        .iter()
+        .filter(|d| !d.hunks.is_empty())
+        .map(|d| d.file_path.as_path())
+        .collect();
+    if changed.len() <= max {
+        return None;
+    }
+    Some(PolicyViolation::new(
+        "MaxChangedFiles",
+        format!("{} files changed, exceeds max {}", changed.len(), max),
+    ))
+}
+
+/// Checks that every `pub fn` in the original is still in the modified content.
+fn check_forbid_pub_fn_removal(diff: &Diff) -> Option<PolicyViolation> {
+    let remaining = pub_fn_names(&diff.modified);
+    let removed: Vec<_> = pub_fn_names(&diff.original)
+        .into_iter()
+        .filter(|name| !remaining.contains(name))
+        .collect();
+    if removed.is_empty() {
+        return None;
+    }
+    Some(PolicyViolation::new(
+        "ForbidPubFnRemoval",
+        format!(
+            "Public functions removed from {}: {}",
+            diff.file_path.display(),
+            removed.join(", ")
+        ),
+    ))
+}
+
+/// Names of the `pub fn` items declared in content.
+fn pub_fn_names(content: &str) -> BTreeSet<String> {
+    let pattern = regex::Regex::new(
+        r#"(?m)^\s*pub(?:\([^)]*\))?\s+(?:(?:const|async|unsafe|extern\s+"[^"]*")\s+)*fn\s+([A-Za-z_][A-Za-z0-9_]*)"#,
+    )
+    .expect("invariant: valid regex");
+    pattern
+        .captures_iter(content)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
 /// Estimates complexity from a single line (for inline functions).
 fn estimate_complexity_from_line(line: &str) -> usize {
     let mut complexity = 1; // Base complexity
@@ -382,12 +559,11 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let forge = Forge::open(temp_dir.path()).await.unwrap();
 
-        let diff = Diff {
-            file_path: PathBuf::from("test.rs"),
-            original: "fn safe() {}".to_string(),
-            modified: "pub unsafe fn dangerous() {}".to_string(),
-            changes: vec![],
-        };
+        let diff = Diff::new(
+            PathBuf::from("test.rs"),
+            "fn safe() {}".to_string(),
+            "pub unsafe fn dangerous() {}".to_string(),
+        );
 
         let policy = Policy::NoUnsafeInPublicAPI;
         let report = policy.validate(&forge, &diff).await.unwrap();
@@ -401,12 +577,11 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let forge = Forge::open(temp_dir.path()).await.unwrap();
 
-        let diff = Diff {
-            file_path: PathBuf::from("test.rs"),
-            original: "#[test]\nfn test_one() {}\n#[test]\nfn test_two() {}".to_string(),
-            modified: "#[test]\nfn test_one() {}".to_string(),
-            changes: vec![],
-        };
+        let diff = Diff::new(
+            PathBuf::from("test.rs"),
+            "#[test]\nfn test_one() {}\n#[test]\nfn test_two() {}".to_string(),
+            "#[test]\nfn test_one() {}".to_string(),
+        );
 
         let policy = Policy::PreserveTests;
         let report = policy.validate(&forge, &diff).await.unwrap();
@@ -420,12 +595,11 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let forge = Forge::open(temp_dir.path()).await.unwrap();
 
-        let diff = Diff {
-            file_path: PathBuf::from("test.rs"),
-            original: "".to_string(),
-            modified: "pub fn complex() { if x { if y { if z {} } } }".to_string(),
-            changes: vec![],
-        };
+        let diff = Diff::new(
+            PathBuf::from("test.rs"),
+            "".to_string(),
+            "pub fn complex() { if x { if y { if z {} } } }".to_string(),
+        );
 
         let policy = Policy::MaxComplexity(3);
         let report = policy.validate(&forge, &diff).await.unwrap();
@@ -458,12 +632,11 @@ mod tests {
         // Validator that always passes (no violations)
         let policy = Policy::custom("allow-all", "permits everything", |_diff| vec![]);
 
-        let diff = Diff {
-            file_path: std::path::PathBuf::from("src/lib.rs"),
-            original: "fn foo() {}".to_string(),
-            modified: "fn foo() { /* changed */ }".to_string(),
-            changes: vec![],
-        };
+        let diff = Diff::new(
+            std::path::PathBuf::from("src/lib.rs"),
+            "fn foo() {}".to_string(),
+            "fn foo() { /* changed */ }".to_string(),
+        );
 
         let report = policy.validate(&forge, &diff).await.unwrap();
         assert!(
@@ -485,12 +658,11 @@ mod tests {
             )]
         });
 
-        let diff = Diff {
-            file_path: std::path::PathBuf::from("src/lib.rs"),
-            original: "fn foo() {}".to_string(),
-            modified: "fn foo() { /* changed */ }".to_string(),
-            changes: vec![],
-        };
+        let diff = Diff::new(
+            std::path::PathBuf::from("src/lib.rs"),
+            "fn foo() {}".to_string(),
+            "fn foo() { /* changed */ }".to_string(),
+        );
 
         let report = policy.validate(&forge, &diff).await.unwrap();
         assert!(
@@ -502,4 +674,127 @@ mod tests {
             .message
             .contains("must not be modified"));
     }
+
+    #[test]
+    fn test_from_file_loads_sample_rules() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/policies.toml");
+        let policies = Policy::from_file(&path).unwrap();
+
+        let names: Vec<_> = policies.iter().map(|p| format!("{p:?}")).collect();
+        assert_eq!(
+            names,
+            vec![
+                "ForbidPath(src/generated/)",
+                "MaxChangedFiles(20)",
+                "ForbidPubFnRemoval",
+                "NoUnsafeInPublicAPI",
+                "PreserveTests",
+                "MaxComplexity(10)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_file_reads_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("policies.json");
+        std::fs::write(
+            &path,
+            r#"{"rules": [{"kind": "max_changed_files", "max": 3}]}"#,
+        )
+        .unwrap();
+
+        let policies = Policy::from_file(&path).unwrap();
+        assert!(matches!(policies[..], [Policy::MaxChangedFiles(3)]));
+    }
+
+    #[test]
+    fn test_from_file_rejects_unknown_kind() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("policies.toml");
+        std::fs::write(
+            &path,
+            "[[rules]]\nkind = \"preserve_tests\"\n\n[[rules]]\nkind = \"no_todo_comments\"\n",
+        )
+        .unwrap();
+
+        let err = Policy::from_file(&path).unwrap_err();
+        assert!(matches!(err, AgentError::InvalidPolicy(_)));
+        let message = err.to_string();
+        assert!(message.contains("rule 2 has unknown kind `no_todo_comments`"));
+        assert!(message.contains("forbid_path"));
+    }
+
+    #[test]
+    fn test_from_file_rejects_unknown_field() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("policies.toml");
+        std::fs::write(
+            &path,
+            "[[rules]]\nkind = \"max_changed_files\"\nlimit = 3\n",
+        )
+        .unwrap();
+
+        let err = Policy::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("rule 1"));
+    }
+
+    #[tokio::test]
+    async fn test_forbid_path_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let forge = Forge::open(temp_dir.path()).await.unwrap();
+        let policy = Policy::ForbidPath(PathBuf::from("src/generated/"));
+
+        let generated = Diff::new("src/generated/schema.rs", "a\n", "b\n");
+        let report = policy.validate(&forge, &generated).await.unwrap();
+        assert!(!report.passed);
+        assert!(report.violations[0].message.contains("src/generated"));
+
+        // A sibling whose name merely starts with the same text is allowed
+        let sibling = Diff::new("src/generated_docs.rs", "a\n", "b\n");
+        assert!(policy.validate(&forge, &sibling).await.unwrap().passed);
+    }
+
+    #[tokio::test]
+    async fn test_forbid_pub_fn_removal_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let forge = Forge::open(temp_dir.path()).await.unwrap();
+        let policy = Policy::ForbidPubFnRemoval;
+        let original = "pub fn keep() {}\npub(crate) async fn gone() {}\nfn private() {}\n";
+
+        let removed = Diff::new("src/lib.rs", original, "pub fn keep() {}\n");
+        let report = policy.validate(&forge, &removed).await.unwrap();
+        assert!(!report.passed);
+        assert!(report.violations[0].message.ends_with(": gone"));
+
+        let edited = Diff::new(
+            "src/lib.rs",
+            original,
+            "pub fn keep() { body() }\npub(crate) async fn gone() {}\n",
+        );
+        assert!(policy.validate(&forge, &edited).await.unwrap().passed);
+    }
+
+    #[tokio::test]
+    async fn test_validate_changes_counts_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let forge = Forge::open(temp_dir.path()).await.unwrap();
+        let validator = PolicyValidator::new(forge);
+        let policies = [Policy::MaxChangedFiles(2)];
+
+        let mut diffs = vec![
+            Diff::new("src/a.rs", "a\n", "b\n"),
+            Diff::new("src/b.rs", "a\n", "b\n"),
+            // Unchanged content does not count
+            Diff::new("src/c.rs", "a\n", "a\n"),
+        ];
+        let report = validator.validate_changes(&diffs, &policies).await.unwrap();
+        assert!(report.passed);
+
+        diffs.push(Diff::new("src/d.rs", "a\n", "b\n"));
+        let report = validator.validate_changes(&diffs, &policies).await.unwrap();
+        assert!(!report.passed);
+        assert_eq!(report.violations.len(), 1);
+        assert!(report.violations[0].message.contains("3 files changed"));
+    }
 }
@@ -4,9 +4,48 @@
 //! mutations meet quality and policy requirements.
 
 use crate::{AgentError, Result};
-use std::path::Path;
-use std::process::Command;
+use forgekit_core::diagnostic::{CargoDiagnosticParser, DiagnosticParser, DiagnosticSeverity};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+/// The build command and limits applied to the subprocesses run during
+/// verification.
+#[derive(Clone, Debug)]
+pub struct VerifyConfig {
+    /// Build command run by the compile check when no Forge build module is
+    /// available, program first (default: `cargo check --message-format=json`)
+    ///
+    /// Cargo JSON diagnostics in its output are parsed with file and line;
+    /// other output is matched as rustc text.
+    pub check_command: Vec<String>,
+    /// Directory the build command runs in, instead of the one being
+    /// verified
+    pub working_dir: Option<PathBuf>,
+    /// Maximum wall-clock time for each check before the subprocess is killed
+    pub timeout: Duration,
+    /// Maximum bytes captured from each of stdout and stderr; the rest is discarded
+    pub max_output_bytes: usize,
+    /// Fail verification on warnings as well as errors (default: false)
+    pub deny_warnings: bool,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            check_command: ["cargo", "check", "--message-format=json"]
+                .map(String::from)
+                .to_vec(),
+            working_dir: None,
+            timeout: Duration::from_secs(600),
+            max_output_bytes: 1024 * 1024,
+            deny_warnings: false,
+        }
+    }
+}
 
 /// Verifier for post-mutation validation.
 ///
@@ -18,6 +57,8 @@ pub struct Verifier {
     forge: Option<forgekit_core::Forge>,
     /// Optional LLM provider for error interpretation
     llm: Option<Arc<dyn crate::llm::LlmProvider>>,
+    /// Subprocess timeout and output limits
+    config: VerifyConfig,
 }
 
 impl Default for Verifier {
@@ -32,6 +73,7 @@ impl Verifier {
         Self {
             forge: None,
             llm: None,
+            config: VerifyConfig::default(),
         }
     }
 
@@ -40,6 +82,7 @@ impl Verifier {
         Self {
             forge: Some(forge),
             llm: None,
+            config: VerifyConfig::default(),
         }
     }
 
@@ -49,63 +92,150 @@ impl Verifier {
         self
     }
 
-    /// Runs compile check.
+    /// Sets the timeout and output limits for verification subprocesses.
+    pub fn with_config(mut self, config: VerifyConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Runs `cargo <args>` under the configured timeout and output cap.
     ///
-    /// Uses `BuildModule::check()` when a Forge instance is available,
-    /// falling back to raw `cargo check` otherwise.
-    pub async fn compile_check(&self, working_dir: &std::path::Path) -> Result<Vec<Diagnostic>> {
-        if let Some(ref forge) = self.forge {
-            if let Some(build) = forge.build() {
-                let output = build.check(working_dir).await.map_err(|e| {
-                    AgentError::VerificationFailed(format!("Build check failed: {}", e))
-                })?;
-                return Ok(output
-                    .diagnostics
-                    .iter()
-                    .filter_map(|d| {
-                        let level = match d.severity {
-                            forgekit_core::diagnostic::DiagnosticSeverity::Error => {
-                                Some(DiagnosticLevel::Error)
-                            }
-                            forgekit_core::diagnostic::DiagnosticSeverity::Warning => {
-                                Some(DiagnosticLevel::Warning)
-                            }
-                            _ => None,
-                        };
-                        level.map(|l| Diagnostic {
-                            level: l,
-                            message: d.message.clone(),
-                        })
-                    })
-                    .collect());
+    /// Returns the captured (stdout, stderr).
+    async fn run_cargo(&self, args: &[&str], working_dir: &Path) -> Result<(String, String)> {
+        let (_, stdout, stderr) = self.run_command("cargo", args, working_dir).await?;
+        Ok((stdout, stderr))
+    }
+
+    /// Runs `program <args>` under the configured timeout and output cap.
+    ///
+    /// The subprocess is killed if it outlives the timeout. Returns whether
+    /// it exited successfully and the captured (stdout, stderr), each
+    /// truncated to `max_output_bytes`.
+    async fn run_command(
+        &self,
+        program: &str,
+        args: &[&str],
+        working_dir: &Path,
+    ) -> Result<(bool, String, String)> {
+        let command = match args.first() {
+            Some(first) => format!("{} {}", program, first),
+            None => program.to_string(),
+        };
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(working_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AgentError::VerificationFailed(format!("{} failed: {}", command, e)))?;
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let max = self.config.max_output_bytes;
+        let run = async {
+            let (stdout, stderr, status) = tokio::join!(
+                read_capped(stdout, max),
+                read_capped(stderr, max),
+                child.wait()
+            );
+            status.map(|status| (status.success(), stdout, stderr))
+        };
+
+        match tokio::time::timeout(self.config.timeout, run).await {
+            Ok(Ok((success, stdout, stderr))) => Ok((
+                success,
+                String::from_utf8_lossy(&stdout).into_owned(),
+                String::from_utf8_lossy(&stderr).into_owned(),
+            )),
+            Ok(Err(e)) => Err(AgentError::VerificationFailed(format!(
+                "{} failed: {}",
+                command, e
+            ))),
+            Err(_) => {
+                let _ = child.kill().await;
+                Err(AgentError::VerificationFailed(format!(
+                    "{} timed out after {:?}",
+                    command, self.config.timeout
+                )))
             }
         }
+    }
 
-        let output = Command::new("cargo")
-            .args(["check", "--message-format=short"])
-            .current_dir(working_dir)
-            .output()
-            .map_err(|e| AgentError::VerificationFailed(format!("Cargo check failed: {}", e)))?;
+    /// The Forge build module, capped at the configured output size.
+    fn forge_build(&self) -> Option<forgekit_core::build::BuildModule> {
+        self.forge
+            .as_ref()?
+            .build()
+            .map(|build| build.with_max_output_bytes(self.config.max_output_bytes))
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    /// Applies the configured timeout to a Forge build call.
+    ///
+    /// Dropping `fut` on timeout kills the build it spawned.
+    async fn with_timeout<T>(
+        &self,
+        command: &str,
+        fut: impl std::future::Future<Output = forgekit_core::Result<T>>,
+    ) -> Result<T> {
+        match tokio::time::timeout(self.config.timeout, fut).await {
+            Ok(result) => result.map_err(|e| {
+                AgentError::VerificationFailed(format!("Build {} failed: {}", command, e))
+            }),
+            Err(_) => Err(AgentError::VerificationFailed(format!(
+                "Build {} timed out after {:?}",
+                command, self.config.timeout
+            ))),
+        }
+    }
 
-        let mut diagnostics = Vec::new();
-        for line in stdout.lines().chain(stderr.lines()) {
-            if line.contains("error:") {
-                diagnostics.push(Diagnostic {
-                    level: DiagnosticLevel::Error,
-                    message: line.trim().to_string(),
-                });
-            } else if line.contains("warning:") {
-                diagnostics.push(Diagnostic {
-                    level: DiagnosticLevel::Warning,
-                    message: line.trim().to_string(),
-                });
-            }
+    /// Runs compile check.
+    ///
+    /// Uses `BuildModule::check()` when a Forge instance is available,
+    /// falling back to the configured
+    /// [`check_command`](VerifyConfig::check_command) otherwise. Either
+    /// runs in the configured [`working_dir`](VerifyConfig::working_dir)
+    /// if set. A command that fails without reporting an error yields one
+    /// error naming it.
+    pub async fn compile_check(&self, working_dir: &std::path::Path) -> Result<Vec<Diagnostic>> {
+        let dir = self.config.working_dir.as_deref().unwrap_or(working_dir);
+        if let Some(build) = self.forge_build() {
+            let output = self.with_timeout("check", build.check(dir)).await?;
+            let mut diagnostics: Vec<Diagnostic> = output
+                .diagnostics
+                .iter()
+                .filter(|d| {
+                    matches!(
+                        d.severity,
+                        DiagnosticSeverity::Error | DiagnosticSeverity::Warning
+                    )
+                })
+                .map(Diagnostic::from_core)
+                .collect();
+            report_failure(
+                output.success,
+                &mut diagnostics,
+                &output.stderr,
+                &format!("{} check", build.system_name()),
+            );
+            return Ok(diagnostics);
         }
 
-        Ok(diagnostics)
+        let Some((program, args)) = self.config.check_command.split_first() else {
+            return Err(AgentError::VerificationFailed(
+                "No check command configured".to_string(),
+            ));
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let (success, stdout, stderr) = self.run_command(program, &args, dir).await?;
+
+        Ok(parse_check_output(
+            success,
+            &stdout,
+            &stderr,
+            &self.config.check_command.join(" "),
+        ))
     }
 
     /// Runs tests.
@@ -113,50 +243,28 @@ impl Verifier {
     /// Uses `BuildModule::test()` when a Forge instance is available,
     /// falling back to raw `cargo test` otherwise.
     pub async fn test_check(&self, working_dir: &std::path::Path) -> Result<Vec<Diagnostic>> {
-        if let Some(ref forge) = self.forge {
-            if let Some(build) = forge.build() {
-                let output = build.test(working_dir).await.map_err(|e| {
-                    AgentError::VerificationFailed(format!("Build test failed: {}", e))
-                })?;
-                if output.success {
-                    return Ok(Vec::new());
-                }
-                return Ok(output
-                    .diagnostics
-                    .iter()
-                    .filter_map(|d| {
-                        let level = match d.severity {
-                            forgekit_core::diagnostic::DiagnosticSeverity::Error => {
-                                Some(DiagnosticLevel::Error)
-                            }
-                            _ => None,
-                        };
-                        level.map(|l| Diagnostic {
-                            level: l,
-                            message: d.message.clone(),
-                        })
-                    })
-                    .collect());
+        if let Some(build) = self.forge_build() {
+            let output = self.with_timeout("test", build.test(working_dir)).await?;
+            if output.success {
+                return Ok(Vec::new());
             }
+            return Ok(output
+                .diagnostics
+                .iter()
+                .filter(|d| d.severity == DiagnosticSeverity::Error)
+                .map(Diagnostic::from_core)
+                .collect());
         }
 
-        let output = Command::new("cargo")
-            .args(["test", "--message-format=short"])
-            .current_dir(working_dir)
-            .output()
-            .map_err(|e| AgentError::VerificationFailed(format!("Cargo test failed: {}", e)))?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let (stdout, stderr) = self
+            .run_cargo(&["test", "--message-format=short"], working_dir)
+            .await?;
 
         let mut diagnostics = Vec::new();
 
         for line in stdout.lines().chain(stderr.lines()) {
             if line.contains("test result:") && line.contains("FAILED") {
-                diagnostics.push(Diagnostic {
-                    level: DiagnosticLevel::Error,
-                    message: line.trim().to_string(),
-                });
+                diagnostics.push(Diagnostic::new(DiagnosticLevel::Error, line.trim()));
             } else if line.contains("test result:") && line.contains("ok") {
                 // Tests passed
             }
@@ -175,27 +283,27 @@ impl Verifier {
             // Use Forge SDK to check graph health
             match forge.graph().symbol_count().await {
                 Ok(count) => {
-                    diagnostics.push(Diagnostic {
-                        level: DiagnosticLevel::Info,
-                        message: format!(
+                    diagnostics.push(Diagnostic::new(
+                        DiagnosticLevel::Info,
+                        format!(
                             "Graph consistency: {} symbols indexed in {}",
                             count,
                             working_dir.display()
                         ),
-                    });
+                    ));
                 }
                 Err(e) => {
-                    diagnostics.push(Diagnostic {
-                        level: DiagnosticLevel::Warning,
-                        message: format!("Graph query failed: {}", e),
-                    });
+                    diagnostics.push(Diagnostic::new(
+                        DiagnosticLevel::Warning,
+                        format!("Graph query failed: {}", e),
+                    ));
                 }
             }
         } else {
-            diagnostics.push(Diagnostic {
-                level: DiagnosticLevel::Info,
-                message: "Graph check: no Forge SDK available".to_string(),
-            });
+            diagnostics.push(Diagnostic::new(
+                DiagnosticLevel::Info,
+                "Graph check: no Forge SDK available",
+            ));
         }
 
         Ok(diagnostics)
@@ -221,12 +329,7 @@ impl Verifier {
         let graph_diags = self.graph_check(working_dir).await?;
         all_diagnostics.extend(graph_diags);
 
-        let errors = all_diagnostics
-            .iter()
-            .filter(|d| d.level == DiagnosticLevel::Error)
-            .count();
-
-        let passed = errors == 0;
+        let passed = self.passes(&all_diagnostics);
 
         // Interpret errors using LLM if available and errors exist
         let suggestions = if !passed {
@@ -268,12 +371,7 @@ impl Verifier {
         let graph_diags = self.graph_check(working_dir).await?;
         all_diagnostics.extend(graph_diags);
 
-        let errors = all_diagnostics
-            .iter()
-            .filter(|d| d.level == DiagnosticLevel::Error)
-            .count();
-
-        let passed = errors == 0;
+        let passed = self.passes(&all_diagnostics);
 
         let suggestions = if !passed {
             if diffs.is_empty() {
@@ -293,6 +391,16 @@ impl Verifier {
         })
     }
 
+    /// Returns true if `diagnostics` hold no error, and no warning when
+    /// [`deny_warnings`](VerifyConfig::deny_warnings) is set.
+    fn passes(&self, diagnostics: &[Diagnostic]) -> bool {
+        diagnostics.iter().all(|d| match d.level {
+            DiagnosticLevel::Error => false,
+            DiagnosticLevel::Warning => !self.config.deny_warnings,
+            DiagnosticLevel::Info => true,
+        })
+    }
+
     /// Interpret verification errors using LLM. Returns suggestions.
     pub async fn interpret_errors(&self, diagnostics: &[Diagnostic]) -> Option<String> {
         let llm = self.llm.as_ref()?;
@@ -306,7 +414,7 @@ impl Verifier {
             return None;
         }
 
-        let error_text: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
+        let error_text: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
         let prompt = format!(
             "The following compilation/test errors were detected:\n\n{}",
             error_text.join("\n")
@@ -340,7 +448,7 @@ impl Verifier {
             return None;
         }
 
-        let error_text: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
+        let error_text: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
         let diff_text = diffs.join("\n");
         let prompt = format!(
             "The following compilation/test errors were detected:\n\n{}\n\nRecent changes (diffs):\n{}",
@@ -378,6 +486,50 @@ pub struct Diagnostic {
     pub level: DiagnosticLevel,
     /// Diagnostic message
     pub message: String,
+    /// File the diagnostic points at, as reported by the compiler
+    pub file: Option<PathBuf>,
+    /// 1-based line in `file`
+    pub line: Option<usize>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic without a location.
+    pub fn new(level: DiagnosticLevel, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            file: None,
+            line: None,
+        }
+    }
+
+    /// Converts a parsed build diagnostic; severities below warning
+    /// become [`Info`](DiagnosticLevel::Info).
+    fn from_core(d: &forgekit_core::diagnostic::Diagnostic) -> Self {
+        let level = match d.severity {
+            DiagnosticSeverity::Error => DiagnosticLevel::Error,
+            DiagnosticSeverity::Warning => DiagnosticLevel::Warning,
+            _ => DiagnosticLevel::Info,
+        };
+        Self {
+            level,
+            message: d.message.clone(),
+            file: d.location.as_ref().map(|l| l.file.clone()),
+            line: d.location.as_ref().map(|l| l.line),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    /// Formats as `file:line: message`, or just the message when the
+    /// location is unknown.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{}: {}", file.display(), line, self.message),
+            (Some(file), None) => write!(f, "{}: {}", file.display(), self.message),
+            _ => f.write_str(&self.message),
+        }
+    }
 }
 
 /// Diagnostic severity level.
@@ -391,6 +543,65 @@ pub enum DiagnosticLevel {
     Error,
 }
 
+/// Parses the output of a build command into diagnostics.
+///
+/// Cargo writes JSON messages to stdout and its own errors to stderr, so
+/// both are parsed. If the command failed without an error among them,
+/// the last `error:` line of stderr, or else a note naming `command`,
+/// stands in for one.
+fn parse_check_output(success: bool, stdout: &str, stderr: &str, command: &str) -> Vec<Diagnostic> {
+    let parser = CargoDiagnosticParser;
+    let mut diagnostics: Vec<Diagnostic> = parser
+        .parse("", stdout)
+        .iter()
+        .chain(&parser.parse("", stderr))
+        .map(Diagnostic::from_core)
+        .collect();
+    report_failure(success, &mut diagnostics, stderr, command);
+    diagnostics
+}
+
+/// Adds an error naming `command` if it failed without reporting one, so
+/// a failed check never passes.
+fn report_failure(success: bool, diagnostics: &mut Vec<Diagnostic>, stderr: &str, command: &str) {
+    if success
+        || diagnostics
+            .iter()
+            .any(|d| d.level == DiagnosticLevel::Error)
+    {
+        return;
+    }
+    let message = stderr
+        .lines()
+        .rev()
+        .find(|line| line.starts_with("error"))
+        .map(|line| line.trim().to_string())
+        .unwrap_or_else(|| format!("`{}` failed", command));
+    diagnostics.push(Diagnostic::new(DiagnosticLevel::Error, message));
+}
+
+/// Reads `reader` to EOF, keeping at most `max` bytes.
+///
+/// Output past the cap is drained and discarded so the child never blocks
+/// on a full pipe.
+async fn read_capped<R: AsyncRead + Unpin>(reader: Option<R>, max: usize) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let Some(mut reader) = reader else {
+        return buf;
+    };
+    let mut chunk = [0u8; 8192];
+    loop {
+        match reader.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let room = max.saturating_sub(buf.len());
+                buf.extend_from_slice(&chunk[..n.min(room)]);
+            }
+        }
+    }
+    buf
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,10 +625,10 @@ mod tests {
 
         let verifier = Verifier::new().with_llm(mock);
 
-        let diagnostics = vec![Diagnostic {
-            level: DiagnosticLevel::Error,
-            message: "error: expected `;`, found `let`".to_string(),
-        }];
+        let diagnostics = vec![Diagnostic::new(
+            DiagnosticLevel::Error,
+            "error: expected `;`, found `let`",
+        )];
 
         let result = verifier.interpret_errors(&diagnostics).await;
         assert!(result.is_some());
@@ -428,10 +639,10 @@ mod tests {
     async fn test_verifier_interpret_errors_without_llm() {
         let verifier = Verifier::new();
 
-        let diagnostics = vec![Diagnostic {
-            level: DiagnosticLevel::Error,
-            message: "error: something broke".to_string(),
-        }];
+        let diagnostics = vec![Diagnostic::new(
+            DiagnosticLevel::Error,
+            "error: something broke",
+        )];
 
         let result = verifier.interpret_errors(&diagnostics).await;
         assert!(
@@ -445,10 +656,10 @@ mod tests {
         let mock = Arc::new(crate::llm::MockProvider::new("should not be called"));
         let verifier = Verifier::new().with_llm(mock);
 
-        let diagnostics = vec![Diagnostic {
-            level: DiagnosticLevel::Warning,
-            message: "warning: unused variable".to_string(),
-        }];
+        let diagnostics = vec![Diagnostic::new(
+            DiagnosticLevel::Warning,
+            "warning: unused variable",
+        )];
 
         let result = verifier.interpret_errors(&diagnostics).await;
         assert!(
@@ -485,10 +696,10 @@ mod tests {
 
         let verifier = Verifier::new().with_llm(mock);
 
-        let diagnostics = vec![Diagnostic {
-            level: DiagnosticLevel::Error,
-            message: "error: cannot find type `Foo` in scope".to_string(),
-        }];
+        let diagnostics = vec![Diagnostic::new(
+            DiagnosticLevel::Error,
+            "error: cannot find type `Foo` in scope",
+        )];
         let diffs = vec!["--- src/lib.rs\n- use module::Foo;".to_string()];
 
         let result = verifier
@@ -512,4 +723,196 @@ mod tests {
         let result = verifier.compile_check(temp_dir.path()).await;
         assert!(result.is_ok(), "compile_check with forge should succeed");
     }
+
+    #[tokio::test]
+    async fn test_read_capped_truncates_output() {
+        let data = vec![b'x'; 20_000];
+        let captured = read_capped(Some(&data[..]), 100).await;
+        assert_eq!(captured.len(), 100);
+
+        let captured = read_capped(None::<&[u8]>, 100).await;
+        assert!(captured.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_verify_times_out_on_hanging_build() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("Cargo.toml"),
+            "[package]\nname = \"hang\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(temp.path().join("src")).unwrap();
+        std::fs::write(temp.path().join("src/lib.rs"), "").unwrap();
+        // Build script that stalls well past the verify timeout
+        std::fs::write(
+            temp.path().join("build.rs"),
+            "fn main() { std::thread::sleep(std::time::Duration::from_secs(30)); }\n",
+        )
+        .unwrap();
+
+        let verifier = Verifier::new().with_config(VerifyConfig {
+            timeout: Duration::from_secs(3),
+            max_output_bytes: 4096,
+            ..Default::default()
+        });
+
+        let start = std::time::Instant::now();
+        let result = verifier.verify(temp.path()).await;
+
+        assert!(start.elapsed() < Duration::from_secs(20));
+        match result {
+            Err(AgentError::VerificationFailed(msg)) => assert!(msg.contains("timed out")),
+            other => panic!(
+                "expected timeout failure, got {:?}",
+                other.map(|r| r.passed)
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_forge_failed_check_without_diagnostics_fails() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("Makefile"),
+            "check:\n\t@echo broken >&2; exit 1\n",
+        )
+        .unwrap();
+        let forge = forgekit_core::ForgeBuilder::new()
+            .path(temp.path())
+            .db_path(temp.path().join("test.db"))
+            .build()
+            .await
+            .unwrap();
+
+        let diagnostics = Verifier::with_forge(forge)
+            .compile_check(temp.path())
+            .await
+            .unwrap();
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.level == DiagnosticLevel::Error && d.message.contains("make check")),
+            "{:?}",
+            diagnostics
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_forge_timeout_kills_build() {
+        let temp = tempfile::tempdir().unwrap();
+        // `make check` records its own pid, then stalls past the timeout
+        std::fs::write(
+            temp.path().join("Makefile"),
+            "check:\n\techo $$PPID > make.pid; sleep 10\n",
+        )
+        .unwrap();
+        let forge = forgekit_core::ForgeBuilder::new()
+            .path(temp.path())
+            .db_path(temp.path().join("test.db"))
+            .build()
+            .await
+            .unwrap();
+        let verifier = Verifier::with_forge(forge).with_config(VerifyConfig {
+            timeout: Duration::from_secs(2),
+            ..Default::default()
+        });
+
+        match verifier.compile_check(temp.path()).await {
+            Err(AgentError::VerificationFailed(msg)) => assert!(msg.contains("timed out")),
+            other => panic!("expected timeout failure, got {:?}", other),
+        }
+
+        let pid = std::fs::read_to_string(temp.path().join("make.pid")).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            // A killed child is gone once reaped, a zombie until then
+            let alive = std::fs::read_to_string(&stat)
+                .map(|s| !s.contains(") Z "))
+                .unwrap_or(false);
+            if !alive {
+                break;
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "make still running after timeout"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Writes a one-file library crate with `lib_rs` as its source.
+    fn write_crate(dir: &Path, lib_rs: &str) {
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"probe\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), lib_rs).unwrap();
+    }
+
+    #[test]
+    fn test_parse_check_output_reads_json_diagnostics() {
+        let stdout = concat!(
+            r#"{"reason":"compiler-artifact","package_id":"probe"}"#,
+            "\n",
+            r#"{"reason":"compiler-message","message":{"message":"mismatched types","level":"error","code":{"code":"E0308"},"spans":[{"file_name":"src/lib.rs","line_start":3,"column_start":5,"is_primary":true}],"rendered":"error[E0308]"}}"#,
+            "\n",
+        );
+
+        let diagnostics = parse_check_output(false, stdout, "", "cargo check");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, DiagnosticLevel::Error);
+        assert_eq!(
+            diagnostics[0].file.as_deref(),
+            Some(Path::new("src/lib.rs"))
+        );
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(diagnostics[0].to_string(), "src/lib.rs:3: mismatched types");
+
+        // A failed command with nothing parseable still reports an error
+        let diagnostics = parse_check_output(false, "", "", "make check");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("make check"));
+    }
+
+    #[tokio::test]
+    async fn test_compile_check_reports_error_location() {
+        let temp = tempfile::tempdir().unwrap();
+        write_crate(
+            temp.path(),
+            "pub fn answer() -> i32 {\n    \"forty-two\"\n}\n",
+        );
+
+        let report = Verifier::new().verify(temp.path()).await.unwrap();
+        assert!(!report.passed);
+        let error = report
+            .diagnostics
+            .iter()
+            .find(|d| d.level == DiagnosticLevel::Error)
+            .expect("type error reported");
+        assert_eq!(error.file.as_deref(), Some(Path::new("src/lib.rs")));
+        assert_eq!(error.line, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_deny_warnings_fails_on_warning() {
+        let temp = tempfile::tempdir().unwrap();
+        write_crate(temp.path(), "fn unused() {}\n");
+
+        let report = Verifier::new().verify(temp.path()).await.unwrap();
+        assert!(report.passed);
+        assert!(report
+            .diagnostics
+            .iter()
+            .any(|d| d.level == DiagnosticLevel::Warning));
+
+        let strict = Verifier::new().with_config(VerifyConfig {
+            deny_warnings: true,
+            ..Default::default()
+        });
+        assert!(!strict.verify(temp.path()).await.unwrap().passed);
+    }
 }
@@ -116,34 +116,15 @@ fn count_decision_points(cfg: &TestCfg) -> usize {
         .count()
 }
 
-/// Calculate maximum nesting depth using DFS
+/// Calculate maximum nesting depth as the depth of the dominator tree
 fn calculate_max_depth(cfg: &TestCfg) -> usize {
-    let mut max_depth = 0;
-    let mut visited = std::collections::HashSet::new();
-
-    fn dfs(
-        cfg: &TestCfg,
-        node: BlockId,
-        depth: usize,
-        visited: &mut std::collections::HashSet<BlockId>,
-        max_depth: &mut usize,
-    ) {
-        if visited.contains(&node) {
-            return;
-        }
-        visited.insert(node);
-
-        *max_depth = (*max_depth).max(depth);
-
-        if let Some(succs) = cfg.successors.get(&node) {
-            for succ in succs {
-                dfs(cfg, *succ, depth + 1, visited, max_depth);
-            }
-        }
-    }
-
-    dfs(cfg, cfg.entry, 1, &mut visited, &mut max_depth);
-    max_depth
+    let dominators = cfg.compute_dominators();
+    dominators
+        .dominators
+        .keys()
+        .map(|&block| dominators.depth(block))
+        .max()
+        .unwrap_or(0)
 }
 
 /// Analyze source code to estimate complexity without full CFG
@@ -215,6 +196,27 @@ mod tests {
         assert_eq!(metrics.decision_points, 0);
     }
 
+    #[test]
+    fn test_if_else_complexity() {
+        let metrics = ComplexityMetrics::from_cfg(&TestCfg::if_else(), 5);
+
+        // 4 edges, 4 nodes: CC = 4 - 4 + 2 = 2
+        assert_eq!(metrics.cyclomatic_complexity, 2);
+        assert_eq!(metrics.decision_points, 1);
+        // Both branches and the join are immediately dominated by the entry
+        assert_eq!(metrics.max_nesting_depth, 1);
+    }
+
+    #[test]
+    fn test_loop_complexity() {
+        let metrics = ComplexityMetrics::from_cfg(&TestCfg::simple_loop(), 5);
+
+        // 4 edges, 4 nodes: CC = 4 - 4 + 2 = 2
+        assert_eq!(metrics.cyclomatic_complexity, 2);
+        assert_eq!(metrics.decision_points, 1);
+        assert_eq!(metrics.max_nesting_depth, 2);
+    }
+
     #[test]
     fn test_risk_levels() {
         let low = ComplexityMetrics {
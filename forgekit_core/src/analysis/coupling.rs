@@ -0,0 +1,155 @@
+//! File coupling analysis
+//!
+//! Counts cross-file references between every pair of files. magellan
+//! records each usage as `reference -REFERENCES-> symbol`, so the source
+//! file is the reference node's file and the target file is the symbol's.
+
+use crate::error::{ForgeError, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// File × file counts of cross-file references.
+///
+/// Self-references are never counted, so the diagonal is always zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CouplingMatrix {
+    /// Files that reference or are referenced by another file, sorted
+    pub files: Vec<String>,
+    /// Reference counts keyed by `(from, to)`; zero cells are omitted
+    pub counts: BTreeMap<(String, String), usize>,
+}
+
+impl CouplingMatrix {
+    /// Number of references from `from` to symbols defined in `to`.
+    pub fn get(&self, from: &str, to: &str) -> usize {
+        self.counts
+            .get(&(from.to_string(), to.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Non-zero cells, most tightly coupled first.
+    ///
+    /// Ties are ordered by `from`, then `to`.
+    pub fn pairs(&self) -> Vec<(&str, &str, usize)> {
+        let mut pairs: Vec<(&str, &str, usize)> = self
+            .counts
+            .iter()
+            .map(|((from, to), count)| (from.as_str(), to.as_str(), *count))
+            .collect();
+        pairs.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| (a.0, a.1).cmp(&(b.0, b.1))));
+        pairs
+    }
+
+    /// Renders the matrix as CSV.
+    ///
+    /// The header row lists target files; each following row starts with
+    /// the referencing file and holds one count per target.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        csv.push_str("file");
+        for file in &self.files {
+            csv.push(',');
+            csv.push_str(&csv_field(file));
+        }
+        csv.push('\n');
+
+        for from in &self.files {
+            csv.push_str(&csv_field(from));
+            for to in &self.files {
+                csv.push(',');
+                csv.push_str(&self.get(from, to).to_string());
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Builds the coupling matrix from the graph database.
+///
+/// File paths under `codebase_path` are reported relative to it. Returns
+/// an empty matrix if the database is missing or has no graph tables.
+pub(crate) fn coupling_matrix(db_path: &Path, codebase_path: &Path) -> Result<CouplingMatrix> {
+    if !db_path.exists() {
+        return Ok(CouplingMatrix::default());
+    }
+    let conn = crate::storage::open_read_only(db_path)
+        .map_err(|e| ForgeError::DatabaseError(format!("Open db failed: {}", e)))?;
+
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT r.file_path, s.file_path
+         FROM graph_edges e
+         JOIN graph_entities r ON r.id = e.from_id
+         JOIN graph_entities s ON s.id = e.to_id
+         WHERE e.edge_type = 'REFERENCES' AND r.kind = 'Reference'",
+    ) else {
+        return Ok(CouplingMatrix::default());
+    };
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })
+        .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?;
+
+    let relative = |file: String| -> String {
+        Path::new(&file)
+            .strip_prefix(codebase_path)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or(file)
+    };
+
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    for (from, to) in rows.flatten() {
+        let (Some(from), Some(to)) = (from, to) else {
+            continue;
+        };
+        let (from, to) = (relative(from), relative(to));
+        if from != to {
+            *counts.entry((from, to)).or_default() += 1;
+        }
+    }
+
+    let files: BTreeSet<String> = counts
+        .keys()
+        .flat_map(|(from, to)| [from.clone(), to.clone()])
+        .collect();
+
+    Ok(CouplingMatrix {
+        files: files.into_iter().collect(),
+        counts: counts.into_iter().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_quotes_fields_and_fills_zeros() {
+        let mut matrix = CouplingMatrix {
+            files: vec!["a,b.rs".to_string(), "c.rs".to_string()],
+            counts: BTreeMap::new(),
+        };
+        matrix
+            .counts
+            .insert(("c.rs".to_string(), "a,b.rs".to_string()), 3);
+
+        assert_eq!(
+            matrix.to_csv(),
+            "file,\"a,b.rs\",c.rs\n\"a,b.rs\",0,0\nc.rs,3,0\n"
+        );
+        assert_eq!(matrix.pairs(), vec![("c.rs", "a,b.rs", 3)]);
+    }
+}
@@ -3,69 +3,393 @@
 //! Finds symbols that are defined but never referenced/called.
 
 use crate::error::{ForgeError, Result};
-use crate::types::Symbol;
-use std::path::Path;
-use std::sync::Arc;
+use crate::search::declaration_from_line;
+use crate::types::{Language, Symbol, SymbolId, SymbolKind, Visibility};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Symbols that [`DeadCodeAnalyzer`] treats as used even without references.
+///
+/// Every check is off by default. The checks read the source files the
+/// symbols are declared in, so they need the codebase root passed to
+/// [`DeadCodeAnalyzer::with_config`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeadCodeConfig {
+    /// Treat `pub` and `pub(crate)` items in `lib.rs` or `main.rs`, and
+    /// items re-exported there with `pub use`, as entry points
+    pub public_roots: bool,
+    /// Skip methods of `impl Trait for Type` blocks, which may be called
+    /// through the trait
+    pub trait_impls: bool,
+    /// Skip items marked `#[no_mangle]` or `#[export_name]`
+    pub exported_symbols: bool,
+    /// Skip `#[test]` functions and items in `#[cfg(test)]` modules
+    pub tests: bool,
+}
+
+impl DeadCodeConfig {
+    /// Enables every check.
+    pub fn all() -> Self {
+        Self {
+            public_roots: true,
+            trait_impls: true,
+            exported_symbols: true,
+            tests: true,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+}
 
 /// Dead code analyzer
 pub struct DeadCodeAnalyzer<'a> {
     db_path: &'a Path,
+    config: DeadCodeConfig,
+    codebase_path: Option<&'a Path>,
+    /// Kept between [`update`](Self::update) calls
+    index: Mutex<Option<ReferenceIndex>>,
+}
+
+/// Symbols that became dead or stopped being dead, from
+/// [`DeadCodeAnalyzer::update`].
+#[derive(Debug, Clone, Default)]
+pub struct DeadCodeDelta {
+    pub newly_dead: Vec<DeadSymbol>,
+    /// Previously dead symbols that are now referenced or were removed
+    pub newly_live: Vec<DeadSymbol>,
+}
+
+/// What the last [`DeadCodeAnalyzer::update`] saw.
+struct ReferenceIndex {
+    /// The symbols each symbol references
+    references: HashMap<i64, HashSet<i64>>,
+    dead: BTreeMap<i64, DeadSymbol>,
 }
 
 impl<'a> DeadCodeAnalyzer<'a> {
     /// Create a new dead code analyzer
     pub fn new(db_path: &'a Path) -> Self {
-        Self { db_path }
+        Self {
+            db_path,
+            config: DeadCodeConfig::default(),
+            codebase_path: None,
+            index: Mutex::new(None),
+        }
+    }
+
+    /// Applies `config`, resolving stored file paths against `codebase_path`.
+    pub fn with_config(self, config: DeadCodeConfig, codebase_path: &'a Path) -> Self {
+        Self {
+            config,
+            codebase_path: Some(codebase_path),
+            ..self
+        }
     }
 
     /// Find all dead code (symbols with no references)
     pub fn find_dead_code(&self) -> Result<Vec<DeadSymbol>> {
-        use sqlitegraph::{open_graph, snapshot::SnapshotId, GraphConfig};
-
-        let config = GraphConfig::sqlite();
-        let backend = open_graph(self.db_path, &config)
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to open graph: {}", e)))?;
-
-        let snapshot = SnapshotId::current();
-        let mut dead_symbols = Vec::new();
+        self.iter()?.collect()
+    }
 
+    /// Lazily iterate over dead code.
+    ///
+    /// Only the entity ID list is loaded up front; each entity is inspected
+    /// when the iterator is advanced.
+    pub fn iter(&self) -> Result<DeadCodeIter> {
+        let backend = self.open_backend()?;
         let entity_ids = backend
             .entity_ids()
             .map_err(|e| ForgeError::DatabaseError(format!("Failed to list entities: {}", e)))?;
 
-        for id in entity_ids {
-            if let Ok(node) = backend.get_node(snapshot, id) {
-                if !is_function_kind(&node.kind) {
-                    continue;
+        Ok(DeadCodeIter {
+            backend,
+            entity_ids: entity_ids.into_iter(),
+            roots: self.source_roots(),
+        })
+    }
+
+    /// Brings the dead code report up to date after `changed` symbols were
+    /// added, edited or removed.
+    ///
+    /// Only the changed symbols and the symbols they referenced before or
+    /// reference now are re-checked, since no other reference count can
+    /// have moved. A callee whose last caller was edited away is therefore
+    /// reported as newly dead. Pass the IDs from both before and after
+    /// reindexing, as [`changed_symbols`](crate::indexing::IncrementalIndexer::changed_symbols)
+    /// does.
+    ///
+    /// The first call scans the whole graph and reports everything dead as
+    /// newly dead.
+    pub fn update(&self, changed: &[SymbolId]) -> Result<DeadCodeDelta> {
+        let backend = self.open_backend()?;
+        let mut roots = self.source_roots();
+        let mut guard = self
+            .index
+            .lock()
+            .expect("invariant: dead code index mutex not poisoned");
+
+        let Some(index) = guard.as_mut() else {
+            let entity_ids = backend.entity_ids().map_err(|e| {
+                ForgeError::DatabaseError(format!("Failed to list entities: {}", e))
+            })?;
+            let mut index = ReferenceIndex {
+                references: HashMap::new(),
+                dead: BTreeMap::new(),
+            };
+            for id in entity_ids {
+                index.references.insert(
+                    id,
+                    linked_symbols(backend.as_ref(), id, Direction::Outgoing)?,
+                );
+                if let Some(dead) = check_entity(backend.as_ref(), &mut roots, id)? {
+                    index.dead.insert(id, dead);
                 }
+            }
+            let delta = DeadCodeDelta {
+                newly_dead: index.dead.values().cloned().collect(),
+                newly_live: Vec::new(),
+            };
+            *guard = Some(index);
+            return Ok(delta);
+        };
 
-                if is_test_or_entry_point(&node.name) {
-                    continue;
+        let mut affected: HashSet<i64> = HashSet::new();
+        for &SymbolId(id) in changed {
+            affected.insert(id);
+            affected.extend(index.references.remove(&id).unwrap_or_default());
+            if node_exists(backend.as_ref(), id) {
+                let now = linked_symbols(backend.as_ref(), id, Direction::Outgoing)?;
+                affected.extend(now.iter().copied());
+                index.references.insert(id, now);
+                // Unchanged referrers may have been re-pointed at a new ID
+                for referrer in linked_symbols(backend.as_ref(), id, Direction::Incoming)? {
+                    index.references.entry(referrer).or_default().insert(id);
                 }
+            }
+        }
 
-                let incoming = backend
-                    .fetch_incoming(id)
-                    .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?;
-
-                if incoming.is_empty() {
-                    let is_public = node.data.to_string().contains("\"public\":true")
-                        || node.data.to_string().contains("\"visibility\":\"public\"");
-
-                    if !is_public {
-                        dead_symbols.push(DeadSymbol {
-                            id,
-                            kind: node.kind,
-                            name: node.name,
-                            file_path: node.file_path.unwrap_or_default(),
-                            is_public,
-                            reason: "No references found".to_string(),
-                        });
+        let mut affected: Vec<i64> = affected.into_iter().collect();
+        affected.sort_unstable();
+        let mut delta = DeadCodeDelta::default();
+        for id in affected {
+            match check_entity(backend.as_ref(), &mut roots, id)? {
+                Some(dead) => {
+                    if !index.dead.contains_key(&id) {
+                        delta.newly_dead.push(dead.clone());
                     }
+                    index.dead.insert(id, dead);
                 }
+                None => delta.newly_live.extend(index.dead.remove(&id)),
             }
         }
+        Ok(delta)
+    }
 
-        Ok(dead_symbols)
+    fn open_backend(&self) -> Result<Box<dyn sqlitegraph::GraphBackend>> {
+        use sqlitegraph::{open_graph, GraphConfig};
+
+        open_graph(self.db_path, &GraphConfig::sqlite())
+            .map_err(|e| ForgeError::DatabaseError(format!("Failed to open graph: {}", e)))
+    }
+
+    fn source_roots(&self) -> SourceRoots {
+        let codebase_path = self.codebase_path.map(Path::to_path_buf);
+        let reexports = match &codebase_path {
+            Some(root) if self.config.public_roots => crate_root_reexports(root),
+            _ => HashSet::new(),
+        };
+        SourceRoots {
+            config: self.config,
+            codebase_path,
+            reexports,
+            files: HashMap::new(),
+        }
+    }
+}
+
+/// Iterator over dead symbols, produced by [`DeadCodeAnalyzer::iter`].
+pub struct DeadCodeIter {
+    backend: Box<dyn sqlitegraph::GraphBackend>,
+    entity_ids: std::vec::IntoIter<i64>,
+    roots: SourceRoots,
+}
+
+/// Decides which unreferenced symbols the source marks as used.
+struct SourceRoots {
+    config: DeadCodeConfig,
+    codebase_path: Option<PathBuf>,
+    /// Names re-exported with `pub use` from a crate root file
+    reexports: HashSet<String>,
+    /// Declarations per file, read on first use
+    files: HashMap<String, HashMap<String, Vec<Declaration>>>,
+}
+
+impl SourceRoots {
+    fn is_root(&mut self, name: &str, file_path: &str, line: Option<u64>) -> bool {
+        let config = self.config;
+        if !config.is_enabled() {
+            return false;
+        }
+        if config.public_roots && self.reexports.contains(name) {
+            return true;
+        }
+
+        let Some(declaration) = self.declaration(name, file_path, line) else {
+            return false;
+        };
+        (config.public_roots
+            && declaration.visibility.is_exported()
+            && declaration.top_level
+            && is_crate_root(file_path))
+            || (config.trait_impls && declaration.trait_impl)
+            || (config.exported_symbols && declaration.exported)
+            || (config.tests && declaration.test)
+    }
+
+    /// The declaration of `name` in a Rust `file_path`, preferring the
+    /// one on `line`.
+    fn declaration(
+        &mut self,
+        name: &str,
+        file_path: &str,
+        line: Option<u64>,
+    ) -> Option<&Declaration> {
+        if !file_path.ends_with(".rs") {
+            return None;
+        }
+        let codebase_path = &self.codebase_path;
+        let declarations = self.files.entry(file_path.to_string()).or_insert_with(|| {
+            let path = match codebase_path {
+                Some(root) => root.join(file_path),
+                None => PathBuf::from(file_path),
+            };
+            std::fs::read_to_string(path)
+                .map(|source| scan_declarations(&source))
+                .unwrap_or_default()
+        });
+        let candidates = declarations.get(name)?;
+        candidates
+            .iter()
+            .find(|d| Some(d.line as u64) == line)
+            .or_else(|| candidates.first())
+    }
+}
+
+fn check_entity(
+    backend: &dyn sqlitegraph::GraphBackend,
+    roots: &mut SourceRoots,
+    id: i64,
+) -> Result<Option<DeadSymbol>> {
+    use sqlitegraph::snapshot::SnapshotId;
+
+    let Ok(node) = backend.get_node(SnapshotId::current(), id) else {
+        return Ok(None);
+    };
+
+    if !is_function_kind(&node.kind) || is_test_or_entry_point(&node.name) {
+        return Ok(None);
+    }
+
+    let incoming = backend
+        .fetch_incoming(id)
+        .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?;
+    if !incoming.is_empty() {
+        return Ok(None);
+    }
+
+    let is_public = node.data.to_string().contains("\"public\":true")
+        || node.data.to_string().contains("\"visibility\":\"public\"");
+    if is_public {
+        return Ok(None);
+    }
+
+    let file_path = node.file_path.clone().unwrap_or_default();
+    let line = node.data.get("start_line").and_then(|l| l.as_u64());
+    if roots.is_root(&node.name, &file_path, line) {
+        return Ok(None);
+    }
+    let visibility = roots
+        .declaration(&node.name, &file_path, line)
+        .map(|d| d.visibility)
+        .unwrap_or_default();
+
+    Ok(Some(DeadSymbol {
+        id,
+        kind: node.kind,
+        name: node.name,
+        file_path: node.file_path.unwrap_or_default(),
+        is_public,
+        visibility,
+        reason: "No references found".to_string(),
+    }))
+}
+
+fn node_exists(backend: &dyn sqlitegraph::GraphBackend, id: i64) -> bool {
+    use sqlitegraph::snapshot::SnapshotId;
+
+    backend.get_node(SnapshotId::current(), id).is_ok()
+}
+
+enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+/// The symbols `id` references, or that reference it, following edges
+/// through nodes that are not themselves symbols, such as call sites.
+fn linked_symbols(
+    backend: &dyn sqlitegraph::GraphBackend,
+    id: i64,
+    direction: Direction,
+) -> Result<HashSet<i64>> {
+    use sqlitegraph::snapshot::SnapshotId;
+
+    let mut linked = HashSet::new();
+    let mut visited = HashSet::from([id]);
+    let mut pending = vec![id];
+    while let Some(current) = pending.pop() {
+        let neighbors = match direction {
+            Direction::Outgoing => backend.fetch_outgoing(current),
+            Direction::Incoming => backend.fetch_incoming(current),
+        }
+        .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?;
+        for neighbor in neighbors {
+            if !visited.insert(neighbor) {
+                continue;
+            }
+            match backend.get_node(SnapshotId::current(), neighbor) {
+                Ok(node) if is_function_kind(&node.kind) => {
+                    linked.insert(neighbor);
+                }
+                Ok(_) => pending.push(neighbor),
+                Err(_) => {}
+            }
+        }
+    }
+    Ok(linked)
+}
+
+impl Iterator for DeadCodeIter {
+    type Item = Result<DeadSymbol>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let backend = self.backend.as_ref();
+        for id in self.entity_ids.by_ref() {
+            match check_entity(backend, &mut self.roots, id) {
+                Ok(Some(dead)) => return Some(Ok(dead)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // At most one dead symbol per entity not yet inspected
+        (0, Some(self.entity_ids.len()))
     }
 }
 
@@ -79,6 +403,137 @@ fn is_test_or_entry_point(name: &str) -> bool {
         || matches!(name, "main" | "lib" | "init" | "setup" | "teardown")
 }
 
+/// Where and how an item is declared, as far as a line scan can tell.
+#[derive(Clone, Debug, Default)]
+struct Declaration {
+    line: usize,
+    /// Visibility read from the declaration's modifiers
+    visibility: Visibility,
+    /// Not nested in any block
+    top_level: bool,
+    /// Inside an `impl Trait for Type` block
+    trait_impl: bool,
+    /// A `#[test]` function or inside a `#[cfg(test)]` module
+    test: bool,
+    /// Marked `#[no_mangle]` or `#[export_name]`
+    exported: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    TraitImpl,
+    Test,
+}
+
+/// Scans Rust `source` line by line for item declarations, tracking
+/// attributes and the blocks each item is nested in.
+fn scan_declarations(source: &str) -> HashMap<String, Vec<Declaration>> {
+    let mut declarations: HashMap<String, Vec<Declaration>> = HashMap::new();
+    let mut depth = 0usize;
+    let mut scopes: Vec<(usize, Scope)> = Vec::new();
+    // A scope whose opening brace has not been seen yet
+    let mut opening: Option<(usize, Scope)> = None;
+    let mut attributes: Vec<&str> = Vec::new();
+
+    for (line_num, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("#[") {
+            attributes.push(trimmed);
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if let Some((name, kind)) = declaration_from_line(trimmed) {
+            let in_scope = |scope: Scope| scopes.iter().any(|(_, s)| *s == scope);
+            let has_attribute = |needle: &str| attributes.iter().any(|a| a.contains(needle));
+            let visibility = Visibility::from_declaration(trimmed, &name, &Language::Rust);
+            declarations.entry(name).or_default().push(Declaration {
+                line: line_num + 1,
+                visibility,
+                top_level: depth == 0,
+                trait_impl: in_scope(Scope::TraitImpl),
+                test: in_scope(Scope::Test)
+                    || attributes
+                        .iter()
+                        .any(|a| *a == "#[test]" || a.contains("::test")),
+                exported: has_attribute("no_mangle") || has_attribute("export_name"),
+            });
+            if kind == SymbolKind::Impl && trimmed.contains(" for ") {
+                opening = Some((depth, Scope::TraitImpl));
+            } else if kind == SymbolKind::Module && has_attribute("cfg(test)") {
+                opening = Some((depth, Scope::Test));
+            }
+        }
+        attributes.clear();
+
+        for c in trimmed.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        if let Some((at, scope)) = opening {
+            if depth > at {
+                scopes.push((at, scope));
+                opening = None;
+            } else if trimmed.ends_with(';') || trimmed.ends_with('}') {
+                opening = None;
+            }
+        }
+        scopes.retain(|(at, _)| depth > *at);
+    }
+    declarations
+}
+
+/// Returns true for `lib.rs` and `main.rs`.
+fn is_crate_root(file_path: &str) -> bool {
+    matches!(
+        Path::new(file_path).file_name().and_then(|n| n.to_str()),
+        Some("lib.rs" | "main.rs")
+    )
+}
+
+/// Names re-exported with `pub use` or `pub(crate) use` from the crate
+/// root files under `codebase_path`. Glob re-exports are not expanded.
+fn crate_root_reexports(codebase_path: &Path) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for root in ["src/lib.rs", "src/main.rs", "lib.rs", "main.rs"] {
+        let Ok(source) = std::fs::read_to_string(codebase_path.join(root)) else {
+            continue;
+        };
+        let mut statement = String::new();
+        for line in source.lines() {
+            let trimmed = line.trim();
+            if statement.is_empty() {
+                match trimmed
+                    .strip_prefix("pub use ")
+                    .or_else(|| trimmed.strip_prefix("pub(crate) use "))
+                {
+                    Some(rest) => statement.push_str(rest),
+                    None => continue,
+                }
+            } else {
+                statement.push(' ');
+                statement.push_str(trimmed);
+            }
+            if let Some((body, _)) = statement.split_once(';') {
+                for item in body.split([',', '{', '}']) {
+                    let item = item.split(" as ").next().unwrap_or_default().trim();
+                    let name = item.rsplit("::").next().unwrap_or(item);
+                    if !name.is_empty() && name != "*" && name != "self" {
+                        names.insert(name.to_string());
+                    }
+                }
+                statement.clear();
+            }
+        }
+    }
+    names
+}
+
 /// Dead symbol information
 #[derive(Debug, Clone)]
 pub struct DeadSymbol {
@@ -87,6 +542,8 @@ pub struct DeadSymbol {
     pub name: String,
     pub file_path: String,
     pub is_public: bool,
+    /// Visibility from the declaration, for Rust sources that could be read
+    pub visibility: Visibility,
     pub reason: String,
 }
 
@@ -107,6 +564,8 @@ impl From<DeadSymbol> for Symbol {
                 line_number: 0,
             },
             parent_id: None,
+            visibility: dead.visibility,
+            body_hash: None,
             metadata: serde_json::json!({
                 "dead_code": true,
                 "reason": dead.reason,
@@ -145,4 +604,136 @@ mod tests {
         // Just verify it creates without error
         assert!(!analyzer.db_path.exists()); // DB doesn't exist yet
     }
+
+    #[test]
+    fn test_scan_declarations_tracks_context() {
+        let source = "\
+pub fn api() {}
+pub(crate) mod inner {
+    pub fn nested() {}
+}
+impl Display for Thing {
+    fn fmt(&self) {}
+}
+impl Thing
+{
+    fn own(&self) {}
+}
+#[no_mangle]
+extern \"C\" fn hook() {}
+#[test]
+fn checks() {}
+#[cfg(test)]
+mod tests {
+    fn helper() {}
+}
+fn after() {}
+";
+        let decls = scan_declarations(source);
+        let get = |name: &str| decls.get(name).unwrap()[0].clone();
+
+        assert!(get("api").visibility == Visibility::Public && get("api").top_level);
+        assert_eq!(get("inner").visibility, Visibility::Crate);
+        assert!(get("nested").visibility == Visibility::Public && !get("nested").top_level);
+        assert_eq!(get("fmt").visibility, Visibility::Private);
+        assert!(get("fmt").trait_impl);
+        assert!(!get("own").trait_impl);
+        assert!(get("hook").exported);
+        assert!(get("checks").test);
+        assert!(get("helper").test);
+        let after = get("after");
+        assert!(after.top_level && !after.test && !after.trait_impl && !after.exported);
+        assert_eq!(after.line, 20);
+    }
+
+    #[test]
+    fn test_crate_root_reexports() {
+        let temp = tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(
+            temp.path().join("src/lib.rs"),
+            "pub use api::single;\npub use api::{\n    first,\n    nested::second as renamed,\n};\npub use glob::*;\nuse private::hidden;\n",
+        )
+        .unwrap();
+
+        let names = crate_root_reexports(temp.path());
+        let mut names: Vec<_> = names.into_iter().collect();
+        names.sort();
+        assert_eq!(names, vec!["first", "second", "single"]);
+    }
+
+    fn dead_ids(analyzer: &DeadCodeAnalyzer) -> Vec<i64> {
+        analyzer
+            .find_dead_code()
+            .unwrap()
+            .iter()
+            .map(|d| d.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_update_matches_full_recompute() {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+
+        let temp = tempdir().unwrap();
+        let db_path = temp.path().join("dead.db");
+        let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+        let node = |name: &str| {
+            backend
+                .insert_node(NodeSpec {
+                    kind: "fn".to_string(),
+                    name: name.to_string(),
+                    file_path: Some("src/lib.rs".to_string()),
+                    data: serde_json::Value::Null,
+                })
+                .unwrap()
+        };
+        let call = |from: i64, to: i64| {
+            backend
+                .insert_edge(EdgeSpec {
+                    from,
+                    to,
+                    edge_type: "CALLS".to_string(),
+                    data: serde_json::Value::Null,
+                })
+                .unwrap();
+        };
+        let main = node("main");
+        let caller = node("caller");
+        let x = node("x");
+        let y = node("y");
+        let z = node("z");
+        call(main, caller);
+        call(caller, x);
+
+        let analyzer = DeadCodeAnalyzer::new(&db_path);
+        let baseline = analyzer.update(&[]).unwrap();
+        let ids = |dead: &[DeadSymbol]| dead.iter().map(|d| d.id).collect::<Vec<_>>();
+        assert_eq!(ids(&baseline.newly_dead), vec![y, z]);
+        assert_eq!(ids(&baseline.newly_dead), dead_ids(&analyzer));
+
+        // Nothing changed
+        let unchanged = analyzer.update(&[]).unwrap();
+        assert!(unchanged.newly_dead.is_empty() && unchanged.newly_live.is_empty());
+
+        // `caller` is reindexed under a new id and now calls `y` instead of
+        // `x`, leaving `x` without references although it was not edited
+        backend.delete_entity(caller).unwrap();
+        let new_caller = node("caller");
+        call(main, new_caller);
+        call(new_caller, y);
+        let delta = analyzer
+            .update(&[SymbolId(caller), SymbolId(new_caller)])
+            .unwrap();
+        assert_eq!(ids(&delta.newly_dead), vec![x]);
+        assert_eq!(ids(&delta.newly_live), vec![y]);
+        assert_eq!(dead_ids(&analyzer), vec![x, z]);
+
+        // Removing the only caller of `new_caller` cascades one level
+        backend.delete_entity(main).unwrap();
+        let delta = analyzer.update(&[SymbolId(main)]).unwrap();
+        assert_eq!(ids(&delta.newly_dead), vec![new_caller]);
+        assert!(delta.newly_live.is_empty());
+        assert_eq!(dead_ids(&analyzer), vec![x, z, new_caller]);
+    }
 }
@@ -0,0 +1,144 @@
+//! Technical-debt marker scanning
+//!
+//! Finds `TODO`/`FIXME`/`HACK`/`XXX` comments and ties each one to the
+//! function whose span encloses it.
+
+use crate::treesitter::CfgExtractor;
+use crate::types::Location;
+use regex::Regex;
+use std::path::Path;
+
+/// Kind of technical-debt marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DebtKind {
+    Todo,
+    Fixme,
+    Hack,
+    Xxx,
+}
+
+impl DebtKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DebtKind::Todo => "TODO",
+            DebtKind::Fixme => "FIXME",
+            DebtKind::Hack => "HACK",
+            DebtKind::Xxx => "XXX",
+        }
+    }
+
+    fn parse(tag: &str) -> Option<Self> {
+        match tag {
+            "TODO" => Some(DebtKind::Todo),
+            "FIXME" => Some(DebtKind::Fixme),
+            "HACK" => Some(DebtKind::Hack),
+            "XXX" => Some(DebtKind::Xxx),
+            _ => None,
+        }
+    }
+}
+
+/// A debt marker found in a comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebtMarker {
+    /// Marker kind
+    pub kind: DebtKind,
+    /// Text following the marker (e.g. `fix this` for `// TODO: fix this`)
+    pub text: String,
+    /// Name of the enclosing function, if the marker is inside one
+    pub symbol: Option<String>,
+    /// Location of the marker itself
+    pub location: Location,
+}
+
+fn marker_regex() -> Regex {
+    Regex::new(r"(?://+|/\*+|#|\*)\s*(TODO|FIXME|HACK|XXX)\b[:\s]*(.*)")
+        .expect("invariant: static regex pattern is valid")
+}
+
+/// Scans one source file for debt markers.
+///
+/// `file_path` is recorded as-is in each marker's location. Enclosing
+/// symbols are resolved from tree-sitter function spans when the language
+/// is supported; otherwise `symbol` is `None`.
+pub fn scan_source(source: &str, file_path: &Path) -> Vec<DebtMarker> {
+    let regex = marker_regex();
+
+    let functions = CfgExtractor::detect_language(file_path)
+        .and_then(|lang| CfgExtractor::extract(source, lang).ok())
+        .unwrap_or_default();
+
+    let mut markers = Vec::new();
+    let mut line_start = 0usize;
+    for (line_idx, line) in source.split_inclusive('\n').enumerate() {
+        if let Some(caps) = regex.captures(line) {
+            let tag = caps.get(1).expect("invariant: group 1 always matches");
+            if let Some(kind) = DebtKind::parse(tag.as_str()) {
+                let byte_start = line_start + tag.start();
+                let text = caps
+                    .get(2)
+                    .map(|m| m.as_str().trim().trim_end_matches("*/").trim())
+                    .unwrap_or_default()
+                    .to_string();
+
+                // Innermost enclosing function wins
+                let symbol = functions
+                    .iter()
+                    .filter(|f| f.start_byte <= byte_start && byte_start < f.end_byte)
+                    .min_by_key(|f| f.end_byte - f.start_byte)
+                    .map(|f| f.name.clone());
+
+                markers.push(DebtMarker {
+                    kind,
+                    text,
+                    symbol,
+                    location: Location {
+                        file_path: file_path.to_path_buf(),
+                        byte_start: byte_start as u32,
+                        byte_end: (line_start + line.trim_end().len()) as u32,
+                        line_number: line_idx + 1,
+                    },
+                });
+            }
+        }
+        line_start += line.len();
+    }
+
+    markers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_source_marker_inside_function() {
+        let source = "fn outer() {}\n\nfn work() {\n    // TODO: fix this\n    let x = 1;\n}\n";
+        let markers = scan_source(source, Path::new("src/lib.rs"));
+
+        assert_eq!(markers.len(), 1);
+        let marker = &markers[0];
+        assert_eq!(marker.kind, DebtKind::Todo);
+        assert_eq!(marker.text, "fix this");
+        assert_eq!(marker.symbol.as_deref(), Some("work"));
+        assert_eq!(marker.location.line_number, 4);
+        assert_eq!(&source[marker.location.byte_start as usize..][..4], "TODO");
+    }
+
+    #[test]
+    fn test_scan_source_all_kinds_and_top_level() {
+        let source = "// FIXME top level\n/* HACK: block */\n# XXX python style\n";
+        let markers = scan_source(source, Path::new("notes.txt"));
+
+        let kinds: Vec<DebtKind> = markers.iter().map(|m| m.kind).collect();
+        assert_eq!(kinds, vec![DebtKind::Fixme, DebtKind::Hack, DebtKind::Xxx]);
+        assert_eq!(markers[1].text, "block");
+        assert!(markers.iter().all(|m| m.symbol.is_none()));
+    }
+
+    #[test]
+    fn test_scan_source_ignores_identifiers() {
+        let source = "fn todo_list() { let TODO_COUNT = 1; }\n";
+        assert!(scan_source(source, Path::new("src/lib.rs")).is_empty());
+    }
+}
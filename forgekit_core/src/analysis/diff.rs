@@ -1,54 +1,6 @@
 use super::{AnalysisModule, ApplyResult, Result};
-
-#[derive(Debug, Clone)]
-pub struct Diff {
-    /// Original content
-    pub original: String,
-    /// New content
-    pub new: String,
-    /// Changed lines
-    pub changed_lines: Vec<usize>,
-}
-
-impl Diff {
-    /// Create a new diff.
-    pub fn new(original: String, new: String) -> Self {
-        let changed_lines = compute_changed_lines(&original, &new);
-        Self {
-            original,
-            new,
-            changed_lines,
-        }
-    }
-
-    /// Returns true if there are any changes.
-    pub fn has_changes(&self) -> bool {
-        !self.changed_lines.is_empty()
-    }
-}
-
-/// Compute which lines changed between two strings.
-fn compute_changed_lines(original: &str, new: &str) -> Vec<usize> {
-    let orig_lines: Vec<&str> = original.lines().collect();
-    let new_lines: Vec<&str> = new.lines().collect();
-
-    let mut changed = Vec::new();
-
-    for (i, (o, n)) in orig_lines.iter().zip(new_lines.iter()).enumerate() {
-        if o != n {
-            changed.push(i);
-        }
-    }
-
-    // Handle lines added at the end
-    if new_lines.len() > orig_lines.len() {
-        for i in orig_lines.len()..new_lines.len() {
-            changed.push(i);
-        }
-    }
-
-    changed
-}
+use crate::types::Diff;
+use std::path::PathBuf;
 
 /// Edit operation trait for code transformations.
 #[async_trait::async_trait]
@@ -93,6 +45,7 @@ impl EditOperation for InsertOperation {
 
         if symbols.is_empty() {
             return Ok(Diff::new(
+                PathBuf::new(),
                 String::from(""),
                 format!(
                     "// Would insert after: {}\n{}",
@@ -104,7 +57,11 @@ impl EditOperation for InsertOperation {
         let original = format!("// Original content at {}\n", self.after_symbol);
         let new_content = format!("{}\n// Inserted content\n{}", original, self.content);
 
-        Ok(Diff::new(original, new_content))
+        Ok(Diff::new(
+            symbols[0].location.file_path.clone(),
+            original,
+            new_content,
+        ))
     }
 
     async fn apply(&self, module: &mut AnalysisModule) -> Result<ApplyResult> {
@@ -185,7 +142,7 @@ impl EditOperation for DeleteOperation {
         );
         let new_content = String::from("// Symbol deleted\n");
 
-        Ok(Diff::new(original, new_content))
+        Ok(Diff::new(PathBuf::new(), original, new_content))
     }
 
     async fn apply(&self, _module: &mut AnalysisModule) -> Result<ApplyResult> {
@@ -278,7 +235,7 @@ impl EditOperation for RenameOperation {
         let original = format!("fn {}()", self.old_name);
         let new_content = format!("fn {}()", self.new_name);
 
-        Ok(Diff::new(original, new_content))
+        Ok(Diff::new(PathBuf::new(), original, new_content))
     }
 
     async fn apply(&self, module: &mut AnalysisModule) -> Result<ApplyResult> {
@@ -319,6 +276,7 @@ impl EditOperation for ErrorResult {
 
     async fn preview(&self, _module: &AnalysisModule) -> Result<Diff> {
         Ok(Diff::new(
+            PathBuf::new(),
             format!("// Error: {}", self.reason),
             format!("// Error: {}", self.reason),
         ))
@@ -382,7 +340,7 @@ mod tests {
         };
 
         let diff = insert.preview(&analysis).await.unwrap();
-        assert!(!diff.new.is_empty());
+        assert!(!diff.modified.is_empty());
     }
 
     #[tokio::test]
@@ -426,7 +384,7 @@ mod tests {
         };
 
         let diff = delete.preview(&analysis).await.unwrap();
-        assert!(diff.new.contains("deleted"));
+        assert!(diff.modified.contains("deleted"));
     }
 
     #[tokio::test]
@@ -488,20 +446,24 @@ mod tests {
 
     #[test]
     fn test_diff_creation() {
-        let diff = Diff::new("original content".to_string(), "new content".to_string());
+        let diff = Diff::new(
+            PathBuf::new(),
+            "original content".to_string(),
+            "new content".to_string(),
+        );
         assert_eq!(diff.original, "original content");
-        assert_eq!(diff.new, "new content");
+        assert_eq!(diff.modified, "new content");
     }
 
     #[test]
     fn test_diff_has_changes() {
-        let diff = Diff::new("a".to_string(), "b".to_string());
+        let diff = Diff::new(PathBuf::new(), "a".to_string(), "b".to_string());
         assert!(diff.has_changes());
     }
 
     #[test]
     fn test_diff_no_changes() {
-        let diff = Diff::new("same".to_string(), "same".to_string());
+        let diff = Diff::new(PathBuf::new(), "same".to_string(), "same".to_string());
         assert!(!diff.has_changes());
     }
 
@@ -3,7 +3,9 @@
 //! Extracted from `mod.rs` (SPLIT-27). Pure data structs consumed by
 //! `AnalysisModule` impact/cross-reference/call-chain methods.
 
+use crate::graph::ImpactedSymbol;
 use crate::types::Symbol;
+use std::collections::HashMap;
 
 /// Detailed impact analysis result for a symbol.
 #[derive(Debug, Clone)]
@@ -20,6 +22,45 @@ pub struct ImpactData {
     pub references: Vec<Symbol>,
     /// Total estimated impact score
     pub impact_score: usize,
+    /// Transitive impact: each impacted symbol weighted by `1 / (hop + 1)`
+    pub weighted_score: f64,
+    /// Number of impacted symbols at each hop, starting at hop 1
+    pub per_hop_counts: Vec<usize>,
+}
+
+/// Direct impact counts for a symbol, read from the reference index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactCounts {
+    /// Symbol that was analyzed
+    pub symbol: String,
+    /// Number of references to this symbol
+    pub ref_count: usize,
+    /// Number of call sites (for functions)
+    pub call_count: usize,
+    /// `ref_count + 2 * call_count`, as in [`ImpactData::impact_score`]
+    pub impact_score: usize,
+}
+
+/// Computes the weighted score and per-hop counts of `impacted`.
+///
+/// A symbol listed more than once counts only at its shortest hop.
+pub(crate) fn weigh_impact(impacted: &[ImpactedSymbol]) -> (f64, Vec<usize>) {
+    let mut shortest: HashMap<i64, u32> = HashMap::new();
+    for symbol in impacted.iter().filter(|s| s.hop_distance > 0) {
+        shortest
+            .entry(symbol.symbol_id)
+            .and_modify(|hop| *hop = (*hop).min(symbol.hop_distance))
+            .or_insert(symbol.hop_distance);
+    }
+
+    let max_hop = shortest.values().copied().max().unwrap_or(0) as usize;
+    let mut per_hop_counts = vec![0; max_hop];
+    let mut weighted_score = 0.0;
+    for &hop in shortest.values() {
+        per_hop_counts[hop as usize - 1] += 1;
+        weighted_score += 1.0 / (hop as f64 + 1.0);
+    }
+    (weighted_score, per_hop_counts)
 }
 
 /// Impact analysis result.
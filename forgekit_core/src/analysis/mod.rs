@@ -7,23 +7,40 @@ use crate::edit::EditModule;
 use crate::error::Result;
 use crate::graph::GraphModule;
 use crate::search::SearchModule;
-use crate::types::Symbol;
+use crate::types::{Symbol, Visibility};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
 pub mod complexity;
+pub mod coupling;
 pub mod dead_code;
+pub mod debt;
 pub mod diff;
 pub mod impact;
 pub mod modules;
+pub mod ref_index;
+pub mod sarif;
 
+/// Traversal depth used for [`AnalysisModule::impact_analysis`]'s weighted score.
+const IMPACT_SCORE_HOPS: u32 = 3;
+
+/// Where the dead code scan thread sends the next symbol, or `None` once
+/// the scan is done.
+type DeadCodeReply = tokio::sync::oneshot::Sender<Option<Result<DeadSymbol>>>;
+
+pub use crate::types::Diff;
 pub use complexity::{ComplexityMetrics, RiskLevel};
-pub use dead_code::{DeadCodeAnalyzer, DeadSymbol};
-pub use diff::{
-    DeleteOperation, Diff, EditOperation, ErrorResult, InsertOperation, RenameOperation,
+pub use coupling::CouplingMatrix;
+pub use dead_code::{DeadCodeAnalyzer, DeadCodeConfig, DeadCodeDelta, DeadCodeIter, DeadSymbol};
+pub use debt::{DebtKind, DebtMarker};
+pub use diff::{DeleteOperation, EditOperation, ErrorResult, InsertOperation, RenameOperation};
+pub use impact::{
+    CallChain, CrossReferences, ImpactAnalysis, ImpactCounts, ImpactData, ReferenceChain,
 };
-pub use impact::{CallChain, CrossReferences, ImpactAnalysis, ImpactData, ReferenceChain};
-pub use modules::{ModuleAnalyzer, ModuleDependencyGraph, ModuleInfo};
+pub use modules::{LayerRule, LayerViolation, ModuleAnalyzer, ModuleDependencyGraph, ModuleInfo};
+pub use ref_index::ReferenceIndex;
+pub use sarif::AnalysisFindings;
 
 /// Analysis module for combined operations.
 pub struct AnalysisModule {
@@ -62,7 +79,9 @@ pub enum ApplyResult {
 }
 
 /// Module dependency.
-#[derive(Debug, Clone)]
+///
+/// Ordered by `from`, then `to`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ModuleDependency {
     /// Source module
     pub from: String,
@@ -104,14 +123,31 @@ impl AnalysisModule {
     /// Analyze the impact of changing a symbol.
     ///
     /// Returns detailed impact data including references, calls, and impact score.
+    /// `weighted_score` follows callers up to three hops away, weighting each
+    /// by its shortest distance; an error from that traversal is returned
+    /// rather than scored as zero.
     pub async fn impact_analysis(&self, symbol: &str) -> Result<ImpactData> {
         let start = Instant::now();
 
+        // The index rules out symbols nothing calls or references without
+        // querying them one by one
+        let index = self.reference_index().ok();
+        let has_calls = index.as_ref().is_none_or(|i| i.calls(symbol) > 0);
+        let has_refs = index.as_ref().is_none_or(|i| i.refs(symbol) > 0);
+
         // Get all callers
-        let callers = self.graph.callers_of(symbol).await.unwrap_or_default();
+        let callers = if has_calls {
+            self.graph.callers_of(symbol).await.unwrap_or_default()
+        } else {
+            Vec::new()
+        };
 
         // Get all references
-        let refs = self.graph.references(symbol).await.unwrap_or_default();
+        let refs = if has_refs {
+            self.graph.references(symbol).await.unwrap_or_default()
+        } else {
+            Vec::new()
+        };
 
         // Also search for the symbol to get its metadata
         let _symbol_info = self
@@ -127,6 +163,12 @@ impl AnalysisModule {
         let call_count = callers.len();
         let impact_score = ref_count + call_count * 2; // Calls weigh more
 
+        let impacted = self
+            .graph
+            .impact_analysis(symbol, Some(IMPACT_SCORE_HOPS))
+            .await?;
+        let (weighted_score, per_hop_counts) = impact::weigh_impact(&impacted);
+
         tracing::debug!(
             "Impact analysis for '{}' completed in {:?}",
             symbol,
@@ -149,6 +191,8 @@ impl AnalysisModule {
                         language: crate::types::Language::Unknown("unknown".to_string()),
                         location: r.location,
                         parent_id: None,
+                        visibility: Visibility::Unknown,
+                        body_hash: None,
                         metadata: serde_json::Value::Null,
                     }
                 })
@@ -165,14 +209,50 @@ impl AnalysisModule {
                         language: crate::types::Language::Unknown("unknown".to_string()),
                         location: r.location,
                         parent_id: None,
+                        visibility: Visibility::Unknown,
+                        body_hash: None,
                         metadata: serde_json::Value::Null,
                     }
                 })
                 .collect(),
             impact_score,
+            weighted_score,
+            per_hop_counts,
         })
     }
 
+    /// Count the direct callers and references of many symbols at once.
+    ///
+    /// Reads the store's [`ReferenceIndex`], so the graph is scanned at
+    /// most once however many symbols are given, and not at all if the
+    /// index is already built. Results are in the order of `symbols`.
+    pub async fn impact_analysis_bulk(&self, symbols: &[&str]) -> Result<Vec<ImpactCounts>> {
+        let index = self.reference_index()?;
+        Ok(symbols
+            .iter()
+            .map(|symbol| {
+                let ref_count = index.refs(symbol);
+                let call_count = index.calls(symbol);
+                ImpactCounts {
+                    symbol: symbol.to_string(),
+                    ref_count,
+                    call_count,
+                    impact_score: ref_count + call_count * 2,
+                }
+            })
+            .collect())
+    }
+
+    /// Get the call and reference counts of every symbol.
+    ///
+    /// Built in one pass over the graph on first use and shared by every
+    /// module on the same store until the codebase is re-indexed or the
+    /// incremental indexer flushes a change.
+    pub fn reference_index(&self) -> Result<Arc<ReferenceIndex>> {
+        let store = self.graph.store();
+        store.ref_index.get_or_build(store.db_path())
+    }
+
     /// Analyze the impact of changing a symbol.
     ///
     /// Returns all symbols that would be affected by modifying the given symbol.
@@ -188,9 +268,88 @@ impl AnalysisModule {
     ///
     /// Returns symbols that are defined but never called/referenced.
     pub async fn dead_code_detection(&self) -> Result<Vec<Symbol>> {
+        self.collect_dead_code(None, DeadCodeConfig::default())
+            .await
+    }
+
+    /// Find dead code, treating the symbols selected by `cfg` as used.
+    ///
+    /// Use [`DeadCodeConfig::all`] to keep public API items, trait method
+    /// implementations, exported symbols and tests out of the report.
+    pub async fn dead_code_detection_with_config(
+        &self,
+        cfg: DeadCodeConfig,
+    ) -> Result<Vec<Symbol>> {
+        self.collect_dead_code(None, cfg).await
+    }
+
+    /// Find dead code, stopping after `limit` symbols.
+    ///
+    /// Useful on large codebases where the full report is too big to
+    /// consume at once.
+    pub async fn dead_code_detection_limited(&self, limit: usize) -> Result<Vec<Symbol>> {
+        self.collect_dead_code(Some(limit), DeadCodeConfig::default())
+            .await
+    }
+
+    /// Stream dead code one symbol at a time.
+    ///
+    /// Entities are inspected as the stream is polled, so callers can stop
+    /// early without paying for the whole scan. The scan runs on its own
+    /// thread, which inspects one more entity for each poll, so SQLite is
+    /// never queried on the async runtime. Yields nothing if the graph
+    /// database does not exist.
+    pub fn stream_dead_code(&self) -> impl futures::Stream<Item = Result<DeadSymbol>> {
+        let db_path = self.graph.store().db_path().to_path_buf();
+        let (requests, pending) = std::sync::mpsc::channel::<DeadCodeReply>();
+        let spawned = std::thread::Builder::new()
+            .name("forge-dead-code".to_string())
+            .spawn(move || {
+                let mut items: Box<dyn Iterator<Item = Result<DeadSymbol>>> = if !db_path.exists() {
+                    Box::new(std::iter::empty())
+                } else {
+                    match dead_code::DeadCodeAnalyzer::new(&db_path).iter() {
+                        Ok(iter) => Box::new(iter),
+                        Err(e) => Box::new(std::iter::once(Err(e))),
+                    }
+                };
+                // Ends once the stream is dropped
+                for reply in pending {
+                    let _ = reply.send(items.next());
+                }
+            });
+
+        let failed = spawned.err().map(|e| {
+            crate::error::ForgeError::DatabaseError(format!(
+                "Failed to start dead code scan: {}",
+                e
+            ))
+        });
+        futures::stream::unfold((Some(requests), failed), |(requests, failed)| async move {
+            if let Some(e) = failed {
+                return Some((Err(e), (None, None)));
+            }
+            let requests = requests?;
+            let (reply, item) = tokio::sync::oneshot::channel();
+            requests.send(reply).ok()?;
+            let item = item.await.ok()??;
+            Some((item, (Some(requests), None)))
+        })
+    }
+
+    #[tracing::instrument(
+        name = "dead_code_detection",
+        skip(self, config),
+        fields(symbols = tracing::field::Empty, elapsed_ms = tracing::field::Empty)
+    )]
+    async fn collect_dead_code(
+        &self,
+        limit: Option<usize>,
+        config: DeadCodeConfig,
+    ) -> Result<Vec<Symbol>> {
         let start = Instant::now();
 
-        let db_path = self.graph.store().db_path().join("graph.db");
+        let db_path = self.graph.store().db_path();
 
         // Check if database exists first
         if !db_path.exists() {
@@ -201,11 +360,19 @@ impl AnalysisModule {
             return Ok(Vec::new());
         }
 
-        let analyzer = dead_code::DeadCodeAnalyzer::new(&db_path);
+        let analyzer = dead_code::DeadCodeAnalyzer::new(db_path)
+            .with_config(config, &self.graph.store().codebase_path);
+
+        let found: Result<Vec<DeadSymbol>> = analyzer
+            .iter()
+            .and_then(|iter| iter.take(limit.unwrap_or(usize::MAX)).collect());
 
-        match analyzer.find_dead_code() {
+        match found {
             Ok(dead_symbols) => {
                 let result: Vec<Symbol> = dead_symbols.into_iter().map(Into::into).collect();
+                let span = tracing::Span::current();
+                span.record("symbols", result.len());
+                span.record("elapsed_ms", start.elapsed().as_millis() as u64);
                 tracing::debug!(
                     "Dead code detection found {} symbols in {:?}",
                     result.len(),
@@ -239,30 +406,15 @@ impl AnalysisModule {
         self.dead_code_detection().await
     }
 
-    /// Trace the reference chain from a symbol.
+    /// Trace the reference chain into a symbol.
     ///
-    /// Returns an ordered list showing how symbols reference each other.
-    pub async fn reference_chain(&self, symbol: &str) -> Result<Vec<Symbol>> {
+    /// Returns the symbols that reference `symbol`, then the symbols that
+    /// reference those, and so on up to `max_depth` hops, nearest first.
+    /// Each symbol appears once, so reference cycles terminate.
+    pub async fn reference_chain(&self, symbol: &str, max_depth: usize) -> Result<Vec<Symbol>> {
         let start = Instant::now();
 
-        let refs = self.graph.references(symbol).await?;
-
-        let chain: Vec<Symbol> = refs
-            .into_iter()
-            .map(|r| {
-                let name: Arc<str> = Arc::from(r.to_name.unwrap_or_default());
-                Symbol {
-                    id: r.from,
-                    name: name.clone(),
-                    fully_qualified_name: name,
-                    kind: crate::types::SymbolKind::Function,
-                    language: crate::types::Language::Unknown("unknown".to_string()),
-                    location: r.location,
-                    parent_id: None,
-                    metadata: serde_json::Value::Null,
-                }
-            })
-            .collect();
+        let chain = self.graph.referrer_chain(symbol, max_depth, false)?;
 
         tracing::debug!(
             "Reference chain for '{}' has {} symbols, found in {:?}",
@@ -275,28 +427,13 @@ impl AnalysisModule {
 
     /// Trace all callers to a function.
     ///
-    /// Returns an ordered list of calling symbols.
-    pub async fn call_chain(&self, symbol: &str) -> Result<Vec<Symbol>> {
+    /// Returns the direct callers of `symbol`, then their callers, and so
+    /// on up to `max_depth` hops, nearest first. Each caller appears once,
+    /// so recursion terminates.
+    pub async fn call_chain(&self, symbol: &str, max_depth: usize) -> Result<Vec<Symbol>> {
         let start = Instant::now();
 
-        let callers = self.graph.callers_of(symbol).await?;
-
-        let chain: Vec<Symbol> = callers
-            .into_iter()
-            .map(|r| {
-                let name: Arc<str> = Arc::from(r.from_name.unwrap_or_default());
-                Symbol {
-                    id: r.from,
-                    name: name.clone(),
-                    fully_qualified_name: name,
-                    kind: crate::types::SymbolKind::Function,
-                    language: crate::types::Language::Unknown("unknown".to_string()),
-                    location: r.location,
-                    parent_id: None,
-                    metadata: serde_json::Value::Null,
-                }
-            })
-            .collect();
+        let chain = self.graph.referrer_chain(symbol, max_depth, true)?;
 
         tracing::debug!(
             "Call chain for '{}' has {} symbols, found in {:?}",
@@ -311,26 +448,41 @@ impl AnalysisModule {
     ///
     /// Returns timing data for each operation type.
     pub async fn benchmarks(&self) -> Result<BenchmarkResults> {
+        self.benchmarks_cancellable(&AtomicBool::new(false)).await
+    }
+
+    /// Runs [`benchmarks`](Self::benchmarks), returning
+    /// [`ForgeError::Cancelled`](crate::error::ForgeError::Cancelled) before
+    /// the next operation once `cancel` is set.
+    pub async fn benchmarks_cancellable(&self, cancel: &AtomicBool) -> Result<BenchmarkResults> {
+        let check = || match cancel.load(Ordering::Relaxed) {
+            true => Err(crate::error::ForgeError::Cancelled),
+            false => Ok(()),
+        };
         let total_start = Instant::now();
 
         // Benchmark impact analysis
+        check()?;
         let impact_start = Instant::now();
         let _ = self.impact_analysis("test_symbol").await;
         let impact_analysis_ms = impact_start.elapsed().as_secs_f64() * 1000.0;
 
         // Benchmark dead code detection
+        check()?;
         let dead_start = Instant::now();
         let _ = self.dead_code_detection().await;
         let dead_code_ms = dead_start.elapsed().as_secs_f64() * 1000.0;
 
         // Benchmark reference chain
+        check()?;
         let ref_start = Instant::now();
-        let _ = self.reference_chain("test_symbol").await;
+        let _ = self.reference_chain("test_symbol", 3).await;
         let reference_chain_ms = ref_start.elapsed().as_secs_f64() * 1000.0;
 
         // Benchmark call chain
+        check()?;
         let call_start = Instant::now();
-        let _ = self.call_chain("test_symbol").await;
+        let _ = self.call_chain("test_symbol", 3).await;
         let call_chain_ms = call_start.elapsed().as_secs_f64() * 1000.0;
 
         let total_ms = total_start.elapsed().as_secs_f64() * 1000.0;
@@ -346,9 +498,10 @@ impl AnalysisModule {
 
     /// Calculate complexity metrics for a function.
     ///
-    /// Looks up the symbol's source code and analyzes it for complexity.
+    /// Uses the function's CFG when one can be built, so cyclomatic
+    /// complexity is `E - N + 2` over its blocks. Falls back to a keyword
+    /// scan of the function's source when no CFG is available.
     pub async fn complexity_metrics(&self, symbol_name: &str) -> Result<ComplexityMetrics> {
-        // Try to find the symbol's source and analyze it
         let symbols = self
             .graph
             .find_symbol(symbol_name)
@@ -360,14 +513,25 @@ impl AnalysisModule {
                 .store()
                 .codebase_path
                 .join(&sym.location.file_path);
-            if let Ok(content) = tokio::fs::read_to_string(&full_path).await {
-                // Extract the function body from byte span
-                let start = sym.location.byte_start as usize;
-                let end = sym.location.byte_end as usize;
-                if end <= content.len() {
-                    let source = &content[start..end];
-                    return Ok(self.analyze_source_complexity(source));
-                }
+            // Extract the function body from byte span
+            let source = tokio::fs::read_to_string(&full_path)
+                .await
+                .ok()
+                .and_then(|content| {
+                    let start = sym.location.byte_start as usize;
+                    let end = sym.location.byte_end as usize;
+                    content
+                        .get(start..end)
+                        .filter(|body| !body.is_empty())
+                        .map(str::to_string)
+                });
+
+            if let Some(cfg) = crate::cfg::function_cfg(self.graph.store(), sym.id)? {
+                let lines_of_code = source.as_deref().map_or(1, |s| s.lines().count());
+                return Ok(ComplexityMetrics::from_cfg(&cfg, lines_of_code));
+            }
+            if let Some(source) = source {
+                return Ok(self.analyze_source_complexity(&source));
             }
         }
 
@@ -410,6 +574,8 @@ impl AnalysisModule {
                     language: crate::types::Language::Unknown(String::new()),
                     location: r.location.clone(),
                     parent_id: None,
+                    visibility: Visibility::Unknown,
+                    body_hash: None,
                     metadata: serde_json::Value::Null,
                 }
             })
@@ -427,6 +593,8 @@ impl AnalysisModule {
                     language: crate::types::Language::Unknown(String::new()),
                     location: r.location.clone(),
                     parent_id: None,
+                    visibility: Visibility::Unknown,
+                    body_hash: None,
                     metadata: serde_json::Value::Null,
                 }
             })
@@ -435,9 +603,47 @@ impl AnalysisModule {
         Ok(CrossReferences { callers, callees })
     }
 
+    /// Find technical-debt markers (`TODO`, `FIXME`, `HACK`, `XXX`) in comments.
+    ///
+    /// Each marker is associated with its enclosing function where one exists.
+    /// Results are ordered by file path, then line.
+    pub async fn debt_markers(&self) -> Result<Vec<DebtMarker>> {
+        let codebase_path = &self.graph.store().codebase_path;
+
+        let mut files = Vec::new();
+        crate::search::collect_source_files(
+            codebase_path,
+            self.graph.store().walk_options(),
+            &mut files,
+        )
+        .await;
+        files.sort();
+
+        let mut markers = Vec::new();
+        for path in files {
+            let Ok(content) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            let relative_path = path.strip_prefix(codebase_path).unwrap_or(&path);
+            markers.extend(debt::scan_source(&content, relative_path));
+        }
+
+        Ok(markers)
+    }
+
+    /// Count cross-file references between every pair of files.
+    ///
+    /// Cell `(a, b)` is the number of references in `a` to symbols defined
+    /// in `b`. Use [`CouplingMatrix::to_csv`] to export it as a heatmap.
+    pub fn file_coupling_matrix(&self) -> Result<CouplingMatrix> {
+        let store = self.graph.store();
+        coupling::coupling_matrix(store.db_path(), &store.codebase_path)
+    }
+
     /// Analyze module dependencies.
     ///
-    /// Returns dependencies between modules in the codebase.
+    /// Returns dependencies between modules in the codebase, deduplicated and
+    /// sorted by `from` then `to` so the output is stable across calls.
     pub async fn module_dependencies(&self) -> Result<Vec<ModuleDependency>> {
         let db_path = self.graph.store().db_path();
         let analyzer = ModuleAnalyzer::new(db_path);
@@ -453,6 +659,8 @@ impl AnalysisModule {
                 });
             }
         }
+        deps.sort();
+        deps.dedup();
 
         Ok(deps)
     }
@@ -463,6 +671,26 @@ impl AnalysisModule {
         let analyzer = ModuleAnalyzer::new(db_path);
         analyzer.find_cycles()
     }
+
+    /// Render findings as a SARIF 2.1.0 log for code scanning tools.
+    ///
+    /// Results use the `dead-code`, `high-complexity` and
+    /// `dependency-cycle` rules. File paths are relative to the codebase
+    /// root and lines are 1-based; symbols with an unknown line are
+    /// located by file only.
+    pub fn to_sarif(&self, findings: &AnalysisFindings) -> Result<String> {
+        sarif::to_sarif(findings, &self.graph.store().codebase_path)
+    }
+
+    /// Find dependencies from a lower architectural layer to a higher one.
+    ///
+    /// See [`ModuleAnalyzer::check_layering`]. An empty result means the
+    /// codebase respects `rules`.
+    pub async fn check_layering(&self, rules: &[LayerRule]) -> Result<Vec<LayerViolation>> {
+        let db_path = self.graph.store().db_path();
+        let analyzer = ModuleAnalyzer::new(db_path);
+        analyzer.check_layering(rules)
+    }
 }
 
 #[cfg(test)]
@@ -479,6 +707,8 @@ mod tests {
             referenced_by: vec![],
             references: vec![],
             impact_score: 11,
+            weighted_score: 0.0,
+            per_hop_counts: vec![],
         };
         assert_eq!(impact.symbol, "test_function");
         assert_eq!(impact.ref_count, 5);
@@ -507,6 +737,121 @@ mod tests {
         assert_eq!(impact.call_count, 0);
     }
 
+    #[tokio::test]
+    async fn test_weighted_impact_counts_shortest_hop() {
+        // a <- b, a <- c, b <- c, b <- d, d <- e: c is reachable at hops
+        // 1 and 2, and must only count once, at hop 1.
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(
+            src_dir.join("lib.rs"),
+            "fn a() {}\nfn b() { a() }\nfn c() { a(); b() }\nfn d() { b() }\nfn e() { d() }\n",
+        )
+        .unwrap();
+
+        let store = Arc::new(
+            crate::storage::UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let graph = GraphModule::new(Arc::clone(&store));
+        graph.index().await.unwrap();
+        let analysis = AnalysisModule::new(
+            graph,
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(store),
+        );
+
+        let impact = analysis.impact_analysis("a").await.unwrap();
+        assert_eq!(impact.per_hop_counts, vec![2, 1, 1]);
+        let expected = 2.0 / 2.0 + 1.0 / 3.0 + 1.0 / 4.0;
+        assert!((impact.weighted_score - expected).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_impact_analysis_bulk_scans_once() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        let mut source = String::from("pub fn hub() {}\n");
+        let names: Vec<String> = (0..50).map(|i| format!("leaf_{}", i)).collect();
+        for (i, name) in names.iter().enumerate() {
+            let calls = "hub(); ".repeat(i % 3);
+            source.push_str(&format!("pub fn {}() {{ {} }}\n", name, calls));
+        }
+        source.push_str("pub fn root() { leaf_1(); leaf_2(); leaf_2() }\n");
+        std::fs::write(src_dir.join("lib.rs"), source).unwrap();
+
+        let store = Arc::new(
+            crate::storage::UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let graph = GraphModule::new(Arc::clone(&store));
+        graph.index().await.unwrap();
+        let analysis = AnalysisModule::new(
+            graph,
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(Arc::clone(&store)),
+        );
+
+        let mut symbols: Vec<&str> = names.iter().map(String::as_str).collect();
+        symbols.extend(["hub", "root", "missing"]);
+        let bulk = analysis.impact_analysis_bulk(&symbols).await.unwrap();
+        assert_eq!(bulk.len(), symbols.len());
+        assert_eq!(store.ref_index.builds(), 1);
+
+        // Counts agree with the per-symbol queries
+        for counts in &bulk {
+            let single = analysis.impact_analysis(&counts.symbol).await.unwrap();
+            assert_eq!(
+                (counts.call_count, counts.ref_count),
+                (single.call_count, single.ref_count),
+                "{}",
+                counts.symbol
+            );
+        }
+        let by_name = |name: &str| bulk.iter().find(|c| c.symbol == name).unwrap();
+        assert!(by_name("leaf_2").call_count > by_name("leaf_1").call_count);
+        assert!(by_name("hub").call_count > 0);
+        assert_eq!(by_name("missing").impact_score, 0);
+
+        // Another module on the same store reuses the index
+        let other = AnalysisModule::new(
+            GraphModule::new(Arc::clone(&store)),
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(Arc::clone(&store)),
+        );
+        other.impact_analysis_bulk(&["hub"]).await.unwrap();
+        assert_eq!(store.ref_index.builds(), 1);
+
+        // Re-indexing invalidates it
+        other.graph().index().await.unwrap();
+        other.impact_analysis_bulk(&["hub"]).await.unwrap();
+        assert_eq!(store.ref_index.builds(), 2);
+    }
+
+    #[test]
+    fn test_weigh_impact_deduplicates_paths() {
+        let at = |symbol_id, hop_distance| crate::graph::ImpactedSymbol {
+            symbol_id,
+            name: String::new(),
+            kind: String::new(),
+            file_path: String::new(),
+            hop_distance,
+            edge_type: "call".to_string(),
+        };
+        let (score, per_hop) = impact::weigh_impact(&[at(1, 2), at(2, 1), at(1, 1), at(3, 3)]);
+        assert_eq!(per_hop, vec![2, 0, 1]);
+        assert!((score - (1.0 + 0.25)).abs() < 1e-9);
+
+        assert_eq!(impact::weigh_impact(&[]), (0.0, vec![]));
+    }
+
     #[tokio::test]
     async fn test_dead_code_detection() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -527,6 +872,256 @@ mod tests {
         assert!(dead_code.is_empty());
     }
 
+    fn make_dead_code_fixture_db(db_path: &std::path::Path) {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+        let backend = open_graph(db_path, &GraphConfig::sqlite()).unwrap();
+        let node = |name: &str| NodeSpec {
+            kind: "fn".to_string(),
+            name: name.to_string(),
+            file_path: Some("src/lib.rs".to_string()),
+            data: serde_json::Value::Null,
+        };
+        for name in ["alpha", "beta", "gamma", "delta"] {
+            backend.insert_node(node(name)).unwrap();
+        }
+        let caller = backend.insert_node(node("caller")).unwrap();
+        let used = backend.insert_node(node("used")).unwrap();
+        backend
+            .insert_edge(EdgeSpec {
+                from: caller,
+                to: used,
+                edge_type: "CALLS".to_string(),
+                data: serde_json::Value::Null,
+            })
+            .unwrap();
+    }
+
+    async fn dead_code_fixture(dir: &std::path::Path) -> AnalysisModule {
+        let db_path = dir.join("dead.db");
+        make_dead_code_fixture_db(&db_path);
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(dir, &db_path, BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        AnalysisModule::new(
+            GraphModule::new(Arc::clone(&store)),
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(store),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_stream_dead_code_is_lazy() {
+        use futures::StreamExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let analysis = dead_code_fixture(temp_dir.path()).await;
+
+        let mut stream = Box::pin(analysis.stream_dead_code());
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.name, "alpha");
+
+        // Only the first entity has been inspected so far, so one removed
+        // now is never reported
+        let conn = rusqlite::Connection::open(analysis.graph.store().db_path()).unwrap();
+        conn.execute("DELETE FROM graph_entities WHERE name = 'beta'", [])
+            .unwrap();
+
+        let rest: Vec<String> = stream.map(|d| d.unwrap().name).collect().await;
+        // "caller" has no incoming edge either, so it is reported as dead
+        assert_eq!(rest, vec!["gamma", "delta", "caller"]);
+    }
+
+    #[tokio::test]
+    async fn test_dead_code_detection_limited_stops_at_cap() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let analysis = dead_code_fixture(temp_dir.path()).await;
+
+        assert_eq!(analysis.dead_code_detection().await.unwrap().len(), 5);
+
+        let limited = analysis.dead_code_detection_limited(2).await.unwrap();
+        let names: Vec<&str> = limited.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(names, vec!["alpha", "beta"]);
+    }
+
+    #[tokio::test]
+    async fn test_dead_code_detection_with_config_skips_roots() {
+        use crate::storage::{open_graph, GraphConfig, NodeSpec};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(
+            src_dir.join("lib.rs"),
+            "mod api;\n\
+             pub use api::reexported;\n\
+             pub fn public_entry() {}\n\
+             pub(crate) fn crate_entry() {}\n\
+             fn private_unused() {}\n\
+             fn main() {}\n\
+             #[no_mangle]\n\
+             extern \"C\" fn ffi_hook() {}\n\
+             struct Counter;\n\
+             impl Iterator for Counter {\n\
+             \x20   fn next(&mut self) -> Option<u32> { None }\n\
+             }\n\
+             #[test]\n\
+             fn checks_counter() {}\n\
+             #[cfg(test)]\n\
+             mod tests {\n\
+             \x20   fn fixture() {}\n\
+             }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            src_dir.join("api.rs"),
+            "pub fn reexported() {}\npub fn not_reexported() {}\n",
+        )
+        .unwrap();
+
+        let db_path = temp_dir.path().join("dead.db");
+        let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+        for (name, file) in [
+            ("public_entry", "src/lib.rs"),
+            ("crate_entry", "src/lib.rs"),
+            ("private_unused", "src/lib.rs"),
+            ("main", "src/lib.rs"),
+            ("ffi_hook", "src/lib.rs"),
+            ("next", "src/lib.rs"),
+            ("checks_counter", "src/lib.rs"),
+            ("fixture", "src/lib.rs"),
+            ("reexported", "src/api.rs"),
+            ("not_reexported", "src/api.rs"),
+        ] {
+            backend
+                .insert_node(NodeSpec {
+                    kind: "fn".to_string(),
+                    name: name.to_string(),
+                    file_path: Some(file.to_string()),
+                    data: serde_json::Value::Null,
+                })
+                .unwrap();
+        }
+        drop(backend);
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp_dir.path(),
+                &db_path,
+                BackendKind::SQLite,
+            )
+            .await
+            .unwrap(),
+        );
+        let analysis = AnalysisModule::new(
+            GraphModule::new(Arc::clone(&store)),
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(store),
+        );
+        let names = |dead: Vec<Symbol>| -> Vec<String> {
+            dead.iter().map(|s| s.name.to_string()).collect()
+        };
+
+        // Without configuration only `main` is exempt
+        let dead = analysis.dead_code_detection().await.unwrap();
+        let visibility = |name: &str| dead.iter().find(|s| &*s.name == name).unwrap().visibility;
+        assert_eq!(visibility("public_entry"), Visibility::Public);
+        assert_eq!(visibility("crate_entry"), Visibility::Crate);
+        assert_eq!(visibility("private_unused"), Visibility::Private);
+        assert_eq!(visibility("fixture"), Visibility::Private);
+        let dead = names(dead);
+        assert_eq!(dead.len(), 9);
+        assert!(!dead.contains(&"main".to_string()));
+
+        let dead = analysis
+            .dead_code_detection_with_config(DeadCodeConfig {
+                public_roots: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            names(dead),
+            vec![
+                "private_unused",
+                "ffi_hook",
+                "next",
+                "checks_counter",
+                "fixture",
+                "not_reexported"
+            ]
+        );
+
+        let dead = analysis
+            .dead_code_detection_with_config(DeadCodeConfig::all())
+            .await
+            .unwrap();
+        assert_eq!(names(dead), vec!["private_unused", "not_reexported"]);
+    }
+
+    #[tokio::test]
+    async fn test_file_coupling_matrix_counts_cross_file_references() {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("coupling.db");
+        let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+        let node = |kind: &str, name: &str, file: &str| NodeSpec {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            file_path: Some(file.to_string()),
+            data: serde_json::Value::Null,
+        };
+        let references = |from: i64, to: i64| EdgeSpec {
+            from,
+            to,
+            edge_type: "REFERENCES".to_string(),
+            data: serde_json::Value::Null,
+        };
+
+        let helper = backend
+            .insert_node(node("Symbol", "helper", "src/b.rs"))
+            .unwrap();
+        let local = backend
+            .insert_node(node("Symbol", "local", "src/a.rs"))
+            .unwrap();
+        for target in [helper, helper, local] {
+            let reference = backend
+                .insert_node(node("Reference", "ref", "src/a.rs"))
+                .unwrap();
+            backend.insert_edge(references(reference, target)).unwrap();
+        }
+        drop(backend);
+
+        let store = Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp_dir.path(),
+                &db_path,
+                BackendKind::SQLite,
+            )
+            .await
+            .unwrap(),
+        );
+        let analysis = AnalysisModule::new(
+            GraphModule::new(Arc::clone(&store)),
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(store),
+        );
+
+        let matrix = analysis.file_coupling_matrix().unwrap();
+        assert_eq!(matrix.get("src/a.rs", "src/b.rs"), 2);
+        assert_eq!(matrix.get("src/a.rs", "src/a.rs"), 0);
+        assert_eq!(matrix.get("src/b.rs", "src/a.rs"), 0);
+        assert_eq!(matrix.files, vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(
+            matrix.to_csv(),
+            "file,src/a.rs,src/b.rs\nsrc/a.rs,0,2\nsrc/b.rs,0,0\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_reference_chain() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -541,7 +1136,7 @@ mod tests {
         let edit = EditModule::new(store);
 
         let analysis = AnalysisModule::new(graph, cfg, edit, search);
-        let chain = analysis.reference_chain("test_symbol").await.unwrap();
+        let chain = analysis.reference_chain("test_symbol", 3).await.unwrap();
 
         // Should return empty chain for non-existent symbol
         assert!(chain.is_empty());
@@ -561,7 +1156,7 @@ mod tests {
         let edit = EditModule::new(store);
 
         let analysis = AnalysisModule::new(graph, cfg, edit, search);
-        let chain = analysis.call_chain("test_function").await.unwrap();
+        let chain = analysis.call_chain("test_function", 3).await.unwrap();
 
         // Should return empty chain for non-existent function
         assert!(chain.is_empty());
@@ -589,6 +1184,14 @@ mod tests {
         assert!(benchmarks.reference_chain_ms >= 0.0);
         assert!(benchmarks.call_chain_ms >= 0.0);
         assert!(benchmarks.total_ms >= 0.0);
+
+        let cancelled = analysis
+            .benchmarks_cancellable(&AtomicBool::new(true))
+            .await;
+        assert!(matches!(
+            cancelled,
+            Err(crate::error::ForgeError::Cancelled)
+        ));
     }
 
     #[tokio::test]
@@ -750,6 +1353,115 @@ mod tests {
         assert!(cycles.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_module_dependencies_sorted_and_deduped() {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("deps.db");
+        {
+            let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+            let node = |name: &str, file: &str| {
+                backend
+                    .insert_node(NodeSpec {
+                        kind: "fn".to_string(),
+                        name: name.to_string(),
+                        file_path: Some(file.to_string()),
+                        data: serde_json::Value::Null,
+                    })
+                    .unwrap()
+            };
+            let a1 = node("a1", "src/a.rs");
+            let a2 = node("a2", "src/a.rs");
+            let b = node("b", "src/b.rs");
+            let c = node("c", "src/c.rs");
+
+            // a.rs -> b.rs is recorded three times, via two symbols and a repeated edge.
+            for (from, to) in [(a1, b), (a1, b), (a2, b), (c, a1), (a2, c)] {
+                backend
+                    .insert_edge(EdgeSpec {
+                        from,
+                        to,
+                        edge_type: "CALLS".to_string(),
+                        data: serde_json::Value::Null,
+                    })
+                    .unwrap();
+            }
+        }
+
+        let store = Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp_dir.path(),
+                &db_path,
+                BackendKind::SQLite,
+            )
+            .await
+            .unwrap(),
+        );
+        let analysis = AnalysisModule::new(
+            GraphModule::new(Arc::clone(&store)),
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(store),
+        );
+
+        let first = analysis.module_dependencies().await.unwrap();
+        let second = analysis.module_dependencies().await.unwrap();
+        assert_eq!(first, second);
+
+        let pairs: Vec<(&str, &str)> = first
+            .iter()
+            .map(|d| (d.from.as_str(), d.to.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("src/a.rs", "src/b.rs"),
+                ("src/a.rs", "src/c.rs"),
+                ("src/c.rs", "src/a.rs"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_debt_markers_reported_against_function() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(
+            src_dir.join("lib.rs"),
+            "fn parse() -> i32 {\n    // TODO: fix this\n    1\n}\n",
+        )
+        .unwrap();
+
+        let store = Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp_dir.path(),
+                temp_dir.path().join("debt.db"),
+                BackendKind::SQLite,
+            )
+            .await
+            .unwrap(),
+        );
+        let analysis = AnalysisModule::new(
+            GraphModule::new(Arc::clone(&store)),
+            CfgModule::new(Arc::clone(&store)),
+            EditModule::new(Arc::clone(&store)),
+            SearchModule::new(store),
+        );
+
+        let markers = analysis.debt_markers().await.unwrap();
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].kind, DebtKind::Todo);
+        assert_eq!(markers[0].text, "fix this");
+        assert_eq!(markers[0].symbol.as_deref(), Some("parse"));
+        assert_eq!(
+            markers[0].location.file_path,
+            std::path::PathBuf::from("src/lib.rs")
+        );
+        assert_eq!(markers[0].location.line_number, 2);
+    }
+
     #[tokio::test]
     async fn test_complexity_metrics_integration() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -783,6 +1495,83 @@ mod tests {
         assert!(source_metrics.cyclomatic_complexity >= 1);
     }
 
+    #[tokio::test]
+    async fn test_complexity_metrics_from_cfg() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn pick(x: i32) -> i32 {\n    if x > 0 {\n        1\n    } else {\n        2\n    }\n}\n\nfn spin(n: i32) {\n    let mut i = 0;\n    while i < n {\n        i += 1;\n    }\n}\n",
+        )
+        .await
+        .unwrap();
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let graph = GraphModule::new(Arc::clone(&store));
+        graph.index().await.unwrap();
+        let search = SearchModule::new(Arc::clone(&store));
+        let cfg = CfgModule::new(Arc::clone(&store));
+        let edit = EditModule::new(store);
+        let analysis = AnalysisModule::new(graph, cfg, edit, search);
+
+        let pick = analysis.complexity_metrics("pick").await.unwrap();
+        assert_eq!(pick.cyclomatic_complexity, 2);
+        assert_eq!(pick.decision_points, 1);
+
+        let spin = analysis.complexity_metrics("spin").await.unwrap();
+        assert_eq!(spin.cyclomatic_complexity, 2);
+        assert_eq!(spin.decision_points, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reference_and_call_chains_follow_a_to_c() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        // a -> b -> c, and c calls back into a
+        let files = [
+            ("lib.rs", "mod a;\nmod b;\nmod c;\n"),
+            ("a.rs", "use crate::b::beta;\npub fn alpha(n: i32) -> i32 {\n    beta(n)\n}\n"),
+            ("b.rs", "use crate::c::gamma;\npub fn beta(n: i32) -> i32 {\n    gamma(n)\n}\n"),
+            (
+                "c.rs",
+                "use crate::a::alpha;\npub fn gamma(n: i32) -> i32 {\n    if n > 0 { alpha(n - 1) } else { 0 }\n}\n",
+            ),
+        ];
+        for (name, content) in files {
+            tokio::fs::write(src_dir.join(name), content).await.unwrap();
+        }
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let graph = GraphModule::new(Arc::clone(&store));
+        graph.index().await.unwrap();
+        let search = SearchModule::new(Arc::clone(&store));
+        let cfg = CfgModule::new(Arc::clone(&store));
+        let edit = EditModule::new(store);
+        let analysis = AnalysisModule::new(graph, cfg, edit, search);
+
+        let names = |chain: Vec<Symbol>| -> Vec<String> {
+            chain.iter().map(|s| s.name.to_string()).collect()
+        };
+
+        let calls = analysis.call_chain("gamma", 10).await.unwrap();
+        assert_eq!(names(calls), vec!["beta", "alpha"]);
+        let calls = analysis.call_chain("gamma", 1).await.unwrap();
+        assert_eq!(names(calls), vec!["beta"]);
+
+        let refs = analysis.reference_chain("gamma", 10).await.unwrap();
+        assert_eq!(names(refs), vec!["beta", "alpha"]);
+        let refs = analysis.reference_chain("gamma", 0).await.unwrap();
+        assert!(refs.is_empty());
+    }
+
     #[tokio::test]
     async fn test_cross_references_integration() {
         let temp_dir = tempfile::tempdir().unwrap();
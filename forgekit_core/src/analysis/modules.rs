@@ -139,6 +139,157 @@ impl<'a> ModuleAnalyzer<'a> {
 
         Ok(cycles)
     }
+
+    /// Find dependencies that point from a lower layer to a higher one
+    ///
+    /// Each file is assigned to the layer whose rule matches it most
+    /// specifically; files no rule matches are not checked. One violation
+    /// is reported per pair of files, located at the earliest symbol in
+    /// `from` with an edge into `to`. Results are sorted by `from`, then
+    /// `to`.
+    pub fn check_layering(&self, rules: &[LayerRule]) -> Result<Vec<LayerViolation>> {
+        use sqlitegraph::{open_graph, snapshot::SnapshotId, GraphConfig};
+
+        let config = GraphConfig::sqlite();
+        let backend = open_graph(self.db_path, &config)
+            .map_err(|e| ForgeError::DatabaseError(format!("Failed to open graph: {}", e)))?;
+
+        let snapshot = SnapshotId::current();
+        let entity_ids = backend
+            .entity_ids()
+            .map_err(|e| ForgeError::DatabaseError(format!("Failed to list entities: {}", e)))?;
+
+        let mut violations: HashMap<(String, String), LayerViolation> = HashMap::new();
+
+        for id in entity_ids {
+            let Ok(node) = backend.get_node(snapshot, id) else {
+                continue;
+            };
+            let Some(from_file) = node.file_path.clone().filter(|f| !f.is_empty()) else {
+                continue;
+            };
+            let Some(from_layer) = layer_of(rules, &from_file) else {
+                continue;
+            };
+            let Ok(outgoing) = backend.fetch_outgoing(id) else {
+                continue;
+            };
+            let line = node.data.get("start_line").and_then(|l| l.as_u64());
+
+            for target_id in outgoing {
+                let Ok(target) = backend.get_node(snapshot, target_id) else {
+                    continue;
+                };
+                let Some(to_file) = target
+                    .file_path
+                    .filter(|f| !f.is_empty() && *f != from_file)
+                else {
+                    continue;
+                };
+                let Some(to_layer) = layer_of(rules, &to_file) else {
+                    continue;
+                };
+                if to_layer.level <= from_layer.level {
+                    continue;
+                }
+
+                let candidate = LayerViolation {
+                    from: from_file.clone(),
+                    to: to_file.clone(),
+                    from_layer: from_layer.name.clone(),
+                    to_layer: to_layer.name.clone(),
+                    symbol: node.name.clone(),
+                    line,
+                };
+                violations
+                    .entry((from_file.clone(), to_file))
+                    .and_modify(|existing| {
+                        // Prefer located symbols, then the earliest line
+                        let key = |v: &LayerViolation| (v.line.is_none(), v.line, v.symbol.clone());
+                        if key(&candidate) < key(existing) {
+                            *existing = candidate.clone();
+                        }
+                    })
+                    .or_insert(candidate);
+            }
+        }
+
+        let mut violations: Vec<LayerViolation> = violations.into_values().collect();
+        violations.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        Ok(violations)
+    }
+}
+
+/// One layer of a layered architecture
+///
+/// A file belongs to the layer when one of `paths` appears in its path as
+/// whole components, so `"src/ui"` matches `/repo/src/ui/view.rs` but not
+/// `src/uikit.rs`. Code may depend on its own layer and those with a lower
+/// `level`; an edge to a higher level is a [`LayerViolation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerRule {
+    pub name: String,
+    pub level: u32,
+    pub paths: Vec<String>,
+}
+
+impl LayerRule {
+    /// Create a layer named `name` at `level` covering `paths`
+    pub fn new(
+        name: impl Into<String>,
+        level: u32,
+        paths: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            level,
+            paths: paths.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Number of components in the longest of `paths` found in `file`
+    fn match_len(&self, file: &str) -> Option<usize> {
+        let file: Vec<&str> = components(file).collect();
+        self.paths
+            .iter()
+            .map(|p| components(p).collect::<Vec<_>>())
+            .filter(|p| !p.is_empty() && file.windows(p.len()).any(|w| w == p.as_slice()))
+            .map(|p| p.len())
+            .max()
+    }
+}
+
+/// A dependency from a lower layer to a higher one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerViolation {
+    /// File the dependency starts in
+    pub from: String,
+    /// File depended on
+    pub to: String,
+    pub from_layer: String,
+    pub to_layer: String,
+    /// Symbol in `from` that references `to`
+    pub symbol: String,
+    /// Line of `symbol`, when the index recorded one
+    pub line: Option<u64>,
+}
+
+/// The most specifically matching rule for `file`, first rule on a tie
+fn layer_of<'r>(rules: &'r [LayerRule], file: &str) -> Option<&'r LayerRule> {
+    let mut best: Option<(&LayerRule, usize)> = None;
+    for rule in rules {
+        if let Some(len) = rule.match_len(file) {
+            if best.is_none_or(|(_, best_len)| len > best_len) {
+                best = Some((rule, len));
+            }
+        }
+    }
+    best.map(|(rule, _)| rule)
+}
+
+fn components(path: &str) -> impl Iterator<Item = &str> {
+    path.split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
 }
 
 /// Information about a module
@@ -210,4 +361,76 @@ mod tests {
         // Verify creation
         assert!(!analyzer.db_path.exists());
     }
+
+    #[test]
+    fn test_check_layering_catches_backward_edge() {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+
+        let temp = tempdir().unwrap();
+        let db_path = temp.path().join("layers.db");
+        let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+        let node = |name: &str, file: &str, line: u64| {
+            backend
+                .insert_node(NodeSpec {
+                    kind: "fn".to_string(),
+                    name: name.to_string(),
+                    file_path: Some(file.to_string()),
+                    data: serde_json::json!({ "start_line": line }),
+                })
+                .unwrap()
+        };
+        let call = |from: i64, to: i64| {
+            backend
+                .insert_edge(EdgeSpec {
+                    from,
+                    to,
+                    edge_type: "CALLS".to_string(),
+                    data: serde_json::Value::Null,
+                })
+                .unwrap();
+        };
+        let render = node("render", "/repo/src/ui/view.rs", 3);
+        let handle = node("handle", "/repo/src/service/api.rs", 10);
+        let load = node("load", "/repo/src/core/model.rs", 7);
+        let notify = node("notify", "/repo/src/core/model.rs", 20);
+        let helper = node("helper", "/repo/src/util.rs", 1);
+        call(render, handle);
+        call(handle, load);
+        call(render, load);
+        call(load, helper);
+        // Backward: core reaches up into the UI
+        call(notify, render);
+
+        let rules = [
+            LayerRule::new("core", 0, ["src/core"]),
+            LayerRule::new("service", 1, ["src/service"]),
+            LayerRule::new("ui", 2, ["src/ui"]),
+        ];
+        let violations = ModuleAnalyzer::new(&db_path)
+            .check_layering(&rules)
+            .unwrap();
+
+        assert_eq!(
+            violations,
+            vec![LayerViolation {
+                from: "/repo/src/core/model.rs".to_string(),
+                to: "/repo/src/ui/view.rs".to_string(),
+                from_layer: "core".to_string(),
+                to_layer: "ui".to_string(),
+                symbol: "notify".to_string(),
+                line: Some(20),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_layer_rule_matches_whole_components() {
+        let rules = [
+            LayerRule::new("app", 1, ["src"]),
+            LayerRule::new("ui", 2, ["src/ui"]),
+        ];
+        assert_eq!(layer_of(&rules, "src/ui/view.rs").unwrap().name, "ui");
+        assert_eq!(layer_of(&rules, "./src/uikit.rs").unwrap().name, "app");
+        assert!(layer_of(&rules, "tests/ui.rs").is_none());
+    }
 }
@@ -0,0 +1,140 @@
+//! One-pass call and reference counts
+//!
+//! [`GraphModule::callers_of`](crate::graph::GraphModule::callers_of) and
+//! [`GraphModule::references`](crate::graph::GraphModule::references) query
+//! the graph per symbol. The index counts every symbol's calls and
+//! references in a single pass over the database instead, and is kept on
+//! the store so every module sharing it reuses one build.
+
+use crate::error::{ForgeError, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Call and reference counts for every symbol name in the graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferenceIndex {
+    /// `(calls, refs)` keyed by symbol name
+    counts: HashMap<String, (usize, usize)>,
+    /// Types implementing each trait, keyed by simple trait name
+    implementors: HashMap<String, usize>,
+}
+
+impl ReferenceIndex {
+    /// Counts calls and references from the graph database at `db_path`.
+    ///
+    /// Calls are the `CALLS` edges into symbols named `name`; references
+    /// are the cross-file references to `name`. A missing database or
+    /// one without graph tables gives an empty index.
+    pub(crate) fn build(db_path: &Path) -> Result<Self> {
+        let mut index = Self::default();
+        if !db_path.exists() {
+            return Ok(index);
+        }
+
+        let conn = crate::storage::open_read_only(db_path)
+            .map_err(|e| ForgeError::DatabaseError(format!("Open db failed: {}", e)))?;
+        let mut count = |sql: &str, into_calls: bool| -> Result<()> {
+            let Ok(mut stmt) = conn.prepare(sql) else {
+                return Ok(());
+            };
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                })
+                .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?;
+            for (name, n) in rows.flatten() {
+                let entry = index.counts.entry(name).or_default();
+                if into_calls {
+                    entry.0 += n as usize;
+                } else {
+                    entry.1 += n as usize;
+                }
+            }
+            Ok(())
+        };
+        count(
+            "SELECT s.name, COUNT(*)
+             FROM graph_edges e
+             JOIN graph_entities s ON s.id = e.to_id
+             WHERE e.edge_type = 'CALLS' AND s.kind = 'Symbol'
+             GROUP BY s.name",
+            true,
+        )?;
+        count(
+            "SELECT to_symbol_id, COUNT(*) FROM cross_file_refs GROUP BY to_symbol_id",
+            false,
+        )?;
+
+        for i in crate::graph::impls::load_impls(db_path)?.unwrap_or_default() {
+            *index.implementors.entry(i.trait_name).or_default() += 1;
+        }
+        Ok(index)
+    }
+
+    /// Number of calls to `name`.
+    pub fn calls(&self, name: &str) -> usize {
+        self.counts.get(name).map_or(0, |c| c.0)
+    }
+
+    /// Number of references to `name`, counting each type that implements
+    /// it when it is a trait.
+    pub fn refs(&self, name: &str) -> usize {
+        let implementors = self
+            .implementors
+            .get(crate::graph::alias::simple_name(name))
+            .copied()
+            .unwrap_or(0);
+        self.counts.get(name).map_or(0, |c| c.1) + implementors
+    }
+
+    /// Number of symbol names with at least one call or reference.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Whether no symbol is called or referenced.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty() && self.implementors.is_empty()
+    }
+}
+
+/// The store's [`ReferenceIndex`], built on first use and dropped when the
+/// graph changes.
+#[derive(Debug, Default)]
+pub(crate) struct ReferenceIndexCache {
+    index: Mutex<Option<Arc<ReferenceIndex>>>,
+    builds: AtomicUsize,
+}
+
+impl ReferenceIndexCache {
+    /// Returns the index for `db_path`, building it if it was invalidated.
+    pub(crate) fn get_or_build(&self, db_path: &Path) -> Result<Arc<ReferenceIndex>> {
+        let mut slot = self
+            .index
+            .lock()
+            .expect("invariant: reference index mutex not poisoned");
+        if let Some(index) = slot.as_ref() {
+            return Ok(Arc::clone(index));
+        }
+        let index = Arc::new(ReferenceIndex::build(db_path)?);
+        self.builds.fetch_add(1, Ordering::Relaxed);
+        *slot = Some(Arc::clone(&index));
+        Ok(index)
+    }
+
+    /// Drops the index so the next use rebuilds it.
+    pub(crate) fn invalidate(&self) {
+        *self
+            .index
+            .lock()
+            .expect("invariant: reference index mutex not poisoned") = None;
+    }
+
+    /// How many times the index has been built.
+    #[cfg(test)]
+    pub(crate) fn builds(&self) -> usize {
+        self.builds.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,298 @@
+//! SARIF export of analysis findings
+//!
+//! Renders dead code, complexity and dependency-cycle findings as a
+//! [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! log, the format GitHub code scanning ingests. Paths are written
+//! relative to the codebase root under the `%SRCROOT%` base.
+
+use crate::analysis::ComplexityMetrics;
+use crate::error::Result;
+use crate::types::Symbol;
+use serde_json::{json, Value};
+use std::path::{Component, Path};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Rule ids, in the order of the driver's `rules` array
+const RULES: [(&str, &str, &str); 3] = [
+    ("dead-code", "Symbol is never used", "warning"),
+    (
+        "high-complexity",
+        "Function has high cyclomatic complexity",
+        "warning",
+    ),
+    (
+        "dependency-cycle",
+        "Files depend on each other in a cycle",
+        "warning",
+    ),
+];
+
+/// Findings to export with [`AnalysisModule::to_sarif`](super::AnalysisModule::to_sarif).
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisFindings {
+    /// Unused symbols, as from
+    /// [`dead_code_detection`](super::AnalysisModule::dead_code_detection)
+    pub dead_code: Vec<Symbol>,
+    /// Functions judged too complex, with their metrics
+    pub high_complexity: Vec<(Symbol, ComplexityMetrics)>,
+    /// Files forming each cycle, as from
+    /// [`find_dependency_cycles`](super::AnalysisModule::find_dependency_cycles)
+    pub dependency_cycles: Vec<Vec<String>>,
+}
+
+impl AnalysisFindings {
+    /// Whether there is nothing to report.
+    pub fn is_empty(&self) -> bool {
+        self.dead_code.is_empty()
+            && self.high_complexity.is_empty()
+            && self.dependency_cycles.is_empty()
+    }
+}
+
+/// Renders `findings` as a SARIF log with paths relative to `root`.
+pub(crate) fn to_sarif(findings: &AnalysisFindings, root: &Path) -> Result<String> {
+    let mut results = Vec::new();
+    for symbol in &findings.dead_code {
+        results.push(result(
+            0,
+            format!("`{}` is never used", symbol.name),
+            vec![symbol_location(symbol, root)],
+        ));
+    }
+    for (symbol, metrics) in &findings.high_complexity {
+        results.push(result(
+            1,
+            format!(
+                "`{}` has cyclomatic complexity {} and nesting depth {}",
+                symbol.name, metrics.cyclomatic_complexity, metrics.max_nesting_depth
+            ),
+            vec![symbol_location(symbol, root)],
+        ));
+    }
+    for cycle in &findings.dependency_cycles {
+        let Some(first) = cycle.first() else {
+            continue;
+        };
+        let files: Vec<String> = cycle
+            .iter()
+            .map(|file| artifact_uri(Path::new(file), root).0)
+            .collect();
+        let mut result = result(
+            2,
+            format!("Dependency cycle: {} -> {}", files.join(" -> "), files[0]),
+            vec![file_location(Path::new(first), root, None)],
+        );
+        let related: Vec<Value> = cycle[1..]
+            .iter()
+            .map(|file| file_location(Path::new(file), root, None))
+            .collect();
+        if !related.is_empty() {
+            result["relatedLocations"] = Value::Array(related);
+        }
+        results.push(result);
+    }
+
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, description, level)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": level },
+            })
+        })
+        .collect();
+    let log = json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "forgekit",
+                    "version": crate::VERSION,
+                    "informationUri": "https://github.com/oldnordic/forge",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+fn result(rule_index: usize, message: String, locations: Vec<Value>) -> Value {
+    let (id, _, level) = RULES[rule_index];
+    json!({
+        "ruleId": id,
+        "ruleIndex": rule_index,
+        "level": level,
+        "message": { "text": message },
+        "locations": locations,
+    })
+}
+
+/// Location of `symbol`; lines below 1 mean the line is unknown.
+fn symbol_location(symbol: &Symbol, root: &Path) -> Value {
+    let line = symbol.location.line_number;
+    file_location(
+        &symbol.location.file_path,
+        root,
+        (line >= 1).then_some(line),
+    )
+}
+
+fn file_location(path: &Path, root: &Path, line: Option<usize>) -> Value {
+    let (uri, relative) = artifact_uri(path, root);
+    let mut artifact = json!({ "uri": uri });
+    if relative {
+        artifact["uriBaseId"] = json!(SRCROOT);
+    }
+    let mut physical = json!({ "artifactLocation": artifact });
+    if let Some(line) = line {
+        physical["region"] = json!({ "startLine": line });
+    }
+    json!({ "physicalLocation": physical })
+}
+
+/// `path` as a URI, and whether it is relative to `root`.
+///
+/// Paths outside `root` stay absolute, as `file://` URIs.
+fn artifact_uri(path: &Path, root: &Path) -> (String, bool) {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    if relative.is_absolute() {
+        return (
+            format!("file://{}", relative.to_string_lossy().replace('\\', "/")),
+            false,
+        );
+    }
+    let segments: Vec<String> = relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(segment) => Some(segment.to_string_lossy().into_owned()),
+            Component::ParentDir => Some("..".to_string()),
+            _ => None,
+        })
+        .collect();
+    (segments.join("/"), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Language, Location, SymbolId, SymbolKind, Visibility};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn symbol(name: &str, file: PathBuf, line: usize) -> Symbol {
+        Symbol {
+            id: SymbolId(1),
+            name: Arc::from(name),
+            fully_qualified_name: Arc::from(name),
+            kind: SymbolKind::Function,
+            language: Language::Rust,
+            location: Location {
+                file_path: file,
+                byte_start: 0,
+                byte_end: 0,
+                line_number: line,
+            },
+            parent_id: None,
+            visibility: Visibility::Private,
+            body_hash: None,
+            metadata: Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_to_sarif_has_required_fields() {
+        let root = Path::new("/work/project");
+        let findings = AnalysisFindings {
+            dead_code: vec![
+                symbol("unused", root.join("src/lib.rs"), 12),
+                symbol("unknown_line", PathBuf::from("src/util.rs"), 0),
+            ],
+            high_complexity: vec![(
+                symbol("tangled", root.join("src/parse.rs"), 40),
+                ComplexityMetrics {
+                    cyclomatic_complexity: 17,
+                    decision_points: 16,
+                    max_nesting_depth: 5,
+                    lines_of_code: 120,
+                },
+            )],
+            dependency_cycles: vec![vec![
+                "/work/project/src/a.rs".to_string(),
+                "/work/project/src/b.rs".to_string(),
+            ]],
+        };
+
+        let sarif = to_sarif(&findings, root).unwrap();
+        let log: Value = serde_json::from_str(&sarif).unwrap();
+
+        // sarifLog requires version and runs
+        assert_eq!(log["version"], "2.1.0");
+        assert!(log["$schema"].as_str().unwrap().contains("sarif-2.1.0"));
+        let runs = log["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 1);
+
+        // run requires tool, tool requires driver, driver requires name
+        let driver = &runs[0]["tool"]["driver"];
+        assert_eq!(driver["name"], "forgekit");
+        assert_eq!(driver["version"], crate::VERSION);
+        let rule_ids: Vec<&str> = driver["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            rule_ids,
+            ["dead-code", "high-complexity", "dependency-cycle"]
+        );
+
+        // result requires message; ruleIndex must point at its rule
+        let results = runs[0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 4);
+        for result in results {
+            assert!(result["message"]["text"].is_string());
+            let index = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(result["ruleId"], rule_ids[index]);
+            for location in result["locations"].as_array().unwrap() {
+                let artifact = &location["physicalLocation"]["artifactLocation"];
+                let uri = artifact["uri"].as_str().unwrap();
+                assert!(!uri.starts_with('/'), "{uri}");
+                assert_eq!(artifact["uriBaseId"], SRCROOT);
+            }
+        }
+
+        let location = |i: usize| &results[i]["locations"][0]["physicalLocation"];
+        assert_eq!(location(0)["artifactLocation"]["uri"], "src/lib.rs");
+        assert_eq!(location(0)["region"]["startLine"], 12);
+        assert!(location(1).get("region").is_none());
+        assert_eq!(location(2)["artifactLocation"]["uri"], "src/parse.rs");
+        assert_eq!(location(2)["region"]["startLine"], 40);
+        assert_eq!(location(3)["artifactLocation"]["uri"], "src/a.rs");
+        assert_eq!(
+            results[3]["relatedLocations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/b.rs"
+        );
+    }
+
+    #[test]
+    fn test_to_sarif_keeps_paths_outside_root_absolute() {
+        let findings = AnalysisFindings {
+            dead_code: vec![symbol("vendored", PathBuf::from("/elsewhere/x.rs"), 3)],
+            ..Default::default()
+        };
+
+        let log: Value =
+            serde_json::from_str(&to_sarif(&findings, Path::new("/work/project")).unwrap())
+                .unwrap();
+        let artifact =
+            &log["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"];
+        assert_eq!(artifact["uri"], "file:///elsewhere/x.rs");
+        assert!(artifact.get("uriBaseId").is_none());
+    }
+}
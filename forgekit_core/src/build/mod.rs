@@ -1,6 +1,9 @@
 use std::path::Path;
+use std::process::Stdio;
 use std::time::Duration;
 
+use tokio::io::{AsyncRead, AsyncReadExt};
+
 use crate::diagnostic::{Diagnostic, DiagnosticParser};
 use crate::error::{ForgeError, Result};
 #[derive(Debug, Clone)]
@@ -53,13 +56,26 @@ impl BuildOutput {
     }
 }
 
+/// Default cap on the stdout and stderr kept from one build command.
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
 pub struct BuildModule {
     system: Box<dyn BuildSystem>,
+    max_output_bytes: usize,
 }
 
 impl BuildModule {
     pub fn new(system: Box<dyn BuildSystem>) -> Self {
-        Self { system }
+        Self {
+            system,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+        }
+    }
+
+    /// Keeps at most `max_output_bytes` of each command's stdout and stderr.
+    pub fn with_max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
     }
 
     pub fn detect(project_root: &Path) -> Option<Self> {
@@ -71,19 +87,19 @@ impl BuildModule {
     }
 
     pub async fn check(&self, project_root: &Path) -> Result<BuildOutput> {
-        self.system.check(project_root).await
+        self.system.check(project_root, self.max_output_bytes).await
     }
 
     pub async fn build(&self, project_root: &Path) -> Result<BuildOutput> {
-        self.system.build(project_root).await
+        self.system.build(project_root, self.max_output_bytes).await
     }
 
     pub async fn test(&self, project_root: &Path) -> Result<BuildOutput> {
-        self.system.test(project_root).await
+        self.system.test(project_root, self.max_output_bytes).await
     }
 
     pub async fn clean(&self, project_root: &Path) -> Result<BuildOutput> {
-        self.system.clean(project_root).await
+        self.system.clean(project_root, self.max_output_bytes).await
     }
 }
 
@@ -102,24 +118,33 @@ pub fn detect_build_system(project_root: &Path) -> Option<Box<dyn BuildSystem>>
 pub trait BuildSystem: Send + Sync {
     fn name(&self) -> &str;
     fn detect(&self, project_root: &Path) -> bool;
-    async fn check(&self, project_root: &Path) -> Result<BuildOutput>;
-    async fn build(&self, project_root: &Path) -> Result<BuildOutput>;
-    async fn test(&self, project_root: &Path) -> Result<BuildOutput>;
-    async fn clean(&self, project_root: &Path) -> Result<BuildOutput>;
+    /// Each command keeps at most `max_output_bytes` of stdout and of stderr.
+    async fn check(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput>;
+    async fn build(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput>;
+    async fn test(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput>;
+    async fn clean(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput>;
 }
 
+/// Runs one build command, keeping at most `max_output_bytes` of each stream.
+///
+/// The child is killed if the returned future is dropped, so a caller's
+/// timeout does not leave the build running.
 async fn run_command(
     program: &str,
     args: &[&str],
     working_dir: &Path,
     parser: &dyn DiagnosticParser,
+    max_output_bytes: usize,
 ) -> Result<BuildOutput> {
     let start = std::time::Instant::now();
-    let output = tokio::process::Command::new(program)
+    let mut child = tokio::process::Command::new(program)
         .args(args)
         .current_dir(working_dir)
-        .output()
-        .await
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
         .map_err(|e| {
             ForgeError::ToolError(format!(
                 "Failed to run {} {}: {}",
@@ -129,13 +154,29 @@ async fn run_command(
             ))
         })?;
 
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (stdout, stderr, status) = tokio::join!(
+        read_capped(stdout, max_output_bytes),
+        read_capped(stderr, max_output_bytes),
+        child.wait()
+    );
+    let status = status.map_err(|e| {
+        ForgeError::ToolError(format!(
+            "Failed to wait for {} {}: {}",
+            program,
+            args.join(" "),
+            e
+        ))
+    })?;
+
     let duration = start.elapsed();
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let stdout = String::from_utf8_lossy(&stdout).to_string();
+    let stderr = String::from_utf8_lossy(&stderr).to_string();
     let diagnostics = parser.parse(&stdout, &stderr);
 
     Ok(BuildOutput {
-        success: output.status.success(),
+        success: status.success(),
         diagnostics,
         duration,
         stdout,
@@ -143,6 +184,27 @@ async fn run_command(
     })
 }
 
+/// Reads `reader` to the end, keeping only the first `max` bytes.
+///
+/// The rest is drained so the child never blocks on a full pipe.
+async fn read_capped<R: AsyncRead + Unpin>(reader: Option<R>, max: usize) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let Some(mut reader) = reader else {
+        return buf;
+    };
+    let mut chunk = [0u8; 8192];
+    loop {
+        match reader.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let room = max.saturating_sub(buf.len());
+                buf.extend_from_slice(&chunk[..n.min(room)]);
+            }
+        }
+    }
+    buf
+}
+
 pub struct CargoBuildSystem;
 
 #[async_trait::async_trait]
@@ -155,32 +217,33 @@ impl BuildSystem for CargoBuildSystem {
         project_root.join("Cargo.toml").exists()
     }
 
-    async fn check(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn check(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::CargoDiagnosticParser;
         run_command(
             "cargo",
             &["check", "--message-format=json"],
             project_root,
             &parser,
+            max_output_bytes,
         )
         .await
     }
 
-    async fn build(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn build(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::CargoDiagnosticParser;
-        run_command("cargo", &["build"], project_root, &parser).await
+        run_command("cargo", &["build"], project_root, &parser, max_output_bytes).await
     }
 
-    async fn test(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn test(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::CargoDiagnosticParser;
-        run_command("cargo", &["test"], project_root, &parser).await
+        run_command("cargo", &["test"], project_root, &parser, max_output_bytes).await
     }
 
-    async fn clean(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn clean(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "cargo".to_string(),
         };
-        run_command("cargo", &["clean"], project_root, &parser).await
+        run_command("cargo", &["clean"], project_root, &parser, max_output_bytes).await
     }
 }
 
@@ -196,26 +259,54 @@ impl BuildSystem for GoBuildSystem {
         project_root.join("go.mod").exists()
     }
 
-    async fn check(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn check(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GoDiagnosticParser;
-        run_command("go", &["vet", "./..."], project_root, &parser).await
+        run_command(
+            "go",
+            &["vet", "./..."],
+            project_root,
+            &parser,
+            max_output_bytes,
+        )
+        .await
     }
 
-    async fn build(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn build(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GoDiagnosticParser;
-        run_command("go", &["build", "./..."], project_root, &parser).await
+        run_command(
+            "go",
+            &["build", "./..."],
+            project_root,
+            &parser,
+            max_output_bytes,
+        )
+        .await
     }
 
-    async fn test(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn test(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GoDiagnosticParser;
-        run_command("go", &["test", "./..."], project_root, &parser).await
+        run_command(
+            "go",
+            &["test", "./..."],
+            project_root,
+            &parser,
+            max_output_bytes,
+        )
+        .await
     }
 
-    async fn clean(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn clean(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "go".to_string(),
         };
-        run_command("go", &["clean", "-cache"], project_root, &parser).await
+        run_command(
+            "go",
+            &["clean", "-cache"],
+            project_root,
+            &parser,
+            max_output_bytes,
+        )
+        .await
     }
 }
 
@@ -231,32 +322,53 @@ impl BuildSystem for NpmBuildSystem {
         project_root.join("package.json").exists()
     }
 
-    async fn check(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn check(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "npm".to_string(),
         };
-        run_command("npm", &["run", "check"], project_root, &parser).await
+        run_command(
+            "npm",
+            &["run", "check"],
+            project_root,
+            &parser,
+            max_output_bytes,
+        )
+        .await
     }
 
-    async fn build(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn build(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "npm".to_string(),
         };
-        run_command("npm", &["run", "build"], project_root, &parser).await
+        run_command(
+            "npm",
+            &["run", "build"],
+            project_root,
+            &parser,
+            max_output_bytes,
+        )
+        .await
     }
 
-    async fn test(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn test(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "npm".to_string(),
         };
-        run_command("npm", &["test"], project_root, &parser).await
+        run_command("npm", &["test"], project_root, &parser, max_output_bytes).await
     }
 
-    async fn clean(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn clean(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "npm".to_string(),
         };
-        run_command("npm", &["run", "clean"], project_root, &parser).await
+        run_command(
+            "npm",
+            &["run", "clean"],
+            project_root,
+            &parser,
+            max_output_bytes,
+        )
+        .await
     }
 }
 
@@ -272,32 +384,32 @@ impl BuildSystem for MakeBuildSystem {
         project_root.join("Makefile").exists() || project_root.join("makefile").exists()
     }
 
-    async fn check(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn check(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "make".to_string(),
         };
-        run_command("make", &["check"], project_root, &parser).await
+        run_command("make", &["check"], project_root, &parser, max_output_bytes).await
     }
 
-    async fn build(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn build(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "make".to_string(),
         };
-        run_command("make", &[], project_root, &parser).await
+        run_command("make", &[], project_root, &parser, max_output_bytes).await
     }
 
-    async fn test(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn test(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "make".to_string(),
         };
-        run_command("make", &["test"], project_root, &parser).await
+        run_command("make", &["test"], project_root, &parser, max_output_bytes).await
     }
 
-    async fn clean(&self, project_root: &Path) -> Result<BuildOutput> {
+    async fn clean(&self, project_root: &Path, max_output_bytes: usize) -> Result<BuildOutput> {
         let parser = crate::diagnostic::GenericDiagnosticParser {
             tool_name: "make".to_string(),
         };
-        run_command("make", &["clean"], project_root, &parser).await
+        run_command("make", &["clean"], project_root, &parser, max_output_bytes).await
     }
 }
 
@@ -411,10 +523,29 @@ mod tests {
         if !sys.detect(&forge_root) {
             return;
         }
-        let out = sys.check(&forge_root).await.unwrap();
+        let out = sys
+            .check(&forge_root, DEFAULT_MAX_OUTPUT_BYTES)
+            .await
+            .unwrap();
         assert!(out.success, "forge should pass cargo check: {}", out.stderr);
     }
 
+    #[tokio::test]
+    async fn test_build_output_is_capped() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("Makefile"),
+            "check:\n\t@yes | head -c 100000\n",
+        )
+        .unwrap();
+        let module = BuildModule::detect(temp.path())
+            .unwrap()
+            .with_max_output_bytes(512);
+        let out = module.check(temp.path()).await.unwrap();
+        assert!(out.success, "make check failed: {}", out.stderr);
+        assert_eq!(out.stdout.len(), 512);
+    }
+
     #[test]
     fn test_build_module_detect() {
         let temp = tempfile::tempdir().unwrap();
@@ -1,26 +1,105 @@
-//! Query caching layer with LRU eviction and TTL expiration.
+//! Query caching layer with configurable eviction and TTL expiration.
 //!
 //! This module provides a thread-safe cache for query results to reduce
-//! database load and improve response times.
+//! database load and improve response times. Entries can record the files
+//! they were computed from, so a file change invalidates them before their
+//! TTL lapses.
+//!
+//! The [`EvictionPolicy`] decides which entry goes when the cache is full;
+//! the TTL applies the same way under every policy.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-/// Cache entry with expiration time.
+/// How a full [`QueryCache`] picks the entry to evict.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EvictionPolicy {
+    /// Evict the least recently used entry.
+    #[default]
+    Lru,
+    /// Evict the least frequently hit entry, the least recently used first
+    /// among equals.
+    Lfu,
+    /// Segmented LRU: new entries start in a probationary segment and move
+    /// to a protected segment on their first hit. Eviction takes the least
+    /// recently used probationary entry, so a burst of one-off queries
+    /// cannot push out entries that are hit repeatedly. The protected
+    /// segment holds at most 80% of the cache; its overflow is demoted back
+    /// to probation.
+    SegmentedLru,
+}
+
+/// The part of the cache an entry lives in.
+///
+/// Only [`EvictionPolicy::SegmentedLru`] protects entries; under the other
+/// policies every entry stays probationary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    Probationary,
+    Protected,
+}
+
+/// Hit and eviction counts for one cache segment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SegmentStats {
+    /// Entries currently in the segment.
+    pub len: usize,
+    /// Lookups answered by an entry in the segment.
+    pub hits: u64,
+    /// Entries evicted from the segment to make room.
+    pub evictions: u64,
+}
+
+/// Snapshot of a cache's policy and per-segment counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The eviction policy in use.
+    pub policy: EvictionPolicy,
+    /// Lookups that found no live entry.
+    pub misses: u64,
+    /// Entries that have not been hit since they were inserted or demoted.
+    /// Holds every entry unless the policy is segmented.
+    pub probationary: SegmentStats,
+    /// Entries promoted by a hit under [`EvictionPolicy::SegmentedLru`].
+    pub protected: SegmentStats,
+}
+
+impl CacheStats {
+    fn segment_mut(&mut self, segment: Segment) -> &mut SegmentStats {
+        match segment {
+            Segment::Probationary => &mut self.probationary,
+            Segment::Protected => &mut self.protected,
+        }
+    }
+}
+
+/// Cache entry with insertion time.
 #[derive(Debug, Clone)]
 struct CacheEntry<V> {
     /// The cached value.
     value: V,
-    /// When this entry expires.
-    expires_at: Instant,
+    /// When this entry was inserted; it expires once the current TTL has
+    /// elapsed since then.
+    inserted_at: Instant,
+    /// Files the value was computed from.
+    depends_on: HashSet<PathBuf>,
+    /// Number of hits since the entry was first inserted.
+    frequency: u64,
+    /// Segment the entry lives in.
+    segment: Segment,
 }
 
 /// Thread-safe query cache with LRU eviction.
 ///
 /// The `QueryCache` stores query results with a TTL (time-to-live)
-/// and evicts oldest entries when the cache is full.
+/// and evicts entries by its [`EvictionPolicy`] when the cache is full,
+/// least recently used first unless built with
+/// [`with_policy`](Self::with_policy). Both limits can be
+/// changed at runtime with [`resize`](Self::resize) and
+/// [`set_ttl`](Self::set_ttl); clones share the same policy.
 ///
 /// # Examples
 ///
@@ -48,10 +127,6 @@ where
     K: Clone + Eq + std::hash::Hash + Send + Sync + 'static,
     V: Clone + Send + Sync + 'static,
 {
-    /// Maximum number of entries.
-    max_size: usize,
-    /// Time-to-live for cache entries.
-    ttl: Duration,
     /// The underlying cache store.
     inner: Arc<RwLock<CacheInner<K, V>>>,
 }
@@ -63,10 +138,108 @@ where
     K: Clone + Eq + std::hash::Hash,
     V: Clone,
 {
+    /// Maximum number of entries.
+    max_size: usize,
+    /// Time-to-live for cache entries.
+    ttl: Duration,
+    /// How entries are chosen for eviction.
+    policy: EvictionPolicy,
     /// Map from key to cached entry.
     entries: HashMap<K, CacheEntry<V>>,
-    /// Keys in insertion order (for FIFO eviction).
+    /// Keys from least to most recently used.
     keys: Vec<K>,
+    /// Hit, miss and eviction counters.
+    stats: CacheStats,
+}
+
+impl<K, V> CacheInner<K, V>
+where
+    K: Clone + Eq + std::hash::Hash,
+    V: Clone,
+{
+    /// Evicts entries chosen by the policy until at most `max` remain.
+    fn evict_to(&mut self, max: usize) {
+        while self.keys.len() > max {
+            let pos = self.victim();
+            let key = self.keys.remove(pos);
+            if let Some(entry) = self.entries.remove(&key) {
+                self.stats.segment_mut(entry.segment).evictions += 1;
+            }
+        }
+    }
+
+    /// Position in `keys` of the entry to evict next.
+    fn victim(&self) -> usize {
+        let segment = |k: &K| self.entries.get(k).map(|e| e.segment);
+        match self.policy {
+            EvictionPolicy::Lru => 0,
+            EvictionPolicy::Lfu => self
+                .keys
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, k)| self.entries.get(*k).map_or(0, |e| e.frequency))
+                .map_or(0, |(pos, _)| pos),
+            EvictionPolicy::SegmentedLru => self
+                .keys
+                .iter()
+                .position(|k| segment(k) == Some(Segment::Probationary))
+                .unwrap_or(0),
+        }
+    }
+
+    /// Moves the key at `pos` to the most recently used end.
+    fn touch(&mut self, pos: usize) {
+        let key = self.keys.remove(pos);
+        self.keys.push(key);
+    }
+
+    /// Records a hit on `key`, promoting it under the segmented policy.
+    fn record_hit(&mut self, key: &K) {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return;
+        };
+        entry.frequency += 1;
+        let segment = entry.segment;
+        self.stats.segment_mut(segment).hits += 1;
+        if self.policy == EvictionPolicy::SegmentedLru && segment == Segment::Probationary {
+            entry.segment = Segment::Protected;
+            self.demote_overflow();
+        }
+    }
+
+    /// Demotes the least recently used protected entries to probation
+    /// until the protected segment fits in 80% of the cache.
+    fn demote_overflow(&mut self) {
+        let capacity = (self.max_size * 4 / 5).max(1);
+        let protected = |inner: &Self| {
+            inner
+                .entries
+                .values()
+                .filter(|e| e.segment == Segment::Protected)
+                .count()
+        };
+        while protected(self) > capacity {
+            let Some(pos) = self
+                .keys
+                .iter()
+                .position(|k| self.entries.get(k).map(|e| e.segment) == Some(Segment::Protected))
+            else {
+                break;
+            };
+            if let Some(entry) = self.entries.get_mut(&self.keys[pos]) {
+                entry.segment = Segment::Probationary;
+            }
+            self.touch(pos);
+        }
+    }
+
+    /// Drops `key` without counting it as an eviction.
+    fn remove(&mut self, key: &K) {
+        self.entries.remove(key);
+        if let Some(pos) = self.keys.iter().position(|k| k == key) {
+            self.keys.remove(pos);
+        }
+    }
 }
 
 impl<K, V> QueryCache<K, V>
@@ -81,12 +254,28 @@ where
     /// * `max_size` - Maximum number of entries before eviction
     /// * `ttl` - Time-to-live for cache entries
     pub fn new(max_size: usize, ttl: Duration) -> Self {
+        Self::with_policy(max_size, ttl, EvictionPolicy::default())
+    }
+
+    /// Creates a new query cache that evicts by `policy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_size` - Maximum number of entries before eviction
+    /// * `ttl` - Time-to-live for cache entries
+    /// * `policy` - How entries are chosen for eviction
+    pub fn with_policy(max_size: usize, ttl: Duration, policy: EvictionPolicy) -> Self {
         Self {
-            max_size,
-            ttl,
             inner: Arc::new(RwLock::new(CacheInner {
+                max_size,
+                ttl,
+                policy,
                 entries: HashMap::new(),
                 keys: Vec::new(),
+                stats: CacheStats {
+                    policy,
+                    ..CacheStats::default()
+                },
             })),
         }
     }
@@ -104,26 +293,24 @@ where
         let mut inner = self.inner.write().await;
         let now = Instant::now();
 
-        // Clone key to avoid borrow issues
-        let key_clone = key.clone();
-        let value_opt = inner.entries.get(&key_clone).cloned();
+        let value_opt = inner
+            .entries
+            .get(key)
+            .map(|entry| (entry.value.clone(), entry.inserted_at));
 
-        if let Some(entry) = value_opt {
-            if now < entry.expires_at {
+        if let Some((value, inserted_at)) = value_opt {
+            if now.duration_since(inserted_at) < inner.ttl {
                 // Touch key: move to end of list (LRU behavior)
-                if let Some(pos) = inner.keys.iter().position(|k| k == &key_clone) {
-                    inner.keys.remove(pos);
-                    inner.keys.push(key_clone);
-                }
-                return Some(entry.value);
-            } else {
-                // Expired - remove it
-                inner.entries.remove(&key_clone);
-                if let Some(pos) = inner.keys.iter().position(|k| k == &key_clone) {
-                    inner.keys.remove(pos);
+                if let Some(pos) = inner.keys.iter().position(|k| k == key) {
+                    inner.touch(pos);
                 }
+                inner.record_hit(key);
+                return Some(value);
             }
+            // Expired - remove it, whatever the policy
+            inner.remove(key);
         }
+        inner.stats.misses += 1;
         None
     }
 
@@ -136,30 +323,58 @@ where
     /// * `key` - The cache key
     /// * `value` - The value to cache
     pub async fn insert(&self, key: K, value: V) {
+        self.insert_with_deps(key, value, []).await;
+    }
+
+    /// Inserts a value computed from the files in `depends_on`.
+    ///
+    /// The entry is dropped by [`invalidate_by_path`](Self::invalidate_by_path)
+    /// for any of those files, as well as by the usual TTL and eviction.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The cache key
+    /// * `value` - The value to cache
+    /// * `depends_on` - Paths of the files the value depends on
+    pub async fn insert_with_deps(
+        &self,
+        key: K,
+        value: V,
+        depends_on: impl IntoIterator<Item = PathBuf>,
+    ) {
+        let depends_on = depends_on.into_iter().collect();
         let mut inner = self.inner.write().await;
 
         // If max_size is 0, don't insert anything
-        if self.max_size == 0 {
+        if inner.max_size == 0 {
             return;
         }
 
-        // Check if we need to evict
-        while inner.keys.len() >= self.max_size && !inner.keys.is_empty() {
-            // Evict oldest (FIFO) - or first key
-            if let Some(old_key) = inner.keys.first() {
-                let old_key = old_key.clone();
-                inner.keys.remove(0);
-                inner.entries.remove(&old_key);
-            }
-        }
-
-        let expires_at = Instant::now() + self.ttl;
+        let inserted_at = Instant::now();
 
-        // Update or insert
-        if !inner.keys.contains(&key) {
-            inner.keys.push(key.clone());
+        // Updating keeps the entry's frequency and segment
+        if let Some(entry) = inner.entries.get_mut(&key) {
+            entry.value = value;
+            entry.inserted_at = inserted_at;
+            entry.depends_on = depends_on;
+            return;
         }
-        inner.entries.insert(key, CacheEntry { value, expires_at });
+
+        // Check if we need to evict
+        let max_size = inner.max_size;
+        inner.evict_to(max_size - 1);
+
+        inner.keys.push(key.clone());
+        inner.entries.insert(
+            key,
+            CacheEntry {
+                value,
+                inserted_at,
+                depends_on,
+                frequency: 0,
+                segment: Segment::Probationary,
+            },
+        );
     }
 
     /// Invalidates a specific cache entry.
@@ -168,11 +383,24 @@ where
     ///
     /// * `key` - The cache key to invalidate
     pub async fn invalidate(&self, key: &K) {
+        self.inner.write().await.remove(key);
+    }
+
+    /// Invalidates every entry that depends on `path`.
+    ///
+    /// Paths are compared as given, so they should be recorded in the same
+    /// form (e.g. absolute) that changes are reported in.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The changed file
+    pub async fn invalidate_by_path(&self, path: &Path) {
         let mut inner = self.inner.write().await;
-        inner.entries.remove(key);
-        if let Some(pos) = inner.keys.iter().position(|k| k == key) {
-            inner.keys.remove(pos);
-        }
+        inner
+            .entries
+            .retain(|_, entry| !entry.depends_on.contains(path));
+        let CacheInner { entries, keys, .. } = &mut *inner;
+        keys.retain(|k| entries.contains_key(k));
     }
 
     /// Clears all cached entries.
@@ -193,6 +421,59 @@ where
         let inner = self.inner.read().await;
         inner.entries.is_empty()
     }
+
+    /// Returns the maximum number of entries.
+    pub async fn max_size(&self) -> usize {
+        self.inner.read().await.max_size
+    }
+
+    /// Returns the time-to-live for cache entries.
+    pub async fn ttl(&self) -> Duration {
+        self.inner.read().await.ttl
+    }
+
+    /// Returns the eviction policy.
+    pub async fn policy(&self) -> EvictionPolicy {
+        self.inner.read().await.policy
+    }
+
+    /// Returns the hit, miss and eviction counts, split by segment.
+    pub async fn stats(&self) -> CacheStats {
+        let inner = self.inner.read().await;
+        let mut stats = inner.stats;
+        stats.probationary.len = 0;
+        stats.protected.len = 0;
+        for entry in inner.entries.values() {
+            stats.segment_mut(entry.segment).len += 1;
+        }
+        stats
+    }
+
+    /// Changes the maximum number of entries.
+    ///
+    /// When shrinking, entries are evicted by the cache's policy
+    /// immediately until the cache fits.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_max` - New maximum number of entries
+    pub async fn resize(&self, new_max: usize) {
+        let mut inner = self.inner.write().await;
+        inner.max_size = new_max;
+        inner.evict_to(new_max);
+    }
+
+    /// Changes the time-to-live for cache entries.
+    ///
+    /// Applies to existing entries too: an entry expires once `ttl` has
+    /// elapsed since it was inserted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - New time-to-live
+    pub async fn set_ttl(&self, ttl: Duration) {
+        self.inner.write().await.ttl = ttl;
+    }
 }
 
 impl<K, V> std::fmt::Debug for QueryCache<K, V>
@@ -201,11 +482,15 @@ where
     V: Clone + Send + Sync + 'static,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("QueryCache")
-            .field("max_size", &self.max_size)
-            .field("ttl", &self.ttl)
-            .field("inner", &"<cache>")
-            .finish()
+        let mut debug = f.debug_struct("QueryCache");
+        match self.inner.try_read() {
+            Ok(inner) => debug
+                .field("max_size", &inner.max_size)
+                .field("ttl", &inner.ttl)
+                .field("policy", &inner.policy),
+            Err(_) => debug.field("inner", &"<locked>"),
+        };
+        debug.finish()
     }
 }
 
@@ -277,6 +562,36 @@ mod tests {
         assert_eq!(cache.len().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_cache_invalidate_by_path() {
+        let cache = QueryCache::new(10, Duration::from_secs(60));
+        let lib = PathBuf::from("/repo/src/lib.rs");
+        let main = PathBuf::from("/repo/src/main.rs");
+
+        cache
+            .insert_with_deps("a".to_string(), "1".to_string(), [lib.clone()])
+            .await;
+        cache
+            .insert_with_deps(
+                "b".to_string(),
+                "2".to_string(),
+                [lib.clone(), main.clone()],
+            )
+            .await;
+        cache
+            .insert_with_deps("c".to_string(), "3".to_string(), [main])
+            .await;
+        cache.insert("d".to_string(), "4".to_string()).await;
+
+        cache.invalidate_by_path(&lib).await;
+
+        assert!(cache.get(&"a".to_string()).await.is_none());
+        assert!(cache.get(&"b".to_string()).await.is_none());
+        assert_eq!(cache.get(&"c".to_string()).await, Some("3".to_string()));
+        assert_eq!(cache.get(&"d".to_string()).await, Some("4".to_string()));
+        assert_eq!(cache.len().await, 2);
+    }
+
     #[tokio::test]
     async fn test_cache_clear() {
         let cache = QueryCache::new(10, Duration::from_secs(60));
@@ -450,4 +765,123 @@ mod tests {
         // Verify len decreased
         assert_eq!(cache.len().await, 0);
     }
+
+    #[tokio::test]
+    async fn test_cache_resize_evicts_least_recently_used() {
+        let cache = QueryCache::new(5, Duration::from_secs(60));
+        for i in 0..5 {
+            cache
+                .insert(format!("key{}", i), format!("value{}", i))
+                .await;
+        }
+
+        // Touch key0 so it becomes most recently used
+        assert!(cache.get(&"key0".to_string()).await.is_some());
+
+        cache.resize(2).await;
+
+        assert_eq!(cache.max_size().await, 2);
+        assert_eq!(cache.len().await, 2);
+        for i in 1..4 {
+            assert!(cache.get(&format!("key{}", i)).await.is_none());
+        }
+        assert_eq!(
+            cache.get(&"key4".to_string()).await,
+            Some("value4".to_string())
+        );
+        assert_eq!(
+            cache.get(&"key0".to_string()).await,
+            Some("value0".to_string())
+        );
+
+        // New inserts respect the new limit
+        cache.insert("key5".to_string(), "value5".to_string()).await;
+        assert_eq!(cache.len().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_segmented_lru_keeps_hot_key_through_eviction_storm() {
+        for policy in [EvictionPolicy::SegmentedLru, EvictionPolicy::Lfu] {
+            let cache = QueryCache::with_policy(10, Duration::from_secs(60), policy);
+            cache.insert("hot".to_string(), "value".to_string()).await;
+            for _ in 0..5 {
+                assert!(cache.get(&"hot".to_string()).await.is_some());
+            }
+
+            // A long tail of one-off queries, each missed then inserted
+            for i in 0..1000 {
+                let key = format!("tail{}", i);
+                assert!(cache.get(&key).await.is_none());
+                cache.insert(key, "cold".to_string()).await;
+            }
+
+            assert_eq!(
+                cache.get(&"hot".to_string()).await,
+                Some("value".to_string()),
+                "{:?}",
+                policy
+            );
+            let stats = cache.stats().await;
+            assert_eq!(stats.policy, policy);
+            assert_eq!(stats.misses, 1000);
+            assert_eq!(stats.probationary.evictions, 991);
+            assert_eq!(stats.protected.evictions, 0);
+        }
+
+        // Plain LRU loses the hot key to the same storm
+        let cache = QueryCache::new(10, Duration::from_secs(60));
+        cache.insert("hot".to_string(), "value".to_string()).await;
+        for _ in 0..5 {
+            assert!(cache.get(&"hot".to_string()).await.is_some());
+        }
+        for i in 0..1000 {
+            cache.insert(format!("tail{}", i), "cold".to_string()).await;
+        }
+        assert!(cache.get(&"hot".to_string()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_segment_stats() {
+        let cache =
+            QueryCache::with_policy(5, Duration::from_secs(60), EvictionPolicy::SegmentedLru);
+        cache.insert("a".to_string(), "1".to_string()).await;
+        cache.insert("b".to_string(), "2".to_string()).await;
+
+        // First hit counts against probation, later ones against protected
+        cache.get(&"a".to_string()).await;
+        cache.get(&"a".to_string()).await;
+        cache.get(&"a".to_string()).await;
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.probationary.hits, 1);
+        assert_eq!(stats.protected.hits, 2);
+        assert_eq!(stats.probationary.len, 1);
+        assert_eq!(stats.protected.len, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_applies_to_protected_entries() {
+        let cache =
+            QueryCache::with_policy(10, Duration::from_millis(50), EvictionPolicy::SegmentedLru);
+        cache.insert("key".to_string(), "value".to_string()).await;
+        assert!(cache.get(&"key".to_string()).await.is_some());
+        assert_eq!(cache.stats().await.protected.len, 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(cache.get(&"key".to_string()).await.is_none());
+        assert!(cache.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_cache_set_ttl_applies_to_existing_entries() {
+        let cache = QueryCache::new(10, Duration::from_secs(60));
+        cache.insert("key".to_string(), "value".to_string()).await;
+
+        let shared = cache.clone();
+        shared.set_ttl(Duration::from_millis(20)).await;
+        assert_eq!(cache.ttl().await, Duration::from_millis(20));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(cache.get(&"key".to_string()).await.is_none());
+    }
 }
@@ -0,0 +1,186 @@
+//! CFG cache shared by every module on a store
+//!
+//! CFGs are memoized per symbol until the next index run, and by the hash
+//! of the function body across runs, so re-indexing only costs a parse for
+//! functions whose body changed. Body entries keep the body text, and a
+//! lookup whose text differs is a miss, so a hash collision can never hand
+//! back the wrong CFG.
+
+use super::TestCfg;
+use crate::types::SymbolId;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Body entries kept before the least recently used is evicted.
+const DEFAULT_CAPACITY: usize = 4096;
+
+#[derive(Debug)]
+struct BodyEntry {
+    body: Arc<str>,
+    cfg: TestCfg,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    symbols: HashMap<SymbolId, TestCfg>,
+    bodies: HashMap<blake3::Hash, BodyEntry>,
+    clock: u64,
+}
+
+/// CFGs keyed by symbol and by body hash, the latter bounded by LRU.
+#[derive(Debug)]
+pub(crate) struct CfgCache {
+    inner: Mutex<Inner>,
+    capacity: usize,
+    extractions: AtomicUsize,
+}
+
+impl Default for CfgCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl CfgCache {
+    /// A cache holding at most `capacity` function bodies.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::default(),
+            capacity,
+            extractions: AtomicUsize::new(0),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .expect("invariant: cfg cache mutex not poisoned")
+    }
+
+    /// The CFG built for `function` since the last index run.
+    pub(crate) fn get(&self, function: SymbolId) -> Option<TestCfg> {
+        self.lock().symbols.get(&function).cloned()
+    }
+
+    /// Remembers `cfg` for `function` until the next index run.
+    pub(crate) fn insert(&self, function: SymbolId, cfg: TestCfg) {
+        self.lock().symbols.insert(function, cfg);
+    }
+
+    /// The CFG of a function whose body hashes to `hash`, if its cached
+    /// body is exactly `body`.
+    pub(crate) fn get_body(&self, hash: &blake3::Hash, body: &str) -> Option<TestCfg> {
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let now = inner.clock;
+        let entry = inner.bodies.get_mut(hash)?;
+        if *entry.body != *body {
+            return None;
+        }
+        entry.last_used = now;
+        Some(entry.cfg.clone())
+    }
+
+    /// Remembers `cfg` for the function body `body`, evicting the least
+    /// recently used body when full.
+    pub(crate) fn insert_body(&self, hash: blake3::Hash, body: &str, cfg: TestCfg) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let last_used = inner.clock;
+        if !inner.bodies.contains_key(&hash) && inner.bodies.len() >= self.capacity {
+            let oldest = inner
+                .bodies
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(hash, _)| *hash);
+            if let Some(oldest) = oldest {
+                inner.bodies.remove(&oldest);
+            }
+        }
+        inner.bodies.insert(
+            hash,
+            BodyEntry {
+                body: Arc::from(body),
+                cfg,
+                last_used,
+            },
+        );
+    }
+
+    /// Forgets the per-symbol CFGs, whose ids an index run may reassign.
+    ///
+    /// CFGs keyed by body stay, since a body that still hashes the same
+    /// still has the same control flow.
+    pub(crate) fn clear_symbols(&self) {
+        self.lock().symbols.clear();
+    }
+
+    /// Records that a CFG was extracted from source rather than the cache.
+    pub(crate) fn record_extraction(&self) {
+        self.extractions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// How many CFGs were extracted from source.
+    #[cfg(test)]
+    pub(crate) fn extractions(&self) -> usize {
+        self.extractions.load(Ordering::Relaxed)
+    }
+
+    /// Number of function bodies cached.
+    #[cfg(test)]
+    pub(crate) fn body_count(&self) -> usize {
+        self.lock().bodies.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_lookup_verifies_text() {
+        let cache = CfgCache::default();
+        let hash = blake3::hash(b"fn a() {}");
+        cache.insert_body(hash, "fn a() {}", TestCfg::if_else());
+
+        assert!(cache.get_body(&hash, "fn a() {}").is_some());
+        // Same hash, different body: treated as a collision
+        assert!(cache.get_body(&hash, "fn b() {}").is_none());
+    }
+
+    #[test]
+    fn test_body_entries_evict_least_recently_used() {
+        let cache = CfgCache::with_capacity(2);
+        let bodies = ["fn a() {}", "fn b() {}", "fn c() {}"];
+        let hashes: Vec<_> = bodies.iter().map(|b| blake3::hash(b.as_bytes())).collect();
+
+        cache.insert_body(hashes[0], bodies[0], TestCfg::if_else());
+        cache.insert_body(hashes[1], bodies[1], TestCfg::if_else());
+        // Touch `a` so `b` is the least recently used
+        assert!(cache.get_body(&hashes[0], bodies[0]).is_some());
+        cache.insert_body(hashes[2], bodies[2], TestCfg::if_else());
+
+        assert_eq!(cache.body_count(), 2);
+        assert!(cache.get_body(&hashes[0], bodies[0]).is_some());
+        assert!(cache.get_body(&hashes[1], bodies[1]).is_none());
+        assert!(cache.get_body(&hashes[2], bodies[2]).is_some());
+    }
+
+    #[test]
+    fn test_clear_symbols_keeps_bodies() {
+        let cache = CfgCache::default();
+        let hash = blake3::hash(b"fn a() {}");
+        cache.insert(SymbolId(1), TestCfg::if_else());
+        cache.insert_body(hash, "fn a() {}", TestCfg::if_else());
+
+        cache.clear_symbols();
+
+        assert!(cache.get(SymbolId(1)).is_none());
+        assert!(cache.get_body(&hash, "fn a() {}").is_some());
+    }
+}
@@ -41,6 +41,20 @@ impl DominatorTree {
         false
     }
 
+    /// Number of immediate-dominator steps from `block` up to the root.
+    pub fn depth(&self, block: BlockId) -> usize {
+        let mut depth = 0;
+        let mut current = block;
+        while let Some(&idom) = self.dominators.get(&current) {
+            if idom == current || depth > self.dominators.len() {
+                break;
+            }
+            depth += 1;
+            current = idom;
+        }
+        depth
+    }
+
     pub fn insert(&mut self, block: BlockId, dominator: BlockId) {
         self.dominators.insert(block, dominator);
     }
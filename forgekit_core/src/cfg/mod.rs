@@ -2,15 +2,18 @@
 //!
 //! This module provides CFG operations via Mirage integration.
 
+mod cache;
 mod dominators;
 mod paths;
 mod test_cfg;
 mod types;
 
+pub(crate) use cache::CfgCache;
 pub use dominators::DominatorTree;
 pub use paths::{Path, PathBuilder};
+pub(crate) use test_cfg::dot_quote;
 pub use test_cfg::TestCfg;
-pub use types::Loop;
+pub use types::{CfgStats, Loop, PathCount};
 
 use crate::error::Result;
 use crate::storage::UnifiedGraphStore;
@@ -46,7 +49,7 @@ impl CfgModule {
         if !self.store.db_path.exists() {
             return Ok(None);
         }
-        let conn = rusqlite::Connection::open(&self.store.db_path)
+        let conn = crate::storage::open_read_only(&self.store.db_path)
             .map_err(|e| crate::error::ForgeError::DatabaseError(format!("Open db: {}", e)))?;
         let entity_id: Option<i64> = conn
             .query_row(
@@ -80,6 +83,36 @@ impl CfgModule {
         Ok(cfg.compute_dominators())
     }
 
+    /// Renders the CFG of `function` as a GraphViz digraph.
+    ///
+    /// See [`TestCfg::to_dot`] for the styling.
+    pub async fn cfg_dot(&self, function: SymbolId) -> Result<String> {
+        match function_cfg(&self.store, function)? {
+            Some(cfg) => Ok(cfg.to_dot()),
+            None => Err(crate::error::ForgeError::CfgNotAvailable(function)),
+        }
+    }
+
+    /// Returns the blocks of `function` that cannot be reached from its
+    /// entry, such as statements after an early return.
+    pub async fn unreachable(&self, function: SymbolId) -> Result<Vec<BlockId>> {
+        match function_cfg(&self.store, function)? {
+            Some(cfg) => Ok(cfg.unreachable_blocks()),
+            None => Err(crate::error::ForgeError::CfgNotAvailable(function)),
+        }
+    }
+
+    /// Returns size metrics of the CFG of `function`, to check before
+    /// enumerating its paths.
+    ///
+    /// See [`TestCfg::stats`].
+    pub async fn cfg_stats(&self, function: SymbolId) -> Result<CfgStats> {
+        match function_cfg(&self.store, function)? {
+            Some(cfg) => Ok(cfg.stats()),
+            None => Err(crate::error::ForgeError::CfgNotAvailable(function)),
+        }
+    }
+
     pub async fn loops(&self, function: SymbolId) -> Result<Vec<Loop>> {
         if let Some(cfg) = load_test_cfg(&self.store.db_path, function.0)? {
             return Ok(cfg.detect_loops());
@@ -228,11 +261,97 @@ pub struct DatabaseStatus {
     pub magellan_schema_version: i32,
 }
 
+/// Returns the CFG of `function`, building and caching it on first use.
+///
+/// The CFG is extracted from the function's source file with
+/// [`CfgExtractor`](crate::treesitter::CfgExtractor), falling back to CFG
+/// blocks stored by Mirage. Returns `None` if neither is available.
+/// Extraction is skipped when a function with the same body was extracted
+/// before, even in an earlier index run.
+pub(crate) fn function_cfg(
+    store: &UnifiedGraphStore,
+    function: SymbolId,
+) -> crate::error::Result<Option<TestCfg>> {
+    if let Some(cfg) = store.cfg_cache.get(function) {
+        return Ok(Some(cfg));
+    }
+
+    let cfg = match extract_symbol_cfg(store, function)? {
+        Some(cfg) => cfg,
+        None => match load_test_cfg(&store.db_path, function.0)? {
+            Some(cfg) => cfg,
+            None => return Ok(None),
+        },
+    };
+
+    store.cfg_cache.insert(function, cfg.clone());
+    Ok(Some(cfg))
+}
+
+/// Builds the CFG of the function entity `function` by parsing its file.
+fn extract_symbol_cfg(
+    store: &UnifiedGraphStore,
+    function: SymbolId,
+) -> crate::error::Result<Option<TestCfg>> {
+    use crate::treesitter::CfgExtractor;
+
+    if !store.db_path.exists() {
+        return Ok(None);
+    }
+    let conn = crate::storage::open_read_only(&store.db_path)
+        .map_err(|e| crate::error::ForgeError::DatabaseError(format!("Open db: {}", e)))?;
+    let entity: Option<(String, Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT name, file_path, data FROM graph_entities WHERE id = ?1",
+            rusqlite::params![function.0],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .ok();
+    let Some((name, Some(file_path), data)) = entity else {
+        return Ok(None);
+    };
+    let data = data.and_then(|d| serde_json::from_str::<serde_json::Value>(&d).ok());
+    let offset = |key: &str| data.as_ref()?.get(key)?.as_u64();
+    let byte_start = offset("byte_start");
+
+    let path = store.codebase_path.join(&file_path);
+    let Some(lang) = CfgExtractor::detect_language(&path) else {
+        return Ok(None);
+    };
+    let Ok(source) = std::fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+
+    // An unchanged body has the control flow it had when last extracted
+    let body = byte_start
+        .zip(offset("byte_end"))
+        .and_then(|(start, end)| source.get(start as usize..end as usize))
+        .filter(|body| !body.is_empty())
+        .map(|body| (store.indexer_config.body_hash(body.as_bytes()), body));
+    if let Some((hash, body)) = &body {
+        if let Some(cfg) = store.cfg_cache.get_body(hash, body) {
+            return Ok(Some(cfg));
+        }
+    }
+
+    // Several functions may share a name; take the one nearest the symbol
+    store.cfg_cache.record_extraction();
+    let cfg = CfgExtractor::extract(&source, lang)?
+        .into_iter()
+        .filter(|f| f.name == name)
+        .min_by_key(|f| byte_start.map_or(0, |start| (f.start_byte as u64).abs_diff(start)))
+        .map(|f| f.cfg);
+    if let (Some(cfg), Some((hash, body))) = (&cfg, body) {
+        store.cfg_cache.insert_body(hash, body, cfg.clone());
+    }
+    Ok(cfg)
+}
+
 fn load_test_cfg(
     db_path: &std::path::Path,
     function_id: i64,
 ) -> crate::error::Result<Option<TestCfg>> {
-    use rusqlite::{params, Connection};
+    use rusqlite::params;
 
     let graph_db = db_path;
     if !graph_db.exists() {
@@ -257,7 +376,7 @@ fn load_test_cfg(
     let mut cfg = TestCfg::new(entry);
 
     let mut has_real_edges = false;
-    if let Ok(conn) = Connection::open(graph_db) {
+    if let Ok(conn) = crate::storage::open_read_only(graph_db) {
         let query = r#"
             SELECT source_idx, target_idx, edge_type
             FROM cfg_edges
@@ -347,6 +466,49 @@ mod tests {
         assert_eq!(doms.dominators.len(), 4);
     }
 
+    #[tokio::test]
+    async fn test_cfg_dot_without_cfg() {
+        let store = Arc::new(
+            UnifiedGraphStore::open(tempfile::tempdir().unwrap().path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let module = CfgModule::new(store);
+
+        let err = module.cfg_dot(SymbolId(1)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::ForgeError::CfgNotAvailable(SymbolId(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cfg_dot_of_indexed_function() {
+        use crate::graph::GraphModule;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub fn clamp(n: i32) -> i32 {\n    if n > 10 { 10 } else { n }\n}\n",
+        )
+        .unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open(dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let graph = GraphModule::new(Arc::clone(&store));
+        graph.index().await.unwrap();
+        let function = graph.find_symbol("clamp").await.unwrap()[0].id;
+
+        let dot = CfgModule::new(store).cfg_dot(function).await.unwrap();
+        assert!(dot.starts_with("digraph cfg {"));
+        assert!(dot.contains("fillcolor=green"));
+        assert!(dot.contains("peripheries=2"));
+        assert!(dot.matches(" -> ").count() >= 2);
+    }
+
     #[tokio::test]
     async fn test_loops_detection() {
         let store = Arc::new(
@@ -361,7 +523,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_paths_execute_no_db_returns_synthetic() {
+    async fn test_paths_execute_unknown_symbol_is_empty() {
         let store = Arc::new(
             UnifiedGraphStore::open(tempfile::tempdir().unwrap().path(), BackendKind::SQLite)
                 .await
@@ -370,9 +532,226 @@ mod tests {
         let module = CfgModule::new(store);
 
         let paths = module.paths(SymbolId(1)).execute().await.unwrap();
-        assert!(!paths.is_empty());
-        assert_eq!(paths[0].blocks.len(), 1);
-        assert_eq!(paths[0].blocks[0], BlockId(1));
+        assert!(paths.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paths_execute_enumerates_extracted_cfg() {
+        use crate::storage::{open_graph, GraphConfig, NodeSpec};
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = "fn check(n: i32) -> Result<i32, String> {\n    \
+                      if n < 0 {\n        return Err(\"negative\".to_string());\n    }\n    \
+                      if n > 100 {\n        return Ok(100);\n    }\n    Ok(n)\n}\n";
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), source).unwrap();
+
+        let store = Arc::new(
+            UnifiedGraphStore::open(dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let backend = open_graph(&store.db_path, &GraphConfig::sqlite()).unwrap();
+        let id = backend
+            .insert_node(NodeSpec {
+                kind: "Symbol".to_string(),
+                name: "check".to_string(),
+                file_path: Some("src/lib.rs".to_string()),
+                data: serde_json::json!({ "kind": "Function", "byte_start": 0 }),
+            })
+            .unwrap();
+        drop(backend);
+        let module = CfgModule::new(Arc::clone(&store));
+        let function = SymbolId(id);
+
+        let all = module.paths(function).execute().await.unwrap();
+        assert_eq!(all.len(), 3);
+
+        let errors = module.paths(function).error_only().execute().await.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].is_error());
+
+        let normal = module
+            .paths(function)
+            .normal_only()
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(normal.len(), 2);
+        assert!(normal.iter().all(|p| p.is_normal()));
+
+        let limited = module.paths(function).limit(1).execute().await.unwrap();
+        assert_eq!(limited.len(), 1);
+
+        let shortest = all.iter().map(|p| p.length).min().unwrap();
+        let short = module
+            .paths(function)
+            .max_length(shortest)
+            .execute()
+            .await
+            .unwrap();
+        assert!(!short.is_empty());
+        assert!(short.iter().all(|p| p.length <= shortest));
+
+        // Served from the cache once built
+        std::fs::remove_file(dir.path().join("src/lib.rs")).unwrap();
+        assert_eq!(module.paths(function).execute().await.unwrap(), all);
+    }
+
+    #[tokio::test]
+    async fn test_paths_through_and_avoiding_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open_with_path(
+                dir.path(),
+                dir.path().join("test-graph.db"),
+                BackendKind::SQLite,
+            )
+            .await
+            .unwrap(),
+        );
+        let function = SymbolId(7);
+        store.cfg_cache.insert(function, TestCfg::if_else());
+        let module = CfgModule::new(store);
+        let then_block = BlockId(1);
+
+        let through = module
+            .paths(function)
+            .through(then_block)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(through.len(), 1);
+        assert_eq!(through[0].blocks, vec![BlockId(0), then_block, BlockId(3)]);
+
+        let avoiding = module
+            .paths(function)
+            .avoiding(then_block)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(avoiding.len(), 1);
+        assert!(!avoiding[0].contains(then_block));
+
+        // Composes with the other filters
+        let composed = module
+            .paths(function)
+            .normal_only()
+            .max_length(3)
+            .through(then_block)
+            .avoiding(BlockId(2))
+            .limit(5)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(composed, through);
+        let both = module
+            .paths(function)
+            .through(then_block)
+            .through(BlockId(2))
+            .execute()
+            .await
+            .unwrap();
+        assert!(both.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_reports_statements_after_return() {
+        use crate::storage::{open_graph, GraphConfig, NodeSpec};
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = "fn early(n: i32) -> i32 {\n    if n < 0 {\n        return 0;\n        \
+                      println!(\"never\");\n    }\n    n\n}\n\n\
+                      fn plain(n: i32) -> i32 {\n    if n < 0 {\n        return 0;\n    }\n    n\n}\n";
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), source).unwrap();
+
+        let store = Arc::new(
+            UnifiedGraphStore::open(dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let backend = open_graph(&store.db_path, &GraphConfig::sqlite()).unwrap();
+        let insert = |name: &str, byte_start: usize| {
+            backend
+                .insert_node(NodeSpec {
+                    kind: "Symbol".to_string(),
+                    name: name.to_string(),
+                    file_path: Some("src/lib.rs".to_string()),
+                    data: serde_json::json!({ "kind": "Function", "byte_start": byte_start }),
+                })
+                .unwrap()
+        };
+        let early = insert("early", 0);
+        let plain = insert("plain", source.find("fn plain").unwrap());
+        drop(backend);
+        let module = CfgModule::new(Arc::clone(&store));
+
+        assert_eq!(module.unreachable(SymbolId(early)).await.unwrap().len(), 1);
+        assert!(module
+            .unreachable(SymbolId(plain))
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            module.unreachable(SymbolId(9999)).await,
+            Err(crate::error::ForgeError::CfgNotAvailable(_))
+        ));
+
+        let stats = module.cfg_stats(SymbolId(plain)).await.unwrap();
+        assert_eq!(stats.loop_count, 0);
+        assert_eq!(stats.branch_count, 1);
+        assert!(matches!(stats.path_count, PathCount::Exact(n) if n >= 2));
+        assert!(matches!(
+            module.cfg_stats(SymbolId(9999)).await,
+            Err(crate::error::ForgeError::CfgNotAvailable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_function_cfg_is_not_reparsed() {
+        use crate::graph::GraphModule;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let lib = dir.path().join("src/lib.rs");
+        let steady = "pub fn steady(n: i32) -> i32 {\n    if n > 0 { n } else { 0 }\n}\n";
+        std::fs::write(&lib, format!("{steady}\npub fn edited() {{}}\n")).unwrap();
+
+        let store = Arc::new(
+            UnifiedGraphStore::open(dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let graph = GraphModule::new(Arc::clone(&store));
+        let module = CfgModule::new(Arc::clone(&store));
+        let id = |name: &'static str| {
+            let graph = graph.clone();
+            async move { graph.find_symbol(name).await.unwrap()[0].id }
+        };
+        graph.index().await.unwrap();
+
+        let first = module.cfg_stats(id("steady").await).await.unwrap();
+        assert_eq!(store.cfg_cache.extractions(), 1);
+        module.cfg_stats(id("steady").await).await.unwrap();
+        module.paths(id("steady").await).execute().await.unwrap();
+        assert_eq!(store.cfg_cache.extractions(), 1);
+
+        // Re-indexing after an edit elsewhere reuses the unchanged body
+        std::fs::write(
+            &lib,
+            format!("{steady}\npub fn edited(n: i32) {{\n    while n > 0 {{}}\n}}\n"),
+        )
+        .unwrap();
+        graph.index().await.unwrap();
+        let second = module.cfg_stats(id("steady").await).await.unwrap();
+        assert_eq!(store.cfg_cache.extractions(), 1);
+        assert_eq!(first.branch_count, second.branch_count);
+
+        // The edited function is parsed again
+        let edited = module.cfg_stats(id("edited").await).await.unwrap();
+        assert_eq!(store.cfg_cache.extractions(), 2);
+        assert_eq!(edited.loop_count, 1);
     }
 
     fn make_cfg_fixture_db(db_path: &std::path::Path, fn_name: &str) -> i64 {
@@ -6,7 +6,7 @@ use crate::storage::UnifiedGraphStore;
 use crate::types::{BlockId, PathId, PathKind, SymbolId};
 use std::sync::Arc;
 
-use super::load_test_cfg;
+use super::function_cfg;
 
 // ---------------------------------------------------------------------------
 // Path
@@ -18,6 +18,8 @@ pub struct Path {
     pub kind: PathKind,
     pub blocks: Vec<BlockId>,
     pub length: usize,
+    /// Block that made this an error path, and why. Only set for error paths.
+    pub error: Option<(BlockId, String)>,
 }
 
 impl Path {
@@ -36,6 +38,7 @@ impl Path {
             kind: PathKind::Normal,
             blocks,
             length,
+            error: None,
         }
     }
 
@@ -54,9 +57,17 @@ impl Path {
             kind,
             blocks,
             length,
+            error: None,
         }
     }
 
+    /// Creates an error path whose classification was triggered by `site`.
+    pub fn with_error(blocks: Vec<BlockId>, site: BlockId, reason: impl Into<String>) -> Self {
+        let mut path = Self::with_kind(blocks, PathKind::Error);
+        path.error = Some((site, reason.into()));
+        path
+    }
+
     pub fn is_normal(&self) -> bool {
         self.kind == PathKind::Normal
     }
@@ -65,6 +76,12 @@ impl Path {
         self.kind == PathKind::Error
     }
 
+    /// Returns the block that caused this path to be classified as an error
+    /// path, with a short description (e.g. `return Err(..)`, `panic!`).
+    pub fn error_reason(&self) -> Option<(BlockId, String)> {
+        self.error.clone()
+    }
+
     pub fn contains(&self, block: BlockId) -> bool {
         self.blocks.contains(&block)
     }
@@ -90,6 +107,8 @@ pub struct PathBuilder {
     pub(super) error_only: bool,
     pub(super) max_length: Option<usize>,
     pub(super) limit: Option<usize>,
+    pub(super) through: Vec<BlockId>,
+    pub(super) avoiding: Vec<BlockId>,
 }
 
 impl PathBuilder {
@@ -115,32 +134,43 @@ impl PathBuilder {
         self
     }
 
-    pub async fn execute(self) -> crate::error::Result<Vec<Path>> {
-        if let (Some(symbol), Some(store)) = (&self.function, &self.store) {
-            if let Some(cfg) = load_test_cfg(&store.db_path, symbol.0)? {
-                let mut paths = cfg.enumerate_paths();
-                if let Some(max) = self.max_length {
-                    paths.retain(|p| p.blocks.len() <= max);
-                }
-                if let Some(limit) = self.limit {
-                    paths.truncate(limit);
-                }
-                return Ok(paths);
-            }
-            let _ = symbol;
-        }
+    /// Keeps only paths that pass through `block`. Repeated calls require
+    /// every given block.
+    pub fn through(mut self, block: BlockId) -> Self {
+        self.through.push(block);
+        self
+    }
 
-        if let Some(symbol) = &self.function {
-            let entry = BlockId(symbol.0);
-            Ok(vec![Path {
-                id: PathId([0; 16]),
-                kind: PathKind::Normal,
-                blocks: vec![entry],
-                length: 1,
-            }])
-        } else {
-            Ok(Vec::new())
+    /// Keeps only paths that never enter `block`. Repeated calls exclude
+    /// every given block.
+    pub fn avoiding(mut self, block: BlockId) -> Self {
+        self.avoiding.push(block);
+        self
+    }
+
+    /// Enumerates the paths through the function's CFG, applying the
+    /// kind, length, block and count filters. Returns no paths if no CFG is
+    /// available for the function.
+    pub async fn execute(self) -> crate::error::Result<Vec<Path>> {
+        let (Some(function), Some(store)) = (self.function, &self.store) else {
+            return Ok(Vec::new());
+        };
+        let Some(cfg) = function_cfg(store, function)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut paths = cfg.enumerate_paths();
+        paths.retain(|p| {
+            (!self.normal_only || p.is_normal())
+                && (!self.error_only || p.is_error())
+                && self.max_length.is_none_or(|max| p.length <= max)
+                && self.through.iter().all(|&block| p.contains(block))
+                && !self.avoiding.iter().any(|&block| p.contains(block))
+        });
+        if let Some(limit) = self.limit {
+            paths.truncate(limit);
         }
+        Ok(paths)
     }
 }
 
@@ -168,6 +198,18 @@ mod tests {
         assert_eq!(path.kind, PathKind::Error);
         assert!(!path.is_normal());
         assert!(path.is_error());
+        assert_eq!(path.error_reason(), None);
+    }
+
+    #[test]
+    fn test_path_with_error_reason() {
+        let path = Path::with_error(vec![BlockId(0), BlockId(1)], BlockId(1), "panics");
+
+        assert!(path.is_error());
+        assert_eq!(
+            path.error_reason(),
+            Some((BlockId(1), "panics".to_string()))
+        );
     }
 
     #[test]
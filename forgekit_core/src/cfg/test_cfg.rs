@@ -1,25 +1,32 @@
 use crate::types::BlockId;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Write;
 
 use super::dominators::DominatorTree;
 use super::paths::Path;
-use super::types::Loop;
+use super::types::{CfgStats, Loop, PathCount};
 
 #[derive(Clone, Debug)]
 pub struct TestCfg {
     pub entry: BlockId,
     pub exits: HashSet<BlockId>,
     pub error_blocks: HashSet<BlockId>,
+    /// Why each block in `error_blocks` was classified as an error site.
+    pub error_reasons: HashMap<BlockId, String>,
     pub successors: HashMap<BlockId, Vec<BlockId>>,
     pub predecessors: HashMap<BlockId, Vec<BlockId>>,
 }
 
 impl TestCfg {
+    /// The synthetic block that [`reverse`](Self::reverse) joins all exits to.
+    pub const VIRTUAL_EXIT: BlockId = BlockId(-1);
+
     pub fn new(entry: BlockId) -> Self {
         Self {
             entry,
             exits: HashSet::new(),
             error_blocks: HashSet::new(),
+            error_reasons: HashMap::new(),
             successors: HashMap::new(),
             predecessors: HashMap::new(),
         }
@@ -41,6 +48,104 @@ impl TestCfg {
         self
     }
 
+    /// Marks `block` as an error site and records why (e.g. `return Err(..)`).
+    ///
+    /// The first reason recorded for a block wins.
+    pub fn add_error_with_reason(
+        &mut self,
+        block: BlockId,
+        reason: impl Into<String>,
+    ) -> &mut Self {
+        self.error_blocks.insert(block);
+        self.error_reasons
+            .entry(block)
+            .or_insert_with(|| reason.into());
+        self
+    }
+
+    /// Returns the first error block on `blocks` together with its reason.
+    fn error_site(&self, blocks: &[BlockId]) -> Option<(BlockId, String)> {
+        blocks
+            .iter()
+            .find(|b| self.error_blocks.contains(b))
+            .map(|&b| {
+                let reason = self
+                    .error_reasons
+                    .get(&b)
+                    .cloned()
+                    .unwrap_or_else(|| "block marked as error exit".to_string());
+                (b, reason)
+            })
+    }
+
+    /// Renders the CFG as a GraphViz digraph.
+    ///
+    /// The entry block is filled green, exit blocks get a double border and
+    /// error blocks are filled red with their reason in the label. Blocks are
+    /// emitted in id order and edges in successor order, so the output is
+    /// stable across runs.
+    pub fn to_dot(&self) -> String {
+        let blocks = self.blocks();
+        let mut out = String::from("digraph cfg {\n    node [shape=box];\n");
+        for &block in &blocks {
+            let mut label = format!("bb{}", block.0);
+            let mut attrs = Vec::new();
+            if block == self.entry {
+                attrs.push("style=filled, fillcolor=green".to_string());
+            }
+            if self.exits.contains(&block) {
+                attrs.push("peripheries=2".to_string());
+            }
+            if self.error_blocks.contains(&block) {
+                if let Some(reason) = self.error_reasons.get(&block) {
+                    label = format!("{}\n{}", label, reason);
+                }
+                attrs.push("style=filled, fillcolor=red".to_string());
+            }
+            attrs.insert(0, format!("label={}", dot_quote(&label)));
+            let _ = writeln!(out, "    bb{} [{}];", block.0, attrs.join(", "));
+        }
+        for &block in &blocks {
+            for to in self.successors.get(&block).into_iter().flatten() {
+                let _ = writeln!(out, "    bb{} -> bb{};", block.0, to.0);
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Returns every block in the CFG: the entry and each block with an
+    /// edge or marked as an exit or error, in id order.
+    pub fn blocks(&self) -> BTreeSet<BlockId> {
+        let mut blocks: BTreeSet<BlockId> = BTreeSet::new();
+        blocks.insert(self.entry);
+        blocks.extend(self.exits.iter().copied());
+        blocks.extend(self.error_blocks.iter().copied());
+        for (from, tos) in &self.successors {
+            blocks.insert(*from);
+            blocks.extend(tos.iter().copied());
+        }
+        blocks
+    }
+
+    /// Returns the blocks that cannot be reached from the entry, in id
+    /// order, such as code after an unconditional return.
+    pub fn unreachable_blocks(&self) -> Vec<BlockId> {
+        let mut visited = HashSet::from([self.entry]);
+        let mut queue = VecDeque::from([self.entry]);
+        while let Some(block) = queue.pop_front() {
+            for &next in self.successors.get(&block).into_iter().flatten() {
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        self.blocks()
+            .into_iter()
+            .filter(|block| !visited.contains(block))
+            .collect()
+    }
+
     pub fn chain(start: i64, count: usize) -> Self {
         let mut cfg = Self::new(BlockId(start));
         for i in start..(start + count as i64 - 1) {
@@ -86,7 +191,11 @@ impl TestCfg {
         block: BlockId,
     ) {
         if self.exits.contains(&block) {
-            paths.push(Path::new(current.clone()));
+            let path = match self.error_site(current) {
+                Some((site, reason)) => Path::with_error(current.clone(), site, reason),
+                None => Path::new(current.clone()),
+            };
+            paths.push(path);
             return;
         }
         if visited.contains(&block) {
@@ -187,6 +296,131 @@ impl TestCfg {
         }
     }
 
+    /// Computes the dominance frontier of every block.
+    ///
+    /// The frontier of `b` is the set of blocks where `b`'s dominance
+    /// ends: blocks with a predecessor dominated by `b` that `b` does not
+    /// strictly dominate. Uses the algorithm of Cytron et al., walking up
+    /// the dominator tree from the predecessors of each join block. Every
+    /// block in the graph has an entry, empty if its frontier is.
+    pub fn dominance_frontiers(&self) -> HashMap<BlockId, HashSet<BlockId>> {
+        let dom = self.compute_dominators();
+        let mut frontiers: HashMap<BlockId, HashSet<BlockId>> = HashMap::new();
+        frontiers.insert(self.entry, HashSet::new());
+        for (from, tos) in &self.successors {
+            frontiers.entry(*from).or_default();
+            for to in tos {
+                frontiers.entry(*to).or_default();
+            }
+        }
+
+        for (&block, preds) in &self.predecessors {
+            if preds.len() < 2 {
+                continue;
+            }
+            let idom = dom.immediate_dominator(block);
+            for &pred in preds {
+                let mut runner = Some(pred);
+                while let Some(current) = runner {
+                    if Some(current) == idom {
+                        break;
+                    }
+                    frontiers.entry(current).or_default().insert(block);
+                    runner = dom.immediate_dominator(current);
+                }
+            }
+        }
+
+        frontiers
+    }
+
+    /// Returns the reverse graph, with every edge flipped.
+    ///
+    /// The reverse graph is entered at [`VIRTUAL_EXIT`](Self::VIRTUAL_EXIT),
+    /// a synthetic block with an edge to each exit, so graphs with several
+    /// exits have a single entry. The original entry is its only exit.
+    pub fn reverse(&self) -> TestCfg {
+        let mut reversed = TestCfg::new(Self::VIRTUAL_EXIT);
+        let mut exits: Vec<BlockId> = self.exits.iter().copied().collect();
+        exits.sort();
+        for exit in exits {
+            reversed.add_edge(Self::VIRTUAL_EXIT, exit);
+        }
+
+        let mut edges: Vec<(BlockId, BlockId)> = self
+            .successors
+            .iter()
+            .flat_map(|(from, tos)| tos.iter().map(move |to| (*from, *to)))
+            .collect();
+        edges.sort();
+        for (from, to) in edges {
+            reversed.add_edge(to, from);
+        }
+
+        reversed.add_exit(self.entry);
+        reversed.error_blocks = self.error_blocks.clone();
+        reversed.error_reasons = self.error_reasons.clone();
+        reversed
+    }
+
+    /// Computes the post-dominator tree: the dominator tree of the
+    /// [`reverse`](Self::reverse) graph.
+    ///
+    /// Block `a` post-dominates `b` if every path from `b` to an exit
+    /// passes through `a`. The root is [`VIRTUAL_EXIT`](Self::VIRTUAL_EXIT),
+    /// which is the immediate post-dominator of any block that can leave
+    /// through more than one exit.
+    pub fn compute_post_dominators(&self) -> DominatorTree {
+        self.reverse().compute_dominators()
+    }
+
+    /// Computes size metrics without enumerating paths.
+    ///
+    /// Paths are counted as by [`enumerate_paths`](Self::enumerate_paths),
+    /// ending at the first exit reached, but in time linear in the CFG.
+    pub fn stats(&self) -> CfgStats {
+        let loop_count = self.detect_loops().len();
+        let path_count = if loop_count > 0 {
+            PathCount::Unbounded
+        } else {
+            self.count_paths(self.entry, &mut HashMap::new(), &mut HashSet::new())
+                .map_or(PathCount::Unbounded, PathCount::Exact)
+        };
+        CfgStats {
+            node_count: self.blocks().len(),
+            edge_count: self.successors.values().map(Vec::len).sum(),
+            branch_count: self.successors.values().filter(|s| s.len() > 1).count(),
+            loop_count,
+            path_count,
+        }
+    }
+
+    /// Number of paths from `block` to an exit, or `None` on reaching a
+    /// cycle that [`detect_loops`](Self::detect_loops) missed.
+    fn count_paths(
+        &self,
+        block: BlockId,
+        counts: &mut HashMap<BlockId, u64>,
+        in_progress: &mut HashSet<BlockId>,
+    ) -> Option<u64> {
+        if self.exits.contains(&block) {
+            return Some(1);
+        }
+        if let Some(&count) = counts.get(&block) {
+            return Some(count);
+        }
+        if !in_progress.insert(block) {
+            return None;
+        }
+        let mut count = 0u64;
+        for &succ in self.successors.get(&block).into_iter().flatten() {
+            count = count.saturating_add(self.count_paths(succ, counts, in_progress)?);
+        }
+        in_progress.remove(&block);
+        counts.insert(block, count);
+        Some(count)
+    }
+
     pub fn detect_loops(&self) -> Vec<Loop> {
         let dom = self.compute_dominators();
         let mut loops = Vec::new();
@@ -228,6 +462,22 @@ impl TestCfg {
     }
 }
 
+/// Quotes `s` as a GraphViz string, escaping quotes, backslashes and newlines.
+pub(crate) fn dot_quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,6 +495,111 @@ mod tests {
         assert_eq!(cfg.successors.get(&BlockId(3)), Some(&vec![BlockId(4)]));
     }
 
+    #[test]
+    fn test_stats_of_fixtures() {
+        assert_eq!(
+            TestCfg::chain(0, 5).stats(),
+            CfgStats {
+                node_count: 5,
+                edge_count: 4,
+                branch_count: 0,
+                loop_count: 0,
+                path_count: PathCount::Exact(1),
+            }
+        );
+        assert_eq!(
+            TestCfg::if_else().stats(),
+            CfgStats {
+                node_count: 4,
+                edge_count: 4,
+                branch_count: 1,
+                loop_count: 0,
+                path_count: PathCount::Exact(2),
+            }
+        );
+        assert_eq!(
+            TestCfg::simple_loop().stats(),
+            CfgStats {
+                node_count: 4,
+                edge_count: 4,
+                branch_count: 1,
+                loop_count: 1,
+                path_count: PathCount::Unbounded,
+            }
+        );
+    }
+
+    #[test]
+    fn test_stats_path_count_matches_enumeration() {
+        // Three diamonds in a row, with an early exit from the first
+        let mut cfg = TestCfg::new(BlockId(0));
+        for d in 0..3 {
+            let base = d * 3;
+            cfg.add_edge(BlockId(base), BlockId(base + 1))
+                .add_edge(BlockId(base), BlockId(base + 2))
+                .add_edge(BlockId(base + 1), BlockId(base + 3))
+                .add_edge(BlockId(base + 2), BlockId(base + 3));
+        }
+        cfg.add_edge(BlockId(1), BlockId(20))
+            .add_exit(BlockId(9))
+            .add_exit(BlockId(20));
+
+        let stats = cfg.stats();
+        assert_eq!(stats.path_count, PathCount::Exact(9));
+        assert_eq!(
+            stats.path_count,
+            PathCount::Exact(cfg.enumerate_paths().len() as u64)
+        );
+        assert_eq!(stats.branch_count, 4);
+    }
+
+    #[test]
+    fn test_stats_path_count_saturates() {
+        // 70 diamonds in a row have 2^70 paths
+        let mut cfg = TestCfg::new(BlockId(0));
+        for d in 0..70 {
+            let base = d * 3;
+            cfg.add_edge(BlockId(base), BlockId(base + 1))
+                .add_edge(BlockId(base), BlockId(base + 2))
+                .add_edge(BlockId(base + 1), BlockId(base + 3))
+                .add_edge(BlockId(base + 2), BlockId(base + 3));
+        }
+        cfg.add_exit(BlockId(210));
+        assert_eq!(cfg.stats().path_count, PathCount::Exact(u64::MAX));
+    }
+
+    #[test]
+    fn test_to_dot_styles_and_order() {
+        let mut cfg = TestCfg::if_else();
+        cfg.add_error_with_reason(BlockId(2), "return Err(\"bad\")");
+
+        let expected = "digraph cfg {
+    node [shape=box];
+    bb0 [label=\"bb0\", style=filled, fillcolor=green];
+    bb1 [label=\"bb1\"];
+    bb2 [label=\"bb2\\nreturn Err(\\\"bad\\\")\", style=filled, fillcolor=red];
+    bb3 [label=\"bb3\", peripheries=2];
+    bb0 -> bb1;
+    bb0 -> bb2;
+    bb1 -> bb3;
+    bb2 -> bb3;
+}
+";
+        assert_eq!(cfg.to_dot(), expected);
+        assert_eq!(cfg.to_dot(), cfg.clone().to_dot());
+    }
+
+    #[test]
+    fn test_unreachable_blocks() {
+        assert!(TestCfg::if_else().unreachable_blocks().is_empty());
+        assert!(TestCfg::simple_loop().unreachable_blocks().is_empty());
+
+        let mut cfg = TestCfg::chain(0, 3);
+        cfg.add_edge(BlockId(7), BlockId(2));
+        cfg.add_exit(BlockId(5));
+        assert_eq!(cfg.unreachable_blocks(), vec![BlockId(5), BlockId(7)]);
+    }
+
     #[test]
     fn test_test_cfg_if_else() {
         let cfg = TestCfg::if_else();
@@ -314,6 +669,84 @@ mod tests {
         assert_eq!(dom.immediate_dominator(BlockId(3)), Some(BlockId(0)));
     }
 
+    #[test]
+    fn test_dominance_frontiers_if_else() {
+        let cfg = TestCfg::if_else();
+        let frontiers = cfg.dominance_frontiers();
+
+        let merge = HashSet::from([BlockId(3)]);
+        assert_eq!(frontiers[&BlockId(1)], merge);
+        assert_eq!(frontiers[&BlockId(2)], merge);
+        assert!(frontiers[&BlockId(0)].is_empty());
+        assert!(frontiers[&BlockId(3)].is_empty());
+    }
+
+    #[test]
+    fn test_dominance_frontiers_simple_loop() {
+        let cfg = TestCfg::simple_loop();
+        let frontiers = cfg.dominance_frontiers();
+
+        let header = HashSet::from([BlockId(1)]);
+        assert_eq!(frontiers[&BlockId(1)], header);
+        assert_eq!(frontiers[&BlockId(2)], header);
+        assert!(frontiers[&BlockId(0)].is_empty());
+        assert!(frontiers[&BlockId(3)].is_empty());
+    }
+
+    #[test]
+    fn test_reverse_joins_exits() {
+        let mut cfg = TestCfg::if_else();
+        cfg.exits.clear();
+        cfg.add_exit(BlockId(1)).add_exit(BlockId(2));
+        let reversed = cfg.reverse();
+
+        assert_eq!(reversed.entry, TestCfg::VIRTUAL_EXIT);
+        assert_eq!(
+            reversed.successors[&TestCfg::VIRTUAL_EXIT],
+            vec![BlockId(1), BlockId(2)]
+        );
+        assert_eq!(
+            reversed.successors[&BlockId(3)],
+            vec![BlockId(1), BlockId(2)]
+        );
+        assert_eq!(reversed.exits, HashSet::from([BlockId(0)]));
+
+        let post = cfg.compute_post_dominators();
+        assert_eq!(
+            post.immediate_dominator(BlockId(1)),
+            Some(TestCfg::VIRTUAL_EXIT)
+        );
+        assert_eq!(
+            post.immediate_dominator(BlockId(0)),
+            Some(TestCfg::VIRTUAL_EXIT)
+        );
+    }
+
+    #[test]
+    fn test_post_dominators_if_else() {
+        let cfg = TestCfg::if_else();
+        let post = cfg.compute_post_dominators();
+
+        assert_eq!(post.root, TestCfg::VIRTUAL_EXIT);
+        // Every path from any block passes through the merge block
+        for block in [0, 1, 2] {
+            assert_eq!(post.immediate_dominator(BlockId(block)), Some(BlockId(3)));
+        }
+        assert!(!post.dominates(BlockId(1), BlockId(0)));
+        assert!(!post.dominates(BlockId(2), BlockId(0)));
+    }
+
+    #[test]
+    fn test_post_dominators_simple_loop() {
+        let cfg = TestCfg::simple_loop();
+        let post = cfg.compute_post_dominators();
+
+        assert_eq!(post.immediate_dominator(BlockId(1)), Some(BlockId(3)));
+        assert_eq!(post.immediate_dominator(BlockId(2)), Some(BlockId(1)));
+        assert_eq!(post.immediate_dominator(BlockId(0)), Some(BlockId(1)));
+        assert!(post.dominates(BlockId(3), BlockId(2)));
+    }
+
     #[test]
     fn test_loops_simple_loop() {
         let cfg = TestCfg::simple_loop();
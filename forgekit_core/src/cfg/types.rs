@@ -45,6 +45,31 @@ impl Loop {
     }
 }
 
+/// Size metrics of a CFG, cheap to compute compared with enumerating its
+/// paths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CfgStats {
+    /// Blocks, as listed by [`TestCfg::blocks`](super::TestCfg::blocks)
+    pub node_count: usize,
+    /// Successor edges
+    pub edge_count: usize,
+    /// Blocks with more than one successor
+    pub branch_count: usize,
+    /// Loops found by [`TestCfg::detect_loops`](super::TestCfg::detect_loops)
+    pub loop_count: usize,
+    /// Entry-to-exit paths
+    pub path_count: PathCount,
+}
+
+/// Number of entry-to-exit paths through a CFG.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathCount {
+    /// The CFG is acyclic with this many paths, saturating at `u64::MAX`
+    Exact(u64),
+    /// The CFG contains loops, so paths can repeat blocks without bound
+    Unbounded,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,185 @@
+//! Circuit breaker for external tool integrations.
+//!
+//! Wraps calls into external tools (magellan, tree-sitter) so that a tool
+//! which keeps failing is short-circuited for a cooldown period instead of
+//! being retried on every file.
+
+use std::time::{Duration, Instant};
+
+use crate::error::{ForgeError, Result};
+
+/// Default number of consecutive failures before the breaker opens.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Default time the breaker stays open before allowing a trial call.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// State of a [`CircuitBreaker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls pass through normally.
+    Closed,
+    /// Calls are rejected until the cooldown elapses.
+    Open,
+    /// Cooldown elapsed; the next call is a trial that closes or re-opens the breaker.
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct BreakerInner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Consecutive-failure circuit breaker.
+///
+/// After `failure_threshold` consecutive failures the breaker opens and
+/// [`check`](Self::check) fails fast with [`ForgeError::BackendNotAvailable`].
+/// Once `cooldown` has elapsed it becomes half-open: one success closes it
+/// again, one failure re-opens it for another cooldown.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: String,
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: parking_lot::Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker for the named tool.
+    pub fn new(name: impl Into<String>, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name: name.into(),
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            inner: parking_lot::Mutex::new(BreakerInner::default()),
+        }
+    }
+
+    /// Creates a breaker with the default threshold and cooldown.
+    pub fn with_defaults(name: impl Into<String>) -> Self {
+        Self::new(name, DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> CircuitState {
+        let inner = self.inner.lock();
+        match inner.opened_at {
+            None => CircuitState::Closed,
+            Some(at) if at.elapsed() >= self.cooldown => CircuitState::HalfOpen,
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    /// Returns an error if the breaker is open; otherwise the call may proceed.
+    pub fn check(&self) -> Result<()> {
+        if self.state() == CircuitState::Open {
+            let failures = self.inner.lock().consecutive_failures;
+            return Err(ForgeError::BackendNotAvailable(format!(
+                "{} circuit open after {} consecutive failures; retrying after {:?} cooldown",
+                self.name, failures, self.cooldown
+            )));
+        }
+        Ok(())
+    }
+
+    /// Records a successful call, closing the breaker.
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock();
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+    }
+
+    /// Records a failed call, opening the breaker once the threshold is hit.
+    ///
+    /// A failure while half-open re-opens the breaker immediately.
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        if inner.opened_at.is_some() || inner.consecutive_failures >= self.failure_threshold {
+            if inner.opened_at.is_none() {
+                tracing::warn!(
+                    "{} circuit opened after {} consecutive failures",
+                    self.name,
+                    inner.consecutive_failures
+                );
+            }
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+
+    /// Records the outcome of `result` and passes it through unchanged.
+    pub fn observe<T, E>(&self, result: std::result::Result<T, E>) -> std::result::Result<T, E> {
+        match &result {
+            Ok(_) => self.record_success(),
+            Err(_) => self.record_failure(),
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failing() -> std::result::Result<(), String> {
+        Err("tool crashed".to_string())
+    }
+
+    #[test]
+    fn test_breaker_starts_closed() {
+        let breaker = CircuitBreaker::with_defaults("magellan");
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let breaker = CircuitBreaker::new("magellan", 3, Duration::from_secs(60));
+
+        for _ in 0..2 {
+            let _ = breaker.observe(failing());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let _ = breaker.observe(failing());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let err = breaker.check().unwrap_err();
+        assert!(matches!(err, ForgeError::BackendNotAvailable(_)));
+        assert!(err.to_string().contains("magellan"));
+    }
+
+    #[test]
+    fn test_breaker_success_resets_failure_count() {
+        let breaker = CircuitBreaker::new("magellan", 2, Duration::from_secs(60));
+
+        let _ = breaker.observe(failing());
+        let _ = breaker.observe(Ok::<_, String>(()));
+        let _ = breaker.observe(failing());
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_breaker_half_opens_after_cooldown() {
+        let breaker = CircuitBreaker::new("magellan", 2, Duration::from_millis(20));
+
+        let _ = breaker.observe(failing());
+        let _ = breaker.observe(failing());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.check().is_ok());
+
+        // A failed trial re-opens immediately.
+        let _ = breaker.observe(failing());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // A successful trial closes the breaker.
+        std::thread::sleep(Duration::from_millis(30));
+        let _ = breaker.observe(Ok::<_, String>(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
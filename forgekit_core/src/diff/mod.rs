@@ -1,11 +1,13 @@
 use std::path::PathBuf;
 
+#[derive(Debug, Clone)]
 pub struct UnifiedDiff {
     pub old_path: PathBuf,
     pub new_path: PathBuf,
     pub hunks: Vec<Hunk>,
 }
 
+#[derive(Debug, Clone)]
 pub struct Hunk {
     pub old_start: u32,
     pub old_count: u32,
@@ -1,17 +1,10 @@
 use std::path::Path;
 
+use crate::treesitter::{CfgExtractor, SupportedLanguage};
+
 pub(crate) fn language_from_extension(path: &Path) -> crate::types::Language {
-    match path.extension().and_then(|e| e.to_str()) {
-        Some("rs") => crate::types::Language::Rust,
-        Some("py") => crate::types::Language::Python,
-        Some("java") => crate::types::Language::Java,
-        Some("ts") | Some("tsx") => crate::types::Language::TypeScript,
-        Some("js") | Some("jsx") => crate::types::Language::JavaScript,
-        Some("c") | Some("h") => crate::types::Language::C,
-        Some("cpp") | Some("cc") | Some("cxx") | Some("hpp") => crate::types::Language::Cpp,
-        Some("go") => crate::types::Language::Go,
-        _ => crate::types::Language::Unknown("".to_string()),
-    }
+    crate::types::Language::from_path(path)
+        .unwrap_or_else(|| crate::types::Language::Unknown("".to_string()))
 }
 
 pub(crate) fn identifier_spans(
@@ -93,3 +86,105 @@ fn is_ident_char(b: u8, lang: &crate::types::Language) -> bool {
         crate::types::Language::Unknown(_) => b.is_ascii_alphanumeric() || b == b'_',
     }
 }
+
+/// Byte spans of the identifiers spelled `name` in `content`, found by
+/// parsing it with the grammar for `path`.
+///
+/// Unlike [`identifier_spans`], comments and string literals never match.
+/// Returns `None` if there is no grammar for the file or it fails to parse.
+pub(crate) fn syntax_identifier_spans(
+    content: &str,
+    name: &str,
+    path: &Path,
+) -> Option<Vec<(usize, usize)>> {
+    let tree = parse(content, path)?;
+    let mut spans = Vec::new();
+    visit(tree.root_node(), &mut |node| {
+        if node.kind().ends_with("identifier")
+            && node.child_count() == 0
+            && content.get(node.byte_range()) == Some(name)
+        {
+            spans.push((node.start_byte(), node.end_byte()));
+        }
+    });
+    Some(spans)
+}
+
+/// Byte spans of the Rust identifiers spelled `name` that bind or use a
+/// local variable: `let` and parameter bindings, and the unqualified uses
+/// that follow them in the same block.
+///
+/// Empty for other languages.
+pub(crate) fn local_binding_spans(content: &str, name: &str, path: &Path) -> Vec<(usize, usize)> {
+    if CfgExtractor::detect_language(path) != Some(SupportedLanguage::Rust) {
+        return Vec::new();
+    }
+    let Some(tree) = parse(content, path) else {
+        return Vec::new();
+    };
+    let is_name = |node: &tree_sitter::Node| {
+        node.kind() == "identifier" && content.get(node.byte_range()) == Some(name)
+    };
+
+    // Each binding shadows `name` from its end to the end of its block
+    let mut shadowed = Vec::new();
+    let mut spans = Vec::new();
+    visit(tree.root_node(), &mut |node| {
+        let (pattern, block) = match node.kind() {
+            "let_declaration" => (node.child_by_field_name("pattern"), node.parent()),
+            "parameter" | "closure_parameters" => {
+                let body = node
+                    .parent()
+                    .and_then(|p| p.parent())
+                    .and_then(|f| f.child_by_field_name("body"));
+                (Some(node), body)
+            }
+            _ => return,
+        };
+        let (Some(pattern), Some(block)) = (pattern, block) else {
+            return;
+        };
+        visit(pattern, &mut |binding| {
+            if is_name(&binding) {
+                spans.push((binding.start_byte(), binding.end_byte()));
+                shadowed.push((binding.end_byte(), block.end_byte()));
+            }
+        });
+    });
+    visit(tree.root_node(), &mut |node| {
+        let qualified = node
+            .parent()
+            .is_some_and(|p| p.kind() == "scoped_identifier");
+        let start = node.start_byte();
+        if is_name(&node)
+            && !qualified
+            && shadowed
+                .iter()
+                .any(|&(from, to)| from <= start && start < to)
+        {
+            spans.push((start, node.end_byte()));
+        }
+    });
+    spans
+}
+
+fn parse(content: &str, path: &Path) -> Option<tree_sitter::Tree> {
+    let language: tree_sitter::Language = match CfgExtractor::detect_language(path)? {
+        SupportedLanguage::C => tree_sitter_c::LANGUAGE.into(),
+        SupportedLanguage::Java => tree_sitter_java::LANGUAGE.into(),
+        SupportedLanguage::Python => tree_sitter_python::LANGUAGE.into(),
+        SupportedLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
+    };
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language).ok()?;
+    parser.parse(content, None)
+}
+
+/// Calls `f` on `node` and each of its descendants, in document order.
+fn visit<'t>(node: tree_sitter::Node<'t>, f: &mut impl FnMut(tree_sitter::Node<'t>)) {
+    f(node);
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        visit(child, f);
+    }
+}
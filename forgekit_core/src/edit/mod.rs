@@ -11,26 +11,31 @@ mod undo;
 pub use undo::{PendingUndo, UndoResult};
 
 use crate::error::{ForgeError, Result};
+use crate::types::Span;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use identifiers::language_from_extension;
 use undo::UndoableOp;
 
-use identifiers::identifier_spans;
+use identifiers::{identifier_spans, local_binding_spans, syntax_identifier_spans};
 
 /// Result of an edit operation.
 #[derive(Debug, Clone)]
 pub struct EditResult {
     pub success: bool,
     pub changed_files: Vec<PathBuf>,
+    /// What changed in each file whose content the edit changed.
+    pub diffs: Vec<FileDiff>,
     pub error: Option<String>,
 }
 
 impl EditResult {
-    pub fn success(files: Vec<PathBuf>) -> Self {
+    pub fn success(files: Vec<PathBuf>, diffs: Vec<FileDiff>) -> Self {
         Self {
             success: true,
             changed_files: files,
+            diffs,
             error: None,
         }
     }
@@ -39,11 +44,65 @@ impl EditResult {
         Self {
             success: false,
             changed_files: Vec::new(),
+            diffs: Vec::new(),
             error: Some(error),
         }
     }
 }
 
+/// The change an edit made to one file.
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    /// The file, relative to the codebase root.
+    pub path: PathBuf,
+    /// Content before the edit, empty for a new file.
+    pub original: String,
+    /// Content after the edit.
+    pub new: String,
+    /// The changed lines, with three lines of context.
+    pub hunks: Vec<crate::diff::Hunk>,
+}
+
+impl FileDiff {
+    pub fn new(path: PathBuf, original: String, new: String) -> Self {
+        let hunks = crate::diff::UnifiedDiff::generate(&original, &new, "", "").hunks;
+        Self {
+            path,
+            original,
+            new,
+            hunks,
+        }
+    }
+
+    /// Renders the diff as unified diff text, with `a/` and `b/` paths.
+    pub fn to_unified(&self) -> String {
+        crate::diff::UnifiedDiff {
+            old_path: Path::new("a").join(&self.path),
+            new_path: Path::new("b").join(&self.path),
+            hunks: self.hunks.clone(),
+        }
+        .render()
+    }
+}
+
+/// A rename that has been computed but not yet written.
+///
+/// Returned by [`EditModule::rename_symbol_in_scope`] so the change can be
+/// reviewed; pass it to [`EditModule::apply_rename`] to write it.
+#[derive(Debug, Clone)]
+pub struct RenamePreview {
+    /// Unified diff of every changed file, in path order.
+    pub diff: String,
+    rewrites: Vec<FileRewrite>,
+}
+
+impl RenamePreview {
+    /// Files the rename changes, relative to the codebase root.
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        self.rewrites.iter().map(|r| r.relative.clone()).collect()
+    }
+}
+
 /// Edit module for span-safe refactoring.
 pub struct EditModule {
     store: std::sync::Arc<crate::storage::UnifiedGraphStore>,
@@ -92,7 +151,8 @@ impl EditModule {
         self.push_undo(UndoableOp::CreateFile {
             path: path.to_path_buf(),
         });
-        Ok(EditResult::success(vec![path.to_path_buf()]))
+        let diff = FileDiff::new(path.to_path_buf(), String::new(), content.to_string());
+        Ok(EditResult::success(vec![path.to_path_buf()], vec![diff]))
     }
 
     pub async fn create_directory(&self, path: &Path) -> Result<EditResult> {
@@ -104,7 +164,7 @@ impl EditModule {
         self.push_undo(UndoableOp::CreateDirectory {
             path: path.to_path_buf(),
         });
-        Ok(EditResult::success(vec![path.to_path_buf()]))
+        Ok(EditResult::success(vec![path.to_path_buf()], Vec::new()))
     }
 
     pub async fn write_file(&self, path: &Path, content: &str) -> Result<EditResult> {
@@ -118,33 +178,107 @@ impl EditModule {
             tokio::fs::create_dir_all(parent).await?;
         }
         tokio::fs::write(&resolved, content).await?;
+        let diff = FileDiff::new(
+            path.to_path_buf(),
+            previous.clone().unwrap_or_default(),
+            content.to_string(),
+        );
         self.push_undo(UndoableOp::WriteFile {
             path: path.to_path_buf(),
             previous,
         });
-        Ok(EditResult::success(vec![path.to_path_buf()]))
+        Ok(EditResult::success(vec![path.to_path_buf()], vec![diff]))
     }
 
+    /// Applies a single operation; see [`apply_all`](Self::apply_all).
     pub async fn apply(&mut self, op: EditOperation) -> Result<()> {
-        match op {
-            EditOperation::Replace {
-                file_path,
-                start,
-                end,
-                new_content,
-            } => {
-                splice::patch::replace_span(&file_path, start, end, &new_content).map_err(|e| {
-                    ForgeError::DatabaseError(format!("Splice replace failed: {}", e))
-                })?;
-                Ok(())
+        self.apply_all(vec![op]).await.map(|_| ())
+    }
+
+    /// Applies `ops` together, writing each file once.
+    ///
+    /// Spans are byte offsets into the files as they are before any of
+    /// `ops`, so each file's replacements are applied from the highest
+    /// start down. Nothing is written if a span is out of range, splits a
+    /// UTF-8 character, or overlaps another span in the same file;
+    /// adjacent spans are fine.
+    pub async fn apply_all(&self, ops: Vec<EditOperation>) -> Result<EditResult> {
+        let mut by_file: BTreeMap<PathBuf, Vec<(Span, String)>> = BTreeMap::new();
+        for op in ops {
+            match op {
+                EditOperation::Replace {
+                    file_path,
+                    span,
+                    new_content,
+                } => by_file
+                    .entry(file_path)
+                    .or_default()
+                    .push((span, new_content)),
             }
         }
+
+        let mut rewrites = Vec::new();
+        for (relative, mut replacements) in by_file {
+            let path = self.validate_relative_path(&relative)?;
+            let original = std::fs::read(&path)?;
+            let content = String::from_utf8(original.clone()).map_err(|_| {
+                ForgeError::Parse(format!("{} is not valid UTF-8", relative.display()))
+            })?;
+
+            replacements.sort_by_key(|(span, _)| std::cmp::Reverse((span.start, span.end)));
+            let mut patched = content.clone();
+            // Start of the span applied before this one, further into the file
+            let mut following: Option<u32> = None;
+            for (span, new_content) in &replacements {
+                let invalid = |reason: String| ForgeError::InvalidSpan {
+                    file: relative.clone(),
+                    span: *span,
+                    reason,
+                };
+                let (start, end) = (span.start as usize, span.end as usize);
+                if start > end {
+                    return Err(invalid("start is after end".to_string()));
+                }
+                if end > content.len() {
+                    return Err(invalid(format!(
+                        "ends past the end of the file ({} bytes)",
+                        content.len()
+                    )));
+                }
+                if !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+                    return Err(invalid("splits a UTF-8 character".to_string()));
+                }
+                if following.is_some_and(|next| span.end > next) {
+                    return Err(ForgeError::EditConflict {
+                        file: relative.clone(),
+                        span: *span,
+                    });
+                }
+                following = Some(span.start);
+                patched.replace_range(start..end, new_content);
+            }
+
+            if patched != content {
+                rewrites.push(FileRewrite {
+                    relative,
+                    path,
+                    original,
+                    content: patched.into_bytes(),
+                });
+            }
+        }
+
+        write_all_or_restore(&rewrites, |path, bytes| std::fs::write(path, bytes))?;
+        Ok(EditResult::success(
+            rewrites.iter().map(|r| r.relative.clone()).collect(),
+            rewrites.iter().map(FileRewrite::diff).collect(),
+        ))
     }
 
     pub async fn patch_symbol(&self, symbol: &str, replacement: &str) -> Result<EditResult> {
         let db_path = self.store.db_path.clone();
         if !db_path.exists() {
-            return Err(ForgeError::DatabaseError(
+            return Err(ForgeError::BackendNotAvailable(
                 "graph DB not found; run forge.graph().index() first".to_string(),
             ));
         }
@@ -158,8 +292,126 @@ impl EditModule {
         replacement: &str,
         db_path: &Path,
     ) -> Result<EditResult> {
-        let matches = llmgrep::forge::search_symbols(symbol, db_path, 50)
-            .map_err(|e| ForgeError::DatabaseError(format!("Symbol search failed: {}", e)))?;
+        let spans = self.graph_symbol_spans(symbol, db_path)?;
+        if spans.is_empty() {
+            return self.patch_symbol_by_search(symbol, replacement, db_path);
+        }
+
+        let mut rewrites = Vec::new();
+        for (relative, file_spans) in spans {
+            let path = self.store.codebase_path.join(&relative);
+            let original = std::fs::read(&path)?;
+            let content = String::from_utf8_lossy(&original).into_owned();
+
+            // Spans from a stale index may no longer cover the symbol; refuse
+            // the whole patch rather than apply it to only some files.
+            let mut patched = content.clone();
+            for &(start, end) in file_spans.iter().rev() {
+                match content.get(start..end) {
+                    Some(text) if text.contains(symbol) => {
+                        patched.replace_range(start..end, replacement);
+                    }
+                    _ => {
+                        return Err(ForgeError::InvalidSpan {
+                            file: relative,
+                            span: Span {
+                                start: start as u32,
+                                end: end as u32,
+                            },
+                            reason: format!(
+                                "recorded span of {} is stale; re-index before patching",
+                                symbol
+                            ),
+                        });
+                    }
+                }
+            }
+            if patched == content {
+                continue;
+            }
+            rewrites.push(FileRewrite {
+                relative,
+                path,
+                original,
+                content: patched.into_bytes(),
+            });
+        }
+
+        if rewrites.is_empty() {
+            return self.patch_symbol_by_search(symbol, replacement, db_path);
+        }
+
+        write_all_or_restore(&rewrites, |path, bytes| std::fs::write(path, bytes))?;
+        Ok(EditResult::success(
+            rewrites.iter().map(|r| r.relative.clone()).collect(),
+            rewrites.iter().map(FileRewrite::diff).collect(),
+        ))
+    }
+
+    /// Byte spans of the symbols named exactly `symbol`, by file relative
+    /// to the codebase root. Spans nested in another span of the same
+    /// file are dropped, so each definition is replaced once.
+    fn graph_symbol_spans(
+        &self,
+        symbol: &str,
+        db_path: &Path,
+    ) -> Result<std::collections::BTreeMap<PathBuf, Vec<(usize, usize)>>> {
+        let conn = crate::storage::open_read_only(db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT file_path, data FROM graph_entities \
+             WHERE kind = 'Symbol' AND name = ?1",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![symbol], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })?;
+
+        let mut found: std::collections::BTreeMap<PathBuf, std::collections::BTreeSet<_>> =
+            std::collections::BTreeMap::new();
+        for (file_path, data) in rows.flatten() {
+            let (Some(file_path), Some(data)) = (file_path, data) else {
+                continue;
+            };
+            let Ok(data) = serde_json::from_str::<serde_json::Value>(&data) else {
+                continue;
+            };
+            let offset = |key: &str| data.get(key).and_then(|v| v.as_u64());
+            if let (Some(start), Some(end)) = (offset("byte_start"), offset("byte_end")) {
+                if start < end {
+                    found
+                        .entry(self.relative_path(Path::new(&file_path)))
+                        .or_default()
+                        .insert((start as usize, end as usize));
+                }
+            }
+        }
+
+        Ok(found
+            .into_iter()
+            .map(|(file, spans)| {
+                let mut outer: Vec<(usize, usize)> = Vec::new();
+                for (start, end) in spans {
+                    match outer.last_mut() {
+                        Some(last) if start < last.1 => last.1 = last.1.max(end),
+                        _ => outer.push((start, end)),
+                    }
+                }
+                (file, outer)
+            })
+            .collect())
+    }
+
+    /// Patches `symbol` by finding it in the text of the files the symbol
+    /// search returns. Used when the graph has no location for it.
+    fn patch_symbol_by_search(
+        &self,
+        symbol: &str,
+        replacement: &str,
+        db_path: &Path,
+    ) -> Result<EditResult> {
+        let matches = llmgrep::forge::search_symbols(symbol, db_path, 50)?;
 
         let files: std::collections::HashSet<PathBuf> = matches
             .iter()
@@ -167,10 +419,17 @@ impl EditModule {
             .collect();
 
         let mut changed_files = Vec::new();
+        let mut diffs = Vec::new();
         for file_path in files {
             let full_path = self.store.codebase_path.join(&file_path);
+            let original = read_lossy(&full_path);
             match splice::forge::patch_symbol_in_file(&full_path, symbol, replacement, db_path) {
                 Ok(_) => {
+                    diffs.push(FileDiff::new(
+                        self.relative_path(&file_path),
+                        original,
+                        read_lossy(&full_path),
+                    ));
                     changed_files.push(file_path);
                 }
                 Err(e) => {
@@ -186,13 +445,13 @@ impl EditModule {
             )));
         }
 
-        Ok(EditResult::success(changed_files))
+        Ok(EditResult::success(changed_files, diffs))
     }
 
     pub async fn rename_symbol(&self, old_name: &str, new_name: &str) -> Result<EditResult> {
         let db_path = self.store.db_path.clone();
         if !db_path.exists() {
-            return Err(ForgeError::DatabaseError(
+            return Err(ForgeError::BackendNotAvailable(
                 "graph DB not found; run forge.graph().index() first".to_string(),
             ));
         }
@@ -207,7 +466,7 @@ impl EditModule {
         db_path: &Path,
     ) -> Result<EditResult> {
         let mut graph = magellan::CodeGraph::open(db_path)
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to open graph: {}", e)))?;
+            .map_err(|e| ForgeError::from_graph("Failed to open graph", e))?;
 
         let mut affected_files: std::collections::HashSet<std::path::PathBuf> =
             std::collections::HashSet::new();
@@ -220,7 +479,7 @@ impl EditModule {
 
         let file_nodes = graph
             .all_file_nodes()
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to get file nodes: {}", e)))?;
+            .map_err(|e| ForgeError::from_graph("Failed to get file nodes", e))?;
 
         for file_path in file_nodes.keys() {
             if let Ok(call_facts) = graph.callers_of_symbol(file_path, old_name) {
@@ -271,37 +530,230 @@ impl EditModule {
             )));
         }
 
-        let mut changed_files = Vec::new();
-        let by_file = splice::graph::rename::group_references_by_file(&all_refs);
+        // Phase 1: compute every new file in memory, so a bad span aborts
+        // the rename before anything is written
+        let mut by_file: Vec<_> = splice::graph::rename::group_references_by_file(&all_refs)
+            .into_iter()
+            .collect();
+        by_file.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut rewrites = Vec::new();
+        let mut seen = std::collections::HashSet::new();
         for (file_path, refs) in by_file {
             let full_path = self.store.codebase_path.join(&file_path);
-            match splice::graph::rename::apply_replacements_in_file(
-                &full_path, old_name, new_name, &refs,
-            ) {
-                Ok(count) if count > 0 => {
-                    changed_files.push(file_path);
-                }
-                Ok(_) => {}
-                Err(e) => {
-                    tracing::warn!("Failed to rename in {}: {}", file_path.display(), e);
-                }
+            // The graph can name one file by both absolute and relative path
+            if !seen.insert(full_path.clone()) {
+                continue;
+            }
+            let original = std::fs::read(&full_path)?;
+            let mut content = original.clone();
+            for reference in &refs {
+                content = splice::graph::rename::replace_at_span(
+                    &content,
+                    reference,
+                    new_name.as_bytes(),
+                )
+                .map_err(|e| {
+                    ForgeError::ToolError(format!(
+                        "Failed to rename in {}: {}",
+                        file_path.display(),
+                        e
+                    ))
+                })?;
+            }
+            if content != original {
+                rewrites.push(FileRewrite {
+                    relative: file_path,
+                    path: full_path,
+                    original,
+                    content,
+                });
             }
         }
 
-        if changed_files.is_empty() {
+        if rewrites.is_empty() {
             return Err(ForgeError::SymbolNotFound(format!(
                 "Symbol '{}' references found but no files changed",
                 old_name
             )));
         }
 
-        Ok(EditResult::success(changed_files))
+        // Phase 2: write them all, or none
+        write_all_or_restore(&rewrites, |path, bytes| std::fs::write(path, bytes))?;
+
+        Ok(EditResult::success(
+            rewrites.iter().map(|r| r.relative.clone()).collect(),
+            rewrites.iter().map(FileRewrite::diff).collect(),
+        ))
+    }
+
+    /// Computes a rename of `old_name` to `new_name` limited to one scope.
+    ///
+    /// Only identifiers inside the definition of `scope` are renamed, so a
+    /// local with the same name in another function is left alone. If
+    /// `scope` is itself named `old_name`, its references and call sites
+    /// recorded in the graph are renamed too. Identifiers are found with
+    /// the tree-sitter parse, never in comments or strings.
+    ///
+    /// Nothing is written; review the returned diff and pass the preview
+    /// to [`apply_rename`](Self::apply_rename).
+    pub async fn rename_symbol_in_scope(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        scope: crate::types::SymbolId,
+    ) -> Result<RenamePreview> {
+        let db_path = self.store.db_path.clone();
+        if !db_path.exists() {
+            return Err(ForgeError::BackendNotAvailable(
+                "graph DB not found; run forge.graph().index() first".to_string(),
+            ));
+        }
+
+        let conn = crate::storage::open_read_only(&db_path)?;
+        let entity: Option<(String, Option<String>, Option<String>)> = conn
+            .query_row(
+                "SELECT name, file_path, data FROM graph_entities WHERE id = ?1",
+                rusqlite::params![scope.0],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .ok();
+        let Some((scope_name, Some(scope_file), data)) = entity else {
+            return Err(ForgeError::SymbolNotFound(format!(
+                "Symbol {} not found",
+                scope
+            )));
+        };
+        let data: serde_json::Value = data
+            .and_then(|d| serde_json::from_str(&d).ok())
+            .unwrap_or_default();
+        let span_field = |field: &str| data.get(field).and_then(|v| v.as_u64());
+        let (Some(scope_start), Some(scope_end)) =
+            (span_field("byte_start"), span_field("byte_end"))
+        else {
+            return Err(ForgeError::SymbolNotFound(format!(
+                "Symbol {} has no recorded span",
+                scope
+            )));
+        };
+
+        // Byte ranges to rename within, by file. Graph references are
+        // matched by name, so locals that shadow the symbol are skipped.
+        let scope_path = self.relative_path(Path::new(&scope_file));
+        let scope_range = (scope_start as usize, scope_end as usize);
+        let mut ranges: std::collections::BTreeMap<PathBuf, Vec<(usize, usize)>> =
+            std::collections::BTreeMap::new();
+        ranges
+            .entry(scope_path.clone())
+            .or_default()
+            .push(scope_range);
+
+        if scope_name == old_name {
+            let mut graph = magellan::CodeGraph::open(&db_path)
+                .map_err(|e| ForgeError::from_graph("Failed to open graph", e))?;
+            if let Ok(refs) = graph.references_to_symbol(scope.0) {
+                for r in refs {
+                    ranges
+                        .entry(self.relative_path(&r.file_path))
+                        .or_default()
+                        .push((r.byte_start, r.byte_end));
+                }
+            }
+            // Calls are recorded by name, so check they resolve to `scope`
+            if let Ok(Some(id)) = graph.symbol_id_by_name(&scope_file, old_name) {
+                if id == scope.0 {
+                    for call in graph
+                        .callers_of_symbol(&scope_file, old_name)
+                        .unwrap_or_default()
+                    {
+                        ranges
+                            .entry(self.relative_path(&call.file_path))
+                            .or_default()
+                            .push((call.byte_start, call.byte_end));
+                    }
+                }
+            }
+        }
+
+        let mut rewrites = Vec::new();
+        let mut diff = String::new();
+        for (relative, file_ranges) in ranges {
+            let path = self.store.codebase_path.join(&relative);
+            let original = std::fs::read(&path)?;
+            let content = String::from_utf8_lossy(&original).into_owned();
+            let Some(spans) = syntax_identifier_spans(&content, old_name, &relative) else {
+                continue;
+            };
+
+            let locals = local_binding_spans(&content, old_name, &relative);
+            let in_scope = |start: usize, end: usize| {
+                relative == scope_path && scope_range.0 <= start && end <= scope_range.1
+            };
+
+            let mut renamed = content.clone();
+            for (start, end) in spans.into_iter().rev() {
+                let referenced = file_ranges.iter().any(|&(s, e)| s <= start && end <= e);
+                let local = !in_scope(start, end) && locals.contains(&(start, end));
+                if referenced && !local {
+                    renamed.replace_range(start..end, new_name);
+                }
+            }
+            if renamed == content {
+                continue;
+            }
+
+            let rewrite = FileRewrite {
+                relative,
+                path,
+                original,
+                content: renamed.into_bytes(),
+            };
+            diff.push_str(&rewrite.diff().to_unified());
+            rewrites.push(rewrite);
+        }
+
+        if rewrites.is_empty() {
+            return Err(ForgeError::SymbolNotFound(format!(
+                "'{}' not found in the scope of symbol {}",
+                old_name, scope
+            )));
+        }
+
+        Ok(RenamePreview { diff, rewrites })
+    }
+
+    /// Writes a rename computed by
+    /// [`rename_symbol_in_scope`](Self::rename_symbol_in_scope).
+    ///
+    /// Fails without writing anything if a file has changed since the
+    /// preview was made. If a write fails, files already written are
+    /// restored.
+    pub async fn apply_rename(&self, preview: &RenamePreview) -> Result<EditResult> {
+        for rewrite in &preview.rewrites {
+            if std::fs::read(&rewrite.path)? != rewrite.original {
+                return Err(ForgeError::ToolError(format!(
+                    "{} changed since the rename was previewed",
+                    rewrite.relative.display()
+                )));
+            }
+        }
+        write_all_or_restore(&preview.rewrites, |path, bytes| std::fs::write(path, bytes))?;
+        Ok(EditResult::success(
+            preview.changed_files(),
+            preview.rewrites.iter().map(FileRewrite::diff).collect(),
+        ))
+    }
+
+    /// Returns `path` relative to the codebase root if it is inside it.
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.store.codebase_path)
+            .unwrap_or(path)
+            .to_path_buf()
     }
 
     pub async fn delete_symbol(&self, file_path: &Path, symbol: &str) -> Result<EditResult> {
         let db_path = self.store.db_path.clone();
         if !db_path.exists() {
-            return Err(ForgeError::DatabaseError(
+            return Err(ForgeError::BackendNotAvailable(
                 "graph DB not found; run forge.graph().index() first".to_string(),
             ));
         }
@@ -315,8 +767,16 @@ impl EditModule {
         db_path: &Path,
     ) -> Result<EditResult> {
         let full_path = self.store.codebase_path.join(file_path);
+        let original = read_lossy(&full_path);
         match splice::forge::delete_symbol_from_file(&full_path, symbol, db_path) {
-            Ok(result) => Ok(EditResult::success(vec![result.file])),
+            Ok(result) => {
+                let diff = FileDiff::new(
+                    self.relative_path(file_path),
+                    original,
+                    read_lossy(&full_path),
+                );
+                Ok(EditResult::success(vec![result.file], vec![diff]))
+            }
             Err(e) => Err(ForgeError::DatabaseError(format!("Delete failed: {}", e))),
         }
     }
@@ -328,7 +788,7 @@ impl EditModule {
     ) -> Result<splice::forge::SymbolSpan> {
         let db_path = self.store.db_path.clone();
         if !db_path.exists() {
-            return Err(ForgeError::DatabaseError(
+            return Err(ForgeError::BackendNotAvailable(
                 "graph DB not found; run forge.graph().index() first".to_string(),
             ));
         }
@@ -342,11 +802,15 @@ impl EditModule {
 }
 
 /// An edit operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EditOperation {
+    /// Replace the bytes `span.start..span.end` of a file
     Replace {
+        /// The file, relative to the codebase root
         file_path: PathBuf,
-        start: usize,
-        end: usize,
+        /// Byte range to replace; must fall on UTF-8 character boundaries
+        span: Span,
+        /// Text written in place of the range
         new_content: String,
     },
 }
@@ -438,6 +902,55 @@ fn simple_word_replace(content: &str, old: &str, new: &str) -> String {
     result
 }
 
+/// A file's content before and after an edit.
+#[derive(Debug, Clone)]
+struct FileRewrite {
+    relative: PathBuf,
+    path: PathBuf,
+    original: Vec<u8>,
+    content: Vec<u8>,
+}
+
+impl FileRewrite {
+    fn diff(&self) -> FileDiff {
+        FileDiff::new(
+            self.relative.clone(),
+            String::from_utf8_lossy(&self.original).into_owned(),
+            String::from_utf8_lossy(&self.content).into_owned(),
+        )
+    }
+}
+
+/// Reads `path` as text for a diff, empty if it cannot be read.
+fn read_lossy(path: &Path) -> String {
+    std::fs::read(path)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default()
+}
+
+/// Writes every rewrite with `write`. If one fails, the files already
+/// written are restored to their original content and the error returned.
+fn write_all_or_restore(
+    rewrites: &[FileRewrite],
+    mut write: impl FnMut(&Path, &[u8]) -> std::io::Result<()>,
+) -> Result<()> {
+    for (written, rewrite) in rewrites.iter().enumerate() {
+        if let Err(e) = write(&rewrite.path, &rewrite.content) {
+            for done in &rewrites[..written] {
+                if let Err(restore) = write(&done.path, &done.original) {
+                    tracing::error!("Failed to restore {}: {}", done.path.display(), restore);
+                }
+            }
+            return Err(ForgeError::ToolError(format!(
+                "Failed to write {}: {}; earlier files were restored",
+                rewrite.relative.display(),
+                e
+            )));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -452,17 +965,144 @@ mod tests {
     fn test_edit_operation_replace() {
         let _op = EditOperation::Replace {
             file_path: PathBuf::from("test.rs"),
-            start: 10,
-            end: 20,
+            span: Span { start: 10, end: 20 },
             new_content: String::from("test"),
         };
     }
 
+    fn replace(file: &str, start: u32, end: u32, new_content: &str) -> EditOperation {
+        EditOperation::Replace {
+            file_path: PathBuf::from(file),
+            span: Span { start, end },
+            new_content: new_content.to_string(),
+        }
+    }
+
+    async fn edit_module_in(dir: &Path) -> EditModule {
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                dir,
+                dir.join("test.db"),
+                crate::storage::BackendKind::default(),
+            )
+            .await
+            .unwrap(),
+        );
+        EditModule::new(store)
+    }
+
+    #[tokio::test]
+    async fn test_apply_replaces_span() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("lib.rs"), "fn old() {}\n").unwrap();
+        let mut edit = edit_module_in(temp.path()).await;
+
+        edit.apply(replace("lib.rs", 3, 6, "new")).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("lib.rs")).unwrap(),
+            "fn new() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_all_adjacent_spans_in_any_order() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("lib.rs"), "let ab = a + b;\n").unwrap();
+        let edit = edit_module_in(temp.path()).await;
+
+        // Offsets refer to the original text, whatever the queue order
+        let result = edit
+            .apply_all(vec![
+                replace("lib.rs", 4, 5, "left_"),
+                replace("lib.rs", 9, 10, "left"),
+                replace("lib.rs", 5, 6, "right"),
+                replace("lib.rs", 13, 14, "right"),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("lib.rs")).unwrap(),
+            "let left_right = left + right;\n"
+        );
+        assert_eq!(result.changed_files, vec![PathBuf::from("lib.rs")]);
+    }
+
+    #[tokio::test]
+    async fn test_apply_all_rejects_overlapping_spans() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = "fn old() {}\n";
+        std::fs::write(temp.path().join("lib.rs"), source).unwrap();
+        std::fs::write(temp.path().join("other.rs"), source).unwrap();
+        let edit = edit_module_in(temp.path()).await;
+
+        let err = edit
+            .apply_all(vec![
+                replace("other.rs", 3, 6, "new"),
+                replace("lib.rs", 3, 6, "new"),
+                replace("lib.rs", 5, 8, "x"),
+            ])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ForgeError::EditConflict { ref file, span } if file == Path::new("lib.rs") && span.start == 3
+        ));
+        // Nothing was written, not even the valid file
+        for file in ["lib.rs", "other.rs"] {
+            assert_eq!(
+                std::fs::read_to_string(temp.path().join(file)).unwrap(),
+                source
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_rejects_invalid_spans() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = "// héllo\n";
+        std::fs::write(temp.path().join("lib.rs"), source).unwrap();
+        let mut edit = edit_module_in(temp.path()).await;
+
+        // 'é' occupies bytes 4..6
+        for (start, end) in [(4, 5), (5, 7), (8, 4), (0, 100)] {
+            let err = edit
+                .apply(replace("lib.rs", start, end, "x"))
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(err, ForgeError::InvalidSpan { .. }),
+                "{start}..{end}: {err}"
+            );
+        }
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("lib.rs")).unwrap(),
+            source
+        );
+
+        edit.apply(replace("lib.rs", 4, 6, "e")).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("lib.rs")).unwrap(),
+            "// hello\n"
+        );
+    }
+
     #[test]
     fn test_edit_result_success() {
-        let result = EditResult::success(vec![PathBuf::from("foo.rs")]);
+        let diff = FileDiff::new(
+            PathBuf::from("foo.rs"),
+            "fn a() {}\n".to_string(),
+            "fn b() {}\n".to_string(),
+        );
+        let result = EditResult::success(vec![PathBuf::from("foo.rs")], vec![diff]);
         assert!(result.success);
         assert_eq!(result.changed_files.len(), 1);
+        assert_eq!(
+            result.diffs[0].to_unified(),
+            "--- a/foo.rs\n+++ b/foo.rs\n@@ -1,1 +1,1 @@\n-fn a() {}\n+fn b() {}\n"
+        );
         assert!(result.error.is_none());
     }
 
@@ -649,6 +1289,9 @@ mod tests {
             .await
             .unwrap();
         assert!(result.success);
+        assert_eq!(result.diffs.len(), 1);
+        assert_eq!(result.diffs[0].original, "old content");
+        assert_eq!(result.diffs[0].new, "new content");
 
         let content = tokio::fs::read_to_string(&file).await.unwrap();
         assert_eq!(content, "new content");
@@ -872,4 +1515,279 @@ mod tests {
         assert_eq!(edit.undo_depth(), 0);
         assert!(!edit.can_undo());
     }
+
+    #[test]
+    fn test_write_all_or_restore_rolls_back_on_failure() {
+        let temp = tempfile::tempdir().unwrap();
+        let rewrites: Vec<FileRewrite> = ["a.rs", "b.rs", "c.rs"]
+            .iter()
+            .map(|name| {
+                let path = temp.path().join(name);
+                std::fs::write(&path, "fn old() {}").unwrap();
+                FileRewrite {
+                    relative: PathBuf::from(name),
+                    path,
+                    original: b"fn old() {}".to_vec(),
+                    content: b"fn new() {}".to_vec(),
+                }
+            })
+            .collect();
+
+        let failing = temp.path().join("b.rs");
+        let result = write_all_or_restore(&rewrites, |path, bytes| {
+            if path == failing && bytes == b"fn new() {}" {
+                return Err(std::io::Error::other("disk full"));
+            }
+            std::fs::write(path, bytes)
+        });
+
+        assert!(result.is_err());
+        for rewrite in &rewrites {
+            assert_eq!(std::fs::read(&rewrite.path).unwrap(), b"fn old() {}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename_symbol_read_only_file_leaves_tree_unchanged() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let files = [
+            ("a.rs", "pub fn helper() -> i32 { 1 }\n"),
+            ("b.rs", "pub fn b() -> i32 { crate::a::helper() }\n"),
+            ("c.rs", "pub fn c() -> i32 { crate::a::helper() }\n"),
+        ];
+        for (name, content) in files {
+            std::fs::write(src.join(name), content).unwrap();
+        }
+
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp.path(),
+                temp.path().join("test.db"),
+                crate::storage::BackendKind::default(),
+            )
+            .await
+            .unwrap(),
+        );
+        crate::graph::GraphModule::new(std::sync::Arc::clone(&store))
+            .index()
+            .await
+            .unwrap();
+
+        let read_only = src.join("b.rs");
+        std::fs::set_permissions(&read_only, std::fs::Permissions::from_mode(0o444)).unwrap();
+        if std::fs::OpenOptions::new()
+            .write(true)
+            .open(&read_only)
+            .is_ok()
+        {
+            // Permissions are not enforced (e.g. running as root)
+            return;
+        }
+
+        let edit = EditModule::new(store);
+        let result = edit.rename_symbol("helper", "assist").await;
+        std::fs::set_permissions(&read_only, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        assert!(result.is_err());
+        for (name, content) in files {
+            assert_eq!(std::fs::read_to_string(src.join(name)).unwrap(), content);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename_symbol_in_scope_leaves_other_scopes() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let original = "\
+fn first() -> i32 {
+    let count = 1;
+    // count is local
+    count + 1
+}
+
+fn second() -> i32 {
+    let count = 2;
+    count
+}
+";
+        std::fs::write(src.join("lib.rs"), original).unwrap();
+
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp.path(),
+                temp.path().join("test.db"),
+                crate::storage::BackendKind::default(),
+            )
+            .await
+            .unwrap(),
+        );
+        let graph = crate::graph::GraphModule::new(std::sync::Arc::clone(&store));
+        graph.index().await.unwrap();
+        let first = graph.find_symbol("first").await.unwrap()[0].id;
+
+        let edit = EditModule::new(store);
+        let preview = edit
+            .rename_symbol_in_scope("count", "total", first)
+            .await
+            .unwrap();
+
+        assert_eq!(preview.changed_files(), vec![PathBuf::from("src/lib.rs")]);
+        assert!(preview.diff.contains("-    let count = 1;"));
+        assert!(preview.diff.contains("+    let total = 1;"));
+        assert!(preview.diff.contains("+    total + 1"));
+        // Nothing is written until the preview is applied
+        assert_eq!(
+            std::fs::read_to_string(src.join("lib.rs")).unwrap(),
+            original
+        );
+
+        let result = edit.apply_rename(&preview).await.unwrap();
+        assert_eq!(result.diffs[0].to_unified(), preview.diff);
+        let renamed = std::fs::read_to_string(src.join("lib.rs")).unwrap();
+        assert_eq!(
+            renamed,
+            original
+                .replacen("let count = 1", "let total = 1", 1)
+                .replacen("    count + 1", "    total + 1", 1)
+        );
+
+        // A stale preview is refused
+        assert!(edit.apply_rename(&preview).await.is_err());
+
+        let missing = edit.rename_symbol_in_scope("absent", "x", first).await;
+        assert!(matches!(missing, Err(ForgeError::SymbolNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rename_symbol_in_scope_follows_graph_references() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(
+            src.join("lib.rs"),
+            "fn helper() -> i32 { 1 }\n\nfn caller() -> i32 {\n    let helper = 2;\n    helper + self::helper()\n}\n",
+        )
+        .unwrap();
+
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp.path(),
+                temp.path().join("test.db"),
+                crate::storage::BackendKind::default(),
+            )
+            .await
+            .unwrap(),
+        );
+        let graph = crate::graph::GraphModule::new(std::sync::Arc::clone(&store));
+        graph.index().await.unwrap();
+        let helper = graph.find_symbol("helper").await.unwrap()[0].id;
+
+        let edit = EditModule::new(store);
+        let preview = edit
+            .rename_symbol_in_scope("helper", "assist", helper)
+            .await
+            .unwrap();
+        let renamed = "fn assist() -> i32 { 1 }\n\nfn caller() -> i32 {\n    let helper = 2;\n    helper + self::assist()\n}\n";
+        assert!(preview.diff.contains("+    helper + self::assist()"));
+        edit.apply_rename(&preview).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(src.join("lib.rs")).unwrap(),
+            renamed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_symbol_refuses_stale_span() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let fresh = "pub fn process(item: i32) -> i32 {\n    item + 1\n}\n";
+        std::fs::write(src.join("a.rs"), fresh).unwrap();
+        std::fs::write(src.join("b.rs"), fresh).unwrap();
+
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp.path(),
+                temp.path().join("test.db"),
+                crate::storage::BackendKind::default(),
+            )
+            .await
+            .unwrap(),
+        );
+        crate::graph::GraphModule::new(std::sync::Arc::clone(&store))
+            .index()
+            .await
+            .unwrap();
+
+        // Shift b.rs after indexing so its recorded span no longer fits
+        let stale = format!("{}\n{}", "// moved down ".repeat(10), fresh);
+        std::fs::write(src.join("b.rs"), &stale).unwrap();
+
+        let edit = EditModule::new(store);
+        let result = edit
+            .patch_symbol(
+                "process",
+                "pub fn process(item: i32) -> i32 {\n    item * 2\n}",
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(ForgeError::InvalidSpan { ref file, .. }) if file == Path::new("src/b.rs")),
+            "expected a stale span error, got {:?}",
+            result.map(|r| r.changed_files)
+        );
+        assert_eq!(std::fs::read_to_string(src.join("a.rs")).unwrap(), fresh);
+        assert_eq!(std::fs::read_to_string(src.join("b.rs")).unwrap(), stale);
+    }
+
+    #[tokio::test]
+    async fn test_patch_symbol_replaces_exact_graph_span() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let original = "\
+pub fn process_all(items: &[i32]) -> i32 {
+    items.iter().map(|&i| process(i)).sum()
+}
+
+pub fn process(item: i32) -> i32 {
+    item + 1
+}
+";
+        std::fs::write(src.join("lib.rs"), original).unwrap();
+
+        let store = std::sync::Arc::new(
+            crate::storage::UnifiedGraphStore::open_with_path(
+                temp.path(),
+                temp.path().join("test.db"),
+                crate::storage::BackendKind::default(),
+            )
+            .await
+            .unwrap(),
+        );
+        crate::graph::GraphModule::new(std::sync::Arc::clone(&store))
+            .index()
+            .await
+            .unwrap();
+
+        let edit = EditModule::new(store);
+        let result = edit
+            .patch_symbol(
+                "process",
+                "pub fn process(item: i32) -> i32 {\n    item * 2\n}",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.changed_files, vec![PathBuf::from("src/lib.rs")]);
+        assert_eq!(
+            std::fs::read_to_string(src.join("lib.rs")).unwrap(),
+            original.replace("    item + 1", "    item * 2")
+        );
+    }
 }
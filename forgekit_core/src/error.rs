@@ -16,6 +16,14 @@ pub enum ForgeError {
     #[error("Symbol not found: {0}")]
     SymbolNotFound(String),
 
+    /// A statement against the graph database failed.
+    #[error("Query failed: {0}")]
+    Query(#[from] rusqlite::Error),
+
+    /// Stored or external data could not be parsed.
+    #[error("Parse error: {0}")]
+    Parse(String),
+
     /// Invalid query syntax or parameters.
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
@@ -29,6 +37,17 @@ pub enum ForgeError {
         span: Span,
     },
 
+    /// Edit span is out of range or does not fall on character boundaries.
+    #[error("Invalid span {span:?} in {file:?}: {reason}")]
+    InvalidSpan {
+        /// File the span points into
+        file: PathBuf,
+        /// The rejected span
+        span: Span,
+        /// What is wrong with it
+        reason: String,
+    },
+
     /// Pre-commit verification failed.
     #[error("Verification failed: {0}")]
     VerificationFailed(String),
@@ -72,6 +91,45 @@ pub enum ForgeError {
     /// File already exists.
     #[error("File already exists: {0}")]
     FileAlreadyExists(PathBuf),
+
+    /// Write attempted on a store opened read-only.
+    #[error("Store is read-only: {0}")]
+    ReadOnly(PathBuf),
+
+    /// The operation was cancelled by the caller.
+    #[error("Operation cancelled")]
+    Cancelled,
+}
+
+impl ForgeError {
+    /// Classifies an error from the magellan graph by its cause, falling
+    /// back to [`DatabaseError`](Self::DatabaseError) with `context`.
+    pub(crate) fn from_graph(context: &str, err: anyhow::Error) -> Self {
+        let err = match err.downcast::<rusqlite::Error>() {
+            Ok(e) => return ForgeError::Query(e),
+            Err(err) => err,
+        };
+        match err.downcast::<std::io::Error>() {
+            Ok(e) => ForgeError::Io(e),
+            Err(err) => ForgeError::DatabaseError(format!("{}: {}", context, err)),
+        }
+    }
+}
+
+impl From<llmgrep::error::LlmError> for ForgeError {
+    fn from(err: llmgrep::error::LlmError) -> Self {
+        use llmgrep::error::LlmError;
+        match err {
+            LlmError::DatabaseNotFound { .. } => ForgeError::BackendNotAvailable(err.to_string()),
+            LlmError::InvalidQuery { .. }
+            | LlmError::EmptyQuery
+            | LlmError::RegexRejected { .. } => ForgeError::InvalidQuery(err.to_string()),
+            LlmError::IoError(e) => ForgeError::Io(e),
+            LlmError::SqliteError(e) => ForgeError::Query(e),
+            LlmError::JsonError(e) => ForgeError::Json(e),
+            _ => ForgeError::DatabaseError(err.to_string()),
+        }
+    }
 }
 
 /// Type alias for Result with ForgeError.
@@ -87,6 +145,43 @@ mod tests {
         assert_eq!(err.to_string(), "Symbol not found: test");
     }
 
+    #[test]
+    fn test_graph_errors_keep_their_cause() {
+        let sql = rusqlite::Connection::open_in_memory()
+            .unwrap()
+            .execute("SELECT * FROM missing", [])
+            .unwrap_err();
+        assert!(matches!(
+            ForgeError::from_graph("search", sql.into()),
+            ForgeError::Query(_)
+        ));
+
+        let io = std::io::Error::new(std::io::ErrorKind::TimedOut, "slow disk");
+        assert!(matches!(
+            ForgeError::from_graph("search", io.into()),
+            ForgeError::Io(_)
+        ));
+
+        let other = ForgeError::from_graph("search", anyhow::anyhow!("bad"));
+        assert_eq!(other.to_string(), "Database error: search: bad");
+    }
+
+    #[test]
+    fn test_llmgrep_errors_are_classified() {
+        use llmgrep::error::LlmError;
+        let missing = LlmError::DatabaseNotFound {
+            path: "graph.db".to_string(),
+        };
+        assert!(matches!(
+            ForgeError::from(missing),
+            ForgeError::BackendNotAvailable(_)
+        ));
+        assert!(matches!(
+            ForgeError::from(LlmError::EmptyQuery),
+            ForgeError::InvalidQuery(_)
+        ));
+    }
+
     #[test]
     fn test_span_is_empty() {
         let span = Span { start: 10, end: 10 };
@@ -0,0 +1,355 @@
+//! Symbol aliases and re-exports.
+//!
+//! Rust `use foo as bar;`, `pub use foo as bar;` and `type Bar = Foo;`
+//! introduce names that magellan indexes separately from the original
+//! symbol. Aliases are extracted during indexing and stored in the graph
+//! database so queries on the original can also find usages via the alias.
+
+use crate::error::{ForgeError, Result};
+use crate::types::{Location, Reference, ReferenceKind, SymbolId};
+use std::path::Path;
+
+/// How an alias was introduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AliasKind {
+    /// `pub use path::foo as bar;`
+    ReExport,
+    /// `use path::foo as bar;`
+    Import,
+    /// `type Bar = Foo;`
+    TypeAlias,
+}
+
+impl AliasKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AliasKind::ReExport => "re_export",
+            AliasKind::Import => "import",
+            AliasKind::TypeAlias => "type_alias",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "re_export" => Some(AliasKind::ReExport),
+            "import" => Some(AliasKind::Import),
+            "type_alias" => Some(AliasKind::TypeAlias),
+            _ => None,
+        }
+    }
+}
+
+/// An alternative name for a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolAlias {
+    /// The new name (`bar` in `use foo as bar`)
+    pub alias: String,
+    /// Simple name of the aliased symbol (`foo`)
+    pub target: String,
+    /// How the alias was introduced
+    pub kind: AliasKind,
+    /// Location of the alias declaration
+    pub location: Location,
+}
+
+pub(crate) fn parse_rust(source: &str) -> Option<tree_sitter::Tree> {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&tree_sitter_rust::LANGUAGE.into())
+        .ok()?;
+    parser.parse(source, None)
+}
+
+pub(crate) fn text<'a>(source: &'a str, node: &tree_sitter::Node) -> &'a str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+/// Last path segment with generic arguments stripped (`a::b::Foo<T>` -> `Foo`).
+pub(crate) fn simple_name(path: &str) -> &str {
+    let path = path.split('<').next().unwrap_or(path).trim();
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+pub(crate) fn location(file_path: &Path, node: &tree_sitter::Node) -> Location {
+    Location {
+        file_path: file_path.to_path_buf(),
+        byte_start: node.start_byte() as u32,
+        byte_end: node.end_byte() as u32,
+        line_number: node.start_position().row + 1,
+    }
+}
+
+/// Extracts aliases declared in Rust source.
+///
+/// Only renaming imports create aliases; a plain `pub use foo;` keeps the
+/// original name and is already found by name-based queries.
+pub fn extract_aliases(source: &str, file_path: &Path) -> Vec<SymbolAlias> {
+    let mut aliases = Vec::new();
+    if let Some(tree) = parse_rust(source) {
+        collect_aliases(source, file_path, &tree.root_node(), &mut aliases);
+    }
+    aliases
+}
+
+fn collect_aliases(
+    source: &str,
+    file_path: &Path,
+    node: &tree_sitter::Node,
+    aliases: &mut Vec<SymbolAlias>,
+) {
+    match node.kind() {
+        "use_declaration" => {
+            let mut cursor = node.walk();
+            let is_pub = node
+                .children(&mut cursor)
+                .any(|c| c.kind() == "visibility_modifier");
+            let kind = if is_pub {
+                AliasKind::ReExport
+            } else {
+                AliasKind::Import
+            };
+            collect_use_as(source, file_path, node, kind, aliases);
+            return;
+        }
+        "type_item" => {
+            if let (Some(name), Some(ty)) = (
+                node.child_by_field_name("name"),
+                node.child_by_field_name("type"),
+            ) {
+                let target = simple_name(text(source, &ty));
+                if !target.is_empty() {
+                    aliases.push(SymbolAlias {
+                        alias: text(source, &name).to_string(),
+                        target: target.to_string(),
+                        kind: AliasKind::TypeAlias,
+                        location: location(file_path, node),
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_aliases(source, file_path, &child, aliases);
+    }
+}
+
+fn collect_use_as(
+    source: &str,
+    file_path: &Path,
+    node: &tree_sitter::Node,
+    kind: AliasKind,
+    aliases: &mut Vec<SymbolAlias>,
+) {
+    if node.kind() == "use_as_clause" {
+        if let (Some(path), Some(alias)) = (
+            node.child_by_field_name("path"),
+            node.child_by_field_name("alias"),
+        ) {
+            let target = simple_name(text(source, &path));
+            let alias = text(source, &alias);
+            // `use foo as _;` imports traits without naming them
+            if alias != "_" && alias != target {
+                aliases.push(SymbolAlias {
+                    alias: alias.to_string(),
+                    target: target.to_string(),
+                    kind,
+                    location: location(file_path, node),
+                });
+            }
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_use_as(source, file_path, &child, kind, aliases);
+    }
+}
+
+/// Finds calls to `name` in Rust source, attributed to the enclosing function.
+pub(crate) fn call_sites(source: &str, file_path: &Path, name: &str) -> Vec<Reference> {
+    let mut calls = Vec::new();
+    if let Some(tree) = parse_rust(source) {
+        collect_calls(source, file_path, name, &tree.root_node(), None, &mut calls);
+    }
+    calls
+}
+
+fn collect_calls(
+    source: &str,
+    file_path: &Path,
+    name: &str,
+    node: &tree_sitter::Node,
+    caller: Option<&str>,
+    calls: &mut Vec<Reference>,
+) {
+    let mut caller = caller;
+    if node.kind() == "function_item" {
+        caller = node
+            .child_by_field_name("name")
+            .map(|n| text(source, &n))
+            .or(caller);
+    }
+
+    if node.kind() == "call_expression" {
+        if let Some(function) = node.child_by_field_name("function") {
+            let matches = match function.kind() {
+                "identifier" => text(source, &function) == name,
+                "scoped_identifier" => function
+                    .child_by_field_name("name")
+                    .is_some_and(|n| text(source, &n) == name),
+                _ => false,
+            };
+            if matches {
+                calls.push(Reference {
+                    from: SymbolId(0),
+                    to: SymbolId(0),
+                    from_name: caller.map(str::to_string),
+                    to_name: Some(name.to_string()),
+                    kind: ReferenceKind::Call,
+                    location: location(file_path, &function),
+                });
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_calls(source, file_path, name, &child, caller, calls);
+    }
+}
+
+const CREATE_ALIAS_TABLE: &str = "CREATE TABLE IF NOT EXISTS forge_aliases (
+    alias TEXT NOT NULL,
+    target TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    byte_start INTEGER NOT NULL,
+    byte_end INTEGER NOT NULL,
+    line_number INTEGER NOT NULL
+);";
+
+/// Replaces the stored aliases with `aliases`.
+pub(crate) fn save_aliases(db_path: &Path, aliases: &[SymbolAlias]) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(db_path)?;
+    conn.execute_batch(CREATE_ALIAS_TABLE)?;
+
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM forge_aliases", [])?;
+    for alias in aliases {
+        tx.execute(
+            "INSERT INTO forge_aliases
+                (alias, target, kind, file_path, byte_start, byte_end, line_number)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                alias.alias,
+                alias.target,
+                alias.kind.as_str(),
+                alias.location.file_path.to_string_lossy(),
+                alias.location.byte_start,
+                alias.location.byte_end,
+                alias.location.line_number as i64,
+            ],
+        )?;
+    }
+    tx.commit().map_err(ForgeError::from)
+}
+
+/// Loads all stored aliases; empty if the database has never been indexed.
+pub(crate) fn load_aliases(db_path: &Path) -> Result<Vec<SymbolAlias>> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = crate::storage::open_read_only(db_path)?;
+    let mut stmt = match conn.prepare(
+        "SELECT alias, target, kind, file_path, byte_start, byte_end, line_number
+         FROM forge_aliases",
+    ) {
+        Ok(stmt) => stmt,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, u32>(4)?,
+            row.get::<_, u32>(5)?,
+            row.get::<_, i64>(6)?,
+        ))
+    })?;
+
+    Ok(rows
+        .flatten()
+        .filter_map(
+            |(alias, target, kind, file_path, byte_start, byte_end, line)| {
+                Some(SymbolAlias {
+                    alias,
+                    target,
+                    kind: AliasKind::parse(&kind)?,
+                    location: Location {
+                        file_path: file_path.into(),
+                        byte_start,
+                        byte_end,
+                        line_number: line as usize,
+                    },
+                })
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_aliases_use_as_and_type() {
+        let source = "pub use crate::util::foo as bar;\n\
+                      use std::io::{Read as _, Write as W};\n\
+                      type Map<V> = std::collections::HashMap<String, V>;\n\
+                      pub use crate::plain;\n";
+        let aliases = extract_aliases(source, Path::new("src/lib.rs"));
+
+        let summary: Vec<(&str, &str, AliasKind)> = aliases
+            .iter()
+            .map(|a| (a.alias.as_str(), a.target.as_str(), a.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("bar", "foo", AliasKind::ReExport),
+                ("W", "Write", AliasKind::Import),
+                ("Map", "HashMap", AliasKind::TypeAlias),
+            ]
+        );
+        assert_eq!(aliases[0].location.line_number, 1);
+    }
+
+    #[test]
+    fn test_call_sites_records_enclosing_function() {
+        let source = "fn run() {\n    bar();\n    util::bar(1);\n    other();\n}\n";
+        let calls = call_sites(source, Path::new("src/main.rs"), "bar");
+
+        assert_eq!(calls.len(), 2);
+        assert!(calls.iter().all(|c| c.from_name.as_deref() == Some("run")));
+        assert_eq!(calls[0].location.line_number, 2);
+    }
+
+    #[test]
+    fn test_save_and_load_aliases_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("aliases.db");
+        let aliases = extract_aliases("pub use a::foo as bar;\n", Path::new("src/lib.rs"));
+
+        save_aliases(&db_path, &aliases).unwrap();
+        save_aliases(&db_path, &aliases).unwrap();
+
+        assert_eq!(load_aliases(&db_path).unwrap(), aliases);
+    }
+}
@@ -0,0 +1,103 @@
+//! Symbol body hashes stored with the symbol entities
+//!
+//! Hashes are taken at index time from the source that was indexed and
+//! kept in each symbol entity's `data` under `body_hash`, so reading them
+//! back never depends on the files still matching the graph.
+
+use crate::error::Result;
+use crate::indexing::IndexerConfig;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Reads the body hash stored in a symbol entity's `data`.
+pub(crate) fn from_data(data: &Value) -> Option<blake3::Hash> {
+    let hex = data.get("body_hash")?.as_str()?;
+    blake3::Hash::from_hex(hex).ok()
+}
+
+/// Hashes, from `source`, the bodies of the symbols stored under any of
+/// `file_paths`.
+///
+/// Symbols whose span lies outside `source` are left without a hash.
+pub(crate) fn store_for_file(
+    conn: &mut Connection,
+    file_paths: &[&str],
+    source: &[u8],
+    config: &IndexerConfig,
+) -> Result<()> {
+    let tx = conn.transaction()?;
+    for file_path in file_paths {
+        let symbols = symbol_spans(&tx, "file_path = ?1", params![file_path])?;
+        for (id, _, (start, end)) in symbols {
+            if let Some(body) = source.get(start..end) {
+                set_hash(&tx, id, &config.body_hash(body))?;
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Hashes the symbols indexed without a body hash, such as those written
+/// by a magellan scan outside a full index, reading each file once.
+///
+/// Relative file paths are resolved against `codebase_path`.
+pub(crate) fn store_missing(
+    db_path: &Path,
+    codebase_path: &Path,
+    config: &IndexerConfig,
+) -> Result<()> {
+    let mut conn = Connection::open(db_path)?;
+    let tx = conn.transaction()?;
+    let symbols = symbol_spans(&tx, "json_extract(data, '$.body_hash') IS NULL", [])?;
+    let mut sources: HashMap<String, Option<Vec<u8>>> = HashMap::new();
+    for (id, file_path, (start, end)) in symbols {
+        let source = sources
+            .entry(file_path)
+            .or_insert_with_key(|file_path| std::fs::read(codebase_path.join(file_path)).ok());
+        if let Some(body) = source.as_deref().and_then(|s| s.get(start..end)) {
+            set_hash(&tx, id, &config.body_hash(body))?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Entity id, file path and byte span of a stored symbol.
+type SymbolSpan = (i64, String, (usize, usize));
+
+/// Spans of the symbol entities matching `filter`.
+fn symbol_spans(
+    conn: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<SymbolSpan>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, file_path, data FROM graph_entities WHERE kind = 'Symbol' AND {filter}"
+    ))?;
+    let rows = stmt.query_map(params, |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+    Ok(rows
+        .flatten()
+        .filter_map(|(id, file_path, data)| {
+            let data: Value = serde_json::from_str(data.as_deref()?).ok()?;
+            let offset = |key: &str| data.get(key)?.as_u64().map(|n| n as usize);
+            Some((id, file_path?, (offset("byte_start")?, offset("byte_end")?)))
+        })
+        .collect())
+}
+
+fn set_hash(conn: &Connection, id: i64, hash: &blake3::Hash) -> Result<()> {
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.body_hash', ?2) WHERE id = ?1",
+        params![id, hash.to_hex().as_str()],
+    )?;
+    Ok(())
+}
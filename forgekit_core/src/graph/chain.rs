@@ -0,0 +1,91 @@
+//! Transitive walks over the references into a symbol.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::export::GraphExport;
+use crate::types::{Location, ReferenceKind, Symbol, SymbolId};
+
+/// Returns the symbols that reach a symbol named `name` through incoming
+/// references, up to `max_depth` hops away.
+///
+/// Symbols are ordered by distance, then by name and position. Each symbol
+/// appears once, at its shortest distance, so cycles terminate. With
+/// `calls_only`, only call references are followed.
+pub(crate) fn incoming_chain(
+    graph: &GraphExport,
+    root: &Path,
+    name: &str,
+    max_depth: usize,
+    calls_only: bool,
+) -> Vec<Symbol> {
+    let by_id: HashMap<SymbolId, &Symbol> = graph.symbols.iter().map(|s| (s.id, s)).collect();
+    let mut frontier: HashSet<SymbolId> = graph
+        .symbols
+        .iter()
+        .filter(|s| &*s.name == name || &*s.fully_qualified_name == name)
+        .map(|s| s.id)
+        .collect();
+    let mut visited = frontier.clone();
+
+    let mut chain = Vec::new();
+    for _ in 0..max_depth {
+        let mut level: Vec<&Symbol> = Vec::new();
+        for reference in &graph.references {
+            if !frontier.contains(&reference.to)
+                || (calls_only && reference.kind != ReferenceKind::Call)
+            {
+                continue;
+            }
+            let referrer = match by_id.get(&reference.from) {
+                Some(symbol) => Some(*symbol),
+                None => enclosing_symbol(&graph.symbols, root, &reference.location),
+            };
+            let Some(referrer) = referrer else {
+                continue;
+            };
+            // A declaration names itself; that is not a reference to it
+            let target = by_id.get(&reference.to);
+            if target.is_some_and(|t| t.name == referrer.name) {
+                continue;
+            }
+            if visited.insert(referrer.id) {
+                level.push(referrer);
+            }
+        }
+        if level.is_empty() {
+            break;
+        }
+        level.sort_by(|a, b| {
+            (&a.name, &a.location.file_path, a.location.byte_start).cmp(&(
+                &b.name,
+                &b.location.file_path,
+                b.location.byte_start,
+            ))
+        });
+        frontier = level.iter().map(|s| s.id).collect();
+        chain.extend(level.into_iter().cloned());
+    }
+    chain
+}
+
+/// Returns the narrowest symbol whose span contains `location`.
+///
+/// Paths are compared after resolving relative ones against `root`, since
+/// the graph records some paths relative and others absolute.
+pub(crate) fn enclosing_symbol<'a>(
+    symbols: &'a [Symbol],
+    root: &Path,
+    location: &Location,
+) -> Option<&'a Symbol> {
+    let file = root.join(&location.file_path);
+    symbols
+        .iter()
+        .filter(|s| {
+            root.join(&s.location.file_path) == file
+                && s.location.byte_start <= location.byte_start
+                && location.byte_end <= s.location.byte_end
+                && s.location.byte_start < s.location.byte_end
+        })
+        .min_by_key(|s| s.location.byte_end - s.location.byte_start)
+}
@@ -0,0 +1,124 @@
+//! Resumable indexing progress.
+//!
+//! [`GraphModule::index`](super::GraphModule::index) records which files it
+//! has finished in `.forge/index-checkpoint.json`. If indexing is
+//! interrupted, the next run skips the symbol scan and every file that was
+//! completed and has not changed since. The checkpoint is removed once a
+//! run completes, so the following run starts from scratch.
+
+use crate::error::{ForgeError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Checkpoint location relative to the codebase root.
+pub(crate) const CHECKPOINT_FILE: &str = ".forge/index-checkpoint.json";
+
+/// Files indexed between checkpoint writes.
+pub(crate) const SAVE_EVERY: usize = 32;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct IndexCheckpoint {
+    /// Database the progress belongs to
+    db_path: PathBuf,
+    /// Whether the magellan symbol scan has completed
+    pub(crate) symbols_scanned: bool,
+    /// Completed files (relative path) and their modification time in
+    /// nanoseconds since the Unix epoch
+    pub(crate) completed: BTreeMap<String, u64>,
+}
+
+impl IndexCheckpoint {
+    fn path(codebase_path: &Path) -> PathBuf {
+        codebase_path.join(CHECKPOINT_FILE)
+    }
+
+    /// Creates an empty checkpoint for `db_path`.
+    pub(crate) fn new(db_path: &Path) -> Self {
+        Self {
+            db_path: db_path.to_path_buf(),
+            ..Self::default()
+        }
+    }
+
+    /// Loads the checkpoint for `db_path`, or an empty one if there is no
+    /// usable checkpoint (missing, unreadable, or for another database).
+    pub(crate) fn load(codebase_path: &Path, db_path: &Path) -> Self {
+        std::fs::read(Self::path(codebase_path))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok())
+            .filter(|checkpoint| checkpoint.db_path == db_path)
+            .unwrap_or_else(|| Self::new(db_path))
+    }
+
+    /// Returns true if nothing has been recorded yet.
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.symbols_scanned && self.completed.is_empty()
+    }
+
+    /// Returns true if `file` was completed and has not changed since.
+    pub(crate) fn is_done(&self, file: &str, mtime: u64) -> bool {
+        self.completed.get(file) == Some(&mtime)
+    }
+
+    pub(crate) fn mark_done(&mut self, file: String, mtime: u64) {
+        self.completed.insert(file, mtime);
+    }
+
+    /// Writes the checkpoint atomically.
+    pub(crate) fn save(&self, codebase_path: &Path) -> Result<()> {
+        let path = Self::path(codebase_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_vec(self)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(ForgeError::from)
+    }
+
+    /// Deletes the checkpoint after a completed run.
+    pub(crate) fn remove(codebase_path: &Path) {
+        let _ = std::fs::remove_file(Self::path(codebase_path));
+    }
+}
+
+/// Modification time of `path` in nanoseconds since the Unix epoch; 0 if
+/// unavailable, which never matches a recorded time from a real file.
+pub(crate) fn modified_nanos(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_roundtrip_and_db_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("graph.db");
+
+        let mut checkpoint = IndexCheckpoint::load(dir.path(), &db_path);
+        assert!(checkpoint.is_empty());
+        checkpoint.symbols_scanned = true;
+        checkpoint.mark_done("src/a.rs".to_string(), 42);
+        checkpoint.save(dir.path()).unwrap();
+
+        let loaded = IndexCheckpoint::load(dir.path(), &db_path);
+        assert!(loaded.symbols_scanned);
+        assert!(loaded.is_done("src/a.rs", 42));
+        assert!(!loaded.is_done("src/a.rs", 43));
+
+        let other = IndexCheckpoint::load(dir.path(), &dir.path().join("other.db"));
+        assert!(other.is_empty());
+
+        IndexCheckpoint::remove(dir.path());
+        assert!(IndexCheckpoint::load(dir.path(), &db_path).is_empty());
+    }
+}
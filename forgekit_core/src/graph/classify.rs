@@ -0,0 +1,296 @@
+//! Classifies a reference by the source text around it.
+//!
+//! Works on Rust syntax without parsing: the statement a name appears in
+//! and its neighbouring characters decide whether it is imported, called,
+//! used as a type, or used some other way.
+
+use super::alias::{location, parse_rust, text};
+use crate::types::{Location, Reference, ReferenceKind, SymbolId};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Items whose `name` field declares a symbol rather than referring to one.
+const DECLARATIONS: &[&str] = &[
+    "function_item",
+    "function_signature_item",
+    "struct_item",
+    "enum_item",
+    "enum_variant",
+    "union_item",
+    "trait_item",
+    "type_item",
+    "associated_type",
+    "mod_item",
+    "const_item",
+    "static_item",
+    "macro_definition",
+    "field_declaration",
+];
+
+/// Finds the references to `name` in Rust source, classified by context.
+///
+/// Declarations of `name` are skipped, as are comments and literals. Each
+/// reference is attributed to the enclosing function, if any.
+pub(crate) fn references_in(source: &str, file_path: &Path, name: &str) -> Vec<Reference> {
+    let mut found = Vec::new();
+    if let Some(tree) = parse_rust(source) {
+        collect_references(source, file_path, name, &tree.root_node(), None, &mut found);
+    }
+    found
+}
+
+fn collect_references(
+    source: &str,
+    file_path: &Path,
+    name: &str,
+    node: &tree_sitter::Node,
+    caller: Option<&str>,
+    found: &mut Vec<Reference>,
+) {
+    let mut caller = caller;
+    if node.kind() == "function_item" {
+        caller = node
+            .child_by_field_name("name")
+            .map(|n| text(source, &n))
+            .or(caller);
+    }
+
+    let is_identifier = matches!(
+        node.kind(),
+        "identifier" | "type_identifier" | "field_identifier"
+    );
+    if is_identifier && text(source, node) == name && !is_declaration(node) {
+        found.push(Reference {
+            from: SymbolId(0),
+            to: SymbolId(0),
+            from_name: caller.map(str::to_string),
+            to_name: Some(name.to_string()),
+            kind: classify_reference(source, node.start_byte(), node.end_byte()),
+            location: location(file_path, node),
+        });
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_references(source, file_path, name, &child, caller, found);
+    }
+}
+
+fn is_declaration(node: &tree_sitter::Node) -> bool {
+    node.parent().is_some_and(|parent| {
+        DECLARATIONS.contains(&parent.kind())
+            && parent.child_by_field_name("name").as_ref() == Some(node)
+    })
+}
+
+/// Classifies references found in the graph by reading their source files.
+pub(crate) struct SourceClassifier {
+    root: PathBuf,
+    /// File contents by path, `None` if the file could not be read
+    sources: HashMap<PathBuf, Option<String>>,
+}
+
+impl SourceClassifier {
+    /// Creates a classifier resolving relative paths against `root`.
+    pub(crate) fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Classifies the occurrence of `name` in the span of `location`.
+    ///
+    /// Returns `fallback` if the file cannot be read or the name does not
+    /// occur in the span.
+    pub(crate) fn classify(
+        &mut self,
+        location: &Location,
+        name: &str,
+        fallback: ReferenceKind,
+    ) -> ReferenceKind {
+        let root = &self.root;
+        let source = self
+            .sources
+            .entry(location.file_path.clone())
+            .or_insert_with(|| std::fs::read_to_string(root.join(&location.file_path)).ok());
+        let Some(source) = source else {
+            return fallback;
+        };
+        // The span may cover a whole call, so find the name inside it
+        let name = name.rsplit("::").next().unwrap_or(name);
+        let start = location.byte_start as usize;
+        let end = (location.byte_end as usize).max(start + name.len());
+        let Some(span) = source.get(start..end.min(source.len())) else {
+            return fallback;
+        };
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let found = span.match_indices(name).find(|(i, _)| {
+            let before = source[..start + i].chars().next_back();
+            let after = source[start + i + name.len()..].chars().next();
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        });
+        match found {
+            Some((i, _)) => classify_reference(source, start + i, start + i + name.len()),
+            None => fallback,
+        }
+    }
+}
+
+/// Classifies the occurrence of a name at `start..end` in `source`.
+///
+/// In order of precedence, a name in a `use` path is an
+/// [`Import`](ReferenceKind::Import), one followed by `(` is a
+/// [`Call`](ReferenceKind::Call), one after `:` or `->` or inside `<...>`
+/// is a [`TypeReference`](ReferenceKind::TypeReference), and anything
+/// else is a [`Use`](ReferenceKind::Use).
+pub(crate) fn classify_reference(source: &str, start: usize, end: usize) -> ReferenceKind {
+    let (Some(before), Some(after)) = (source.get(..start), source.get(end..)) else {
+        return ReferenceKind::Use;
+    };
+
+    if in_use_declaration(before) {
+        return ReferenceKind::Import;
+    }
+    if after.trim_start().starts_with('(') {
+        return ReferenceKind::Call;
+    }
+
+    // Look past a leading path such as `crate::types::`
+    let mut prefix = before.trim_end();
+    while let Some(rest) = prefix.strip_suffix("::") {
+        prefix = rest
+            .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_')
+            .trim_end();
+    }
+    let after_colon = prefix.ends_with(':') && !prefix.ends_with("::");
+    if after_colon || prefix.ends_with("->") || inside_angle_brackets(statement(before)) {
+        return ReferenceKind::TypeReference;
+    }
+    ReferenceKind::Use
+}
+
+/// The text of the statement that `before` ends in.
+fn statement(before: &str) -> &str {
+    match before.rfind([';', '{', '}']) {
+        Some(i) => &before[i + 1..],
+        None => before,
+    }
+}
+
+/// Returns true if `before` ends inside a `use` declaration, including
+/// inside a `{...}` group of one.
+fn in_use_declaration(before: &str) -> bool {
+    let mut text = before;
+    loop {
+        let Some(i) = text.rfind([';', '{', '}']) else {
+            return starts_use(text);
+        };
+        let tail = &text[i + 1..];
+        if starts_use(tail) {
+            return true;
+        }
+        // A brace directly after `::` opens a use group; keep looking back
+        if text.as_bytes()[i] == b'{' && text[..i].trim_end().ends_with("::") {
+            text = &text[..i];
+            continue;
+        }
+        return false;
+    }
+}
+
+fn starts_use(statement: &str) -> bool {
+    let mut rest = statement.trim_start();
+    if let Some(after) = rest.strip_prefix("pub") {
+        rest = match after.strip_prefix('(') {
+            Some(scope) => scope.split_once(')').map_or("", |(_, r)| r),
+            None => after,
+        };
+        rest = rest.trim_start();
+    }
+    rest.strip_prefix("use")
+        .is_some_and(|r| r.starts_with(char::is_whitespace))
+}
+
+/// Returns true if `text` has more `<` than `>`, ignoring `->` and `=>`.
+fn inside_angle_brackets(text: &str) -> bool {
+    let mut depth = 0i32;
+    let mut previous = ' ';
+    for c in text.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' if previous != '-' && previous != '=' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+    }
+    depth > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(source: &str, needle: &str, occurrence: usize) -> ReferenceKind {
+        let start = source
+            .match_indices(needle)
+            .nth(occurrence)
+            .map(|(i, _)| i)
+            .unwrap();
+        classify_reference(source, start, start + needle.len())
+    }
+
+    #[test]
+    fn test_classify_reference_kinds() {
+        let source = "\
+use crate::config::Config;
+pub(crate) use crate::io::{read, write as put};
+fn load(path: &str) -> Config {
+    let cfg: Config = Config::default();
+    let items: Vec<Config> = Vec::new();
+    let f = read;
+    read(path);
+    put(path);
+    cfg
+}
+";
+        assert_eq!(classify(source, "Config", 0), ReferenceKind::Import);
+        assert_eq!(classify(source, "read", 0), ReferenceKind::Import);
+        assert_eq!(classify(source, "write", 0), ReferenceKind::Import);
+        // Return type, annotation, then the path of an associated call
+        assert_eq!(classify(source, "Config", 1), ReferenceKind::TypeReference);
+        assert_eq!(classify(source, "Config", 2), ReferenceKind::TypeReference);
+        assert_eq!(classify(source, "Config", 3), ReferenceKind::Use);
+        assert_eq!(classify(source, "Config", 4), ReferenceKind::TypeReference);
+        assert_eq!(classify(source, "read", 1), ReferenceKind::Use);
+        assert_eq!(classify(source, "read", 2), ReferenceKind::Call);
+        assert_eq!(classify(source, "put", 1), ReferenceKind::Call);
+    }
+
+    #[test]
+    fn test_references_in_skips_declarations() {
+        let source = "\
+struct Config;
+// Config in a comment
+fn load() -> Config {
+    let text = \"Config\";
+    Config
+}
+";
+        let found = references_in(source, Path::new("src/lib.rs"), "Config");
+        let kinds: Vec<_> = found
+            .iter()
+            .map(|r| (r.location.line_number, r.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![(3, ReferenceKind::TypeReference), (5, ReferenceKind::Use)]
+        );
+        assert_eq!(found[1].from_name.as_deref(), Some("load"));
+    }
+
+    #[test]
+    fn test_classify_reference_out_of_range() {
+        assert_eq!(classify_reference("abc", 2, 10), ReferenceKind::Use);
+    }
+}
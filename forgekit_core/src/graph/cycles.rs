@@ -0,0 +1,438 @@
+//! Cycle detection over selectable reference kinds.
+//!
+//! magellan stores calls as `caller -CALLER-> call -CALLS-> callee` and
+//! references as `reference -REFERENCES-> symbol`. This module collapses
+//! those into symbol-to-symbol edges, keeps only the requested
+//! [`ReferenceKind`]s, and reports strongly connected components. A single
+//! symbol is only reported when it calls itself directly.
+
+use crate::error::Result;
+use crate::types::{Cycle, CycleMember, ReferenceKind};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Maps a graph edge type to the reference kind it represents.
+fn edge_kind(edge_type: &str) -> Option<ReferenceKind> {
+    match edge_type.to_ascii_uppercase().as_str() {
+        "CALLS" => Some(ReferenceKind::Call),
+        "REFERENCES" => Some(ReferenceKind::TypeReference),
+        "IMPORTS" => Some(ReferenceKind::Import),
+        "IMPLEMENTS" => Some(ReferenceKind::Implements),
+        "INHERITS" => Some(ReferenceKind::Inherit),
+        "OVERRIDES" => Some(ReferenceKind::Override),
+        _ => None,
+    }
+}
+
+struct Entity {
+    kind: String,
+    name: String,
+    file_path: Option<String>,
+    data: serde_json::Value,
+}
+
+impl Entity {
+    fn is_symbol(&self) -> bool {
+        !matches!(self.kind.as_str(), "Call" | "Reference" | "File")
+    }
+
+    fn span(&self) -> Option<(u64, u64)> {
+        Some((
+            self.data.get("byte_start")?.as_u64()?,
+            self.data.get("byte_end")?.as_u64()?,
+        ))
+    }
+
+    fn member(&self) -> CycleMember {
+        let text = |key: &str| {
+            self.data
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        CycleMember {
+            symbol_id: text("symbol_id"),
+            fqn: text("fqn")
+                .or_else(|| text("display_fqn"))
+                .or_else(|| Some(self.name.clone())),
+            file_path: self.file_path.clone().unwrap_or_else(|| "?".to_string()),
+            kind: text("kind").unwrap_or_else(|| self.kind.clone()),
+        }
+    }
+}
+
+/// Detects cycles whose edges are all of one of `kinds`.
+///
+/// Returns an empty list if the database is missing or has no graph
+/// tables.
+pub(crate) fn detect_cycles(db_path: &Path, kinds: &[ReferenceKind]) -> Result<Vec<Cycle>> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = crate::storage::open_read_only(db_path)?;
+
+    let mut entities: HashMap<i64, Entity> = HashMap::new();
+    let Ok(mut stmt) = conn.prepare("SELECT id, kind, name, file_path, data FROM graph_entities")
+    else {
+        return Ok(Vec::new());
+    };
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+        ))
+    })?;
+    for (id, kind, name, file_path, data) in rows.flatten() {
+        let data = data
+            .and_then(|d| serde_json::from_str(&d).ok())
+            .unwrap_or(serde_json::Value::Null);
+        entities.insert(
+            id,
+            Entity {
+                kind,
+                name,
+                file_path,
+                data,
+            },
+        );
+    }
+    drop(stmt);
+
+    let Ok(mut stmt) = conn.prepare("SELECT from_id, to_id, edge_type FROM graph_edges") else {
+        return Ok(Vec::new());
+    };
+    let raw_edges: Vec<(i64, i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .flatten()
+        .collect();
+
+    // Call nodes: caller -CALLER-> call
+    let mut call_sources: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (from, to, edge_type) in &raw_edges {
+        if edge_type.eq_ignore_ascii_case("CALLER") {
+            call_sources.entry(*to).or_default().push(*from);
+        }
+    }
+
+    // Symbols with spans per file, for attributing reference nodes
+    let mut spans: HashMap<&str, Vec<(u64, u64, i64)>> = HashMap::new();
+    for (id, entity) in &entities {
+        if let (true, Some(file), Some((start, end))) = (
+            entity.is_symbol(),
+            entity.file_path.as_deref(),
+            entity.span(),
+        ) {
+            spans.entry(file).or_default().push((start, end, *id));
+        }
+    }
+    let enclosing = |reference: &Entity| -> Option<i64> {
+        let file = reference
+            .data
+            .get("file")
+            .and_then(|f| f.as_str())
+            .or(reference.file_path.as_deref())?;
+        let offset = reference.data.get("byte_start")?.as_u64()?;
+        spans
+            .get(file)?
+            .iter()
+            .filter(|(start, end, _)| *start <= offset && offset < *end)
+            .min_by_key(|(start, end, _)| end - start)
+            .map(|(_, _, id)| *id)
+    };
+
+    let mut adjacency: HashMap<i64, HashSet<i64>> = HashMap::new();
+    // Functions that call themselves directly
+    let mut self_calls: HashSet<i64> = HashSet::new();
+    for (from, to, edge_type) in &raw_edges {
+        let Some(kind) = edge_kind(edge_type) else {
+            continue;
+        };
+        if !kinds.contains(&kind) || !entities.get(to).is_some_and(Entity::is_symbol) {
+            continue;
+        }
+        let sources = match entities.get(from) {
+            Some(e) if e.kind == "Call" => call_sources.get(from).cloned().unwrap_or_default(),
+            Some(e) if e.kind == "Reference" => enclosing(e).into_iter().collect(),
+            Some(e) if e.is_symbol() => vec![*from],
+            _ => Vec::new(),
+        };
+        for source in sources {
+            if source == *to && kind == ReferenceKind::Call {
+                self_calls.insert(source);
+            }
+            adjacency.entry(source).or_default().insert(*to);
+        }
+    }
+
+    let mut cycles: Vec<Cycle> = strongly_connected_components(&adjacency)
+        .into_iter()
+        .filter(|component| component.len() > 1 || self_calls.contains(&component[0]))
+        .map(|component| {
+            let mut members: Vec<CycleMember> = component
+                .iter()
+                .filter_map(|id| entities.get(id).map(Entity::member))
+                .collect();
+            members.sort_by(|a, b| (&a.fqn, &a.file_path).cmp(&(&b.fqn, &b.file_path)));
+            Cycle { members }
+        })
+        .collect();
+    cycles.sort_by(|a, b| {
+        let key = |c: &Cycle| {
+            c.members
+                .first()
+                .map(|m| (m.fqn.clone(), m.file_path.clone()))
+        };
+        key(a).cmp(&key(b))
+    });
+
+    Ok(cycles)
+}
+
+/// Tarjan's algorithm, iterative to avoid deep recursion on large graphs.
+fn strongly_connected_components(adjacency: &HashMap<i64, HashSet<i64>>) -> Vec<Vec<i64>> {
+    let mut nodes: Vec<i64> = adjacency
+        .iter()
+        .flat_map(|(from, tos)| std::iter::once(*from).chain(tos.iter().copied()))
+        .collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+
+    let mut tarjan = Tarjan {
+        adjacency,
+        index: HashMap::new(),
+        on_stack: HashSet::new(),
+        stack: Vec::new(),
+        calls: Vec::new(),
+        components: Vec::new(),
+    };
+    for start in nodes {
+        if tarjan.index.contains_key(&start) {
+            continue;
+        }
+        tarjan.visit(start);
+        while let Some((node, pending)) = tarjan.calls.last_mut() {
+            let node = *node;
+            if let Some(next) = pending.pop() {
+                match tarjan.index.get(&next) {
+                    None => tarjan.visit(next),
+                    Some(&(next_index, _)) if tarjan.on_stack.contains(&next) => {
+                        tarjan.lower(node, next_index);
+                    }
+                    Some(_) => {}
+                }
+            } else {
+                tarjan.calls.pop();
+                tarjan.finish(node);
+            }
+        }
+    }
+
+    tarjan.components
+}
+
+/// State of one [`strongly_connected_components`] run.
+struct Tarjan<'a> {
+    adjacency: &'a HashMap<i64, HashSet<i64>>,
+    /// Discovery index and lowest index reachable, per visited node
+    index: HashMap<i64, (usize, usize)>,
+    on_stack: HashSet<i64>,
+    stack: Vec<i64>,
+    /// The simulated call stack: each node with its unvisited successors
+    calls: Vec<(i64, Vec<i64>)>,
+    components: Vec<Vec<i64>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: i64) {
+        let next = self.index.len();
+        self.index.insert(node, (next, next));
+        self.on_stack.insert(node);
+        self.stack.push(node);
+        self.calls.push((node, successors(self.adjacency, node)));
+    }
+
+    fn lower(&mut self, node: i64, to: usize) {
+        if let Some((_, low)) = self.index.get_mut(&node) {
+            *low = (*low).min(to);
+        }
+    }
+
+    /// Returns from `node`: passes its low link to its caller and pops its
+    /// component if it is the root of one.
+    fn finish(&mut self, node: i64) {
+        let (node_index, node_low) = self.index[&node];
+        if let Some(&(parent, _)) = self.calls.last() {
+            self.lower(parent, node_low);
+        }
+        if node_low != node_index {
+            return;
+        }
+        let mut component = Vec::new();
+        while let Some(member) = self.stack.pop() {
+            self.on_stack.remove(&member);
+            component.push(member);
+            if member == node {
+                break;
+            }
+        }
+        self.components.push(component);
+    }
+}
+
+fn successors(adjacency: &HashMap<i64, HashSet<i64>>, node: i64) -> Vec<i64> {
+    let mut next: Vec<i64> = adjacency
+        .get(&node)
+        .map(|s| s.iter().copied().collect())
+        .unwrap_or_default();
+    next.sort_unstable();
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scc_finds_components() {
+        let mut adjacency: HashMap<i64, HashSet<i64>> = HashMap::new();
+        adjacency.entry(1).or_default().insert(2);
+        adjacency.entry(2).or_default().insert(1);
+        adjacency.entry(2).or_default().insert(3);
+        adjacency.entry(3).or_default().insert(4);
+
+        let mut components: Vec<Vec<i64>> = strongly_connected_components(&adjacency)
+            .into_iter()
+            .map(|mut c| {
+                c.sort_unstable();
+                c
+            })
+            .collect();
+        components.sort();
+
+        assert_eq!(components, vec![vec![1, 2], vec![3], vec![4]]);
+    }
+
+    #[test]
+    fn test_scc_handles_long_cycles_without_recursion() {
+        let mut adjacency: HashMap<i64, HashSet<i64>> = HashMap::new();
+        let n = 100_000;
+        for i in 0..n {
+            adjacency.entry(i).or_default().insert((i + 1) % n);
+        }
+        adjacency.entry(n - 1).or_default().insert(n);
+
+        let components = strongly_connected_components(&adjacency);
+        assert_eq!(components.len(), 2);
+        assert!(components.iter().any(|c| c.len() == n as usize));
+        assert!(components.contains(&vec![n]));
+    }
+
+    #[test]
+    fn test_call_cycle_and_direct_recursion() {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("calls.db");
+        let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+
+        let node = |kind: &str, name: &str| NodeSpec {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            file_path: Some("src/lib.rs".to_string()),
+            data: serde_json::json!({ "fqn": name, "kind": "Function" }),
+        };
+        let edge = |from: i64, to: i64, edge_type: &str| EdgeSpec {
+            from,
+            to,
+            edge_type: edge_type.to_string(),
+            data: serde_json::Value::Null,
+        };
+        let call = |caller: i64, callee: i64| {
+            let site = backend.insert_node(node("Call", "call")).unwrap();
+            backend.insert_edge(edge(caller, site, "CALLER")).unwrap();
+            backend.insert_edge(edge(site, callee, "CALLS")).unwrap();
+        };
+
+        let a = backend.insert_node(node("Symbol", "a")).unwrap();
+        let b = backend.insert_node(node("Symbol", "b")).unwrap();
+        let c = backend.insert_node(node("Symbol", "c")).unwrap();
+        let fact = backend.insert_node(node("Symbol", "fact")).unwrap();
+        let tree = backend.insert_node(node("Symbol", "Tree")).unwrap();
+        call(a, b);
+        call(b, c);
+        call(c, a);
+        call(a, fact);
+        call(fact, fact);
+        // A type naming itself is not recursion
+        backend.insert_edge(edge(tree, tree, "REFERENCES")).unwrap();
+        drop(backend);
+
+        let cycles = detect_cycles(
+            &db_path,
+            &[ReferenceKind::Call, ReferenceKind::TypeReference],
+        )
+        .unwrap();
+        let names: Vec<Vec<String>> = cycles
+            .iter()
+            .map(|c| c.members.iter().filter_map(|m| m.fqn.clone()).collect())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                vec!["fact".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reference_nodes_attributed_to_enclosing_symbol() {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("refs.db");
+        let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+
+        let symbol = |name: &str, start: u64, end: u64| NodeSpec {
+            kind: "Symbol".to_string(),
+            name: name.to_string(),
+            file_path: Some("src/lib.rs".to_string()),
+            data: serde_json::json!({
+                "fqn": name, "kind": "Struct", "byte_start": start, "byte_end": end,
+            }),
+        };
+        let reference = |offset: u64| NodeSpec {
+            kind: "Reference".to_string(),
+            name: "ref".to_string(),
+            file_path: Some("src/lib.rs".to_string()),
+            data: serde_json::json!({ "file": "src/lib.rs", "byte_start": offset }),
+        };
+        let edge = |from: i64, to: i64| EdgeSpec {
+            from,
+            to,
+            edge_type: "REFERENCES".to_string(),
+            data: serde_json::Value::Null,
+        };
+
+        let a = backend.insert_node(symbol("A", 0, 50)).unwrap();
+        let b = backend.insert_node(symbol("B", 60, 100)).unwrap();
+        let in_a = backend.insert_node(reference(10)).unwrap();
+        let in_b = backend.insert_node(reference(70)).unwrap();
+        backend.insert_edge(edge(in_a, b)).unwrap();
+        backend.insert_edge(edge(in_b, a)).unwrap();
+        drop(backend);
+
+        assert!(detect_cycles(&db_path, &[ReferenceKind::Call])
+            .unwrap()
+            .is_empty());
+
+        let cycles = detect_cycles(&db_path, &[ReferenceKind::TypeReference]).unwrap();
+        assert_eq!(cycles.len(), 1);
+        let names: Vec<_> = cycles[0].members.iter().map(|m| m.fqn.clone()).collect();
+        assert_eq!(names, vec![Some("A".to_string()), Some("B".to_string())]);
+        assert_eq!(cycles[0].members[0].kind, "Struct");
+    }
+}
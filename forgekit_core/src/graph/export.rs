@@ -0,0 +1,283 @@
+//! JSON export and import of the code graph.
+//!
+//! The document has the shape `{"symbols": [...], "references": [...]}`,
+//! with [`Symbol`] and [`Reference`] in their serde form. Symbols are
+//! ordered by id and references by the order magellan recorded them, so
+//! exporting the same graph twice gives the same bytes.
+
+use super::{map_magellan_language, parse_symbol_kind_str};
+use crate::error::{ForgeError, Result};
+use crate::types::{Location, Reference, ReferenceKind, Symbol, SymbolId, SymbolKind, Visibility};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A serialized code graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphExport {
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+/// Reads every symbol and reference from the graph database.
+pub(crate) fn read_graph(db_path: &Path) -> Result<GraphExport> {
+    if !db_path.exists() {
+        return Ok(GraphExport::default());
+    }
+    let conn = crate::storage::open_read_only(db_path)?;
+    Ok(GraphExport {
+        symbols: read_symbols(&conn)?,
+        references: read_references(&conn)?,
+    })
+}
+
+fn read_symbols(conn: &Connection) -> Result<Vec<Symbol>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, file_path, data FROM graph_entities
+             WHERE kind = 'Symbol' ORDER BY id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+
+    let mut symbols = Vec::new();
+    for row in rows {
+        let (id, name, file_path, data) = row?;
+        let data: Value = serde_json::from_str(&data)?;
+        let file_path = PathBuf::from(file_path.unwrap_or_default());
+        let kind = data
+            .get("kind_normalized")
+            .or_else(|| data.get("kind"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let fqn = data.get("fqn").and_then(Value::as_str).unwrap_or(&name);
+        symbols.push(Symbol {
+            id: SymbolId(id),
+            name: Arc::from(name.as_str()),
+            fully_qualified_name: Arc::from(fqn),
+            kind: parse_symbol_kind_str(kind),
+            language: map_magellan_language(&file_path),
+            location: location_of(&data, file_path),
+            parent_id: None,
+            visibility: Visibility::Unknown,
+            body_hash: super::body_hash::from_data(&data),
+            metadata: data,
+        });
+    }
+    Ok(symbols)
+}
+
+/// Reads `Reference` and `Call` entities, resolving their endpoints
+/// through the `REFERENCES`, `CALLS` and `CALLER` edges.
+fn read_references(conn: &Connection) -> Result<Vec<Reference>> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.kind, e.file_path, e.data,
+                    (SELECT to_id FROM graph_edges
+                     WHERE from_id = e.id AND edge_type IN ('REFERENCES', 'CALLS')),
+                    (SELECT from_id FROM graph_edges
+                     WHERE to_id = e.id AND edge_type = 'CALLER')
+             FROM graph_entities e
+             WHERE e.kind IN ('Reference', 'Call') ORDER BY e.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, Option<i64>>(4)?,
+            row.get::<_, Option<i64>>(5)?,
+        ))
+    })?;
+
+    let name_of = |id: Option<i64>| -> Result<Option<String>> {
+        let Some(id) = id else { return Ok(None) };
+        conn.query_row(
+            "SELECT name FROM graph_entities WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(ForgeError::from)
+    };
+
+    let mut references = Vec::new();
+    for row in rows {
+        let (kind, file_path, data, to, from) = row?;
+        let data: Value = serde_json::from_str(&data)?;
+        let file_path = PathBuf::from(file_path.unwrap_or_default());
+        let (kind, from_name, to_name) = if kind == "Call" {
+            let field = |key: &str| data.get(key).and_then(Value::as_str).map(str::to_string);
+            (ReferenceKind::Call, field("caller"), field("callee"))
+        } else {
+            (ReferenceKind::TypeReference, name_of(from)?, name_of(to)?)
+        };
+        references.push(Reference {
+            from: SymbolId(from.unwrap_or(0)),
+            to: SymbolId(to.unwrap_or(0)),
+            from_name,
+            to_name,
+            kind,
+            location: location_of(&data, file_path),
+        });
+    }
+    Ok(references)
+}
+
+fn location_of(data: &Value, file_path: PathBuf) -> Location {
+    let number = |key: &str| data.get(key).and_then(Value::as_u64).unwrap_or(0);
+    Location {
+        file_path,
+        byte_start: number("byte_start") as u32,
+        byte_end: number("byte_end") as u32,
+        line_number: number("start_line") as usize,
+    }
+}
+
+/// Writes `graph` into an empty graph database, creating it if needed.
+///
+/// Symbols keep their ids, so references resolve to the same symbols they
+/// did in the exported graph.
+pub(crate) fn write_graph(db_path: &Path, graph: &GraphExport) -> Result<()> {
+    // Let magellan create its schema before writing rows into it
+    drop(
+        magellan::CodeGraph::open(db_path)
+            .map_err(|e| ForgeError::from_graph("Failed to open magellan graph", e))?,
+    );
+
+    let mut conn = Connection::open(db_path)?;
+    let existing: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM graph_entities WHERE kind IN ('Symbol', 'Reference', 'Call')",
+        [],
+        |row| row.get(0),
+    )?;
+    if existing > 0 {
+        return Err(ForgeError::InvalidQuery(format!(
+            "Cannot import into {}: the graph is not empty",
+            db_path.display()
+        )));
+    }
+
+    let tx = conn.transaction()?;
+    let insert_entity = |kind: &str, name: &str, file: &Path, data: &Value| -> Result<i64> {
+        tx.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES (?1, ?2, ?3, ?4)",
+            params![kind, name, file.to_string_lossy(), data.to_string()],
+        )?;
+        Ok(tx.last_insert_rowid())
+    };
+    let insert_edge = |from: i64, to: i64, edge_type: &str| -> Result<()> {
+        tx.execute(
+            "INSERT INTO graph_edges (from_id, to_id, edge_type, data) VALUES (?1, ?2, ?3, '{}')",
+            params![from, to, edge_type],
+        )?;
+        Ok(())
+    };
+
+    for symbol in &graph.symbols {
+        tx.execute(
+            "INSERT INTO graph_entities (id, kind, name, file_path, data)
+             VALUES (?1, 'Symbol', ?2, ?3, ?4)",
+            params![
+                symbol.id.0,
+                symbol.name.as_ref(),
+                symbol.location.file_path.to_string_lossy(),
+                symbol_data(symbol).to_string()
+            ],
+        )?;
+    }
+
+    let files: BTreeSet<&Path> = graph
+        .symbols
+        .iter()
+        .map(|s| s.location.file_path.as_path())
+        .collect();
+    for file in files {
+        let path = file.to_string_lossy();
+        let file_id = insert_entity("File", &path, file, &serde_json::json!({ "path": path }))?;
+        for symbol in graph
+            .symbols
+            .iter()
+            .filter(|s| s.location.file_path == file)
+        {
+            insert_edge(file_id, symbol.id.0, "DEFINES")?;
+        }
+    }
+
+    for reference in &graph.references {
+        let location = &reference.location;
+        let mut data = serde_json::json!({
+            "byte_start": location.byte_start,
+            "byte_end": location.byte_end,
+            "start_line": location.line_number,
+            "file": location.file_path.to_string_lossy(),
+        });
+        let to_name = reference.to_name.as_deref().unwrap_or_default();
+        let id = if reference.kind == ReferenceKind::Call {
+            let caller = reference.from_name.as_deref().unwrap_or_default();
+            data["caller"] = caller.into();
+            data["callee"] = to_name.into();
+            let name = format!("{} calls {}", caller, to_name);
+            let id = insert_entity("Call", &name, &location.file_path, &data)?;
+            if reference.from.0 != 0 {
+                insert_edge(reference.from.0, id, "CALLER")?;
+            }
+            id
+        } else {
+            let name = format!("ref to {}", to_name);
+            insert_entity("Reference", &name, &location.file_path, &data)?
+        };
+        if reference.to.0 != 0 {
+            let edge_type = match reference.kind {
+                ReferenceKind::Call => "CALLS",
+                _ => "REFERENCES",
+            };
+            insert_edge(id, reference.to.0, edge_type)?;
+        }
+    }
+
+    tx.commit().map_err(ForgeError::from)
+}
+
+/// Magellan's `data` blob for `symbol`: its metadata when that came from
+/// magellan, otherwise built from the symbol's fields.
+fn symbol_data(symbol: &Symbol) -> Value {
+    if symbol.metadata.is_object() {
+        return symbol.metadata.clone();
+    }
+    let mut data = serde_json::json!({
+        "name": symbol.name.as_ref(),
+        "fqn": symbol.fully_qualified_name.as_ref(),
+        "kind_normalized": kind_name(symbol.kind),
+        "byte_start": symbol.location.byte_start,
+        "byte_end": symbol.location.byte_end,
+        "start_line": symbol.location.line_number,
+    });
+    if let Some(hash) = &symbol.body_hash {
+        data["body_hash"] = hash.to_hex().as_str().into();
+    }
+    data
+}
+
+/// The normalized kind name that [`parse_symbol_kind_str`] reads back.
+fn kind_name(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Method => "method",
+        SymbolKind::Struct => "struct",
+        SymbolKind::Trait => "trait",
+        SymbolKind::Enum => "enum",
+        SymbolKind::Impl => "impl",
+        SymbolKind::Module => "module",
+        SymbolKind::TypeAlias => "type",
+        SymbolKind::Constant => "const",
+        SymbolKind::Static => "static",
+        _ => "fn",
+    }
+}
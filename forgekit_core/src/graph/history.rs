@@ -0,0 +1,485 @@
+//! Checkpointed history of the code graph.
+//!
+//! [`GraphHistory::checkpoint`] records how the symbols and references
+//! changed since the previous checkpoint in `.forge/graph-history.db`.
+//! Only the changes are stored, so the history grows with edits rather
+//! than with the size of the codebase. The graph at a checkpoint is
+//! rebuilt by replaying the changes up to it.
+
+use super::export::read_graph;
+use crate::error::{ForgeError, Result};
+use crate::types::{Reference, Symbol, SymbolId};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// History location relative to the codebase root.
+pub(crate) const HISTORY_FILE: &str = ".forge/graph-history.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS checkpoints (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        label TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS changes (
+        checkpoint INTEGER NOT NULL REFERENCES checkpoints(id),
+        item TEXT NOT NULL,
+        key TEXT NOT NULL,
+        added INTEGER NOT NULL,
+        data TEXT
+    );
+    CREATE INDEX IF NOT EXISTS changes_by_checkpoint ON changes(checkpoint);
+";
+
+/// Identifies a checkpoint in the graph history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GraphCheckpointId(pub i64);
+
+impl std::fmt::Display for GraphCheckpointId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A recorded point in the graph history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphCheckpoint {
+    pub id: GraphCheckpointId,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    /// Symbols and references added, removed or updated since the previous
+    /// checkpoint
+    pub changes: usize,
+}
+
+/// How the graph differs between two checkpoints.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub added_symbols: Vec<Symbol>,
+    pub removed_symbols: Vec<Symbol>,
+    pub added_references: Vec<Reference>,
+    pub removed_references: Vec<Reference>,
+}
+
+impl GraphDiff {
+    /// Returns true if the graph did not change.
+    pub fn is_empty(&self) -> bool {
+        self.added_symbols.is_empty()
+            && self.removed_symbols.is_empty()
+            && self.added_references.is_empty()
+            && self.removed_references.is_empty()
+    }
+}
+
+/// Symbols and references by identity key, holding their serde form.
+#[derive(Default)]
+struct GraphState {
+    symbols: BTreeMap<String, String>,
+    references: BTreeMap<String, String>,
+}
+
+impl GraphState {
+    fn items(&self, item: Item) -> &BTreeMap<String, String> {
+        match item {
+            Item::Symbol => &self.symbols,
+            Item::Reference => &self.references,
+        }
+    }
+
+    fn items_mut(&mut self, item: Item) -> &mut BTreeMap<String, String> {
+        match item {
+            Item::Symbol => &mut self.symbols,
+            Item::Reference => &mut self.references,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Item {
+    Symbol,
+    Reference,
+}
+
+impl Item {
+    const ALL: [Item; 2] = [Item::Symbol, Item::Reference];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Item::Symbol => "symbol",
+            Item::Reference => "reference",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "symbol" => Some(Item::Symbol),
+            "reference" => Some(Item::Reference),
+            _ => None,
+        }
+    }
+}
+
+/// Identity of each symbol across reindexing, which renumbers ids and
+/// shifts offsets when lines are inserted above.
+///
+/// Symbols are keyed by file, qualified name and kind, numbered in file
+/// order among those sharing all three.
+fn symbol_keys(symbols: &[Symbol]) -> Vec<String> {
+    let mut order: Vec<usize> = (0..symbols.len()).collect();
+    order.sort_by_key(|&i| symbols[i].location.byte_start);
+
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let mut keys = vec![String::new(); symbols.len()];
+    for i in order {
+        let symbol = &symbols[i];
+        let identity = format!(
+            "{}\0{}\0{:?}",
+            symbol.location.file_path.display(),
+            symbol.fully_qualified_name,
+            symbol.kind
+        );
+        let occurrence = occurrences.entry(identity.clone()).or_default();
+        keys[i] = format!("{}\0{}", identity, occurrence);
+        *occurrence += 1;
+    }
+    keys
+}
+
+/// Identity of each reference across reindexing, which renumbers ids and
+/// shifts offsets when lines are inserted above.
+///
+/// References are keyed by kind, file and the symbols at either end,
+/// numbered in file order among those sharing all of them. An end is named
+/// by the key of the symbol it resolves to in `symbols`, or by the name the
+/// reference carries when it resolves to none.
+fn reference_keys(references: &[Reference], symbols: &HashMap<SymbolId, String>) -> Vec<String> {
+    let end = |id: SymbolId, name: &Option<String>| {
+        symbols
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| name.clone().unwrap_or_default())
+    };
+
+    let mut order: Vec<usize> = (0..references.len()).collect();
+    order.sort_by_key(|&i| references[i].location.byte_start);
+
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let mut keys = vec![String::new(); references.len()];
+    for i in order {
+        let reference = &references[i];
+        let identity = format!(
+            "{:?}\0{}\0{}\0{}",
+            reference.kind,
+            reference.location.file_path.display(),
+            end(reference.from, &reference.from_name),
+            end(reference.to, &reference.to_name)
+        );
+        let occurrence = occurrences.entry(identity.clone()).or_default();
+        keys[i] = format!("{}\0{}", identity, occurrence);
+        *occurrence += 1;
+    }
+    keys
+}
+
+/// Records and replays the history of the code graph.
+///
+/// Obtained from [`GraphModule::history`](super::GraphModule::history).
+#[derive(Clone, Debug)]
+pub struct GraphHistory {
+    codebase_path: PathBuf,
+    db_path: PathBuf,
+    read_only: bool,
+}
+
+impl GraphHistory {
+    pub(crate) fn new(codebase_path: &Path, db_path: &Path, read_only: bool) -> Self {
+        Self {
+            codebase_path: codebase_path.to_path_buf(),
+            db_path: db_path.to_path_buf(),
+            read_only,
+        }
+    }
+
+    fn open(&self) -> Result<Connection> {
+        let path = self.codebase_path.join(HISTORY_FILE);
+        if self.read_only {
+            return Err(ForgeError::ReadOnly(path));
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(conn)
+    }
+
+    /// Opens the history for reading only; `None` if nothing was ever
+    /// checkpointed.
+    fn open_read(&self) -> Result<Option<Connection>> {
+        let path = self.codebase_path.join(HISTORY_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(crate::storage::open_read_only(&path)?))
+    }
+
+    /// Records the current graph as a new checkpoint.
+    ///
+    /// Stores only what changed since the previous checkpoint; the first
+    /// checkpoint stores the whole graph. A symbol or reference that only
+    /// moved within its file is updated in place rather than removed and
+    /// added. Fails
+    /// with [`ForgeError::ReadOnly`] on a read-only store.
+    pub fn checkpoint(&self, label: &str) -> Result<GraphCheckpoint> {
+        let graph = read_graph(&self.db_path)?;
+        let mut current = GraphState::default();
+        let mut symbol_ids = HashMap::new();
+        for (key, symbol) in symbol_keys(&graph.symbols).into_iter().zip(&graph.symbols) {
+            current
+                .symbols
+                .insert(key.clone(), serde_json::to_string(symbol)?);
+            symbol_ids.insert(symbol.id, key);
+        }
+        let keys = reference_keys(&graph.references, &symbol_ids);
+        for (key, reference) in keys.into_iter().zip(&graph.references) {
+            current
+                .references
+                .insert(key, serde_json::to_string(reference)?);
+        }
+
+        let mut conn = self.open()?;
+        let previous = replay(&conn, None)?;
+        let created_at = Utc::now();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO checkpoints (label, created_at) VALUES (?1, ?2)",
+            params![label, created_at.to_rfc3339()],
+        )?;
+        let id = tx.last_insert_rowid();
+
+        let mut changes = 0;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO changes (checkpoint, item, key, added, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for item in Item::ALL {
+                let (before, after) = (previous.items(item), current.items(item));
+                // New items, and kept items whose record changed
+                let changed = |(key, data): &(&String, &String)| {
+                    before.get(*key).is_none_or(|old| record_changed(old, data))
+                };
+                for (key, data) in after.iter().filter(changed) {
+                    insert.execute(params![id, item.as_str(), key, true, data])?;
+                    changes += 1;
+                }
+                for key in before.keys().filter(|k| !after.contains_key(*k)) {
+                    insert.execute(params![id, item.as_str(), key, false, None::<String>])?;
+                    changes += 1;
+                }
+            }
+        }
+        tx.commit()?;
+
+        Ok(GraphCheckpoint {
+            id: GraphCheckpointId(id),
+            label: label.to_string(),
+            created_at,
+            changes,
+        })
+    }
+
+    /// Lists the checkpoints, oldest first.
+    pub fn checkpoints(&self) -> Result<Vec<GraphCheckpoint>> {
+        let Some(conn) = self.open_read()? else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.label, c.created_at,
+                        (SELECT COUNT(*) FROM changes WHERE checkpoint = c.id)
+                 FROM checkpoints c ORDER BY c.id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+
+        let mut checkpoints = Vec::new();
+        for row in rows {
+            let (id, label, created_at, changes) = row?;
+            let created_at = DateTime::parse_from_rfc3339(&created_at)
+                .map_err(|e| ForgeError::Parse(format!("Bad checkpoint time: {}", e)))?
+                .with_timezone(&Utc);
+            checkpoints.push(GraphCheckpoint {
+                id: GraphCheckpointId(id),
+                label,
+                created_at,
+                changes: changes as usize,
+            });
+        }
+        Ok(checkpoints)
+    }
+
+    /// Returns the symbols that existed at `checkpoint`, ordered by file
+    /// and position.
+    pub fn symbols_at(&self, checkpoint: GraphCheckpointId) -> Result<Vec<Symbol>> {
+        let state = self.state_at(checkpoint)?;
+        let mut symbols = decode::<Symbol>(state.symbols.values())?;
+        sort_symbols(&mut symbols);
+        Ok(symbols)
+    }
+
+    /// Returns the references that existed at `checkpoint`, ordered by
+    /// file and position.
+    pub fn references_at(&self, checkpoint: GraphCheckpointId) -> Result<Vec<Reference>> {
+        let state = self.state_at(checkpoint)?;
+        let mut references = decode::<Reference>(state.references.values())?;
+        sort_references(&mut references);
+        Ok(references)
+    }
+
+    /// Returns what changed from checkpoint `a` to checkpoint `b`.
+    ///
+    /// `b` may be older than `a`, in which case the diff undoes the
+    /// changes made between them. Symbols and references that only moved
+    /// are in neither list.
+    pub fn diff_graph(&self, a: GraphCheckpointId, b: GraphCheckpointId) -> Result<GraphDiff> {
+        let before = self.state_at(a)?;
+        let after = self.state_at(b)?;
+        let only_in = |x: &BTreeMap<String, String>, y: &BTreeMap<String, String>| {
+            x.iter()
+                .filter(|(k, _)| !y.contains_key(*k))
+                .map(|(_, v)| v.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut diff = GraphDiff {
+            added_symbols: decode(&only_in(&after.symbols, &before.symbols))?,
+            removed_symbols: decode(&only_in(&before.symbols, &after.symbols))?,
+            added_references: decode(&only_in(&after.references, &before.references))?,
+            removed_references: decode(&only_in(&before.references, &after.references))?,
+        };
+        sort_symbols(&mut diff.added_symbols);
+        sort_symbols(&mut diff.removed_symbols);
+        sort_references(&mut diff.added_references);
+        sort_references(&mut diff.removed_references);
+        Ok(diff)
+    }
+
+    fn state_at(&self, checkpoint: GraphCheckpointId) -> Result<GraphState> {
+        let conn = self.open_read()?;
+        let exists = match &conn {
+            Some(conn) => conn
+                .query_row(
+                    "SELECT 1 FROM checkpoints WHERE id = ?1",
+                    params![checkpoint.0],
+                    |_| Ok(()),
+                )
+                .optional()?,
+            None => None,
+        };
+        match (conn, exists) {
+            (Some(conn), Some(())) => replay(&conn, Some(checkpoint)),
+            _ => Err(ForgeError::InvalidQuery(format!(
+                "Unknown graph checkpoint {}",
+                checkpoint
+            ))),
+        }
+    }
+}
+
+/// Whether two records of an item differ in more than the ids that
+/// reindexing renumbers.
+fn record_changed(before: &str, after: &str) -> bool {
+    if before == after {
+        return false;
+    }
+    let without_ids = |record: &str| {
+        let mut value: serde_json::Value = serde_json::from_str(record).unwrap_or_default();
+        if let Some(fields) = value.as_object_mut() {
+            for id in ["id", "parent_id", "from", "to"] {
+                fields.remove(id);
+            }
+        }
+        value
+    };
+    without_ids(before) != without_ids(after)
+}
+
+/// Rebuilds the graph by applying the changes of every checkpoint up to
+/// and including `upto`, or of all checkpoints if `None`.
+fn replay(conn: &Connection, upto: Option<GraphCheckpointId>) -> Result<GraphState> {
+    let mut stmt = conn.prepare(
+        "SELECT item, key, added, data FROM changes
+             WHERE checkpoint <= ?1 ORDER BY checkpoint, rowid",
+    )?;
+    let rows = stmt.query_map(params![upto.map_or(i64::MAX, |id| id.0)], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, bool>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    let mut state = GraphState::default();
+    for row in rows {
+        let (item, key, added, data) = row?;
+        let Some(item) = Item::parse(&item) else {
+            continue;
+        };
+        let items = state.items_mut(item);
+        match data {
+            Some(data) if added => {
+                items.insert(key, data);
+            }
+            _ => {
+                items.remove(&key);
+            }
+        }
+    }
+    Ok(state)
+}
+
+fn decode<'a, T: serde::de::DeserializeOwned>(
+    values: impl IntoIterator<Item = &'a String>,
+) -> Result<Vec<T>> {
+    values
+        .into_iter()
+        .map(|v| serde_json::from_str(v).map_err(ForgeError::from))
+        .collect()
+}
+
+fn sort_symbols(symbols: &mut [Symbol]) {
+    symbols.sort_by(|a, b| {
+        (&a.location.file_path, a.location.byte_start, &a.name).cmp(&(
+            &b.location.file_path,
+            b.location.byte_start,
+            &b.name,
+        ))
+    });
+}
+
+fn sort_references(references: &mut [Reference]) {
+    references.sort_by(|a, b| {
+        (
+            &a.location.file_path,
+            a.location.byte_start,
+            &a.to_name,
+            &a.from_name,
+        )
+            .cmp(&(
+                &b.location.file_path,
+                b.location.byte_start,
+                &b.to_name,
+                &b.from_name,
+            ))
+    });
+}
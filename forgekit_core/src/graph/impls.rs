@@ -0,0 +1,413 @@
+//! Trait implementations.
+//!
+//! `impl Trait for Type` blocks are extracted during indexing and stored in
+//! the graph database, so [`implementors_of`](super::GraphModule::implementors_of)
+//! can answer for traits that are not themselves in the graph, such as
+//! `Display`. Generic impls are attributed to their base type:
+//! `impl<T> Trait for Vec<T>` records `Vec`.
+//!
+//! When both the type and the trait are declared in the graph, the impl
+//! is also stored as an `IMPLEMENTS` edge between their symbols.
+
+use super::alias::{location, parse_rust, simple_name, text};
+use crate::error::{ForgeError, Result};
+use crate::types::{
+    Language, Location, Reference, ReferenceKind, Symbol, SymbolId, SymbolKind, Visibility,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A type implementing a trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitImpl {
+    /// Base name of the implementing type (`Vec` for `Vec<T>`)
+    pub type_name: String,
+    /// Simple name of the trait (`Display` for `std::fmt::Display`)
+    pub trait_name: String,
+    /// Location of the `impl` block
+    pub location: Location,
+}
+
+impl TraitImpl {
+    /// The impl block as a symbol named after the implementing type, for
+    /// types that are not declared in the graph.
+    ///
+    /// The id is hashed from `"{trait} for {type}"`, so impls of different
+    /// traits for one type in the same file get distinct ids.
+    pub fn to_symbol(&self) -> Symbol {
+        let header = format!("{} for {}", self.trait_name, self.type_name);
+        Symbol {
+            id: SymbolId::stable(&header, SymbolKind::Impl, &self.location.file_path),
+            name: Arc::from(self.type_name.as_str()),
+            fully_qualified_name: Arc::from(self.type_name.as_str()),
+            kind: SymbolKind::Impl,
+            language: Language::Rust,
+            location: self.location.clone(),
+            parent_id: None,
+            visibility: Visibility::Unknown,
+            body_hash: None,
+            metadata: serde_json::json!({ "implements": self.trait_name }),
+        }
+    }
+
+    /// The [`Implements`](ReferenceKind::Implements) edge from the type to
+    /// the trait, located at the impl block.
+    ///
+    /// `ids` are the symbols of the type and the trait; without them both
+    /// ends are `SymbolId(0)`.
+    pub fn to_reference(&self, ids: Option<(SymbolId, SymbolId)>) -> Reference {
+        let (from, to) = ids.unwrap_or((SymbolId(0), SymbolId(0)));
+        Reference {
+            from,
+            to,
+            from_name: Some(self.type_name.clone()),
+            to_name: Some(self.trait_name.clone()),
+            kind: ReferenceKind::Implements,
+            location: self.location.clone(),
+        }
+    }
+}
+
+/// Base name of a type as written in an impl header: references,
+/// lifetimes, `dyn`, paths and generic arguments are dropped
+/// (`&'a mut foo::Bar<T>` -> `Bar`).
+fn base_type(ty: &str) -> &str {
+    let mut ty = ty.trim();
+    loop {
+        let rest = ty.trim_start_matches('&').trim_start();
+        let rest = match rest.strip_prefix('\'') {
+            Some(lifetime) => lifetime
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, rest)| rest),
+            None => rest,
+        };
+        let rest = ["mut ", "dyn "]
+            .iter()
+            .find_map(|keyword| rest.strip_prefix(keyword))
+            .unwrap_or(rest)
+            .trim_start();
+        if rest == ty {
+            return simple_name(ty);
+        }
+        ty = rest;
+    }
+}
+
+/// Extracts the trait impls in Rust source. Inherent impls are skipped.
+pub fn extract_impls(source: &str, file_path: &Path) -> Vec<TraitImpl> {
+    let mut impls = Vec::new();
+    if let Some(tree) = parse_rust(source) {
+        collect_impls(source, file_path, &tree.root_node(), &mut impls);
+    }
+    impls
+}
+
+fn collect_impls(
+    source: &str,
+    file_path: &Path,
+    node: &tree_sitter::Node,
+    impls: &mut Vec<TraitImpl>,
+) {
+    if node.kind() == "impl_item" {
+        if let (Some(tr), Some(ty)) = (
+            node.child_by_field_name("trait"),
+            node.child_by_field_name("type"),
+        ) {
+            let type_name = base_type(text(source, &ty));
+            let trait_name = simple_name(text(source, &tr));
+            if !type_name.is_empty() && !trait_name.is_empty() {
+                impls.push(TraitImpl {
+                    type_name: type_name.to_string(),
+                    trait_name: trait_name.to_string(),
+                    location: location(file_path, node),
+                });
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_impls(source, file_path, &child, impls);
+    }
+}
+
+/// Finds `impl Trait for Type` headers line by line, for sources that have
+/// not been indexed. Headers split across lines are missed.
+pub(crate) fn scan_impl_lines(source: &str, file_path: &Path) -> Vec<TraitImpl> {
+    let mut impls = Vec::new();
+    let mut offset = 0;
+    for (index, line) in source.lines().enumerate() {
+        let line_start = offset;
+        offset += line.len() + 1;
+
+        let trimmed = line.trim_start();
+        let header = trimmed.strip_prefix("unsafe ").unwrap_or(trimmed);
+        let Some(mut rest) = header.strip_prefix("impl") else {
+            continue;
+        };
+        if rest.starts_with('<') {
+            // Skip the impl's own generic parameters
+            let mut depth = 0;
+            let Some(end) = rest.char_indices().find_map(|(i, c)| {
+                match c {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(i)
+            }) else {
+                continue;
+            };
+            rest = &rest[end + 1..];
+        } else if !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let Some((tr, ty)) = rest.split_once(" for ") else {
+            continue;
+        };
+        let ty = ty.split(['{', ';']).next().unwrap_or_default();
+        let ty = ty.split(" where").next().unwrap_or_default();
+        let trait_name = simple_name(tr.trim().trim_start_matches('!'));
+        let type_name = base_type(ty);
+        if trait_name.is_empty() || type_name.is_empty() {
+            continue;
+        }
+        let column = line.len() - trimmed.len();
+        impls.push(TraitImpl {
+            type_name: type_name.to_string(),
+            trait_name: trait_name.to_string(),
+            location: Location {
+                file_path: file_path.to_path_buf(),
+                byte_start: (line_start + column) as u32,
+                byte_end: (line_start + line.len()) as u32,
+                line_number: index + 1,
+            },
+        });
+    }
+    impls
+}
+
+const CREATE_IMPL_TABLE: &str = "CREATE TABLE IF NOT EXISTS forge_impls (
+    type_name TEXT NOT NULL,
+    trait_name TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    byte_start INTEGER NOT NULL,
+    byte_end INTEGER NOT NULL,
+    line_number INTEGER NOT NULL
+);";
+
+/// Replaces the stored trait impls with `impls`, and the `IMPLEMENTS`
+/// edges with one for each impl whose type and trait are in the graph.
+pub(crate) fn save_impls(db_path: &Path, impls: &[TraitImpl]) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(db_path)?;
+    conn.execute_batch(CREATE_IMPL_TABLE)?;
+    let linked = has_graph(&conn)?;
+
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM forge_impls", [])?;
+    if linked {
+        tx.execute("DELETE FROM graph_edges WHERE edge_type = 'IMPLEMENTS'", [])?;
+    }
+    for imp in impls {
+        tx.execute(
+            "INSERT INTO forge_impls
+                (type_name, trait_name, file_path, byte_start, byte_end, line_number)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                imp.type_name,
+                imp.trait_name,
+                imp.location.file_path.to_string_lossy(),
+                imp.location.byte_start,
+                imp.location.byte_end,
+                imp.location.line_number as i64,
+            ],
+        )?;
+        if !linked {
+            continue;
+        }
+        if let Some((from, to)) = resolve(&tx, imp)? {
+            let data = serde_json::json!({
+                "file_path": imp.location.file_path.to_string_lossy(),
+                "byte_start": imp.location.byte_start,
+            });
+            tx.execute(
+                "INSERT INTO graph_edges (from_id, to_id, edge_type, data)
+                 VALUES (?1, ?2, 'IMPLEMENTS', ?3)",
+                params![from.0, to.0, data.to_string()],
+            )?;
+        }
+    }
+    tx.commit().map_err(ForgeError::from)
+}
+
+fn has_graph(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'graph_edges'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Symbols of the type and the trait of `imp`, preferring declarations in
+/// the impl's file.
+fn resolve(conn: &Connection, imp: &TraitImpl) -> Result<Option<(SymbolId, SymbolId)>> {
+    let declared = |name: &str, is_kind: fn(SymbolKind) -> bool| -> Result<Option<SymbolId>> {
+        let symbols: Vec<_> = super::reader::symbols_named(conn, name)?
+            .into_iter()
+            .filter(|s| is_kind(super::parse_symbol_kind_str(&s.kind)))
+            .collect();
+        let same_file = symbols
+            .iter()
+            .find(|s| Path::new(&s.file_path).ends_with(&imp.location.file_path));
+        Ok(same_file.or(symbols.first()).map(|s| SymbolId(s.entity_id)))
+    };
+    let Some(from) = declared(&imp.type_name, |k| k.is_type())? else {
+        return Ok(None);
+    };
+    let Some(to) = declared(&imp.trait_name, |k| k == SymbolKind::Trait)? else {
+        return Ok(None);
+    };
+    Ok(Some((from, to)))
+}
+
+/// The type and trait symbols of the stored `IMPLEMENTS` edges, keyed by
+/// the file and start of their impl block. Edges to symbols removed since
+/// are skipped.
+pub(crate) fn load_links(
+    conn: &Connection,
+) -> Result<HashMap<(PathBuf, u32), (SymbolId, SymbolId)>> {
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT e.from_id, e.to_id, e.data FROM graph_edges e
+         JOIN graph_entities f ON f.id = e.from_id
+         JOIN graph_entities t ON t.id = e.to_id
+         WHERE e.edge_type = 'IMPLEMENTS'",
+    ) else {
+        return Ok(HashMap::new());
+    };
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    Ok(rows
+        .flatten()
+        .filter_map(|(from, to, data)| {
+            let data: serde_json::Value = serde_json::from_str(&data).ok()?;
+            let file_path = PathBuf::from(data.get("file_path")?.as_str()?);
+            let byte_start = data.get("byte_start")?.as_u64()? as u32;
+            Some(((file_path, byte_start), (SymbolId(from), SymbolId(to))))
+        })
+        .collect())
+}
+
+/// Loads all stored trait impls, or `None` if the database has never been
+/// indexed with them.
+pub(crate) fn load_impls(db_path: &Path) -> Result<Option<Vec<TraitImpl>>> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let conn = crate::storage::open_read_only(db_path)?;
+    let mut stmt = match conn.prepare(
+        "SELECT type_name, trait_name, file_path, byte_start, byte_end, line_number
+         FROM forge_impls",
+    ) {
+        Ok(stmt) => stmt,
+        Err(_) => return Ok(None),
+    };
+
+    let rows = stmt.query_map([], |row| {
+        Ok(TraitImpl {
+            type_name: row.get(0)?,
+            trait_name: row.get(1)?,
+            location: Location {
+                file_path: row.get::<_, String>(2)?.into(),
+                byte_start: row.get(3)?,
+                byte_end: row.get(4)?,
+                line_number: row.get::<_, i64>(5)? as usize,
+            },
+        })
+    })?;
+    Ok(Some(rows.flatten().collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "use std::fmt;\n\
+                          struct Meters(f64);\n\
+                          impl fmt::Display for Meters {\n    \
+                              fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) }\n\
+                          }\n\
+                          impl Meters {\n    fn new() -> Self { Meters(0.0) }\n}\n\
+                          impl<T: Clone> Summary for Vec<T>\nwhere\n    T: Send,\n{\n}\n\
+                          impl<'a> Summary for &'a mut Meters {}\n\
+                          unsafe impl Send for Meters {}\n";
+
+    fn pairs(impls: &[TraitImpl]) -> Vec<(&str, &str)> {
+        impls
+            .iter()
+            .map(|i| (i.type_name.as_str(), i.trait_name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_impl_symbol_ids_tell_traits_apart() {
+        let impls = extract_impls(SOURCE, Path::new("src/lib.rs"));
+        let meters: Vec<SymbolId> = impls
+            .iter()
+            .filter(|i| i.type_name == "Meters")
+            .map(|i| i.to_symbol().id)
+            .collect();
+        assert_eq!(meters.len(), 3);
+        let distinct: std::collections::HashSet<_> = meters.iter().collect();
+        assert_eq!(distinct.len(), meters.len());
+    }
+
+    #[test]
+    fn test_extract_impls_uses_base_types() {
+        let impls = extract_impls(SOURCE, Path::new("src/lib.rs"));
+        assert_eq!(
+            pairs(&impls),
+            vec![
+                ("Meters", "Display"),
+                ("Vec", "Summary"),
+                ("Meters", "Summary"),
+                ("Meters", "Send"),
+            ]
+        );
+        assert_eq!(impls[0].location.line_number, 3);
+        assert_eq!(impls[1].location.line_number, 9);
+    }
+
+    #[test]
+    fn test_scan_impl_lines_matches_parser() {
+        let impls = scan_impl_lines(SOURCE, Path::new("src/lib.rs"));
+        assert_eq!(
+            pairs(&impls),
+            pairs(&extract_impls(SOURCE, Path::new("src/lib.rs")))
+        );
+        let first = &impls[0];
+        assert_eq!(first.location.line_number, 3);
+        assert!(SOURCE[first.location.byte_start as usize..].starts_with("impl fmt::Display"));
+    }
+
+    #[test]
+    fn test_save_and_load_impls() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("graph.db");
+        rusqlite::Connection::open(&db_path).unwrap();
+        assert_eq!(load_impls(&db_path).unwrap(), None);
+
+        let impls = extract_impls(SOURCE, Path::new("src/lib.rs"));
+        save_impls(&db_path, &impls).unwrap();
+        assert_eq!(load_impls(&db_path).unwrap(), Some(impls));
+    }
+}
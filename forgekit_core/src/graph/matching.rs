@@ -0,0 +1,108 @@
+//! Name matching for glob and fuzzy symbol lookup.
+
+use super::reader::SymbolRow;
+use crate::error::Result;
+use std::path::Path;
+
+fn fold(s: &str, case_insensitive: bool) -> Vec<char> {
+    if case_insensitive {
+        s.chars().flat_map(char::to_lowercase).collect()
+    } else {
+        s.chars().collect()
+    }
+}
+
+/// Returns true if `text` matches `pattern`, where `*` matches any run of
+/// characters (including none) and `?` matches exactly one.
+pub(crate) fn glob_match(pattern: &str, text: &str, case_insensitive: bool) -> bool {
+    let (pattern, text) = (
+        fold(pattern, case_insensitive),
+        fold(text, case_insensitive),
+    );
+
+    // Greedy match, backtracking to the most recent `*`
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+pub(crate) fn edit_distance(a: &str, b: &str, case_insensitive: bool) -> usize {
+    let (a, b) = (fold(a, case_insensitive), fold(b, case_insensitive));
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Connections opened by [`all_symbols`] on this thread.
+    pub(crate) static OPENS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Reads every symbol in the graph database; empty if it does not exist.
+pub(crate) fn all_symbols(db_path: &Path) -> Result<Vec<SymbolRow>> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = crate::storage::open_read_only(db_path)?;
+    #[cfg(test)]
+    OPENS.with(|opens| opens.set(opens.get() + 1));
+    super::reader::symbols_where(&conn, "1", [])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match_wildcards() {
+        assert!(glob_match("parse_*", "parse_config", false));
+        assert!(glob_match("*config", "parse_config", false));
+        assert!(glob_match("p?rse*", "parse", false));
+        assert!(glob_match("*_*_*", "a_b_c", false));
+        assert!(glob_match("*", "", false));
+        assert!(!glob_match("parse_?", "parse_", false));
+        assert!(!glob_match("Parse*", "parse_config", false));
+        assert!(glob_match("Parse*", "parse_config", true));
+        assert!(!glob_match("a*b", "acbx", false));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting", false), 3);
+        assert_eq!(edit_distance("", "abc", false), 3);
+        assert_eq!(edit_distance("same", "same", false), 0);
+        assert_eq!(edit_distance("Helper", "helper", false), 1);
+        assert_eq!(edit_distance("Helper", "helper", true), 0);
+    }
+}
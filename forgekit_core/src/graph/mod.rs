@@ -4,10 +4,40 @@
 //! finding references, and running graph algorithms.
 
 use crate::error::Result;
-use crate::storage::UnifiedGraphStore;
-use crate::types::{Cycle, CycleMember, Reference, ReferenceKind, Symbol, SymbolId};
+use crate::storage::{BackendKind, UnifiedGraphStore};
+use crate::types::{Cycle, Language, Reference, ReferenceKind, Symbol, SymbolId};
+use checkpoint::IndexCheckpoint;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Caller references buffered ahead of a [`GraphModule::callers_of_stream`] consumer.
+const CALLER_STREAM_BUFFER: usize = 64;
+
+pub mod alias;
+pub(crate) mod body_hash;
+mod chain;
+mod checkpoint;
+mod classify;
+mod cycles;
+mod export;
+mod history;
+pub mod impls;
+mod matching;
+pub mod query;
+mod reader;
+mod select;
+pub mod signature;
+
+pub use alias::{AliasKind, SymbolAlias};
+pub use export::GraphExport;
+pub use history::{GraphCheckpoint, GraphCheckpointId, GraphDiff, GraphHistory};
+pub use impls::TraitImpl;
+pub use query::{QueryPlan, QueryStrategy, SymbolQuery};
+pub use select::{GraphQuery, ReferenceQuery};
+pub use signature::{FunctionSignature, TypePattern};
 
 /// Impacted symbol from k-hop impact analysis.
 #[derive(Debug, Clone)]
@@ -20,6 +50,43 @@ pub struct ImpactedSymbol {
     pub edge_type: String,
 }
 
+/// Outcome of [`GraphModule::index`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexReport {
+    /// Files indexed by this call, relative to the codebase root
+    pub indexed: Vec<PathBuf>,
+    /// Files that could not be indexed, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+    /// Indexed files the graph had no record of before this call
+    pub added: usize,
+    /// Indexed files the graph already held, now re-indexed
+    pub updated: usize,
+    /// Files recorded in the graph that no longer exist, whose symbols
+    /// and references were deleted
+    pub removed: usize,
+    /// Files not indexed because they exceed
+    /// [`WalkOptions::max_file_bytes`](crate::walk::WalkOptions::max_file_bytes),
+    /// relative to the codebase root
+    pub skipped: Vec<PathBuf>,
+    /// Rust files indexed despite syntax errors, with the first error;
+    /// only what parsed was extracted from them
+    pub syntax_errors: Vec<(PathBuf, String)>,
+}
+
+impl IndexReport {
+    /// Returns true if no file failed.
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    fn fail(&mut self, path: impl Into<PathBuf>, reason: String) {
+        let path = path.into();
+        if !self.failed.iter().any(|(p, _)| *p == path) {
+            self.failed.push((path, reason));
+        }
+    }
+}
+
 /// Graph module for symbol and reference queries.
 ///
 /// # Examples
@@ -50,47 +117,475 @@ impl GraphModule {
     ///
     /// A vector of matching symbols, or empty if the graph DB does not exist.
     pub async fn find_symbol(&self, name: &str) -> Result<Vec<Symbol>> {
-        use magellan::CodeGraph;
-
         let db_path = &self.store.db_path;
         if !db_path.exists() {
             return Ok(Vec::new());
         }
 
-        let graph = CodeGraph::open(db_path).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to open magellan graph: {}", e))
-        })?;
+        let name = name.to_string();
+        let results = self
+            .store
+            .graph_readers
+            .query(move |conn| reader::symbols_named(conn, &name))
+            .await?;
 
-        let results = graph.search_symbols_by_name(name).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Symbol search failed: {}", e))
-        })?;
+        Ok(self.to_symbols(results))
+    }
+
+    /// Finds the symbols named by each of `names` at once.
+    ///
+    /// The graph is opened and scanned once, and each source file read at
+    /// most once, so this is much cheaper than calling
+    /// [`find_symbol`](Self::find_symbol) per name. Every input name maps
+    /// to its matches, empty if it has none.
+    pub async fn find_symbols_batch(&self, names: &[&str]) -> Result<HashMap<String, Vec<Symbol>>> {
+        let mut found: HashMap<String, Vec<Symbol>> = names
+            .iter()
+            .map(|name| (name.to_string(), Vec::new()))
+            .collect();
+        if names.is_empty() {
+            return Ok(found);
+        }
 
-        Ok(results
+        let rows = matching::all_symbols(&self.store.db_path)?
             .into_iter()
-            .map(|r| {
-                let file_path = std::path::PathBuf::from(&r.file_path);
-                let line_number = std::fs::read(&file_path)
-                    .map(|content| byte_offset_to_line_number(&content, r.byte_start))
-                    .unwrap_or(0);
-                Symbol {
-                    id: SymbolId(r.entity_id),
-                    name: Arc::from(r.name.clone()),
-                    fully_qualified_name: Arc::from(r.name.clone()),
-                    kind: parse_symbol_kind_str(&r.kind),
-                    language: map_magellan_language(&file_path),
-                    location: crate::types::Location {
-                        file_path,
-                        byte_start: r.byte_start as u32,
-                        byte_end: r.byte_end as u32,
-                        line_number,
-                    },
-                    parent_id: None,
-                    metadata: serde_json::Value::Null,
+            .filter(|r| found.contains_key(&r.name));
+        for symbol in self.to_symbols(rows) {
+            if let Some(matches) = found.get_mut(&*symbol.name) {
+                matches.push(symbol);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns the body hash of every symbol, as taken when it was indexed.
+    ///
+    /// Keep the result to pass to [`changed_since`](Self::changed_since)
+    /// after the next index.
+    pub async fn body_hashes(&self) -> Result<HashMap<SymbolId, blake3::Hash>> {
+        Ok(self
+            .to_symbols(matching::all_symbols(&self.store.db_path)?)
+            .into_iter()
+            .filter_map(|s| Some((s.id, s.body_hash?)))
+            .collect())
+    }
+
+    /// Returns the symbols whose bodies changed since `previous` was taken
+    /// with [`body_hashes`](Self::body_hashes), including new symbols.
+    ///
+    /// Symbols get new ids when their file is reindexed, so a symbol whose
+    /// id is not in `previous` counts as unchanged if its hash is.
+    pub async fn changed_since(
+        &self,
+        previous: &HashMap<SymbolId, blake3::Hash>,
+    ) -> Result<Vec<Symbol>> {
+        let known: HashSet<&blake3::Hash> = previous.values().collect();
+        Ok(self
+            .to_symbols(matching::all_symbols(&self.store.db_path)?)
+            .into_iter()
+            .filter(|s| {
+                let Some(hash) = &s.body_hash else {
+                    return true;
+                };
+                match previous.get(&s.id) {
+                    Some(old) => old != hash,
+                    None => !known.contains(hash),
                 }
             })
             .collect())
     }
 
+    /// Returns when the file defining `symbol` was last modified.
+    ///
+    /// Attribution is by file: every symbol in a file reports that file's
+    /// mtime. If several symbols share the name, the latest of their files
+    /// wins. Returns `None` if the symbol is not in the graph or its file
+    /// no longer exists.
+    pub async fn last_modified(&self, symbol: &str) -> Result<Option<SystemTime>> {
+        let mut latest = None;
+        for found in self.find_symbol(symbol).await? {
+            let path = self.store.codebase_path.join(&found.location.file_path);
+            if let Ok(modified) = tokio::fs::metadata(&path).await.and_then(|m| m.modified()) {
+                latest = latest.max(Some(modified));
+            }
+        }
+        Ok(latest)
+    }
+
+    /// Finds symbols whose name matches a glob `pattern`.
+    ///
+    /// `*` matches any run of characters and `?` matches exactly one.
+    /// Results are ordered by name, then file and position. An empty
+    /// pattern matches nothing.
+    pub async fn find_symbol_glob(
+        &self,
+        pattern: &str,
+        case_insensitive: bool,
+    ) -> Result<Vec<Symbol>> {
+        if pattern.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut results: Vec<_> = matching::all_symbols(&self.store.db_path)?
+            .into_iter()
+            .filter(|r| matching::glob_match(pattern, &r.name, case_insensitive))
+            .collect();
+        results.sort_by(|a, b| {
+            (&a.name, &a.file_path, a.byte_start).cmp(&(&b.name, &b.file_path, b.byte_start))
+        });
+        Ok(self.to_symbols(results))
+    }
+
+    /// Finds symbols whose name is within `max_distance` edits of `name`.
+    ///
+    /// Distance is the Levenshtein distance, so typos are tolerated.
+    /// Results are ordered by distance ascending, then by name. An empty
+    /// name matches nothing.
+    pub async fn find_symbol_fuzzy(
+        &self,
+        name: &str,
+        max_distance: usize,
+        case_insensitive: bool,
+    ) -> Result<Vec<Symbol>> {
+        if name.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut results: Vec<_> = matching::all_symbols(&self.store.db_path)?
+            .into_iter()
+            .filter_map(|r| {
+                let distance = matching::edit_distance(name, &r.name, case_insensitive);
+                (distance <= max_distance).then_some((distance, r))
+            })
+            .collect();
+        results.sort_by(|(da, a), (db, b)| {
+            (da, &a.name, &a.file_path, a.byte_start).cmp(&(
+                db,
+                &b.name,
+                &b.file_path,
+                b.byte_start,
+            ))
+        });
+        Ok(self.to_symbols(results.into_iter().map(|(_, r)| r)))
+    }
+
+    /// Converts symbol rows in order, reading and parsing each file once
+    /// for line numbers and visibility.
+    fn to_symbols(&self, rows: impl IntoIterator<Item = reader::SymbolRow>) -> Vec<Symbol> {
+        let mut sources: HashMap<String, Option<SourceFile>> = HashMap::new();
+        rows.into_iter()
+            .map(|r| {
+                let source = sources
+                    .entry(r.file_path.clone())
+                    .or_insert_with(|| SourceFile::read(Path::new(&r.file_path)));
+                symbol_from_query(r, source.as_ref())
+            })
+            .collect()
+    }
+
+    /// Writes every symbol and reference as a JSON document.
+    ///
+    /// The document is `{"symbols": [...], "references": [...]}` and is
+    /// stable for an unchanged graph, so exports can be diffed.
+    pub fn export_json(&self, writer: impl std::io::Write) -> Result<()> {
+        let graph = export::read_graph(&self.store.db_path)?;
+        serde_json::to_writer_pretty(writer, &graph)?;
+        Ok(())
+    }
+
+    /// Populates an empty store from a document written by
+    /// [`export_json`](Self::export_json).
+    ///
+    /// Fails without writing anything if the store already has symbols
+    /// or references.
+    pub fn import_json(&self, reader: impl std::io::Read) -> Result<()> {
+        self.store.ensure_writable()?;
+        let graph: GraphExport = serde_json::from_reader(reader)?;
+        export::write_graph(&self.store.db_path, &graph)
+    }
+
+    /// Returns the checkpointed history of this graph.
+    ///
+    /// See [`GraphHistory`] for recording checkpoints and reading the
+    /// graph as it was at one.
+    pub fn history(&self) -> GraphHistory {
+        GraphHistory::new(
+            &self.store.codebase_path,
+            &self.store.db_path,
+            self.store.read_only,
+        )
+    }
+
+    /// Returns the symbol whose definition contains `location`.
+    ///
+    /// When definitions nest, such as a method in an `impl`, the innermost
+    /// one is returned. `None` if the location is outside every symbol.
+    pub fn symbol_at(&self, location: &crate::types::Location) -> Result<Option<Symbol>> {
+        let graph = export::read_graph(&self.store.db_path)?;
+        Ok(chain::enclosing_symbol(&graph.symbols, &self.store.codebase_path, location).cloned())
+    }
+
+    /// Returns the symbols that reference a symbol named `name`, directly or
+    /// through other symbols, up to `max_depth` hops away.
+    ///
+    /// Each reference is resolved to the symbol whose definition contains
+    /// it. Symbols are ordered nearest first and appear once, so cycles
+    /// terminate. With `calls_only`, only calls are followed.
+    pub fn referrer_chain(
+        &self,
+        name: &str,
+        max_depth: usize,
+        calls_only: bool,
+    ) -> Result<Vec<Symbol>> {
+        let graph = export::read_graph(&self.store.db_path)?;
+        Ok(chain::incoming_chain(
+            &graph,
+            &self.store.codebase_path,
+            name,
+            max_depth,
+            calls_only,
+        ))
+    }
+
+    /// Renders the call graph around `root` as a GraphViz digraph.
+    ///
+    /// Includes every function within `max_hops` calls of a symbol named
+    /// `root`, in either direction, and the calls between them. The root is
+    /// filled. Nodes are emitted in symbol id order and edges sorted by
+    /// caller then callee, so the output is stable for an unchanged graph.
+    pub fn call_graph_dot(&self, root: &str, max_hops: u32) -> Result<String> {
+        use std::collections::{BTreeMap, BTreeSet};
+        use std::fmt::Write;
+
+        let graph = export::read_graph(&self.store.db_path)?;
+        let roots: Vec<SymbolId> = graph
+            .symbols
+            .iter()
+            .filter(|s| &*s.name == root || &*s.fully_qualified_name == root)
+            .map(|s| s.id)
+            .collect();
+        if roots.is_empty() {
+            return Err(crate::error::ForgeError::SymbolNotFound(root.to_string()));
+        }
+
+        // Magellan records some calls more than once, so collect into a set
+        let calls: BTreeSet<(SymbolId, SymbolId)> = graph
+            .references
+            .iter()
+            .filter(|r| r.kind == ReferenceKind::Call && r.from.0 != 0 && r.to.0 != 0)
+            .map(|r| (r.from, r.to))
+            .collect();
+
+        let mut included: BTreeSet<SymbolId> = roots.iter().copied().collect();
+        let mut frontier = roots.clone();
+        for _ in 0..max_hops {
+            let mut next = Vec::new();
+            for &(caller, callee) in &calls {
+                for (near, far) in [(caller, callee), (callee, caller)] {
+                    if frontier.contains(&near) && included.insert(far) {
+                        next.push(far);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        let names: BTreeMap<SymbolId, &str> =
+            graph.symbols.iter().map(|s| (s.id, &*s.name)).collect();
+        let mut out = String::from("digraph call_graph {\n    node [shape=box];\n");
+        for id in &included {
+            let label = crate::cfg::dot_quote(names.get(id).copied().unwrap_or_default());
+            let style = if roots.contains(id) {
+                ", style=filled, fillcolor=lightblue"
+            } else {
+                ""
+            };
+            let _ = writeln!(out, "    s{} [label={}{}];", id.0, label, style);
+        }
+        for (caller, callee) in &calls {
+            if included.contains(caller) && included.contains(callee) {
+                let _ = writeln!(out, "    s{} -> s{};", caller.0, callee.0);
+            }
+        }
+        out.push_str("}\n");
+        Ok(out)
+    }
+
+    /// Reports how [`query`](Self::query) will execute `query`.
+    ///
+    /// Uses an indexed lookup when the graph database exists and has been
+    /// indexed, and falls back to scanning source files otherwise.
+    pub fn explain(&self, query: &SymbolQuery) -> QueryPlan {
+        let db_path = &self.store.db_path;
+        let db_exists = db_path.exists();
+        let entity_count = entity_count(db_path);
+
+        QueryPlan::build(
+            query,
+            self.store.backend_kind,
+            db_path.clone(),
+            db_exists,
+            entity_count,
+        )
+    }
+
+    /// Starts a fluent [`GraphQuery`] that composes symbol lookups, caller
+    /// hops and filters, evaluated lazily when collected.
+    pub fn select(&self) -> GraphQuery {
+        GraphQuery::new(self.clone())
+    }
+
+    /// Runs a symbol query according to its [`QueryPlan`].
+    pub async fn query(&self, query: &SymbolQuery) -> Result<Vec<Symbol>> {
+        let plan = self.explain(query);
+        tracing::debug!("symbol query plan:\n{}", plan);
+
+        let candidates = match plan.strategy {
+            QueryStrategy::Indexed => self.find_symbol(&query.name).await?,
+            QueryStrategy::FileScan => {
+                let pattern = format!(r"\b{}\b", regex::escape(&query.name));
+                crate::search::SearchModule::new(self.store.clone())
+                    .pattern_search_via_files(&pattern)
+                    .await?
+            }
+        };
+
+        let codebase = &self.store.codebase_path;
+        let matches = candidates
+            .into_iter()
+            .filter(|s| s.name.as_ref() == query.name)
+            .filter(|s| query.kind.is_none_or(|kind| s.kind == kind))
+            .filter(|s| {
+                query.file.as_ref().is_none_or(|prefix| {
+                    let path = &s.location.file_path;
+                    path.starts_with(prefix)
+                        || path
+                            .strip_prefix(codebase)
+                            .is_ok_and(|rel| rel.starts_with(prefix))
+                })
+            });
+
+        Ok(match query.limit {
+            Some(n) => matches.take(n).collect(),
+            None => matches.collect(),
+        })
+    }
+
+    /// Finds the types that implement the trait named `trait_name`.
+    ///
+    /// Uses the trait impls recorded by [`index`](Self::index), which
+    /// include traits declared outside the codebase such as `Display`.
+    /// Before the first index, Rust sources are scanned for
+    /// `impl Trait for Type` lines instead. Generic impls count for their
+    /// base type, so `impl<T> Trait for Vec<T>` yields `Vec`.
+    ///
+    /// Each type is returned once, as its declaration when the graph has
+    /// one and otherwise as a [`SymbolKind::Impl`](crate::types::SymbolKind::Impl)
+    /// symbol at the impl block. Results are in the order of the impls by
+    /// file, then position.
+    pub async fn implementors_of(&self, trait_name: &str) -> Result<Vec<Symbol>> {
+        let trait_name = alias::simple_name(trait_name);
+        let mut found = match impls::load_impls(&self.store.db_path)? {
+            Some(found) => found,
+            None => self.scan_impls().await,
+        };
+        found.retain(|i| i.trait_name == trait_name);
+        found.sort_by(|a, b| {
+            (&a.location.file_path, a.location.byte_start)
+                .cmp(&(&b.location.file_path, b.location.byte_start))
+        });
+
+        let names: Vec<&str> = found.iter().map(|i| i.type_name.as_str()).collect();
+        let declared = self.find_symbols_batch(&names).await?;
+        let codebase = &self.store.codebase_path;
+        let mut seen = HashSet::new();
+        let mut implementors = Vec::new();
+        for imp in &found {
+            let types: Vec<&Symbol> = declared
+                .get(&imp.type_name)
+                .into_iter()
+                .flatten()
+                .filter(|s| s.kind.is_type())
+                .collect();
+            // Prefer a declaration in the same file as the impl
+            let same_file = types.iter().find(|s| {
+                codebase.join(&s.location.file_path) == codebase.join(&imp.location.file_path)
+            });
+            let symbol = match same_file.or(types.first()) {
+                Some(declaration) => (*declaration).clone(),
+                None => imp.to_symbol(),
+            };
+            let key = (
+                symbol.name.clone(),
+                codebase.join(&symbol.location.file_path),
+                symbol.location.byte_start,
+            );
+            if seen.insert(key) {
+                implementors.push(symbol);
+            }
+        }
+        Ok(implementors)
+    }
+
+    /// Scans Rust sources for trait impl headers.
+    async fn scan_impls(&self) -> Vec<TraitImpl> {
+        let codebase_path = &self.store.codebase_path;
+        let mut files = Vec::new();
+        crate::search::collect_source_files(codebase_path, self.store.walk_options(), &mut files)
+            .await;
+        files.retain(|f| crate::walk::is_supported_source(f) == Some(Language::Rust));
+
+        let mut found = Vec::new();
+        for path in files {
+            let Ok(source) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            let relative = path.strip_prefix(codebase_path).unwrap_or(&path);
+            found.extend(impls::scan_impl_lines(&source, relative));
+        }
+        found
+    }
+
+    /// Finds functions whose signature fits the given type patterns.
+    ///
+    /// Every pattern in `params` must match a distinct parameter (in any
+    /// order, `self` excluded) and `returns`, if given, must match the
+    /// return type. Signatures are read from Rust sources, so this works
+    /// without an indexed database. Results are ordered by file, then
+    /// position.
+    pub async fn find_by_signature(
+        &self,
+        params: &[TypePattern],
+        returns: Option<TypePattern>,
+    ) -> Result<Vec<Symbol>> {
+        let codebase_path = &self.store.codebase_path;
+        let mut files = Vec::new();
+        crate::search::collect_source_files(codebase_path, self.store.walk_options(), &mut files)
+            .await;
+        // Signatures are parsed as Rust
+        files.retain(|f| crate::walk::is_supported_source(f) == Some(Language::Rust));
+        files.sort();
+
+        let mut symbols = Vec::new();
+        for path in files {
+            let Ok(source) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            let relative = path.strip_prefix(codebase_path).unwrap_or(&path);
+            symbols.extend(
+                signature::extract_signatures(&source, relative)
+                    .iter()
+                    .filter(|sig| sig.matches(params, returns.as_ref()))
+                    .map(FunctionSignature::to_symbol),
+            );
+        }
+
+        Ok(symbols)
+    }
+
     /// Finds a symbol by its stable ID.
     ///
     /// # Arguments
@@ -114,42 +609,124 @@ impl GraphModule {
     ///
     /// A vector of call-references to this symbol, or empty if the graph DB does not exist.
     pub async fn callers_of(&self, name: &str) -> Result<Vec<Reference>> {
-        use magellan::CodeGraph;
+        use futures::TryStreamExt;
 
-        let db_path = &self.store.db_path;
-        if !db_path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let mut graph = CodeGraph::open(db_path).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to open magellan graph: {}", e))
-        })?;
+        self.callers_of_stream(name).try_collect().await
+    }
 
-        let symbols = graph.search_symbols_by_name(name).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Symbol search failed: {}", e))
-        })?;
+    /// Streams the callers of a symbol as they are read.
+    ///
+    /// References are produced on a graph reader thread and buffered a
+    /// few at a time, so a slow consumer applies backpressure and dropping
+    /// the stream stops the lookup. Yields nothing if the graph DB does
+    /// not exist.
+    pub fn callers_of_stream(&self, name: &str) -> impl futures::Stream<Item = Result<Reference>> {
+        use futures::StreamExt;
+
+        if !self.store.db_path.exists() {
+            return futures::stream::empty().left_stream();
+        }
 
-        let mut callers = Vec::new();
-        for sym in &symbols {
-            if let Ok(call_facts) = graph.callers_of_symbol(&sym.file_path, name) {
-                for fact in call_facts {
-                    callers.push(Reference {
-                        from: SymbolId(0),
-                        to: SymbolId(0),
-                        from_name: Some(fact.caller.clone()),
-                        to_name: Some(fact.callee.clone()),
-                        kind: ReferenceKind::Call,
-                        location: crate::types::Location {
-                            file_path: fact.file_path.clone(),
+        let name = name.to_string();
+        let codebase_path = self.store.codebase_path.clone();
+        self.store
+            .graph_readers
+            .stream(CALLER_STREAM_BUFFER, move |conn, emit| {
+                let symbols = reader::symbols_named(conn, &name)?;
+                let mut classifier = classify::SourceClassifier::new(&codebase_path);
+
+                for sym in &symbols {
+                    for fact in reader::callers_of(conn, sym.entity_id)? {
+                        let location = crate::types::Location {
+                            file_path: fact.file_path,
                             byte_start: fact.byte_start as u32,
                             byte_end: fact.byte_end as u32,
                             line_number: fact.start_line,
-                        },
-                    });
+                        };
+                        let kind =
+                            classifier.classify(&location, &fact.callee, ReferenceKind::Call);
+                        let reference = Reference {
+                            from: SymbolId(0),
+                            to: SymbolId(0),
+                            from_name: Some(fact.caller),
+                            to_name: Some(fact.callee),
+                            kind,
+                            location,
+                        };
+                        if !emit(reference) {
+                            return Ok(());
+                        }
+                    }
+                }
+                Ok(())
+            })
+            .right_stream()
+    }
+
+    /// Returns the aliases of `name`, following aliases of aliases.
+    ///
+    /// Aliases are recorded by [`index`](Self::index); this is empty until
+    /// the codebase has been indexed.
+    pub async fn aliases_of(&self, name: &str) -> Result<Vec<SymbolAlias>> {
+        let all = alias::load_aliases(&self.store.db_path)?;
+
+        let mut seen: HashSet<String> = HashSet::from([name.to_string()]);
+        let mut queue: VecDeque<String> = VecDeque::from([name.to_string()]);
+        let mut found = Vec::new();
+        while let Some(target) = queue.pop_front() {
+            for a in all.iter().filter(|a| a.target == target) {
+                if seen.insert(a.alias.clone()) {
+                    queue.push_back(a.alias.clone());
                 }
+                found.push(a.clone());
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Finds all callers of a symbol, including calls made through its aliases.
+    ///
+    /// Like [`callers_of`](Self::callers_of), but a call to `bar()` after
+    /// `pub use foo as bar;` is reported as a caller of `foo`.
+    pub async fn callers_of_with_aliases(&self, name: &str) -> Result<Vec<Reference>> {
+        let mut callers = self.callers_of(name).await?;
+
+        let aliases = self.aliases_of(name).await?;
+        if aliases.is_empty() {
+            return Ok(callers);
+        }
+
+        let codebase_path = &self.store.codebase_path;
+        let mut files = Vec::new();
+        crate::search::collect_source_files(codebase_path, self.store.walk_options(), &mut files)
+            .await;
+        // Alias call sites are found with the Rust parser
+        files.retain(|f| crate::walk::is_supported_source(f) == Some(Language::Rust));
+        files.sort();
+
+        let mut sources = Vec::new();
+        for path in files {
+            if let Ok(source) = tokio::fs::read_to_string(&path).await {
+                let relative = path.strip_prefix(codebase_path).unwrap_or(&path);
+                sources.push((relative.to_path_buf(), source));
+            }
+        }
+
+        let names: HashSet<&str> = aliases.iter().map(|a| a.alias.as_str()).collect();
+        for alias_name in names {
+            let mut via_alias = self.callers_of(alias_name).await?;
+            for (relative, source) in &sources {
+                via_alias.extend(alias::call_sites(source, relative, alias_name));
+            }
+            for mut caller in via_alias {
+                caller.to_name = Some(name.to_string());
+                callers.push(caller);
             }
         }
 
+        let mut seen = HashSet::new();
+        callers.retain(|r| seen.insert((r.location.file_path.clone(), r.location.byte_start)));
         Ok(callers)
     }
 
@@ -161,81 +738,159 @@ impl GraphModule {
     ///
     /// # Returns
     ///
-    /// A vector of all cross-file references, or empty if the graph DB does not exist.
+    /// A vector of all cross-file references, plus an
+    /// [`Implements`](ReferenceKind::Implements) edge from each type
+    /// implementing `name` if it is a trait, or empty if the graph DB does
+    /// not exist.
     pub async fn references(&self, name: &str) -> Result<Vec<Reference>> {
-        use magellan::{cross_file_references_to, CodeGraph};
-
         let db_path = &self.store.db_path;
         if !db_path.exists() {
             return Ok(Vec::new());
         }
 
-        let graph = CodeGraph::open(db_path).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to open magellan graph: {}", e))
-        })?;
-
-        let cross_refs = cross_file_references_to(&graph, name).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Reference query failed: {}", e))
-        })?;
+        let query = name.to_string();
+        let cross_refs = self
+            .store
+            .graph_readers
+            .query(move |conn| reader::references_to(conn, &query))
+            .await?;
 
-        Ok(cross_refs
+        let mut classifier = classify::SourceClassifier::new(&self.store.codebase_path);
+        let mut references: Vec<Reference> = cross_refs
             .into_iter()
-            .map(|r| Reference {
-                from: SymbolId(0),
-                to: SymbolId(0),
-                from_name: Some(r.from_symbol_id.clone()),
-                to_name: Some(r.to_symbol_id.clone()),
-                kind: ReferenceKind::TypeReference,
-                location: crate::types::Location {
+            .map(|r| {
+                let location = crate::types::Location {
                     file_path: std::path::PathBuf::from(&r.file_path),
                     byte_start: r.byte_start as u32,
                     byte_end: r.byte_end as u32,
                     line_number: r.line_number,
-                },
+                };
+                Reference {
+                    from: SymbolId(0),
+                    to: SymbolId(0),
+                    from_name: Some(r.from_symbol_id.clone()),
+                    to_name: Some(r.to_symbol_id.clone()),
+                    kind: classifier.classify(&location, name, ReferenceKind::TypeReference),
+                    location,
+                }
             })
-            .collect())
+            .collect();
+
+        // Types implementing a trait point at it through their impl blocks
+        let trait_name = alias::simple_name(name);
+        let links = self.store.graph_readers.query(impls::load_links).await?;
+        references.extend(
+            impls::load_impls(db_path)?
+                .unwrap_or_default()
+                .iter()
+                .filter(|i| i.trait_name == trait_name)
+                .map(|i| {
+                    let key = (i.location.file_path.clone(), i.location.byte_start);
+                    i.to_reference(links.get(&key).copied())
+                }),
+        );
+        Ok(references)
+    }
+
+    /// Finds the references to `name` of the given `kind`.
+    ///
+    /// Scans the Rust sources under the codebase path and classifies each
+    /// occurrence of `name` by its context: a name in a `use` path is an
+    /// `Import`, one followed by `(` a `Call`, one in a type position a
+    /// `TypeReference`, and any other a `Use`. Declarations of `name` are
+    /// not references. Results are ordered by file and position.
+    pub async fn references_by_kind(
+        &self,
+        name: &str,
+        kind: ReferenceKind,
+    ) -> Result<Vec<Reference>> {
+        let codebase_path = &self.store.codebase_path;
+        let mut files = Vec::new();
+        crate::search::collect_source_files(codebase_path, self.store.walk_options(), &mut files)
+            .await;
+        files.retain(|f| crate::walk::is_supported_source(f) == Some(Language::Rust));
+        files.sort();
+
+        let mut found = Vec::new();
+        for path in files {
+            if let Ok(source) = tokio::fs::read_to_string(&path).await {
+                let relative = path.strip_prefix(codebase_path).unwrap_or(&path);
+                found.extend(
+                    classify::references_in(&source, relative, name)
+                        .into_iter()
+                        .filter(|r| r.kind == kind),
+                );
+            }
+        }
+        Ok(found)
+    }
+
+    /// Finds all cross-file references to a symbol, including references
+    /// through its aliases and the alias declarations themselves.
+    pub async fn references_with_aliases(&self, name: &str) -> Result<Vec<Reference>> {
+        let mut references = self.references(name).await?;
+
+        for a in self.aliases_of(name).await? {
+            for mut reference in self.references(&a.alias).await? {
+                reference.to_name = Some(name.to_string());
+                references.push(reference);
+            }
+            references.push(Reference {
+                from: SymbolId(0),
+                to: SymbolId(0),
+                from_name: None,
+                to_name: Some(a.target.clone()),
+                kind: match a.kind {
+                    AliasKind::TypeAlias => ReferenceKind::TypeReference,
+                    AliasKind::ReExport | AliasKind::Import => ReferenceKind::Import,
+                },
+                location: a.location,
+            });
+        }
+
+        let mut seen = HashSet::new();
+        references.retain(|r| seen.insert((r.location.file_path.clone(), r.location.byte_start)));
+        Ok(references)
     }
 
     /// Finds all symbols reachable from a given symbol.
     ///
-    /// Uses BFS traversal to find all symbols that can be reached
-    /// from the starting symbol through the call graph.
+    /// Walks the forward call graph breadth first: magellan's call edges on
+    /// SQLite, the stored references on Native V3. Calls whose caller was
+    /// not recorded are attributed to the symbol whose definition contains
+    /// them.
     ///
     /// # Arguments
     ///
     /// * `id` - The starting symbol ID
+    /// * `max_depth` - Maximum number of hops to follow, or `None` for no limit
     ///
     /// # Returns
     ///
-    /// A vector of reachable symbol IDs
-    pub async fn reachable_from(&self, id: SymbolId) -> Result<Vec<SymbolId>> {
-        // Build adjacency list for BFS
+    /// The reachable symbol IDs, nearest first, each once and without `id`
+    /// itself, or empty if the graph DB does not exist.
+    pub async fn reachable_from(
+        &self,
+        id: SymbolId,
+        max_depth: Option<usize>,
+    ) -> Result<Vec<SymbolId>> {
         let mut adjacency: HashMap<SymbolId, Vec<SymbolId>> = HashMap::new();
-
-        // Query references to build the graph
-        let refs = self.store.query_references(id).await?;
-        for reference in &refs {
-            adjacency
-                .entry(reference.from)
-                .or_default()
-                .push(reference.to);
+        for (from, to) in self.call_edges()? {
+            adjacency.entry(from).or_default().push(to);
         }
 
-        // BFS from the starting node
-        let mut visited = HashSet::new();
-        let mut queue = VecDeque::new();
+        let mut visited = HashSet::from([id]);
+        let mut queue = VecDeque::from([(id, 0)]);
         let mut reachable = Vec::new();
 
-        queue.push_back(id);
-        visited.insert(id);
-
-        while let Some(current) = queue.pop_front() {
-            if let Some(neighbors) = adjacency.get(&current) {
-                for &neighbor in neighbors {
-                    if visited.insert(neighbor) {
-                        queue.push_back(neighbor);
-                        reachable.push(neighbor);
-                    }
+        while let Some((current, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for &neighbor in adjacency.get(&current).into_iter().flatten() {
+                if visited.insert(neighbor) {
+                    queue.push_back((neighbor, depth + 1));
+                    reachable.push(neighbor);
                 }
             }
         }
@@ -243,46 +898,61 @@ impl GraphModule {
         Ok(reachable)
     }
 
+    /// Caller to callee pairs of the forward call graph.
+    fn call_edges(&self) -> Result<Vec<(SymbolId, SymbolId)>> {
+        if self.store.backend_kind == BackendKind::NativeV3 {
+            return Ok(self.store.reference_edges());
+        }
+
+        let graph = export::read_graph(&self.store.db_path)?;
+        let by_id: HashSet<SymbolId> = graph.symbols.iter().map(|s| s.id).collect();
+        Ok(graph
+            .references
+            .iter()
+            .filter(|r| r.kind == ReferenceKind::Call && r.to.0 != 0)
+            .filter_map(|r| {
+                let from = if by_id.contains(&r.from) {
+                    r.from
+                } else {
+                    chain::enclosing_symbol(&graph.symbols, &self.store.codebase_path, &r.location)?
+                        .id
+                };
+                (from != r.to).then_some((from, r.to))
+            })
+            .collect())
+    }
+
     /// Detects cycles in the call graph using SCC condensation.
     ///
     /// Supernodes with more than one member represent strongly connected
-    /// components (mutual recursion / call cycles).
+    /// components (mutual recursion / call cycles); a function that calls
+    /// itself directly is reported as a one-member cycle. Only call edges are
+    /// followed; see [`cycles_with_edges`](Self::cycles_with_edges) to
+    /// include other reference kinds.
     ///
     /// # Returns
     ///
     /// A vector of detected cycles, or empty if the graph DB does not exist.
     pub async fn cycles(&self) -> Result<Vec<Cycle>> {
-        use magellan::CodeGraph;
+        self.cycles_with_edges(&[ReferenceKind::Call]).await
+    }
 
+    /// Detects cycles using only edges of the given reference kinds.
+    ///
+    /// Type and trait-bound references can form cycles that are not
+    /// recursive calls; passing `[Call]` reports call cycles only, while
+    /// adding `TypeReference` also reports type-reference cycles.
+    ///
+    /// # Returns
+    ///
+    /// A vector of detected cycles, or empty if the graph DB does not exist.
+    pub async fn cycles_with_edges(&self, edge_kinds: &[ReferenceKind]) -> Result<Vec<Cycle>> {
         let db_path = &self.store.db_path;
         if !db_path.exists() {
             return Ok(Vec::new());
         }
 
-        let graph = CodeGraph::open(db_path).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to open magellan graph: {}", e))
-        })?;
-
-        let report = graph.detect_cycles().map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Cycle detection failed: {}", e))
-        })?;
-
-        Ok(report
-            .cycles
-            .into_iter()
-            .map(|c| Cycle {
-                members: c
-                    .members
-                    .into_iter()
-                    .map(|si| CycleMember {
-                        symbol_id: si.symbol_id,
-                        fqn: si.fqn,
-                        file_path: si.file_path,
-                        kind: si.kind,
-                    })
-                    .collect(),
-            })
-            .collect())
+        cycles::detect_cycles(db_path, edge_kinds)
     }
 
     /// Returns the number of symbols in the graph.
@@ -317,12 +987,12 @@ impl GraphModule {
         }
 
         let mut graph = CodeGraph::open(db_path).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to open magellan graph: {}", e))
+            crate::error::ForgeError::from_graph("Failed to open magellan graph", e)
         })?;
 
-        let symbols = graph.search_symbols_by_name(symbol_name).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Symbol search failed: {}", e))
-        })?;
+        let symbols = graph
+            .search_symbols_by_name(symbol_name)
+            .map_err(|e| crate::error::ForgeError::from_graph("Symbol search failed", e))?;
 
         let start_entity_id = match symbols.first() {
             Some(s) => s.entity_id,
@@ -388,93 +1058,601 @@ impl GraphModule {
     /// For Native V3 backend, also indexes cross-file references using
     /// sqlitegraph directly (a capability SQLite doesn't support).
     ///
+    /// Magellan calls go through the store's circuit breaker: after repeated
+    /// consecutive failures, indexing fails fast with
+    /// [`ForgeError::BackendNotAvailable`](crate::error::ForgeError::BackendNotAvailable)
+    /// until the cooldown elapses, instead of retrying every file.
+    ///
+    /// Progress is checkpointed to `.forge/index-checkpoint.json` every
+    /// few files. If indexing is interrupted, the next call resumes from
+    /// the checkpoint and only processes files that were not completed or
+    /// have changed since.
+    ///
+    /// A file that cannot be read or that magellan fails on is recorded in
+    /// the report and skipped; the rest of the codebase is still indexed.
+    /// Failed files are retried by the next run. A Rust file with syntax
+    /// errors is still indexed from what parsed, and its first error is
+    /// recorded in [`IndexReport::syntax_errors`].
+    ///
+    /// Files recorded in the graph that have since been deleted from disk
+    /// are dropped, with their symbols and references, before anything is
+    /// added, so re-indexing converges on the current tree.
+    ///
     /// # Returns
     ///
-    /// Ok(()) on success, or an error if indexing fails.
-    pub async fn index(&self) -> Result<()> {
+    /// The files indexed and the files that failed, with how many were
+    /// added, updated and removed, or an error if the database or backend
+    /// is unusable.
+    pub async fn index(&self) -> Result<IndexReport> {
+        self.index_with_budget(None, None).await
+    }
+
+    /// Runs [`index`](Self::index), stopping before the next file once
+    /// `cancel` is set.
+    ///
+    /// A cancelled run returns [`ForgeError::Cancelled`](crate::error::ForgeError::Cancelled)
+    /// and keeps the files indexed so far, checkpointed, so calling again
+    /// resumes where it stopped. The initial symbol scan cannot be
+    /// interrupted; the flag is checked before and after it.
+    pub async fn index_cancellable(&self, cancel: &AtomicBool) -> Result<IndexReport> {
+        self.index_with_budget(None, Some(cancel)).await
+    }
+
+    /// Runs [`index`](Self::index), stopping as if interrupted after
+    /// `budget` files have been processed or once `cancel` is set.
+    #[tracing::instrument(
+        name = "index",
+        skip(self, cancel),
+        fields(
+            codebase = %self.store.codebase_path.display(),
+            files = tracing::field::Empty,
+            symbols = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        )
+    )]
+    pub(crate) async fn index_with_budget(
+        &self,
+        budget: Option<usize>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<IndexReport> {
         use magellan::CodeGraph;
-        use std::path::Path;
+
+        let start = std::time::Instant::now();
 
         let codebase_path = &self.store.codebase_path;
         let db_path = &self.store.db_path;
+        let breaker = &self.store.index_breaker;
 
-        let mut graph = CodeGraph::open(db_path).map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to open magellan graph: {}", e))
+        self.store.ensure_writable()?;
+        breaker.check()?;
+
+        // Symbol ids and reference counts may change with the re-index;
+        // CFGs cached by body hash still hold for unchanged functions
+        self.store.cfg_cache.clear_symbols();
+        self.store.ref_index.invalidate();
+
+        let mut checkpoint = IndexCheckpoint::load(codebase_path, db_path);
+        if !checkpoint.is_empty() && entity_count(db_path) == 0 {
+            // The database was reset since the checkpoint was written
+            checkpoint = IndexCheckpoint::new(db_path);
+        }
+
+        let mut report = IndexReport::default();
+        let mut graph = breaker.observe(CodeGraph::open(db_path)).map_err(|e| {
+            crate::error::ForgeError::from_graph("Failed to open magellan graph", e)
         })?;
 
-        let count = graph
-            .scan_directory(Path::new(codebase_path), None)
-            .map_err(|e| {
-                crate::error::ForgeError::DatabaseError(format!("Failed to scan directory: {}", e))
-            })?;
+        let recorded = remove_vanished_files(&mut graph, codebase_path, &mut report)?;
+        let cancelled = || cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+
+        let mut files = Vec::new();
+        let oversized = crate::search::collect_source_files(
+            codebase_path,
+            self.store.walk_options(),
+            &mut files,
+        )
+        .await;
+        let config = self.store.indexer_config();
+        files.retain(|f| crate::walk::is_supported_source(f).is_some() && config.allows(f));
+        files.sort();
+        tracing::Span::current().record("files", files.len());
+        report.skipped = oversized
+            .iter()
+            .filter(|f| crate::walk::is_supported_source(f).is_some() && config.allows(f))
+            .map(|f| f.strip_prefix(codebase_path).unwrap_or(f).to_path_buf())
+            .collect();
+        report.skipped.sort();
+
+        if checkpoint.symbols_scanned {
+            tracing::info!(
+                "Resuming index of {} ({} files already done)",
+                codebase_path.display(),
+                checkpoint.completed.len()
+            );
+        } else {
+            if cancelled() {
+                return Err(crate::error::ForgeError::Cancelled);
+            }
+            let scan = breaker
+                .observe(config.scan(&mut graph, codebase_path, &oversized))
+                .map_err(|e| crate::error::ForgeError::from_graph("Failed to scan directory", e))?;
+
+            tracing::info!(
+                "Indexed {} files from {}",
+                scan.indexed,
+                codebase_path.display()
+            );
+            for diagnostic in scan.diagnostics {
+                if let magellan::WatchDiagnostic::Error { path, message, .. } = diagnostic {
+                    report.fail(path, message);
+                }
+            }
+
+            let _ = graph.rebuild_fts5();
+            checkpoint.symbols_scanned = true;
+            checkpoint.save(codebase_path)?;
+            if cancelled() {
+                return Err(crate::error::ForgeError::Cancelled);
+            }
+        }
 
-        tracing::info!("Indexed {} symbols from {}", count, codebase_path.display());
+        // Facts of files completed by an earlier run were saved with it
+        let mut facts = FileFacts::load(db_path, &checkpoint)?;
+
+        let result = Self::index_references(
+            &mut graph,
+            breaker,
+            codebase_path,
+            db_path,
+            &files,
+            budget,
+            cancel,
+            config,
+            &mut checkpoint,
+            &mut facts,
+            &mut report,
+        )
+        .await;
+        drop(graph);
+
+        // Facts first, so the checkpoint never claims a file whose aliases
+        // and impls were not stored
+        facts.save(db_path)?;
+        #[cfg(feature = "semantic")]
+        crate::search::embedding::index_embeddings(db_path, self.store.embedder.as_ref())?;
+        match result? {
+            true => IndexCheckpoint::remove(codebase_path),
+            false if cancelled() => {
+                checkpoint.save(codebase_path)?;
+                return Err(crate::error::ForgeError::Cancelled);
+            }
+            false => checkpoint.save(codebase_path)?,
+        }
 
-        let _ = graph.rebuild_fts5();
+        report.updated = report
+            .indexed
+            .iter()
+            .filter(|path| recorded.contains(&codebase_path.join(path)))
+            .count();
+        report.added = report.indexed.len() - report.updated;
 
-        Self::index_references_recursive(&mut graph, codebase_path, codebase_path).await
+        for (path, reason) in &report.failed {
+            tracing::warn!("Failed to index {}: {}", path.display(), reason);
+        }
+        for (path, error) in &report.syntax_errors {
+            tracing::warn!("Indexed {} despite errors: {}", path.display(), error);
+        }
+        let span = tracing::Span::current();
+        match self.store.symbol_count().await {
+            Ok(symbols) => {
+                span.record("symbols", symbols);
+            }
+            Err(e) => tracing::debug!("Failed to count indexed symbols: {}", e),
+        }
+        span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+        Ok(report)
     }
 
-    async fn index_references_recursive(
+    /// Indexes references and calls for each file not yet in `checkpoint`.
+    ///
+    /// Files are read, syntax-checked and scanned for aliases and trait
+    /// impls on up to `config.workers()` blocking threads at once, a batch
+    /// ahead of the magellan writes, then written to `graph` one at a time
+    /// in path order, so the result matches a sequential run. The body
+    /// hashes of each file's symbols are stored from the same source. Files that fail
+    /// are recorded in `report` and left out of the checkpoint. Returns
+    /// false if `budget` ran out or `cancel` was set before all files were
+    /// processed; `cancel` is checked before every file.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all, fields(files = files.len(), workers = config.workers()))]
+    async fn index_references(
         graph: &mut magellan::CodeGraph,
-        codebase_path: &std::path::Path,
-        current_dir: &std::path::Path,
-    ) -> Result<()> {
-        use tokio::fs;
-
-        let mut entries = fs::read_dir(current_dir).await.map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to read dir: {}", e))
-        })?;
-
-        while let Some(entry) = entries.next_entry().await.map_err(|e| {
-            crate::error::ForgeError::DatabaseError(format!("Failed to read entry: {}", e))
-        })? {
-            let path = entry.path();
-            if path.is_dir() {
-                // Recurse into subdirectories
-                Box::pin(Self::index_references_recursive(
-                    graph,
-                    codebase_path,
-                    &path,
-                ))
-                .await?;
-            } else if path.is_file() && path.extension().map(|e| e == "rs").unwrap_or(false) {
+        breaker: &crate::circuit::CircuitBreaker,
+        codebase_path: &Path,
+        graph_db_path: &Path,
+        files: &[std::path::PathBuf],
+        budget: Option<usize>,
+        cancel: Option<&AtomicBool>,
+        config: &crate::indexing::IndexerConfig,
+        checkpoint: &mut IndexCheckpoint,
+        facts: &mut FileFacts,
+        report: &mut IndexReport,
+    ) -> Result<bool> {
+        let mut pending: Vec<(PathBuf, String, u64)> = files
+            .iter()
+            .filter_map(|path| {
                 // Get relative path from codebase root
                 let relative_path = path
                     .strip_prefix(codebase_path)
-                    .unwrap_or(&path)
-                    .to_string_lossy();
-
-                if let Ok(source) = fs::read_to_string(&path).await {
-                    // Index references using relative path
-                    let _ = graph.index_references(&relative_path, source.as_bytes());
-                    // Index calls using relative path
-                    let _ = graph.index_calls(&relative_path, source.as_bytes());
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .to_string();
+                let mtime = checkpoint::modified_nanos(path);
+                (!checkpoint.is_done(&relative_path, mtime))
+                    .then(|| (path.clone(), relative_path, mtime))
+            })
+            .collect();
+        let finished = budget.is_none_or(|b| pending.len() <= b);
+        if let Some(budget) = budget {
+            pending.truncate(budget);
+        }
+
+        let workers = config.workers();
+        let mut hashes = rusqlite::Connection::open(graph_db_path)?;
+        // Bound how many parsed files are held in memory at once, and parse
+        // the next batch while magellan indexes this one
+        let mut batches = pending
+            .chunks(workers.max(1) * 4)
+            .map(<[_]>::to_vec)
+            .collect::<Vec<_>>()
+            .into_iter();
+        let spawn_parse = |batch: Vec<(PathBuf, String, u64)>| {
+            tokio::task::spawn_blocking(move || {
+                let parsed = parse_files(&batch, workers);
+                (batch, parsed)
+            })
+        };
+        let mut next = batches.next().map(spawn_parse);
+        while let Some(parsing) = next.take() {
+            let (batch, parsed) = parsing.await.map_err(std::io::Error::from)?;
+            next = batches.next().map(spawn_parse);
+            for ((path, relative_path, mtime), parsed) in batch.iter().zip(parsed) {
+                if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                    return Ok(false);
+                }
+                let _file = tracing::debug_span!("index_file", path = %relative_path).entered();
+                let relative_path = relative_path.clone();
+                let source = match parsed {
+                    Ok((source, file_facts, syntax_error)) => {
+                        if let Some(error) = syntax_error {
+                            report
+                                .syntax_errors
+                                .push((PathBuf::from(&relative_path), error));
+                        }
+                        facts.extend(file_facts);
+                        source
+                    }
+                    Err(reason) => {
+                        report.fail(relative_path, reason);
+                        continue;
+                    }
+                };
+                // Stop touching magellan once it has failed repeatedly
+                breaker.check()?;
+                let mut errors = Vec::new();
+                // Magellan replaces references only under the absolute path
+                clear_references(&hashes, &relative_path)?;
+                // Index references using relative path
+                if let Err(e) =
+                    breaker.observe(graph.index_references(&relative_path, source.as_bytes()))
+                {
+                    errors.push(format!("Reference indexing failed: {}", e));
+                }
+                // Index calls using relative path
+                if let Err(e) =
+                    breaker.observe(graph.index_calls(&relative_path, source.as_bytes()))
+                {
+                    errors.push(format!("Call indexing failed: {}", e));
+                }
+                if !errors.is_empty() {
+                    report.fail(relative_path, errors.join("; "));
+                    continue;
+                }
+                // Scanned symbols are stored under the absolute path
+                let absolute = path.to_string_lossy();
+                body_hash::store_for_file(
+                    &mut hashes,
+                    &[absolute.as_ref(), relative_path.as_str()],
+                    source.as_bytes(),
+                    config,
+                )?;
+
+                checkpoint.mark_done(relative_path.clone(), *mtime);
+                report.indexed.push(PathBuf::from(relative_path));
+                if report.indexed.len().is_multiple_of(checkpoint::SAVE_EVERY) {
+                    facts.save(graph_db_path)?;
+                    checkpoint.save(codebase_path)?;
                 }
             }
         }
 
-        Ok(())
+        Ok(finished)
     }
 }
 
-fn parse_symbol_kind_str(kind: &str) -> crate::types::SymbolKind {
-    use crate::types::SymbolKind;
-    match kind {
+/// Deletes the references and calls recorded under `relative_path` by an
+/// earlier run, with their edges, so reindexing a file does not leave the
+/// old ones behind.
+fn clear_references(conn: &rusqlite::Connection, relative_path: &str) -> Result<()> {
+    const STALE: &str = "SELECT id FROM graph_entities
+         WHERE kind IN ('Reference', 'Call')
+           AND (file_path = ?1 OR json_extract(data, '$.file') = ?1)";
+    conn.execute(
+        &format!("DELETE FROM graph_edges WHERE from_id IN ({STALE}) OR to_id IN ({STALE})"),
+        rusqlite::params![relative_path],
+    )?;
+    conn.execute(
+        &format!("DELETE FROM graph_entities WHERE id IN ({STALE})"),
+        rusqlite::params![relative_path],
+    )?;
+    Ok(())
+}
+
+/// Deletes every file recorded in `graph` that no longer exists on disk,
+/// counting them in `report`, and returns the absolute paths of the files
+/// that were recorded beforehand.
+fn remove_vanished_files(
+    graph: &mut magellan::CodeGraph,
+    codebase_path: &Path,
+    report: &mut IndexReport,
+) -> Result<HashSet<PathBuf>> {
+    let recorded: HashSet<PathBuf> = graph
+        .all_file_nodes()
+        .map_err(|e| crate::error::ForgeError::from_graph("Failed to list indexed files", e))?
+        .into_keys()
+        .map(|path| codebase_path.join(path))
+        .collect();
+
+    let mut vanished: Vec<&PathBuf> = recorded.iter().filter(|p| !p.exists()).collect();
+    vanished.sort();
+    for path in vanished {
+        graph
+            .delete_file(&path.to_string_lossy())
+            .map_err(|e| crate::error::ForgeError::from_graph("Failed to delete file", e))?;
+        // References and calls are recorded under the relative path
+        if let Ok(relative) = path.strip_prefix(codebase_path) {
+            graph
+                .delete_file(&relative.to_string_lossy())
+                .map_err(|e| crate::error::ForgeError::from_graph("Failed to delete file", e))?;
+        }
+        report.removed += 1;
+    }
+    Ok(recorded)
+}
+
+/// What indexing extracts from Rust sources besides magellan's graph.
+#[derive(Default)]
+struct FileFacts {
+    aliases: Vec<SymbolAlias>,
+    impls: Vec<TraitImpl>,
+}
+
+impl FileFacts {
+    /// Loads the stored facts of the files `checkpoint` marks completed.
+    fn load(db_path: &Path, checkpoint: &IndexCheckpoint) -> Result<Self> {
+        let completed = |location: &crate::types::Location| {
+            checkpoint
+                .completed
+                .contains_key(location.file_path.to_string_lossy().as_ref())
+        };
+        let mut facts = Self {
+            aliases: alias::load_aliases(db_path)?,
+            impls: impls::load_impls(db_path)?.unwrap_or_default(),
+        };
+        facts.aliases.retain(|a| completed(&a.location));
+        facts.impls.retain(|i| completed(&i.location));
+        Ok(facts)
+    }
+
+    fn extend(&mut self, other: FileFacts) {
+        self.aliases.extend(other.aliases);
+        self.impls.extend(other.impls);
+    }
+
+    fn save(&self, db_path: &Path) -> Result<()> {
+        alias::save_aliases(db_path, &self.aliases)?;
+        impls::save_impls(db_path, &self.impls)
+    }
+}
+
+/// A file's source, extracted facts and first syntax error, or why it
+/// cannot be indexed.
+type ParsedFile = std::result::Result<(String, FileFacts, Option<String>), String>;
+
+/// Reads and parses `files` on up to `workers` threads, returning the
+/// results in the order of `files`.
+fn parse_files(files: &[(PathBuf, String, u64)], workers: usize) -> Vec<ParsedFile> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let next = AtomicUsize::new(0);
+    let results: Vec<std::sync::Mutex<Option<ParsedFile>>> =
+        files.iter().map(|_| std::sync::Mutex::new(None)).collect();
+    std::thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, files.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some((path, relative_path, _)) = files.get(i) else {
+                    break;
+                };
+                let parsed = parse_file(path, relative_path);
+                *results[i]
+                    .lock()
+                    .expect("invariant: parse result mutex not poisoned") = Some(parsed);
+            });
+        }
+    });
+    results
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .expect("invariant: parse result mutex not poisoned")
+                .expect("invariant: every file is parsed")
+        })
+        .collect()
+}
+
+/// Reads `path` and, for Rust, checks its syntax and extracts its aliases
+/// and trait impls. Tree-sitter recovers around syntax errors, so the
+/// facts of a broken file are those of the parts that parsed.
+fn parse_file(path: &Path, relative_path: &str) -> ParsedFile {
+    let source =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    // Syntax checks and fact extraction only understand Rust
+    let mut facts = FileFacts::default();
+    let mut error = None;
+    if crate::walk::is_supported_source(path) == Some(Language::Rust) {
+        error = syntax_error(&source);
+        let relative_path = Path::new(relative_path);
+        facts.aliases = alias::extract_aliases(&source, relative_path);
+        facts.impls = impls::extract_impls(&source, relative_path);
+    }
+    Ok((source, facts, error))
+}
+
+/// Describes the first syntax error in Rust `source`, if any.
+fn syntax_error(source: &str) -> Option<String> {
+    let tree = alias::parse_rust(source)?;
+    let root = tree.root_node();
+    if !root.has_error() {
+        return None;
+    }
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.is_error() || node.is_missing() {
+            let kind = if node.is_missing() {
+                format!("missing `{}`", node.kind())
+            } else {
+                "unexpected input".to_string()
+            };
+            return Some(format!(
+                "Syntax error at line {}: {}",
+                node.start_position().row + 1,
+                kind
+            ));
+        }
+        let mut cursor = node.walk();
+        let mut children: Vec<_> = node
+            .children(&mut cursor)
+            .filter(|child| child.has_error())
+            .collect();
+        children.reverse();
+        stack.extend(children);
+    }
+    Some("Syntax error".to_string())
+}
+
+/// Number of entities in the graph database; 0 if it is missing or unindexed.
+fn entity_count(db_path: &Path) -> usize {
+    if !db_path.exists() {
+        return 0;
+    }
+    crate::storage::open_read_only(db_path)
+        .and_then(|conn| {
+            conn.query_row("SELECT COUNT(*) FROM graph_entities", [], |row| {
+                row.get::<_, i64>(0)
+            })
+        })
+        .map(|n| n as usize)
+        .unwrap_or(0)
+}
+
+/// A source file read for [`symbol_from_query`], parsed if tree-sitter
+/// has a grammar for it.
+struct SourceFile {
+    content: Vec<u8>,
+    parsed: Option<(crate::treesitter::SupportedLanguage, tree_sitter::Tree)>,
+}
+
+impl SourceFile {
+    fn read(path: &Path) -> Option<SourceFile> {
+        let content = std::fs::read(path).ok()?;
+        let parsed = crate::treesitter::CfgExtractor::detect_language(path)
+            .and_then(|lang| Some((lang, crate::treesitter::visibility::parse(&content, lang)?)));
+        Some(SourceFile { content, parsed })
+    }
+}
+
+/// Converts a symbol row, taking the line number and visibility from
+/// `source`, if the file could be read; the body hash is the one stored at
+/// index time.
+///
+/// Visibility comes from the declaration's modifiers in the tree-sitter
+/// parse, or from the start of its first line for languages without a
+/// grammar.
+fn symbol_from_query(r: reader::SymbolRow, source: Option<&SourceFile>) -> Symbol {
+    use crate::types::Visibility;
+
+    let file_path = std::path::PathBuf::from(&r.file_path);
+    let language = map_magellan_language(&file_path);
+    let content = source.map(|s| s.content.as_slice());
+    let line_number = content
+        .map(|content| byte_offset_to_line_number(content, r.byte_start))
+        .unwrap_or(0);
+    let visibility = source
+        .and_then(|source| {
+            let parsed = source.parsed.as_ref().and_then(|(lang, tree)| {
+                crate::treesitter::visibility::visibility_at(
+                    tree,
+                    &source.content,
+                    r.byte_start,
+                    *lang,
+                )
+            });
+            parsed.or_else(|| {
+                let rest = source.content.get(r.byte_start..)?;
+                let line = rest.split(|b| *b == b'\n').next()?;
+                let line = String::from_utf8_lossy(line);
+                Some(Visibility::from_declaration(&line, &r.name, &language))
+            })
+        })
+        .unwrap_or(Visibility::Unknown);
+    Symbol {
+        id: SymbolId(r.entity_id),
+        name: Arc::from(r.name.clone()),
+        fully_qualified_name: Arc::from(r.name),
+        kind: parse_symbol_kind_str(&r.kind),
+        language,
+        location: crate::types::Location {
+            file_path,
+            byte_start: r.byte_start as u32,
+            byte_end: r.byte_end as u32,
+            line_number,
+        },
+        parent_id: None,
+        visibility,
+        body_hash: r.body_hash,
+        metadata: serde_json::Value::Null,
+    }
+}
+
+pub(crate) fn parse_symbol_kind_str(kind: &str) -> crate::types::SymbolKind {
+    use crate::types::SymbolKind;
+    match kind {
         "fn" | "function" => SymbolKind::Function,
         "method" => SymbolKind::Method,
         "struct" | "class" => SymbolKind::Struct,
         "trait" | "interface" => SymbolKind::Trait,
         "enum" => SymbolKind::Enum,
-        "module" | "namespace" => SymbolKind::Module,
+        "impl" => SymbolKind::Impl,
+        "module" | "mod" | "namespace" => SymbolKind::Module,
         "type_alias" | "type" => SymbolKind::TypeAlias,
+        "const" | "constant" => SymbolKind::Constant,
+        "static" => SymbolKind::Static,
         _ => SymbolKind::Function,
     }
 }
 
-fn map_magellan_language(file_path: &std::path::Path) -> crate::types::Language {
-    use crate::types::Language;
-
+pub(crate) fn map_magellan_language(file_path: &std::path::Path) -> Language {
     match file_path.extension().and_then(|e| e.to_str()) {
         Some("rs") => Language::Rust,
         Some("py") => Language::Python,
@@ -600,46 +1778,1338 @@ mod tests {
         assert_eq!(symbols[0].name.as_ref(), "hello");
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_find_symbol_uses_reader_pool() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("lib.rs"), "fn hello() {}\n")
+            .await
+            .unwrap();
+
+        let forge = crate::ForgeBuilder::new()
+            .path(temp_dir.path())
+            .db_path(temp_dir.path().join("test-graph.db"))
+            .pool_size(2)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(forge.graph().store().pool_size(), 2);
+
+        // Readers opened before indexing still see the new symbols
+        assert!(forge.graph().find_symbol("hello").await.unwrap().is_empty());
+        forge.graph().index().await.unwrap();
+
+        let lookups: Vec<_> = (0..16)
+            .map(|_| {
+                let graph = forge.graph();
+                tokio::spawn(async move { graph.find_symbol("hello").await })
+            })
+            .collect();
+        for lookup in lookups {
+            assert_eq!(lookup.await.unwrap().unwrap().len(), 1);
+        }
+    }
+
     #[tokio::test]
-    async fn test_callers_of_after_index() {
+    async fn test_explain_file_scan_then_indexed() {
         let temp_dir = tempfile::tempdir().unwrap();
         let src_dir = temp_dir.path().join("src");
         tokio::fs::create_dir_all(&src_dir).await.unwrap();
         tokio::fs::write(
             src_dir.join("lib.rs"),
-            "fn helper() -> i32 { 1 }\nfn caller() -> i32 { helper() }\n",
+            "fn hello() {}\nfn hello_world() {}\n",
         )
         .await
         .unwrap();
 
         let forge = test_forge(temp_dir.path()).await;
+        let query = SymbolQuery::named("hello").kind(crate::types::SymbolKind::Function);
+
+        let plan = forge.graph().explain(&query);
+        assert_eq!(plan.strategy, QueryStrategy::FileScan);
+        assert_eq!(plan.entity_count, 0);
+        assert!(plan.residual.iter().any(|f| f.starts_with("kind")));
+        assert!(plan.to_string().contains("file-scan fallback"));
+
+        let symbols = forge.graph().query(&query).await.unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name.as_ref(), "hello");
+
         forge.graph().index().await.unwrap();
 
-        let callers = forge.graph().callers_of("helper").await.unwrap();
-        assert!(!callers.is_empty(), "should find caller calling helper");
+        let plan = forge.graph().explain(&query);
+        assert_eq!(plan.strategy, QueryStrategy::Indexed);
+        assert!(plan.db_exists);
+        assert!(plan.entity_count > 0);
+        assert_eq!(plan.pushed_down, vec!["name = \"hello\"".to_string()]);
+
+        let symbols = forge.graph().query(&query.limit(1)).await.unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name.as_ref(), "hello");
     }
 
     #[tokio::test]
-    async fn test_cycles_detect_mutual_recursion() {
+    async fn test_find_by_signature_returning_result() {
         let temp_dir = tempfile::tempdir().unwrap();
         let src_dir = temp_dir.path().join("src");
         tokio::fs::create_dir_all(&src_dir).await.unwrap();
-        tokio::fs::write(src_dir.join("lib.rs"), "fn a() { b() }\nfn b() { a() }\n")
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "pub fn load(path: &Path) -> Result<String, Error> { todo!() }\n\
+             pub fn parse(s: &str) -> io::Result<u32> { todo!() }\n\
+             pub fn name() -> String { String::new() }\n\
+             pub fn maybe() -> Option<Result<()>> { None }\n\
+             pub fn run() {}\n",
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+
+        let symbols = graph
+            .find_by_signature(&[], Some(TypePattern::named("Result")))
+            .await
+            .unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(names, vec!["load", "parse"]);
+        assert_eq!(symbols[0].location.line_number, 1);
+
+        let symbols = graph
+            .find_by_signature(
+                &[TypePattern::exact("&str")],
+                Some(TypePattern::named("Result")),
+            )
+            .await
+            .unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(names, vec!["parse"]);
+    }
+
+    #[tokio::test]
+    async fn test_explain_without_database() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let forge = test_forge(temp_dir.path()).await;
+        std::fs::remove_file(temp_dir.path().join("test-graph.db")).unwrap();
+
+        let plan = forge.graph().explain(&SymbolQuery::named("hello"));
+        assert_eq!(plan.strategy, QueryStrategy::FileScan);
+        assert!(!plan.db_exists);
+        assert!(plan.to_string().contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_history_recovers_graph_at_checkpoint() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("a.rs"),
+            "pub fn alpha() {}\npub fn beta() {}\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(src_dir.join("b.rs"), "pub fn untouched() {}\n")
             .await
             .unwrap();
 
         let forge = test_forge(temp_dir.path()).await;
+        let history = forge.graph().history();
         forge.graph().index().await.unwrap();
+        let first = history.checkpoint("before").unwrap();
 
-        let cycles = forge.graph().cycles().await.unwrap();
+        tokio::fs::write(
+            src_dir.join("a.rs"),
+            "pub fn alpha() {}\npub fn gamma() {}\n",
+        )
+        .await
+        .unwrap();
+        forge.graph().index().await.unwrap();
+        let second = history.checkpoint("after").unwrap();
+
+        let names = |symbols: Vec<Symbol>| {
+            let mut names: Vec<String> = symbols.iter().map(|s| s.name.to_string()).collect();
+            names.sort();
+            names.dedup();
+            names
+        };
+        assert_eq!(
+            names(history.symbols_at(first.id).unwrap()),
+            ["alpha", "beta", "untouched"]
+        );
+        assert_eq!(
+            names(history.symbols_at(second.id).unwrap()),
+            ["alpha", "gamma", "untouched"]
+        );
+
+        // Only the edited file contributes changes to the second checkpoint
+        let diff = history.diff_graph(first.id, second.id).unwrap();
+        assert_eq!(names(diff.added_symbols.clone()), ["gamma"]);
+        assert_eq!(names(diff.removed_symbols.clone()), ["beta"]);
+        assert!(diff
+            .added_symbols
+            .iter()
+            .chain(&diff.removed_symbols)
+            .all(|s| s.location.file_path.ends_with("a.rs")));
+        assert!(second.changes < first.changes);
+
+        let back = history.diff_graph(second.id, first.id).unwrap();
+        assert_eq!(names(back.added_symbols), ["beta"]);
+
+        // An unchanged graph adds an empty checkpoint
+        let third = history.checkpoint("unchanged").unwrap();
+        assert_eq!(third.changes, 0);
+        assert!(history.diff_graph(second.id, third.id).unwrap().is_empty());
+
+        let labels: Vec<_> = history
+            .checkpoints()
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.label, c.changes))
+            .collect();
+        assert_eq!(labels[0].0, "before");
+        assert_eq!(labels[1], ("after".to_string(), second.changes));
+        assert!(matches!(
+            history.symbols_at(GraphCheckpointId(99)),
+            Err(crate::error::ForgeError::InvalidQuery(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_callers_of_follows_reexport_alias() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("util.rs"), "pub fn foo() -> i32 { 1 }\n")
+            .await
+            .unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "mod util;\npub use util::foo as bar;\n\nfn consumer() -> i32 {\n    bar()\n}\n",
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let aliases = forge.graph().aliases_of("foo").await.unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].alias, "bar");
+        assert_eq!(aliases[0].kind, AliasKind::ReExport);
+
+        let callers = forge.graph().callers_of_with_aliases("foo").await.unwrap();
+        let via_alias = callers
+            .iter()
+            .find(|r| r.from_name.as_deref() == Some("consumer"))
+            .expect("call through alias should be reported");
+        assert_eq!(via_alias.to_name.as_deref(), Some("foo"));
+        assert_eq!(via_alias.location.line_number, 5);
+        assert!(via_alias.location.file_path.ends_with("lib.rs"));
+
+        let references = forge.graph().references_with_aliases("foo").await.unwrap();
+        assert!(references
+            .iter()
+            .any(|r| r.kind == ReferenceKind::Import && r.location.line_number == 2));
+    }
+
+    #[tokio::test]
+    async fn test_index_language_allowlist() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("lib.rs"), "pub fn rust_fn() {}\n")
+            .await
+            .unwrap();
+        tokio::fs::write(src_dir.join("util.c"), "int c_fn(void) { return 0; }\n")
+            .await
+            .unwrap();
+
+        let all = test_forge(temp_dir.path()).await;
+        all.graph().index().await.unwrap();
+        assert_eq!(all.graph().find_symbol("c_fn").await.unwrap().len(), 1);
+
+        let rust_only = crate::ForgeBuilder::new()
+            .path(temp_dir.path())
+            .db_path(temp_dir.path().join("rust-only.db"))
+            .languages([crate::types::Language::Rust])
+            .build()
+            .await
+            .unwrap();
+        rust_only.graph().index().await.unwrap();
+        assert_eq!(
+            rust_only
+                .graph()
+                .find_symbol("rust_fn")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(rust_only
+            .graph()
+            .find_symbol("c_fn")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_keeps_symbols_shifted_by_inserted_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        let lib = src_dir.join("lib.rs");
+        tokio::fs::write(&lib, "pub fn first() { second() }\npub fn second() {}\n")
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let history = forge.graph().history();
+        forge.graph().index().await.unwrap();
+        let before = history.checkpoint("before").unwrap();
+        assert!(!history.references_at(before.id).unwrap().is_empty());
+
+        tokio::fs::write(&lib, "\npub fn first() { second() }\npub fn second() {}\n")
+            .await
+            .unwrap();
+        forge.graph().index().await.unwrap();
+        let after = history.checkpoint("after").unwrap();
+
+        // Shifted symbols are neither removed nor added
+        let diff = history.diff_graph(before.id, after.id).unwrap();
+        assert!(diff.added_symbols.is_empty(), "{:?}", diff.added_symbols);
         assert!(
-            !cycles.is_empty(),
-            "should detect mutual recursion between a and b"
+            diff.removed_symbols.is_empty(),
+            "{:?}",
+            diff.removed_symbols
+        );
+        // and so are the references between them
+        assert!(
+            diff.added_references.is_empty(),
+            "{:?}",
+            diff.added_references
+        );
+        assert!(
+            diff.removed_references.is_empty(),
+            "{:?}",
+            diff.removed_references
+        );
+
+        // but the checkpoint holds their new locations
+        let second = history
+            .symbols_at(after.id)
+            .unwrap()
+            .into_iter()
+            .find(|s| &*s.name == "second")
+            .unwrap();
+        assert_eq!(second.location.line_number, 3);
+    }
+
+    #[tokio::test]
+    async fn test_index_resumes_from_checkpoint() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        for name in ["a", "b", "c", "d"] {
+            tokio::fs::write(
+                src_dir.join(format!("{}.rs", name)),
+                format!("pub fn {}() {{}}\n", name),
+            )
+            .await
+            .unwrap();
+        }
+        let checkpoint_path = temp_dir.path().join(checkpoint::CHECKPOINT_FILE);
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+
+        // Interrupted after half of the files
+        let first = graph.index_with_budget(Some(2), None).await.unwrap();
+        assert_eq!(
+            first.indexed,
+            vec![PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")]
+        );
+        assert!(checkpoint_path.exists());
+
+        let resumed = graph.index_with_budget(None, None).await.unwrap();
+        assert_eq!(
+            resumed.indexed,
+            vec![PathBuf::from("src/c.rs"), PathBuf::from("src/d.rs")]
+        );
+        assert!(!checkpoint_path.exists());
+        assert_eq!(graph.find_symbol("a").await.unwrap().len(), 1);
+
+        // A completed run leaves no checkpoint, so the next run starts over
+        let full = graph.index_with_budget(None, None).await.unwrap();
+        assert_eq!(full.indexed.len(), 4);
+    }
+
+    /// Sets the flag once the first file has been indexed
+    struct CancelAfterFirstFile(Arc<AtomicBool>);
+
+    impl<S> tracing_subscriber::Layer<S> for CancelAfterFirstFile
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_close(&self, id: tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+            if ctx
+                .span(&id)
+                .is_some_and(|span| span.name() == "index_file")
+            {
+                self.0.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_cancelled_after_first_file_resumes() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        for name in ["a", "b", "c"] {
+            tokio::fs::write(
+                src_dir.join(format!("{}.rs", name)),
+                format!("pub fn {}() {{}}\n", name),
+            )
+            .await
+            .unwrap();
+        }
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let subscriber =
+            tracing_subscriber::registry().with(CancelAfterFirstFile(Arc::clone(&cancel)));
+        let guard = tracing::subscriber::set_default(subscriber);
+        let err = graph.index_cancellable(&cancel).await.unwrap_err();
+        drop(guard);
+        assert!(matches!(err, crate::error::ForgeError::Cancelled));
+        assert!(temp_dir.path().join(checkpoint::CHECKPOINT_FILE).exists());
+
+        // The next call picks up after the file that was indexed
+        let resumed = graph.index().await.unwrap();
+        assert_eq!(
+            resumed.indexed,
+            vec![PathBuf::from("src/b.rs"), PathBuf::from("src/c.rs")]
+        );
+        for name in ["a", "b", "c"] {
+            assert_eq!(graph.find_symbol(name).await.unwrap().len(), 1);
+        }
+
+        // Set before starting, the scan is not started
+        let cancelled = AtomicBool::new(true);
+        assert!(matches!(
+            graph.index_cancellable(&cancelled).await,
+            Err(crate::error::ForgeError::Cancelled)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reindex_removes_deleted_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("a.rs"), "pub fn alpha() { beta(); }\n")
+            .await
+            .unwrap();
+        tokio::fs::write(src_dir.join("b.rs"), "pub fn beta() {}\n")
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+        let first = graph.index().await.unwrap();
+        assert_eq!((first.added, first.updated, first.removed), (2, 0, 0));
+        assert_eq!(graph.find_symbol("beta").await.unwrap().len(), 1);
+
+        tokio::fs::remove_file(src_dir.join("b.rs")).await.unwrap();
+        let second = graph.index().await.unwrap();
+        assert_eq!((second.added, second.updated, second.removed), (0, 1, 1));
+        assert!(graph.find_symbol("beta").await.unwrap().is_empty());
+        assert_eq!(graph.find_symbol("alpha").await.unwrap().len(), 1);
+
+        let conn = rusqlite::Connection::open(&forge.graph().store().db_path).unwrap();
+        let ghosts: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM graph_entities WHERE file_path LIKE '%b.rs'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(ghosts, 0);
+
+        // Nothing left to remove
+        let third = graph.index().await.unwrap();
+        assert_eq!(third.removed, 0);
+    }
+
+    /// A span's name and its parent's name
+    type SpanEdge = (String, Option<String>);
+
+    /// Records each span as it is created
+    struct SpanTree(Arc<std::sync::Mutex<Vec<SpanEdge>>>);
+
+    impl<S> tracing_subscriber::Layer<S> for SpanTree
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            _attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let span = ctx.span(id).expect("new span is registered");
+            let parent = span.parent().map(|p| p.name().to_string());
+            self.0
+                .lock()
+                .unwrap()
+                .push((span.name().to_string(), parent));
+        }
+    }
+
+    /// Collects the values recorded for the `symbols` span field.
+    struct RecordedSymbols(Arc<std::sync::Mutex<Vec<u64>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for RecordedSymbols {
+        fn on_record(
+            &self,
+            _id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            struct Visitor<'a>(&'a std::sync::Mutex<Vec<u64>>);
+            impl tracing::field::Visit for Visitor<'_> {
+                fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+                    if field.name() == "symbols" {
+                        self.0.lock().unwrap().push(value);
+                    }
+                }
+                fn record_debug(&mut self, _: &tracing::field::Field, _: &dyn std::fmt::Debug) {}
+            }
+            values.record(&mut Visitor(&self.0));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_span_records_symbol_count() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("a.rs"), "pub fn alpha() { beta(); }\n")
+            .await
+            .unwrap();
+        tokio::fs::write(src_dir.join("b.rs"), "pub fn beta() {}\n")
+            .await
+            .unwrap();
+        let forge = test_forge(temp_dir.path()).await;
+
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(RecordedSymbols(recorded.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        forge.graph().index().await.unwrap();
+
+        let symbols = forge.graph().store().symbol_count().await.unwrap() as u64;
+        assert_eq!(symbols, 2);
+        assert_eq!(
+            *recorded.lock().unwrap(),
+            vec![symbols],
+            "files, references and calls are not counted"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_spans_nest() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("a.rs"), "pub fn alpha() { beta(); }\n")
+            .await
+            .unwrap();
+        tokio::fs::write(src_dir.join("b.rs"), "pub fn beta() {}\n")
+            .await
+            .unwrap();
+        let forge = test_forge(temp_dir.path()).await;
+
+        let spans = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(SpanTree(spans.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        forge.graph().index().await.unwrap();
+
+        let spans = spans.lock().unwrap();
+        let parent_of = |name: &str| {
+            spans
+                .iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, parent)| parent.as_deref())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parent_of("index"), vec![None]);
+        assert_eq!(parent_of("index_references"), vec![Some("index")]);
+        assert_eq!(
+            parent_of("index_file"),
+            vec![Some("index_references"), Some("index_references")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_callers_of_after_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn helper() -> i32 { 1 }\nfn caller() -> i32 { helper() }\n",
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let callers = forge.graph().callers_of("helper").await.unwrap();
+        assert!(!callers.is_empty(), "should find caller calling helper");
+    }
+
+    #[tokio::test]
+    async fn test_reachable_from_follows_call_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn c() -> i32 { 1 }\nfn b() -> i32 { c() }\nfn a() -> i32 { b() }\n",
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+        let id_of = |symbols: Vec<Symbol>| symbols.first().expect("indexed").id;
+        let a = id_of(forge.graph().find_symbol("a").await.unwrap());
+        let b = id_of(forge.graph().find_symbol("b").await.unwrap());
+        let c = id_of(forge.graph().find_symbol("c").await.unwrap());
+
+        let reachable = forge.graph().reachable_from(a, None).await.unwrap();
+        assert_eq!(reachable, vec![b, c]);
+
+        let one_hop = forge.graph().reachable_from(a, Some(1)).await.unwrap();
+        assert_eq!(one_hop, vec![b]);
+        assert!(forge
+            .graph()
+            .reachable_from(c, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_last_modified_uses_file_mtime() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        let lib = src_dir.join("lib.rs");
+        tokio::fs::write(&lib, "pub fn alpha() {}\npub fn beta() {}\n")
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let mtime = std::fs::metadata(&lib).unwrap().modified().unwrap();
+        let alpha = forge.graph().last_modified("alpha").await.unwrap();
+        let beta = forge.graph().last_modified("beta").await.unwrap();
+        assert_eq!(alpha, Some(mtime));
+        assert_eq!(beta, Some(mtime));
+        assert_eq!(forge.graph().last_modified("missing").await.unwrap(), None);
+
+        // A file deleted since indexing has no mtime
+        tokio::fs::remove_file(&lib).await.unwrap();
+        assert_eq!(forge.graph().last_modified("alpha").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_implementors_of_trait() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "pub trait Shape { fn area(&self) -> f64; }\n\
+             pub struct Circle { r: f64 }\n\
+             pub struct Square { side: f64 }\n\
+             impl Shape for Circle { fn area(&self) -> f64 { self.r * self.r } }\n\
+             impl Shape for Square { fn area(&self) -> f64 { self.side * self.side } }\n\
+             impl<T> Shape for Vec<T> { fn area(&self) -> f64 { 0.0 } }\n\
+             impl std::fmt::Display for Circle {\n    \
+                 fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n\
+             }\n",
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let names = |symbols: Vec<Symbol>| {
+            symbols
+                .iter()
+                .map(|s| s.name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Before indexing, impl headers are scanned from the sources
+        let scanned = forge.graph().implementors_of("Shape").await.unwrap();
+        assert_eq!(names(scanned), vec!["Circle", "Square", "Vec"]);
+
+        forge.graph().index().await.unwrap();
+        let shapes = forge.graph().implementors_of("Shape").await.unwrap();
+        assert_eq!(names(shapes.clone()), vec!["Circle", "Square", "Vec"]);
+        assert_eq!(shapes[0].kind, crate::types::SymbolKind::Struct);
+        assert_eq!(shapes[2].kind, crate::types::SymbolKind::Impl);
+
+        let display = forge
+            .graph()
+            .implementors_of("std::fmt::Display")
+            .await
+            .unwrap();
+        assert_eq!(names(display), vec!["Circle"]);
+
+        let mut ids = Vec::new();
+        for name in ["Shape", "Circle", "Square"] {
+            ids.push(forge.graph().find_symbol(name).await.unwrap()[0].id);
+        }
+        let (shape, circle, square) = (ids[0], ids[1], ids[2]);
+        let edges: Vec<_> = forge
+            .graph()
+            .references("Shape")
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.kind == ReferenceKind::Implements)
+            .map(|r| (r.from_name.unwrap(), r.to_name.unwrap(), r.from, r.to))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("Circle".to_string(), "Shape".to_string(), circle, shape),
+                ("Square".to_string(), "Shape".to_string(), square, shape),
+                // Vec is not declared in the graph
+                (
+                    "Vec".to_string(),
+                    "Shape".to_string(),
+                    SymbolId(0),
+                    SymbolId(0)
+                ),
+            ]
+        );
+
+        // The resolved impls are stored as graph edges
+        let conn = rusqlite::Connection::open(forge.graph().store.db_path()).unwrap();
+        let stored: Vec<(i64, i64)> = conn
+            .prepare("SELECT from_id, to_id FROM graph_edges WHERE edge_type = 'IMPLEMENTS'")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(stored, vec![(circle.0, shape.0), (square.0, shape.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_references_by_kind_after_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("config.rs"),
+            "pub struct Config;\npub fn load() -> Config { Config }\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "mod config;\nuse config::{load, Config};\n\nfn start() -> Config {\n    load()\n}\n",
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let graph = forge.graph();
+        let lines = |found: Vec<Reference>| -> Vec<(String, usize)> {
+            found
+                .iter()
+                .map(|r| {
+                    let file = r.location.file_path.file_name().unwrap();
+                    (file.to_string_lossy().into_owned(), r.location.line_number)
+                })
+                .collect()
+        };
+        let imports = graph.references_by_kind("Config", ReferenceKind::Import);
+        assert_eq!(lines(imports.await.unwrap()), vec![("lib.rs".into(), 2)]);
+        let types = graph.references_by_kind("Config", ReferenceKind::TypeReference);
+        assert_eq!(
+            lines(types.await.unwrap()),
+            vec![("config.rs".into(), 2), ("lib.rs".into(), 4)]
+        );
+        let uses = graph.references_by_kind("Config", ReferenceKind::Use);
+        assert_eq!(lines(uses.await.unwrap()), vec![("config.rs".into(), 2)]);
+
+        let calls = graph
+            .references_by_kind("load", ReferenceKind::Call)
+            .await
+            .unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].from_name.as_deref(), Some("start"));
+        assert_eq!(calls[0].location.line_number, 5);
+    }
+
+    #[tokio::test]
+    async fn test_find_symbol_glob_and_fuzzy() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn parse_config() {}\nfn parse_args() {}\nfn Parse_env() {}\nfn render() {}\n",
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+        graph.index().await.unwrap();
+        let names = |symbols: Vec<Symbol>| -> Vec<String> {
+            symbols.iter().map(|s| s.name.to_string()).collect()
+        };
+
+        let hits = graph.find_symbol_glob("parse_*", false).await.unwrap();
+        assert_eq!(names(hits), vec!["parse_args", "parse_config"]);
+        let hits = graph.find_symbol_glob("parse_*", true).await.unwrap();
+        assert_eq!(names(hits), vec!["Parse_env", "parse_args", "parse_config"]);
+        let hits = graph.find_symbol_glob("rend?r", false).await.unwrap();
+        assert_eq!(names(hits), vec!["render"]);
+        assert!(graph.find_symbol_glob("", false).await.unwrap().is_empty());
+
+        let hits = graph
+            .find_symbol_fuzzy("parse_arg", 3, false)
+            .await
+            .unwrap();
+        assert_eq!(names(hits), vec!["parse_args"]);
+        let hits = graph.find_symbol_fuzzy("parse_arg", 3, true).await.unwrap();
+        assert_eq!(names(hits), vec!["parse_args", "Parse_env"]);
+        let hits = graph.find_symbol_fuzzy("rendr", 1, false).await.unwrap();
+        assert_eq!(names(hits), vec!["render"]);
+        assert!(graph
+            .find_symbol_fuzzy("", 5, false)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_symbols_batch_opens_graph_once() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        let source: String = (0..50).map(|i| format!("fn f{}() {{}}\n", i)).collect();
+        tokio::fs::write(src_dir.join("lib.rs"), source)
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+        graph.index().await.unwrap();
+
+        let wanted: Vec<String> = (0..50).map(|i| format!("f{}", i)).collect();
+        let mut names: Vec<&str> = wanted.iter().map(String::as_str).collect();
+        names.push("missing");
+
+        matching::OPENS.with(|opens| opens.set(0));
+        let found = graph.find_symbols_batch(&names).await.unwrap();
+        assert_eq!(matching::OPENS.with(|opens| opens.get()), 1);
+
+        assert_eq!(found.len(), names.len());
+        assert!(found["missing"].is_empty());
+        for (i, name) in wanted.iter().enumerate() {
+            let matches = &found[name];
+            assert_eq!(matches.len(), 1, "{}", name);
+            assert_eq!(&*matches[0].name, name);
+            assert_eq!(matches[0].location.line_number, i + 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changed_since_reports_edited_bodies() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn stable() -> i32 { 1 }\nfn edited() -> i32 { 2 }\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(src_dir.join("other.rs"), "fn other() {}\n")
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+        graph.index().await.unwrap();
+        let before = graph.body_hashes().await.unwrap();
+        assert!(graph.changed_since(&before).await.unwrap().is_empty());
+
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn stable() -> i32 { 1 }\nfn edited() -> i32 { 3 }\n",
+        )
+        .await
+        .unwrap();
+        graph.index().await.unwrap();
+
+        let changed = graph.changed_since(&before).await.unwrap();
+        let names: Vec<&str> = changed.iter().map(|s| &*s.name).collect();
+        assert_eq!(names, vec!["edited"]);
+    }
+
+    #[tokio::test]
+    async fn test_body_hashes_come_from_the_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        let source = "fn first() -> i32 { 1 }\nfn second() -> i32 { 2 }\n";
+        tokio::fs::write(src_dir.join("lib.rs"), source)
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+        graph.index().await.unwrap();
+        let indexed = graph.body_hashes().await.unwrap();
+        assert_eq!(indexed.len(), 2);
+        assert!(indexed
+            .values()
+            .any(|h| *h == blake3::hash(b"fn second() -> i32 { 2 }")));
+
+        // Edits after indexing move the stored offsets, but not the hashes
+        tokio::fs::write(src_dir.join("lib.rs"), format!("// header\n{}", source))
+            .await
+            .unwrap();
+        assert_eq!(graph.body_hashes().await.unwrap(), indexed);
+        assert!(graph.changed_since(&indexed).await.unwrap().is_empty());
+
+        let exported = export::read_graph(&graph.store.db_path).unwrap();
+        for symbol in &exported.symbols {
+            assert_eq!(symbol.body_hash, indexed.get(&symbol.id).copied());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_import_json_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "struct Config;\nfn helper(c: Config) {}\nfn main() { helper(Config); }\n",
+        )
+        .await
+        .unwrap();
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let mut exported = Vec::new();
+        forge.graph().export_json(&mut exported).unwrap();
+        let document: GraphExport = serde_json::from_slice(&exported).unwrap();
+        assert!(document.symbols.iter().any(|s| &*s.name == "helper"));
+        assert!(document
+            .references
+            .iter()
+            .any(|r| r.kind == ReferenceKind::Call && r.to_name.as_deref() == Some("helper")));
+
+        let other_dir = tempfile::tempdir().unwrap();
+        let copy = test_forge(other_dir.path()).await;
+        copy.graph().import_json(exported.as_slice()).unwrap();
+        let mut reexported = Vec::new();
+        copy.graph().export_json(&mut reexported).unwrap();
+        let round_tripped: GraphExport = serde_json::from_slice(&reexported).unwrap();
+        assert_eq!(round_tripped, document);
+        assert_eq!(reexported, exported);
+
+        let found = copy.graph().find_symbol("helper").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(copy.graph().import_json(exported.as_slice()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_call_graph_dot_respects_hops() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn leaf() {}\nfn middle() { leaf(); }\nfn top() { middle(); }\n",
+        )
+        .await
+        .unwrap();
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let near = forge.graph().call_graph_dot("top", 1).unwrap();
+        assert!(near.starts_with("digraph call_graph {"));
+        assert!(near.contains("label=\"top\", style=filled"));
+        assert!(near.contains("label=\"middle\""));
+        assert!(!near.contains("label=\"leaf\""));
+        assert_eq!(near.matches(" -> ").count(), 1);
+
+        let far = forge.graph().call_graph_dot("top", 2).unwrap();
+        assert!(far.contains("label=\"leaf\""));
+        assert_eq!(far.matches(" -> ").count(), 2);
+        assert_eq!(far, forge.graph().call_graph_dot("top", 2).unwrap());
+
+        let from_middle = forge.graph().call_graph_dot("middle", 1).unwrap();
+        assert!(from_middle.contains("label=\"top\""));
+        assert!(from_middle.contains("label=\"leaf\""));
+
+        assert!(forge.graph().call_graph_dot("missing", 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_index_matches_sequential() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        for i in 0..40 {
+            let source = format!(
+                "use std::collections::HashMap as Map{i};\n\
+                 fn leaf_{i}() -> u32 {{ {i} }}\n\
+                 fn caller_{i}() -> u32 {{ leaf_{i}() + leaf_{i}() }}\n"
+            );
+            tokio::fs::write(src_dir.join(format!("m{:02}.rs", i)), source)
+                .await
+                .unwrap();
+        }
+        tokio::fs::write(src_dir.join("broken.rs"), "fn broken( {\n")
+            .await
+            .unwrap();
+
+        let mut runs = Vec::new();
+        for (threads, db) in [(1, "sequential.db"), (4, "parallel.db")] {
+            let forge = crate::ForgeBuilder::new()
+                .path(temp_dir.path())
+                .db_path(temp_dir.path().join(db))
+                .index_parallelism(threads)
+                .build()
+                .await
+                .unwrap();
+            let report = forge.graph().index().await.unwrap();
+
+            let mut exported = Vec::new();
+            forge.graph().export_json(&mut exported).unwrap();
+            let aliases = alias::load_aliases(&temp_dir.path().join(db)).unwrap();
+            runs.push((report, exported, aliases.len()));
+        }
+
+        let (sequential, parallel) = (&runs[0], &runs[1]);
+        assert_eq!(sequential.0.indexed.len(), 41);
+        assert_eq!(sequential.0.syntax_errors.len(), 1);
+        assert_eq!(sequential.0, parallel.0);
+        assert_eq!(sequential.1, parallel.1);
+        assert_eq!(sequential.2, 40);
+        assert_eq!(sequential.2, parallel.2);
+    }
+
+    #[tokio::test]
+    async fn test_index_mixed_language_tree() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            "fn rust_entry() {}
+",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(
+            src_dir.join("tools.py"),
+            "def py_helper():\n    return 1\n\ndef py_main():\n    py_helper()\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(src_dir.join("notes.txt"), "fn not_code() {}\n")
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+        let report = graph.index().await.unwrap();
+        assert!(report.is_clean(), "{:?}", report.failed);
+        let mut indexed = report.indexed.clone();
+        indexed.sort();
+        assert_eq!(
+            indexed,
+            vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/tools.py")]
+        );
+
+        let rust = graph.find_symbol("rust_entry").await.unwrap();
+        assert_eq!(rust[0].language, Language::Rust);
+        let python = graph.find_symbol("py_helper").await.unwrap();
+        assert_eq!(python[0].language, Language::Python);
+        assert!(graph.find_symbol("not_code").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_callers_of_stream_yields_incrementally() {
+        use futures::StreamExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        let callers: String = (0..200)
+            .map(|i| format!("fn caller_{i}() -> i32 {{ helper() }}\n"))
+            .collect();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            format!("fn helper() -> i32 {{ 1 }}\n{callers}"),
+        )
+        .await
+        .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let first: Vec<_> = forge
+            .graph()
+            .callers_of_stream("helper")
+            .take(3)
+            .collect()
+            .await;
+        assert_eq!(first.len(), 3);
+        assert!(first.iter().all(|r| r.is_ok()));
+
+        // The dropped stream must not hold up later queries.
+        let all = forge.graph().callers_of("helper").await.unwrap();
+        assert!(all.len() >= 200);
+    }
+
+    #[tokio::test]
+    async fn test_open_stream_does_not_block_single_reader() {
+        use futures::StreamExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        let callers: String = (0..200)
+            .map(|i| format!("fn caller_{i}() -> i32 {{ helper() }}\n"))
+            .collect();
+        tokio::fs::write(
+            src_dir.join("lib.rs"),
+            format!("fn helper() -> i32 {{ 1 }}\n{callers}"),
+        )
+        .await
+        .unwrap();
+
+        let forge = crate::ForgeBuilder::new()
+            .path(temp_dir.path())
+            .db_path(temp_dir.path().join("test.db"))
+            .pool_size(1)
+            .build()
+            .await
+            .unwrap();
+        forge.graph().index().await.unwrap();
+
+        // Held open with its buffer full, the stream's producer is blocked
+        let mut stream = Box::pin(forge.graph().callers_of_stream("helper"));
+        assert!(stream.next().await.unwrap().is_ok());
+
+        let symbols = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            forge.graph().find_symbol("helper"),
+        )
+        .await
+        .expect("query blocked behind an open stream")
+        .unwrap();
+        assert!(!symbols.is_empty());
+        assert!(stream.next().await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cycles_detect_mutual_recursion() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("lib.rs"), "fn a() { b() }\nfn b() { a() }\n")
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        forge.graph().index().await.unwrap();
+
+        let cycles = forge.graph().cycles().await.unwrap();
+        assert!(
+            !cycles.is_empty(),
+            "should detect mutual recursion between a and b"
+        );
+        let cycle = &cycles[0];
+        assert!(cycle.members.len() >= 2);
+        assert!(cycle.members.iter().any(|m| m.fqn.as_deref() == Some("a")));
+        assert!(cycle.members.iter().any(|m| m.fqn.as_deref() == Some("b")));
+    }
+
+    #[tokio::test]
+    async fn test_cycles_exclude_type_reference_cycles_by_default() {
+        use crate::storage::{open_graph, EdgeSpec, GraphConfig, NodeSpec};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("test-graph.db");
+        let backend = open_graph(&db_path, &GraphConfig::sqlite()).unwrap();
+        let node = |name: &str| NodeSpec {
+            kind: "fn".to_string(),
+            name: name.to_string(),
+            file_path: Some("src/lib.rs".to_string()),
+            data: serde_json::Value::Null,
+        };
+        let edge = |from: i64, to: i64, edge_type: &str| EdgeSpec {
+            from,
+            to,
+            edge_type: edge_type.to_string(),
+            data: serde_json::Value::Null,
+        };
+        let config = backend.insert_node(node("Config")).unwrap();
+        let builder = backend.insert_node(node("Builder")).unwrap();
+        backend
+            .insert_edge(edge(config, builder, "REFERENCES"))
+            .unwrap();
+        backend
+            .insert_edge(edge(builder, config, "REFERENCES"))
+            .unwrap();
+        drop(backend);
+
+        let forge = test_forge(temp_dir.path()).await;
+
+        assert!(forge.graph().cycles().await.unwrap().is_empty());
+
+        let cycles = forge
+            .graph()
+            .cycles_with_edges(&[ReferenceKind::Call, ReferenceKind::TypeReference])
+            .await
+            .unwrap();
+        assert_eq!(cycles.len(), 1);
+        let names: Vec<_> = cycles[0]
+            .members
+            .iter()
+            .map(|m| m.fqn.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, vec!["Builder", "Config"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_reports_syntax_error_and_indexes_what_parsed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("a.rs"), "pub fn good_a() {}\n")
+            .await
+            .unwrap();
+        tokio::fs::write(
+            src_dir.join("broken.rs"),
+            "use std::fmt::Display as Show;\npub fn broken( {\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(src_dir.join("c.rs"), "pub fn good_c() {}\n")
+            .await
+            .unwrap();
+
+        let forge = test_forge(temp_dir.path()).await;
+        let report = forge.graph().index().await.unwrap();
+
+        assert_eq!(
+            report.indexed,
+            vec![
+                PathBuf::from("src/a.rs"),
+                PathBuf::from("src/broken.rs"),
+                PathBuf::from("src/c.rs")
+            ]
+        );
+        assert!(report.is_clean(), "{:?}", report.failed);
+        assert_eq!(report.syntax_errors.len(), 1);
+        assert_eq!(report.syntax_errors[0].0, PathBuf::from("src/broken.rs"));
+        assert!(
+            report.syntax_errors[0].1.contains("line 2"),
+            "{:?}",
+            report.syntax_errors
+        );
+        let aliases = alias::load_aliases(&temp_dir.path().join("test-graph.db")).unwrap();
+        assert!(
+            aliases.iter().any(|a| a.alias == "Show"),
+            "the alias before the error is still extracted"
+        );
+        assert_eq!(forge.graph().find_symbol("good_a").await.unwrap().len(), 1);
+        assert_eq!(forge.graph().find_symbol("good_c").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_index_skips_oversized_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::write(src_dir.join("small.rs"), "pub fn small_fn() {}\n")
+            .await
+            .unwrap();
+        let mut big = String::from("pub fn big_fn() {}\n");
+        big.push_str(&"// padding\n".repeat(1000));
+        tokio::fs::write(src_dir.join("big.rs"), big).await.unwrap();
+
+        let forge = crate::ForgeBuilder::new()
+            .path(temp_dir.path())
+            .db_path(temp_dir.path().join("test-graph.db"))
+            .max_file_bytes(4096)
+            .build()
+            .await
+            .unwrap();
+        let report = forge.graph().index().await.unwrap();
+
+        assert_eq!(report.indexed, vec![PathBuf::from("src/small.rs")]);
+        assert_eq!(report.skipped, vec![PathBuf::from("src/big.rs")]);
+        assert!(report.is_clean());
+        assert_eq!(
+            forge.graph().find_symbol("small_fn").await.unwrap().len(),
+            1
+        );
+        assert!(forge
+            .graph()
+            .find_symbol("big_fn")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_index_short_circuits_when_breaker_open() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let forge = test_forge(temp_dir.path()).await;
+        let graph = forge.graph();
+
+        for _ in 0..crate::circuit::DEFAULT_FAILURE_THRESHOLD {
+            graph.store().index_breaker.record_failure();
+        }
+
+        let err = graph.index().await.unwrap_err();
+        assert!(
+            matches!(err, crate::error::ForgeError::BackendNotAvailable(_)),
+            "expected BackendNotAvailable, got {err:?}"
         );
-        let cycle = &cycles[0];
-        assert!(cycle.members.len() >= 2);
-        assert!(cycle.members.iter().any(|m| m.fqn.as_deref() == Some("a")));
-        assert!(cycle.members.iter().any(|m| m.fqn.as_deref() == Some("b")));
     }
 
     #[tokio::test]
@@ -0,0 +1,152 @@
+//! Symbol queries and their execution plans.
+//!
+//! [`SymbolQuery`] describes a lookup; [`QueryPlan`] reports how
+//! [`GraphModule::query`](super::GraphModule::query) will execute it.
+
+use crate::storage::BackendKind;
+use crate::types::SymbolKind;
+use std::fmt;
+use std::path::PathBuf;
+
+/// A symbol lookup by name with optional filters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolQuery {
+    /// Exact symbol name
+    pub name: String,
+    /// Only symbols of this kind
+    pub kind: Option<SymbolKind>,
+    /// Only symbols whose file path starts with this prefix
+    pub file: Option<PathBuf>,
+    /// Maximum number of results
+    pub limit: Option<usize>,
+}
+
+impl SymbolQuery {
+    /// Creates a query for symbols named `name`.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: None,
+            file: None,
+            limit: None,
+        }
+    }
+
+    /// Restricts results to one symbol kind.
+    pub fn kind(mut self, kind: SymbolKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Restricts results to files under `prefix`.
+    pub fn in_file(mut self, prefix: impl Into<PathBuf>) -> Self {
+        self.file = Some(prefix.into());
+        self
+    }
+
+    /// Limits the number of results.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = Some(n);
+        self
+    }
+}
+
+/// How a query is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryStrategy {
+    /// Name lookup against the magellan graph database
+    Indexed,
+    /// Regex scan over source files; used when the graph database is
+    /// missing or has not been indexed yet
+    FileScan,
+}
+
+/// Execution plan for a [`SymbolQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPlan {
+    /// Chosen execution strategy
+    pub strategy: QueryStrategy,
+    /// Storage backend configured for the store
+    pub backend: BackendKind,
+    /// Graph database path
+    pub db_path: PathBuf,
+    /// Whether the graph database exists
+    pub db_exists: bool,
+    /// Entities in the graph database; 0 when it is missing or unindexed
+    pub entity_count: usize,
+    /// Filters evaluated by the backend while fetching candidates
+    pub pushed_down: Vec<String>,
+    /// Filters applied to candidates after they are fetched
+    pub residual: Vec<String>,
+}
+
+impl QueryPlan {
+    pub(crate) fn build(
+        query: &SymbolQuery,
+        backend: BackendKind,
+        db_path: PathBuf,
+        db_exists: bool,
+        entity_count: usize,
+    ) -> Self {
+        let strategy = if db_exists && entity_count > 0 {
+            QueryStrategy::Indexed
+        } else {
+            QueryStrategy::FileScan
+        };
+
+        let mut residual = Vec::new();
+        let pushed_down = match strategy {
+            QueryStrategy::Indexed => vec![format!("name = {:?}", query.name)],
+            QueryStrategy::FileScan => {
+                // The line regex over-matches, so the exact name is re-checked.
+                residual.push(format!("name = {:?}", query.name));
+                vec![format!("name ~ {:?}", query.name)]
+            }
+        };
+        if let Some(kind) = &query.kind {
+            residual.push(format!("kind = {:?}", kind));
+        }
+        if let Some(file) = &query.file {
+            residual.push(format!("file starts with {}", file.display()));
+        }
+        if let Some(limit) = query.limit {
+            residual.push(format!("limit {}", limit));
+        }
+
+        Self {
+            strategy,
+            backend,
+            db_path,
+            db_exists,
+            entity_count,
+            pushed_down,
+            residual,
+        }
+    }
+}
+
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let strategy = match self.strategy {
+            QueryStrategy::Indexed => "indexed lookup",
+            QueryStrategy::FileScan => "file-scan fallback",
+        };
+        writeln!(f, "strategy: {} ({:?} backend)", strategy, self.backend)?;
+        if self.db_exists {
+            writeln!(
+                f,
+                "database: {} ({} entities)",
+                self.db_path.display(),
+                self.entity_count
+            )?;
+        } else {
+            writeln!(f, "database: {} (missing)", self.db_path.display())?;
+        }
+        writeln!(f, "pushed down: {}", self.pushed_down.join(", "))?;
+        if self.residual.is_empty() {
+            write!(f, "residual: none")
+        } else {
+            write!(f, "residual: {}", self.residual.join(", "))
+        }
+    }
+}
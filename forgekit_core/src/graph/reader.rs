@@ -0,0 +1,125 @@
+//! Queries run on the store's graph reader connections
+//!
+//! These read magellan's tables directly over the read-only connections
+//! of [`GraphReaderPool`](crate::pool::GraphReaderPool), so looking up
+//! symbols, callers and references never writes to the database.
+
+use crate::error::Result;
+use magellan::graph::schema::CallNode;
+use magellan::{CallFact, CrossFileRef};
+use rusqlite::{params, Connection};
+use std::path::PathBuf;
+
+/// A symbol entity as indexing stored it.
+#[derive(Debug, Clone)]
+pub(crate) struct SymbolRow {
+    pub(crate) entity_id: i64,
+    pub(crate) name: String,
+    pub(crate) file_path: String,
+    /// Magellan's kind name, such as `fn` or `struct`
+    pub(crate) kind: String,
+    pub(crate) byte_start: usize,
+    pub(crate) byte_end: usize,
+    /// Hash of the body taken at index time
+    pub(crate) body_hash: Option<blake3::Hash>,
+}
+
+/// Symbols whose stored name is exactly `name`.
+pub(crate) fn symbols_named(conn: &Connection, name: &str) -> Result<Vec<SymbolRow>> {
+    symbols_where(conn, "name = ?1", params![name])
+}
+
+/// Reads the symbol entities matching `filter`, a condition on
+/// `graph_entities`.
+pub(super) fn symbols_where(
+    conn: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<SymbolRow>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, name, file_path, data FROM graph_entities
+         WHERE kind = 'Symbol' AND {filter}"
+    ))?;
+    let rows = stmt.query_map(params, |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    Ok(rows
+        .flatten()
+        .filter_map(|(entity_id, name, file_path, data)| {
+            let data: serde_json::Value = serde_json::from_str(data.as_deref()?).ok()?;
+            let offset = |key: &str| data.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            Some(SymbolRow {
+                entity_id,
+                name: name?,
+                file_path: file_path.unwrap_or_else(|| "?".to_string()),
+                kind: data
+                    .get("kind_normalized")
+                    .or_else(|| data.get("kind"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("Unknown")
+                    .to_string(),
+                byte_start: offset("byte_start"),
+                byte_end: offset("byte_end"),
+                body_hash: super::body_hash::from_data(&data),
+            })
+        })
+        .collect())
+}
+
+/// Calls into the symbol stored as entity `symbol`, in the order they
+/// were indexed.
+pub(crate) fn callers_of(conn: &Connection, symbol: i64) -> Result<Vec<CallFact>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.data FROM graph_edges e
+         JOIN graph_entities c ON c.id = e.from_id
+         WHERE e.edge_type = 'CALLS' AND e.to_id = ?1 AND c.kind = 'Call'
+         ORDER BY e.id",
+    )?;
+    let rows = stmt.query_map(params![symbol], |row| row.get::<_, Option<String>>(0))?;
+
+    Ok(rows
+        .flatten()
+        .filter_map(|data| serde_json::from_str::<CallNode>(data.as_deref()?).ok())
+        .map(|call| CallFact {
+            file_path: PathBuf::from(call.file),
+            caller: call.caller,
+            callee: call.callee,
+            caller_symbol_id: call.caller_symbol_id,
+            callee_symbol_id: call.callee_symbol_id,
+            byte_start: call.byte_start as usize,
+            byte_end: call.byte_end as usize,
+            start_line: call.start_line as usize,
+            start_col: call.start_col as usize,
+            end_line: call.end_line as usize,
+            end_col: call.end_col as usize,
+        })
+        .collect())
+}
+
+/// Cross-file references to `fqn`; empty if the database has never stored
+/// any.
+pub(crate) fn references_to(conn: &Connection, fqn: &str) -> Result<Vec<CrossFileRef>> {
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT from_symbol_id, to_symbol_id, file_path, line_number, byte_start, byte_end
+         FROM cross_file_refs WHERE to_symbol_id = ?1",
+    ) else {
+        return Ok(Vec::new());
+    };
+    let rows = stmt.query_map(params![fqn], |row| {
+        Ok(CrossFileRef {
+            from_symbol_id: row.get(0)?,
+            to_symbol_id: row.get(1)?,
+            file_path: row.get(2)?,
+            line_number: row.get::<_, i64>(3)? as usize,
+            byte_start: row.get::<_, i64>(4)? as usize,
+            byte_end: row.get::<_, i64>(5)? as usize,
+        })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
@@ -0,0 +1,448 @@
+//! Fluent queries over the code graph.
+//!
+//! A [`GraphQuery`] is a pipeline of steps built with
+//! [`GraphModule::select`]: a starting set of symbols, then hops such as
+//! [`callers`](GraphQuery::callers) and filters such as
+//! [`filter_kind`](GraphQuery::filter_kind). Nothing runs until a terminal
+//! is awaited. Evaluation is depth-first, one symbol at a time, so a
+//! [`limit`](GraphQuery::limit) stops the walk as soon as enough results
+//! are found instead of expanding every intermediate step in full.
+
+use super::{matching, GraphModule};
+use crate::error::Result;
+use crate::types::{Reference, ReferenceKind, Symbol, SymbolId, SymbolKind};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Where a query's symbols come from.
+#[derive(Clone, Debug)]
+enum Source {
+    Named(String),
+    Matching(String),
+}
+
+/// A step applied to each symbol in turn.
+#[derive(Clone, Debug)]
+enum Step {
+    Callers,
+    Kind(SymbolKind),
+    File(String),
+}
+
+/// A lazily evaluated symbol query.
+///
+/// # Examples
+///
+/// Callers of callers of `parse`, restricted to functions:
+///
+/// ```no_run
+/// use forgekit_core::types::SymbolKind;
+/// use forgekit_core::Forge;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let forge = Forge::open("./my-project").await?;
+/// let symbols = forge
+///     .graph()
+///     .select()
+///     .symbols_named("parse")
+///     .callers()
+///     .callers()
+///     .filter_kind(SymbolKind::Function)
+///     .limit(10)
+///     .collect()
+///     .await?;
+/// for symbol in symbols {
+///     println!("{} in {}", symbol.name, symbol.location.file_path.display());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct GraphQuery {
+    graph: GraphModule,
+    source: Option<Source>,
+    steps: Vec<Step>,
+    limit: Option<usize>,
+}
+
+impl GraphQuery {
+    pub(crate) fn new(graph: GraphModule) -> Self {
+        Self {
+            graph,
+            source: None,
+            steps: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Starts from the symbols named exactly `name`.
+    pub fn symbols_named(mut self, name: impl Into<String>) -> Self {
+        self.source = Some(Source::Named(name.into()));
+        self
+    }
+
+    /// Starts from the symbols whose name matches the glob `pattern`.
+    pub fn symbols_matching(mut self, pattern: impl Into<String>) -> Self {
+        self.source = Some(Source::Matching(pattern.into()));
+        self
+    }
+
+    /// Replaces each symbol with the symbols that call it.
+    pub fn callers(mut self) -> Self {
+        self.steps.push(Step::Callers);
+        self
+    }
+
+    /// Keeps only symbols of `kind`.
+    pub fn filter_kind(mut self, kind: SymbolKind) -> Self {
+        self.steps.push(Step::Kind(kind));
+        self
+    }
+
+    /// Keeps only symbols whose file, relative to the codebase root,
+    /// matches the glob `pattern`.
+    pub fn in_file(mut self, pattern: impl Into<String>) -> Self {
+        self.steps.push(Step::File(pattern.into()));
+        self
+    }
+
+    /// Stops after `n` results.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    /// Switches to the references to the symbols found so far.
+    pub fn references(self) -> ReferenceQuery {
+        ReferenceQuery {
+            symbols: self,
+            kind: None,
+            file: None,
+            limit: None,
+        }
+    }
+
+    /// Runs the query, returning each symbol once in the order found.
+    pub async fn collect(&self) -> Result<Vec<Symbol>> {
+        let mut out = Vec::new();
+        self.walk(|symbol| {
+            out.push(symbol);
+            self.limit.is_none_or(|n| out.len() < n)
+        })
+        .await?;
+        Ok(out)
+    }
+
+    /// Feeds the query's symbols to `emit` until it returns false.
+    async fn walk(&self, mut emit: impl FnMut(Symbol) -> bool) -> Result<()> {
+        if self.limit == Some(0) {
+            return Ok(());
+        }
+        let roots = match &self.source {
+            Some(Source::Named(name)) => self.graph.find_symbol(name).await?,
+            Some(Source::Matching(pattern)) => self.graph.find_symbol_glob(pattern, false).await?,
+            None => Vec::new(),
+        };
+
+        let mut resolver = Resolver::default();
+        let mut seen: HashSet<(usize, SymbolId)> = HashSet::new();
+        // Depth-first, with each symbol paired with the next step to apply
+        let mut stack: Vec<(Symbol, usize)> = roots.into_iter().rev().map(|s| (s, 0)).collect();
+        while let Some((symbol, step)) = stack.pop() {
+            if !seen.insert((step, symbol.id)) {
+                continue;
+            }
+            let Some(current) = self.steps.get(step) else {
+                if !emit(symbol) {
+                    return Ok(());
+                }
+                continue;
+            };
+            match current {
+                Step::Callers => {
+                    let callers = resolver.callers(&self.graph, &symbol).await?;
+                    stack.extend(callers.into_iter().rev().map(|s| (s, step + 1)));
+                }
+                Step::Kind(kind) => {
+                    if symbol.kind == *kind {
+                        stack.push((symbol, step + 1));
+                    }
+                }
+                Step::File(pattern) => {
+                    if self.file_matches(pattern, &symbol.location.file_path) {
+                        stack.push((symbol, step + 1));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn file_matches(&self, pattern: &str, path: &Path) -> bool {
+        let codebase = &self.graph.store.codebase_path;
+        let relative = path.strip_prefix(codebase).unwrap_or(path);
+        matching::glob_match(pattern, &relative.to_string_lossy(), false)
+    }
+}
+
+/// References to the symbols of a [`GraphQuery`].
+///
+/// # Examples
+///
+/// Calls made from tests to the functions that call `run`:
+///
+/// ```no_run
+/// use forgekit_core::types::ReferenceKind;
+/// use forgekit_core::Forge;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let forge = Forge::open("./my-project").await?;
+/// let calls = forge
+///     .graph()
+///     .select()
+///     .symbols_named("run")
+///     .callers()
+///     .references()
+///     .kind(ReferenceKind::Call)
+///     .in_file("tests/*")
+///     .collect()
+///     .await?;
+/// println!("{} calls", calls.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ReferenceQuery {
+    symbols: GraphQuery,
+    kind: Option<ReferenceKind>,
+    file: Option<String>,
+    limit: Option<usize>,
+}
+
+impl ReferenceQuery {
+    /// Keeps only references of `kind`.
+    pub fn kind(mut self, kind: ReferenceKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Keeps only references made in a file, relative to the codebase
+    /// root, that matches the glob `pattern`.
+    pub fn in_file(mut self, pattern: impl Into<String>) -> Self {
+        self.file = Some(pattern.into());
+        self
+    }
+
+    /// Stops after `n` references.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    /// Runs the query: cross-file references and calls to each symbol,
+    /// in the order the symbols are found.
+    pub async fn collect(&self) -> Result<Vec<Reference>> {
+        if self.limit == Some(0) {
+            return Ok(Vec::new());
+        }
+        let graph = &self.symbols.graph;
+        let mut names = Vec::new();
+        let symbol_limit = self.symbols.limit;
+        self.symbols
+            .walk(|symbol| {
+                if !names.contains(&symbol.name) {
+                    names.push(symbol.name.clone());
+                }
+                symbol_limit.is_none_or(|n| names.len() < n)
+            })
+            .await?;
+
+        let mut out: Vec<Reference> = Vec::new();
+        for name in names {
+            let mut found = graph.references(&name).await?;
+            found.extend(graph.callers_of(&name).await?);
+            for reference in found {
+                let keep = self.kind.is_none_or(|k| reference.kind == k)
+                    && self.file.as_ref().is_none_or(|pattern| {
+                        self.symbols
+                            .file_matches(pattern, &reference.location.file_path)
+                    })
+                    && !out.contains(&reference);
+                if keep {
+                    out.push(reference);
+                    if self.limit.is_some_and(|n| out.len() >= n) {
+                        return Ok(out);
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Caches symbol lookups for the duration of one query.
+#[derive(Default)]
+struct Resolver {
+    by_name: HashMap<String, Vec<Symbol>>,
+}
+
+impl Resolver {
+    /// The symbols that call `symbol`, in call order, each once.
+    ///
+    /// A caller name defined in several places resolves to the definition
+    /// in the file the call is made from when there is one.
+    async fn callers(&mut self, graph: &GraphModule, symbol: &Symbol) -> Result<Vec<Symbol>> {
+        let mut callers: Vec<Symbol> = Vec::new();
+        for call in graph.callers_of(&symbol.name).await? {
+            let Some(name) = call.from_name else {
+                continue;
+            };
+            if !self.by_name.contains_key(&name) {
+                let found = graph.find_symbol(&name).await?;
+                self.by_name.insert(name.clone(), found);
+            }
+            let candidates = &self.by_name[&name];
+            let caller = candidates
+                .iter()
+                .find(|s| same_file(&s.location.file_path, &call.location.file_path))
+                .or_else(|| candidates.first());
+            if let Some(caller) = caller {
+                if !callers.iter().any(|c| c.id == caller.id) {
+                    callers.push(caller.clone());
+                }
+            }
+        }
+        Ok(callers)
+    }
+}
+
+/// Whether two paths name the same file when one may be relative.
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || a.ends_with(b) || b.ends_with(a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn indexed_forge(dir: &Path) -> crate::Forge {
+        let src = dir.join("src");
+        tokio::fs::create_dir_all(&src).await.unwrap();
+        tokio::fs::write(
+            src.join("lib.rs"),
+            "pub fn leaf() {}\n\
+             pub fn mid() { leaf(); }\n\
+             pub fn top() { mid(); }\n\
+             pub fn other_top() { mid(); }\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(src.join("app.rs"), "pub fn run() { crate::top(); }\n")
+            .await
+            .unwrap();
+        let forge = crate::ForgeBuilder::new()
+            .path(dir)
+            .db_path(dir.join("test-graph.db"))
+            .build()
+            .await
+            .unwrap();
+        forge.graph().index().await.unwrap();
+        forge
+    }
+
+    fn names(symbols: &[Symbol]) -> Vec<&str> {
+        symbols.iter().map(|s| s.name.as_ref()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_select_callers_of_callers() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph = indexed_forge(dir.path()).await.graph();
+
+        let one_hop = graph
+            .select()
+            .symbols_named("leaf")
+            .callers()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(names(&one_hop), vec!["mid"]);
+
+        let two_hops = graph
+            .select()
+            .symbols_named("leaf")
+            .callers()
+            .callers()
+            .filter_kind(SymbolKind::Function)
+            .collect()
+            .await
+            .unwrap();
+        let mut found = names(&two_hops);
+        found.sort();
+        assert_eq!(found, vec!["other_top", "top"]);
+
+        let limited = graph
+            .select()
+            .symbols_named("leaf")
+            .callers()
+            .callers()
+            .limit(1)
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+
+        let structs = graph
+            .select()
+            .symbols_named("leaf")
+            .callers()
+            .filter_kind(SymbolKind::Struct)
+            .collect()
+            .await
+            .unwrap();
+        assert!(structs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_select_filters_by_file_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph = indexed_forge(dir.path()).await.graph();
+
+        let in_lib = graph
+            .select()
+            .symbols_matching("*")
+            .in_file("src/lib.rs")
+            .collect()
+            .await
+            .unwrap();
+        let mut found = names(&in_lib);
+        found.sort();
+        assert_eq!(found, vec!["leaf", "mid", "other_top", "top"]);
+
+        let calls = graph
+            .select()
+            .symbols_named("top")
+            .references()
+            .kind(ReferenceKind::Call)
+            .in_file("src/app*")
+            .collect()
+            .await
+            .unwrap();
+        assert!(!calls.is_empty());
+        assert!(calls
+            .iter()
+            .all(|r| r.location.file_path.to_string_lossy().ends_with("app.rs")));
+
+        let none = graph
+            .select()
+            .symbols_named("top")
+            .references()
+            .in_file("tests/*")
+            .collect()
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+    }
+}
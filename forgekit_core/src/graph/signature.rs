@@ -0,0 +1,273 @@
+//! Function signature extraction and matching.
+//!
+//! Signatures are read from Rust source with tree-sitter, so they are
+//! available without an indexed graph database. Parameter and return
+//! types are kept as source text and matched with [`TypePattern`]s.
+
+use super::alias::{location, parse_rust, simple_name, text};
+use crate::types::{Language, Location, Symbol, SymbolId, SymbolKind, Visibility};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A pattern over a type as written in source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypePattern {
+    /// Matches any type
+    Any,
+    /// Matches types whose outermost name is this, ignoring path, generic
+    /// arguments and references: `Result` matches `io::Result<()>` and
+    /// `&Result<T, E>`
+    Named(String),
+    /// Matches the type text exactly, ignoring whitespace
+    Exact(String),
+}
+
+impl TypePattern {
+    /// Pattern matching any type.
+    pub fn any() -> Self {
+        TypePattern::Any
+    }
+
+    /// Pattern matching types named `name`, whatever their arguments.
+    pub fn named(name: impl Into<String>) -> Self {
+        TypePattern::Named(name.into())
+    }
+
+    /// Pattern matching exactly `ty`.
+    pub fn exact(ty: impl Into<String>) -> Self {
+        TypePattern::Exact(ty.into())
+    }
+
+    /// Returns true if the type text `ty` fits this pattern.
+    pub fn matches(&self, ty: &str) -> bool {
+        match self {
+            TypePattern::Any => true,
+            TypePattern::Named(name) => simple_name(strip_indirection(ty)) == name,
+            TypePattern::Exact(expected) => normalize(ty) == normalize(expected),
+        }
+    }
+}
+
+fn normalize(ty: &str) -> String {
+    ty.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Strips leading references, lifetimes, `mut`, `dyn` and `impl`.
+fn strip_indirection(ty: &str) -> &str {
+    let mut ty = ty.trim();
+    loop {
+        let before = ty;
+        ty = ty.trim_start_matches('&').trim_start();
+        if ty.starts_with('\'') {
+            ty = ty
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, rest)| rest.trim_start());
+        }
+        for keyword in ["mut ", "dyn ", "impl "] {
+            if let Some(rest) = ty.strip_prefix(keyword) {
+                ty = rest.trim_start();
+            }
+        }
+        if ty == before {
+            return ty;
+        }
+    }
+}
+
+/// A function's parameter and return types as written in source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSignature {
+    /// Function name
+    pub name: String,
+    /// `Method` when declared in an `impl` or `trait` block
+    pub kind: SymbolKind,
+    /// Parameter types in order, excluding any `self` receiver
+    pub params: Vec<String>,
+    /// Return type; `None` for functions returning `()` implicitly
+    pub returns: Option<String>,
+    /// Location of the function declaration
+    pub location: Location,
+    /// Visibility from the declaration's modifiers
+    pub visibility: Visibility,
+}
+
+impl FunctionSignature {
+    /// Returns true if every pattern in `params` matches a distinct
+    /// parameter and `returns` (if given) matches the return type.
+    ///
+    /// Parameter patterns are order-independent; an empty slice places no
+    /// constraint on parameters.
+    pub fn matches(&self, params: &[TypePattern], returns: Option<&TypePattern>) -> bool {
+        if let Some(pattern) = returns {
+            match &self.returns {
+                Some(ty) if pattern.matches(ty) => {}
+                _ => return false,
+            }
+        }
+
+        // Bipartite matching of patterns to parameters: a pattern may take
+        // a parameter an earlier pattern claimed if that one can move on.
+        let fits: Vec<Vec<usize>> = params
+            .iter()
+            .map(|pattern| {
+                (0..self.params.len())
+                    .filter(|&i| pattern.matches(&self.params[i]))
+                    .collect()
+            })
+            .collect();
+        let mut owner: Vec<Option<usize>> = vec![None; self.params.len()];
+        (0..params.len()).all(|pattern| {
+            let mut seen = vec![false; self.params.len()];
+            claim_param(pattern, &fits, &mut owner, &mut seen)
+        })
+    }
+
+    /// Converts to a [`Symbol`] with the signature in its metadata.
+    pub fn to_symbol(&self) -> Symbol {
+        Symbol {
+            id: SymbolId::stable(&self.name, self.kind, &self.location.file_path),
+            name: Arc::from(self.name.as_str()),
+            fully_qualified_name: Arc::from(self.name.as_str()),
+            kind: self.kind,
+            language: Language::Rust,
+            location: self.location.clone(),
+            parent_id: None,
+            visibility: self.visibility,
+            body_hash: None,
+            metadata: serde_json::json!({
+                "params": self.params,
+                "returns": self.returns,
+            }),
+        }
+    }
+}
+
+/// Finds a parameter for `pattern`, displacing earlier claims along an
+/// augmenting path. `owner` maps each parameter to the pattern holding it.
+fn claim_param(
+    pattern: usize,
+    fits: &[Vec<usize>],
+    owner: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for &param in &fits[pattern] {
+        if seen[param] {
+            continue;
+        }
+        seen[param] = true;
+        let free = match owner[param] {
+            None => true,
+            Some(other) => claim_param(other, fits, owner, seen),
+        };
+        if free {
+            owner[param] = Some(pattern);
+            return true;
+        }
+    }
+    false
+}
+
+/// Extracts the signatures of all functions declared in Rust source.
+pub fn extract_signatures(source: &str, file_path: &Path) -> Vec<FunctionSignature> {
+    let mut signatures = Vec::new();
+    if let Some(tree) = parse_rust(source) {
+        collect_signatures(source, file_path, &tree.root_node(), false, &mut signatures);
+    }
+    signatures
+}
+
+fn collect_signatures(
+    source: &str,
+    file_path: &Path,
+    node: &tree_sitter::Node,
+    in_impl: bool,
+    signatures: &mut Vec<FunctionSignature>,
+) {
+    let in_impl = in_impl || matches!(node.kind(), "impl_item" | "trait_item");
+
+    if matches!(node.kind(), "function_item" | "function_signature_item") {
+        if let Some(name) = node.child_by_field_name("name") {
+            let mut params = Vec::new();
+            if let Some(list) = node.child_by_field_name("parameters") {
+                let mut cursor = list.walk();
+                for param in list.children(&mut cursor) {
+                    if param.kind() == "parameter" {
+                        if let Some(ty) = param.child_by_field_name("type") {
+                            params.push(text(source, &ty).to_string());
+                        }
+                    }
+                }
+            }
+            let mut cursor = node.walk();
+            let modifier = node
+                .children(&mut cursor)
+                .find(|child| child.kind() == "visibility_modifier")
+                .map_or("", |modifier| text(source, &modifier));
+            let name = text(source, &name);
+            signatures.push(FunctionSignature {
+                name: name.to_string(),
+                kind: if in_impl {
+                    SymbolKind::Method
+                } else {
+                    SymbolKind::Function
+                },
+                params,
+                returns: node
+                    .child_by_field_name("return_type")
+                    .map(|ty| text(source, &ty).to_string()),
+                location: location(file_path, node),
+                visibility: Visibility::from_declaration(modifier, name, &Language::Rust),
+            });
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_signatures(source, file_path, &child, in_impl, signatures);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_signatures_skips_self_receiver() {
+        let source = "struct S;\n\
+                      impl S {\n    fn get(&self, key: &str, n: usize) -> Option<&str> { None }\n}\n\
+                      fn free() {}\n";
+        let signatures = extract_signatures(source, Path::new("src/lib.rs"));
+
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(signatures[0].name, "get");
+        assert_eq!(signatures[0].kind, SymbolKind::Method);
+        assert_eq!(signatures[0].params, vec!["&str", "usize"]);
+        assert_eq!(signatures[0].returns.as_deref(), Some("Option<&str>"));
+        assert_eq!(signatures[1].kind, SymbolKind::Function);
+        assert_eq!(signatures[1].returns, None);
+        assert_eq!(signatures[1].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_type_pattern_matching() {
+        assert!(TypePattern::named("Result").matches("io::Result<()>"));
+        assert!(TypePattern::named("Path").matches("&'a mut Path"));
+        assert!(TypePattern::named("Display").matches("impl Display"));
+        assert!(!TypePattern::named("Result").matches("Option<Result<()>>"));
+        assert!(TypePattern::exact("Vec<u8>").matches("Vec< u8 >"));
+        assert!(!TypePattern::exact("Vec<u8>").matches("Vec<u16>"));
+        assert!(TypePattern::any().matches("anything"));
+    }
+
+    #[test]
+    fn test_param_patterns_match_without_greedy_false_negative() {
+        let source = "fn f(a: String, b: u32) {}\n";
+        let signature = &extract_signatures(source, Path::new("src/lib.rs"))[0];
+
+        // `Any` must not take `String` away from the pattern that needs it
+        let params = [TypePattern::any(), TypePattern::named("String")];
+        assert!(signature.matches(&params, None));
+        let params = [TypePattern::named("String"), TypePattern::named("String")];
+        assert!(!signature.matches(&params, None));
+    }
+}
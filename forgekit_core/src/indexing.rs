@@ -4,10 +4,14 @@
 //! re-scans when files are modified.
 
 use crate::storage::UnifiedGraphStore;
+use crate::treesitter::CfgExtractor;
+use crate::types::{Language, SymbolId};
+use crate::walk::DirWalk;
 use crate::watcher::WatchEvent;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Path filter for controlling which files get indexed.
 ///
@@ -206,11 +210,209 @@ impl PathFilter {
     }
 }
 
+/// Settings shared by full and incremental symbol indexing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexerConfig {
+    /// Languages whose files are parsed and stored (empty = all supported)
+    pub languages: HashSet<Language>,
+    /// Threads reading and parsing files during a full index (0 = one per CPU)
+    pub parallelism: usize,
+    /// Hash symbol bodies ignoring trailing whitespace and blank-line runs
+    pub normalize_body_hash: bool,
+}
+
+impl IndexerConfig {
+    /// Restricts indexing to `languages`.
+    pub fn languages(mut self, languages: impl IntoIterator<Item = Language>) -> Self {
+        self.languages = languages.into_iter().collect();
+        self
+    }
+
+    /// Sets how many files are read and parsed at once during a full index.
+    ///
+    /// Writes to the graph are still made one file at a time.
+    pub fn parallelism(mut self, threads: usize) -> Self {
+        self.parallelism = threads;
+        self
+    }
+
+    /// Sets whether symbol body hashes ignore insignificant whitespace.
+    ///
+    /// When set, trailing whitespace is stripped from each line and runs
+    /// of blank lines count as one, so reformatting alone does not change
+    /// a symbol's hash.
+    pub fn normalize_body_hash(mut self, normalize: bool) -> Self {
+        self.normalize_body_hash = normalize;
+        self
+    }
+
+    /// Hashes a symbol's source `body`, normalized if configured.
+    pub fn body_hash(&self, body: &[u8]) -> blake3::Hash {
+        if !self.normalize_body_hash {
+            return blake3::hash(body);
+        }
+        let mut hasher = blake3::Hasher::new();
+        let mut blank_run = false;
+        for line in body.split(|&b| b == b'\n') {
+            let line = line.trim_ascii_end();
+            if line.is_empty() {
+                if blank_run {
+                    continue;
+                }
+                blank_run = true;
+            } else {
+                blank_run = false;
+            }
+            hasher.update(line);
+            hasher.update(b"\n");
+        }
+        hasher.finalize()
+    }
+
+    /// Number of parsing threads to use, resolving 0 to the CPU count.
+    pub(crate) fn workers(&self) -> usize {
+        match self.parallelism {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+
+    /// Returns true if `path` is in an allowed language.
+    pub fn allows(&self, path: &Path) -> bool {
+        self.languages.is_empty()
+            || Language::from_path(path).is_some_and(|lang| self.languages.contains(&lang))
+    }
+
+    /// Runs a magellan scan of `dir` limited to the allowed languages,
+    /// leaving out the files in `skip`.
+    pub(crate) fn scan(
+        &self,
+        graph: &mut magellan::CodeGraph,
+        dir: &Path,
+        skip: &[PathBuf],
+    ) -> anyhow::Result<magellan::ScanResult> {
+        let include: Vec<String> = self
+            .languages
+            .iter()
+            .flat_map(Language::extensions)
+            .map(|ext| format!("**/*.{}", ext))
+            .collect();
+        if !self.languages.is_empty() && include.is_empty() {
+            return Ok(magellan::ScanResult {
+                indexed: 0,
+                diagnostics: Vec::new(),
+            });
+        }
+        let exclude: Vec<String> = skip
+            .iter()
+            .filter_map(|path| path.strip_prefix(dir).ok())
+            .map(|relative| escape_glob(&relative.to_string_lossy()))
+            .collect();
+        let filter = magellan::FileFilter::new(dir, &include, &exclude)?;
+        graph.scan_directory_with_filter(dir, &filter, None)
+    }
+}
+
+/// Escapes glob metacharacters so `path` matches only itself.
+fn escape_glob(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | '!') {
+            escaped.push('[');
+            escaped.push(c);
+            escaped.push(']');
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Final pending action for a path after coalescing its events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PendingChange {
+    Index,
+    Delete,
+}
+
+/// Queued changes, keyed by path so iteration order is deterministic.
+#[derive(Debug, Default)]
+struct PendingQueue {
+    changes: BTreeMap<PathBuf, PendingChange>,
+    last_event: Option<Instant>,
+}
+
+/// A function as extracted by [`CfgExtractor`] when its file was last
+/// flushed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FunctionSnapshot {
+    name: String,
+    start_byte: usize,
+    end_byte: usize,
+    body_hash: [u8; 32],
+}
+
+impl FunctionSnapshot {
+    /// Extracts the functions in `path`; empty if the file cannot be read
+    /// or its language has no CFG extractor.
+    fn read(path: &Path) -> Vec<Self> {
+        let Some(lang) = CfgExtractor::detect_language(path) else {
+            return Vec::new();
+        };
+        let Ok(source) = std::fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        CfgExtractor::extract(&source, lang)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|f| Self {
+                body_hash: *blake3::hash(&source.as_bytes()[f.start_byte..f.end_byte]).as_bytes(),
+                name: f.name,
+                start_byte: f.start_byte,
+                end_byte: f.end_byte,
+            })
+            .collect()
+    }
+
+    /// Splits two versions of a file into the functions that were removed
+    /// or changed (from `previous`) and those added or changed (from
+    /// `current`).
+    ///
+    /// Functions are paired by name in order of appearance, so a rename
+    /// shows up as one removal and one addition. A pair is unchanged only
+    /// if both its span and its text are.
+    fn diff(previous: &[Self], current: &[Self]) -> (Vec<Self>, Vec<Self>) {
+        let mut unmatched: HashMap<&str, Vec<&Self>> = HashMap::new();
+        for function in previous.iter().rev() {
+            unmatched.entry(&function.name).or_default().push(function);
+        }
+
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for function in current {
+            match unmatched.get_mut(function.name.as_str()).and_then(Vec::pop) {
+                Some(old) if old == function => {}
+                Some(old) => {
+                    before.push(old.clone());
+                    after.push(function.clone());
+                }
+                None => after.push(function.clone()),
+            }
+        }
+        before.extend(unmatched.into_values().flatten().cloned());
+        (before, after)
+    }
+}
+
 /// Incremental indexer for processing file changes.
 ///
 /// The `IncrementalIndexer` batches file system events and processes
 /// them on flush, avoiding full re-indexing of the codebase.
 ///
+/// Events are coalesced per path: a burst such as truncate/write/chmod
+/// for one save leaves a single pending change reflecting the last
+/// event, so each file is processed once per flush, in path order.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -239,12 +441,14 @@ impl PathFilter {
 pub struct IncrementalIndexer {
     /// The graph store for writing index updates.
     store: Arc<UnifiedGraphStore>,
-    /// Pending files to process.
-    pending: Arc<tokio::sync::Mutex<HashSet<PathBuf>>>,
-    /// Files to delete.
-    deleted: Arc<tokio::sync::Mutex<HashSet<PathBuf>>>,
+    /// Pending changes to process, coalesced per path.
+    pending: Arc<parking_lot::Mutex<PendingQueue>>,
     /// Path filter for controlling which files get indexed.
     filter: PathFilter,
+    /// Functions per file as of the last flush that touched it.
+    snapshots: Arc<parking_lot::Mutex<HashMap<PathBuf, Vec<FunctionSnapshot>>>>,
+    /// Symbols changed by the last flush.
+    changed: Arc<parking_lot::Mutex<Vec<SymbolId>>>,
 }
 
 impl IncrementalIndexer {
@@ -258,9 +462,10 @@ impl IncrementalIndexer {
     pub fn new(store: Arc<UnifiedGraphStore>) -> Self {
         Self {
             store,
-            pending: Arc::new(tokio::sync::Mutex::new(HashSet::new())),
-            deleted: Arc::new(tokio::sync::Mutex::new(HashSet::new())),
+            pending: Arc::new(parking_lot::Mutex::new(PendingQueue::default())),
             filter: PathFilter::default(),
+            snapshots: Arc::default(),
+            changed: Arc::default(),
         }
     }
 
@@ -273,9 +478,10 @@ impl IncrementalIndexer {
     pub fn with_filter(store: Arc<UnifiedGraphStore>, filter: PathFilter) -> Self {
         Self {
             store,
-            pending: Arc::new(tokio::sync::Mutex::new(HashSet::new())),
-            deleted: Arc::new(tokio::sync::Mutex::new(HashSet::new())),
+            pending: Arc::new(parking_lot::Mutex::new(PendingQueue::default())),
             filter,
+            snapshots: Arc::default(),
+            changed: Arc::default(),
         }
     }
 
@@ -291,45 +497,38 @@ impl IncrementalIndexer {
 
     /// Queues a watch event for processing.
     ///
-    /// Only files matching the path filter will be queued.
+    /// Only files matching the path filter will be queued. A later event
+    /// for the same path replaces the earlier one, so a delete followed by
+    /// a create (as editors do for atomic saves) reindexes the file.
     ///
     /// # Arguments
     ///
     /// * `event` - The watch event to queue
     pub fn queue(&self, event: WatchEvent) {
-        match event {
-            WatchEvent::Created(path) | WatchEvent::Modified(path) => {
-                // Apply path filter
-                if !self.filter.should_index(&path) {
-                    return;
-                }
-
-                let pending = self.pending.clone();
-                tokio::spawn(async move {
-                    pending.lock().await.insert(path);
-                });
-            }
-            WatchEvent::Deleted(path) => {
-                // Apply path filter for deletions too
-                if !self.filter.should_index(&path) {
-                    return;
-                }
-
-                let deleted = self.deleted.clone();
-                tokio::spawn(async move {
-                    deleted.lock().await.insert(path);
-                });
-            }
+        let (path, change) = match event {
+            WatchEvent::Created(path) | WatchEvent::Modified(path) => (path, PendingChange::Index),
+            WatchEvent::Deleted(path) => (path, PendingChange::Delete),
             WatchEvent::Error(_) => {
                 // Log error but don't fail
+                return;
             }
+        };
+
+        // Apply path filter and language allowlist (deletions too)
+        if !self.filter.should_index(&path) || !self.store.indexer_config().allows(&path) {
+            return;
         }
+
+        let mut pending = self.pending.lock();
+        pending.changes.insert(path, change);
+        pending.last_event = Some(Instant::now());
     }
 
     /// Flushes pending changes to the graph store.
     ///
     /// This method processes all queued file changes and updates
-    /// the index incrementally.
+    /// the index incrementally. Deletions are applied first, then
+    /// re-indexing, each in path order.
     ///
     /// # Returns
     ///
@@ -338,33 +537,158 @@ impl IncrementalIndexer {
     /// # Errors
     ///
     /// Returns an error if any file cannot be indexed.
+    #[tracing::instrument(
+        skip(self),
+        fields(
+            changes = tracing::field::Empty,
+            indexed = tracing::field::Empty,
+            deleted = tracing::field::Empty,
+            skipped = tracing::field::Empty,
+        )
+    )]
     pub async fn flush(&self) -> anyhow::Result<FlushStats> {
-        let mut pending = self.pending.lock().await;
-        let mut deleted = self.deleted.lock().await;
+        self.store.ensure_writable()?;
+        // Take the batch so events queued during processing land in the next flush
+        let changes = {
+            let mut pending = self.pending.lock();
+            pending.last_event = None;
+            std::mem::take(&mut pending.changes)
+        };
+
+        // Diff functions before the store changes, so removed and changed
+        // symbols still resolve to their old entities
+        let mut changed = Vec::new();
+        let mut updated = Vec::new();
+        for (path, change) in &changes {
+            let current = match change {
+                PendingChange::Index => FunctionSnapshot::read(path),
+                PendingChange::Delete => Vec::new(),
+            };
+            let previous = {
+                let mut snapshots = self.snapshots.lock();
+                let previous = snapshots.remove(path).unwrap_or_default();
+                if !current.is_empty() {
+                    snapshots.insert(path.clone(), current.clone());
+                }
+                previous
+            };
+            let (before, after) = FunctionSnapshot::diff(&previous, &current);
+            changed.extend(self.resolve_symbols(path, &before));
+            updated.push((path, after));
+        }
 
         let mut stats = FlushStats::default();
 
         // Process deletions first
-        for path in deleted.drain() {
-            if let Err(e) = self.delete_file(&path).await {
+        for (path, _) in changes.iter().filter(|(_, c)| **c == PendingChange::Delete) {
+            if let Err(e) = self.delete_file(path).await {
                 eprintln!("Error deleting {:?}: {}", path, e);
+                stats.failed.push((path.clone(), e.to_string()));
             } else {
                 stats.deleted += 1;
             }
         }
 
         // Process additions/updates
-        for path in pending.drain() {
-            if let Err(e) = self.index_file(&path).await {
-                eprintln!("Error indexing {:?}: {}", path, e);
-            } else {
-                stats.indexed += 1;
+        for (path, _) in changes.iter().filter(|(_, c)| **c == PendingChange::Index) {
+            match self.index_file(path).await {
+                Ok(true) => stats.indexed += 1,
+                Ok(false) => stats.skipped += 1,
+                Err(e) => {
+                    eprintln!("Error indexing {:?}: {}", path, e);
+                    stats.failed.push((path.clone(), e.to_string()));
+                }
             }
         }
 
+        if !changes.is_empty() {
+            self.store.ref_index.invalidate();
+        }
+
+        for (path, after) in updated {
+            changed.extend(self.resolve_symbols(path, &after));
+        }
+        changed.sort_unstable();
+        changed.dedup();
+        *self.changed.lock() = changed;
+
+        let span = tracing::Span::current();
+        span.record("changes", changes.len());
+        span.record("indexed", stats.indexed);
+        span.record("deleted", stats.deleted);
+        span.record("skipped", stats.skipped);
         Ok(stats)
     }
 
+    /// Returns the symbols changed by the last flush, sorted by ID.
+    ///
+    /// A function counts as changed if it was added, removed, or its span
+    /// or body differs from the previous flush of its file. Functions in a
+    /// file flushed for the first time all count as added. Changed
+    /// functions are reported under their IDs from before and after
+    /// reindexing, so caches keyed by either can be invalidated.
+    pub fn changed_symbols(&self) -> Vec<SymbolId> {
+        self.changed.lock().clone()
+    }
+
+    /// Looks up the graph entities for `functions` in `path`.
+    ///
+    /// Entities are matched by name and start offset; functions with no
+    /// stored entity are skipped.
+    fn resolve_symbols(&self, path: &Path, functions: &[FunctionSnapshot]) -> Vec<SymbolId> {
+        if functions.is_empty() {
+            return Vec::new();
+        }
+        let Ok(conn) = crate::storage::open_read_only(self.store.db_path()) else {
+            return Vec::new();
+        };
+        let Ok(mut stmt) = conn.prepare(
+            "SELECT id, name, data FROM graph_entities WHERE kind = 'Symbol' AND file_path = ?1",
+        ) else {
+            return Vec::new();
+        };
+        let entities: Vec<(i64, String, Option<u64>)> = stmt
+            .query_map(rusqlite::params![path.to_string_lossy()], |row| {
+                let data: Option<String> = row.get(2)?;
+                let start = data
+                    .and_then(|d| serde_json::from_str::<serde_json::Value>(&d).ok())
+                    .and_then(|d| d.get("byte_start")?.as_u64());
+                Ok((row.get(0)?, row.get(1)?, start))
+            })
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default();
+
+        functions
+            .iter()
+            .filter_map(|f| {
+                entities
+                    .iter()
+                    .find(|(_, name, start)| *name == f.name && *start == Some(f.start_byte as u64))
+                    .map(|(id, _, _)| SymbolId(*id))
+            })
+            .collect()
+    }
+
+    /// Flushes only once no event has been queued for `debounce`.
+    ///
+    /// Returns `None` while the queue is empty or events are still
+    /// arriving, so a burst of events is processed as one batch after
+    /// it settles.
+    pub async fn flush_if_quiet(&self, debounce: Duration) -> Option<anyhow::Result<FlushStats>> {
+        if self.is_settled(debounce) {
+            Some(self.flush().await)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if changes are queued and none has arrived for
+    /// `debounce`, so [`flush_if_quiet`](Self::flush_if_quiet) would flush.
+    pub fn is_settled(&self, debounce: Duration) -> bool {
+        let pending = self.pending.lock();
+        !pending.changes.is_empty() && pending.last_event.is_none_or(|at| at.elapsed() >= debounce)
+    }
+
     /// Performs a full rescan of the codebase.
     ///
     /// This clears all pending changes and re-indexes from scratch,
@@ -379,21 +703,26 @@ impl IncrementalIndexer {
     /// `Ok(count)` with number of files indexed, or an error.
     pub async fn full_rescan(&self, root: &Path) -> anyhow::Result<usize> {
         // Clear pending
-        self.pending.lock().await.clear();
-        self.deleted.lock().await.clear();
+        self.clear_pending().await;
 
         let mut count = 0;
 
         // Walk directory tree
         if root.is_dir() {
-            self.scan_directory(root, &mut count).await?;
+            let mut walk = DirWalk::new(root, self.store.walk_options());
+            self.scan_directory(root, &mut walk, &mut count).await?;
         }
 
         Ok(count)
     }
 
     /// Recursively scans a directory for files to index.
-    async fn scan_directory(&self, dir: &Path, count: &mut usize) -> anyhow::Result<()> {
+    async fn scan_directory(
+        &self,
+        dir: &Path,
+        walk: &mut DirWalk,
+        count: &mut usize,
+    ) -> anyhow::Result<()> {
         let mut entries = tokio::fs::read_dir(dir).await?;
 
         while let Some(entry) = entries.next_entry().await? {
@@ -411,9 +740,17 @@ impl IncrementalIndexer {
                 }
 
                 // Recurse into allowed directories
-                Box::pin(self.scan_directory(&path, count)).await?;
-            } else if path.is_file() && self.filter.should_index(&path) {
-                self.pending.lock().await.insert(path);
+                if walk.enter_dir(&path) {
+                    Box::pin(self.scan_directory(&path, walk, count)).await?;
+                }
+            } else if walk.is_file(&path)
+                && self.filter.should_index(&path)
+                && self.store.indexer_config().allows(&path)
+            {
+                self.pending
+                    .lock()
+                    .changes
+                    .insert(path, PendingChange::Index);
                 *count += 1;
             }
         }
@@ -423,45 +760,63 @@ impl IncrementalIndexer {
 
     /// Returns the number of pending files to process.
     pub async fn pending_count(&self) -> usize {
-        self.pending.lock().await.len() + self.deleted.lock().await.len()
+        self.pending.lock().changes.len()
     }
 
     /// Clears all pending changes without processing.
     pub async fn clear_pending(&self) {
-        self.pending.lock().await.clear();
-        self.deleted.lock().await.clear();
+        let mut pending = self.pending.lock();
+        pending.changes.clear();
+        pending.last_event = None;
     }
 
     /// Indexes a single file using magellan.
-    async fn index_file(&self, path: &Path) -> anyhow::Result<()> {
+    ///
+    /// Returns false if the file was skipped for exceeding
+    /// [`WalkOptions::max_file_bytes`](crate::walk::WalkOptions::max_file_bytes).
+    async fn index_file(&self, path: &Path) -> anyhow::Result<bool> {
         if !path.exists() || !path.is_file() {
-            return Ok(());
+            return Ok(true);
+        }
+
+        let options = self.store.walk_options();
+        if options.is_oversized(path) {
+            tracing::info!("Skipping {} (over the file size limit)", path.display());
+            return Ok(false);
         }
 
-        let db_path = self.store.db_path().join("graph.db");
+        let db_path = self.store.db_path();
         if !db_path.exists() {
-            return Ok(());
+            return Ok(true);
         }
 
-        {
-            let mut graph = magellan::CodeGraph::open(&db_path)?;
-            if let Some(parent) = path.parent() {
-                graph.scan_directory(parent, None)?;
+        if let Some(parent) = path.parent() {
+            // The scan covers the whole directory, so leave out oversized
+            // neighbours too
+            let mut oversized = Vec::new();
+            if options.max_file_bytes.is_some() {
+                oversized =
+                    crate::search::collect_source_files(parent, options, &mut Vec::new()).await;
             }
+            let mut graph = magellan::CodeGraph::open(db_path)?;
+            let config = self.store.indexer_config();
+            config.scan(&mut graph, parent, &oversized)?;
+            drop(graph);
+            crate::graph::body_hash::store_missing(db_path, &self.store.codebase_path, config)?;
         }
 
-        Ok(())
+        Ok(true)
     }
 
     /// Deletes a file from the index using magellan.
     async fn delete_file(&self, path: &Path) -> anyhow::Result<()> {
-        let db_path = self.store.db_path().join("graph.db");
+        let db_path = self.store.db_path();
         if !db_path.exists() {
             return Ok(());
         }
 
         {
-            let mut graph = magellan::CodeGraph::open(&db_path)?;
+            let mut graph = magellan::CodeGraph::open(db_path)?;
             let path_str = path.to_string_lossy();
             let _ = graph.delete_file(&path_str);
         }
@@ -477,6 +832,10 @@ pub struct FlushStats {
     pub indexed: usize,
     /// Number of files deleted.
     pub deleted: usize,
+    /// Number of files skipped for exceeding the size limit.
+    pub skipped: usize,
+    /// Files that could not be indexed or deleted, with the reason.
+    pub failed: Vec<(PathBuf, String)>,
 }
 
 #[cfg(test)]
@@ -484,6 +843,31 @@ mod tests {
     use super::*;
     use crate::storage::{BackendKind, UnifiedGraphStore};
 
+    #[test]
+    fn test_body_hash_normalization() {
+        let body = b"fn add(a: i32) -> i32 {\n    a + 1\n}";
+        let exact = IndexerConfig::default();
+        assert_eq!(exact.body_hash(body), exact.body_hash(body));
+        assert_ne!(
+            exact.body_hash(body),
+            exact.body_hash(b"fn add(a: i32) -> i32 {\n    a - 1\n}")
+        );
+
+        let reformatted = b"fn add(a: i32) -> i32 {   \n\n\n    a + 1\t\n}";
+        assert_ne!(exact.body_hash(body), exact.body_hash(reformatted));
+
+        let normalized = IndexerConfig::default().normalize_body_hash(true);
+        let spaced = b"fn add(a: i32) -> i32 {   \n\n    a + 1\t\n}";
+        assert_eq!(
+            normalized.body_hash(spaced),
+            normalized.body_hash(reformatted)
+        );
+        assert_ne!(
+            normalized.body_hash(spaced),
+            normalized.body_hash(b"fn add(a: i32) -> i32 {\n\n    a - 1\n}")
+        );
+    }
+
     #[tokio::test]
     async fn test_indexer_creation() {
         let store = Arc::new(UnifiedGraphStore::memory().await.unwrap());
@@ -625,6 +1009,32 @@ mod tests {
         assert_eq!(stats.deleted, 1);
     }
 
+    #[tokio::test]
+    async fn test_flush_reports_failed_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open_with_path(
+                temp.path(),
+                temp.path().join("graph.db"),
+                BackendKind::SQLite,
+            )
+            .await
+            .unwrap(),
+        );
+        std::fs::write(store.db_path(), "not a database").unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        let file = temp.path().join("src/lib.rs");
+        std::fs::write(&file, "fn a() {}\n").unwrap();
+        let indexer = IncrementalIndexer::new(store);
+
+        indexer.queue(WatchEvent::Modified(file.clone()));
+        let stats = indexer.flush().await.unwrap();
+
+        assert_eq!(stats.indexed, 0);
+        assert_eq!(stats.failed.len(), 1);
+        assert_eq!(stats.failed[0].0, file);
+    }
+
     #[tokio::test]
     async fn test_clear_pending() {
         let store = Arc::new(UnifiedGraphStore::memory().await.unwrap());
@@ -682,11 +1092,156 @@ mod tests {
         assert_eq!(count, 3);
 
         // Verify pending queue has the files
-        let pending = indexer.pending.lock().await;
-        assert!(pending.contains(&src_dir.join("lib.rs")));
-        assert!(pending.contains(&src_dir.join("main.rs")));
-        assert!(pending.contains(&tests_dir.join("test.rs")));
-        assert!(!pending.contains(&target_dir.join("build.rs")));
-        assert!(!pending.contains(&temp.path().join("README.md")));
+        let pending = &indexer.pending.lock().changes;
+        assert!(pending.contains_key(&src_dir.join("lib.rs")));
+        assert!(pending.contains_key(&src_dir.join("main.rs")));
+        assert!(pending.contains_key(&tests_dir.join("test.rs")));
+        assert!(!pending.contains_key(&target_dir.join("build.rs")));
+        assert!(!pending.contains_key(&temp.path().join("README.md")));
+    }
+
+    #[tokio::test]
+    async fn test_flush_stores_body_hashes() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        let file = src.join("lib.rs");
+        std::fs::write(&file, "fn a() {}\nfn b() -> u8 { 1 }\n").unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open(temp.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let indexer = IncrementalIndexer::new(Arc::clone(&store));
+        indexer.queue(WatchEvent::Modified(file));
+        indexer.flush().await.unwrap();
+
+        let hashes = crate::graph::GraphModule::new(store)
+            .body_hashes()
+            .await
+            .unwrap();
+        let mut hashes: Vec<_> = hashes.into_values().collect();
+        let mut expected = vec![
+            blake3::hash(b"fn a() {}"),
+            blake3::hash(b"fn b() -> u8 { 1 }"),
+        ];
+        hashes.sort_by_key(|h| *h.as_bytes());
+        expected.sort_by_key(|h| *h.as_bytes());
+        assert_eq!(hashes, expected);
+    }
+
+    #[tokio::test]
+    async fn test_changed_symbols_after_flush() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        let file = src.join("lib.rs");
+        let store = Arc::new(
+            UnifiedGraphStore::open(temp.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let indexer = IncrementalIndexer::new(Arc::clone(&store));
+        let ids = || -> BTreeMap<String, SymbolId> {
+            let conn = rusqlite::Connection::open(store.db_path()).unwrap();
+            let mut stmt = conn
+                .prepare("SELECT name, id FROM graph_entities WHERE kind = 'Symbol'")
+                .unwrap();
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, SymbolId(row.get(1)?))))
+                .unwrap();
+            rows.flatten().collect()
+        };
+        let flush = |source: &str| {
+            std::fs::write(&file, source).unwrap();
+            indexer.queue(WatchEvent::Modified(file.clone()));
+            indexer.flush()
+        };
+
+        // First flush: every function is new
+        flush("fn a() {}\nfn b() {}\nfn c() {}\n").await.unwrap();
+        let v1 = ids();
+        assert_eq!(
+            indexer.changed_symbols(),
+            v1.values().copied().collect::<Vec<_>>()
+        );
+
+        // Renamed in place: old and new entity, neighbours untouched
+        flush("fn z() {}\nfn b() {}\nfn c() {}\n").await.unwrap();
+        let v2 = ids();
+        let mut expected = vec![v1["a"], v2["z"]];
+        expected.sort();
+        assert_eq!(indexer.changed_symbols(), expected);
+
+        // Swapped order: both spans shift
+        flush("fn z() {}\nfn c() {}\nfn b() {}\n").await.unwrap();
+        let v3 = ids();
+        let mut expected = vec![v2["b"], v2["c"], v3["b"], v3["c"]];
+        expected.sort();
+        expected.dedup();
+        assert_eq!(indexer.changed_symbols(), expected);
+        assert!(!indexer.changed_symbols().contains(&v3["z"]));
+
+        // Deleted file: every function removed
+        std::fs::remove_file(&file).unwrap();
+        indexer.queue(WatchEvent::Deleted(file.clone()));
+        indexer.flush().await.unwrap();
+        let mut expected: Vec<_> = v3.values().copied().collect();
+        expected.sort();
+        assert_eq!(indexer.changed_symbols(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_burst_of_events_coalesces_to_one_reindex() {
+        let store = Arc::new(UnifiedGraphStore::memory().await.unwrap());
+        let indexer = IncrementalIndexer::new(store);
+        let debounce = Duration::from_millis(100);
+
+        // One editor save: truncate, write, chmod; then an atomic replace
+        let path = PathBuf::from("src/lib.rs");
+        indexer.queue(WatchEvent::Modified(path.clone()));
+        indexer.queue(WatchEvent::Modified(path.clone()));
+        indexer.queue(WatchEvent::Modified(path.clone()));
+        indexer.queue(WatchEvent::Deleted(path.clone()));
+        indexer.queue(WatchEvent::Created(path.clone()));
+        assert_eq!(indexer.pending_count().await, 1);
+
+        // Still inside the debounce window
+        assert!(indexer.flush_if_quiet(debounce).await.is_none());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let stats = indexer.flush_if_quiet(debounce).await.unwrap().unwrap();
+        assert_eq!(
+            stats,
+            FlushStats {
+                indexed: 1,
+                deleted: 0,
+                skipped: 0,
+                failed: vec![],
+            }
+        );
+
+        // Nothing left to process
+        assert!(indexer.flush_if_quiet(debounce).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_last_event_wins_for_deleted_file() {
+        let store = Arc::new(UnifiedGraphStore::memory().await.unwrap());
+        let indexer = IncrementalIndexer::new(store);
+
+        indexer.queue(WatchEvent::Created(PathBuf::from("src/tmp.rs")));
+        indexer.queue(WatchEvent::Deleted(PathBuf::from("src/tmp.rs")));
+
+        let stats = indexer.flush().await.unwrap();
+        assert_eq!(
+            stats,
+            FlushStats {
+                indexed: 0,
+                deleted: 1,
+                skipped: 0,
+                failed: vec![],
+            }
+        );
     }
 }
@@ -33,6 +33,7 @@ pub mod error;
 pub mod types;
 
 pub mod build;
+pub mod circuit;
 pub mod dependency;
 pub mod diagnostic;
 pub mod diff;
@@ -50,6 +51,7 @@ pub mod graph;
 pub mod search;
 pub mod storage;
 pub mod treesitter;
+pub mod walk;
 
 // Knowledge graph module (sqlitegraph native-v3)
 pub mod knowledge;
@@ -70,14 +72,18 @@ pub use sqlitegraph::graph::{GraphEntity, SqliteGraph};
 pub use error::{ForgeError, Result};
 pub use storage::{BackendKind, UnifiedGraphStore};
 pub use types::{Location, SymbolId};
+pub use walk::WalkOptions;
 
 // Re-export runtime module types
-pub use cache::QueryCache;
-pub use indexing::{FlushStats, IncrementalIndexer, PathFilter};
-pub use pool::{ConnectionPermit, ConnectionPool};
+pub use cache::{CacheStats, EvictionPolicy, QueryCache, SegmentStats};
+pub use indexing::{FlushStats, IncrementalIndexer, IndexerConfig, PathFilter};
+pub use pool::{ConnectionPermit, ConnectionPool, GraphReaderPool};
 pub use runtime::Runtime;
 pub use watcher::{WatchEvent, Watcher};
 
+/// Version of the ForgeKit core crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 use anyhow::anyhow;
 
 /// Main entry point for ForgeKit SDK.
@@ -139,6 +145,19 @@ impl Forge {
         Ok(forge)
     }
 
+    /// Opens a prebuilt graph for the codebase without writing to it.
+    ///
+    /// Unlike [`open`](Self::open), nothing is created or indexed: fails if
+    /// the graph database does not exist, and inserts and indexing through
+    /// the returned instance fail with [`ForgeError::ReadOnly`].
+    pub async fn open_readonly(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let store = storage::UnifiedGraphStore::open_readonly(path, BackendKind::default()).await?;
+        Ok(Forge {
+            store: std::sync::Arc::new(store),
+            undo_capacity: 100,
+        })
+    }
+
     /// Returns the backend kind currently in use.
     pub fn backend_kind(&self) -> BackendKind {
         self.store.backend_kind()
@@ -234,7 +253,18 @@ pub struct ForgeBuilder {
     backend_kind: Option<BackendKind>,
     db_path: Option<std::path::PathBuf>,
     db_dir: Option<std::path::PathBuf>,
+    db_filename: Option<String>,
     undo_capacity: Option<usize>,
+    follow_symlinks: Option<bool>,
+    respect_gitignore: Option<bool>,
+    exclude: Option<Vec<String>>,
+    max_file_bytes: Option<usize>,
+    languages: Option<std::collections::HashSet<types::Language>>,
+    pool_size: Option<usize>,
+    index_parallelism: Option<usize>,
+    normalize_body_hash: Option<bool>,
+    #[cfg(feature = "semantic")]
+    embedder: Option<Arc<dyn search::Embedder>>,
 }
 
 impl ForgeBuilder {
@@ -275,6 +305,21 @@ impl ForgeBuilder {
         }
     }
 
+    /// Sets the database file name, keeping the directory from
+    /// [`db_dir`](Self::db_dir) or the default location.
+    ///
+    /// Without it SQLite databases are named after the project root, as
+    /// magellan names them, and other backends use their
+    /// [`default_filename`](BackendKind::default_filename), so SQLite and
+    /// Native V3 databases can share a directory. Ignored when
+    /// [`db_path`](Self::db_path) is set.
+    pub fn db_filename(self, name: impl Into<String>) -> Self {
+        Self {
+            db_filename: Some(name.into()),
+            ..self
+        }
+    }
+
     /// Sets the undo stack capacity (default: 100).
     pub fn undo_capacity(self, capacity: usize) -> Self {
         Self {
@@ -283,6 +328,98 @@ impl ForgeBuilder {
         }
     }
 
+    /// Sets whether directory walks follow symlinks (default: false).
+    ///
+    /// Symlinked directories are walked at most once either way, so link
+    /// cycles cannot cause infinite recursion.
+    pub fn follow_symlinks(self, follow: bool) -> Self {
+        Self {
+            follow_symlinks: Some(follow),
+            ..self
+        }
+    }
+
+    /// Sets whether directory walks skip paths ignored by `.gitignore`
+    /// files (default: true).
+    pub fn respect_gitignore(self, respect: bool) -> Self {
+        Self {
+            respect_gitignore: Some(respect),
+            ..self
+        }
+    }
+
+    /// Skips paths matching these gitignore-style patterns, relative to
+    /// the codebase root, in every directory walk.
+    pub fn exclude<S: Into<String>>(self, patterns: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            exclude: Some(patterns.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Skips files larger than `max` bytes in every directory walk
+    /// (default: no limit).
+    ///
+    /// Indexing reports the skipped files in
+    /// [`IndexReport::skipped`](graph::IndexReport::skipped).
+    pub fn max_file_bytes(self, max: usize) -> Self {
+        Self {
+            max_file_bytes: Some(max),
+            ..self
+        }
+    }
+
+    /// Restricts symbol indexing to files of the given languages.
+    ///
+    /// By default every supported language is indexed.
+    pub fn languages(self, languages: impl IntoIterator<Item = types::Language>) -> Self {
+        Self {
+            languages: Some(languages.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Sets the number of graph reader threads serving symbol queries
+    /// (default: [`DEFAULT_GRAPH_READERS`](pool::DEFAULT_GRAPH_READERS)).
+    pub fn pool_size(self, size: usize) -> Self {
+        Self {
+            pool_size: Some(size),
+            ..self
+        }
+    }
+
+    /// Sets how many files [`GraphModule::index`](graph::GraphModule::index)
+    /// reads and parses at once (default: one per CPU).
+    ///
+    /// Magellan's reference and call indexing parses inside its graph
+    /// writes, so it still runs one file at a time, overlapped with the
+    /// parsing of the next batch.
+    pub fn index_parallelism(self, threads: usize) -> Self {
+        Self {
+            index_parallelism: Some(threads),
+            ..self
+        }
+    }
+
+    /// Sets whether symbol body hashes ignore trailing whitespace and
+    /// extra blank lines (default: false).
+    pub fn normalize_body_hash(self, normalize: bool) -> Self {
+        Self {
+            normalize_body_hash: Some(normalize),
+            ..self
+        }
+    }
+
+    /// Sets the embedder used for semantic search
+    /// (default: [`HashingEmbedder`](search::HashingEmbedder)).
+    #[cfg(feature = "semantic")]
+    pub fn embedder(self, embedder: Arc<dyn search::Embedder>) -> Self {
+        Self {
+            embedder: Some(embedder),
+            ..self
+        }
+    }
+
     /// Builds a `Forge` instance with configured options.
     pub async fn build(self) -> anyhow::Result<Forge> {
         let path = self.path.ok_or_else(|| anyhow!("path is required"))?;
@@ -290,16 +427,43 @@ impl ForgeBuilder {
 
         let resolved_db = if let Some(explicit) = self.db_path {
             explicit
-        } else if let Some(dir) = self.db_dir {
-            let stem = path.file_name().and_then(|n| n.to_str()).unwrap_or("graph");
-            dir.join(format!("{}.db", stem))
         } else {
-            storage::default_db_path(&path)
+            let default = match self.db_dir {
+                Some(dir) => {
+                    let stem = path.file_name().and_then(|n| n.to_str()).unwrap_or("graph");
+                    dir.join(format!("{}.db", stem))
+                }
+                None => storage::default_db_path(&path),
+            };
+            match self.db_filename {
+                Some(name) => default.with_file_name(name),
+                // SQLite keeps magellan's `<project>.db` name
+                None if backend == BackendKind::SQLite => default,
+                None => default.with_file_name(backend.default_filename()),
+            }
         };
 
-        let store = std::sync::Arc::new(
-            storage::UnifiedGraphStore::open_with_path(&path, &resolved_db, backend).await?,
-        );
+        let store = storage::UnifiedGraphStore::open_with_path(&path, &resolved_db, backend)
+            .await?
+            .with_walk_options(
+                WalkOptions::default()
+                    .follow_symlinks(self.follow_symlinks.unwrap_or(false))
+                    .respect_gitignore(self.respect_gitignore.unwrap_or(true))
+                    .exclude(self.exclude.unwrap_or_default())
+                    .max_file_bytes(self.max_file_bytes),
+            )
+            .with_indexer_config(IndexerConfig {
+                languages: self.languages.unwrap_or_default(),
+                parallelism: self.index_parallelism.unwrap_or(0),
+                normalize_body_hash: self.normalize_body_hash.unwrap_or(false),
+            })
+            .with_pool_size(self.pool_size.unwrap_or(pool::DEFAULT_GRAPH_READERS));
+        #[cfg(feature = "semantic")]
+        let store = match self.embedder {
+            Some(embedder) => store.with_embedder(embedder),
+            None => store,
+        };
+        let store = std::sync::Arc::new(store);
 
         Ok(Forge {
             store,
@@ -554,6 +718,43 @@ mod tests {
         assert_eq!(forge.store.db_path, db_dir.join("my-project.db"));
     }
 
+    #[tokio::test]
+    async fn test_forge_builder_db_filename_per_backend() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_dir = temp_dir.path().join(".forge");
+        let project_dir = temp_dir.path().join("my-project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        // Both backends side by side in one directory
+        let sqlite = ForgeBuilder::new()
+            .path(&project_dir)
+            .db_dir(db_dir.clone())
+            .build()
+            .await
+            .unwrap();
+        let native = ForgeBuilder::new()
+            .path(&project_dir)
+            .db_dir(db_dir.clone())
+            .backend_kind(BackendKind::NativeV3)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(sqlite.store.db_path, db_dir.join("my-project.db"));
+        assert_eq!(native.store.db_path, db_dir.join("graph.v3"));
+        assert!(db_dir.join("graph.v3").exists());
+
+        let named = ForgeBuilder::new()
+            .path(&project_dir)
+            .db_dir(db_dir.clone())
+            .backend_kind(BackendKind::NativeV3)
+            .db_filename("side.v3")
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(named.store.db_path, db_dir.join("side.v3"));
+        assert!(db_dir.join("side.v3").exists());
+    }
+
     #[tokio::test]
     async fn test_connection_pool_exported() {
         use tempfile::TempDir;
@@ -1,11 +1,173 @@
 //! Connection pool for concurrent database access.
 //!
 //! This module provides a semaphore-based connection pool
-//! for limiting concurrent database connections.
+//! for limiting concurrent database connections, and a pool of
+//! graph reader threads for running read-only graph queries.
 
+use crate::error::{ForgeError, Result};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 
+/// Default number of graph reader threads per store.
+pub const DEFAULT_GRAPH_READERS: usize = 4;
+
+/// A query run on a reader thread against its connection.
+type ReaderJob = Box<dyn FnOnce(&mut Option<rusqlite::Connection>, &Path) + Send>;
+
+/// Bounded pool of threads that each own a read-only graph connection.
+///
+/// Queries block on SQLite, so they run off the async runtime: each
+/// reader thread opens its own connection on first use and keeps it;
+/// queries are sent to whichever reader is idle over a channel and their
+/// results returned through a oneshot. Threads are started on the first
+/// query and exit once the pool is dropped.
+///
+/// Connections are opened read-only, so readers never take write locks
+/// and work on databases that cannot be written. Streams run outside the
+/// reader set, see [`GraphReaderPool::stream`].
+pub struct GraphReaderPool {
+    db_path: PathBuf,
+    size: usize,
+    sender: parking_lot::Mutex<Option<mpsc::Sender<ReaderJob>>>,
+}
+
+impl GraphReaderPool {
+    /// Creates a pool of `size` readers (at least one) for `db_path`.
+    pub fn new(db_path: impl AsRef<Path>, size: usize) -> Self {
+        Self {
+            db_path: db_path.as_ref().to_path_buf(),
+            size: size.max(1),
+            sender: parking_lot::Mutex::new(None),
+        }
+    }
+
+    /// Returns the number of reader threads.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Runs `query` on an idle reader and returns its result.
+    pub(crate) async fn query<T, F>(&self, query: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&rusqlite::Connection) -> Result<T> + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let job: ReaderJob = Box::new(move |conn, db_path| {
+            let _ = tx.send(open_reader(conn, db_path).and_then(query));
+        });
+
+        self.sender()
+            .send(job)
+            .map_err(|_| ForgeError::DatabaseError("Graph readers stopped".to_string()))?;
+        rx.await
+            .map_err(|_| ForgeError::DatabaseError("Graph reader stopped".to_string()))?
+    }
+
+    /// Runs `query` on its own thread, streaming the items it emits.
+    ///
+    /// At most `buffer` items wait unread; beyond that the producer blocks
+    /// until the stream is polled. `emit` returns false once the stream
+    /// has been dropped, so the query can stop early.
+    ///
+    /// A stream can stay open for as long as its consumer likes, so the
+    /// producer opens its own read-only connection rather than holding one
+    /// of the pool's readers; queries keep running while streams are open.
+    pub(crate) fn stream<T, F>(
+        &self,
+        buffer: usize,
+        query: F,
+    ) -> impl futures::Stream<Item = Result<T>>
+    where
+        T: Send + 'static,
+        F: FnOnce(&rusqlite::Connection, &mut dyn FnMut(T) -> bool) -> Result<()> + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel(buffer.max(1));
+        let failed = tx.clone();
+        let db_path = self.db_path.clone();
+        let spawned = std::thread::Builder::new()
+            .name("forge-graph-stream".to_string())
+            .spawn(move || {
+                let mut conn = None;
+                let result = open_reader(&mut conn, &db_path)
+                    .and_then(|conn| query(conn, &mut |item| tx.blocking_send(Ok(item)).is_ok()));
+                if let Err(e) = result {
+                    let _ = tx.blocking_send(Err(e));
+                }
+            });
+
+        if let Err(e) = spawned {
+            let _ = failed.try_send(Err(ForgeError::DatabaseError(format!(
+                "Failed to start graph stream: {}",
+                e
+            ))));
+        }
+        drop(failed);
+
+        futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        })
+    }
+
+    /// Returns the job sender, starting the reader threads on first use.
+    fn sender(&self) -> mpsc::Sender<ReaderJob> {
+        let mut sender = self.sender.lock();
+        sender
+            .get_or_insert_with(|| {
+                let (tx, rx) = mpsc::channel::<ReaderJob>();
+                let rx = Arc::new(parking_lot::Mutex::new(rx));
+                for i in 0..self.size {
+                    let rx = Arc::clone(&rx);
+                    let db_path = self.db_path.clone();
+                    std::thread::Builder::new()
+                        .name(format!("forge-graph-reader-{}", i))
+                        .spawn(move || {
+                            let mut conn = None;
+                            loop {
+                                // Lock only while waiting, so idle readers
+                                // take turns picking up jobs
+                                let job = rx.lock().recv();
+                                match job {
+                                    Ok(job) => job(&mut conn, &db_path),
+                                    Err(_) => break,
+                                }
+                            }
+                        })
+                        .expect("invariant: graph reader thread spawns");
+                }
+                tx
+            })
+            .clone()
+    }
+}
+
+/// Returns the reader's connection, opening it read-only on first use.
+fn open_reader<'a>(
+    conn: &'a mut Option<rusqlite::Connection>,
+    db_path: &Path,
+) -> Result<&'a rusqlite::Connection> {
+    if conn.is_none() {
+        let opened = crate::storage::open_read_only(db_path).map_err(|e| {
+            ForgeError::DatabaseError(format!("Failed to open graph reader: {}", e))
+        })?;
+        *conn = Some(opened);
+    }
+    Ok(conn
+        .as_ref()
+        .expect("invariant: reader connection opened above"))
+}
+
+impl std::fmt::Debug for GraphReaderPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GraphReaderPool")
+            .field("db_path", &self.db_path)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
 /// Connection pool for database connections.
 ///
 /// The pool limits the number of concurrent connections
@@ -173,6 +173,8 @@ impl Runtime {
         crate::indexing::FlushStats {
             indexed: 0,
             deleted: 0,
+            skipped: 0,
+            failed: Vec::new(),
         }
     }
 }
@@ -0,0 +1,324 @@
+//! Vector embeddings for semantic symbol search.
+//!
+//! With the `semantic` feature, [`GraphModule::index`](crate::graph::GraphModule::index)
+//! embeds each symbol's name and doc comment with the store's [`Embedder`]
+//! and stores the vectors in the graph database's `forge_embeddings` table.
+//! [`SearchModule::semantic_search_ranked`](super::SearchModule::semantic_search_ranked)
+//! embeds the query the same way and ranks symbols by cosine similarity.
+
+use crate::error::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Turns text into a fixed-length vector.
+///
+/// Implement this to plug in a local model or a remote embedding API.
+/// Every vector returned by one embedder must have the same length.
+pub trait Embedder: Send + Sync {
+    /// Embeds `text`.
+    fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// Default vector length of [`HashingEmbedder`].
+pub const DEFAULT_DIMENSIONS: usize = 256;
+
+/// Deterministic embedder based on feature hashing.
+///
+/// Identifiers are split into words (`parseConfig` and `parse_config` both
+/// give `parse`, `config`), and each word and its character trigrams are
+/// hashed into a bucket. Needs no model, so results are the same on every
+/// machine; similarity reflects shared vocabulary, not meaning.
+#[derive(Debug, Clone, Copy)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    /// Creates an embedder producing vectors of `dimensions` (at least 1).
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIMENSIONS)
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        let mut add = |feature: &str, weight: f32| {
+            let hash = blake3::hash(feature.as_bytes());
+            let bytes: [u8; 8] = hash.as_bytes()[..8]
+                .try_into()
+                .expect("invariant: blake3 hash is 32 bytes");
+            let bits = u64::from_le_bytes(bytes);
+            let bucket = (bits % self.dimensions as u64) as usize;
+            let sign = if bits >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign * weight;
+        };
+
+        for word in words(text) {
+            add(&word, 1.0);
+            let padded: Vec<char> = format!("<{}>", word).chars().collect();
+            for trigram in padded.windows(3) {
+                add(&trigram.iter().collect::<String>(), 0.5);
+            }
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        Ok(vector)
+    }
+}
+
+/// Splits `text` into lowercase words, breaking identifiers at `_` and
+/// lowercase-to-uppercase transitions.
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Cosine similarity of two vectors; 0 if either is zero or the lengths differ.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// A symbol with its stored embedding.
+pub(crate) struct EmbeddedSymbol {
+    pub(crate) entity_id: i64,
+    pub(crate) name: String,
+    pub(crate) kind: String,
+    pub(crate) file_path: PathBuf,
+    pub(crate) byte_start: usize,
+    pub(crate) byte_end: usize,
+    pub(crate) line_number: usize,
+    pub(crate) vector: Vec<f32>,
+}
+
+const CREATE_EMBEDDING_TABLE: &str = "CREATE TABLE IF NOT EXISTS forge_embeddings (
+    entity_id INTEGER PRIMARY KEY,
+    vector BLOB NOT NULL
+);";
+
+/// Re-embeds every symbol in the graph database, replacing stored vectors.
+///
+/// Returns the number of symbols embedded.
+pub(crate) fn index_embeddings(db_path: &Path, embedder: &dyn Embedder) -> Result<usize> {
+    let mut conn = rusqlite::Connection::open(db_path)?;
+    conn.execute_batch(CREATE_EMBEDDING_TABLE)?;
+
+    let symbols = symbol_rows(&conn)?;
+    let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut vectors = Vec::with_capacity(symbols.len());
+    for (entity_id, name, file_path, byte_start) in symbols {
+        let source = sources
+            .entry(file_path.clone())
+            .or_insert_with(|| std::fs::read_to_string(&file_path).ok());
+        let docs = source
+            .as_deref()
+            .map(|s| doc_comment(s, byte_start))
+            .unwrap_or_default();
+        vectors.push((entity_id, embedder.embed(&format!("{} {}", name, docs))?));
+    }
+
+    let tx = conn.transaction()?;
+    // Entity ids change on reindex, so stale rows are never reusable
+    tx.execute("DELETE FROM forge_embeddings", [])?;
+    for (entity_id, vector) in &vectors {
+        let blob: Vec<u8> = vector.iter().flat_map(|x| x.to_le_bytes()).collect();
+        tx.execute(
+            "INSERT INTO forge_embeddings (entity_id, vector) VALUES (?1, ?2)",
+            rusqlite::params![entity_id, blob],
+        )?;
+    }
+    tx.commit()?;
+    Ok(vectors.len())
+}
+
+/// Returns `(entity_id, name, file_path, byte_start)` for every symbol.
+fn symbol_rows(conn: &rusqlite::Connection) -> Result<Vec<(i64, String, PathBuf, usize)>> {
+    let mut stmt =
+        conn.prepare("SELECT id, name, file_path, data FROM graph_entities WHERE kind = 'Symbol'")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    Ok(rows
+        .flatten()
+        .filter_map(|(id, name, file_path, data)| {
+            let data: serde_json::Value = serde_json::from_str(data.as_deref()?).ok()?;
+            let byte_start = data.get("byte_start")?.as_u64()? as usize;
+            Some((id, name?, PathBuf::from(file_path?), byte_start))
+        })
+        .collect())
+}
+
+/// Loads stored embeddings joined with their symbols.
+///
+/// Empty if the database has never been indexed with embeddings.
+pub(crate) fn load_embeddings(db_path: &Path) -> Result<Vec<EmbeddedSymbol>> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = crate::storage::open_read_only(db_path)?;
+    let mut stmt = match conn.prepare(
+        "SELECT e.id, e.name, e.file_path, e.data, f.vector
+         FROM forge_embeddings f JOIN graph_entities e ON e.id = f.entity_id",
+    ) {
+        Ok(stmt) => stmt,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Vec<u8>>(4)?,
+        ))
+    })?;
+
+    Ok(rows
+        .flatten()
+        .filter_map(|(entity_id, name, file_path, data, blob)| {
+            let data: serde_json::Value = serde_json::from_str(data.as_deref()?).ok()?;
+            let field = |key: &str| data.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let kind = data
+                .get("kind_normalized")
+                .or_else(|| data.get("kind"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_lowercase();
+            Some(EmbeddedSymbol {
+                entity_id,
+                name: name?,
+                kind,
+                file_path: PathBuf::from(file_path?),
+                byte_start: field("byte_start"),
+                byte_end: field("byte_end"),
+                line_number: field("start_line"),
+                vector: blob
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// Returns the doc comment attached to the item starting at `byte_start`.
+///
+/// Collects the comment lines directly above the item, skipping attributes
+/// and decorators, and, for Python, the docstring on the lines following a `def` or `class`.
+fn doc_comment(source: &str, byte_start: usize) -> String {
+    let Some(before) = source.get(..byte_start) else {
+        return String::new();
+    };
+    // Drop the item's own line, including any indentation before it
+    let before = &before[..before.rfind('\n').unwrap_or(0)];
+
+    let mut lines = Vec::new();
+    for line in before.lines().rev().map(str::trim) {
+        if line.starts_with("#[") || line.starts_with('@') {
+            continue;
+        }
+        let text = ["///", "//!", "//", "#", "*"]
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker));
+        match text {
+            Some(text) if !line.starts_with("/*") => lines.push(text.trim().to_string()),
+            _ => break,
+        }
+    }
+    lines.reverse();
+
+    let after = &source[byte_start..];
+    let docstring = after.lines().skip(1).map(str::trim).find(|l| !l.is_empty());
+    if let Some(quote) =
+        docstring.and_then(|l| ["\"\"\"", "'''"].into_iter().find(|q| l.starts_with(q)))
+    {
+        let start = after.find(quote).map(|i| i + quote.len()).unwrap_or(0);
+        let body = &after[start..];
+        let end = body.find(quote).unwrap_or(body.len());
+        lines.push(body[..end].trim().to_string());
+    }
+
+    lines.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashing_embedder_is_deterministic_and_normalized() {
+        let embedder = HashingEmbedder::default();
+        let a = embedder.embed("parse_config").unwrap();
+        let b = embedder.embed("parse_config").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.len(), DEFAULT_DIMENSIONS);
+        let norm = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+
+        let camel = embedder.embed("parseConfig").unwrap();
+        assert!(cosine_similarity(&a, &camel) > 0.99);
+        let unrelated = embedder.embed("render widget").unwrap();
+        assert!(cosine_similarity(&a, &unrelated) < cosine_similarity(&a, &camel));
+    }
+
+    #[test]
+    fn test_doc_comment_reads_comments_and_docstrings() {
+        let rust = "/// Loads the config.\n/// From disk.\n#[inline]\nfn load() {}\n";
+        assert_eq!(
+            doc_comment(rust, rust.find("fn load").unwrap()),
+            "Loads the config. From disk."
+        );
+
+        let python = "def load():\n    \"\"\"Loads the config.\"\"\"\n    pass\n";
+        assert_eq!(doc_comment(python, 0), "Loads the config.");
+
+        let bare = "fn a() {}\n\nfn b() {}\n";
+        assert_eq!(doc_comment(bare, bare.find("fn b").unwrap()), "");
+    }
+}
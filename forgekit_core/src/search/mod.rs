@@ -6,10 +6,46 @@
 
 use crate::error::{ForgeError, Result as ForgeResult};
 use crate::storage::UnifiedGraphStore;
-use crate::types::{Language, Location, Symbol, SymbolId, SymbolKind};
-use std::path::PathBuf;
+use crate::types::{Language, Location, Symbol, SymbolId, SymbolKind, Visibility};
+use crate::walk::{DirWalk, WalkOptions};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+#[cfg(feature = "semantic")]
+pub mod embedding;
+
+#[cfg(feature = "semantic")]
+pub use embedding::{cosine_similarity, Embedder, HashingEmbedder};
+
+/// Options for [`SearchModule::pattern_search_opts`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOpts {
+    /// Do not match inside comments
+    pub ignore_comments: bool,
+    /// Do not match inside string and character literals
+    pub ignore_strings: bool,
+}
+
+/// A [`SearchModule::semantic_search_detailed`] result: a symbol and where
+/// the query matched its source line.
+#[derive(Clone, Debug)]
+pub struct SearchHit {
+    pub symbol: Symbol,
+    /// Keyword matches in the symbol's source line, in line order
+    pub spans: Vec<MatchSpan>,
+}
+
+/// A query keyword found in a source line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchSpan {
+    /// The query keyword that matched
+    pub keyword: String,
+    /// Byte offset of the match within the line
+    pub byte_start: usize,
+    /// Byte offset just past the match
+    pub byte_end: usize,
+}
+
 /// Search module for semantic code queries.
 pub struct SearchModule {
     store: Arc<UnifiedGraphStore>,
@@ -44,6 +80,24 @@ impl SearchModule {
         self.pattern_search_via_files(pattern).await
     }
 
+    /// Pattern-based search that can skip comments and string literals.
+    ///
+    /// With no option set this is [`pattern_search`](Self::pattern_search).
+    /// Otherwise source files are scanned with comments and/or strings
+    /// blanked out using the tree-sitter grammar for the file's language,
+    /// so a pattern only matches real code. Files in languages without a
+    /// grammar (anything but C, Java, Python and Rust) are matched raw.
+    pub async fn pattern_search_opts(
+        &self,
+        pattern: &str,
+        opts: SearchOpts,
+    ) -> ForgeResult<Vec<Symbol>> {
+        if opts == SearchOpts::default() {
+            return self.pattern_search(pattern).await;
+        }
+        self.scan_files(pattern, opts).await
+    }
+
     /// Pattern-based search (alias for `pattern_search`).
     pub async fn pattern(&self, pattern: &str) -> ForgeResult<Vec<Symbol>> {
         self.pattern_search(pattern).await
@@ -54,6 +108,21 @@ impl SearchModule {
     /// With llmgrep: delegates to `llmgrep::forge::search_symbols`.
     /// Without: splits query into keywords and scans files.
     pub async fn semantic_search(&self, query: &str) -> ForgeResult<Vec<Symbol>> {
+        Ok(self
+            .semantic_search_detailed(query)
+            .await?
+            .into_iter()
+            .map(|hit| hit.symbol)
+            .collect())
+    }
+
+    /// [`semantic_search`](Self::semantic_search) with the matched spans.
+    ///
+    /// Each hit lists every occurrence of a query keyword in the line the
+    /// symbol is declared on, matched case-insensitively. A symbol found
+    /// through a keyword that merely contains its name is spanned at the
+    /// name.
+    pub async fn semantic_search_detailed(&self, query: &str) -> ForgeResult<Vec<SearchHit>> {
         if query.trim().is_empty() {
             return Ok(Vec::new());
         }
@@ -61,7 +130,7 @@ impl SearchModule {
         let db_path = self.store.db_path.clone();
         if db_path.exists() {
             if let Ok(results) = self.search_via_llmgrep(query, false).await {
-                return Ok(results);
+                return Ok(self.with_spans(results, &query_keywords(query)).await);
             }
         }
 
@@ -73,6 +142,62 @@ impl SearchModule {
         self.semantic_search(query).await
     }
 
+    /// Ranks indexed symbols by embedding similarity to `query`.
+    ///
+    /// Embeds `query` with the store's [`Embedder`] and returns the
+    /// `top_k` symbols whose name and doc comment embeddings have the
+    /// highest cosine similarity, best first. Empty until the graph has
+    /// been indexed.
+    #[cfg(feature = "semantic")]
+    pub async fn semantic_search_ranked(
+        &self,
+        query: &str,
+        top_k: usize,
+    ) -> ForgeResult<Vec<(Symbol, f32)>> {
+        if query.trim().is_empty() || top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let query_vector = self.store.embedder.embed(query)?;
+        let mut scored: Vec<_> = embedding::load_embeddings(&self.store.db_path)?
+            .into_iter()
+            .map(|symbol| {
+                let score = cosine_similarity(&query_vector, &symbol.vector);
+                (symbol, score)
+            })
+            .collect();
+        scored.sort_by(|(a, x), (b, y)| {
+            y.total_cmp(x)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.byte_start.cmp(&b.byte_start))
+        });
+        scored.truncate(top_k);
+
+        Ok(scored
+            .into_iter()
+            .map(|(s, score)| {
+                let symbol = Symbol {
+                    id: SymbolId(s.entity_id),
+                    name: Arc::from(s.name.as_str()),
+                    fully_qualified_name: Arc::from(s.name.as_str()),
+                    kind: crate::graph::parse_symbol_kind_str(&s.kind),
+                    language: crate::graph::map_magellan_language(&s.file_path),
+                    location: Location {
+                        file_path: s.file_path,
+                        byte_start: s.byte_start as u32,
+                        byte_end: s.byte_end as u32,
+                        line_number: s.line_number,
+                    },
+                    parent_id: None,
+                    visibility: Visibility::Unknown,
+                    body_hash: None,
+                    metadata: serde_json::Value::Null,
+                };
+                (symbol, score)
+            })
+            .collect())
+    }
+
     /// Find a specific symbol by name.
     pub async fn symbol_by_name(&self, name: &str) -> ForgeResult<Option<Symbol>> {
         let symbols = self.pattern_search(name).await?;
@@ -81,13 +206,19 @@ impl SearchModule {
 
     /// Find all symbols of a specific kind.
     pub async fn symbols_by_kind(&self, kind: SymbolKind) -> ForgeResult<Vec<Symbol>> {
-        let all_symbols = self
-            .store
-            .get_all_symbols()
-            .await
-            .map_err(|e| ForgeError::DatabaseError(format!("Kind search failed: {}", e)))?;
-
-        Ok(all_symbols.into_iter().filter(|s| s.kind == kind).collect())
+        let mut matches = Vec::new();
+        let mut offset = 0;
+        loop {
+            let (page, has_more) = self
+                .store
+                .list_symbols(offset, crate::storage::SYMBOL_PAGE_SIZE)
+                .await?;
+            offset += page.len();
+            matches.extend(page.into_iter().filter(|s| s.kind == kind));
+            if !has_more {
+                return Ok(matches);
+            }
+        }
     }
 
     /// Find all references to a symbol.
@@ -96,27 +227,39 @@ impl SearchModule {
         if !db_path.exists() {
             return Ok(Vec::new());
         }
-        llmgrep::forge::search_references(symbol_name, &db_path, limit)
-            .map(|refs| {
-                refs.into_iter()
-                    .map(|r| Symbol {
-                        id: SymbolId(0),
-                        name: Arc::from(r.referenced_symbol.clone()),
-                        fully_qualified_name: Arc::from(r.referenced_symbol),
-                        kind: SymbolKind::Function,
-                        language: Language::Unknown("unknown".to_string()),
-                        location: Location {
-                            file_path: PathBuf::from(&r.span.file_path),
-                            byte_start: r.span.byte_start as u32,
-                            byte_end: r.span.byte_end as u32,
-                            line_number: r.span.start_line as usize,
-                        },
-                        parent_id: None,
-                        metadata: serde_json::Value::Null,
-                    })
-                    .collect()
+        let root = &self.store.codebase_path;
+        let mut refs = llmgrep::forge::search_references(symbol_name, &db_path, limit)
+            .map_err(ForgeError::from)?;
+        refs.sort_by(|a, b| {
+            (&a.span.file_path, a.span.byte_start).cmp(&(&b.span.file_path, b.span.byte_start))
+        });
+
+        let ids = reference_site_ids(
+            root,
+            refs.iter()
+                .map(|r| (r.referenced_symbol.as_str(), Path::new(&r.span.file_path))),
+        );
+        Ok(refs
+            .into_iter()
+            .zip(ids)
+            .map(|(r, id)| Symbol {
+                id,
+                name: Arc::from(r.referenced_symbol.clone()),
+                fully_qualified_name: Arc::from(r.referenced_symbol),
+                kind: SymbolKind::Function,
+                language: Language::Unknown("unknown".to_string()),
+                location: Location {
+                    file_path: PathBuf::from(&r.span.file_path),
+                    byte_start: r.span.byte_start as u32,
+                    byte_end: r.span.byte_end as u32,
+                    line_number: r.span.start_line as usize,
+                },
+                parent_id: None,
+                visibility: Visibility::Unknown,
+                body_hash: None,
+                metadata: serde_json::Value::Null,
             })
-            .map_err(|e| ForgeError::DatabaseError(format!("Reference search failed: {}", e)))
+            .collect())
     }
 
     /// Find all calls involving a symbol.
@@ -125,12 +268,17 @@ impl SearchModule {
         if !db_path.exists() {
             return Ok(Vec::new());
         }
+        let root = &self.store.codebase_path;
         llmgrep::forge::search_calls(symbol_name, &db_path, limit)
             .map(|calls| {
                 calls
                     .into_iter()
                     .map(|c| Symbol {
-                        id: SymbolId(0),
+                        id: SymbolId::stable(
+                            &c.caller,
+                            SymbolKind::Function,
+                            relative_to(root, Path::new(&c.span.file_path)),
+                        ),
                         name: Arc::from(c.caller.clone()),
                         fully_qualified_name: Arc::from(c.caller.clone()),
                         kind: SymbolKind::Function,
@@ -142,11 +290,13 @@ impl SearchModule {
                             line_number: c.span.start_line as usize,
                         },
                         parent_id: None,
+                        visibility: Visibility::Unknown,
+                        body_hash: None,
                         metadata: serde_json::Value::Null,
                     })
                     .collect()
             })
-            .map_err(|e| ForgeError::DatabaseError(format!("Call search failed: {}", e)))
+            .map_err(ForgeError::from)
     }
 
     /// Lookup a symbol by fully-qualified name.
@@ -156,8 +306,8 @@ impl SearchModule {
             return Ok(None);
         }
         llmgrep::forge::lookup_symbol(fqn, &db_path)
-            .map(|m| Some(llmgrep_match_to_symbol(m)))
-            .map_err(|e| ForgeError::DatabaseError(format!("Lookup failed: {}", e)))
+            .map(|m| Some(llmgrep_match_to_symbol(m, &self.store.codebase_path)))
+            .map_err(ForgeError::from)
     }
 
     // -- llmgrep-backed search --
@@ -172,35 +322,102 @@ impl SearchModule {
         };
 
         result
-            .map(|matches| matches.into_iter().map(llmgrep_match_to_symbol).collect())
-            .map_err(|e| ForgeError::DatabaseError(format!("llmgrep search failed: {}", e)))
+            .map(|matches| {
+                matches
+                    .into_iter()
+                    .map(|m| llmgrep_match_to_symbol(m, &self.store.codebase_path))
+                    .collect()
+            })
+            .map_err(ForgeError::from)
+    }
+
+    /// Pairs each symbol with the keyword matches in its source line.
+    async fn with_spans(&self, symbols: Vec<Symbol>, keywords: &[&str]) -> Vec<SearchHit> {
+        let mut sources: std::collections::HashMap<PathBuf, Option<String>> =
+            std::collections::HashMap::new();
+        let mut hits = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let path = self.store.codebase_path.join(&symbol.location.file_path);
+            if !sources.contains_key(&path) {
+                let content = tokio::fs::read_to_string(&path).await.ok();
+                sources.insert(path.clone(), content);
+            }
+            let line = sources[&path]
+                .as_deref()
+                .and_then(|content| {
+                    content
+                        .lines()
+                        .nth(symbol.location.line_number.checked_sub(1)?)
+                })
+                .unwrap_or_default();
+            hits.push(SearchHit {
+                spans: match_spans(line, keywords, &symbol.name),
+                symbol,
+            });
+        }
+        hits
     }
 
     // -- File-based fallback search --
 
-    async fn pattern_search_via_files(&self, pattern: &str) -> ForgeResult<Vec<Symbol>> {
+    pub(crate) async fn pattern_search_via_files(&self, pattern: &str) -> ForgeResult<Vec<Symbol>> {
+        self.scan_files(pattern, SearchOpts::default()).await
+    }
+
+    /// Matches `pattern` line by line against every source file, after
+    /// blanking whatever `opts` asks to ignore.
+    async fn scan_files(&self, pattern: &str, opts: SearchOpts) -> ForgeResult<Vec<Symbol>> {
+        use crate::treesitter::{strip, CfgExtractor};
         use regex::Regex;
 
         let regex = Regex::new(pattern)
-            .map_err(|e| ForgeError::DatabaseError(format!("Invalid regex pattern: {}", e)))?;
+            .map_err(|e| ForgeError::InvalidQuery(format!("Invalid regex pattern: {}", e)))?;
 
         let mut results = Vec::new();
         let mut files = Vec::new();
-        collect_source_files(&self.store.codebase_path, &mut files).await;
+        collect_source_files(
+            &self.store.codebase_path,
+            self.store.walk_options(),
+            &mut files,
+        )
+        .await;
 
         for path in files {
             if let Ok(content) = tokio::fs::read_to_string(&path).await {
-                for (line_num, line) in content.lines().enumerate() {
-                    if regex.is_match(line) {
+                let code = CfgExtractor::detect_language(&path)
+                    .filter(|_| opts != SearchOpts::default())
+                    .and_then(|lang| {
+                        strip::blank_non_code(
+                            &content,
+                            lang,
+                            opts.ignore_comments,
+                            opts.ignore_strings,
+                        )
+                    });
+                let searched = code.as_deref().unwrap_or(&content);
+                let source_language = Language::from_path(&path).unwrap_or(Language::Rust);
+                for (line_num, (line, code_line)) in
+                    content.lines().zip(searched.lines()).enumerate()
+                {
+                    if regex.is_match(code_line) {
                         let symbol_name = extract_symbol_from_line(line);
                         let relative_path = path
                             .strip_prefix(&self.store.codebase_path)
                             .unwrap_or(&path);
+                        let visibility =
+                            Visibility::from_declaration(line, &symbol_name, &source_language);
+                        let kind = kind_from_line(line);
                         results.push(Symbol {
-                            id: SymbolId(0),
+                            id: scanned_symbol_id(
+                                &content,
+                                line_num,
+                                &symbol_name,
+                                kind,
+                                relative_path,
+                            ),
                             name: Arc::from(symbol_name.clone()),
                             fully_qualified_name: Arc::from(symbol_name),
-                            kind: SymbolKind::Function,
+                            kind,
                             language: Language::Rust,
                             location: Location {
                                 file_path: relative_path.to_path_buf(),
@@ -209,6 +426,8 @@ impl SearchModule {
                                 line_number: line_num + 1,
                             },
                             parent_id: None,
+                            visibility,
+                            body_hash: None,
                             metadata: serde_json::Value::Null,
                         });
                     }
@@ -219,13 +438,8 @@ impl SearchModule {
         Ok(results)
     }
 
-    async fn semantic_search_via_files(&self, query: &str) -> ForgeResult<Vec<Symbol>> {
-        let keywords: Vec<&str> = query
-            .split_whitespace()
-            .filter(|w| w.len() >= 3)
-            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
-            .filter(|w| !w.is_empty())
-            .collect();
+    async fn semantic_search_via_files(&self, query: &str) -> ForgeResult<Vec<SearchHit>> {
+        let keywords = query_keywords(query);
 
         if keywords.is_empty() {
             return Ok(Vec::new());
@@ -233,12 +447,18 @@ impl SearchModule {
 
         let mut results = Vec::new();
         let mut files = Vec::new();
-        collect_source_files(&self.store.codebase_path, &mut files).await;
+        collect_source_files(
+            &self.store.codebase_path,
+            self.store.walk_options(),
+            &mut files,
+        )
+        .await;
 
         for path in files {
             let Ok(content) = tokio::fs::read_to_string(&path).await else {
                 continue;
             };
+            let source_language = Language::from_path(&path).unwrap_or(Language::Rust);
             for (line_num, line) in content.lines().enumerate() {
                 let name = extract_symbol_from_line(line);
                 if name.is_empty() || name == "fn" {
@@ -253,37 +473,151 @@ impl SearchModule {
                     let relative_path = path
                         .strip_prefix(&self.store.codebase_path)
                         .unwrap_or(&path);
-                    results.push(Symbol {
-                        id: SymbolId(0),
-                        name: Arc::from(name.clone()),
-                        fully_qualified_name: Arc::from(name.clone()),
-                        kind: if line.contains("struct ") {
-                            SymbolKind::Struct
-                        } else {
-                            SymbolKind::Function
-                        },
-                        language: Language::Rust,
-                        location: Location {
-                            file_path: relative_path.to_path_buf(),
-                            byte_start: 0,
-                            byte_end: line.len() as u32,
-                            line_number: line_num + 1,
+                    let kind = kind_from_line(line);
+                    results.push(SearchHit {
+                        spans: match_spans(line, &keywords, &name),
+                        symbol: Symbol {
+                            id: scanned_symbol_id(&content, line_num, &name, kind, relative_path),
+                            name: Arc::from(name.clone()),
+                            fully_qualified_name: Arc::from(name.clone()),
+                            kind,
+                            language: Language::Rust,
+                            location: Location {
+                                file_path: relative_path.to_path_buf(),
+                                byte_start: 0,
+                                byte_end: line.len() as u32,
+                                line_number: line_num + 1,
+                            },
+                            parent_id: None,
+                            visibility: Visibility::from_declaration(line, &name, &source_language),
+                            body_hash: None,
+                            metadata: serde_json::Value::Null,
                         },
-                        parent_id: None,
-                        metadata: serde_json::Value::Null,
                     });
                 }
             }
         }
 
         let mut seen = std::collections::HashSet::new();
-        results.retain(|s| seen.insert(s.name.clone()));
+        results.retain(|hit| seen.insert(hit.symbol.name.clone()));
 
         Ok(results)
     }
 }
 
-async fn collect_source_files(dir: &std::path::Path, files: &mut Vec<PathBuf>) {
+/// Id of the symbol declared on line `line_index` of `content`, found by
+/// scanning lines.
+///
+/// Lines carry no enclosing impl or module, so a name declared more than
+/// once in a file with the same kind is told apart by how many of those
+/// declarations come before it; the first keeps the plain id.
+fn scanned_symbol_id(
+    content: &str,
+    line_index: usize,
+    name: &str,
+    kind: SymbolKind,
+    relative_path: &Path,
+) -> SymbolId {
+    let earlier = content
+        .lines()
+        .take(line_index)
+        .filter(|line| kind_from_line(line) == kind && extract_symbol_from_line(line) == name)
+        .count();
+    match earlier {
+        0 => SymbolId::stable(name, kind, relative_path),
+        n => SymbolId::stable(&format!("{}#{}", name, n), kind, relative_path),
+    }
+}
+
+/// Ids of reference sites given as (target, file) pairs in file order.
+///
+/// Each site is numbered among the references to the same target in its
+/// file, so every site gets its own id and none collides with the id of
+/// the symbol it references.
+fn reference_site_ids<'a>(
+    root: &Path,
+    sites: impl IntoIterator<Item = (&'a str, &'a Path)>,
+) -> Vec<SymbolId> {
+    let mut occurrences: std::collections::HashMap<(&Path, &str), usize> =
+        std::collections::HashMap::new();
+    sites
+        .into_iter()
+        .map(|(target, file)| {
+            let occurrence = occurrences.entry((file, target)).or_default();
+            let name = format!("{}\0ref\0{}", target, occurrence);
+            *occurrence += 1;
+            SymbolId::stable(&name, SymbolKind::Function, relative_to(root, file))
+        })
+        .collect()
+}
+
+/// Words of a semantic query worth matching: at least three characters,
+/// with surrounding punctuation trimmed.
+fn query_keywords(query: &str) -> Vec<&str> {
+    query
+        .split_whitespace()
+        .filter(|w| w.len() >= 3)
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Finds every case-insensitive occurrence of `keywords` in `line`, in
+/// line order. A keyword absent from the line but containing `name` is
+/// spanned at the occurrences of `name`.
+fn match_spans(line: &str, keywords: &[&str], name: &str) -> Vec<MatchSpan> {
+    // ASCII lowercasing keeps byte offsets valid in the original line
+    let haystack = line.to_ascii_lowercase();
+    let name = name.to_ascii_lowercase();
+    let mut spans = Vec::new();
+    for keyword in keywords {
+        let lowered = keyword.to_ascii_lowercase();
+        let mut found: Vec<MatchSpan> = haystack
+            .match_indices(&lowered)
+            .map(|(start, m)| MatchSpan {
+                keyword: keyword.to_string(),
+                byte_start: start,
+                byte_end: start + m.len(),
+            })
+            .collect();
+        if found.is_empty() && !name.is_empty() && lowered.contains(&name) {
+            found = haystack
+                .match_indices(&name)
+                .map(|(start, m)| MatchSpan {
+                    keyword: keyword.to_string(),
+                    byte_start: start,
+                    byte_end: start + m.len(),
+                })
+                .collect();
+        }
+        spans.extend(found);
+    }
+    spans.sort_by_key(|span| (span.byte_start, span.byte_end));
+    spans.dedup_by_key(|span| (span.byte_start, span.byte_end));
+    spans
+}
+
+/// Recursively collects source files under `dir`, skipping build and tool directories.
+///
+/// Symlinks are followed only if `options` says so; each directory is
+/// walked at most once. Paths ignored by `.gitignore` or excluded by
+/// `options` are skipped. Returns the files skipped for exceeding
+/// [`WalkOptions::max_file_bytes`].
+pub(crate) async fn collect_source_files(
+    dir: &std::path::Path,
+    options: &WalkOptions,
+    files: &mut Vec<PathBuf>,
+) -> Vec<PathBuf> {
+    let mut walk = DirWalk::new(dir, options);
+    collect_source_files_in(dir, &mut walk, files).await;
+    walk.oversized().to_vec()
+}
+
+async fn collect_source_files_in(
+    dir: &std::path::Path,
+    walk: &mut DirWalk,
+    files: &mut Vec<PathBuf>,
+) {
     let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
         return;
     };
@@ -298,24 +632,21 @@ async fn collect_source_files(dir: &std::path::Path, files: &mut Vec<PathBuf>) {
                     continue;
                 }
             }
-            Box::pin(collect_source_files(&path, files)).await;
-        } else if path.is_file()
-            && path
-                .extension()
-                .map(|e| {
-                    matches!(
-                        e.to_str(),
-                        Some("rs" | "py" | "ts" | "js" | "go" | "java" | "c" | "cpp")
-                    )
-                })
-                .unwrap_or(false)
-        {
+            if walk.enter_dir(&path) {
+                Box::pin(collect_source_files_in(&path, walk, files)).await;
+            }
+        } else if walk.is_file(&path) && crate::walk::is_supported_source(&path).is_some() {
             files.push(path);
         }
     }
 }
 
-fn llmgrep_match_to_symbol(m: llmgrep::output::SymbolMatch) -> Symbol {
+/// `path` relative to the codebase `root`, or as given if outside it.
+fn relative_to<'a>(root: &Path, path: &'a Path) -> &'a Path {
+    path.strip_prefix(root).unwrap_or(path)
+}
+
+fn llmgrep_match_to_symbol(m: llmgrep::output::SymbolMatch, root: &Path) -> Symbol {
     let kind = map_llmgrep_kind(&m.kind);
     let language = m
         .language
@@ -324,19 +655,23 @@ fn llmgrep_match_to_symbol(m: llmgrep::output::SymbolMatch) -> Symbol {
         .unwrap_or(Language::Unknown("unknown".to_string()));
     let fqn: Arc<str> = Arc::from(m.fqn.clone().unwrap_or_else(|| m.name.clone()));
 
+    let file_path = PathBuf::from(&m.span.file_path);
+
     Symbol {
-        id: SymbolId(0),
+        id: SymbolId::stable(&fqn, kind, relative_to(root, &file_path)),
         name: Arc::from(m.name),
         fully_qualified_name: fqn,
         kind,
         language,
         location: Location {
-            file_path: PathBuf::from(&m.span.file_path),
+            file_path,
             byte_start: m.span.byte_start as u32,
             byte_end: m.span.byte_end as u32,
             line_number: m.span.start_line as usize,
         },
         parent_id: None,
+        visibility: Visibility::Unknown,
+        body_hash: None,
         metadata: serde_json::Value::Null,
     }
 }
@@ -371,6 +706,10 @@ fn map_llmgrep_language(lang: &str) -> Language {
 }
 
 fn extract_symbol_from_line(line: &str) -> String {
+    if let Some((name, _)) = declaration_from_line(line) {
+        return name;
+    }
+
     let line = line.trim();
 
     if let Some(fn_pos) = line.find("fn ") {
@@ -383,6 +722,113 @@ fn extract_symbol_from_line(line: &str) -> String {
     line.split_whitespace().next().unwrap_or("").to_string()
 }
 
+/// Kind of the symbol declared on `line`, or `Function` if none is.
+fn kind_from_line(line: &str) -> SymbolKind {
+    declaration_from_line(line).map_or(SymbolKind::Function, |(_, kind)| kind)
+}
+
+/// Recognizes a Rust item declared on `line`, returning its name and kind.
+///
+/// Visibility such as `pub(crate)` and qualifiers such as `async` or
+/// `unsafe` are skipped, and generic parameters are not part of the name.
+/// An `impl` block is named after the type it implements.
+pub(crate) fn declaration_from_line(line: &str) -> Option<(String, SymbolKind)> {
+    let mut rest = line.trim_start();
+    if let Some(after) = strip_keyword(rest, "pub") {
+        rest = match after.strip_prefix('(') {
+            Some(scope) => scope.split_once(')')?.1.trim_start(),
+            None => after,
+        };
+    }
+    loop {
+        if let Some(after) = ["default", "async", "unsafe"]
+            .iter()
+            .find_map(|kw| strip_keyword(rest, kw))
+        {
+            rest = after;
+        } else if let Some(after) = strip_keyword(rest, "extern") {
+            // Skip the ABI string, as in `extern "C" fn`
+            rest = match after.strip_prefix('"') {
+                Some(abi) => abi.split_once('"')?.1.trim_start(),
+                None => after,
+            };
+        } else if let Some(after) = strip_keyword(rest, "const").filter(|after| {
+            ["fn", "async", "unsafe", "extern"]
+                .iter()
+                .any(|kw| strip_keyword(after, kw).is_some())
+        }) {
+            rest = after;
+        } else {
+            break;
+        }
+    }
+
+    let (kind, rest) = [
+        ("fn", SymbolKind::Function),
+        ("struct", SymbolKind::Struct),
+        ("enum", SymbolKind::Enum),
+        ("trait", SymbolKind::Trait),
+        ("impl", SymbolKind::Impl),
+        ("const", SymbolKind::Constant),
+        ("static", SymbolKind::Static),
+        ("type", SymbolKind::TypeAlias),
+        ("mod", SymbolKind::Module),
+    ]
+    .into_iter()
+    .find_map(|(kw, kind)| strip_keyword(rest, kw).map(|rest| (kind, rest)))?;
+
+    let name = match kind {
+        SymbolKind::Impl => impl_target(rest),
+        SymbolKind::Static => leading_ident(strip_keyword(rest, "mut").unwrap_or(rest)),
+        _ => leading_ident(rest),
+    };
+    (!name.is_empty()).then(|| (name.to_string(), kind))
+}
+
+/// Returns the text after `keyword` if `s` starts with it as a whole word.
+fn strip_keyword<'a>(s: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = s.strip_prefix(keyword)?;
+    match rest.chars().next() {
+        Some(c) if c.is_alphanumeric() || c == '_' => None,
+        _ => Some(rest.trim_start()),
+    }
+}
+
+fn leading_ident(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Name of the type implemented by an `impl` header, given the text after
+/// the `impl` keyword: `Foo` for both `<T> Foo<T>` and `Display for Foo`.
+fn impl_target(header: &str) -> &str {
+    let mut rest = header;
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        for (i, c) in rest.char_indices() {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                rest = rest[i + 1..].trim_start();
+                break;
+            }
+        }
+    }
+    if let Some((_, target)) = rest.split_once(" for ") {
+        rest = target.trim_start();
+    }
+    let path_end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .unwrap_or(rest.len());
+    let path = &rest[..path_end];
+    path.rsplit("::").next().unwrap_or(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -399,6 +845,109 @@ mod tests {
         let _search = SearchModule::new(Arc::clone(&store));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_collect_source_files_terminates_on_symlink_cycle() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let src = root.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "fn a() {}\n").unwrap();
+        // src/loop -> root forms a cycle; linked -> src aliases a subtree
+        std::os::unix::fs::symlink(root, src.join("loop")).unwrap();
+        std::os::unix::fs::symlink(&src, root.join("linked")).unwrap();
+
+        for follow in [false, true] {
+            let mut files = Vec::new();
+            collect_source_files(
+                root,
+                &WalkOptions::default().follow_symlinks(follow),
+                &mut files,
+            )
+            .await;
+            let names: Vec<_> = files.iter().filter_map(|f| f.file_name()).collect();
+            assert_eq!(names, vec!["lib.rs"], "follow_symlinks = {}", follow);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_scan_skips_ignored_and_excluded_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for dir in ["src", "generated", "vendor/dep"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in ["src/lib.rs", "generated/api.rs", "vendor/dep/lib.rs"] {
+            std::fs::write(root.join(file), "fn needle() {}\n").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+
+        let store = Arc::new(
+            UnifiedGraphStore::open(root, BackendKind::SQLite)
+                .await
+                .unwrap()
+                .with_walk_options(WalkOptions::default().exclude(["vendor/"])),
+        );
+        let search = SearchModule::new(store);
+
+        let hits = search.pattern_search_via_files("fn needle").await.unwrap();
+        let files: Vec<_> = hits.iter().map(|s| s.location.file_path.clone()).collect();
+        assert_eq!(files, vec![PathBuf::from("src/lib.rs")]);
+    }
+
+    #[tokio::test]
+    async fn test_file_scan_symbol_ids_are_stable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "fn other() {}\nfn needle() {}\n").unwrap();
+        std::fs::write(src.join("extra.rs"), "fn unrelated() {}\n").unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let search = SearchModule::new(store);
+        let needle_id = || async {
+            let hits = search.pattern_search_via_files("fn needle").await.unwrap();
+            assert_eq!(hits.len(), 1);
+            hits[0].id
+        };
+
+        let id = needle_id().await;
+        assert_ne!(id, SymbolId(0));
+        assert_eq!(needle_id().await, id);
+
+        // Moving the function within its file keeps the id
+        std::fs::write(
+            src.join("lib.rs"),
+            "fn needle() {}\n\n// moved up\nfn other() {}\n",
+        )
+        .unwrap();
+        assert_eq!(needle_id().await, id);
+
+        // Moving it to another module does not
+        std::fs::write(src.join("lib.rs"), "fn other() {}\n").unwrap();
+        std::fs::write(src.join("moved.rs"), "fn needle() {}\n").unwrap();
+        assert_ne!(needle_id().await, id);
+
+        // Same-named methods in one file get distinct ids
+        std::fs::write(
+            src.join("lib.rs"),
+            "impl A {\n    fn new() -> Self { A }\n}\nimpl B {\n    fn new() -> Self { B }\n}\n",
+        )
+        .unwrap();
+        let ids: Vec<_> = search
+            .pattern_search_via_files("fn new")
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+    }
+
     #[tokio::test]
     async fn test_pattern_search_empty() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -413,6 +962,76 @@ mod tests {
         assert_eq!(results.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_pattern_search_opts_ignores_comments() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(
+            temp_dir.path().join("lib.rs"),
+            "// TODO fix parse()\nfn run() {\n    let msg = \"parse() failed\";\n    parse();\n}\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(temp_dir.path().join("notes.js"), "// parse()\n")
+            .await
+            .unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open_with_path(
+                temp_dir.path(),
+                temp_dir.path().join("test-graph.db"),
+                BackendKind::SQLite,
+            )
+            .await
+            .unwrap(),
+        );
+        let search = SearchModule::new(store);
+        let lines = |results: Vec<Symbol>| {
+            let mut lines: Vec<_> = results
+                .into_iter()
+                .map(|s| (s.location.file_path, s.location.line_number))
+                .collect();
+            lines.sort();
+            lines
+        };
+
+        let raw = search.scan_files(r"parse\(\)", SearchOpts::default()).await;
+        assert_eq!(raw.unwrap().len(), 4);
+
+        let code = search
+            .pattern_search_opts(
+                r"parse\(\)",
+                SearchOpts {
+                    ignore_comments: true,
+                    ..SearchOpts::default()
+                },
+            )
+            .await
+            .unwrap();
+        // No grammar for JavaScript, so its comment still matches
+        assert_eq!(
+            lines(code),
+            vec![
+                (PathBuf::from("lib.rs"), 3),
+                (PathBuf::from("lib.rs"), 4),
+                (PathBuf::from("notes.js"), 1),
+            ]
+        );
+
+        let code = search
+            .pattern_search_opts(
+                r"parse\(\)",
+                SearchOpts {
+                    ignore_comments: true,
+                    ignore_strings: true,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            lines(code),
+            vec![(PathBuf::from("lib.rs"), 4), (PathBuf::from("notes.js"), 1)]
+        );
+    }
+
     #[tokio::test]
     async fn test_symbol_by_name_not_found() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -441,6 +1060,194 @@ mod tests {
         assert!(functions.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_symbols_by_kind_uses_stored_kind() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        for (name, kind) in [
+            ("run", SymbolKind::Function),
+            ("Config", SymbolKind::Struct),
+            ("Mode", SymbolKind::Enum),
+            ("MAX", SymbolKind::Constant),
+            ("COUNTER", SymbolKind::Static),
+        ] {
+            store
+                .insert_symbol(&Symbol {
+                    id: SymbolId(0),
+                    name: Arc::from(name),
+                    fully_qualified_name: Arc::from(name),
+                    kind,
+                    language: Language::Rust,
+                    location: Location {
+                        file_path: PathBuf::from("src/lib.rs"),
+                        byte_start: 0,
+                        byte_end: 0,
+                        line_number: 1,
+                    },
+                    parent_id: None,
+                    visibility: Visibility::default(),
+                    body_hash: None,
+                    metadata: serde_json::Value::Null,
+                })
+                .await
+                .unwrap();
+        }
+        let search = SearchModule::new(store);
+
+        for (kind, name) in [
+            (SymbolKind::Function, "run"),
+            (SymbolKind::Struct, "Config"),
+            (SymbolKind::Enum, "Mode"),
+            (SymbolKind::Constant, "MAX"),
+            (SymbolKind::Static, "COUNTER"),
+        ] {
+            let found = search.symbols_by_kind(kind).await.unwrap();
+            let names: Vec<_> = found.iter().map(|s| s.name.as_ref()).collect();
+            assert_eq!(names, vec![name], "{:?}", kind);
+        }
+    }
+
+    #[cfg(feature = "semantic")]
+    #[tokio::test]
+    async fn test_semantic_search_ranked_uses_names_and_docs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(
+            src_dir.join("lib.rs"),
+            "/// Reads settings from a TOML file on disk.\n\
+             fn load() {}\n\
+             fn render_widget() {}\n\
+             fn parse_config() {}\n",
+        )
+        .unwrap();
+
+        let store = Arc::new(
+            UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        crate::graph::GraphModule::new(Arc::clone(&store))
+            .index()
+            .await
+            .unwrap();
+        let search = SearchModule::new(store);
+
+        let hits = search
+            .semantic_search_ranked("toml settings file", 2)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].0.name.as_ref(), "load");
+        assert!(hits[0].1 > hits[1].1);
+        assert_eq!(hits[0].0.kind, SymbolKind::Function);
+
+        let hits = search
+            .semantic_search_ranked("parseConfig", 1)
+            .await
+            .unwrap();
+        assert_eq!(hits[0].0.name.as_ref(), "parse_config");
+    }
+
+    #[test]
+    fn test_reference_site_ids_are_distinct() {
+        let root = Path::new("/repo");
+        let lib = Path::new("/repo/src/lib.rs");
+        let main = Path::new("/repo/src/main.rs");
+        let sites = [
+            ("helper", lib),
+            ("helper", lib),
+            ("helper", main),
+            ("other", lib),
+        ];
+
+        let ids = reference_site_ids(root, sites);
+        let distinct: std::collections::HashSet<SymbolId> = ids.iter().copied().collect();
+        assert_eq!(distinct.len(), sites.len());
+        let helper = SymbolId::stable("helper", SymbolKind::Function, Path::new("src/lib.rs"));
+        assert!(!distinct.contains(&helper));
+        assert_eq!(reference_site_ids(root, sites), ids);
+    }
+
+    #[test]
+    fn test_match_spans_finds_every_keyword_occurrence() {
+        let line = "pub fn parse_config(config: &Config) -> Config {";
+        let spans = match_spans(line, &["config", "parse"], "parse_config");
+        let found: Vec<(&str, &str)> = spans
+            .iter()
+            .map(|s| (s.keyword.as_str(), &line[s.byte_start..s.byte_end]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("parse", "parse"),
+                ("config", "config"),
+                ("config", "config"),
+                ("config", "Config"),
+                ("config", "Config"),
+            ]
+        );
+        assert!(spans.windows(2).all(|w| w[0].byte_start < w[1].byte_start));
+
+        // A keyword containing the name is spanned at the name
+        let spans = match_spans("fn add() {}", &["address"], "add");
+        assert_eq!(
+            spans,
+            vec![MatchSpan {
+                keyword: "address".to_string(),
+                byte_start: 3,
+                byte_end: 6,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_detailed_reports_spans() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(
+            src_dir.join("lib.rs"),
+            "fn load_settings() {}\nfn render() {}\n",
+        )
+        .unwrap();
+        let store = Arc::new(
+            UnifiedGraphStore::open(temp_dir.path(), BackendKind::SQLite)
+                .await
+                .unwrap(),
+        );
+        let search = SearchModule::new(store);
+
+        let hits = search
+            .semantic_search_via_files("load settings")
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].symbol.name.as_ref(), "load_settings");
+        let spans: Vec<(&str, usize, usize)> = hits[0]
+            .spans
+            .iter()
+            .map(|s| (s.keyword.as_str(), s.byte_start, s.byte_end))
+            .collect();
+        assert_eq!(spans, vec![("load", 3, 7), ("settings", 8, 16)]);
+
+        let symbols = search.semantic_search("load settings").await.unwrap();
+        let detailed = search
+            .semantic_search_detailed("load settings")
+            .await
+            .unwrap();
+        assert_eq!(symbols.len(), detailed.len());
+        assert!(search
+            .semantic_search_detailed(" ")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_extract_symbol_from_line() {
         assert_eq!(
@@ -448,5 +1255,78 @@ mod tests {
             "add"
         );
         assert_eq!(extract_symbol_from_line("fn hello() {"), "hello");
+        assert_eq!(
+            extract_symbol_from_line("pub(crate) struct Pair<T> {"),
+            "Pair"
+        );
+    }
+
+    #[test]
+    fn test_declaration_from_line_kinds() {
+        let cases = [
+            ("fn plain() {", "plain", SymbolKind::Function),
+            (
+                "pub async fn fetch<T: Send>(t: T) {",
+                "fetch",
+                SymbolKind::Function,
+            ),
+            (
+                "pub(crate) const unsafe fn raw() {}",
+                "raw",
+                SymbolKind::Function,
+            ),
+            (
+                "pub extern \"C\" fn exported() {}",
+                "exported",
+                SymbolKind::Function,
+            ),
+            ("pub struct Config<'a, T> {", "Config", SymbolKind::Struct),
+            ("struct Unit;", "Unit", SymbolKind::Struct),
+            ("pub(crate) enum Mode {", "Mode", SymbolKind::Enum),
+            ("pub trait Store<K>: Send {", "Store", SymbolKind::Trait),
+            ("pub(super) unsafe trait Raw {", "Raw", SymbolKind::Trait),
+            ("impl<T: Clone> Config<'_, T> {", "Config", SymbolKind::Impl),
+            ("impl fmt::Display for Mode {", "Mode", SymbolKind::Impl),
+            (
+                "impl<K> Store<K> for crate::db::Disk {",
+                "Disk",
+                SymbolKind::Impl,
+            ),
+            ("pub const MAX: usize = 8;", "MAX", SymbolKind::Constant),
+            (
+                "static mut COUNTER: u32 = 0;",
+                "COUNTER",
+                SymbolKind::Static,
+            ),
+            (
+                "pub(crate) static NAME: &str = \"x\";",
+                "NAME",
+                SymbolKind::Static,
+            ),
+            (
+                "pub type Result<T> = std::result::Result<T, E>;",
+                "Result",
+                SymbolKind::TypeAlias,
+            ),
+            ("pub(in crate::a) mod inner {", "inner", SymbolKind::Module),
+            ("mod tests;", "tests", SymbolKind::Module),
+        ];
+        for (line, name, kind) in cases {
+            assert_eq!(
+                declaration_from_line(line),
+                Some((name.to_string(), kind)),
+                "{}",
+                line
+            );
+        }
+
+        for line in [
+            "let x = helper();",
+            "// fn commented()",
+            "public_fn();",
+            "typed.len()",
+        ] {
+            assert_eq!(declaration_from_line(line), None, "{}", line);
+        }
     }
 }
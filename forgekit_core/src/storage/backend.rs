@@ -0,0 +1,150 @@
+//! The storage operations behind [`UnifiedGraphStore`](super::UnifiedGraphStore).
+//!
+//! Each [`BackendKind`] selects a [`GraphBackend`] implementation:
+//! [`SQLite`](BackendKind::SQLite) and [`NativeV3`](BackendKind::NativeV3)
+//! write to the graph database, [`Memory`](BackendKind::Memory) keeps
+//! everything in process, and [`Custom`](BackendKind::Custom) looks up a
+//! factory added with [`register_backend`].
+//!
+//! Magellan indexing and the graph queries built on it read the SQLite
+//! database at the store's `db_path` whatever the backend; the backend
+//! holds what is written through the store itself.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use crate::error::{ForgeError, Result};
+use crate::types::{Reference, Symbol, SymbolId};
+
+use super::memory::MemoryBackend;
+use super::native::NativeV3Backend;
+use super::sqlite::SqliteBackend;
+use super::BackendKind;
+
+/// Storage for the symbols and references written through a store.
+///
+/// Implementations are shared between threads and may be cloned with the
+/// store, so interior mutability is up to them.
+pub trait GraphBackend: Send + Sync + std::fmt::Debug {
+    /// Inserts one symbol, returning its id.
+    fn insert_symbol(&self, symbol: &Symbol) -> Result<SymbolId> {
+        Ok(self.insert_symbols(std::slice::from_ref(symbol))?[0])
+    }
+
+    /// Inserts `symbols`, returning their ids in order. Either every
+    /// symbol is inserted or none is.
+    fn insert_symbols(&self, symbols: &[Symbol]) -> Result<Vec<SymbolId>>;
+
+    /// Finds up to 50 symbols whose name contains `name`.
+    fn query_symbols(&self, name: &str) -> Result<Vec<Symbol>>;
+
+    /// Returns true if a symbol with `id` is stored.
+    fn symbol_exists(&self, id: SymbolId) -> Result<bool>;
+
+    /// Lists up to `limit` symbols ordered by id, skipping the first
+    /// `offset`. The flag is true if more symbols follow this page.
+    fn list_symbols(&self, offset: usize, limit: usize) -> Result<(Vec<Symbol>, bool)>;
+
+    /// Counts the stored symbols.
+    fn symbol_count(&self) -> Result<usize>;
+
+    /// Inserts `references`. Either every reference is inserted or none
+    /// is. Backends that do not store references ignore them.
+    fn insert_references(&self, references: &[Reference]) -> Result<()>;
+
+    /// Removes every stored reference.
+    fn clear_references(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Finds the references to the symbol with `id`.
+    fn query_references(&self, id: SymbolId) -> Result<Vec<Reference>>;
+
+    /// Finds the references to the symbol named `name`.
+    fn query_references_by_name(&self, _name: &str) -> Result<Vec<Reference>> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the `(from, to)` symbol pairs of the stored references
+    /// whose source is known.
+    fn reference_edges(&self) -> Vec<(SymbolId, SymbolId)> {
+        Vec::new()
+    }
+
+    /// Sets the minimum time between writes of buffered changes.
+    fn set_flush_interval(&mut self, _interval: Duration) {}
+
+    /// Writes buffered changes now.
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Clones the backend for a clone of its store.
+    fn clone_box(&self) -> Box<dyn GraphBackend>;
+}
+
+/// Opens a backend for the codebase at the first path, with its graph
+/// database at the second.
+pub type BackendFactory = fn(&Path, &Path) -> Result<Box<dyn GraphBackend>>;
+
+fn registry() -> &'static RwLock<HashMap<&'static str, BackendFactory>> {
+    static BACKENDS: OnceLock<RwLock<HashMap<&'static str, BackendFactory>>> = OnceLock::new();
+    BACKENDS.get_or_init(RwLock::default)
+}
+
+/// Registers `factory` as the backend opened for
+/// [`BackendKind::Custom(name)`](BackendKind::Custom), replacing any
+/// earlier registration under `name`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use forgekit_core::storage::{
+///     create_backend, register_backend, BackendKind, UnifiedGraphStore,
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// register_backend("scratch", |codebase, db| {
+///     create_backend(BackendKind::Memory, codebase, db)
+/// });
+/// let store = UnifiedGraphStore::open("./codebase", BackendKind::Custom("scratch")).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn register_backend(name: &'static str, factory: BackendFactory) {
+    registry()
+        .write()
+        .expect("invariant: backend registry not poisoned")
+        .insert(name, factory);
+}
+
+/// Opens the backend `kind` selects for `codebase_path`, with its graph
+/// database at `db_path`.
+///
+/// Fails with [`ForgeError::BackendNotAvailable`] for a
+/// [`Custom`](BackendKind::Custom) kind that was never registered.
+pub fn create_backend(
+    kind: BackendKind,
+    codebase_path: &Path,
+    db_path: &Path,
+) -> Result<Box<dyn GraphBackend>> {
+    match kind {
+        BackendKind::SQLite => Ok(Box::new(SqliteBackend::new(db_path))),
+        BackendKind::NativeV3 => Ok(Box::new(NativeV3Backend::new(codebase_path, db_path))),
+        BackendKind::Memory => Ok(Box::new(MemoryBackend::default())),
+        BackendKind::Custom(name) => {
+            let factory = registry()
+                .read()
+                .expect("invariant: backend registry not poisoned")
+                .get(name)
+                .copied()
+                .ok_or_else(|| {
+                    ForgeError::BackendNotAvailable(format!("no backend registered as {name}"))
+                })?;
+            factory(codebase_path, db_path)
+        }
+    }
+}
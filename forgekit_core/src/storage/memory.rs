@@ -0,0 +1,126 @@
+//! An in-memory graph backend, for tests and throwaway stores.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::error::{ForgeError, Result};
+use crate::types::{Reference, Symbol, SymbolId};
+
+use super::backend::GraphBackend;
+
+#[derive(Debug, Default)]
+struct Tables {
+    /// In id order; ids start at 1.
+    symbols: Vec<Symbol>,
+    references: Vec<Reference>,
+}
+
+/// Symbols and references held in process and lost when the last clone
+/// of the store is dropped. Clones of the store share them.
+#[derive(Clone, Debug, Default)]
+pub(super) struct MemoryBackend {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl MemoryBackend {
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables
+            .lock()
+            .expect("invariant: memory tables mutex not poisoned")
+    }
+}
+
+impl GraphBackend for MemoryBackend {
+    /// Symbols without a name are rejected, as by the graph database.
+    fn insert_symbols(&self, symbols: &[Symbol]) -> Result<Vec<SymbolId>> {
+        if symbols.iter().any(|s| s.name.is_empty()) {
+            return Err(ForgeError::DatabaseError(
+                "Insert batch failed: symbol has no name".to_string(),
+            ));
+        }
+        let mut tables = self.tables();
+        let mut ids = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let id = SymbolId(tables.symbols.len() as i64 + 1);
+            tables.symbols.push(Symbol {
+                id,
+                ..symbol.clone()
+            });
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    fn query_symbols(&self, name: &str) -> Result<Vec<Symbol>> {
+        Ok(self
+            .tables()
+            .symbols
+            .iter()
+            .filter(|s| s.name.contains(name))
+            .take(50)
+            .cloned()
+            .collect())
+    }
+
+    fn symbol_exists(&self, id: SymbolId) -> Result<bool> {
+        Ok(self.tables().symbols.iter().any(|s| s.id == id))
+    }
+
+    fn list_symbols(&self, offset: usize, limit: usize) -> Result<(Vec<Symbol>, bool)> {
+        let tables = self.tables();
+        let page = tables
+            .symbols
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect();
+        Ok((page, tables.symbols.len() > offset.saturating_add(limit)))
+    }
+
+    fn symbol_count(&self) -> Result<usize> {
+        Ok(self.tables().symbols.len())
+    }
+
+    fn insert_references(&self, references: &[Reference]) -> Result<()> {
+        self.tables().references.extend_from_slice(references);
+        Ok(())
+    }
+
+    fn clear_references(&self) -> Result<()> {
+        self.tables().references.clear();
+        Ok(())
+    }
+
+    fn query_references(&self, id: SymbolId) -> Result<Vec<Reference>> {
+        Ok(self
+            .tables()
+            .references
+            .iter()
+            .filter(|r| r.to == id)
+            .cloned()
+            .collect())
+    }
+
+    fn query_references_by_name(&self, name: &str) -> Result<Vec<Reference>> {
+        Ok(self
+            .tables()
+            .references
+            .iter()
+            .filter(|r| r.to_name.as_deref() == Some(name))
+            .cloned()
+            .collect())
+    }
+
+    fn reference_edges(&self) -> Vec<(SymbolId, SymbolId)> {
+        self.tables()
+            .references
+            .iter()
+            .filter(|r| r.from.0 != 0 && r.to.0 != 0)
+            .map(|r| (r.from, r.to))
+            .collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn GraphBackend> {
+        Box::new(self.clone())
+    }
+}
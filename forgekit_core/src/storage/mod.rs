@@ -13,6 +13,11 @@
 //! | Startup Time | Fast | Faster |
 //! | Tool Compatibility | magellan, llmgrep, mirage, splice (current) | Updated tools |
 //!
+//! [`BackendKind::Memory`] keeps the symbols and references written through
+//! the store in process, for tests. Other backends implement
+//! [`GraphBackend`] and are added with [`register_backend`], then opened
+//! as [`BackendKind::Custom`].
+//!
 //! # Examples
 //!
 //! ```rust,no_run
@@ -29,7 +34,12 @@
 //! # }
 //! ```
 
+mod backend;
+mod memory;
+mod native;
 mod ops;
+mod references;
+mod sqlite;
 mod store;
 #[cfg(test)]
 mod tests;
@@ -38,6 +48,9 @@ pub use sqlitegraph::backend::{EdgeSpec, NodeSpec};
 pub use sqlitegraph::config::{open_graph, BackendKind as SqliteGraphBackendKind, GraphConfig};
 pub use sqlitegraph::graph::{GraphEntity, SqliteGraph};
 
+pub use backend::{create_backend, register_backend, BackendFactory, GraphBackend};
+pub use ops::SYMBOL_PAGE_SIZE;
+pub use references::DEFAULT_REFERENCE_FLUSH_INTERVAL;
 pub use store::UnifiedGraphStore;
 
 use std::path::{Path, PathBuf};
@@ -71,6 +84,17 @@ fn fallback_db_path(project_root: &Path) -> PathBuf {
         .join(format!("{}.db", stem))
 }
 
+/// Opens the SQLite database at `db_path` for reading only.
+///
+/// Takes no write locks and writes nothing to the database, so it works on
+/// read-only files and filesystems. Fails if the database does not exist.
+pub(crate) fn open_read_only(db_path: &Path) -> rusqlite::Result<rusqlite::Connection> {
+    rusqlite::Connection::open_with_flags(
+        db_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+}
+
 fn lookup_registry(project_root: &Path) -> Option<PathBuf> {
     let home = std::env::var("HOME").ok()?;
     let registry_path = PathBuf::from(&home)
@@ -141,11 +165,17 @@ fn parse_toml_string(s: &str) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Selects the [`GraphBackend`] a store opens.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum BackendKind {
     #[default]
     SQLite,
     NativeV3,
+    /// Held in process; the graph database is still SQLite
+    Memory,
+    /// Added with [`register_backend`] under this name; the graph
+    /// database is still SQLite
+    Custom(&'static str),
 }
 
 impl std::fmt::Display for BackendKind {
@@ -153,6 +183,8 @@ impl std::fmt::Display for BackendKind {
         match self {
             Self::SQLite => write!(f, "SQLite"),
             Self::NativeV3 => write!(f, "NativeV3"),
+            Self::Memory => write!(f, "Memory"),
+            Self::Custom(name) => write!(f, "{}", name),
         }
     }
 }
@@ -161,22 +193,30 @@ impl BackendKind {
     #[cfg(test)]
     fn to_sqlitegraph_kind(self) -> SqliteGraphBackendKind {
         match self {
-            Self::SQLite => SqliteGraphBackendKind::SQLite,
             Self::NativeV3 => SqliteGraphBackendKind::Native,
+            _ => SqliteGraphBackendKind::SQLite,
+        }
+    }
+
+    /// Configuration for opening the graph database.
+    pub(crate) fn graph_config(self) -> GraphConfig {
+        match self {
+            Self::NativeV3 => GraphConfig::native(),
+            _ => GraphConfig::sqlite(),
         }
     }
 
     pub fn file_extension(&self) -> &str {
         match self {
-            Self::SQLite => "db",
             Self::NativeV3 => "v3",
+            _ => "db",
         }
     }
 
     pub fn default_filename(&self) -> &str {
         match self {
-            Self::SQLite => "graph.db",
             Self::NativeV3 => "graph.v3",
+            _ => "graph.db",
         }
     }
 }
@@ -0,0 +1,264 @@
+//! The Native V3 graph backend.
+//!
+//! Symbols are sqlitegraph nodes like on SQLite. References are kept in
+//! memory and persisted to the [sidecar](super::references).
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::Duration;
+
+use sqlitegraph::backend::NodeSpec;
+use sqlitegraph::config::GraphConfig;
+
+use crate::error::{ForgeError, Result};
+use crate::types::{Location, Reference, ReferenceKind, Symbol, SymbolId};
+
+use super::backend::GraphBackend;
+use super::references::{ReferenceSidecar, DEFAULT_REFERENCE_FLUSH_INTERVAL};
+use super::sqlite;
+
+#[derive(Clone, Debug)]
+pub(super) struct StoredReference {
+    /// The referencing symbol, when it was known at insert time.
+    pub(super) from_symbol: Option<String>,
+    pub(super) to_symbol: String,
+    pub(super) kind: ReferenceKind,
+    pub(super) file_path: PathBuf,
+    pub(super) line_number: usize,
+}
+
+/// The references of a backend and the sidecar they are saved to.
+#[derive(Debug)]
+struct References {
+    stored: Vec<StoredReference>,
+    /// Where references are persisted between runs.
+    sidecar: ReferenceSidecar,
+}
+
+impl Drop for References {
+    /// Saves references still waiting for a flush once the last clone, or
+    /// the flusher, lets go of them.
+    fn drop(&mut self) {
+        if self.sidecar.is_dirty() {
+            if let Err(e) = self.sidecar.save(&self.stored) {
+                tracing::warn!("Failed to save references on drop: {}", e);
+            }
+        }
+    }
+}
+
+/// Longest the flusher sleeps between checks for changes that are due.
+const FLUSH_POLL: Duration = Duration::from_secs(1);
+
+/// Saves changes left waiting once their flush interval has passed, until
+/// every clone of the backend is gone.
+fn spawn_flusher(references: Weak<Mutex<References>>) {
+    let flusher = std::thread::Builder::new()
+        .name("forge-reference-flush".to_string())
+        .spawn(move || loop {
+            let interval = match references.upgrade() {
+                Some(refs) => refs
+                    .lock()
+                    .expect("invariant: references mutex not poisoned")
+                    .sidecar
+                    .interval(),
+                None => return,
+            };
+            std::thread::sleep(interval.clamp(Duration::from_millis(10), FLUSH_POLL));
+            let Some(refs) = references.upgrade() else {
+                return;
+            };
+            let refs = refs
+                .lock()
+                .expect("invariant: references mutex not poisoned");
+            if let Err(e) = refs.sidecar.flush_if_due(&refs.stored) {
+                tracing::warn!("Failed to flush references: {}", e);
+            }
+        });
+    if let Err(e) = flusher {
+        tracing::warn!("Failed to start the reference flusher: {}", e);
+    }
+}
+
+/// Clones share one set of references and one sidecar, so a reference
+/// inserted through any clone is seen and saved by all of them.
+#[derive(Debug)]
+pub(super) struct NativeV3Backend {
+    codebase_path: PathBuf,
+    db_path: PathBuf,
+    references: Arc<Mutex<References>>,
+}
+
+impl NativeV3Backend {
+    /// Opens the backend, loading the references saved by an earlier run.
+    pub(super) fn new(codebase_path: &Path, db_path: &Path) -> Self {
+        let sidecar = ReferenceSidecar::new(codebase_path, DEFAULT_REFERENCE_FLUSH_INTERVAL);
+        let references = Arc::new(Mutex::new(References {
+            stored: sidecar.load(),
+            sidecar,
+        }));
+        spawn_flusher(Arc::downgrade(&references));
+        Self {
+            codebase_path: codebase_path.to_path_buf(),
+            db_path: db_path.to_path_buf(),
+            references,
+        }
+    }
+
+    fn references(&self) -> MutexGuard<'_, References> {
+        self.references
+            .lock()
+            .expect("invariant: references mutex not poisoned")
+    }
+}
+
+/// A stored reference as returned by queries, which know only its target,
+/// kind and line.
+fn to_reference(stored: &StoredReference, to: SymbolId) -> Reference {
+    Reference {
+        from: SymbolId(0),
+        to,
+        from_name: None,
+        to_name: None,
+        kind: stored.kind,
+        location: Location {
+            file_path: stored.file_path.clone(),
+            byte_start: 0,
+            byte_end: 0,
+            line_number: stored.line_number,
+        },
+    }
+}
+
+impl GraphBackend for NativeV3Backend {
+    fn insert_symbol(&self, symbol: &Symbol) -> Result<SymbolId> {
+        sqlite::insert_node(&self.db_path, &GraphConfig::native(), symbol)
+    }
+
+    /// The native bulk insert makes no atomicity promise, so nodes already
+    /// inserted are deleted again if a later one fails.
+    fn insert_symbols(&self, symbols: &[Symbol]) -> Result<Vec<SymbolId>> {
+        if symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+        let backend = sqlite::open_backend(&self.db_path, &GraphConfig::native())?;
+        let nodes: Vec<NodeSpec> = symbols.iter().map(sqlite::node_spec).collect();
+        let mut ids = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            match backend.insert_node(node) {
+                Ok(id) => ids.push(id),
+                Err(e) => {
+                    for id in ids {
+                        let _ = backend.delete_entity(id);
+                    }
+                    return Err(ForgeError::DatabaseError(format!(
+                        "Insert batch failed: {}",
+                        e
+                    )));
+                }
+            }
+        }
+        Ok(ids.into_iter().map(SymbolId).collect())
+    }
+
+    fn query_symbols(&self, name: &str) -> Result<Vec<Symbol>> {
+        sqlite::query_symbols(&self.db_path, name)
+    }
+
+    fn symbol_exists(&self, id: SymbolId) -> Result<bool> {
+        sqlite::symbol_exists(&self.db_path, id)
+    }
+
+    fn list_symbols(&self, offset: usize, limit: usize) -> Result<(Vec<Symbol>, bool)> {
+        sqlite::list_symbols(&self.db_path, offset, limit)
+    }
+
+    fn symbol_count(&self) -> Result<usize> {
+        sqlite::symbol_count(&self.db_path)
+    }
+
+    /// References to a symbol known only by name are stored under that
+    /// name. If saving them fails, the whole batch is taken back out.
+    fn insert_references(&self, references: &[Reference]) -> Result<()> {
+        if references.is_empty() {
+            return Ok(());
+        }
+        let mut refs = self.references();
+        let before = refs.stored.len();
+        refs.stored
+            .extend(references.iter().map(|reference| StoredReference {
+                from_symbol: (reference.from.0 != 0).then(|| format!("sym_{}", reference.from.0)),
+                to_symbol: match &reference.to_name {
+                    Some(name) if reference.to.0 == 0 => format!("sym_{}", name),
+                    _ => format!("sym_{}", reference.to.0),
+                },
+                kind: reference.kind,
+                file_path: reference.location.file_path.clone(),
+                line_number: reference.location.line_number,
+            }));
+        if let Err(e) = refs.sidecar.changed(&refs.stored) {
+            refs.stored.truncate(before);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn clear_references(&self) -> Result<()> {
+        let mut refs = self.references();
+        refs.stored.clear();
+        refs.sidecar.changed(&refs.stored)
+    }
+
+    fn query_references(&self, id: SymbolId) -> Result<Vec<Reference>> {
+        let target_symbol = format!("sym_{}", id.0);
+        Ok(self
+            .references()
+            .stored
+            .iter()
+            .filter(|stored| stored.to_symbol == target_symbol)
+            .map(|stored| to_reference(stored, id))
+            .collect())
+    }
+
+    fn query_references_by_name(&self, name: &str) -> Result<Vec<Reference>> {
+        let target_symbol = format!("sym_{}", name);
+        Ok(self
+            .references()
+            .stored
+            .iter()
+            .filter(|stored| stored.to_symbol == target_symbol || stored.to_symbol.contains(name))
+            .map(|stored| to_reference(stored, SymbolId(0)))
+            .collect())
+    }
+
+    fn reference_edges(&self) -> Vec<(SymbolId, SymbolId)> {
+        let symbol_id = |name: &str| name.strip_prefix("sym_")?.parse().ok().map(SymbolId);
+        self.references()
+            .stored
+            .iter()
+            .filter_map(|stored| {
+                let from = symbol_id(stored.from_symbol.as_deref()?)?;
+                Some((from, symbol_id(&stored.to_symbol)?))
+            })
+            .collect()
+    }
+
+    /// Applies to every clone, which share the sidecar.
+    fn set_flush_interval(&mut self, interval: Duration) {
+        self.references().sidecar = ReferenceSidecar::new(&self.codebase_path, interval);
+    }
+
+    fn flush(&self) -> Result<()> {
+        let refs = self.references();
+        refs.sidecar.save(&refs.stored)
+    }
+
+    /// The clone shares this backend's references and sidecar.
+    fn clone_box(&self) -> Box<dyn GraphBackend> {
+        Box::new(Self {
+            codebase_path: self.codebase_path.clone(),
+            db_path: self.db_path.clone(),
+            references: Arc::clone(&self.references),
+        })
+    }
+}
@@ -1,111 +1,48 @@
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-
-use sqlitegraph::backend::NodeSpec;
-use sqlitegraph::config::{open_graph, GraphConfig};
 
 use crate::error::{ForgeError, Result};
-use crate::types::{Language, Location, Reference, ReferenceKind, Symbol, SymbolId, SymbolKind};
+use crate::treesitter::CfgExtractor;
+use crate::types::{Language, Location, Reference, ReferenceKind, Symbol, SymbolId};
+use crate::walk::{is_supported_source, DirWalk};
 
-use super::store::{StoredReference, UnifiedGraphStore};
+use super::store::UnifiedGraphStore;
 use super::BackendKind;
 
+/// Symbols read per page by [`UnifiedGraphStore::get_all_symbols`].
+pub const SYMBOL_PAGE_SIZE: usize = 1000;
+
 impl UnifiedGraphStore {
     pub async fn insert_symbol(&self, symbol: &Symbol) -> Result<SymbolId> {
-        let config = match self.backend_kind {
-            BackendKind::SQLite => GraphConfig::sqlite(),
-            BackendKind::NativeV3 => GraphConfig::native(),
-        };
-        let backend = open_graph(&self.db_path, &config)
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to open graph: {}", e)))?;
-
-        let kind = match symbol.kind {
-            SymbolKind::Function | SymbolKind::Method => "fn",
-            SymbolKind::Struct => "struct",
-            SymbolKind::Enum => "enum",
-            SymbolKind::Trait => "trait",
-            SymbolKind::Impl => "impl",
-            SymbolKind::Module => "module",
-            SymbolKind::TypeAlias => "type",
-            SymbolKind::Constant | SymbolKind::Static => "const",
-            SymbolKind::Parameter | SymbolKind::LocalVariable | SymbolKind::Field => "variable",
-            SymbolKind::Macro => "macro",
-            SymbolKind::Use => "use",
-        };
-
-        let node = NodeSpec {
-            kind: kind.to_string(),
-            name: symbol.name.to_string(),
-            file_path: Some(symbol.location.file_path.to_string_lossy().into_owned()),
-            data: symbol.metadata.clone(),
-        };
-
-        let id = backend
-            .insert_node(node)
-            .map_err(|e| ForgeError::DatabaseError(format!("Insert node failed: {}", e)))?;
+        self.ensure_writable()?;
+        self.backend.insert_symbol(symbol)
+    }
 
-        Ok(SymbolId(id))
+    /// Inserts `symbols` as one batch, returning their ids in order.
+    ///
+    /// Either every symbol is inserted or none is: on SQLite the batch is
+    /// a single transaction, and on Native V3 the nodes already inserted
+    /// are deleted again if a later one fails.
+    pub async fn insert_symbols_batch(&self, symbols: &[Symbol]) -> Result<Vec<SymbolId>> {
+        self.ensure_writable()?;
+        self.backend.insert_symbols(symbols)
     }
 
     pub async fn insert_reference(&self, reference: &Reference) -> Result<()> {
-        if self.backend_kind == BackendKind::NativeV3 {
-            let mut refs = self
-                .references
-                .lock()
-                .expect("invariant: references mutex not poisoned");
-
-            let to_symbol = format!("sym_{}", reference.to.0);
-
-            refs.push(StoredReference {
-                to_symbol,
-                kind: reference.kind,
-                file_path: reference.location.file_path.clone(),
-                line_number: reference.location.line_number,
-            });
-        }
-        Ok(())
+        self.insert_references_batch(std::slice::from_ref(reference))
+            .await
+    }
+
+    /// Inserts `references` as one batch.
+    ///
+    /// The SQLite backend does not store references; on Native V3, if
+    /// saving them fails, the whole batch is taken back out.
+    pub async fn insert_references_batch(&self, references: &[Reference]) -> Result<()> {
+        self.ensure_writable()?;
+        self.backend.insert_references(references)
     }
 
     pub async fn query_symbols(&self, name: &str) -> Result<Vec<Symbol>> {
-        let conn = rusqlite::Connection::open(&self.db_path)
-            .map_err(|e| ForgeError::DatabaseError(format!("Open db failed: {}", e)))?;
-
-        let pattern = format!("%{}%", name);
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, kind, name, file_path FROM graph_entities WHERE name LIKE ?1 LIMIT 50",
-            )
-            .map_err(|e| ForgeError::DatabaseError(format!("Prepare failed: {}", e)))?;
-
-        let symbols = stmt
-            .query_map(rusqlite::params![pattern], |row| {
-                let id: i64 = row.get(0)?;
-                let sym_name: String = row.get(2)?;
-                let file_path: Option<String> = row.get(3)?;
-                Ok((id, sym_name, file_path))
-            })
-            .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?
-            .flatten()
-            .map(|(id, sym_name, file_path)| Symbol {
-                id: SymbolId(id),
-                name: Arc::from(sym_name.as_str()),
-                fully_qualified_name: Arc::from(sym_name.as_str()),
-                kind: SymbolKind::Function,
-                language: Language::Rust,
-                location: Location {
-                    file_path: file_path
-                        .map(PathBuf::from)
-                        .unwrap_or_else(|| PathBuf::from("")),
-                    byte_start: 0,
-                    byte_end: 0,
-                    line_number: 0,
-                },
-                parent_id: None,
-                metadata: serde_json::Value::Null,
-            })
-            .collect();
-
-        Ok(symbols)
+        self.backend.query_symbols(name)
     }
 
     pub async fn get_symbol(&self, _id: SymbolId) -> Result<Symbol> {
@@ -113,96 +50,45 @@ impl UnifiedGraphStore {
     }
 
     pub async fn symbol_exists(&self, id: SymbolId) -> Result<bool> {
-        let conn = rusqlite::Connection::open(&self.db_path)
-            .map_err(|e| ForgeError::DatabaseError(format!("Open db failed: {}", e)))?;
-        let exists: i64 = conn
-            .query_row(
-                "SELECT EXISTS(SELECT 1 FROM graph_entities WHERE id = ?1)",
-                rusqlite::params![id.0],
-                |row| row.get(0),
-            )
-            .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?;
-        Ok(exists > 0)
+        self.backend.symbol_exists(id)
+    }
+
+    /// Returns the `(from, to)` symbol pairs of the stored references.
+    ///
+    /// Only Native V3 and in-memory backends store references; references
+    /// whose source was not known when they were inserted are left out.
+    pub(crate) fn reference_edges(&self) -> Vec<(SymbolId, SymbolId)> {
+        self.backend.reference_edges()
     }
 
     pub async fn query_references(&self, symbol_id: SymbolId) -> Result<Vec<Reference>> {
-        if self.backend_kind == BackendKind::NativeV3 {
-            let refs = self
-                .references
-                .lock()
-                .expect("invariant: references mutex not poisoned");
-            let target_symbol = format!("sym_{}", symbol_id.0);
-
-            let mut result = Vec::new();
-            for stored in refs.iter() {
-                if stored.to_symbol == target_symbol {
-                    result.push(Reference {
-                        from: SymbolId(0),
-                        to: symbol_id,
-                        from_name: None,
-                        to_name: None,
-                        kind: stored.kind,
-                        location: Location {
-                            file_path: stored.file_path.clone(),
-                            byte_start: 0,
-                            byte_end: 0,
-                            line_number: stored.line_number,
-                        },
-                    });
-                }
-            }
-            return Ok(result);
-        }
+        self.backend.query_references(symbol_id)
+    }
 
-        Ok(Vec::new())
+    /// Lists up to `limit` symbols ordered by id, skipping the first
+    /// `offset`. The flag is true if more symbols follow this page.
+    pub async fn list_symbols(&self, offset: usize, limit: usize) -> Result<(Vec<Symbol>, bool)> {
+        self.backend.list_symbols(offset, limit)
     }
 
+    /// Lists every symbol, reading [`SYMBOL_PAGE_SIZE`] at a time.
     pub async fn get_all_symbols(&self) -> Result<Vec<Symbol>> {
-        let conn = rusqlite::Connection::open(&self.db_path)
-            .map_err(|e| ForgeError::DatabaseError(format!("Open db failed: {}", e)))?;
-        let mut stmt = conn
-            .prepare("SELECT id, kind, name, file_path FROM graph_entities LIMIT 1000")
-            .map_err(|e| ForgeError::DatabaseError(format!("Prepare failed: {}", e)))?;
-        let symbols = stmt
-            .query_map([], |row| {
-                let id: i64 = row.get(0)?;
-                let sym_name: String = row.get(2)?;
-                let file_path: Option<String> = row.get(3)?;
-                Ok((id, sym_name, file_path))
-            })
-            .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?
-            .flatten()
-            .map(|(id, sym_name, file_path)| Symbol {
-                id: SymbolId(id),
-                name: Arc::from(sym_name.as_str()),
-                fully_qualified_name: Arc::from(sym_name.as_str()),
-                kind: SymbolKind::Function,
-                language: Language::Rust,
-                location: Location {
-                    file_path: file_path
-                        .map(PathBuf::from)
-                        .unwrap_or_else(|| PathBuf::from("")),
-                    byte_start: 0,
-                    byte_end: 0,
-                    line_number: 0,
-                },
-                parent_id: None,
-                metadata: serde_json::Value::Null,
-            })
-            .collect();
-        Ok(symbols)
+        let mut symbols = Vec::new();
+        loop {
+            let (page, has_more) = self.list_symbols(symbols.len(), SYMBOL_PAGE_SIZE).await?;
+            symbols.extend(page);
+            if !has_more {
+                return Ok(symbols);
+            }
+        }
     }
 
     pub async fn symbol_count(&self) -> Result<usize> {
-        let conn = rusqlite::Connection::open(&self.db_path)
-            .map_err(|e| ForgeError::DatabaseError(format!("Open db failed: {}", e)))?;
-        let count: i64 = conn
-            .query_row("SELECT COUNT(*) FROM graph_entities", [], |row| row.get(0))
-            .map_err(|e| ForgeError::DatabaseError(format!("Query failed: {}", e)))?;
-        Ok(count as usize)
+        self.backend.symbol_count()
     }
 
     pub async fn index_cross_file_references(&self) -> Result<usize> {
+        self.ensure_writable()?;
         if self.backend_kind != BackendKind::NativeV3 {
             return Ok(0);
         }
@@ -216,21 +102,16 @@ impl UnifiedGraphStore {
 
         let mut symbols: std::collections::HashMap<String, (PathBuf, usize)> =
             std::collections::HashMap::new();
-        self.collect_symbols_recursive(&self.codebase_path, &mut symbols)
+        let mut walk = DirWalk::new(&self.codebase_path, &self.walk_options);
+        self.collect_symbols_recursive(&self.codebase_path, &mut walk, &mut symbols)
             .await?;
 
         let reference_pattern = Regex::new(r"\b([a-zA-Z_][a-zA-Z0-9_]*)\s*\(")
             .expect("invariant: static regex pattern is valid");
 
-        {
-            let mut refs = self
-                .references
-                .lock()
-                .expect("invariant: references mutex not poisoned");
-            refs.clear();
-        }
+        self.backend.clear_references()?;
 
-        let mut found_refs: Vec<StoredReference> = Vec::new();
+        let mut found_refs: Vec<Reference> = Vec::new();
 
         for (symbol_name, (_file_path, _)) in &symbols {
             for (target_file, _) in symbols.values() {
@@ -243,11 +124,18 @@ impl UnifiedGraphStore {
                         for cap in reference_pattern.captures_iter(line) {
                             if let Some(matched) = cap.get(1) {
                                 if matched.as_str() == symbol_name {
-                                    found_refs.push(StoredReference {
-                                        to_symbol: format!("sym_{}", symbol_name),
+                                    found_refs.push(Reference {
+                                        from: SymbolId(0),
+                                        to: SymbolId(0),
+                                        from_name: None,
+                                        to_name: Some(symbol_name.clone()),
                                         kind: ReferenceKind::Call,
-                                        file_path: target_file.clone(),
-                                        line_number: line_num + 1,
+                                        location: Location {
+                                            file_path: target_file.clone(),
+                                            byte_start: 0,
+                                            byte_end: 0,
+                                            line_number: line_num + 1,
+                                        },
                                     });
                                 }
                             }
@@ -258,10 +146,7 @@ impl UnifiedGraphStore {
         }
 
         let ref_count = found_refs.len();
-        self.references
-            .lock()
-            .expect("invariant: references mutex not poisoned")
-            .extend(found_refs);
+        self.backend.insert_references(&found_refs)?;
 
         Ok(ref_count)
     }
@@ -269,47 +154,26 @@ impl UnifiedGraphStore {
     async fn collect_symbols_recursive(
         &self,
         dir: &Path,
+        walk: &mut DirWalk,
         symbols: &mut std::collections::HashMap<String, (PathBuf, usize)>,
     ) -> Result<()> {
         use tokio::fs;
 
-        let mut entries = fs::read_dir(dir)
-            .await
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to read dir: {}", e)))?;
+        let mut entries = fs::read_dir(dir).await?;
 
-        while let Some(entry) = entries
-            .next_entry()
-            .await
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to read entry: {}", e)))?
-        {
+        while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
             if path.is_dir() {
-                Box::pin(self.collect_symbols_recursive(&path, symbols)).await?;
-            } else if path.extension().map(|e| e == "rs").unwrap_or(false) {
+                if walk.enter_dir(&path) {
+                    Box::pin(self.collect_symbols_recursive(&path, walk, symbols)).await?;
+                }
+            } else if walk.is_file(&path) {
+                let Some(language) = is_supported_source(&path) else {
+                    continue;
+                };
                 if let Ok(content) = fs::read_to_string(&path).await {
-                    for (line_num, line) in content.lines().enumerate() {
-                        if let Some(fn_pos) = line.find("fn ") {
-                            let after_fn = &line[fn_pos + 3..];
-                            if let Some(end_pos) =
-                                after_fn.find(|c: char| c.is_whitespace() || c == '(')
-                            {
-                                let name = after_fn[..end_pos].trim().to_string();
-                                if !name.is_empty() {
-                                    symbols.insert(name, (path.clone(), line_num + 1));
-                                }
-                            }
-                        }
-                        if let Some(struct_pos) = line.find("struct ") {
-                            let after_struct = &line[struct_pos + 7..];
-                            if let Some(end_pos) = after_struct
-                                .find(|c: char| c.is_whitespace() || c == '{' || c == ';')
-                            {
-                                let name = after_struct[..end_pos].trim().to_string();
-                                if !name.is_empty() {
-                                    symbols.insert(name, (path.clone(), line_num + 1));
-                                }
-                            }
-                        }
+                    for (name, line) in declared_symbols(&content, &path, &language) {
+                        symbols.insert(name, (path.clone(), line));
                     }
                 }
             }
@@ -319,36 +183,37 @@ impl UnifiedGraphStore {
     }
 
     pub async fn query_references_for_symbol(&self, symbol_name: &str) -> Result<Vec<Reference>> {
-        if self.backend_kind != BackendKind::NativeV3 {
-            return Ok(Vec::new());
-        }
+        self.backend.query_references_by_name(symbol_name)
+    }
+}
 
-        let refs = self
-            .references
-            .lock()
-            .expect("invariant: references mutex not poisoned");
-        let mut result = Vec::new();
-
-        for stored in refs.iter() {
-            if stored.to_symbol == format!("sym_{}", symbol_name)
-                || stored.to_symbol.contains(symbol_name)
-            {
-                result.push(Reference {
-                    from: SymbolId(0),
-                    to: SymbolId(0),
-                    from_name: None,
-                    to_name: None,
-                    kind: stored.kind,
-                    location: Location {
-                        file_path: stored.file_path.clone(),
-                        byte_start: 0,
-                        byte_end: 0,
-                        line_number: stored.line_number,
-                    },
-                });
+/// Names and 1-indexed lines of the items declared in `content`.
+///
+/// Rust is scanned line by line for item keywords; other languages go through the
+/// tree-sitter [`CfgExtractor`] when it supports them, which finds
+/// functions only.
+fn declared_symbols(content: &str, path: &Path, language: &Language) -> Vec<(String, usize)> {
+    let mut symbols = Vec::new();
+    if *language != Language::Rust {
+        let Some(lang) = CfgExtractor::detect_language(path) else {
+            return symbols;
+        };
+        if let Ok(functions) = CfgExtractor::extract(content, lang) {
+            for function in functions {
+                let line = crate::graph::byte_offset_to_line_number(
+                    content.as_bytes(),
+                    function.start_byte,
+                );
+                symbols.push((function.name, line));
             }
         }
+        return symbols;
+    }
 
-        Ok(result)
+    for (line_num, line) in content.lines().enumerate() {
+        if let Some((name, _)) = crate::search::declaration_from_line(line) {
+            symbols.push((name, line_num + 1));
+        }
     }
+    symbols
 }
@@ -0,0 +1,194 @@
+//! Sidecar persistence for Native V3 cross-file references.
+//!
+//! The Native V3 backend keeps references in memory. They are written to
+//! `.forge/references.v3` at most once per flush interval, by the change
+//! that finds the interval passed or by a background flusher that saves
+//! changes left waiting, and when the store is dropped, then loaded again by
+//! [`UnifiedGraphStore::open`](super::UnifiedGraphStore::open). Files with
+//! an unknown version or that fail to parse are ignored with a warning.
+
+use crate::error::Result;
+use crate::types::ReferenceKind;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use super::native::StoredReference;
+
+/// Sidecar location relative to the codebase root.
+pub(crate) const REFERENCES_FILE: &str = ".forge/references.v3";
+
+/// Current sidecar format version.
+const FORMAT_VERSION: u32 = 1;
+
+/// Default minimum time between sidecar writes.
+pub const DEFAULT_REFERENCE_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize)]
+struct SidecarFile {
+    version: u32,
+    references: Vec<SidecarEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SidecarEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from_symbol: Option<String>,
+    to_symbol: String,
+    kind: String,
+    file_path: PathBuf,
+    line_number: usize,
+}
+
+fn kind_name(kind: ReferenceKind) -> &'static str {
+    match kind {
+        ReferenceKind::Call => "call",
+        ReferenceKind::Use => "use",
+        ReferenceKind::Import => "import",
+        ReferenceKind::TypeReference => "type_reference",
+        ReferenceKind::Inherit => "inherit",
+        ReferenceKind::Implementation => "implementation",
+        ReferenceKind::Implements => "implements",
+        ReferenceKind::Override => "override",
+    }
+}
+
+fn parse_kind(name: &str) -> Option<ReferenceKind> {
+    Some(match name {
+        "call" => ReferenceKind::Call,
+        "use" => ReferenceKind::Use,
+        "import" => ReferenceKind::Import,
+        "type_reference" => ReferenceKind::TypeReference,
+        "inherit" => ReferenceKind::Inherit,
+        "implementation" => ReferenceKind::Implementation,
+        "implements" => ReferenceKind::Implements,
+        "override" => ReferenceKind::Override,
+        _ => return None,
+    })
+}
+
+/// Tracks unsaved reference changes for one store.
+#[derive(Debug)]
+pub(super) struct ReferenceSidecar {
+    path: PathBuf,
+    interval: Duration,
+    dirty: AtomicBool,
+    saved_at: std::sync::Mutex<Instant>,
+}
+
+impl ReferenceSidecar {
+    pub(super) fn new(codebase_path: &Path, interval: Duration) -> Self {
+        Self {
+            path: codebase_path.join(REFERENCES_FILE),
+            interval,
+            dirty: AtomicBool::new(false),
+            saved_at: std::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Loads saved references; empty if there is no usable sidecar.
+    pub(super) fn load(&self) -> Vec<StoredReference> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(_) => return Vec::new(),
+        };
+        let file = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Ignoring corrupt {}: {}", self.path.display(), e);
+                return Vec::new();
+            }
+        };
+        let version = file.get("version").and_then(|v| v.as_u64());
+        if version != Some(FORMAT_VERSION as u64) {
+            tracing::warn!(
+                "Ignoring {} with unsupported version {:?}",
+                self.path.display(),
+                version
+            );
+            return Vec::new();
+        }
+        match serde_json::from_value::<SidecarFile>(file) {
+            Ok(file) => file
+                .references
+                .into_iter()
+                .filter_map(|entry| {
+                    Some(StoredReference {
+                        from_symbol: entry.from_symbol,
+                        to_symbol: entry.to_symbol,
+                        kind: parse_kind(&entry.kind)?,
+                        file_path: entry.file_path,
+                        line_number: entry.line_number,
+                    })
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Ignoring corrupt {}: {}", self.path.display(), e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Records a change, saving if the flush interval has passed since
+    /// the last write.
+    pub(super) fn changed(&self, references: &[StoredReference]) -> Result<()> {
+        self.dirty.store(true, Ordering::SeqCst);
+        self.flush_if_due(references)
+    }
+
+    /// Saves unwritten changes if the flush interval has passed since the
+    /// last write.
+    pub(super) fn flush_if_due(&self, references: &[StoredReference]) -> Result<()> {
+        let due = self
+            .saved_at
+            .lock()
+            .expect("invariant: sidecar mutex not poisoned")
+            .elapsed()
+            >= self.interval;
+        if due && self.is_dirty() {
+            self.save(references)?;
+        }
+        Ok(())
+    }
+
+    /// Minimum time between writes.
+    pub(super) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns true if there are changes not yet written.
+    pub(super) fn is_dirty(&self) -> bool {
+        self.dirty.load(Ordering::SeqCst)
+    }
+
+    /// Writes the sidecar atomically.
+    pub(super) fn save(&self, references: &[StoredReference]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = SidecarFile {
+            version: FORMAT_VERSION,
+            references: references
+                .iter()
+                .map(|r| SidecarEntry {
+                    from_symbol: r.from_symbol.clone(),
+                    to_symbol: r.to_symbol.clone(),
+                    kind: kind_name(r.kind).to_string(),
+                    file_path: r.file_path.clone(),
+                    line_number: r.line_number,
+                })
+                .collect(),
+        };
+        let json = serde_json::to_vec(&file)?;
+        let tmp = self.path.with_extension("v3.tmp");
+        std::fs::write(&tmp, json).and_then(|_| std::fs::rename(&tmp, &self.path))?;
+
+        self.dirty.store(false, Ordering::SeqCst);
+        *self
+            .saved_at
+            .lock()
+            .expect("invariant: sidecar mutex not poisoned") = Instant::now();
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+//! The SQLite graph backend, and the symbol table access it shares with
+//! Native V3.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use sqlitegraph::backend::NodeSpec;
+use sqlitegraph::config::{open_graph, GraphConfig};
+
+use crate::error::{ForgeError, Result};
+use crate::types::{Language, Location, Reference, Symbol, SymbolId, SymbolKind, Visibility};
+
+use super::backend::GraphBackend;
+
+/// Symbols stored as sqlitegraph nodes in a SQLite database. References
+/// are not stored.
+#[derive(Clone, Debug)]
+pub(super) struct SqliteBackend {
+    db_path: PathBuf,
+}
+
+impl SqliteBackend {
+    pub(super) fn new(db_path: &Path) -> Self {
+        Self {
+            db_path: db_path.to_path_buf(),
+        }
+    }
+}
+
+impl GraphBackend for SqliteBackend {
+    fn insert_symbol(&self, symbol: &Symbol) -> Result<SymbolId> {
+        insert_node(&self.db_path, &GraphConfig::sqlite(), symbol)
+    }
+
+    /// The batch is a single transaction.
+    fn insert_symbols(&self, symbols: &[Symbol]) -> Result<Vec<SymbolId>> {
+        if symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+        let backend = open_backend(&self.db_path, &GraphConfig::sqlite())?;
+        let nodes: Vec<NodeSpec> = symbols.iter().map(node_spec).collect();
+        let ids = backend
+            .insert_nodes_bulk(&nodes)
+            .map_err(|e| ForgeError::DatabaseError(format!("Insert batch failed: {}", e)))?;
+        Ok(ids.into_iter().map(SymbolId).collect())
+    }
+
+    fn query_symbols(&self, name: &str) -> Result<Vec<Symbol>> {
+        query_symbols(&self.db_path, name)
+    }
+
+    fn symbol_exists(&self, id: SymbolId) -> Result<bool> {
+        symbol_exists(&self.db_path, id)
+    }
+
+    fn list_symbols(&self, offset: usize, limit: usize) -> Result<(Vec<Symbol>, bool)> {
+        list_symbols(&self.db_path, offset, limit)
+    }
+
+    fn symbol_count(&self) -> Result<usize> {
+        symbol_count(&self.db_path)
+    }
+
+    fn insert_references(&self, _references: &[Reference]) -> Result<()> {
+        Ok(())
+    }
+
+    fn query_references(&self, _id: SymbolId) -> Result<Vec<Reference>> {
+        Ok(Vec::new())
+    }
+
+    fn clone_box(&self) -> Box<dyn GraphBackend> {
+        Box::new(self.clone())
+    }
+}
+
+/// Opens the sqlitegraph backend for the database at `db_path`.
+pub(super) fn open_backend(
+    db_path: &Path,
+    config: &GraphConfig,
+) -> Result<Box<dyn sqlitegraph::backend::GraphBackend>> {
+    open_graph(db_path, config)
+        .map_err(|e| ForgeError::BackendNotAvailable(format!("Failed to open graph: {}", e)))
+}
+
+/// Inserts `symbol` as a single node.
+pub(super) fn insert_node(
+    db_path: &Path,
+    config: &GraphConfig,
+    symbol: &Symbol,
+) -> Result<SymbolId> {
+    let backend = open_backend(db_path, config)?;
+    let id = backend
+        .insert_node(node_spec(symbol))
+        .map_err(|e| ForgeError::DatabaseError(format!("Insert node failed: {}", e)))?;
+    Ok(SymbolId(id))
+}
+
+pub(super) fn query_symbols(db_path: &Path, name: &str) -> Result<Vec<Symbol>> {
+    let conn = super::open_read_only(db_path)?;
+
+    let pattern = format!("%{}%", name);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, kind, name, file_path, data FROM graph_entities \
+             WHERE {IS_SYMBOL} AND name LIKE ?1 LIMIT 50"
+    ))?;
+
+    let symbols = stmt
+        .query_map(rusqlite::params![pattern], entity_row)?
+        .flatten()
+        .map(entity_symbol)
+        .collect();
+
+    Ok(symbols)
+}
+
+pub(super) fn symbol_exists(db_path: &Path, id: SymbolId) -> Result<bool> {
+    let conn = super::open_read_only(db_path)?;
+    let exists: i64 = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM graph_entities WHERE id = ?1)",
+        rusqlite::params![id.0],
+        |row| row.get(0),
+    )?;
+    Ok(exists > 0)
+}
+
+pub(super) fn list_symbols(
+    db_path: &Path,
+    offset: usize,
+    limit: usize,
+) -> Result<(Vec<Symbol>, bool)> {
+    let conn = super::open_read_only(db_path)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, kind, name, file_path, data FROM graph_entities \
+             WHERE {IS_SYMBOL} ORDER BY id LIMIT ?1 OFFSET ?2"
+    ))?;
+    let mut symbols: Vec<Symbol> = stmt
+        .query_map(
+            rusqlite::params![limit as i64 + 1, offset as i64],
+            entity_row,
+        )?
+        .flatten()
+        .map(entity_symbol)
+        .collect();
+    let has_more = symbols.len() > limit;
+    symbols.truncate(limit);
+    Ok((symbols, has_more))
+}
+
+pub(super) fn symbol_count(db_path: &Path) -> Result<usize> {
+    let conn = super::open_read_only(db_path)?;
+    let count: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM graph_entities WHERE {IS_SYMBOL}"),
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count as usize)
+}
+
+/// Condition selecting the entities that are symbols: magellan's `Symbol`
+/// entities and the kinds [`node_spec`] writes, but not the files,
+/// references and calls magellan stores alongside them.
+const IS_SYMBOL: &str = "kind IN ('Symbol', 'fn', 'struct', 'enum', 'trait', 'impl', \
+     'module', 'type', 'const', 'static', 'variable', 'macro', 'use')";
+
+/// Graph node for `symbol` as written by [`insert_node`].
+pub(super) fn node_spec(symbol: &Symbol) -> NodeSpec {
+    let kind = match symbol.kind {
+        SymbolKind::Function | SymbolKind::Method => "fn",
+        SymbolKind::Struct => "struct",
+        SymbolKind::Enum => "enum",
+        SymbolKind::Trait => "trait",
+        SymbolKind::Impl => "impl",
+        SymbolKind::Module => "module",
+        SymbolKind::TypeAlias => "type",
+        SymbolKind::Constant => "const",
+        SymbolKind::Static => "static",
+        SymbolKind::Parameter | SymbolKind::LocalVariable | SymbolKind::Field => "variable",
+        SymbolKind::Macro => "macro",
+        SymbolKind::Use => "use",
+    };
+
+    NodeSpec {
+        kind: kind.to_string(),
+        name: symbol.name.to_string(),
+        file_path: Some(symbol.location.file_path.to_string_lossy().into_owned()),
+        data: symbol.metadata.clone(),
+    }
+}
+
+/// Columns of a `SELECT id, kind, name, file_path, data` entity row.
+type EntityRow = (i64, SymbolKind, String, Option<String>);
+
+fn entity_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<EntityRow> {
+    Ok((
+        row.get(0)?,
+        entity_kind(&row.get::<_, String>(1)?, row.get(4)?),
+        row.get(2)?,
+        row.get(3)?,
+    ))
+}
+
+fn entity_symbol((id, kind, name, file_path): EntityRow) -> Symbol {
+    Symbol {
+        id: SymbolId(id),
+        name: Arc::from(name.as_str()),
+        fully_qualified_name: Arc::from(name.as_str()),
+        kind,
+        language: Language::Rust,
+        location: Location {
+            file_path: file_path.map(PathBuf::from).unwrap_or_default(),
+            byte_start: 0,
+            byte_end: 0,
+            line_number: 0,
+        },
+        parent_id: None,
+        visibility: Visibility::Unknown,
+        body_hash: None,
+        metadata: serde_json::Value::Null,
+    }
+}
+
+/// Kind of a `graph_entities` row: magellan stores symbols as `Symbol`
+/// with the real kind in `data`, while [`insert_node`] stores the kind
+/// directly.
+fn entity_kind(kind: &str, data: Option<String>) -> SymbolKind {
+    if kind != "Symbol" {
+        return crate::graph::parse_symbol_kind_str(kind);
+    }
+    let data = data.and_then(|d| serde_json::from_str::<serde_json::Value>(&d).ok());
+    let kind = data
+        .as_ref()
+        .and_then(|d| d.get("kind_normalized").or_else(|| d.get("kind")))
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    crate::graph::parse_symbol_kind_str(kind)
+}
@@ -1,25 +1,46 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use sqlitegraph::config::{open_graph, GraphConfig};
 
+use crate::analysis::ref_index::ReferenceIndexCache;
+use crate::cfg::CfgCache;
+use crate::circuit::CircuitBreaker;
 use crate::error::{ForgeError, Result};
-use crate::types::ReferenceKind;
+use crate::indexing::IndexerConfig;
+use crate::pool::{GraphReaderPool, DEFAULT_GRAPH_READERS};
+#[cfg(feature = "semantic")]
+use crate::search::embedding::{Embedder, HashingEmbedder};
+use crate::walk::WalkOptions;
 
+use super::backend::{create_backend, GraphBackend};
 use super::{default_db_path, BackendKind};
 
-#[derive(Clone, Debug)]
-pub(super) struct StoredReference {
-    pub(super) to_symbol: String,
-    pub(super) kind: ReferenceKind,
-    pub(super) file_path: PathBuf,
-    pub(super) line_number: usize,
-}
-
 pub struct UnifiedGraphStore {
     pub codebase_path: PathBuf,
     pub db_path: PathBuf,
     pub backend_kind: BackendKind,
-    pub(super) references: std::sync::Mutex<Vec<StoredReference>>,
+    /// Holds what is written through the store; opened for `backend_kind`.
+    pub(super) backend: Box<dyn GraphBackend>,
+    /// Guards magellan indexing calls; shared between clones of the store.
+    pub(crate) index_breaker: std::sync::Arc<CircuitBreaker>,
+    /// How directory walks over the codebase treat symlinks.
+    pub(crate) walk_options: WalkOptions,
+    /// Which files symbol indexing parses and stores.
+    pub(crate) indexer_config: IndexerConfig,
+    /// CFGs built on demand, per symbol until the next index run and per
+    /// function body across runs; shared between clones of the store.
+    pub(crate) cfg_cache: std::sync::Arc<CfgCache>,
+    /// Call and reference counts built on demand; shared between clones
+    /// of the store and dropped whenever the graph is re-indexed.
+    pub(crate) ref_index: std::sync::Arc<ReferenceIndexCache>,
+    /// Reader threads for magellan queries; shared between clones.
+    pub(crate) graph_readers: std::sync::Arc<GraphReaderPool>,
+    /// Computes symbol embeddings at index time.
+    #[cfg(feature = "semantic")]
+    pub(crate) embedder: std::sync::Arc<dyn Embedder>,
+    /// Set by [`open_readonly`](Self::open_readonly); writes are refused.
+    pub(crate) read_only: bool,
 }
 
 impl Clone for UnifiedGraphStore {
@@ -28,12 +49,16 @@ impl Clone for UnifiedGraphStore {
             codebase_path: self.codebase_path.clone(),
             db_path: self.db_path.clone(),
             backend_kind: self.backend_kind,
-            references: std::sync::Mutex::new(
-                self.references
-                    .lock()
-                    .expect("invariant: references mutex not poisoned")
-                    .clone(),
-            ),
+            backend: self.backend.clone_box(),
+            index_breaker: std::sync::Arc::clone(&self.index_breaker),
+            walk_options: self.walk_options.clone(),
+            indexer_config: self.indexer_config.clone(),
+            cfg_cache: std::sync::Arc::clone(&self.cfg_cache),
+            ref_index: std::sync::Arc::clone(&self.ref_index),
+            graph_readers: std::sync::Arc::clone(&self.graph_readers),
+            #[cfg(feature = "semantic")]
+            embedder: std::sync::Arc::clone(&self.embedder),
+            read_only: self.read_only,
         }
     }
 }
@@ -53,21 +78,18 @@ impl UnifiedGraphStore {
     pub async fn open(codebase_path: impl AsRef<Path>, backend_kind: BackendKind) -> Result<Self> {
         let codebase = codebase_path.as_ref();
         if !codebase.exists() {
-            return Err(ForgeError::DatabaseError(format!(
-                "Codebase path does not exist: {}",
-                codebase.display()
+            return Err(ForgeError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Codebase path does not exist: {}", codebase.display()),
             )));
         }
         let db_path = default_db_path(codebase);
 
         if let Some(parent) = db_path.parent() {
-            tokio::fs::create_dir_all(parent).await.map_err(|e| {
-                ForgeError::DatabaseError(format!("Failed to create database directory: {}", e))
-            })?;
+            tokio::fs::create_dir_all(parent).await?;
         }
 
         let sqlitegraph_path = match backend_kind {
-            BackendKind::SQLite => db_path.clone(),
             BackendKind::NativeV3 => {
                 let stem = codebase
                     .file_name()
@@ -78,26 +100,36 @@ impl UnifiedGraphStore {
                     .join(".magellan")
                     .join(format!("{}.v3", stem))
             }
-        };
-        let config = match backend_kind {
-            BackendKind::SQLite => GraphConfig::sqlite(),
-            BackendKind::NativeV3 => GraphConfig::native(),
+            _ => db_path.clone(),
         };
 
-        let _graph = open_graph(&sqlitegraph_path, &config)
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to open database: {}", e)))?;
+        let _graph = open_graph(&sqlitegraph_path, &backend_kind.graph_config()).map_err(|e| {
+            ForgeError::BackendNotAvailable(format!("Failed to open database: {}", e))
+        })?;
 
         if matches!(backend_kind, BackendKind::NativeV3) {
             let _ = open_graph(&db_path, &GraphConfig::sqlite()).map_err(|e| {
-                ForgeError::DatabaseError(format!("Failed to init magellan SQLite DB: {}", e))
+                ForgeError::BackendNotAvailable(format!("Failed to init magellan SQLite DB: {}", e))
             })?;
         }
 
+        let graph_readers =
+            std::sync::Arc::new(GraphReaderPool::new(&db_path, DEFAULT_GRAPH_READERS));
+        let backend = create_backend(backend_kind, codebase, &db_path)?;
         Ok(UnifiedGraphStore {
             codebase_path: codebase.to_path_buf(),
             db_path,
             backend_kind,
-            references: std::sync::Mutex::new(Vec::new()),
+            backend,
+            index_breaker: std::sync::Arc::new(CircuitBreaker::with_defaults("magellan")),
+            walk_options: WalkOptions::default(),
+            indexer_config: IndexerConfig::default(),
+            cfg_cache: std::sync::Arc::default(),
+            ref_index: std::sync::Arc::default(),
+            graph_readers,
+            #[cfg(feature = "semantic")]
+            embedder: std::sync::Arc::new(HashingEmbedder::default()),
+            read_only: false,
         })
     }
 
@@ -110,24 +142,80 @@ impl UnifiedGraphStore {
         let db = db_path.as_ref();
 
         if let Some(parent) = db.parent() {
-            tokio::fs::create_dir_all(parent).await.map_err(|e| {
-                ForgeError::DatabaseError(format!("Failed to create database directory: {}", e))
-            })?;
+            tokio::fs::create_dir_all(parent).await?;
         }
 
-        let config = match backend_kind {
-            BackendKind::SQLite => GraphConfig::sqlite(),
-            BackendKind::NativeV3 => GraphConfig::native(),
-        };
-
-        let _graph = open_graph(db, &config)
-            .map_err(|e| ForgeError::DatabaseError(format!("Failed to open database: {}", e)))?;
+        let _graph = open_graph(db, &backend_kind.graph_config()).map_err(|e| {
+            ForgeError::BackendNotAvailable(format!("Failed to open database: {}", e))
+        })?;
 
+        let graph_readers = std::sync::Arc::new(GraphReaderPool::new(db, DEFAULT_GRAPH_READERS));
+        let backend = create_backend(backend_kind, codebase, db)?;
         Ok(UnifiedGraphStore {
             codebase_path: codebase.to_path_buf(),
             db_path: db.to_path_buf(),
             backend_kind,
-            references: std::sync::Mutex::new(Vec::new()),
+            backend,
+            index_breaker: std::sync::Arc::new(CircuitBreaker::with_defaults("magellan")),
+            walk_options: WalkOptions::default(),
+            indexer_config: IndexerConfig::default(),
+            cfg_cache: std::sync::Arc::default(),
+            ref_index: std::sync::Arc::default(),
+            graph_readers,
+            #[cfg(feature = "semantic")]
+            embedder: std::sync::Arc::new(HashingEmbedder::default()),
+            read_only: false,
+        })
+    }
+
+    /// Opens the graph already built for `codebase_path` without writing.
+    ///
+    /// Nothing is created: fails with
+    /// [`ForgeError::BackendNotAvailable`] if the database does not exist.
+    /// Inserts and indexing on the returned store fail with
+    /// [`ForgeError::ReadOnly`].
+    pub async fn open_readonly(
+        codebase_path: impl AsRef<Path>,
+        backend_kind: BackendKind,
+    ) -> Result<Self> {
+        let codebase = codebase_path.as_ref();
+        Self::open_readonly_with_path(codebase, default_db_path(codebase), backend_kind).await
+    }
+
+    /// Opens the graph at `db_path` without writing, like
+    /// [`open_readonly`](Self::open_readonly).
+    pub async fn open_readonly_with_path(
+        codebase_path: impl AsRef<Path>,
+        db_path: impl AsRef<Path>,
+        backend_kind: BackendKind,
+    ) -> Result<Self> {
+        let codebase = codebase_path.as_ref();
+        let db_path = db_path.as_ref().to_path_buf();
+        if !db_path.is_file() {
+            return Err(ForgeError::BackendNotAvailable(format!(
+                "graph DB not found at {}; index the codebase first",
+                db_path.display()
+            )));
+        }
+        super::open_read_only(&db_path)?.query_row("PRAGMA schema_version", [], |_| Ok(()))?;
+
+        let graph_readers =
+            std::sync::Arc::new(GraphReaderPool::new(&db_path, DEFAULT_GRAPH_READERS));
+        let backend = create_backend(backend_kind, codebase, &db_path)?;
+        Ok(UnifiedGraphStore {
+            codebase_path: codebase.to_path_buf(),
+            db_path,
+            backend_kind,
+            backend,
+            index_breaker: std::sync::Arc::new(CircuitBreaker::with_defaults("magellan")),
+            walk_options: WalkOptions::default(),
+            indexer_config: IndexerConfig::default(),
+            cfg_cache: std::sync::Arc::default(),
+            ref_index: std::sync::Arc::default(),
+            graph_readers,
+            #[cfg(feature = "semantic")]
+            embedder: std::sync::Arc::new(HashingEmbedder::default()),
+            read_only: true,
         })
     }
 
@@ -135,9 +223,7 @@ impl UnifiedGraphStore {
     pub async fn memory() -> Result<Self> {
         use tempfile::tempdir;
 
-        let temp = tempdir().map_err(|e| {
-            ForgeError::DatabaseError(format!("Failed to create temp directory: {}", e))
-        })?;
+        let temp = tempdir()?;
 
         Self::open(temp.path(), BackendKind::SQLite).await
     }
@@ -152,6 +238,83 @@ impl UnifiedGraphStore {
         &self.db_path
     }
 
+    /// Sets how directory walks over the codebase treat symlinks.
+    pub fn with_walk_options(mut self, options: WalkOptions) -> Self {
+        self.walk_options = options;
+        self
+    }
+
+    #[inline]
+    pub fn walk_options(&self) -> &WalkOptions {
+        &self.walk_options
+    }
+
+    /// Sets the number of graph reader threads serving symbol queries.
+    ///
+    /// Replaces the reader pool, so call before the store is shared.
+    pub fn with_pool_size(mut self, size: usize) -> Self {
+        self.graph_readers = std::sync::Arc::new(GraphReaderPool::new(&self.db_path, size));
+        self
+    }
+
+    #[inline]
+    pub fn pool_size(&self) -> usize {
+        self.graph_readers.size()
+    }
+
+    /// Sets the embedder used to index symbols for
+    /// [`semantic_search_ranked`](crate::search::SearchModule::semantic_search_ranked)
+    /// (default: [`HashingEmbedder`]).
+    ///
+    /// Stored vectors come from the embedder in use at the last index run,
+    /// so re-index after changing it.
+    #[cfg(feature = "semantic")]
+    pub fn with_embedder(mut self, embedder: std::sync::Arc<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Sets the minimum time between writes of the Native V3 reference
+    /// sidecar (default: 30 seconds). References are also saved when the
+    /// store is dropped.
+    pub fn with_reference_flush_interval(mut self, interval: Duration) -> Self {
+        self.backend.set_flush_interval(interval);
+        self
+    }
+
+    /// Writes Native V3 references to the sidecar now.
+    ///
+    /// Does nothing for backends that do not buffer writes.
+    pub fn flush_references(&self) -> Result<()> {
+        self.backend.flush()
+    }
+
+    /// Sets which files symbol indexing parses and stores.
+    pub fn with_indexer_config(mut self, config: IndexerConfig) -> Self {
+        self.indexer_config = config;
+        self
+    }
+
+    #[inline]
+    pub fn indexer_config(&self) -> &IndexerConfig {
+        &self.indexer_config
+    }
+
+    /// Returns true if the store was opened with
+    /// [`open_readonly`](Self::open_readonly).
+    #[inline]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Fails with [`ForgeError::ReadOnly`] if the store is read-only.
+    pub(crate) fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(ForgeError::ReadOnly(self.db_path.clone()));
+        }
+        Ok(())
+    }
+
     pub fn is_connected(&self) -> bool {
         self.db_path.exists()
     }
@@ -160,11 +323,7 @@ impl UnifiedGraphStore {
         if !self.is_connected() {
             return true;
         }
-        let config = match self.backend_kind {
-            BackendKind::SQLite => GraphConfig::sqlite(),
-            BackendKind::NativeV3 => GraphConfig::native(),
-        };
-        match open_graph(&self.db_path, &config) {
+        match open_graph(&self.db_path, &self.backend_kind.graph_config()) {
             Ok(backend) => match backend.entity_ids() {
                 Ok(ids) => ids.is_empty(),
                 Err(_) => true,
@@ -1,7 +1,10 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::types::{Language, Location, Reference, ReferenceKind, Symbol, SymbolId, SymbolKind};
+use crate::error::ForgeError;
+use crate::types::{
+    Language, Location, Reference, ReferenceKind, Symbol, SymbolId, SymbolKind, Visibility,
+};
 
 use super::*;
 
@@ -96,6 +99,8 @@ async fn test_insert_symbol_returns_id() {
             line_number: 10,
         },
         parent_id: None,
+        visibility: Visibility::default(),
+        body_hash: None,
         metadata: serde_json::json!({"doc": "Test function"}),
     };
 
@@ -103,6 +108,107 @@ async fn test_insert_symbol_returns_id() {
     assert!(id.0 > 0);
 }
 
+#[tokio::test]
+async fn test_open_readonly_refuses_writes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("graph").join("test.db");
+
+    let missing =
+        UnifiedGraphStore::open_readonly_with_path(temp_dir.path(), &db_path, BackendKind::SQLite)
+            .await
+            .unwrap_err();
+    assert!(matches!(missing, ForgeError::BackendNotAvailable(_)));
+    assert!(!db_path.parent().unwrap().exists());
+
+    UnifiedGraphStore::open_with_path(temp_dir.path(), &db_path, BackendKind::SQLite)
+        .await
+        .unwrap();
+    let store =
+        UnifiedGraphStore::open_readonly_with_path(temp_dir.path(), &db_path, BackendKind::SQLite)
+            .await
+            .unwrap();
+    assert!(store.is_read_only());
+
+    let symbol = Symbol {
+        id: SymbolId(0),
+        name: Arc::from("f"),
+        fully_qualified_name: Arc::from("crate::f"),
+        kind: SymbolKind::Function,
+        language: Language::Rust,
+        location: Location {
+            file_path: PathBuf::from("src/lib.rs"),
+            byte_start: 0,
+            byte_end: 10,
+            line_number: 1,
+        },
+        parent_id: None,
+        visibility: Visibility::default(),
+        body_hash: None,
+        metadata: serde_json::Value::Null,
+    };
+    assert!(matches!(
+        store.insert_symbol(&symbol).await,
+        Err(ForgeError::ReadOnly(_))
+    ));
+    assert!(store.query_symbols("f").await.unwrap().is_empty());
+
+    let graph = crate::graph::GraphModule::new(Arc::new(store));
+    assert!(matches!(graph.index().await, Err(ForgeError::ReadOnly(_))));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_readonly_queries_leave_database_untouched() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let src = temp_dir.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(
+        src.join("lib.rs"),
+        "pub fn helper() -> i32 { 1 }\n\npub fn caller() -> i32 { helper() }\n",
+    )
+    .unwrap();
+    let db_path = temp_dir.path().join("graph.db");
+    {
+        let store =
+            UnifiedGraphStore::open_with_path(temp_dir.path(), &db_path, BackendKind::SQLite)
+                .await
+                .unwrap();
+        crate::graph::GraphModule::new(Arc::new(store))
+            .index()
+            .await
+            .unwrap();
+    }
+
+    std::fs::set_permissions(&db_path, std::fs::Permissions::from_mode(0o444)).unwrap();
+    let before = std::fs::read(&db_path).unwrap();
+    let wal = db_path.with_extension("db-wal");
+    let wal_before = std::fs::metadata(&wal).map(|m| m.len()).unwrap_or(0);
+
+    let store =
+        UnifiedGraphStore::open_readonly_with_path(temp_dir.path(), &db_path, BackendKind::SQLite)
+            .await
+            .unwrap()
+            .with_pool_size(2);
+    let graph = crate::graph::GraphModule::new(Arc::new(store));
+    let found = graph.find_symbol("caller").await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].location.line_number, 3);
+    let callers = graph.callers_of("helper").await.unwrap();
+    assert!(!callers.is_empty());
+    assert!(callers
+        .iter()
+        .all(|c| c.from_name.as_deref() == Some("caller") && c.location.line_number == 3));
+    graph.references("helper").await.unwrap();
+    assert!(graph.history().checkpoints().unwrap().is_empty());
+    drop(graph);
+
+    assert_eq!(std::fs::read(&db_path).unwrap(), before);
+    let wal_after = std::fs::metadata(&wal).map(|m| m.len()).unwrap_or(0);
+    assert_eq!(wal_after, wal_before);
+}
+
 #[tokio::test]
 async fn test_query_symbols_empty() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -154,63 +260,271 @@ async fn test_insert_reference_and_query() {
 }
 
 #[tokio::test]
-async fn test_symbol_exists_unknown_id() {
-    let (store, _dir) = isolated_store().await;
-    assert!(!store.symbol_exists(SymbolId(99999)).await.unwrap());
-}
+async fn test_native_v3_clones_share_references() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
+        .await
+        .unwrap();
+    let clone = store.clone();
 
-#[tokio::test]
-async fn test_get_all_symbols_empty_db() {
-    let (store, _dir) = isolated_store().await;
-    let symbols = store.get_all_symbols().await.unwrap();
-    assert!(symbols.is_empty());
+    let reference = Reference {
+        from: SymbolId(1),
+        to: SymbolId(2),
+        from_name: None,
+        to_name: None,
+        kind: ReferenceKind::Call,
+        location: Location {
+            file_path: PathBuf::from("src/lib.rs"),
+            byte_start: 0,
+            byte_end: 0,
+            line_number: 3,
+        },
+    };
+    clone.insert_reference(&reference).await.unwrap();
+    assert_eq!(store.query_references(SymbolId(2)).await.unwrap().len(), 1);
+
+    // Clearing through the original clears what the clone sees as well
+    store.backend.clear_references().unwrap();
+    assert!(clone
+        .query_references(SymbolId(2))
+        .await
+        .unwrap()
+        .is_empty());
 }
 
 #[tokio::test]
-async fn test_get_all_symbols_returns_inserted() {
-    let (store, _dir) = isolated_store().await;
-    store
-        .insert_symbol(&make_symbol("alpha_get_all"))
+async fn test_native_v3_references_persist_across_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let reference = Reference {
+        from: SymbolId(1),
+        to: SymbolId(2),
+        from_name: None,
+        to_name: None,
+        kind: ReferenceKind::TypeReference,
+        location: Location {
+            file_path: PathBuf::from("src/lib.rs"),
+            byte_start: 0,
+            byte_end: 0,
+            line_number: 7,
+        },
+    };
+
+    // Saved on drop, within the flush interval
+    let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
         .await
         .unwrap();
-    store
-        .insert_symbol(&make_symbol("beta_get_all"))
+    store.insert_reference(&reference).await.unwrap();
+    assert!(!dir.path().join(".forge/references.v3").exists());
+    drop(store);
+
+    let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
         .await
         .unwrap();
-    let symbols = store.get_all_symbols().await.unwrap();
+    let refs = store.query_references(SymbolId(2)).await.unwrap();
+    assert_eq!(refs.len(), 1);
+    assert_eq!(refs[0].kind, ReferenceKind::TypeReference);
+    assert_eq!(refs[0].location.line_number, 7);
+    drop(store);
+
+    // Saved as soon as the interval has passed
+    let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
+        .await
+        .unwrap()
+        .with_reference_flush_interval(std::time::Duration::ZERO);
+    store.insert_reference(&reference).await.unwrap();
+    let saved = std::fs::read_to_string(dir.path().join(".forge/references.v3")).unwrap();
+    assert!(saved.contains("\"version\":1"), "{}", saved);
+    drop(store);
+
+    let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
+        .await
+        .unwrap();
+    assert_eq!(store.query_references(SymbolId(2)).await.unwrap().len(), 2);
     assert_eq!(
-        symbols.len(),
-        2,
-        "get_all_symbols should return all inserted symbols"
+        store.reference_edges(),
+        vec![(SymbolId(1), SymbolId(2)); 2],
+        "the referencing symbol survives the sidecar"
     );
 }
 
 #[tokio::test]
-async fn test_symbol_count_empty_db() {
-    let (store, _dir) = isolated_store().await;
-    assert_eq!(store.symbol_count().await.unwrap(), 0);
-}
-
-#[tokio::test]
-async fn test_symbol_count_after_inserts() {
-    let (store, _dir) = isolated_store().await;
-    store
-        .insert_symbol(&make_symbol("count_sym_a"))
+async fn test_native_v3_flushes_waiting_references_in_background() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
         .await
-        .unwrap();
+        .unwrap()
+        .with_reference_flush_interval(std::time::Duration::from_millis(200));
     store
-        .insert_symbol(&make_symbol("count_sym_b"))
+        .insert_reference(&Reference {
+            from: SymbolId(1),
+            to: SymbolId(2),
+            from_name: None,
+            to_name: None,
+            kind: ReferenceKind::Call,
+            location: Location {
+                file_path: PathBuf::from("src/lib.rs"),
+                byte_start: 0,
+                byte_end: 0,
+                line_number: 3,
+            },
+        })
         .await
         .unwrap();
-    store
-        .insert_symbol(&make_symbol("count_sym_c"))
-        .await
+    let sidecar = dir.path().join(".forge/references.v3");
+    assert!(!sidecar.exists(), "not due yet");
+
+    // No further change comes, yet the store stays open
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !sidecar.exists() && std::time::Instant::now() < deadline {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert!(sidecar.exists(), "flushed while the store is open");
+    drop(store);
+}
+
+#[tokio::test]
+async fn test_unreadable_reference_sidecar_starts_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let sidecar = dir.path().join(".forge/references.v3");
+    std::fs::create_dir_all(sidecar.parent().unwrap()).unwrap();
+
+    for contents in [
+        "not json",
+        r#"{"version":99,"references":"changed"}"#,
+        r#"{"version":1,"references":42}"#,
+    ] {
+        std::fs::write(&sidecar, contents).unwrap();
+        let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
+            .await
+            .unwrap();
+        assert!(store
+            .query_references(SymbolId(2))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_symbol_exists_unknown_id() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        assert!(!store.symbol_exists(SymbolId(99999)).await.unwrap());
+    }
+}
+
+#[tokio::test]
+async fn test_get_all_symbols_empty_db() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        let symbols = store.get_all_symbols().await.unwrap();
+        assert!(symbols.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_list_symbols_pages_in_id_order() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        for i in 0..25 {
+            store
+                .insert_symbol(&make_symbol(&format!("paged_{}", i)))
+                .await
+                .unwrap();
+        }
+
+        let mut listed = Vec::new();
+        let mut pages = Vec::new();
+        loop {
+            let (page, has_more) = store.list_symbols(listed.len(), 10).await.unwrap();
+            pages.push((page.len(), has_more));
+            listed.extend(page);
+            if !has_more {
+                break;
+            }
+        }
+        assert_eq!(pages, vec![(10, true), (10, true), (5, false)]);
+        assert!(listed.windows(2).all(|w| w[0].id < w[1].id));
+
+        let all = store.get_all_symbols().await.unwrap();
+        assert_eq!(all, listed);
+        let (past_end, has_more) = store.list_symbols(25, 10).await.unwrap();
+        assert!(past_end.is_empty() && !has_more);
+    }
+}
+
+#[tokio::test]
+async fn test_sqlite_lists_only_symbol_entities() {
+    let (store, _dir) = isolated_store(BackendKind::SQLite).await;
+    store.insert_symbol(&make_symbol("listed")).await.unwrap();
+    let conn = rusqlite::Connection::open(&store.db_path).unwrap();
+    for kind in ["File", "Reference", "Call"] {
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) \
+             VALUES (?1, 'listed_too', 'src/lib.rs', '{}')",
+            [kind],
+        )
         .unwrap();
-    assert_eq!(
-        store.symbol_count().await.unwrap(),
-        3,
-        "symbol_count should equal insert count"
-    );
+    }
+
+    let (symbols, has_more) = store.list_symbols(0, 10).await.unwrap();
+    assert_eq!(symbols.len(), 1, "{:?}", symbols);
+    assert!(!has_more);
+    assert_eq!(store.symbol_count().await.unwrap(), 1);
+    assert_eq!(store.query_symbols("listed").await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_all_symbols_returns_inserted() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        store
+            .insert_symbol(&make_symbol("alpha_get_all"))
+            .await
+            .unwrap();
+        store
+            .insert_symbol(&make_symbol("beta_get_all"))
+            .await
+            .unwrap();
+        let symbols = store.get_all_symbols().await.unwrap();
+        assert_eq!(
+            symbols.len(),
+            2,
+            "get_all_symbols should return all inserted symbols"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_symbol_count_empty_db() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        assert_eq!(store.symbol_count().await.unwrap(), 0);
+    }
+}
+
+#[tokio::test]
+async fn test_symbol_count_after_inserts() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        store
+            .insert_symbol(&make_symbol("count_sym_a"))
+            .await
+            .unwrap();
+        store
+            .insert_symbol(&make_symbol("count_sym_b"))
+            .await
+            .unwrap();
+        store
+            .insert_symbol(&make_symbol("count_sym_c"))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.symbol_count().await.unwrap(),
+            3,
+            "symbol_count should equal insert count"
+        );
+    }
 }
 
 #[test]
@@ -219,7 +533,18 @@ fn test_unified_graph_store_clone() {
         codebase_path: PathBuf::from("/test"),
         db_path: PathBuf::from("/test/graph.db"),
         backend_kind: BackendKind::SQLite,
-        references: std::sync::Mutex::new(Vec::new()),
+        backend: Box::new(super::sqlite::SqliteBackend::new(std::path::Path::new(
+            "/test/graph.db",
+        ))),
+        index_breaker: Arc::new(crate::circuit::CircuitBreaker::with_defaults("magellan")),
+        walk_options: crate::walk::WalkOptions::default(),
+        indexer_config: crate::indexing::IndexerConfig::default(),
+        cfg_cache: Arc::default(),
+        ref_index: Arc::default(),
+        graph_readers: Arc::new(crate::pool::GraphReaderPool::new("/test/graph.db", 1)),
+        #[cfg(feature = "semantic")]
+        embedder: Arc::new(crate::search::embedding::HashingEmbedder::default()),
+        read_only: false,
     };
 
     let cloned = store.clone();
@@ -235,7 +560,18 @@ fn test_unified_graph_store_debug() {
         codebase_path: PathBuf::from("/test"),
         db_path: PathBuf::from("/test/graph.db"),
         backend_kind: BackendKind::SQLite,
-        references: std::sync::Mutex::new(Vec::new()),
+        backend: Box::new(super::sqlite::SqliteBackend::new(std::path::Path::new(
+            "/test/graph.db",
+        ))),
+        index_breaker: Arc::new(crate::circuit::CircuitBreaker::with_defaults("magellan")),
+        walk_options: crate::walk::WalkOptions::default(),
+        indexer_config: crate::indexing::IndexerConfig::default(),
+        cfg_cache: Arc::default(),
+        ref_index: Arc::default(),
+        graph_readers: Arc::new(crate::pool::GraphReaderPool::new("/test/graph.db", 1)),
+        #[cfg(feature = "semantic")]
+        embedder: Arc::new(crate::search::embedding::HashingEmbedder::default()),
+        read_only: false,
     };
 
     let debug_str = format!("{:?}", store);
@@ -346,14 +682,19 @@ fn make_symbol(name: &str) -> Symbol {
             line_number: 1,
         },
         parent_id: None,
+        visibility: Visibility::default(),
+        body_hash: None,
         metadata: serde_json::Value::Null,
     }
 }
 
-async fn isolated_store() -> (UnifiedGraphStore, tempfile::TempDir) {
+/// Backends the symbol tests run against.
+const SUITE_BACKENDS: [BackendKind; 2] = [BackendKind::SQLite, BackendKind::Memory];
+
+async fn isolated_store(kind: BackendKind) -> (UnifiedGraphStore, tempfile::TempDir) {
     let dir = tempfile::tempdir().unwrap();
     let db_path = dir.path().join("test.db");
-    let store = UnifiedGraphStore::open_with_path(dir.path(), &db_path, BackendKind::SQLite)
+    let store = UnifiedGraphStore::open_with_path(dir.path(), &db_path, kind)
         .await
         .unwrap();
     (store, dir)
@@ -361,29 +702,166 @@ async fn isolated_store() -> (UnifiedGraphStore, tempfile::TempDir) {
 
 #[tokio::test]
 async fn test_insert_symbol_unique_ids() {
-    let (store, _dir) = isolated_store().await;
-    let id1 = store.insert_symbol(&make_symbol("alpha_fn")).await.unwrap();
-    let id2 = store.insert_symbol(&make_symbol("beta_fn")).await.unwrap();
-    assert_ne!(id1, id2, "each insert should return a unique ID");
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        let id1 = store.insert_symbol(&make_symbol("alpha_fn")).await.unwrap();
+        let id2 = store.insert_symbol(&make_symbol("beta_fn")).await.unwrap();
+        assert_ne!(id1, id2, "each insert should return a unique ID");
+    }
 }
 
 #[tokio::test]
 async fn test_symbol_exists_after_insert() {
-    let (store, _dir) = isolated_store().await;
-    let id = store.insert_symbol(&make_symbol("check_fn")).await.unwrap();
-    assert!(
-        store.symbol_exists(id).await.unwrap(),
-        "symbol should exist after insert"
-    );
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        let id = store.insert_symbol(&make_symbol("check_fn")).await.unwrap();
+        assert!(
+            store.symbol_exists(id).await.unwrap(),
+            "symbol should exist after insert"
+        );
+    }
 }
 
 #[tokio::test]
 async fn test_query_symbols_finds_inserted() {
-    let (store, _dir) = isolated_store().await;
-    store
-        .insert_symbol(&make_symbol("my_unique_query_target"))
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        store
+            .insert_symbol(&make_symbol("my_unique_query_target"))
+            .await
+            .unwrap();
+        let results = store.query_symbols("my_unique_query_target").await.unwrap();
+        assert!(!results.is_empty(), "query should find the inserted symbol");
+    }
+}
+
+#[tokio::test]
+async fn test_insert_symbols_batch_of_1000() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        let symbols: Vec<Symbol> = (0..1000)
+            .map(|i| make_symbol(&format!("batch_fn_{}", i)))
+            .collect();
+
+        let ids = store.insert_symbols_batch(&symbols).await.unwrap();
+
+        assert_eq!(ids.len(), 1000);
+        assert_eq!(store.symbol_count().await.unwrap(), 1000);
+        // Ids come back in insertion order
+        assert!(ids.windows(2).all(|w| w[0].0 < w[1].0));
+        let found = store.query_symbols("batch_fn_999").await.unwrap();
+        assert_eq!(found[0].id, ids[999]);
+    }
+}
+
+#[tokio::test]
+async fn test_insert_symbols_batch_is_all_or_nothing() {
+    for kind in SUITE_BACKENDS {
+        let (store, _dir) = isolated_store(kind).await;
+        let mut symbols: Vec<Symbol> = (0..1000)
+            .map(|i| make_symbol(&format!("batch_fn_{}", i)))
+            .collect();
+        // A node without a name is rejected by the graph
+        symbols[500] = make_symbol("");
+
+        assert!(store.insert_symbols_batch(&symbols).await.is_err());
+        assert_eq!(store.symbol_count().await.unwrap(), 0);
+        assert!(store.insert_symbols_batch(&[]).await.unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_insert_references_batch() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = UnifiedGraphStore::open(dir.path(), BackendKind::NativeV3)
+        .await
+        .unwrap();
+    let references: Vec<Reference> = (0..1000)
+        .map(|i| Reference {
+            from: SymbolId(i),
+            to: SymbolId(i % 10),
+            from_name: None,
+            to_name: None,
+            kind: ReferenceKind::Call,
+            location: Location {
+                file_path: PathBuf::from("src/lib.rs"),
+                byte_start: 0,
+                byte_end: 0,
+                line_number: i as usize,
+            },
+        })
+        .collect();
+
+    store.insert_references_batch(&references).await.unwrap();
+
+    assert_eq!(
+        store.query_references(SymbolId(3)).await.unwrap().len(),
+        100
+    );
+}
+
+#[tokio::test]
+async fn test_memory_backend_stores_references() {
+    let (store, _dir) = isolated_store(BackendKind::Memory).await;
+    let reference = Reference {
+        from: SymbolId(1),
+        to: SymbolId(2),
+        from_name: None,
+        to_name: Some("target".to_string()),
+        kind: ReferenceKind::Call,
+        location: Location {
+            file_path: PathBuf::from("src/lib.rs"),
+            byte_start: 0,
+            byte_end: 0,
+            line_number: 3,
+        },
+    };
+    store.insert_reference(&reference).await.unwrap();
+
+    assert_eq!(
+        store.query_references(SymbolId(2)).await.unwrap(),
+        vec![reference]
+    );
+    assert_eq!(
+        store
+            .query_references_for_symbol("target")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(store.reference_edges(), vec![(SymbolId(1), SymbolId(2))]);
+
+    // Clones share the in-memory tables
+    let clone = store.clone();
+    clone.insert_symbol(&make_symbol("shared")).await.unwrap();
+    assert_eq!(store.symbol_count().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_custom_backend_must_be_registered() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("test.db");
+    let missing =
+        UnifiedGraphStore::open_with_path(dir.path(), &db_path, BackendKind::Custom("unknown"))
+            .await
+            .unwrap_err();
+    assert!(matches!(missing, ForgeError::BackendNotAvailable(_)));
+
+    register_backend("test-scratch", |codebase, db| {
+        create_backend(BackendKind::Memory, codebase, db)
+    });
+    let store = UnifiedGraphStore::open_with_path(
+        dir.path(),
+        &db_path,
+        BackendKind::Custom("test-scratch"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(store.backend_kind().to_string(), "test-scratch");
+    let id = store
+        .insert_symbol(&make_symbol("custom_fn"))
         .await
         .unwrap();
-    let results = store.query_symbols("my_unique_query_target").await.unwrap();
-    assert!(!results.is_empty(), "query should find the inserted symbol");
+    assert!(store.symbol_exists(id).await.unwrap());
 }
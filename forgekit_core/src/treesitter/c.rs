@@ -2,11 +2,25 @@ use crate::cfg::TestCfg;
 use crate::error::{ForgeError, Result};
 use crate::types::BlockId;
 
-use super::{CfgExtractor, FunctionInfo, SupportedLanguage};
+use super::{CfgExtractor, CfgGrammarSpec, FunctionInfo, SupportedLanguage};
 
 impl CfgExtractor {
     /// Extract CFG from C source code
     pub fn extract_c(source: &str) -> Result<Vec<FunctionInfo>> {
+        Self::extract_c_with_spec(source, &CfgGrammarSpec::for_language(SupportedLanguage::C))
+    }
+
+    /// Extract CFG from C source code, reading node kinds from `spec`
+    #[tracing::instrument(
+        name = "cfg_extract",
+        level = "debug",
+        skip_all,
+        fields(language = "c", bytes = source.len(), functions = tracing::field::Empty)
+    )]
+    pub(super) fn extract_c_with_spec(
+        source: &str,
+        spec: &CfgGrammarSpec,
+    ) -> Result<Vec<FunctionInfo>> {
         use tree_sitter::Parser;
         use tree_sitter_c;
 
@@ -22,21 +36,23 @@ impl CfgExtractor {
         let root = tree.root_node();
         let mut functions = Vec::new();
 
-        Self::extract_c_functions(source, &root, &mut functions)?;
+        Self::extract_c_functions(source, &root, spec, &mut functions)?;
 
+        tracing::Span::current().record("functions", functions.len());
         Ok(functions)
     }
 
     fn extract_c_functions(
         source: &str,
         node: &tree_sitter::Node,
+        spec: &CfgGrammarSpec,
         functions: &mut Vec<FunctionInfo>,
     ) -> Result<()> {
         let kind = node.kind();
 
         // Look for function definitions
         if kind == "function_definition" {
-            if let Some(func) = Self::parse_c_function(source, node)? {
+            if let Some(func) = Self::parse_c_function(source, node, spec)? {
                 functions.push(func);
             }
         }
@@ -44,13 +60,17 @@ impl CfgExtractor {
         // Recurse into children
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            Self::extract_c_functions(source, &child, functions)?;
+            Self::extract_c_functions(source, &child, spec, functions)?;
         }
 
         Ok(())
     }
 
-    fn parse_c_function(source: &str, node: &tree_sitter::Node) -> Result<Option<FunctionInfo>> {
+    fn parse_c_function(
+        source: &str,
+        node: &tree_sitter::Node,
+        spec: &CfgGrammarSpec,
+    ) -> Result<Option<FunctionInfo>> {
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
 
@@ -114,7 +134,7 @@ impl CfgExtractor {
         }
 
         let cfg = if let Some(body) = body {
-            Self::build_cfg_from_body(source, &body, SupportedLanguage::C)?
+            Self::build_cfg_from_body(source, &body, SupportedLanguage::C, spec)?
         } else {
             // Function declaration without body
             TestCfg::new(BlockId(0))
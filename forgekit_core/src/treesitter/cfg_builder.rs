@@ -2,6 +2,7 @@ use crate::cfg::TestCfg;
 use crate::error::Result;
 use crate::types::BlockId;
 
+use super::grammar::{CfgGrammarSpec, CfgNodeRole, Grammar};
 use super::{CfgExtractor, SupportedLanguage};
 
 impl CfgExtractor {
@@ -9,7 +10,9 @@ impl CfgExtractor {
         source: &str,
         body_node: &tree_sitter::Node,
         lang: SupportedLanguage,
+        spec: &CfgGrammarSpec,
     ) -> Result<TestCfg> {
+        let grammar = Grammar { lang, spec };
         let mut cfg = TestCfg::new(BlockId(0));
         let mut block_counter = 1i64;
         let mut block_stack: Vec<BlockId> = vec![BlockId(0)];
@@ -22,7 +25,7 @@ impl CfgExtractor {
             &mut block_counter,
             &mut block_stack,
             &mut loop_stack,
-            lang,
+            grammar,
         )?;
 
         // Mark last block as exit
@@ -40,13 +43,41 @@ impl CfgExtractor {
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
-        let kind = node.kind();
+        let Some(role) = grammar.role(node) else {
+            // Other nodes: recurse into children
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                Self::process_cfg_node(
+                    source,
+                    &child,
+                    cfg,
+                    counter,
+                    block_stack,
+                    loop_stack,
+                    grammar,
+                )?;
+            }
+            return Ok(());
+        };
+
+        match role {
+            // Python if/elif/else chains
+            CfgNodeRole::Branch if grammar.lang == SupportedLanguage::Python => {
+                Self::process_python_if(
+                    source,
+                    node,
+                    cfg,
+                    counter,
+                    block_stack,
+                    loop_stack,
+                    grammar,
+                )?;
+            }
 
-        match kind {
             // If statement (C, Java, Rust)
-            "if_statement" | "if_expression" | "if_let_expression" => {
+            CfgNodeRole::Branch => {
                 Self::process_if_statement(
                     source,
                     node,
@@ -54,41 +85,46 @@ impl CfgExtractor {
                     counter,
                     block_stack,
                     loop_stack,
-                    lang,
+                    grammar,
                 )?;
             }
 
-            // Loops (C, Java style)
-            "for_statement" | "while_statement" | "do_statement" => {
-                Self::process_loop(source, node, cfg, counter, block_stack, loop_stack, lang)?;
+            // Python try/except/else/finally
+            CfgNodeRole::Try => {
+                Self::process_python_try(
+                    source,
+                    node,
+                    cfg,
+                    counter,
+                    block_stack,
+                    loop_stack,
+                    grammar,
+                )?;
             }
 
-            // Rust loops
-            "loop_expression" => {
-                // Rust infinite loop: loop { ... }
-                Self::process_rust_loop(source, node, cfg, counter, block_stack, loop_stack, lang)?;
+            // Nested definitions do not run their bodies in place
+            CfgNodeRole::Nested => {}
+
+            // for, while, do-while, while let
+            CfgNodeRole::Loop => {
+                Self::process_loop(source, node, cfg, counter, block_stack, loop_stack, grammar)?;
             }
 
-            "while_expression" | "while_let_expression" => {
-                // Rust while and while let
-                Self::process_rust_while(
+            // Rust infinite loop: loop { ... }
+            CfgNodeRole::InfiniteLoop => {
+                Self::process_rust_loop(
                     source,
                     node,
                     cfg,
                     counter,
                     block_stack,
                     loop_stack,
-                    lang,
+                    grammar,
                 )?;
             }
 
-            "for_expression" => {
-                // Rust for loop: for x in iter { ... }
-                Self::process_rust_for(source, node, cfg, counter, block_stack, loop_stack, lang)?;
-            }
-
             // Match expression (Rust)
-            "match_expression" | "match_block" => {
+            CfgNodeRole::Match => {
                 Self::process_rust_match(
                     source,
                     node,
@@ -96,33 +132,54 @@ impl CfgExtractor {
                     counter,
                     block_stack,
                     loop_stack,
-                    lang,
+                    grammar,
                 )?;
             }
 
             // Switch (C)
-            "switch_statement" => {
-                Self::process_switch(source, node, cfg, counter, block_stack, loop_stack, lang)?;
+            CfgNodeRole::Switch => {
+                Self::process_switch(source, node, cfg, counter, block_stack, loop_stack, grammar)?;
             }
 
             // Return statements (all languages)
-            "return_statement" | "return_expression" => {
+            CfgNodeRole::Return => {
                 if let Some(current) = block_stack.last() {
                     cfg.add_exit(*current);
+                    if Self::returns_err(source, node) {
+                        let reason = format!("`return Err(..)` on line {}", Self::line_of(node));
+                        cfg.add_error_with_reason(*current, reason);
+                    }
                 }
             }
 
-            // Break statement - jump to loop exit
-            "break_statement" | "break_expression" => {
-                if let Some(loop_header) = loop_stack.last() {
+            // Thrown exceptions (Java, Python) leave the function on an error path
+            CfgNodeRole::Throw => {
+                if let Some(current) = block_stack.last() {
+                    cfg.add_exit(*current);
+                    let keyword = if grammar.lang == SupportedLanguage::Python {
+                        "raise"
+                    } else {
+                        "throw"
+                    };
+                    let reason = format!("`{}` on line {}", keyword, Self::line_of(node));
+                    cfg.add_error_with_reason(*current, reason);
+                }
+            }
+
+            // Diverging macros (Rust): panic!, unreachable!, todo!, unimplemented!
+            CfgNodeRole::Macro => {
+                if let Some(name) = Self::panicking_macro(source, node) {
                     if let Some(current) = block_stack.last() {
-                        cfg.add_edge(*current, *loop_header);
+                        cfg.add_exit(*current);
+                        let reason = format!("`{}!` on line {}", name, Self::line_of(node));
+                        cfg.add_error_with_reason(*current, reason);
                     }
                 }
             }
 
+            // Break statement - jump to loop exit
             // Continue statement - jump back to loop header
-            "continue_statement" => {
+            CfgNodeRole::Break | CfgNodeRole::Continue => {
                 if let Some(loop_header) = loop_stack.last() {
                     if let Some(current) = block_stack.last() {
                         cfg.add_edge(*current, *loop_header);
@@ -131,9 +188,19 @@ impl CfgExtractor {
             }
 
             // Compound statements / blocks - process children
-            "compound_statement" | "block" => {
+            CfgNodeRole::Block => {
                 let mut cursor = node.walk();
+                let mut diverged = false;
                 for child in node.children(&mut cursor) {
+                    // Statements after a return cannot run; they go in a
+                    // fresh block with no edge in
+                    if diverged && child.is_named() && !child.kind().contains("comment") {
+                        if let Some(current) = block_stack.last_mut() {
+                            *current = BlockId(*counter);
+                            *counter += 1;
+                        }
+                        diverged = false;
+                    }
                     Self::process_cfg_node(
                         source,
                         &child,
@@ -141,40 +208,58 @@ impl CfgExtractor {
                         counter,
                         block_stack,
                         loop_stack,
-                        lang,
+                        grammar,
                     )?;
+                    diverged = diverged || Self::diverges(source, &child, grammar);
                 }
             }
 
             // Sequential flow - no control flow change
-            "expression_statement"
-            | "declaration"
-            | "local_variable_declaration"
-            | "let_declaration"
-            | "call_expression" => {
-                // These are sequential, no control flow change
-            }
-
-            _ => {
-                // For other nodes, recurse into children
-                let mut cursor = node.walk();
-                for child in node.children(&mut cursor) {
-                    Self::process_cfg_node(
-                        source,
-                        &child,
-                        cfg,
-                        counter,
-                        block_stack,
-                        loop_stack,
-                        lang,
-                    )?;
-                }
-            }
+            CfgNodeRole::Sequential => {}
         }
 
         Ok(())
     }
 
+    /// Whether a return statement/expression returns an `Err(..)` value.
+    fn returns_err(source: &str, node: &tree_sitter::Node) -> bool {
+        let Some(value) = node.named_child(0) else {
+            return false;
+        };
+        value.kind() == "call_expression"
+            && value
+                .child_by_field_name("function")
+                .is_some_and(|f| Self::node_text(source, &f) == "Err")
+    }
+
+    /// Whether the statement `node` always leaves the function: a return,
+    /// throw, raise or panicking macro.
+    fn diverges(source: &str, node: &tree_sitter::Node, grammar: Grammar<'_>) -> bool {
+        match grammar.role(node) {
+            Some(CfgNodeRole::Return | CfgNodeRole::Throw) => true,
+            Some(CfgNodeRole::Macro) => Self::panicking_macro(source, node).is_some(),
+            _ if node.kind() == "expression_statement" => node
+                .named_child(0)
+                .is_some_and(|expr| Self::diverges(source, &expr, grammar)),
+            _ => false,
+        }
+    }
+
+    /// Returns the macro name if `node` invokes a macro that always panics.
+    fn panicking_macro(source: &str, node: &tree_sitter::Node) -> Option<String> {
+        let name = Self::node_text(source, &node.child_by_field_name("macro")?);
+        matches!(
+            name.as_str(),
+            "panic" | "unreachable" | "todo" | "unimplemented"
+        )
+        .then_some(name)
+    }
+
+    /// One-based source line on which `node` starts.
+    fn line_of(node: &tree_sitter::Node) -> usize {
+        node.start_position().row + 1
+    }
+
     fn process_if_statement(
         source: &str,
         node: &tree_sitter::Node,
@@ -182,7 +267,7 @@ impl CfgExtractor {
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
         let cond_block = block_stack.last().copied().unwrap_or(BlockId(0));
 
@@ -205,26 +290,31 @@ impl CfgExtractor {
         let mut cursor = node.walk();
 
         for child in node.children(&mut cursor) {
-            match child.kind() {
-                "compound_statement" | "block" | "expression_statement" => {
-                    if then_body.is_none() {
-                        then_body = Some(child);
-                    } else {
-                        else_body = Some(child);
-                    }
-                }
-                "if_statement" => {
-                    // else-if
+            let role = grammar.role(&child);
+            if role == Some(CfgNodeRole::Branch) {
+                // else-if
+                else_body = Some(child);
+            } else if role == Some(CfgNodeRole::Block) || child.kind() == "expression_statement" {
+                if then_body.is_none() {
+                    then_body = Some(child);
+                } else {
                     else_body = Some(child);
                 }
-                _ => {}
             }
         }
 
         // Process then branch
         block_stack.push(then_block);
         if let Some(then) = then_body {
-            Self::process_cfg_node(source, &then, cfg, counter, block_stack, loop_stack, lang)?;
+            Self::process_cfg_node(
+                source,
+                &then,
+                cfg,
+                counter,
+                block_stack,
+                loop_stack,
+                grammar,
+            )?;
         }
         if let Some(current) = block_stack.pop() {
             cfg.add_edge(current, merge_block);
@@ -233,7 +323,15 @@ impl CfgExtractor {
         // Process else branch
         block_stack.push(else_block);
         if let Some(else_) = else_body {
-            Self::process_cfg_node(source, &else_, cfg, counter, block_stack, loop_stack, lang)?;
+            Self::process_cfg_node(
+                source,
+                &else_,
+                cfg,
+                counter,
+                block_stack,
+                loop_stack,
+                grammar,
+            )?;
         }
         if let Some(current) = block_stack.pop() {
             cfg.add_edge(current, merge_block);
@@ -245,125 +343,223 @@ impl CfgExtractor {
         Ok(())
     }
 
-    fn process_loop(
+    /// Python `if`: the consequence and every `elif` consequence get their
+    /// own branch block, and each `elif` condition is tested in a new block
+    /// reached when the previous condition fails.
+    fn process_python_if(
         source: &str,
         node: &tree_sitter::Node,
         cfg: &mut TestCfg,
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
-        let pre_block = block_stack.last().copied().unwrap_or(BlockId(0));
-
-        // Create header block (condition check)
-        let header_block = BlockId(*counter);
-        *counter += 1;
-        cfg.add_edge(pre_block, header_block);
-
-        // Create body block
-        let body_block = BlockId(*counter);
-        *counter += 1;
-        cfg.add_edge(header_block, body_block);
-
-        // Create exit block
-        let exit_block = BlockId(*counter);
+        let mut cond_block = block_stack.last().copied().unwrap_or(BlockId(0));
+        let merge_block = BlockId(*counter);
         *counter += 1;
-        cfg.add_edge(header_block, exit_block);
-
-        // Push loop context
-        loop_stack.push(header_block);
 
-        // Find and process body
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            if child.kind() == "compound_statement" || child.kind() == "block" {
-                block_stack.push(body_block);
+        let branch = |body: Option<tree_sitter::Node>,
+                      from: BlockId,
+                      cfg: &mut TestCfg,
+                      counter: &mut i64,
+                      block_stack: &mut Vec<BlockId>,
+                      loop_stack: &mut Vec<BlockId>|
+         -> Result<()> {
+            let branch_block = BlockId(*counter);
+            *counter += 1;
+            cfg.add_edge(from, branch_block);
+            block_stack.push(branch_block);
+            if let Some(body) = body {
                 Self::process_cfg_node(
                     source,
-                    &child,
+                    &body,
                     cfg,
                     counter,
                     block_stack,
                     loop_stack,
-                    lang,
+                    grammar,
                 )?;
-                if let Some(current) = block_stack.pop() {
-                    // Back edge to header
-                    cfg.add_edge(current, header_block);
+            }
+            if let Some(current) = block_stack.pop() {
+                cfg.add_edge(current, merge_block);
+            }
+            Ok(())
+        };
+
+        branch(
+            node.child_by_field_name("consequence"),
+            cond_block,
+            cfg,
+            counter,
+            block_stack,
+            loop_stack,
+        )?;
+
+        let mut has_else = false;
+        let mut cursor = node.walk();
+        for clause in node.children_by_field_name("alternative", &mut cursor) {
+            match clause.kind() {
+                "elif_clause" => {
+                    let elif_block = BlockId(*counter);
+                    *counter += 1;
+                    cfg.add_edge(cond_block, elif_block);
+                    cond_block = elif_block;
+                    branch(
+                        clause.child_by_field_name("consequence"),
+                        cond_block,
+                        cfg,
+                        counter,
+                        block_stack,
+                        loop_stack,
+                    )?;
                 }
-                break;
+                "else_clause" => {
+                    has_else = true;
+                    branch(
+                        clause.child_by_field_name("body"),
+                        cond_block,
+                        cfg,
+                        counter,
+                        block_stack,
+                        loop_stack,
+                    )?;
+                }
+                _ => {}
             }
         }
 
-        loop_stack.pop();
+        // Without `else`, the last failed condition falls through
+        if !has_else {
+            cfg.add_edge(cond_block, merge_block);
+        }
 
-        // Continue with exit block
-        block_stack.push(exit_block);
+        block_stack.push(merge_block);
 
         Ok(())
     }
 
-    fn process_switch(
+    /// Python `try`: each `except` handler branches from the start of the
+    /// `try` body, `else` runs after a body that did not raise, and
+    /// `finally` runs after all of them.
+    fn process_python_try(
         source: &str,
         node: &tree_sitter::Node,
         cfg: &mut TestCfg,
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
-        let switch_block = block_stack.last().copied().unwrap_or(BlockId(0));
+        let pre_block = block_stack.last().copied().unwrap_or(BlockId(0));
+
+        let try_block = BlockId(*counter);
+        *counter += 1;
+        cfg.add_edge(pre_block, try_block);
         let merge_block = BlockId(*counter);
         *counter += 1;
 
-        // Find switch body
+        let mut handlers = Vec::new();
+        let mut else_body = None;
+        let mut finally_body = None;
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "compound_statement" {
-                // Process case statements
-                let mut case_cursor = child.walk();
-                for case in child.children(&mut case_cursor) {
-                    if case.kind() == "case_statement" || case.kind() == "labeled_statement" {
-                        let case_block = BlockId(*counter);
-                        *counter += 1;
-                        cfg.add_edge(switch_block, case_block);
-
-                        block_stack.push(case_block);
-                        Self::process_cfg_node(
-                            source,
-                            &case,
-                            cfg,
-                            counter,
-                            block_stack,
-                            loop_stack,
-                            lang,
-                        )?;
-                        if let Some(current) = block_stack.pop() {
-                            cfg.add_edge(current, merge_block);
-                        }
-                    }
+            match child.kind() {
+                "except_clause" | "except_group_clause" => {
+                    handlers.push(Self::python_clause_block(&child))
                 }
+                "else_clause" => else_body = child.child_by_field_name("body"),
+                "finally_clause" => finally_body = Self::python_clause_block(&child),
+                _ => {}
+            }
+        }
+
+        // Try body, then `else` on the no-exception path
+        block_stack.push(try_block);
+        if let Some(body) = node.child_by_field_name("body") {
+            Self::process_cfg_node(
+                source,
+                &body,
+                cfg,
+                counter,
+                block_stack,
+                loop_stack,
+                grammar,
+            )?;
+        }
+        if let Some(else_) = else_body {
+            Self::process_cfg_node(
+                source,
+                &else_,
+                cfg,
+                counter,
+                block_stack,
+                loop_stack,
+                grammar,
+            )?;
+        }
+        if let Some(current) = block_stack.pop() {
+            cfg.add_edge(current, merge_block);
+        }
+
+        for handler in handlers {
+            let handler_block = BlockId(*counter);
+            *counter += 1;
+            cfg.add_edge(try_block, handler_block);
+            block_stack.push(handler_block);
+            if let Some(handler) = handler {
+                Self::process_cfg_node(
+                    source,
+                    &handler,
+                    cfg,
+                    counter,
+                    block_stack,
+                    loop_stack,
+                    grammar,
+                )?;
+            }
+            if let Some(current) = block_stack.pop() {
+                cfg.add_edge(current, merge_block);
             }
         }
 
         block_stack.push(merge_block);
+        if let Some(finally) = finally_body {
+            Self::process_cfg_node(
+                source,
+                &finally,
+                cfg,
+                counter,
+                block_stack,
+                loop_stack,
+                grammar,
+            )?;
+        }
+
         Ok(())
     }
 
-    fn process_rust_loop(
+    /// The `block` child of a Python `except` or `finally` clause.
+    fn python_clause_block<'a>(clause: &tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
+        let mut cursor = clause.walk();
+        let block = clause
+            .children(&mut cursor)
+            .find(|child| child.kind() == "block");
+        block
+    }
+
+    fn process_loop(
         source: &str,
         node: &tree_sitter::Node,
         cfg: &mut TestCfg,
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
-        // Rust infinite loop: loop { ... }
         let pre_block = block_stack.last().copied().unwrap_or(BlockId(0));
 
-        // Create header block (loop entry)
+        // Create header block (condition check)
         let header_block = BlockId(*counter);
         *counter += 1;
         cfg.add_edge(pre_block, header_block);
@@ -373,17 +569,18 @@ impl CfgExtractor {
         *counter += 1;
         cfg.add_edge(header_block, body_block);
 
-        // Create exit block (for break)
+        // Create exit block
         let exit_block = BlockId(*counter);
         *counter += 1;
+        cfg.add_edge(header_block, exit_block);
 
-        // Push loop context (header is also exit target for break)
+        // Push loop context
         loop_stack.push(header_block);
 
-        // Find and process body (block)
+        // Find and process body
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "block" {
+            if grammar.role(&child) == Some(CfgNodeRole::Block) {
                 block_stack.push(body_block);
                 Self::process_cfg_node(
                     source,
@@ -392,10 +589,10 @@ impl CfgExtractor {
                     counter,
                     block_stack,
                     loop_stack,
-                    lang,
+                    grammar,
                 )?;
                 if let Some(current) = block_stack.pop() {
-                    // Back edge to header (infinite loop)
+                    // Back edge to header
                     cfg.add_edge(current, header_block);
                 }
                 break;
@@ -406,84 +603,70 @@ impl CfgExtractor {
 
         // Continue with exit block
         block_stack.push(exit_block);
-        cfg.add_edge(header_block, exit_block);
 
         Ok(())
     }
 
-    fn process_rust_while(
+    fn process_switch(
         source: &str,
         node: &tree_sitter::Node,
         cfg: &mut TestCfg,
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
-        // Rust while and while let
-        let pre_block = block_stack.last().copied().unwrap_or(BlockId(0));
-
-        // Create header block (condition check)
-        let header_block = BlockId(*counter);
-        *counter += 1;
-        cfg.add_edge(pre_block, header_block);
-
-        // Create body block
-        let body_block = BlockId(*counter);
-        *counter += 1;
-        cfg.add_edge(header_block, body_block);
-
-        // Create exit block
-        let exit_block = BlockId(*counter);
+        let switch_block = block_stack.last().copied().unwrap_or(BlockId(0));
+        let merge_block = BlockId(*counter);
         *counter += 1;
-        cfg.add_edge(header_block, exit_block);
-
-        // Push loop context
-        loop_stack.push(header_block);
 
-        // Find and process body
+        // Find switch body
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "block" {
-                block_stack.push(body_block);
-                Self::process_cfg_node(
-                    source,
-                    &child,
-                    cfg,
-                    counter,
-                    block_stack,
-                    loop_stack,
-                    lang,
-                )?;
-                if let Some(current) = block_stack.pop() {
-                    // Back edge to header
-                    cfg.add_edge(current, header_block);
+            if child.kind() == "compound_statement" {
+                // Process case statements
+                let mut case_cursor = child.walk();
+                for case in child.children(&mut case_cursor) {
+                    if case.kind() == "case_statement" || case.kind() == "labeled_statement" {
+                        let case_block = BlockId(*counter);
+                        *counter += 1;
+                        cfg.add_edge(switch_block, case_block);
+
+                        block_stack.push(case_block);
+                        Self::process_cfg_node(
+                            source,
+                            &case,
+                            cfg,
+                            counter,
+                            block_stack,
+                            loop_stack,
+                            grammar,
+                        )?;
+                        if let Some(current) = block_stack.pop() {
+                            cfg.add_edge(current, merge_block);
+                        }
+                    }
                 }
-                break;
             }
         }
 
-        loop_stack.pop();
-
-        // Continue with exit block
-        block_stack.push(exit_block);
-
+        block_stack.push(merge_block);
         Ok(())
     }
 
-    fn process_rust_for(
+    fn process_rust_loop(
         source: &str,
         node: &tree_sitter::Node,
         cfg: &mut TestCfg,
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
-        // Rust for loop: for x in iter { ... }
+        // Rust infinite loop: loop { ... }
         let pre_block = block_stack.last().copied().unwrap_or(BlockId(0));
 
-        // Create header block
+        // Create header block (loop entry)
         let header_block = BlockId(*counter);
         *counter += 1;
         cfg.add_edge(pre_block, header_block);
@@ -493,18 +676,17 @@ impl CfgExtractor {
         *counter += 1;
         cfg.add_edge(header_block, body_block);
 
-        // Create exit block
+        // Create exit block (for break)
         let exit_block = BlockId(*counter);
         *counter += 1;
-        cfg.add_edge(header_block, exit_block);
 
-        // Push loop context
+        // Push loop context (header is also exit target for break)
         loop_stack.push(header_block);
 
-        // Find and process body
+        // Find and process body (block)
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "block" {
+            if grammar.role(&child) == Some(CfgNodeRole::Block) {
                 block_stack.push(body_block);
                 Self::process_cfg_node(
                     source,
@@ -513,10 +695,10 @@ impl CfgExtractor {
                     counter,
                     block_stack,
                     loop_stack,
-                    lang,
+                    grammar,
                 )?;
                 if let Some(current) = block_stack.pop() {
-                    // Back edge to header
+                    // Back edge to header (infinite loop)
                     cfg.add_edge(current, header_block);
                 }
                 break;
@@ -527,6 +709,7 @@ impl CfgExtractor {
 
         // Continue with exit block
         block_stack.push(exit_block);
+        cfg.add_edge(header_block, exit_block);
 
         Ok(())
     }
@@ -538,7 +721,7 @@ impl CfgExtractor {
         counter: &mut i64,
         block_stack: &mut Vec<BlockId>,
         loop_stack: &mut Vec<BlockId>,
-        lang: SupportedLanguage,
+        grammar: Grammar<'_>,
     ) -> Result<()> {
         // Rust match expression
         let match_block = block_stack.last().copied().unwrap_or(BlockId(0));
@@ -565,7 +748,7 @@ impl CfgExtractor {
                             counter,
                             block_stack,
                             loop_stack,
-                            lang,
+                            grammar,
                         )?;
                         if let Some(current) = block_stack.pop() {
                             cfg.add_edge(current, merge_block);
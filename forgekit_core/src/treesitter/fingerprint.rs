@@ -0,0 +1,123 @@
+//! Fingerprints of the items a source file defines
+//!
+//! Each top-level item is hashed over its token stream with comments left
+//! out, so whitespace and comment edits keep every fingerprint while any
+//! change to a signature, body, field or literal changes one.
+
+use crate::error::{ForgeError, Result};
+
+use super::{CfgExtractor, SupportedLanguage};
+
+/// A top-level item and the hash of its tokens
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemFingerprint {
+    /// Tree-sitter node kind, such as `struct_item` or `function_definition`
+    pub kind: String,
+    /// The item's name, or its implemented type for impl blocks; empty
+    /// for items without one
+    pub name: String,
+    /// BLAKE3 hash of the item's tokens, excluding comments
+    pub hash: [u8; 32],
+}
+
+impl CfgExtractor {
+    /// Fingerprints every top-level item in `source`, in source order.
+    ///
+    /// Functions, types, impls, traits, imports and constants are all
+    /// covered; impl and module blocks are hashed whole, so an edit to any
+    /// item inside them changes the block's fingerprint.
+    pub fn item_fingerprints(
+        source: &str,
+        lang: SupportedLanguage,
+    ) -> Result<Vec<ItemFingerprint>> {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.grammar()).map_err(|e| {
+            ForgeError::Parse(format!("Failed to set {:?} language: {:?}", lang, e))
+        })?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ForgeError::Parse(format!("Failed to parse {:?} code", lang)))?;
+
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let items = root
+            .named_children(&mut cursor)
+            .filter(|item| !super::strip::is_comment(item.kind()))
+            .map(|item| ItemFingerprint {
+                kind: item.kind().to_string(),
+                name: item_name(source, &item),
+                hash: token_hash(source, &item),
+            })
+            .collect();
+        Ok(items)
+    }
+}
+
+fn item_name(source: &str, item: &tree_sitter::Node) -> String {
+    item.child_by_field_name("name")
+        .or_else(|| item.child_by_field_name("type"))
+        .or_else(|| item.child_by_field_name("declarator"))
+        .map(|node| source[node.byte_range()].to_string())
+        .unwrap_or_default()
+}
+
+/// Hashes the leaf tokens under `node`, skipping comments.
+fn token_hash(source: &str, node: &tree_sitter::Node) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    let mut cursor = node.walk();
+    loop {
+        let current = cursor.node();
+        let comment = super::strip::is_comment(current.kind());
+        if !comment && current.child_count() == 0 {
+            hasher.update(source[current.byte_range()].as_bytes());
+            // Keeps `a b` and `ab` apart
+            hasher.update(&[0]);
+        }
+        if !comment && cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.node() == *node {
+                return *hasher.finalize().as_bytes();
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            cursor.goto_parent();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprints(source: &str) -> Vec<ItemFingerprint> {
+        CfgExtractor::item_fingerprints(source, SupportedLanguage::Rust).unwrap()
+    }
+
+    #[test]
+    fn test_fingerprints_ignore_comments_and_whitespace() {
+        let before = fingerprints("struct P { x: i32 }\nfn f(a: u8) -> u8 { a }\n");
+        let after = fingerprints(
+            "/// A point\nstruct P {\n    x: i32 // x\n}\n\nfn f(a: u8)\n    -> u8 {\n    a\n}\n",
+        );
+        assert_eq!(before, after);
+        assert_eq!(before[0].kind, "struct_item");
+        assert_eq!(before[0].name, "P");
+        assert_eq!(before[1].name, "f");
+    }
+
+    #[test]
+    fn test_fingerprints_see_signatures_fields_and_literals() {
+        let base = fingerprints("struct P { x: i32 }\nfn f(a: u8) -> u8 { g(a, 1) }\n");
+        for edited in [
+            "struct P { x: i64 }\nfn f(a: u8) -> u8 { g(a, 1) }\n",
+            "struct P { x: i32 }\nfn f(a: u16) -> u8 { g(a, 1) }\n",
+            "struct P { x: i32 }\nfn f(a: u8) -> u8 { h(a, 1) }\n",
+            "struct P { x: i32 }\nfn f(a: u8) -> u8 { g(a, 2) }\n",
+        ] {
+            assert_ne!(base, fingerprints(edited), "{edited}");
+        }
+    }
+}
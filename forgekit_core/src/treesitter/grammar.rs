@@ -0,0 +1,155 @@
+//! Node-kind tables that drive CFG construction
+//!
+//! Tree-sitter grammars name their nodes freely and occasionally rename
+//! them between versions. [`CfgGrammarSpec`] records what each node kind
+//! means for control flow, so a newer grammar can be supported by editing
+//! the table instead of the builder.
+
+use std::collections::HashMap;
+
+use super::SupportedLanguage;
+
+/// What a tree-sitter node kind means for control flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CfgNodeRole {
+    /// Two-way branch with an optional else (`if`)
+    Branch,
+    /// Loop that tests its condition before each iteration
+    Loop,
+    /// Loop left only through `break` or a return (Rust `loop`)
+    InfiniteLoop,
+    /// Multi-way branch over `match_arm` children
+    Match,
+    /// Multi-way branch over `case` children
+    Switch,
+    /// `try` with `except`, `else` and `finally` clauses (Python)
+    Try,
+    /// Leaves the function normally
+    Return,
+    /// Leaves the function on an error path
+    Throw,
+    /// Jumps out of the innermost loop
+    Break,
+    /// Jumps back to the innermost loop header
+    Continue,
+    /// Macro call that leaves the function when it names a panicking macro
+    Macro,
+    /// Sequence of statements
+    Block,
+    /// Statement without control flow; its children are not visited
+    Sequential,
+    /// Nested definition whose body does not run in place
+    Nested,
+}
+
+/// Mapping from node kinds to their [`CfgNodeRole`]
+///
+/// Kinds without a role are walked through, so control flow nested in
+/// them is still found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CfgGrammarSpec {
+    roles: HashMap<String, CfgNodeRole>,
+}
+
+impl CfgGrammarSpec {
+    /// The node kinds of the grammar bundled for `lang`
+    pub fn for_language(lang: SupportedLanguage) -> Self {
+        use CfgNodeRole::*;
+
+        let mut spec = Self::default()
+            .with_kinds(
+                Branch,
+                ["if_statement", "if_expression", "if_let_expression"],
+            )
+            .with_kinds(
+                Loop,
+                [
+                    "for_statement",
+                    "while_statement",
+                    "do_statement",
+                    "while_expression",
+                    "while_let_expression",
+                    "for_expression",
+                ],
+            )
+            .with_kinds(InfiniteLoop, ["loop_expression"])
+            .with_kinds(Match, ["match_expression", "match_block"])
+            .with_kinds(Switch, ["switch_statement"])
+            .with_kinds(Return, ["return_statement", "return_expression"])
+            .with_kinds(Throw, ["throw_statement", "raise_statement"])
+            .with_kinds(Macro, ["macro_invocation"])
+            .with_kinds(Break, ["break_statement", "break_expression"])
+            .with_kinds(Continue, ["continue_statement"])
+            .with_kinds(Block, ["compound_statement", "block"])
+            .with_kinds(
+                Sequential,
+                [
+                    "declaration",
+                    "local_variable_declaration",
+                    "let_declaration",
+                    "call_expression",
+                ],
+            );
+
+        if lang == SupportedLanguage::Python {
+            spec = spec
+                .with_kinds(Try, ["try_statement"])
+                .with_kinds(Nested, ["function_definition", "class_definition"]);
+        }
+        spec
+    }
+
+    /// Give `kind` the role `role`, replacing any role it had
+    pub fn with_kind(mut self, kind: impl Into<String>, role: CfgNodeRole) -> Self {
+        self.roles.insert(kind.into(), role);
+        self
+    }
+
+    /// Give every kind in `kinds` the role `role`
+    pub fn with_kinds<I>(mut self, role: CfgNodeRole, kinds: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        for kind in kinds {
+            self.roles.insert(kind.into(), role);
+        }
+        self
+    }
+
+    /// Remove the role of `kind`, so it is walked through
+    pub fn without_kind(mut self, kind: &str) -> Self {
+        self.roles.remove(kind);
+        self
+    }
+
+    /// The role of `kind`, if it has one
+    pub fn role(&self, kind: &str) -> Option<CfgNodeRole> {
+        self.roles.get(kind).copied()
+    }
+
+    /// The kinds with role `role`, sorted
+    pub fn kinds(&self, role: CfgNodeRole) -> Vec<&str> {
+        let mut kinds: Vec<&str> = self
+            .roles
+            .iter()
+            .filter(|(_, r)| **r == role)
+            .map(|(kind, _)| kind.as_str())
+            .collect();
+        kinds.sort_unstable();
+        kinds
+    }
+}
+
+/// The language and spec a CFG is being built with
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Grammar<'a> {
+    pub(super) lang: SupportedLanguage,
+    pub(super) spec: &'a CfgGrammarSpec,
+}
+
+impl Grammar<'_> {
+    pub(super) fn role(&self, node: &tree_sitter::Node) -> Option<CfgNodeRole> {
+        self.spec.role(node.kind())
+    }
+}
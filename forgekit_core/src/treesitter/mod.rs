@@ -1,16 +1,24 @@
-//! Tree-sitter based CFG extraction for C, Java, and Rust
+//! Tree-sitter based CFG extraction for C, Java, Python, and Rust
 //!
 //! This module provides real control flow graph extraction using tree-sitter parsers.
-//! Supports C, Java, and Rust languages with full CFG construction.
+//! Supports C, Java, Python, and Rust languages with full CFG construction.
 
 mod c;
 mod cfg_builder;
+mod fingerprint;
+mod grammar;
 mod java;
+mod python;
 mod rust;
+pub(crate) mod strip;
+pub(crate) mod visibility;
 
 use crate::cfg::TestCfg;
 use crate::error::Result;
 
+pub use fingerprint::ItemFingerprint;
+pub use grammar::{CfgGrammarSpec, CfgNodeRole};
+
 /// Extracted function information
 #[derive(Debug, Clone)]
 pub struct FunctionInfo {
@@ -25,9 +33,32 @@ pub struct FunctionInfo {
 pub enum SupportedLanguage {
     C,
     Java,
+    Python,
     Rust,
 }
 
+impl SupportedLanguage {
+    /// The tree-sitter grammar for this language
+    pub(crate) fn grammar(&self) -> tree_sitter::Language {
+        match self {
+            SupportedLanguage::C => tree_sitter_c::LANGUAGE.into(),
+            SupportedLanguage::Java => tree_sitter_java::LANGUAGE.into(),
+            SupportedLanguage::Python => tree_sitter_python::LANGUAGE.into(),
+            SupportedLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
+        }
+    }
+
+    /// The matching [`Language`](crate::types::Language)
+    pub(crate) fn language(&self) -> crate::types::Language {
+        match self {
+            SupportedLanguage::C => crate::types::Language::C,
+            SupportedLanguage::Java => crate::types::Language::Java,
+            SupportedLanguage::Python => crate::types::Language::Python,
+            SupportedLanguage::Rust => crate::types::Language::Rust,
+        }
+    }
+}
+
 /// CFG extractor using tree-sitter
 pub struct CfgExtractor;
 
@@ -37,6 +68,7 @@ impl CfgExtractor {
         match path.extension()?.to_str()? {
             "c" | "h" => Some(SupportedLanguage::C),
             "java" => Some(SupportedLanguage::Java),
+            "py" => Some(SupportedLanguage::Python),
             "rs" => Some(SupportedLanguage::Rust),
             _ => None,
         }
@@ -44,10 +76,24 @@ impl CfgExtractor {
 
     /// Extract CFG based on language
     pub fn extract(source: &str, lang: SupportedLanguage) -> Result<Vec<FunctionInfo>> {
+        Self::extract_with_spec(source, lang, &CfgGrammarSpec::for_language(lang))
+    }
+
+    /// Extract CFG based on language, reading node kinds from `spec`
+    ///
+    /// Use this when the grammar names its nodes differently from the
+    /// bundled one; start from [`CfgGrammarSpec::for_language`] and
+    /// override the kinds that changed.
+    pub fn extract_with_spec(
+        source: &str,
+        lang: SupportedLanguage,
+        spec: &CfgGrammarSpec,
+    ) -> Result<Vec<FunctionInfo>> {
         match lang {
-            SupportedLanguage::C => Self::extract_c(source),
-            SupportedLanguage::Java => Self::extract_java(source),
-            SupportedLanguage::Rust => Self::extract_rust(source),
+            SupportedLanguage::C => Self::extract_c_with_spec(source, spec),
+            SupportedLanguage::Java => Self::extract_java_with_spec(source, spec),
+            SupportedLanguage::Python => Self::extract_python_with_spec(source, spec),
+            SupportedLanguage::Rust => Self::extract_rust_with_spec(source, spec),
         }
     }
 
@@ -77,6 +123,10 @@ mod tests {
             CfgExtractor::detect_language(Path::new("Test.java")),
             Some(SupportedLanguage::Java)
         );
+        assert_eq!(
+            CfgExtractor::detect_language(Path::new("test.py")),
+            Some(SupportedLanguage::Python)
+        );
         assert_eq!(
             CfgExtractor::detect_language(Path::new("test.rs")),
             Some(SupportedLanguage::Rust)
@@ -192,6 +242,102 @@ mod tests {
         assert!(cfg.entry == BlockId(0));
     }
 
+    #[test]
+    fn test_extract_python_nested_conditionals() {
+        let source = r#"
+def classify(n):
+    if n < 0:
+        if n < -100:
+            return "very negative"
+        return "negative"
+    elif n == 0:
+        return "zero"
+    elif n < 10:
+        with open("log") as f:
+            f.write("small")
+        return "small"
+    else:
+        return "large"
+"#;
+
+        let funcs =
+            CfgExtractor::extract_python(source).expect("invariant: valid Python source parses");
+        assert_eq!(funcs.len(), 1);
+        assert_eq!(funcs[0].name, "classify");
+
+        let cfg = &funcs[0].cfg;
+        assert!(cfg.entry == BlockId(0));
+
+        // `if` branch and first `elif` condition
+        let entry_succs = &cfg.successors[&BlockId(0)];
+        assert_eq!(entry_succs.len(), 2);
+
+        // Each of the four outcomes, plus the nested `if`, is its own path
+        let paths = cfg.enumerate_paths();
+        assert_eq!(paths.len(), 5);
+        assert!(paths.iter().all(|p| p.is_normal()));
+    }
+
+    #[test]
+    fn test_extract_python_try_and_raise() {
+        let source = r#"
+def load(path):
+    try:
+        data = read(path)
+    except OSError:
+        raise ValueError("unreadable")
+    finally:
+        close(path)
+    for line in data:
+        if not line:
+            break
+    return data
+"#;
+
+        let funcs =
+            CfgExtractor::extract_python(source).expect("invariant: valid Python source parses");
+        assert_eq!(funcs.len(), 1);
+
+        let paths = funcs[0].cfg.enumerate_paths();
+        let error_paths: Vec<_> = paths.iter().filter(|p| p.is_error()).collect();
+        assert_eq!(error_paths.len(), 1);
+        let (_, reason) = error_paths[0]
+            .error_reason()
+            .expect("error path should carry a reason");
+        assert!(reason.contains("raise"), "reason: {}", reason);
+        assert!(reason.contains("line 6"), "reason: {}", reason);
+        assert!(paths.iter().any(|p| p.is_normal()));
+    }
+
+    #[test]
+    fn test_extract_rust_error_path_reason() {
+        let source = r#"
+            fn parse(n: i32) -> Result<i32, String> {
+                if n < 0 {
+                    return Err("negative".to_string());
+                }
+                Ok(n)
+            }
+        "#;
+
+        let funcs =
+            CfgExtractor::extract_rust(source).expect("invariant: valid Rust source parses");
+        assert_eq!(funcs.len(), 1);
+
+        let paths = funcs[0].cfg.enumerate_paths();
+        let error_paths: Vec<_> = paths.iter().filter(|p| p.is_error()).collect();
+        assert_eq!(error_paths.len(), 1);
+        assert!(paths.iter().any(|p| p.is_normal()));
+
+        let (block, reason) = error_paths[0]
+            .error_reason()
+            .expect("error path should carry a reason");
+        assert_eq!(error_paths[0].exit(), Some(block));
+        assert!(funcs[0].cfg.error_blocks.contains(&block));
+        assert!(reason.contains("return Err"), "reason: {}", reason);
+        assert!(reason.contains("line 4"), "reason: {}", reason);
+    }
+
     #[test]
     fn test_extract_rust_loop() {
         let source = r#"
@@ -255,4 +401,52 @@ mod tests {
         assert_eq!(funcs.len(), 1);
         assert_eq!(funcs[0].name, "classify");
     }
+
+    #[test]
+    fn test_extract_with_spec_remaps_if_statement() {
+        let source = r#"
+            int sign(int n) {
+                if (n < 0) {
+                    return -1;
+                }
+                while (n > 9) {
+                    n = n / 10;
+                }
+                return 1;
+            }
+        "#;
+        let lang = SupportedLanguage::C;
+        let paths = |spec: &CfgGrammarSpec| {
+            let funcs = CfgExtractor::extract_with_spec(source, lang, spec)
+                .expect("invariant: valid C source parses");
+            funcs[0].cfg.enumerate_paths().len()
+        };
+
+        let default = CfgGrammarSpec::for_language(lang);
+        assert_eq!(
+            paths(&default),
+            CfgExtractor::extract(source, lang).unwrap()[0]
+                .cfg
+                .enumerate_paths()
+                .len()
+        );
+
+        // A grammar that renamed `if_statement` leaves the branch unseen
+        let renamed = default
+            .clone()
+            .without_kind("if_statement")
+            .with_kind("if_stmt", CfgNodeRole::Branch);
+        assert_eq!(
+            renamed.kinds(CfgNodeRole::Branch),
+            ["if_expression", "if_let_expression", "if_stmt"]
+        );
+        let unbranched = CfgExtractor::extract_with_spec(source, lang, &renamed).unwrap();
+        assert_eq!(unbranched[0].cfg.detect_loops().len(), 1);
+        assert!(paths(&renamed) < paths(&default));
+
+        // Remapping `if_statement` as a loop adds a back edge
+        let as_loop = default.with_kind("if_statement", CfgNodeRole::Loop);
+        let funcs = CfgExtractor::extract_with_spec(source, lang, &as_loop).unwrap();
+        assert_eq!(funcs[0].cfg.detect_loops().len(), 2);
+    }
 }
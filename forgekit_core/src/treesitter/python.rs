@@ -0,0 +1,98 @@
+use crate::cfg::TestCfg;
+use crate::error::{ForgeError, Result};
+use crate::types::BlockId;
+
+use super::{CfgExtractor, CfgGrammarSpec, FunctionInfo, SupportedLanguage};
+
+impl CfgExtractor {
+    /// Extract CFG from Python source code
+    pub fn extract_python(source: &str) -> Result<Vec<FunctionInfo>> {
+        Self::extract_python_with_spec(
+            source,
+            &CfgGrammarSpec::for_language(SupportedLanguage::Python),
+        )
+    }
+
+    /// Extract CFG from Python source code, reading node kinds from `spec`
+    #[tracing::instrument(
+        name = "cfg_extract",
+        level = "debug",
+        skip_all,
+        fields(language = "python", bytes = source.len(), functions = tracing::field::Empty)
+    )]
+    pub(super) fn extract_python_with_spec(
+        source: &str,
+        spec: &CfgGrammarSpec,
+    ) -> Result<Vec<FunctionInfo>> {
+        use tree_sitter::Parser;
+        use tree_sitter_python;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .map_err(|e| {
+                ForgeError::DatabaseError(format!("Failed to set Python language: {:?}", e))
+            })?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ForgeError::DatabaseError("Failed to parse Python code".to_string()))?;
+
+        let root = tree.root_node();
+        let mut functions = Vec::new();
+
+        Self::extract_python_functions(source, &root, spec, &mut functions)?;
+
+        tracing::Span::current().record("functions", functions.len());
+        Ok(functions)
+    }
+
+    fn extract_python_functions(
+        source: &str,
+        node: &tree_sitter::Node,
+        spec: &CfgGrammarSpec,
+        functions: &mut Vec<FunctionInfo>,
+    ) -> Result<()> {
+        // Look for function and method definitions (including nested ones)
+        if node.kind() == "function_definition" {
+            if let Some(func) = Self::parse_python_function(source, node, spec)? {
+                functions.push(func);
+            }
+        }
+
+        // Recurse into children
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            Self::extract_python_functions(source, &child, spec, functions)?;
+        }
+
+        Ok(())
+    }
+
+    fn parse_python_function(
+        source: &str,
+        node: &tree_sitter::Node,
+        spec: &CfgGrammarSpec,
+    ) -> Result<Option<FunctionInfo>> {
+        let start_byte = node.start_byte();
+        let end_byte = node.end_byte();
+
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| Self::node_text(source, &n))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let cfg = if let Some(body) = node.child_by_field_name("body") {
+            Self::build_cfg_from_body(source, &body, SupportedLanguage::Python, spec)?
+        } else {
+            TestCfg::new(BlockId(0))
+        };
+
+        Ok(Some(FunctionInfo {
+            name,
+            start_byte,
+            end_byte,
+            cfg,
+        }))
+    }
+}
@@ -2,11 +2,28 @@ use crate::cfg::TestCfg;
 use crate::error::{ForgeError, Result};
 use crate::types::BlockId;
 
-use super::{CfgExtractor, FunctionInfo, SupportedLanguage};
+use super::{CfgExtractor, CfgGrammarSpec, FunctionInfo, SupportedLanguage};
 
 impl CfgExtractor {
     /// Extract CFG from Rust source code
     pub fn extract_rust(source: &str) -> Result<Vec<FunctionInfo>> {
+        Self::extract_rust_with_spec(
+            source,
+            &CfgGrammarSpec::for_language(SupportedLanguage::Rust),
+        )
+    }
+
+    /// Extract CFG from Rust source code, reading node kinds from `spec`
+    #[tracing::instrument(
+        name = "cfg_extract",
+        level = "debug",
+        skip_all,
+        fields(language = "rust", bytes = source.len(), functions = tracing::field::Empty)
+    )]
+    pub(super) fn extract_rust_with_spec(
+        source: &str,
+        spec: &CfgGrammarSpec,
+    ) -> Result<Vec<FunctionInfo>> {
         use tree_sitter::Parser;
         use tree_sitter_rust;
 
@@ -24,21 +41,23 @@ impl CfgExtractor {
         let root = tree.root_node();
         let mut functions = Vec::new();
 
-        Self::extract_rust_functions(source, &root, &mut functions)?;
+        Self::extract_rust_functions(source, &root, spec, &mut functions)?;
 
+        tracing::Span::current().record("functions", functions.len());
         Ok(functions)
     }
 
     fn extract_rust_functions(
         source: &str,
         node: &tree_sitter::Node,
+        spec: &CfgGrammarSpec,
         functions: &mut Vec<FunctionInfo>,
     ) -> Result<()> {
         let kind = node.kind();
 
         // Look for function and method definitions
         if kind == "function_item" || kind == "method_declaration" {
-            if let Some(func) = Self::parse_rust_function(source, node)? {
+            if let Some(func) = Self::parse_rust_function(source, node, spec)? {
                 functions.push(func);
             }
         }
@@ -46,13 +65,17 @@ impl CfgExtractor {
         // Recurse into children
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            Self::extract_rust_functions(source, &child, functions)?;
+            Self::extract_rust_functions(source, &child, spec, functions)?;
         }
 
         Ok(())
     }
 
-    fn parse_rust_function(source: &str, node: &tree_sitter::Node) -> Result<Option<FunctionInfo>> {
+    fn parse_rust_function(
+        source: &str,
+        node: &tree_sitter::Node,
+        spec: &CfgGrammarSpec,
+    ) -> Result<Option<FunctionInfo>> {
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
 
@@ -83,7 +106,7 @@ impl CfgExtractor {
         }
 
         let cfg = if let Some(body) = body {
-            Self::build_cfg_from_body(source, &body, SupportedLanguage::Rust)?
+            Self::build_cfg_from_body(source, &body, SupportedLanguage::Rust, spec)?
         } else {
             // Function without body (trait method)
             TestCfg::new(BlockId(0))
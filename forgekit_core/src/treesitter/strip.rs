@@ -0,0 +1,91 @@
+//! Blanking comments and string literals out of source
+//!
+//! Used by pattern search to match real code only. Blanked bytes become
+//! spaces and newlines are kept, so lines and byte offsets are unchanged.
+
+use super::SupportedLanguage;
+
+/// Returns `source` with comments and/or string literals blanked out.
+///
+/// Returns `None` if the source cannot be parsed.
+pub(crate) fn blank_non_code(
+    source: &str,
+    lang: SupportedLanguage,
+    comments: bool,
+    strings: bool,
+) -> Option<String> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&lang.grammar()).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut bytes = source.as_bytes().to_vec();
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let blank = (comments && is_comment(node.kind())) || (strings && is_string(node.kind()));
+        if blank {
+            for byte in &mut bytes[node.start_byte()..node.end_byte()] {
+                if *byte != b'\n' {
+                    *byte = b' ';
+                }
+            }
+        }
+        // Blanked nodes are not descended into
+        if !blank && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                // Whole nodes were replaced, so multi-byte characters were
+                // replaced whole too
+                return String::from_utf8(bytes).ok();
+            }
+        }
+    }
+}
+
+/// Comment node kinds across the supported grammars
+pub(crate) fn is_comment(kind: &str) -> bool {
+    matches!(kind, "comment" | "line_comment" | "block_comment")
+}
+
+/// String and character literal node kinds across the supported grammars
+fn is_string(kind: &str) -> bool {
+    matches!(
+        kind,
+        "string"
+            | "string_literal"
+            | "raw_string_literal"
+            | "char_literal"
+            | "character_literal"
+            | "text_block"
+            | "concatenated_string"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_rust_comments_and_strings() {
+        let source = "fn f() {\n    // TODO fix parse()\n    let s = \"parse()\"; /* parse()\n */ parse();\n}\n";
+
+        let stripped = blank_non_code(source, SupportedLanguage::Rust, true, true).unwrap();
+        assert_eq!(stripped.len(), source.len());
+        assert_eq!(stripped.lines().count(), source.lines().count());
+        assert_eq!(stripped.matches("parse()").count(), 1);
+        assert!(stripped.contains("let s = "));
+
+        let comments_only = blank_non_code(source, SupportedLanguage::Rust, true, false).unwrap();
+        assert_eq!(comments_only.matches("parse()").count(), 2);
+    }
+
+    #[test]
+    fn test_blank_python_keeps_multibyte_text_valid() {
+        let source = "# naïve parse()\nx = 'é parse()'\nparse()\n";
+        let stripped = blank_non_code(source, SupportedLanguage::Python, true, true).unwrap();
+        assert_eq!(stripped.matches("parse()").count(), 1);
+        assert!(stripped.ends_with("parse()\n"));
+    }
+}
@@ -0,0 +1,152 @@
+//! Reading declaration visibility from a tree-sitter parse
+//!
+//! The modifiers are located in the syntax tree, which keeps attributes,
+//! annotations and comments out of the way, then mapped to a
+//! [`Visibility`] by [`Visibility::from_declaration`].
+
+use super::SupportedLanguage;
+use crate::types::Visibility;
+
+/// Parses `source` for [`visibility_at`].
+pub(crate) fn parse(source: &[u8], lang: SupportedLanguage) -> Option<tree_sitter::Tree> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&lang.grammar()).ok()?;
+    parser.parse(source, None)
+}
+
+/// Visibility of the declaration starting at `byte_start`.
+///
+/// Returns `None` if the offset is outside the parsed source.
+pub(crate) fn visibility_at(
+    tree: &tree_sitter::Tree,
+    source: &[u8],
+    byte_start: usize,
+    lang: SupportedLanguage,
+) -> Option<Visibility> {
+    let text = |node: tree_sitter::Node| node.utf8_text(source).unwrap_or_default();
+
+    // The outermost node starting at the offset is the whole declaration
+    let mut node = tree
+        .root_node()
+        .descendant_for_byte_range(byte_start, byte_start)?;
+    while let Some(parent) = node.parent() {
+        // Stop below the root, which also starts at offset 0
+        if parent.start_byte() != byte_start || parent.parent().is_none() {
+            break;
+        }
+        node = parent;
+    }
+    if let Some(definition) = node.child_by_field_name("definition") {
+        // Python decorators wrap the definition
+        node = definition;
+    }
+
+    let name = node
+        .child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("declarator"))
+        .map(text)
+        .unwrap_or_default();
+    let name = name.split(['(', '[']).next().unwrap_or_default().trim();
+
+    let mut cursor = node.walk();
+    let modifiers: Vec<&str> = node
+        .children(&mut cursor)
+        .filter(|child| {
+            matches!(
+                child.kind(),
+                "visibility_modifier" | "modifiers" | "storage_class_specifier"
+            )
+        })
+        .map(text)
+        .collect();
+
+    // Java interface members are public without saying so
+    let in_interface = node
+        .parent()
+        .is_some_and(|parent| parent.kind() == "interface_body");
+    if lang == SupportedLanguage::Java && in_interface && !modifiers.contains(&"private") {
+        return Some(Visibility::Public);
+    }
+
+    Some(Visibility::from_declaration(
+        &modifiers.join(" "),
+        name,
+        &lang.language(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visibility_of(source: &str, lang: SupportedLanguage, declaration: &str) -> Visibility {
+        let tree = parse(source.as_bytes(), lang).unwrap();
+        let start = source.find(declaration).unwrap();
+        visibility_at(&tree, source.as_bytes(), start, lang).unwrap()
+    }
+
+    #[test]
+    fn test_rust_visibility() {
+        let source = "pub fn a() {}\npub(crate) fn b() {}\npub(super) fn c() {}\n\
+                      pub(in crate::x) fn d() {}\nfn e() {}\n#[inline]\npub(self) fn f() {}\n";
+        let rust = SupportedLanguage::Rust;
+        assert_eq!(visibility_of(source, rust, "pub fn a"), Visibility::Public);
+        assert_eq!(
+            visibility_of(source, rust, "pub(crate) fn b"),
+            Visibility::Crate
+        );
+        assert_eq!(
+            visibility_of(source, rust, "pub(super) fn c"),
+            Visibility::Module
+        );
+        assert_eq!(
+            visibility_of(source, rust, "pub(in crate::x)"),
+            Visibility::Module
+        );
+        assert_eq!(visibility_of(source, rust, "fn e"), Visibility::Private);
+        assert_eq!(
+            visibility_of(source, rust, "pub(self) fn f"),
+            Visibility::Private
+        );
+    }
+
+    #[test]
+    fn test_java_visibility() {
+        let source = "class A {\n  public void a() {}\n  protected void b() {}\n  void c() {}\n\
+                      \x20 @Override private void d() {}\n}\ninterface I {\n  void e();\n}\n";
+        let java = SupportedLanguage::Java;
+        assert_eq!(
+            visibility_of(source, java, "public void a"),
+            Visibility::Public
+        );
+        assert_eq!(
+            visibility_of(source, java, "protected void b"),
+            Visibility::Crate
+        );
+        assert_eq!(visibility_of(source, java, "void c"), Visibility::Module);
+        assert_eq!(
+            visibility_of(source, java, "@Override"),
+            Visibility::Private
+        );
+        assert_eq!(visibility_of(source, java, "void e"), Visibility::Public);
+    }
+
+    #[test]
+    fn test_c_and_python_visibility() {
+        let c = "static int a(void) { return 0; }\nint b(void) { return 1; }\n";
+        assert_eq!(
+            visibility_of(c, SupportedLanguage::C, "static int a"),
+            Visibility::Module
+        );
+        assert_eq!(
+            visibility_of(c, SupportedLanguage::C, "int b"),
+            Visibility::Public
+        );
+
+        let py = "def a():\n    pass\n\ndef _b():\n    pass\n\n@cached\ndef __c__():\n    pass\n";
+        let python = SupportedLanguage::Python;
+        assert_eq!(visibility_of(py, python, "def a"), Visibility::Public);
+        assert_eq!(visibility_of(py, python, "def _b"), Visibility::Private);
+        assert_eq!(visibility_of(py, python, "@cached"), Visibility::Public);
+    }
+}
@@ -1,5 +1,6 @@
 //! Core types for ForgeKit.
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -7,7 +8,7 @@ use std::sync::Arc;
 ///
 /// This ID is generated from a hash of the symbol's fully qualified name
 /// and location, ensuring stability even as the codebase changes.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct SymbolId(pub i64);
 
 impl std::fmt::Display for SymbolId {
@@ -16,6 +17,38 @@ impl std::fmt::Display for SymbolId {
     }
 }
 
+impl SymbolId {
+    /// Derives a symbol's id from its fully qualified name, kind and file
+    /// path relative to the codebase root.
+    ///
+    /// The same symbol gets the same id on every run, whatever order files
+    /// are scanned in. Where in its file the symbol is does not matter, but
+    /// moving it to another file or module gives it a new id. Ids are
+    /// BLAKE3-derived and always positive.
+    pub fn stable(
+        fully_qualified_name: &str,
+        kind: SymbolKind,
+        relative_path: &std::path::Path,
+    ) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(fully_qualified_name.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(kind.id_tag().as_bytes());
+        hasher.update(&[0]);
+        // Separators normalized so ids agree across platforms
+        for (i, component) in relative_path.components().enumerate() {
+            if i > 0 {
+                hasher.update(b"/");
+            }
+            hasher.update(component.as_os_str().as_encoded_bytes());
+        }
+        let hash = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hash.as_bytes()[..8]);
+        Self((i64::from_le_bytes(bytes) & i64::MAX).max(1))
+    }
+}
+
 impl From<i64> for SymbolId {
     fn from(id: i64) -> Self {
         Self(id)
@@ -23,7 +56,7 @@ impl From<i64> for SymbolId {
 }
 
 /// Stable identifier for a CFG block.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct BlockId(pub i64);
 
 impl std::fmt::Display for BlockId {
@@ -35,7 +68,7 @@ impl std::fmt::Display for BlockId {
 /// Stable identifier for an execution path.
 ///
 /// This is a BLAKE3 hash of the path's block sequence.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PathId(pub [u8; 16]);
 
 impl std::fmt::Display for PathId {
@@ -51,7 +84,7 @@ impl std::fmt::Display for PathId {
 }
 
 /// Source code location with file path and byte span.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Location {
     /// Path to the file containing this symbol
     pub file_path: PathBuf,
@@ -120,7 +153,7 @@ impl Span {
 }
 
 /// Symbol kind classification.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SymbolKind {
     // Declarations
     Function,
@@ -157,10 +190,32 @@ impl SymbolKind {
     pub fn is_function(&self) -> bool {
         matches!(self, Self::Function | Self::Method)
     }
+
+    /// Name hashed into [`SymbolId::stable`]; spelled out so renaming a
+    /// variant never changes ids.
+    fn id_tag(self) -> &'static str {
+        match self {
+            Self::Function => "Function",
+            Self::Method => "Method",
+            Self::Struct => "Struct",
+            Self::Enum => "Enum",
+            Self::Trait => "Trait",
+            Self::Impl => "Impl",
+            Self::Module => "Module",
+            Self::TypeAlias => "TypeAlias",
+            Self::Constant => "Constant",
+            Self::Static => "Static",
+            Self::Parameter => "Parameter",
+            Self::LocalVariable => "LocalVariable",
+            Self::Field => "Field",
+            Self::Macro => "Macro",
+            Self::Use => "Use",
+        }
+    }
 }
 
 /// Programming language detection.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Language {
     Rust,
     Python,
@@ -173,6 +228,147 @@ pub enum Language {
     Unknown(String),
 }
 
+impl Language {
+    /// Detects a file's language from its extension.
+    ///
+    /// Returns `None` for extensions that no supported language uses.
+    pub fn from_path(path: &std::path::Path) -> Option<Language> {
+        let ext = path.extension()?.to_str()?;
+        [
+            Language::Rust,
+            Language::Python,
+            Language::C,
+            Language::Cpp,
+            Language::Java,
+            Language::JavaScript,
+            Language::TypeScript,
+            Language::Go,
+        ]
+        .into_iter()
+        .find(|lang| lang.extensions().contains(&ext))
+    }
+
+    /// File extensions used by this language.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["rs"],
+            Language::Python => &["py"],
+            Language::C => &["c", "h"],
+            Language::Cpp => &["cpp", "hpp", "cc", "cxx"],
+            Language::Java => &["java"],
+            Language::JavaScript => &["js", "jsx", "mjs", "cjs"],
+            Language::TypeScript => &["ts", "tsx"],
+            Language::Go => &["go"],
+            Language::Unknown(_) => &[],
+        }
+    }
+}
+
+/// Where a symbol can be used from.
+///
+/// Mapped per language from its declaration: Rust `pub`, `pub(crate)`,
+/// `pub(super)` / `pub(in path)` and no modifier; Java `public`,
+/// `protected` (visible outside the package through subclasses, so wider
+/// than package-private), package-private and `private`; C `static` as
+/// file-local [`Module`](Self::Module); Python names with a leading
+/// underscore as [`Private`](Self::Private); Go lowercase names and
+/// unexported JavaScript/TypeScript items as [`Module`](Self::Module).
+/// Symbols built without reading their declaration are
+/// [`Unknown`](Self::Unknown).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Visibility {
+    /// Not read from the declaration
+    #[default]
+    Unknown,
+    /// Visible everywhere
+    Public,
+    /// Visible within the crate (Rust `pub(crate)`, Java `protected`)
+    Crate,
+    /// Visible within an enclosing module, package or file
+    Module,
+    /// Visible only where it is declared
+    Private,
+}
+
+impl Visibility {
+    /// Reads the visibility of a declaration of `name` from its leading
+    /// modifiers.
+    ///
+    /// `declaration` is the declaration text, or just its modifiers; only
+    /// the part before any parameter list or body is inspected.
+    pub fn from_declaration(declaration: &str, name: &str, language: &Language) -> Visibility {
+        let header = declaration
+            .split(['(', '{', '=', ';'])
+            .next()
+            .unwrap_or_default()
+            .trim_start();
+        // Rust restrictions are parenthesized, so read them before splitting
+        let rust = declaration.trim_start();
+        let words = || header.split_whitespace();
+        match language {
+            Language::Rust => {
+                if rust.starts_with("pub(crate)") || rust.starts_with("crate ") {
+                    Visibility::Crate
+                } else if rust.starts_with("pub(super)") || rust.starts_with("pub(in ") {
+                    Visibility::Module
+                } else if rust.starts_with("pub(self)") {
+                    Visibility::Private
+                } else if rust.starts_with("pub ") || rust == "pub" {
+                    Visibility::Public
+                } else {
+                    Visibility::Private
+                }
+            }
+            Language::Java => {
+                if words().any(|w| w == "public") {
+                    Visibility::Public
+                } else if words().any(|w| w == "protected") {
+                    Visibility::Crate
+                } else if words().any(|w| w == "private") {
+                    Visibility::Private
+                } else {
+                    Visibility::Module
+                }
+            }
+            Language::C | Language::Cpp => {
+                if words().any(|w| w == "static") {
+                    Visibility::Module
+                } else {
+                    Visibility::Public
+                }
+            }
+            Language::Python => {
+                let dunder = name.starts_with("__") && name.ends_with("__");
+                if name.starts_with('_') && !dunder {
+                    Visibility::Private
+                } else {
+                    Visibility::Public
+                }
+            }
+            Language::Go => {
+                if name.starts_with(|c: char| c.is_uppercase()) {
+                    Visibility::Public
+                } else {
+                    Visibility::Module
+                }
+            }
+            Language::JavaScript | Language::TypeScript => {
+                if words().next() == Some("export") {
+                    Visibility::Public
+                } else {
+                    Visibility::Module
+                }
+            }
+            Language::Unknown(_) => Visibility::Unknown,
+        }
+    }
+
+    /// Returns true for items known to be usable outside their module.
+    pub fn is_exported(&self) -> bool {
+        matches!(self, Visibility::Public | Visibility::Crate)
+    }
+}
+
 /// Path kind classification.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PathKind {
@@ -189,24 +385,28 @@ pub enum PathKind {
 }
 
 /// Reference type between symbols.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ReferenceKind {
     /// Function or method call
     Call,
-    /// Use or import statement
+    /// Any other use of the name, e.g. as a value
     Use,
+    /// Name imported by a `use` declaration
+    Import,
     /// Type reference (annotation, bound, etc.)
     TypeReference,
     /// Inheritance relationship
     Inherit,
     /// Trait implementation
     Implementation,
+    /// Edge from a type to a trait it implements, at the impl block
+    Implements,
     /// Method override
     Override,
 }
 
 /// A symbol in the code graph.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Symbol {
     /// Stable symbol identifier
     pub id: SymbolId,
@@ -222,12 +422,18 @@ pub struct Symbol {
     pub location: Location,
     /// Parent symbol ID (if nested)
     pub parent_id: Option<SymbolId>,
+    /// Where the symbol can be used from
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// Hash of the symbol's source span, taken when it was indexed
+    #[serde(default)]
+    pub body_hash: Option<blake3::Hash>,
     /// Additional metadata
     pub metadata: serde_json::Value,
 }
 
 /// A reference between two symbols.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Reference {
     /// The referencing symbol
     pub from: SymbolId,
@@ -287,12 +493,154 @@ pub struct Loop {
     pub depth: usize,
 }
 
+/// A change to one file: its original and modified content plus the
+/// line hunks that differ between them.
+///
+/// Shared by the analysis edit previews and the agent's policy checks.
+/// Hunks are computed by [`Diff::new`]; build a new `Diff` rather than
+/// editing `original` or `modified` in place.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diff {
+    /// File the change applies to (empty when not tied to a file)
+    pub file_path: PathBuf,
+    /// Original content
+    pub original: String,
+    /// Modified content
+    pub modified: String,
+    /// Changed regions, in order
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// A contiguous run of changed lines.
+///
+/// Line numbers are 0-based. A pure insertion has `original_len == 0`
+/// and a pure deletion has `modified_len == 0`; the start on that side is
+/// where the lines would be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// First affected line in the original
+    pub original_start: usize,
+    /// Number of original lines replaced or removed
+    pub original_len: usize,
+    /// First affected line in the modified content
+    pub modified_start: usize,
+    /// Number of lines added or substituted in the modified content
+    pub modified_len: usize,
+}
+
+impl Diff {
+    /// Computes the line diff of `original` against `modified`.
+    pub fn new(
+        file_path: impl Into<PathBuf>,
+        original: impl Into<String>,
+        modified: impl Into<String>,
+    ) -> Self {
+        let original = original.into();
+        let modified = modified.into();
+        let hunks = similar::TextDiff::from_lines(&original, &modified)
+            .grouped_ops(0)
+            .iter()
+            .filter_map(|group| {
+                let (first, last) = (group.first()?, group.last()?);
+                let old = first.old_range().start..last.old_range().end;
+                let new = first.new_range().start..last.new_range().end;
+                Some(DiffHunk {
+                    original_start: old.start,
+                    original_len: old.len(),
+                    modified_start: new.start,
+                    modified_len: new.len(),
+                })
+            })
+            .collect();
+
+        Self {
+            file_path: file_path.into(),
+            original,
+            modified,
+            hunks,
+        }
+    }
+
+    /// Returns true if there are any changes.
+    pub fn has_changes(&self) -> bool {
+        !self.hunks.is_empty()
+    }
+
+    /// Lines of the modified content that were added or changed (0-based).
+    pub fn changed_lines(&self) -> Vec<usize> {
+        self.hunks
+            .iter()
+            .flat_map(|h| h.modified_start..h.modified_start + h.modified_len)
+            .collect()
+    }
+
+    /// Lines of the original content that were removed or changed (0-based).
+    pub fn removed_lines(&self) -> Vec<usize> {
+        self.hunks
+            .iter()
+            .flat_map(|h| h.original_start..h.original_start + h.original_len)
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
     use std::sync::Arc;
 
+    // Diff Tests
+
+    #[test]
+    fn test_diff_insertion() {
+        let diff = Diff::new("src/lib.rs", "a\nb\nc\n", "a\nb\nx\ny\nc\n");
+        assert_eq!(
+            diff.hunks,
+            vec![DiffHunk {
+                original_start: 2,
+                original_len: 0,
+                modified_start: 2,
+                modified_len: 2,
+            }]
+        );
+        assert_eq!(diff.changed_lines(), vec![2, 3]);
+        assert!(diff.removed_lines().is_empty());
+        assert_eq!(diff.file_path, PathBuf::from("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_diff_deletion() {
+        let diff = Diff::new("", "a\nb\nc\nd\n", "a\nd\n");
+        assert_eq!(
+            diff.hunks,
+            vec![DiffHunk {
+                original_start: 1,
+                original_len: 2,
+                modified_start: 1,
+                modified_len: 0,
+            }]
+        );
+        assert!(diff.changed_lines().is_empty());
+        assert_eq!(diff.removed_lines(), vec![1, 2]);
+        assert!(diff.has_changes());
+    }
+
+    #[test]
+    fn test_diff_modification_in_separate_hunks() {
+        let diff = Diff::new("", "a\nb\nc\nd\ne\n", "A\nb\nc\nd\nE\n");
+        assert_eq!(diff.hunks.len(), 2);
+        assert_eq!(diff.hunks[0].original_start, 0);
+        assert_eq!(diff.hunks[1].original_start, 4);
+        assert!(diff
+            .hunks
+            .iter()
+            .all(|h| h.original_len == 1 && h.modified_len == 1));
+        assert_eq!(diff.changed_lines(), vec![0, 4]);
+        assert_eq!(diff.removed_lines(), vec![0, 4]);
+
+        assert!(!Diff::new("", "same\n", "same\n").has_changes());
+    }
+
     // SymbolId Tests (4 tests)
 
     #[test]
@@ -315,6 +663,43 @@ mod tests {
         assert!(id2 > id1);
     }
 
+    #[test]
+    fn test_symbol_id_stable_is_deterministic() {
+        use std::path::Path;
+
+        let path = Path::new("src/parser.rs");
+        let id = SymbolId::stable("parser::parse", SymbolKind::Function, path);
+        assert_eq!(
+            id,
+            SymbolId::stable("parser::parse", SymbolKind::Function, path)
+        );
+        assert!(id.0 > 0);
+
+        // Round-trips through serialization unchanged
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(serde_json::from_str::<SymbolId>(&json).unwrap(), id);
+
+        // Name, kind and path all matter
+        for other in [
+            SymbolId::stable("parser::lex", SymbolKind::Function, path),
+            SymbolId::stable("parser::parse", SymbolKind::Struct, path),
+            SymbolId::stable(
+                "parser::parse",
+                SymbolKind::Function,
+                Path::new("src/lib.rs"),
+            ),
+        ] {
+            assert_ne!(other, id);
+        }
+        assert_eq!(
+            SymbolId::stable("a", SymbolKind::Function, Path::new("src/x.rs")),
+            SymbolId::stable("a", SymbolKind::Function, &Path::new("src").join("x.rs"))
+        );
+
+        // Pinned, so changes to the hashed fields show up here
+        assert_eq!(id, SymbolId(5743622943542829358));
+    }
+
     #[test]
     fn test_symbol_id_zero() {
         let id = SymbolId(0);
@@ -541,6 +926,20 @@ mod tests {
         let _unknown = Language::Unknown("SomeLang".to_string());
     }
 
+    #[test]
+    fn test_language_from_path() {
+        assert_eq!(
+            Language::from_path(std::path::Path::new("src/lib.rs")),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            Language::from_path(std::path::Path::new("include/a.h")),
+            Some(Language::C)
+        );
+        assert_eq!(Language::from_path(std::path::Path::new("README.md")), None);
+        assert_eq!(Language::from_path(std::path::Path::new("Makefile")), None);
+    }
+
     #[test]
     fn test_language_unknown() {
         let lang = Language::Unknown("MyLang".to_string());
@@ -584,9 +983,11 @@ mod tests {
     fn test_reference_kind_variants() {
         let _call = ReferenceKind::Call;
         let _use = ReferenceKind::Use;
+        let _import = ReferenceKind::Import;
         let _type_ref = ReferenceKind::TypeReference;
         let _inherit = ReferenceKind::Inherit;
         let _impl = ReferenceKind::Implementation;
+        let _implements = ReferenceKind::Implements;
         let _override = ReferenceKind::Override;
     }
 
@@ -615,6 +1016,8 @@ mod tests {
                 line_number: 1,
             },
             parent_id: None,
+            visibility: Visibility::default(),
+            body_hash: None,
             metadata: serde_json::Value::Null,
         };
         assert_eq!(symbol.id.0, 1);
@@ -655,6 +1058,8 @@ mod tests {
                 line_number: 2,
             },
             parent_id: Some(SymbolId(1)),
+            visibility: Visibility::default(),
+            body_hash: None,
             metadata: serde_json::Value::Null,
         };
         assert!(symbol.parent_id.is_some());
@@ -679,6 +1084,8 @@ mod tests {
                 line_number: 1,
             },
             parent_id: None,
+            visibility: Visibility::default(),
+            body_hash: None,
             metadata,
         };
         assert_eq!(symbol.metadata["key"], "value");
@@ -720,10 +1127,85 @@ mod tests {
                 line_number: 1,
             },
             parent_id: None,
+            visibility: Visibility::default(),
+            body_hash: None,
             metadata,
         };
         assert!(symbol.metadata.as_object().unwrap().is_empty());
     }
+
+    #[test]
+    fn test_visibility_from_declaration() {
+        let of = |line: &str, name: &str, language: Language| {
+            Visibility::from_declaration(line, name, &language)
+        };
+
+        assert_eq!(
+            of("pub fn run() {", "run", Language::Rust),
+            Visibility::Public
+        );
+        assert_eq!(
+            of("pub(crate) struct S;", "S", Language::Rust),
+            Visibility::Crate
+        );
+        assert_eq!(
+            of("pub(super) fn f()", "f", Language::Rust),
+            Visibility::Module
+        );
+        assert_eq!(
+            of("pub(in crate::graph) fn f()", "f", Language::Rust),
+            Visibility::Module
+        );
+        assert_eq!(
+            of("fn helper() {", "helper", Language::Rust),
+            Visibility::Private
+        );
+        assert_eq!(
+            of(
+                "  public static void main(String[] a) {",
+                "main",
+                Language::Java
+            ),
+            Visibility::Public
+        );
+        assert_eq!(
+            of("protected int size() {", "size", Language::Java),
+            Visibility::Crate
+        );
+        assert_eq!(
+            of("int size() {", "size", Language::Java),
+            Visibility::Module
+        );
+        assert_eq!(
+            of("private final int n = 1;", "n", Language::Java),
+            Visibility::Private
+        );
+        assert_eq!(
+            of("static int count(void) {", "count", Language::C),
+            Visibility::Module
+        );
+        assert_eq!(
+            of("def _cache():", "_cache", Language::Python),
+            Visibility::Private
+        );
+        assert_eq!(
+            of("def __init__(self):", "__init__", Language::Python),
+            Visibility::Public
+        );
+        assert_eq!(of("func Run() {", "Run", Language::Go), Visibility::Public);
+        assert_eq!(of("func run() {", "run", Language::Go), Visibility::Module);
+        assert_eq!(
+            of("export function run() {", "run", Language::JavaScript),
+            Visibility::Public
+        );
+        assert!(Visibility::Crate.is_exported() && !Visibility::Module.is_exported());
+        assert_eq!(
+            of("fn run()", "run", Language::Unknown("x".to_string())),
+            Visibility::Unknown
+        );
+        assert_eq!(Visibility::default(), Visibility::Unknown);
+        assert!(!Visibility::Unknown.is_exported());
+    }
 }
 
 /// CFG block kind.
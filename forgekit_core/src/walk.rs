@@ -0,0 +1,357 @@
+//! Directory walking policy shared by all recursive walkers.
+//!
+//! Walkers ask a [`DirWalk`] before descending into a directory or
+//! reading a file. Symlinks are skipped unless
+//! [`WalkOptions::follow_symlinks`] is set, and directories are tracked by
+//! canonical path so a symlink cycle or a second link to the same tree is
+//! only walked once.
+//!
+//! Paths ignored by a `.gitignore` at or below the walk root are skipped,
+//! as are paths matching [`WalkOptions::exclude`]. Patterns use gitignore
+//! syntax: a deeper `.gitignore` overrides a shallower one, and a `!`
+//! pattern re-includes a path ignored earlier.
+//!
+//! Files larger than [`WalkOptions::max_file_bytes`] are skipped by their
+//! metadata length, without being read, and recorded by the walk.
+
+use crate::types::Language;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Options for directory walks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// Descend into symlinked directories and read symlinked files
+    /// (default: false)
+    pub follow_symlinks: bool,
+    /// Skip paths ignored by `.gitignore` files (default: true)
+    pub respect_gitignore: bool,
+    /// Gitignore-style patterns, relative to the walk root, for paths to
+    /// skip regardless of `.gitignore`
+    pub exclude: Vec<String>,
+    /// Skip files larger than this many bytes (default: no limit)
+    pub max_file_bytes: Option<usize>,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            follow_symlinks: false,
+            respect_gitignore: true,
+            exclude: Vec::new(),
+            max_file_bytes: None,
+        }
+    }
+}
+
+impl WalkOptions {
+    /// Sets whether symlinks are followed.
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Sets whether `.gitignore` files are honored.
+    pub fn respect_gitignore(mut self, respect: bool) -> Self {
+        self.respect_gitignore = respect;
+        self
+    }
+
+    /// Adds patterns for paths to skip.
+    pub fn exclude<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclude.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the size above which files are skipped; `None` for no limit.
+    pub fn max_file_bytes(mut self, max: Option<usize>) -> Self {
+        self.max_file_bytes = max;
+        self
+    }
+
+    /// Returns true if `path` is larger than
+    /// [`max_file_bytes`](Self::max_file_bytes), judged by its metadata.
+    pub fn is_oversized(&self, path: &Path) -> bool {
+        self.max_file_bytes
+            .is_some_and(|max| std::fs::metadata(path).is_ok_and(|meta| meta.len() > max as u64))
+    }
+}
+
+/// State of one directory walk.
+#[derive(Debug)]
+pub struct DirWalk {
+    follow_symlinks: bool,
+    visited: HashSet<PathBuf>,
+    root: PathBuf,
+    exclude: Option<Gitignore>,
+    /// `.gitignore` matcher per directory, loaded on first use; `None`
+    /// when `.gitignore` files are not honored.
+    gitignores: Option<HashMap<PathBuf, Option<Gitignore>>>,
+    max_file_bytes: Option<usize>,
+    oversized: Vec<PathBuf>,
+}
+
+impl DirWalk {
+    /// Starts a walk rooted at `root`.
+    pub fn new(root: &Path, options: &WalkOptions) -> Self {
+        let mut visited = HashSet::new();
+        if let Ok(canonical) = root.canonicalize() {
+            visited.insert(canonical);
+        }
+
+        let exclude = (!options.exclude.is_empty()).then(|| {
+            let mut builder = GitignoreBuilder::new(root);
+            for pattern in &options.exclude {
+                if let Err(e) = builder.add_line(None, pattern) {
+                    tracing::warn!("Ignoring invalid exclude pattern {:?}: {}", pattern, e);
+                }
+            }
+            builder.build().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring exclude patterns: {}", e);
+                Gitignore::empty()
+            })
+        });
+
+        Self {
+            follow_symlinks: options.follow_symlinks,
+            visited,
+            root: root.to_path_buf(),
+            exclude,
+            gitignores: options.respect_gitignore.then(HashMap::new),
+            max_file_bytes: options.max_file_bytes,
+            oversized: Vec::new(),
+        }
+    }
+
+    /// Returns true if the walk should descend into `path`.
+    ///
+    /// False for non-directories, for symlinks when they are not followed,
+    /// for ignored or excluded directories, and for directories already
+    /// visited under another path.
+    pub fn enter_dir(&mut self, path: &Path) -> bool {
+        let Ok(meta) = std::fs::symlink_metadata(path) else {
+            return false;
+        };
+        if meta.file_type().is_symlink() && !self.follow_symlinks {
+            return false;
+        }
+        if !path.is_dir() || self.is_ignored(path, true) {
+            return false;
+        }
+        match path.canonicalize() {
+            Ok(canonical) => self.visited.insert(canonical),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns true if `path` is a file the walk should process.
+    ///
+    /// Files over the size limit are logged and recorded in
+    /// [`oversized`](Self::oversized).
+    pub fn is_file(&mut self, path: &Path) -> bool {
+        let is_file = match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_symlink() => self.follow_symlinks && path.is_file(),
+            Ok(meta) => meta.is_file(),
+            Err(_) => false,
+        };
+        if !is_file || self.is_ignored(path, false) {
+            return false;
+        }
+        if let Some(max) = self.max_file_bytes {
+            let len = std::fs::metadata(path).map_or(0, |meta| meta.len());
+            if len > max as u64 {
+                tracing::info!(
+                    "Skipping {} ({} bytes, over the {} byte limit)",
+                    path.display(),
+                    len,
+                    max
+                );
+                self.oversized.push(path.to_path_buf());
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Files skipped so far for exceeding the size limit, in the order
+    /// they were seen.
+    pub fn oversized(&self) -> &[PathBuf] {
+        &self.oversized
+    }
+
+    /// Returns true if `path` is excluded or ignored by a `.gitignore`
+    /// between its directory and the walk root.
+    fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        if let Some(exclude) = &self.exclude {
+            if exclude.matched(path, is_dir).is_ignore() {
+                return true;
+            }
+        }
+
+        let Some(gitignores) = self.gitignores.as_mut() else {
+            return false;
+        };
+        // The deepest `.gitignore` with an opinion wins
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(&self.root) {
+                break;
+            }
+            let matcher = gitignores
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| load_gitignore(dir));
+            if let Some(matcher) = matcher {
+                let matched = matcher.matched(path, is_dir);
+                if matched.is_ignore() {
+                    return true;
+                }
+                if matched.is_whitelist() {
+                    return false;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Returns the language of `path` if it is a source file Forge indexes.
+///
+/// Every walker that picks source files out of a tree goes through this,
+/// so they all agree on what counts as source.
+pub fn is_supported_source(path: &Path) -> Option<Language> {
+    Language::from_path(path)
+}
+
+/// Parses `dir/.gitignore`, if there is one.
+fn load_gitignore(dir: &Path) -> Option<Gitignore> {
+    let file = dir.join(".gitignore");
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(&file) {
+        tracing::warn!("Problem reading {}: {}", file.display(), e);
+    }
+    builder.build().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_skipped_unless_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        std::fs::create_dir(&real).unwrap();
+        std::fs::write(real.join("a.rs"), "").unwrap();
+        std::os::unix::fs::symlink(&real, dir.path().join("link")).unwrap();
+        std::os::unix::fs::symlink(real.join("a.rs"), dir.path().join("b.rs")).unwrap();
+
+        let mut walk = DirWalk::new(dir.path(), &WalkOptions::default());
+        assert!(!walk.enter_dir(&dir.path().join("link")));
+        assert!(!walk.is_file(&dir.path().join("b.rs")));
+        assert!(walk.enter_dir(&real));
+        assert!(!walk.enter_dir(&real));
+
+        let mut walk = DirWalk::new(dir.path(), &WalkOptions::default().follow_symlinks(true));
+        assert!(walk.is_file(&dir.path().join("b.rs")));
+        assert!(walk.enter_dir(&dir.path().join("link")));
+        // Same canonical directory as the link
+        assert!(!walk.enter_dir(&real));
+    }
+
+    #[test]
+    fn test_nested_gitignore_and_negation() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["target", "src/generated", "vendor/keep"] {
+            std::fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        std::fs::write(
+            root.join(".gitignore"),
+            "target/\n*.gen.rs\nvendor/*\n!vendor/keep/\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/.gitignore"), "generated/\n!keep.gen.rs\n").unwrap();
+
+        let mut walk = DirWalk::new(root, &WalkOptions::default());
+        assert!(!walk.enter_dir(&root.join("target")));
+        assert!(walk.enter_dir(&root.join("src")));
+        assert!(!walk.enter_dir(&root.join("src/generated")));
+        assert!(walk.enter_dir(&root.join("vendor")));
+        assert!(walk.enter_dir(&root.join("vendor/keep")));
+
+        for (file, expected) in [
+            ("src/lib.rs", true),
+            ("src/api.gen.rs", false),
+            ("src/keep.gen.rs", true),
+            ("vendor/lib.rs", false),
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+            assert_eq!(walk.is_file(&root.join(file)), expected, "{}", file);
+        }
+
+        let mut walk = DirWalk::new(root, &WalkOptions::default().respect_gitignore(false));
+        assert!(walk.enter_dir(&root.join("target")));
+        assert!(walk.is_file(&root.join("src/api.gen.rs")));
+    }
+
+    #[test]
+    fn test_exclude_patterns_override_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/fixtures")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::write(root.join("src/big.rs"), "").unwrap();
+        std::fs::write(root.join(".gitignore"), "!src/big.rs\n").unwrap();
+
+        let options = WalkOptions::default().exclude(["fixtures/", "src/big.rs"]);
+        let mut walk = DirWalk::new(root, &options);
+        assert!(!walk.enter_dir(&root.join("src/fixtures")));
+        assert!(!walk.is_file(&root.join("src/big.rs")));
+        assert!(walk.is_file(&root.join("src/lib.rs")));
+    }
+
+    #[test]
+    fn test_oversized_files_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("small.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("big.rs"), vec![b'/'; 4096]).unwrap();
+
+        let mut walk = DirWalk::new(root, &WalkOptions::default());
+        assert!(walk.is_file(&root.join("big.rs")));
+
+        let options = WalkOptions::default().max_file_bytes(Some(1024));
+        assert!(options.is_oversized(&root.join("big.rs")));
+        assert!(!options.is_oversized(&root.join("small.rs")));
+        let mut walk = DirWalk::new(root, &options);
+        assert!(walk.is_file(&root.join("small.rs")));
+        assert!(!walk.is_file(&root.join("big.rs")));
+        assert_eq!(walk.oversized(), [root.join("big.rs")]);
+    }
+
+    #[test]
+    fn test_is_supported_source() {
+        assert_eq!(
+            is_supported_source(Path::new("src/lib.rs")),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            is_supported_source(Path::new("a/b.py")),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            is_supported_source(Path::new("x.tsx")),
+            Some(Language::TypeScript)
+        );
+        assert_eq!(is_supported_source(Path::new("Cargo.toml")), None);
+        assert_eq!(is_supported_source(Path::new("Makefile")), None);
+    }
+}
@@ -82,7 +82,9 @@ impl Watcher {
         let mut last_path: Option<PathBuf> = None;
 
         let event_handler = move |res: notify::Result<notify::Event>| {
-            // Debounce: ignore events within 100ms of same path
+            // Debounce: ignore modifications within 100ms of an event for the
+            // same path. Creates and removals are always forwarded so that
+            // consumers see each file's final state.
             let now = std::time::Instant::now();
 
             match res {
@@ -90,7 +92,10 @@ impl Watcher {
                     for path in event.paths {
                         // Check debounce
                         if let Some(last) = &last_path {
-                            if last == &path && now.duration_since(last_event).as_millis() < 100 {
+                            if last == &path
+                                && matches!(event.kind, notify::EventKind::Modify(_))
+                                && now.duration_since(last_event).as_millis() < 100
+                            {
                                 continue;
                             }
                         }
@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 
 use crate::error::{ForgeError, Result};
 use crate::project::ProjectInfo;
+use crate::walk::{DirWalk, WalkOptions};
 
 #[derive(Debug, Clone)]
 pub struct Workspace {
@@ -231,6 +232,7 @@ fn generic_project(root: &Path) -> ProjectInfo {
 
 fn walk_dirs(root: &Path, max_depth: usize) -> Vec<PathBuf> {
     let mut result = Vec::new();
+    let mut walk = DirWalk::new(root, &WalkOptions::default());
     let mut stack = vec![(root.to_path_buf(), 0)];
     while let Some((dir, depth)) = stack.pop() {
         if depth >= max_depth {
@@ -244,6 +246,9 @@ fn walk_dirs(root: &Path, max_depth: usize) -> Vec<PathBuf> {
                     if name.starts_with('.') || name == "node_modules" || name == "target" {
                         continue;
                     }
+                    if !walk.enter_dir(&path) {
+                        continue;
+                    }
                     result.push(path.clone());
                     stack.push((path, depth + 1));
                 }
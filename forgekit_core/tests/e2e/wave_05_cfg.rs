@@ -25,12 +25,19 @@ async fn e2e_cfg_paths_basic() {
     std::fs::write(temp_dir.path().join("lib.rs"), "fn test() { let x = 1; }").unwrap();
 
     let forge = Forge::open(temp_dir.path()).await.unwrap();
-    let paths = forge.cfg().paths(SymbolId(1)).execute().await;
+    forge.graph().index().await.unwrap();
+    let symbol = forge.graph().find_symbol("test").await.unwrap()[0].id;
+    let paths = forge.cfg().paths(symbol).execute().await;
 
     assert!(paths.is_ok());
     let paths = paths.unwrap();
-    // Returns placeholder path for v0.1
-    assert!(!paths.is_empty());
+    // Straight-line function has a single normal path
+    assert_eq!(paths.len(), 1);
+    assert!(paths[0].is_normal());
+
+    // Unknown symbols have no CFG
+    let paths = forge.cfg().paths(SymbolId(-1)).execute().await.unwrap();
+    assert!(paths.is_empty());
 }
 
 #[tokio::test]
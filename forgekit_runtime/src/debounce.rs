@@ -0,0 +1,200 @@
+//! Per-event debouncing for the watch loop.
+//!
+//! Each queued event asks for its own quiet period before the batch is
+//! flushed, and a burst of events, such as a branch checkout, stretches
+//! that period so the whole burst is reindexed once.
+
+use std::time::{Duration, Instant};
+
+use forgekit_core::WatchEvent;
+
+/// How much longer than usual to wait once a burst is detected.
+const BURST_FACTOR: u32 = 4;
+
+/// Debounce delays for file events, in milliseconds.
+///
+/// A single value applies the same delay to every event and never
+/// detects bursts:
+///
+/// ```rust
+/// use forgekit_runtime::DebounceConfig;
+///
+/// let config = DebounceConfig::from(500);
+/// assert_eq!(config.create_ms, 500);
+/// assert_eq!(config.burst_threshold, usize::MAX);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DebounceConfig {
+    /// Delay after a file is created
+    pub create_ms: u64,
+    /// Delay after a file is modified
+    pub modify_ms: u64,
+    /// Delay after a file is deleted
+    pub delete_ms: u64,
+    /// Events within one window beyond which the delay is extended
+    ///
+    /// The window is the longest of the three delays. Once more events
+    /// than this arrive within it, each further event asks for four times
+    /// its usual delay until the batch is flushed.
+    pub burst_threshold: usize,
+}
+
+impl From<u64> for DebounceConfig {
+    fn from(ms: u64) -> Self {
+        Self {
+            create_ms: ms,
+            modify_ms: ms,
+            delete_ms: ms,
+            burst_threshold: usize::MAX,
+        }
+    }
+}
+
+impl DebounceConfig {
+    /// The delay `event` asks for, or `None` for events that are not
+    /// file changes.
+    fn delay(&self, event: &WatchEvent) -> Option<Duration> {
+        let ms = match event {
+            WatchEvent::Created(_) => self.create_ms,
+            WatchEvent::Modified(_) => self.modify_ms,
+            WatchEvent::Deleted(_) => self.delete_ms,
+            WatchEvent::Error(_) => return None,
+        };
+        Some(Duration::from_millis(ms))
+    }
+
+    /// The window bursts are counted in.
+    fn window(&self) -> Duration {
+        Duration::from_millis(self.create_ms.max(self.modify_ms).max(self.delete_ms))
+    }
+
+    /// The shortest delay of any event.
+    pub(crate) fn shortest(&self) -> Duration {
+        Duration::from_millis(self.create_ms.min(self.modify_ms).min(self.delete_ms))
+    }
+}
+
+/// Tracks the quiet period the pending batch needs before a flush.
+#[derive(Debug)]
+pub(crate) struct Debouncer {
+    config: DebounceConfig,
+    window_start: Option<Instant>,
+    window_events: usize,
+    quiet: Duration,
+}
+
+impl Debouncer {
+    pub(crate) fn new(config: DebounceConfig) -> Self {
+        Self {
+            config,
+            window_start: None,
+            window_events: 0,
+            quiet: Duration::ZERO,
+        }
+    }
+
+    /// Records a queued event.
+    pub(crate) fn record(&mut self, event: &WatchEvent) {
+        self.record_at(event, Instant::now());
+    }
+
+    fn record_at(&mut self, event: &WatchEvent, now: Instant) {
+        let Some(delay) = self.config.delay(event) else {
+            return;
+        };
+        let window = self.config.window();
+        if self
+            .window_start
+            .is_none_or(|start| now.duration_since(start) >= window)
+        {
+            self.window_start = Some(now);
+            self.window_events = 0;
+        }
+        self.window_events += 1;
+
+        let delay = if self.window_events > self.config.burst_threshold {
+            delay * BURST_FACTOR
+        } else {
+            delay
+        };
+        self.quiet = self.quiet.max(delay);
+    }
+
+    /// How long events must be quiet before the batch is flushed: the
+    /// longest delay asked for since the last flush.
+    pub(crate) fn quiet(&self) -> Duration {
+        self.quiet
+    }
+
+    /// Starts over after a flush.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn config() -> DebounceConfig {
+        DebounceConfig {
+            create_ms: 200,
+            modify_ms: 50,
+            delete_ms: 100,
+            burst_threshold: 10,
+        }
+    }
+
+    #[test]
+    fn test_delay_depends_on_event_type() {
+        let mut debouncer = Debouncer::new(config());
+        let start = Instant::now();
+        debouncer.record_at(&WatchEvent::Modified(PathBuf::from("a.rs")), start);
+        assert_eq!(debouncer.quiet(), Duration::from_millis(50));
+
+        debouncer.record_at(&WatchEvent::Deleted(PathBuf::from("b.rs")), start);
+        assert_eq!(debouncer.quiet(), Duration::from_millis(100));
+
+        debouncer.record_at(&WatchEvent::Error("oops".to_string()), start);
+        assert_eq!(debouncer.quiet(), Duration::from_millis(100));
+
+        debouncer.reset();
+        assert_eq!(debouncer.quiet(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_burst_extends_debounce() {
+        let mut debouncer = Debouncer::new(config());
+        let start = Instant::now();
+        // A checkout creating hundreds of files within a few milliseconds
+        for i in 0..300 {
+            let event = WatchEvent::Created(PathBuf::from(format!("src/file{i}.rs")));
+            debouncer.record_at(&event, start + Duration::from_micros(i * 10));
+        }
+        assert_eq!(debouncer.quiet(), Duration::from_millis(800));
+    }
+
+    #[test]
+    fn test_steady_saves_are_not_a_burst() {
+        let mut debouncer = Debouncer::new(config());
+        let start = Instant::now();
+        // One save every 100ms never puts more than two in a 200ms window
+        for i in 0..100 {
+            let event = WatchEvent::Modified(PathBuf::from("src/lib.rs"));
+            debouncer.record_at(&event, start + Duration::from_millis(i * 100));
+        }
+        assert_eq!(debouncer.quiet(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_single_value_never_bursts() {
+        let mut debouncer = Debouncer::new(DebounceConfig::from(30));
+        let start = Instant::now();
+        for i in 0..1000 {
+            let event = WatchEvent::Created(PathBuf::from(format!("src/file{i}.rs")));
+            debouncer.record_at(&event, start);
+        }
+        assert_eq!(debouncer.quiet(), Duration::from_millis(30));
+    }
+}
@@ -0,0 +1,151 @@
+//! Definition-change filtering for the watch loop.
+//!
+//! Used when [`RuntimeConfig::reindex_on_definition_change_only`] is set:
+//! each modified file is fingerprinted item by item with
+//! [`CfgExtractor::item_fingerprints`] and compared to what it defined last
+//! time, so whitespace and comment edits are dropped before they reach the
+//! indexer. Any token change inside an item, from a signature to a struct
+//! field or a literal, still passes.
+//!
+//! [`RuntimeConfig::reindex_on_definition_change_only`]: crate::RuntimeConfig::reindex_on_definition_change_only
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use forgekit_core::treesitter::{CfgExtractor, ItemFingerprint};
+use forgekit_core::WatchEvent;
+
+/// Tracks the definitions of each watched file between events.
+#[derive(Debug, Default)]
+pub(crate) struct DefinitionFilter {
+    definitions: HashMap<PathBuf, Vec<ItemFingerprint>>,
+}
+
+impl DefinitionFilter {
+    /// Returns true if `event` should be queued for reindexing.
+    ///
+    /// A modification is dropped only when the file's definitions are
+    /// known from an earlier event and are unchanged. Creations,
+    /// deletions and files the extractor cannot read always pass.
+    pub(crate) async fn admits(&mut self, event: &WatchEvent) -> bool {
+        match event {
+            WatchEvent::Created(path) => {
+                match read_definitions(path).await {
+                    Some(current) => self.definitions.insert(path.clone(), current),
+                    None => self.definitions.remove(path),
+                };
+                true
+            }
+            WatchEvent::Modified(path) => match read_definitions(path).await {
+                Some(current) => {
+                    self.definitions.insert(path.clone(), current.clone()) != Some(current)
+                }
+                None => {
+                    self.definitions.remove(path);
+                    true
+                }
+            },
+            WatchEvent::Deleted(path) => {
+                self.definitions.remove(path);
+                true
+            }
+            WatchEvent::Error(_) => true,
+        }
+    }
+}
+
+/// Fingerprints the items in `path` on a blocking thread, or `None` if
+/// the file cannot be read or parsed.
+async fn read_definitions(path: &Path) -> Option<Vec<ItemFingerprint>> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let lang = CfgExtractor::detect_language(&path)?;
+        let source = std::fs::read_to_string(&path).ok()?;
+        let mut items = CfgExtractor::item_fingerprints(&source, lang).ok()?;
+        // The order items appear in does not change what is defined
+        items.sort();
+        Some(items)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_comment_edits_are_dropped() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("lib.rs");
+        let modified = WatchEvent::Modified(path.clone());
+        let mut filter = DefinitionFilter::default();
+
+        std::fs::write(&path, "fn a() {}\n").unwrap();
+        // Nothing known about the file yet
+        assert!(filter.admits(&modified).await);
+
+        std::fs::write(&path, "// doc\nfn a() {\n}\n").unwrap();
+        assert!(!filter.admits(&modified).await);
+
+        std::fs::write(&path, "fn a() {\n    if true { b(); }\n}\n").unwrap();
+        assert!(filter.admits(&modified).await);
+
+        std::fs::write(&path, "fn a() {\n    if true { b(); }\n}\nfn b() {}\n").unwrap();
+        assert!(filter.admits(&modified).await);
+        assert!(!filter.admits(&modified).await);
+
+        assert!(filter.admits(&WatchEvent::Deleted(path.clone())).await);
+        assert!(filter.admits(&modified).await);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_files_always_pass() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("notes.txt");
+        std::fs::write(&path, "hello").unwrap();
+        let modified = WatchEvent::Modified(path);
+        let mut filter = DefinitionFilter::default();
+
+        assert!(filter.admits(&modified).await);
+        assert!(filter.admits(&modified).await);
+    }
+
+    #[tokio::test]
+    async fn test_signature_edits_pass() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("lib.rs");
+        let modified = WatchEvent::Modified(path.clone());
+        let mut filter = DefinitionFilter::default();
+
+        std::fs::write(&path, "pub fn scale(n: i32) -> i32 {\n    n * 2\n}\n").unwrap();
+        filter.admits(&modified).await;
+
+        // Same name and control flow, different parameter type
+        std::fs::write(&path, "pub fn scale(n: i64) -> i32 {\n    n * 2\n}\n").unwrap();
+        assert!(filter.admits(&modified).await);
+    }
+
+    #[tokio::test]
+    async fn test_struct_field_edits_pass() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("lib.rs");
+        let modified = WatchEvent::Modified(path.clone());
+        let mut filter = DefinitionFilter::default();
+
+        std::fs::write(&path, "pub struct Point {\n    x: i32,\n}\n").unwrap();
+        filter.admits(&modified).await;
+
+        std::fs::write(&path, "pub struct Point {\n    x: i32,\n    y: i32,\n}\n").unwrap();
+        assert!(filter.admits(&modified).await);
+
+        // A comment on the field is not a definition change
+        std::fs::write(
+            &path,
+            "pub struct Point {\n    x: i32,\n    /// Vertical\n    y: i32,\n}\n",
+        )
+        .unwrap();
+        assert!(!filter.admits(&modified).await);
+    }
+}
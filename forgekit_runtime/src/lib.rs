@@ -16,7 +16,7 @@
 //! # async fn main() -> anyhow::Result<()> {
 //! let config = RuntimeConfig {
 //!     watch_enabled: true,
-//!     debounce_ms: 500,
+//!     debounce_ms: 500.into(),
 //!     cache_size: 1000,
 //!     cache_ttl_secs: 300,
 //!     ..Default::default()
@@ -37,39 +37,97 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context as _;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+use tracing::Instrument as _;
 
 // Re-export forgekit_core types
 pub use forgekit_core::{
-    FlushStats, IncrementalIndexer, PathFilter, QueryCache, WatchEvent, Watcher,
+    CacheStats, EvictionPolicy, FlushStats, IncrementalIndexer, PathFilter, QueryCache,
+    SegmentStats, WatchEvent, Watcher,
 };
 
+mod debounce;
+mod definitions;
 pub mod metrics;
+pub use debounce::DebounceConfig;
 pub use metrics::{MetricKind, MetricsSummary, RuntimeMetrics};
 
+/// Events buffered for each subscriber before the oldest are dropped.
+const EVENT_BUFFER: usize = 256;
+
+/// Notification pushed to [`ForgeRuntime::subscribe`] receivers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuntimeEvent {
+    /// A watched file was created, modified or deleted
+    ///
+    /// Watcher errors are reported as [`RuntimeEvent::Error`] instead.
+    FileChanged(WatchEvent),
+    /// A batch of queued changes is being reindexed
+    ReindexStarted {
+        /// Files in the batch
+        files: usize,
+    },
+    /// A reindex finished
+    ReindexCompleted {
+        /// Files indexed
+        indexed: usize,
+        /// Files removed from the index
+        deleted: usize,
+        /// Functions added, removed or changed by the reindex
+        changed_symbols: usize,
+    },
+    /// Watching or reindexing failed; a reindex sends one for each file
+    /// it could not index
+    Error(String),
+}
+
 /// Runtime configuration for indexing and caching.
 #[derive(Clone, Debug)]
 pub struct RuntimeConfig {
     /// Enable file watching
     pub watch_enabled: bool,
-    /// Debounce delay for file events (milliseconds)
-    pub debounce_ms: u64,
+    /// Debounce delays for file events (milliseconds)
+    ///
+    /// A plain number applies to every event:
+    /// `debounce_ms: 500.into()`.
+    pub debounce_ms: DebounceConfig,
     /// Maximum cache size
     pub cache_size: usize,
     /// Cache TTL (seconds)
     pub cache_ttl_secs: u64,
-    /// Directory to watch (default: "src/")
-    pub watch_dir: String,
+    /// How the query cache picks entries to evict when full
+    pub cache_policy: EvictionPolicy,
+    /// Directories to watch, relative to the codebase (default: `["src"]`)
+    ///
+    /// Entries may be glob patterns such as `crates/*/src`. Every plain
+    /// path must exist; a pattern matching no directory is logged and
+    /// skipped, as long as some entry names a directory.
+    pub watch_dir: Vec<String>,
+    /// Only reindex a modified file when the items it defines change
+    ///
+    /// Edits that leave the tokens of every function, type, impl and other
+    /// top-level item intact, such as whitespace and comment changes, are
+    /// dropped before they are queued. The first modification seen for a
+    /// file always reindexes.
+    pub reindex_on_definition_change_only: bool,
+    /// Skip files larger than this many bytes when scanning and indexing
+    /// (default: no limit)
+    ///
+    /// Skipped files are logged; index results report them.
+    pub max_file_bytes: Option<usize>,
 }
 
 impl Default for RuntimeConfig {
     fn default() -> Self {
         Self {
             watch_enabled: false,
-            debounce_ms: 500,
+            debounce_ms: 500.into(),
             cache_size: 10_000,
             cache_ttl_secs: 300,
-            watch_dir: "src".to_string(),
+            cache_policy: EvictionPolicy::default(),
+            watch_dir: vec!["src".to_string()],
+            reindex_on_definition_change_only: false,
+            max_file_bytes: None,
         }
     }
 }
@@ -98,8 +156,8 @@ pub struct ForgeRuntime {
     config: RuntimeConfig,
     /// Graph store for indexing
     store: Option<Arc<forgekit_core::UnifiedGraphStore>>,
-    /// File watcher
-    watcher: Option<Watcher>,
+    /// File watchers, one per watched directory
+    watchers: Vec<Watcher>,
     /// Incremental indexer
     indexer: Option<IncrementalIndexer>,
     /// Query cache
@@ -110,6 +168,8 @@ pub struct ForgeRuntime {
     watch_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     /// Whether watching is active
     watch_active: Arc<std::sync::atomic::AtomicBool>,
+    /// Watch loop notifications, fanned out to subscribers
+    events: broadcast::Sender<RuntimeEvent>,
 }
 
 impl ForgeRuntime {
@@ -144,29 +204,34 @@ impl ForgeRuntime {
                 forgekit_core::BackendKind::default(),
             )
             .await
-            .context("Failed to open graph store")?,
+            .context("Failed to open graph store")?
+            .with_walk_options(
+                forgekit_core::WalkOptions::default().max_file_bytes(config.max_file_bytes),
+            ),
         );
 
-        // Create indexer with path filter
-        let filter = PathFilter::include_dirs(&[&config.watch_dir]);
-        let indexer = IncrementalIndexer::with_filter(Arc::clone(&store), filter);
+        // The path filter follows the watched directories, which `watch`
+        // resolves
+        let indexer = IncrementalIndexer::new(Arc::clone(&store));
 
         // Create query cache
-        let cache = QueryCache::new(
+        let cache = QueryCache::with_policy(
             config.cache_size,
             Duration::from_secs(config.cache_ttl_secs),
+            config.cache_policy,
         );
 
         Ok(Self {
             codebase_path,
             config,
             store: Some(store),
-            watcher: None,
+            watchers: Vec::new(),
             indexer: Some(indexer),
             cache: Some(cache),
             metrics: RuntimeMetrics::new(),
             watch_handle: Arc::new(Mutex::new(None)),
             watch_active: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            events: broadcast::channel(EVENT_BUFFER).0,
         })
     }
 
@@ -174,7 +239,8 @@ impl ForgeRuntime {
     ///
     /// This will monitor the codebase for changes and trigger
     /// reindexing as needed. Events are debounced to avoid
-    /// excessive reindexing.
+    /// excessive reindexing, for longer during a burst of events; see
+    /// [`DebounceConfig`].
     pub async fn watch(&mut self) -> anyhow::Result<()> {
         if !self.config.watch_enabled {
             return Err(anyhow::anyhow!("File watching is not enabled in config"));
@@ -188,45 +254,51 @@ impl ForgeRuntime {
             .store
             .clone()
             .ok_or_else(|| anyhow::anyhow!("Store not initialized"))?;
+        // Verify the directories exist before watching any of them, and
+        // index exactly the directories watched
+        let watch_paths = watch_dirs(&self.codebase_path, &self.config.watch_dir)?;
         let indexer = self
             .indexer
-            .clone()
+            .as_mut()
             .ok_or_else(|| anyhow::anyhow!("Indexer not initialized"))?;
+        indexer.set_filter(include_filter(&self.codebase_path, &watch_paths));
+        let indexer = indexer.clone();
 
-        // Create watcher channel
+        // Start a watcher per directory, all sending into one channel
         let (tx, rx) = Watcher::channel();
-        let watcher = Watcher::new(store, tx);
-        self.watcher = Some(watcher);
-
-        // Start watching the configured directory
-        let watch_path = self.codebase_path.join(&self.config.watch_dir);
-
-        // Verify directory exists before watching
-        if !watch_path.exists() {
-            return Err(anyhow::anyhow!(
-                "Watch directory does not exist: {}",
-                watch_path.display()
-            ));
-        }
-
-        if let Some(watcher) = &self.watcher {
-            watcher
-                .start(watch_path.clone())
-                .await
-                .context("Failed to start file watcher")?;
+        let mut watchers = Vec::with_capacity(watch_paths.len());
+        for watch_path in watch_paths {
+            let watcher = Watcher::new(Arc::clone(&store), tx.clone());
+            watcher.start(watch_path.clone()).await.with_context(|| {
+                format!("Failed to start file watcher for {}", watch_path.display())
+            })?;
+            watchers.push(watcher);
         }
+        self.watchers = watchers;
 
         // Spawn background task to handle events
         let metrics = self.metrics.clone();
+        let cache = self.cache.clone();
         let indexer_clone = indexer.clone();
         let watch_active = self.watch_active.clone();
-        let debounce = Duration::from_millis(self.config.debounce_ms);
-
+        let events = self.events.clone();
+        let mut debouncer = debounce::Debouncer::new(self.config.debounce_ms);
+        // Wake often enough to flush soon after a burst settles
+        let poll = self
+            .config
+            .debounce_ms
+            .shortest()
+            .clamp(Duration::from_millis(10), Duration::from_secs(1));
+        let mut definitions = self
+            .config
+            .reindex_on_definition_change_only
+            .then(definitions::DefinitionFilter::default);
+
+        // Mark watching active before the task runs, so stopping right
+        // away is not undone when it starts
+        watch_active.store(true, std::sync::atomic::Ordering::Relaxed);
         let handle = tokio::spawn(async move {
-            watch_active.store(true, std::sync::atomic::Ordering::Relaxed);
-
             let mut rx = rx;
-            let mut last_flush = std::time::Instant::now();
 
             loop {
                 let is_running = watch_active.load(std::sync::atomic::Ordering::Relaxed);
@@ -234,10 +306,34 @@ impl ForgeRuntime {
                     break;
                 }
 
-                match tokio::time::timeout(Duration::from_secs(1), rx.recv()).await {
+                match tokio::time::timeout(poll, rx.recv()).await {
                     Ok(Some(event)) => {
-                        // Queue the event for processing
-                        indexer_clone.queue(event);
+                        // Sending never waits; with no subscribers it fails
+                        let _ = events.send(match &event {
+                            WatchEvent::Error(message) => RuntimeEvent::Error(message.clone()),
+                            _ => RuntimeEvent::FileChanged(event.clone()),
+                        });
+                        // Drop cached results computed from the changed file
+                        if let (
+                            Some(cache),
+                            WatchEvent::Created(path)
+                            | WatchEvent::Modified(path)
+                            | WatchEvent::Deleted(path),
+                        ) = (&cache, &event)
+                        {
+                            cache.invalidate_by_path(path).await;
+                        }
+                        // Queue the event for processing, unless it leaves
+                        // every definition unchanged. A batch with nothing
+                        // queued is never flushed.
+                        let admitted = match definitions.as_mut() {
+                            Some(filter) => filter.admits(&event).await,
+                            None => true,
+                        };
+                        if admitted {
+                            debouncer.record(&event);
+                            indexer_clone.queue(event);
+                        }
                     }
                     Ok(None) => {
                         // Channel closed
@@ -248,12 +344,47 @@ impl ForgeRuntime {
                     }
                 }
 
-                // Flush on debounce interval
-                if last_flush.elapsed() >= debounce {
-                    if let Ok(_stats) = indexer_clone.flush().await {
-                        metrics.record(MetricKind::Reindex);
+                // Flush once events have been quiet for the debounce window,
+                // so a burst for one save coalesces into a single reindex
+                if indexer_clone.is_settled(debouncer.quiet()) {
+                    debouncer.reset();
+                    let files = indexer_clone.pending_count().await;
+                    let span = tracing::info_span!(
+                        "watch_flush",
+                        files,
+                        changed_symbols = tracing::field::Empty,
+                        elapsed_ms = tracing::field::Empty,
+                    );
+                    async {
+                        let start = std::time::Instant::now();
+                        let _ = events.send(RuntimeEvent::ReindexStarted { files });
+                        match indexer_clone.flush().await {
+                            Ok(stats) => {
+                                metrics.record(MetricKind::Reindex);
+                                for (path, reason) in &stats.failed {
+                                    let _ = events.send(RuntimeEvent::Error(format!(
+                                        "Failed to index {}: {reason}",
+                                        path.display()
+                                    )));
+                                }
+                                let changed_symbols = indexer_clone.changed_symbols().len();
+                                let span = tracing::Span::current();
+                                span.record("changed_symbols", changed_symbols);
+                                span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+                                let _ = events.send(RuntimeEvent::ReindexCompleted {
+                                    indexed: stats.indexed,
+                                    deleted: stats.deleted,
+                                    changed_symbols,
+                                });
+                            }
+                            Err(e) => {
+                                let _ = events
+                                    .send(RuntimeEvent::Error(format!("Reindex failed: {e}")));
+                            }
+                        }
                     }
-                    last_flush = std::time::Instant::now();
+                    .instrument(span)
+                    .await;
                 }
             }
 
@@ -278,6 +409,16 @@ impl ForgeRuntime {
         Ok(())
     }
 
+    /// Subscribes to file change and reindex notifications from [`watch`](Self::watch).
+    ///
+    /// Every receiver gets every event sent after it subscribed. Sending
+    /// never waits for a receiver: one that falls more than 256 events
+    /// behind loses the oldest and sees
+    /// [`RecvError::Lagged`](broadcast::error::RecvError::Lagged).
+    pub fn subscribe(&self) -> broadcast::Receiver<RuntimeEvent> {
+        self.events.subscribe()
+    }
+
     /// Gets a reference to the query cache.
     pub fn cache(&self) -> Option<&QueryCache<String, String>> {
         self.cache.as_ref()
@@ -288,6 +429,13 @@ impl ForgeRuntime {
         &self.metrics
     }
 
+    /// Renders the runtime metrics in the Prometheus text exposition format.
+    ///
+    /// See [`RuntimeMetrics::to_prometheus`].
+    pub fn metrics_prometheus(&self) -> String {
+        self.metrics.to_prometheus()
+    }
+
     /// Clears all caches.
     pub async fn clear_cache(&self) -> anyhow::Result<()> {
         if let Some(cache) = &self.cache {
@@ -306,7 +454,14 @@ impl ForgeRuntime {
                 .unwrap_or(0),
             watch_active: self.watch_active.load(std::sync::atomic::Ordering::Relaxed),
             reindex_count: self.metrics.count(MetricKind::Reindex),
-            metrics: self.metrics.summary(),
+            metrics: MetricsSummary {
+                cache: self
+                    .cache
+                    .as_ref()
+                    .map(|c| futures::executor::block_on(c.stats()))
+                    .unwrap_or_default(),
+                ..self.metrics.summary()
+            },
         }
     }
 
@@ -321,6 +476,62 @@ impl ForgeRuntime {
     }
 }
 
+/// Whether a `watch_dir` entry is a glob pattern rather than a path.
+fn is_pattern(entry: &str) -> bool {
+    entry.contains(['*', '?', '['])
+}
+
+/// Directories under `root` matching the glob `pattern`, sorted.
+///
+/// Only `pattern` is matched as a glob; `root` is taken literally.
+fn glob_dirs(root: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let root = glob::Pattern::escape(&root.to_string_lossy());
+    let paths = glob::glob(&Path::new(&root).join(pattern).to_string_lossy())
+        .with_context(|| format!("Invalid watch pattern: {pattern}"))?;
+    Ok(paths.flatten().filter(|path| path.is_dir()).collect())
+}
+
+/// Indexer filter admitting files under the watched `dirs`.
+fn include_filter(root: &Path, dirs: &[PathBuf]) -> PathFilter {
+    let include: Vec<String> = dirs
+        .iter()
+        .filter_map(|dir| dir.strip_prefix(root).ok())
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .collect();
+    let include: Vec<&str> = include.iter().map(String::as_str).collect();
+    PathFilter::include_dirs(&include)
+}
+
+/// Resolves `watch_dir` entries to the directories to watch.
+fn watch_dirs(root: &Path, entries: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in entries {
+        if is_pattern(entry) {
+            let matched = glob_dirs(root, entry)?;
+            if matched.is_empty() {
+                tracing::warn!(pattern = %entry, "Watch pattern matched no directories");
+            }
+            dirs.extend(matched);
+        } else {
+            let dir = root.join(entry);
+            if !dir.exists() {
+                anyhow::bail!("Watch directory does not exist: {}", dir.display());
+            }
+            dirs.push(dir);
+        }
+    }
+    if dirs.is_empty() {
+        anyhow::bail!(
+            "No watch directory matches {} under {}",
+            entries.join(", "),
+            root.display()
+        );
+    }
+    dirs.sort();
+    dirs.dedup();
+    Ok(dirs)
+}
+
 impl Drop for ForgeRuntime {
     fn drop(&mut self) {
         // Signal shutdown
@@ -336,9 +547,10 @@ mod tests {
     #[tokio::test]
     async fn test_runtime_config_default() {
         let config = RuntimeConfig::default();
-        assert_eq!(config.debounce_ms, 500);
+        assert_eq!(config.debounce_ms, DebounceConfig::from(500));
         assert_eq!(config.cache_size, 10_000);
         assert_eq!(config.cache_ttl_secs, 300);
+        assert!(!config.reindex_on_definition_change_only);
     }
 
     #[tokio::test]
@@ -359,18 +571,44 @@ mod tests {
         let temp = tempfile::tempdir().unwrap();
         let config = RuntimeConfig {
             watch_enabled: false,
-            debounce_ms: 1000,
+            debounce_ms: DebounceConfig {
+                create_ms: 2000,
+                modify_ms: 1000,
+                delete_ms: 1000,
+                burst_threshold: 50,
+            },
             cache_size: 100,
             cache_ttl_secs: 600,
-            watch_dir: "src".to_string(),
+            cache_policy: EvictionPolicy::SegmentedLru,
+            watch_dir: vec!["src".to_string()],
+            reindex_on_definition_change_only: true,
+            max_file_bytes: Some(1 << 20),
         };
 
         let runtime = ForgeRuntime::with_config(temp.path(), config)
             .await
             .unwrap();
 
-        assert_eq!(runtime.config().debounce_ms, 1000);
+        assert_eq!(runtime.config().debounce_ms.modify_ms, 1000);
         assert_eq!(runtime.config().cache_size, 100);
+        assert!(runtime.config().reindex_on_definition_change_only);
+        assert_eq!(
+            runtime
+                .store
+                .as_ref()
+                .unwrap()
+                .walk_options()
+                .max_file_bytes,
+            Some(1 << 20)
+        );
+
+        let cache = runtime.cache().unwrap();
+        cache.insert("key".to_string(), "value".to_string()).await;
+        assert!(cache.get(&"key".to_string()).await.is_some());
+        let stats = runtime.stats().metrics.cache;
+        assert_eq!(stats.policy, EvictionPolicy::SegmentedLru);
+        assert_eq!(stats.probationary.hits, 1);
+        assert_eq!(stats.protected.len, 1);
     }
 
     #[tokio::test]
@@ -395,6 +633,10 @@ mod tests {
         runtime.metrics().record(MetricKind::GraphQuery);
 
         assert_eq!(runtime.metrics().count(MetricKind::GraphQuery), 2);
+        assert!(runtime
+            .metrics_prometheus()
+            .lines()
+            .any(|line| line == "forge_graph_queries_total 2"));
     }
 
     #[tokio::test]
@@ -439,12 +681,147 @@ mod tests {
         assert!(runtime.watch().await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_runtime_watch_invalidates_dependent_cache_entries() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let lib = src.join("lib.rs");
+        std::fs::write(&lib, "fn a() {}\n").unwrap();
+
+        let config = RuntimeConfig {
+            watch_enabled: true,
+            debounce_ms: 10.into(),
+            ..Default::default()
+        };
+        let mut runtime = ForgeRuntime::with_config(temp.path(), config)
+            .await
+            .unwrap();
+        let cache = runtime.cache().unwrap().clone();
+        cache
+            .insert_with_deps("find:a".to_string(), "a".to_string(), [lib.clone()])
+            .await;
+        cache.insert("other".to_string(), "b".to_string()).await;
+
+        runtime.watch().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        std::fs::write(&lib, "fn a() {}\nfn b() {}\n").unwrap();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while cache.get(&"find:a".to_string()).await.is_some() {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "entry should be invalidated by the file change"
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(cache.get(&"other".to_string()).await, Some("b".to_string()));
+
+        runtime.stop_watching().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_runtime_subscribers_receive_reindex_events() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let lib = src.join("lib.rs");
+        std::fs::write(&lib, "fn a() {}\n").unwrap();
+
+        let config = RuntimeConfig {
+            watch_enabled: true,
+            debounce_ms: 10.into(),
+            ..Default::default()
+        };
+        let mut runtime = ForgeRuntime::with_config(temp.path(), config)
+            .await
+            .unwrap();
+        let mut first = runtime.subscribe();
+        let mut second = runtime.subscribe();
+        // Never read, so it must not hold up indexing
+        let _idle = runtime.subscribe();
+
+        runtime.watch().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        std::fs::write(&lib, "fn a() {}\nfn b() {}\n").unwrap();
+
+        for receiver in [&mut first, &mut second] {
+            let mut seen = Vec::new();
+            let completed = tokio::time::timeout(Duration::from_secs(5), async {
+                loop {
+                    let event = receiver.recv().await.unwrap();
+                    seen.push(event.clone());
+                    if let RuntimeEvent::ReindexCompleted { .. } = event {
+                        return event;
+                    }
+                }
+            })
+            .await
+            .expect("reindex should complete");
+
+            assert!(seen.iter().any(|e| matches!(
+                e,
+                RuntimeEvent::FileChanged(WatchEvent::Created(p) | WatchEvent::Modified(p))
+                    if p.ends_with("lib.rs")
+            )));
+            assert!(seen
+                .iter()
+                .any(|e| matches!(e, RuntimeEvent::ReindexStarted { files } if *files >= 1)));
+            assert!(matches!(
+                completed,
+                RuntimeEvent::ReindexCompleted { indexed: 1, deleted: 0, changed_symbols } if changed_symbols > 0
+            ));
+        }
+
+        runtime.stop_watching().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_runtime_subscribers_receive_failed_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let lib = src.join("lib.rs");
+        std::fs::write(&lib, "fn a() {}\n").unwrap();
+
+        let config = RuntimeConfig {
+            watch_enabled: true,
+            debounce_ms: 10.into(),
+            ..Default::default()
+        };
+        let mut runtime = ForgeRuntime::with_config(temp.path(), config)
+            .await
+            .unwrap();
+        let db_path = runtime.store.as_ref().unwrap().db_path().to_path_buf();
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        std::fs::write(&db_path, "not a database").unwrap();
+        let mut events = runtime.subscribe();
+
+        runtime.watch().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        std::fs::write(&lib, "fn a() {}\nfn b() {}\n").unwrap();
+
+        let error = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let RuntimeEvent::Error(message) = events.recv().await.unwrap() {
+                    return message;
+                }
+            }
+        })
+        .await
+        .expect("the failed file should be reported");
+        assert!(error.contains("lib.rs"), "{error}");
+
+        runtime.stop_watching().await.unwrap();
+        let _ = std::fs::remove_dir_all(db_path.parent().unwrap());
+    }
+
     #[tokio::test]
     async fn test_runtime_watch_fails_for_nonexistent_dir() {
         let temp = tempfile::tempdir().unwrap();
         let config = RuntimeConfig {
             watch_enabled: true,
-            watch_dir: "nonexistent".to_string(),
+            watch_dir: vec!["nonexistent".to_string()],
             ..Default::default()
         };
 
@@ -454,4 +831,122 @@ mod tests {
 
         assert!(runtime.watch().await.is_err());
     }
+
+    #[tokio::test]
+    async fn test_runtime_watch_fails_for_any_nonexistent_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        let config = RuntimeConfig {
+            watch_enabled: true,
+            watch_dir: vec!["src".to_string(), "examples".to_string()],
+            ..Default::default()
+        };
+
+        let mut runtime = ForgeRuntime::with_config(temp.path(), config)
+            .await
+            .unwrap();
+
+        let err = runtime.watch().await.unwrap_err();
+        assert!(err.to_string().contains("examples"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_runtime_watch_skips_patterns_matching_nothing() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        let config = RuntimeConfig {
+            watch_enabled: true,
+            watch_dir: vec!["src".to_string(), "crates/*/src".to_string()],
+            ..Default::default()
+        };
+        let mut runtime = ForgeRuntime::with_config(temp.path(), config.clone())
+            .await
+            .unwrap();
+        runtime.watch().await.unwrap();
+        runtime.stop_watching().await.unwrap();
+
+        // With no directory at all, there is nothing to watch
+        let config = RuntimeConfig {
+            watch_dir: vec!["crates/*/src".to_string()],
+            ..config
+        };
+        let mut runtime = ForgeRuntime::with_config(temp.path(), config)
+            .await
+            .unwrap();
+        assert!(runtime.watch().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_runtime_indexes_dirs_matched_when_watching() {
+        let temp = tempfile::tempdir().unwrap();
+        // Glob metacharacters in the root are matched literally
+        let root = temp.path().join("repo [v2]");
+        std::fs::create_dir_all(root.join("crates/core/src")).unwrap();
+        let config = RuntimeConfig {
+            watch_enabled: true,
+            watch_dir: vec!["crates/*/src".to_string()],
+            ..Default::default()
+        };
+        let mut runtime = ForgeRuntime::with_config(&root, config).await.unwrap();
+        // Created after construction, before watching
+        std::fs::create_dir_all(root.join("crates/late/src")).unwrap();
+
+        runtime.watch().await.unwrap();
+        assert_eq!(runtime.watchers.len(), 2);
+        let filter = runtime.indexer.as_ref().unwrap().filter();
+        let root = runtime.codebase_path().to_path_buf();
+        assert!(filter.should_index(&root.join("crates/core/src/lib.rs")));
+        assert!(filter.should_index(&root.join("crates/late/src/lib.rs")));
+        assert!(!filter.should_index(&root.join("crates/late/build.rs")));
+
+        runtime.stop_watching().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_runtime_watches_every_listed_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        let crate_src = temp.path().join("crates").join("util").join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir_all(&crate_src).unwrap();
+        // Not listed, so never watched
+        std::fs::create_dir_all(temp.path().join("target")).unwrap();
+
+        let config = RuntimeConfig {
+            watch_enabled: true,
+            debounce_ms: 10.into(),
+            watch_dir: vec!["src".to_string(), "crates/*/src".to_string()],
+            ..Default::default()
+        };
+        let mut runtime = ForgeRuntime::with_config(temp.path(), config)
+            .await
+            .unwrap();
+        let mut events = runtime.subscribe();
+
+        runtime.watch().await.unwrap();
+        assert_eq!(runtime.watchers.len(), 2);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        std::fs::write(temp.path().join("target").join("out.rs"), "fn t() {}\n").unwrap();
+        std::fs::write(src.join("lib.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(crate_src.join("util.rs"), "fn b() {}\n").unwrap();
+
+        let mut changed = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !(changed.iter().any(|p: &PathBuf| p.ends_with("lib.rs"))
+                && changed.iter().any(|p| p.ends_with("util.rs")))
+            {
+                if let RuntimeEvent::FileChanged(
+                    WatchEvent::Created(path) | WatchEvent::Modified(path),
+                ) = events.recv().await.unwrap()
+                {
+                    changed.push(path);
+                }
+            }
+        })
+        .await
+        .expect("changes in both directories should be handled");
+        assert!(!changed.iter().any(|p| p.ends_with("out.rs")));
+
+        runtime.stop_watching().await.unwrap();
+    }
 }
@@ -1,5 +1,7 @@
 //! Runtime metrics for operations, timing, and cache statistics.
 
+use forgekit_core::CacheStats;
+use std::fmt::Write;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -118,9 +120,76 @@ impl RuntimeMetrics {
             reindex_ops: self.count(MetricKind::Reindex),
             total_time: self.total_time(),
             cache_hit_rate: self.cache_hit_rate(),
+            cache: CacheStats::default(),
         }
     }
 
+    /// Renders the metrics in the Prometheus text exposition format.
+    ///
+    /// Operation counts are counters and the cache hit rate is a gauge.
+    /// Metrics are emitted in name order, each with `# HELP` and `# TYPE`
+    /// lines, so the output is stable between scrapes.
+    pub fn to_prometheus(&self) -> String {
+        let counts = [
+            (
+                "forge_cache_hit_rate",
+                "gauge",
+                "Fraction of cache accesses that were hits",
+                self.cache_hit_rate(),
+            ),
+            (
+                "forge_cache_hits_total",
+                "counter",
+                "Cache hits",
+                self.inner.cache_hits.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "forge_cache_misses_total",
+                "counter",
+                "Cache misses",
+                self.inner.cache_misses.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "forge_cfg_analyses_total",
+                "counter",
+                "CFG analyses performed",
+                self.count(MetricKind::CfgAnalysis) as f64,
+            ),
+            (
+                "forge_graph_queries_total",
+                "counter",
+                "Graph queries performed",
+                self.count(MetricKind::GraphQuery) as f64,
+            ),
+            (
+                "forge_operation_seconds_total",
+                "counter",
+                "Total time spent in timed operations",
+                self.total_time().as_secs_f64(),
+            ),
+            (
+                "forge_reindex_total",
+                "counter",
+                "Re-index operations performed",
+                self.count(MetricKind::Reindex) as f64,
+            ),
+            (
+                "forge_searches_total",
+                "counter",
+                "Search operations performed",
+                self.count(MetricKind::Search) as f64,
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, kind, help, value) in counts {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+        out
+    }
+
     /// Resets all metrics to zero.
     pub fn reset(&self) {
         for count in &self.inner.counts {
@@ -153,6 +222,11 @@ pub struct MetricsSummary {
     pub total_time: Duration,
     /// Cache hit rate (0.0 to 1.0)
     pub cache_hit_rate: f64,
+    /// Per-segment hit and eviction counts of the runtime's query cache
+    ///
+    /// Filled in by `ForgeRuntime::stats`; empty when the summary comes
+    /// straight from [`RuntimeMetrics::summary`].
+    pub cache: CacheStats,
 }
 
 #[cfg(test)]
@@ -205,6 +279,42 @@ mod tests {
         assert_eq!(metrics.cache_hit_rate(), 0.0);
     }
 
+    #[test]
+    fn test_to_prometheus() {
+        let metrics = RuntimeMetrics::new();
+
+        metrics.record(MetricKind::GraphQuery);
+        metrics.record(MetricKind::GraphQuery);
+        metrics.record(MetricKind::Reindex);
+        metrics.record_timing(MetricKind::Search, Duration::from_millis(1500));
+        metrics.record_cache_access(true);
+        metrics.record_cache_access(false);
+
+        let text = metrics.to_prometheus();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert!(lines.contains(&"# TYPE forge_graph_queries_total counter"));
+        assert!(lines.contains(&"forge_graph_queries_total 2"));
+        assert!(lines.contains(&"# TYPE forge_cache_hit_rate gauge"));
+        assert!(lines.contains(&"forge_cache_hit_rate 0.5"));
+        assert!(lines.contains(&"forge_reindex_total 1"));
+        assert!(lines.contains(&"forge_operation_seconds_total 1.5"));
+
+        // Every sample is preceded by its HELP and TYPE lines, in name order
+        let names: Vec<&str> = lines
+            .chunks(3)
+            .map(|chunk| {
+                assert!(chunk[0].starts_with("# HELP "));
+                assert!(chunk[1].starts_with("# TYPE "));
+                chunk[2].split(' ').next().unwrap()
+            })
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+        assert_eq!(text, metrics.to_prometheus());
+    }
+
     #[test]
     fn test_metrics_summary() {
         let metrics = RuntimeMetrics::new();
@@ -84,6 +84,7 @@ edition = "2021"
 /// - language: Language::Rust
 /// - location: from test_location()
 /// - parent_id: None
+/// - body_hash: None
 /// - metadata: serde_json::Value::Null
 pub fn test_symbol() -> forgekit_core::Symbol {
     forgekit_core::Symbol {
@@ -94,6 +95,7 @@ pub fn test_symbol() -> forgekit_core::Symbol {
         language: Language::Rust,
         location: test_location(),
         parent_id: None,
+        body_hash: None,
         metadata: serde_json::Value::Null,
     }
 }